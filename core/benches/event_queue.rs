@@ -0,0 +1,95 @@
+//! Compares the event queue structures on a synthetic hold workload and on
+//! a full simulation:
+//!
+//! ```text
+//! cargo bench -p slay-core --bench event_queue
+//! ```
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use slay_core::{Event, EventType, PendingEvents, Topology};
+use std::time::{Duration, Instant};
+
+const HOLD_OPERATIONS: usize = 1_000_000;
+
+type NewQueue = fn() -> PendingEvents;
+
+fn queues() -> [(&'static str, NewQueue); 2] {
+    [
+        ("heap", PendingEvents::heap),
+        ("calendar", PendingEvents::calendar),
+    ]
+}
+
+fn event(time: u64, seq: u64) -> Event {
+    Event {
+        time,
+        seq,
+        node_id: 1,
+        event_type: EventType::GenerateNext { generation_id: seq },
+    }
+}
+
+/// The classic hold model: `pending` events queued, then each pop is
+/// followed by a push a random delay later, so the size stays put.
+fn hold(mut queue: PendingEvents, pending: usize) -> Duration {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut delay = move || rng.gen_range(1..pending as u64 * 10);
+    for seq in 0..pending as u64 {
+        queue.push(event(delay(), seq));
+    }
+    let started = Instant::now();
+    for seq in (pending..pending + HOLD_OPERATIONS).map(|s| s as u64) {
+        let next = queue.pop().unwrap();
+        queue.push(event(next.time + delay(), seq));
+    }
+    started.elapsed()
+}
+
+/// Ten virtual seconds of slow servers taking heavy traffic, so about
+/// 200,000 requests are in flight, each with an event pending.
+fn simulate(queue: PendingEvents) -> (Duration, u64) {
+    let topology = Topology::new()
+        .client_with("users", |c| {
+            c.arrival_rate = 40_000.0;
+            c.timeout = 30_000;
+        })
+        .lb("lb")
+        .servers("api", 100, |s| {
+            s.service_time = 5_000;
+            s.concurrency = 2_000;
+        })
+        .connect("users", "lb")
+        .connect("lb", "api");
+    let mut sim = topology.build(1);
+    sim.set_event_queue(queue);
+    let started = Instant::now();
+    sim.advance_by(10_000_000);
+    (started.elapsed(), sim.success_count + sim.failure_count)
+}
+
+fn main() {
+    println!("hold model, {} pop/push pairs", HOLD_OPERATIONS);
+    for pending in [1_000, 10_000, 100_000, 500_000] {
+        for (name, queue) in queues() {
+            let took = hold(queue(), pending);
+            println!(
+                "  {:>7} pending  {:<8} {:>8.1} ns/op",
+                pending,
+                name,
+                took.as_nanos() as f64 / HOLD_OPERATIONS as f64
+            );
+        }
+    }
+
+    println!("simulation, 10s of virtual time");
+    for (name, queue) in queues() {
+        let (took, completed) = simulate(queue());
+        println!(
+            "  {:<8} {:>8.0} ms  {} requests",
+            name,
+            took.as_secs_f64() * 1000.0,
+            completed
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! How building, running and checking a topology scale with its size, on
+//! the generated topologies of `stress_topology`:
+//!
+//! ```text
+//! cargo bench -p slay-core --bench large_topology
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use slay_core::stress_topology;
+
+const SIZES: [usize; 3] = [1_000, 5_000, 10_000];
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    for nodes in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(nodes), &nodes, |b, &nodes| {
+            b.iter(|| stress_topology(nodes).build(1))
+        });
+    }
+    group.finish();
+}
+
+/// 100 ms of virtual time once traffic has settled.
+fn advance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("advance_100ms");
+    group.sample_size(10);
+    for nodes in SIZES {
+        let mut sim = stress_topology(nodes).build(1);
+        sim.advance_by(1_000_000);
+        group.bench_function(BenchmarkId::from_parameter(nodes), |b| {
+            b.iter(|| sim.advance_by(100_000))
+        });
+    }
+    group.finish();
+}
+
+/// The checks the canvas shows as badges.
+fn validate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+    for nodes in SIZES {
+        let sim = stress_topology(nodes).build(1);
+        group.bench_function(BenchmarkId::from_parameter(nodes), |b| {
+            b.iter(|| sim.validate())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build, advance, validate);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Cost of carrying requests along their path: through chains of servers
+//! deeper and shallower than the inline capacity of `Path`, and through
+//! balancers choosing among few or many targets, wired or found by service.
+//!
+//! ```text
+//! cargo bench -p slay-core --bench request_path
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use slay_core::{Simulation, Topology};
+
+/// A client calling `depth` servers one after another.
+fn chain(depth: usize) -> Simulation {
+    let mut topology = Topology::new().client("users", 2_000.0);
+    let mut prev = "users".to_string();
+    for i in 1..=depth {
+        let name = format!("s{}", i);
+        topology = topology
+            .server(&name, |s| {
+                s.service_time = 1;
+                s.concurrency = 64;
+            })
+            .connect(&prev, &name);
+        prev = name;
+    }
+    topology.build(1)
+}
+
+/// A client behind one balancer spreading over `width` servers.
+fn fan_out(width: usize) -> Simulation {
+    Topology::new()
+        .client("users", 5_000.0)
+        .lb("lb")
+        .servers("api", width, |s| {
+            s.service_time = 5;
+            s.concurrency = 64;
+        })
+        .connect("users", "lb")
+        .connect("lb", "api")
+        .build(1)
+}
+
+/// Like `fan_out`, but the balancer finds its servers by service name.
+fn service_fan_out(width: usize) -> Simulation {
+    let mut topology = Topology::new()
+        .client("users", 5_000.0)
+        .lb_with("lb", |l| l.service = Some("api".into()))
+        .servers("api", width, |s| {
+            s.service_time = 5;
+            s.concurrency = 64;
+        })
+        .connect("users", "lb");
+    for i in 1..=width {
+        topology = topology.service(&format!("api-{}", i), "api");
+    }
+    topology.build(1)
+}
+
+/// 100 ms of virtual time once traffic has settled.
+fn bench(c: &mut Criterion, group: &str, sizes: &[usize], make: fn(usize) -> Simulation) {
+    let mut group = c.benchmark_group(group);
+    for &size in sizes {
+        let mut sim = make(size);
+        sim.advance_by(1_000_000);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| sim.advance_by(100_000))
+        });
+    }
+    group.finish();
+}
+
+fn chains(c: &mut Criterion) {
+    bench(c, "chain", &[4, 8, 16], chain);
+}
+
+fn fan_outs(c: &mut Criterion) {
+    bench(c, "fan_out", &[4, 20, 100], fan_out);
+}
+
+fn service_fan_outs(c: &mut Criterion) {
+    bench(c, "service_fan_out", &[4, 20, 100], service_fan_out);
+}
+
+criterion_group!(benches, chains, fan_outs, service_fan_outs);
+criterion_main!(benches);
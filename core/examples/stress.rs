@@ -0,0 +1,60 @@
+//! Builds a large topology and runs it headless, reporting how fast the
+//! engine gets through it:
+//!
+//! ```text
+//! cargo run --release -p slay-core --example stress -- [nodes] [seconds]
+//! ```
+//!
+//! Defaults to 5,000 nodes and 5 seconds of virtual time.
+
+use slay_core::stress_topology;
+use std::time::Instant;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let nodes: usize = args.next().and_then(|a| a.parse().ok()).unwrap_or(5_000);
+    let seconds: u64 = args.next().and_then(|a| a.parse().ok()).unwrap_or(5);
+
+    let started = Instant::now();
+    let topology = stress_topology(nodes);
+    let generated = started.elapsed();
+    let mut sim = topology.build(1);
+    println!(
+        "{} nodes: generated in {:.0} ms, built in {:.0} ms",
+        sim.components.len(),
+        generated.as_secs_f64() * 1000.0,
+        (started.elapsed() - generated).as_secs_f64() * 1000.0
+    );
+
+    for second in 1..=seconds {
+        let seq = sim.next_seq;
+        let started = Instant::now();
+        sim.advance_by(1_000_000);
+        let took = started.elapsed().as_secs_f64();
+        let events = sim.next_seq - seq;
+        println!(
+            "t={:>3}s  {:>9} events  {:>6.0} ms  {:>5.2} M events/s  {:>8} pending  {} ok / {} failed",
+            second,
+            events,
+            took * 1000.0,
+            events as f64 / took / 1e6,
+            sim.events.len(),
+            sim.success_count,
+            sim.failure_count
+        );
+    }
+
+    let started = Instant::now();
+    let issues = sim.validate().len();
+    let validated = started.elapsed();
+    let started = Instant::now();
+    for comp in sim.components.values_mut() {
+        comp.sync_display_stats(sim.time);
+    }
+    println!(
+        "validate: {:.1} ms ({} issues), display sync: {:.1} ms",
+        validated.as_secs_f64() * 1000.0,
+        issues,
+        started.elapsed().as_secs_f64() * 1000.0
+    );
+}
@@ -1,18 +1,194 @@
+use crate::components::server::Server;
 use crate::engine::Simulation;
+use crate::network::EdgeCounters;
+use crate::queueing::{LiveLoad, MmcModel, QueueingCheck};
+use crate::traits::NodeId;
+use hdrhistogram::Histogram;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use smallvec::SmallVec;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, Write};
+use std::path::Path;
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+/// Percentiles a user can pick from in dashboards.
+pub const STANDARD_PERCENTILES: [f32; 5] = [50.0, 90.0, 95.0, 99.0, 99.9];
+
+/// Virtual time between two samples.
+const SAMPLE_STEP_US: u64 = 200_000;
+
+/// Identical annotations closer together than this are merged.
+const ANNOTATION_MERGE_US: u64 = 1_000_000;
+
+/// Short name of a percentile, e.g. `p99` or `p99.9`.
+pub fn percentile_label(p: f32) -> String {
+    format!("p{}", p)
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct MetricPoint {
     pub sim_time_us: u64,
-    pub p99_ms: f32,
+    /// `(percentile, latency_ms)` for every percentile recorded at this time
+    #[serde(default)]
+    pub latency_ms: SmallVec<[(f32, f32); 5]>,
     pub success_rps: f32,
     pub failure_rps: f32,
 }
 
+impl MetricPoint {
+    /// Latency at percentile `p`, if it was recorded for this point.
+    pub fn latency_ms(&self, p: f32) -> Option<f32> {
+        self.latency_ms
+            .iter()
+            .find(|(q, _)| *q == p)
+            .map(|(_, ms)| *ms)
+    }
+}
+
+/// One sample of a single node's load.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct NodeMetricPoint {
+    pub sim_time_us: u64,
+    /// Fraction of the node's capacity in use (0.0..=1.0)
+    pub utilization: f32,
+    pub queue_depth: u32,
+    pub error_rps: f32,
+    /// Requests received per second
+    pub rps: f32,
+    /// Smoothed time requests waited before processing started
+    #[serde(default)]
+    pub queue_wait_ms: f32,
+    /// Smoothed processing time, waiting excluded
+    #[serde(default)]
+    pub service_ms: f32,
+}
+
+/// Something that happened during the run, marked on the metric history.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Annotation {
+    pub sim_time_us: u64,
+    pub text: String,
+}
+
+/// One sample of every node of a kind combined.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct KindMetricPoint {
+    pub sim_time_us: u64,
+    pub nodes: u32,
+    pub healthy: u32,
+    /// Mean utilization across the nodes
+    pub utilization: f32,
+    /// Utilization of the busiest node
+    pub max_utilization: f32,
+    /// Requests queued across all nodes
+    pub queue_depth: u32,
+    pub error_rps: f32,
+    pub rps: f32,
+}
+
+impl KindMetricPoint {
+    fn add(&mut self, node: &NodeMetricPoint, healthy: bool) {
+        self.nodes += 1;
+        self.healthy += healthy as u32;
+        self.utilization += node.utilization;
+        self.max_utilization = self.max_utilization.max(node.utilization);
+        self.queue_depth += node.queue_depth;
+        self.error_rps += node.error_rps;
+        self.rps += node.rps;
+    }
+}
+
+/// Sampled history of one node plus the counters needed to derive rates.
+#[derive(Debug, Default)]
+struct NodeSeries {
+    points: VecDeque<NodeMetricPoint>,
+    last_received: u64,
+    last_errors: u64,
+    rps: f32,
+    error_rps: f32,
+}
+
+/// One sample of traffic over a directed edge.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct EdgeMetricPoint {
+    pub sim_time_us: u64,
+    /// Messages sent per second (requests and responses)
+    pub rps: f32,
+    /// Messages lost per second
+    pub drop_rps: f32,
+    /// Mean latency the link added to messages delivered since the last sample
+    pub avg_latency_ms: f32,
+    /// Requests sent per second, first attempts and retries
+    #[serde(default)]
+    pub request_rps: f32,
+    /// Retried requests sent per second
+    #[serde(default)]
+    pub retry_rps: f32,
+}
+
+/// Retry traffic, relative to first attempts, at which an edge counts as
+/// caught in a retry storm.
+const RETRY_STORM_RATIO: f32 = 1.0;
+/// Retries per second below which an edge is never flagged, so a handful
+/// of retries on a quiet edge is not called a storm.
+const RETRY_STORM_MIN_RPS: f32 = 1.0;
+
+impl EdgeMetricPoint {
+    /// Requests sent for every first attempt; 1.0 without retries.
+    pub fn amplification(&self) -> Option<f32> {
+        amplification(self.request_rps, self.retry_rps)
+    }
+
+    /// Retries outweigh first attempts on this edge.
+    pub fn is_retry_storm(&self) -> bool {
+        is_retry_storm(self.request_rps, self.retry_rps)
+    }
+}
+
+fn amplification(request_rps: f32, retry_rps: f32) -> Option<f32> {
+    let first = request_rps - retry_rps;
+    (first > 0.0).then(|| request_rps / first)
+}
+
+fn is_retry_storm(request_rps: f32, retry_rps: f32) -> bool {
+    let first = (request_rps - retry_rps).max(0.0);
+    retry_rps >= RETRY_STORM_MIN_RPS && retry_rps > first * RETRY_STORM_RATIO
+}
+
+/// Retry amplification across every edge out of one node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryAmplification {
+    /// Requests sent for every first attempt
+    pub factor: f32,
+    pub retry_rps: f32,
+    /// Retries outweigh first attempts over all of the node's edges
+    pub storm: bool,
+}
+
+#[derive(Debug, Default)]
+struct EdgeSeries {
+    points: VecDeque<EdgeMetricPoint>,
+    last: EdgeCounters,
+    rps: f32,
+    drop_rps: f32,
+    avg_latency_ms: f32,
+    request_rps: f32,
+    retry_rps: f32,
+}
+
 pub struct MetricsCollector {
     pub history: VecDeque<MetricPoint>,
     pub max_points: usize,
+    /// Latency percentiles recorded with each point, in ascending order
+    pub percentiles: Vec<f32>,
+    nodes: HashMap<NodeId, NodeSeries>,
+    edges: HashMap<(NodeId, NodeId), EdgeSeries>,
+    kinds: BTreeMap<String, VecDeque<KindMetricPoint>>,
+    annotations: VecDeque<Annotation>,
+    /// Bucket size of the thinned part of the history
+    coarse_step_us: u64,
+    /// Scenario steps already annotated; `None` right after a reset, when
+    /// steps applied before it should not be marked
+    scenario_seen: Option<usize>,
     last_sample_time_us: u64,
     last_success_count: u64,
     last_failure_count: u64,
@@ -25,6 +201,13 @@ impl MetricsCollector {
         Self {
             history: VecDeque::with_capacity(max_points),
             max_points,
+            percentiles: vec![50.0, 99.0],
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+            kinds: BTreeMap::new(),
+            annotations: VecDeque::new(),
+            coarse_step_us: 2 * SAMPLE_STEP_US,
+            scenario_seen: Some(0),
             last_sample_time_us: 0,
             last_success_count: 0,
             last_failure_count: 0,
@@ -33,15 +216,31 @@ impl MetricsCollector {
         }
     }
 
+    /// Records `percentiles` instead of the default p50 and p99.
+    pub fn with_percentiles(mut self, percentiles: impl IntoIterator<Item = f32>) -> Self {
+        self.set_percentiles(percentiles);
+        self
+    }
+
+    /// Changes the recorded percentiles from the next sample on; points
+    /// already taken keep the ones they were recorded with.
+    pub fn set_percentiles(&mut self, percentiles: impl IntoIterator<Item = f32>) {
+        self.percentiles = percentiles.into_iter().collect();
+        self.percentiles.sort_by(f32::total_cmp);
+        self.percentiles.dedup();
+    }
+
     pub fn update(&mut self, sim: &Simulation, stats_window_us: u64) {
-        let step_us = 200_000;
-        if sim.time < self.last_sample_time_us + step_us {
+        if sim.time < self.last_sample_time_us + SAMPLE_STEP_US {
             return;
         }
 
-        let p99_ms = sim
-            .get_percentile(99.0, stats_window_us)
-            .map_or(0.0, |v| v as f32 / 1000.0);
+        let latency_ms = sim
+            .get_percentiles(&self.percentiles, stats_window_us)
+            .into_iter()
+            .zip(&self.percentiles)
+            .map(|(v, &p)| (p, v.map_or(0.0, |v| v as f32 / 1000.0)))
+            .collect();
 
         let success_delta = sim.success_count.saturating_sub(self.last_success_count);
         let failure_delta = sim.failure_count.saturating_sub(self.last_failure_count);
@@ -58,22 +257,547 @@ impl MetricsCollector {
 
         self.history.push_back(MetricPoint {
             sim_time_us: sim.time,
-            p99_ms,
+            latency_ms,
             success_rps: self.current_success_rps,
             failure_rps: self.current_failure_rps,
         });
 
-        if self.history.len() > self.max_points {
-            self.history.pop_front();
-        }
+        self.annotate_scenario(sim);
+        self.sample_nodes(sim, delta_t_s, alpha);
+        self.sample_edges(sim, delta_t_s, alpha);
+        self.thin_history();
 
         self.last_sample_time_us = sim.time;
         self.last_success_count = sim.success_count;
         self.last_failure_count = sim.failure_count;
     }
 
+    fn sample_nodes(&mut self, sim: &Simulation, delta_t_s: f32, alpha: f32) {
+        self.nodes.retain(|id, _| sim.components.contains_key(id));
+        let mut kinds: BTreeMap<&str, KindMetricPoint> = BTreeMap::new();
+        for (&id, comp) in &sim.components {
+            let series = self.nodes.entry(id).or_default();
+            let received = comp.received_count();
+            let errors = comp.error_count();
+            // Counters restart when stats are reset; treat that as zero traffic
+            let raw_rps = received.saturating_sub(series.last_received) as f32 / delta_t_s;
+            let raw_error_rps = errors.saturating_sub(series.last_errors) as f32 / delta_t_s;
+            series.rps = series.rps * (1.0 - alpha) + raw_rps * alpha;
+            series.error_rps = series.error_rps * (1.0 - alpha) + raw_error_rps * alpha;
+            series.last_received = received;
+            series.last_errors = errors;
+
+            series.points.push_back(NodeMetricPoint {
+                sim_time_us: sim.time,
+                utilization: comp.utilization(),
+                queue_depth: comp.queue_depth(),
+                error_rps: series.error_rps,
+                rps: series.rps,
+                queue_wait_ms: comp.queue_wait_us().unwrap_or(0) as f32 / 1000.0,
+                service_ms: comp.service_time_us().unwrap_or(0) as f32 / 1000.0,
+            });
+            if let Some(point) = series.points.back() {
+                kinds
+                    .entry(comp.kind())
+                    .or_default()
+                    .add(point, comp.is_healthy());
+            }
+        }
+
+        self.kinds
+            .retain(|kind, _| kinds.contains_key(kind.as_str()));
+        for (kind, mut point) in kinds {
+            point.sim_time_us = sim.time;
+            point.utilization /= point.nodes as f32;
+            self.kinds
+                .entry(kind.to_string())
+                .or_default()
+                .push_back(point);
+        }
+    }
+
+    fn sample_edges(&mut self, sim: &Simulation, delta_t_s: f32, alpha: f32) {
+        self.edges.retain(|key, _| sim.edge_stats.contains_key(key));
+        for (&key, counters) in &sim.edge_stats {
+            let series = self.edges.entry(key).or_default();
+            let sent = counters.sent.saturating_sub(series.last.sent);
+            let dropped = counters.dropped.saturating_sub(series.last.dropped);
+            let requests = counters.requests.saturating_sub(series.last.requests);
+            let retries = counters.retries.saturating_sub(series.last.retries);
+            let latency = counters
+                .added_latency_us
+                .saturating_sub(series.last.added_latency_us);
+            series.rps = series.rps * (1.0 - alpha) + (sent as f32 / delta_t_s) * alpha;
+            series.drop_rps =
+                series.drop_rps * (1.0 - alpha) + (dropped as f32 / delta_t_s) * alpha;
+            series.request_rps =
+                series.request_rps * (1.0 - alpha) + (requests as f32 / delta_t_s) * alpha;
+            series.retry_rps =
+                series.retry_rps * (1.0 - alpha) + (retries as f32 / delta_t_s) * alpha;
+            // Keep the previous mean while the link is idle
+            let delivered = sent.saturating_sub(dropped);
+            if delivered > 0 {
+                series.avg_latency_ms = latency as f32 / delivered as f32 / 1000.0;
+            }
+            series.last = *counters;
+
+            series.points.push_back(EdgeMetricPoint {
+                sim_time_us: sim.time,
+                rps: series.rps,
+                drop_rps: series.drop_rps,
+                avg_latency_ms: series.avg_latency_ms,
+                request_rps: series.request_rps,
+                retry_rps: series.retry_rps,
+            });
+        }
+    }
+
+    /// Keeps every series within `max_points` without losing the start of
+    /// the run: the newest half stays at full resolution, older samples are
+    /// merged into buckets of `coarse_step_us`, which doubles whenever they
+    /// no longer fit. Every series is bucketed the same way, so samples stay
+    /// aligned across series.
+    fn thin_history(&mut self) {
+        if self.history.len() <= self.max_points {
+            return;
+        }
+        let fine = (self.max_points / 2).max(1);
+        let coarse_cap = (self.max_points - fine).max(1);
+        let fine_since = self.history[self.history.len() - fine].sim_time_us;
+        while thin(&mut self.history, fine_since, self.coarse_step_us) > coarse_cap {
+            self.coarse_step_us *= 2;
+        }
+        let step = self.coarse_step_us;
+        for series in self.nodes.values_mut() {
+            thin(&mut series.points, fine_since, step);
+        }
+        for series in self.edges.values_mut() {
+            thin(&mut series.points, fine_since, step);
+        }
+        for points in self.kinds.values_mut() {
+            thin(points, fine_since, step);
+        }
+    }
+
+    /// How much retries multiply the requests `id` sends, from the latest
+    /// sample of each of its outgoing edges. `None` while it sends next to
+    /// no retries.
+    pub fn retry_amplification(&self, id: NodeId) -> Option<RetryAmplification> {
+        let (requests, retries) = self
+            .edges
+            .iter()
+            .filter(|((from, _), _)| *from == id)
+            .filter_map(|(_, series)| series.points.back())
+            .fold((0.0, 0.0), |(req, ret), p| {
+                (req + p.request_rps, ret + p.retry_rps)
+            });
+        if retries < RETRY_STORM_MIN_RPS / 10.0 {
+            return None;
+        }
+        Some(RetryAmplification {
+            factor: amplification(requests, retries).unwrap_or(f32::INFINITY),
+            retry_rps: retries,
+            storm: is_retry_storm(requests, retries),
+        })
+    }
+
+    /// Sampled history of the directed edge `from -> to`, oldest first.
+    pub fn edge_history(&self, from: NodeId, to: NodeId) -> Option<&VecDeque<EdgeMetricPoint>> {
+        self.edges.get(&(from, to)).map(|s| &s.points)
+    }
+
+    /// Sampled history of one node, oldest first.
+    pub fn node_history(&self, id: NodeId) -> Option<&VecDeque<NodeMetricPoint>> {
+        self.nodes.get(&id).map(|s| &s.points)
+    }
+
+    /// Marks `text` at `sim_time_us`. Repeating the latest annotation within
+    /// a second (e.g. while a slider is dragged) only moves it forward.
+    pub fn annotate(&mut self, sim_time_us: u64, text: impl Into<String>) {
+        let text = text.into();
+        if let Some(last) = self.annotations.back_mut() {
+            if last.text == text
+                && sim_time_us.saturating_sub(last.sim_time_us) < ANNOTATION_MERGE_US
+            {
+                last.sim_time_us = sim_time_us;
+                return;
+            }
+        }
+        self.push_annotation(Annotation { sim_time_us, text });
+    }
+
+    fn push_annotation(&mut self, annotation: Annotation) {
+        self.annotations.push_back(annotation);
+        if self.annotations.len() > self.max_points {
+            self.annotations.pop_front();
+        }
+    }
+
+    /// Recent annotations (at most `max_points`), oldest first.
+    pub fn annotations(&self) -> &VecDeque<Annotation> {
+        &self.annotations
+    }
+
+    /// Marks scenario steps the simulation applied since the last sample.
+    fn annotate_scenario(&mut self, sim: &Simulation) {
+        let cursor = sim.scenario_cursor.min(sim.scenario.steps.len());
+        // A reloaded or rewound scenario restarts from its new cursor
+        let seen = self.scenario_seen.unwrap_or(cursor).min(cursor);
+        for step in sim.scenario.steps[seen..cursor]
+            .iter()
+            .filter(|s| s.enabled)
+        {
+            self.push_annotation(Annotation {
+                sim_time_us: step.at_us,
+                text: step.action.describe(),
+            });
+        }
+        self.scenario_seen = Some(cursor);
+    }
+
+    /// Sampled history of all nodes of `kind` combined, oldest first.
+    pub fn kind_history(&self, kind: &str) -> Option<&VecDeque<KindMetricPoint>> {
+        self.kinds.get(kind)
+    }
+
+    /// Component kinds present at the last sample, in name order.
+    pub fn kinds(&self) -> impl Iterator<Item = &str> {
+        self.kinds.keys().map(String::as_str)
+    }
+
+    /// Predicted vs simulated queueing behaviour of a server, built from its
+    /// current configuration and its samples over the last `window_us`.
+    /// `None` for other kinds of node or before the first sample.
+    pub fn queueing_check(
+        &self,
+        sim: &Simulation,
+        id: NodeId,
+        window_us: u64,
+    ) -> Option<QueueingCheck> {
+        let server = sim.components.get(&id)?.downcast_ref::<Server>()?;
+        let points = &self.nodes.get(&id)?.points;
+        let last = points.back()?;
+        let since = last.sim_time_us.saturating_sub(window_us);
+        let recent: Vec<&NodeMetricPoint> =
+            points.iter().filter(|p| p.sim_time_us >= since).collect();
+        let n = recent.len() as f64;
+
+        let config = server.config.read().unwrap();
+        Some(QueueingCheck {
+            model: MmcModel {
+                arrival_rps: last.rps as f64,
+                service_us: config.service_time as f64 * 1000.0,
+                servers: config.concurrency,
+            },
+            utilization: recent.iter().map(|p| p.utilization as f64).sum::<f64>() / n,
+            queue_len: recent.iter().map(|p| p.queue_depth as f64).sum::<f64>() / n,
+            response_us: server.latency_ewma_us,
+        })
+    }
+
+    /// Live utilization, ρ and Little's-law occupancy of server `id`, with
+    /// the arrival rate of the latest sample; `None` for other kinds.
+    pub fn live_load(&self, sim: &Simulation, id: NodeId) -> Option<LiveLoad> {
+        let server = sim.components.get(&id)?.downcast_ref::<Server>()?;
+        let arrival_rps = self
+            .nodes
+            .get(&id)
+            .and_then(|n| n.points.back())
+            .map_or(0.0, |p| p.rps as f64);
+        let config = server.config.read().unwrap();
+        Some(LiveLoad {
+            busy: server.active_threads,
+            workers: config.concurrency,
+            arrival_rps,
+            capacity_rps: config.concurrency as f64 * 1000.0 / config.service_time.max(1) as f64,
+            response_us: server.latency_ewma_us,
+        })
+    }
+
+    /// Writes the whole history as CSV, one row per sample.
+    ///
+    /// The global series come first, followed by `node<id>_*` columns for
+    /// every node and `edge<from>_<to>_*` columns for every directed edge,
+    /// both in id order. Cells are left empty where a node or edge has no
+    /// sample at that time (added later, or removed).
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut node_ids: Vec<_> = self.nodes.keys().copied().collect();
+        node_ids.sort_unstable();
+        let mut edge_keys: Vec<_> = self.edges.keys().copied().collect();
+        edge_keys.sort_unstable();
+
+        write!(out, "sim_time_us")?;
+        for &p in &self.percentiles {
+            write!(out, ",{}_ms", percentile_label(p))?;
+        }
+        write!(out, ",success_rps,failure_rps")?;
+        for id in &node_ids {
+            write!(
+                out,
+                ",node{id}_utilization,node{id}_queue_depth,node{id}_rps,node{id}_error_rps,\
+                 node{id}_queue_wait_ms,node{id}_service_ms"
+            )?;
+        }
+        for (from, to) in &edge_keys {
+            let e = format!("edge{}_{}", from, to);
+            write!(out, ",{e}_rps,{e}_drop_rps,{e}_avg_latency_ms")?;
+        }
+        writeln!(out)?;
+
+        let nodes: Vec<HashMap<u64, &NodeMetricPoint>> = node_ids
+            .iter()
+            .map(|id| {
+                self.nodes[id]
+                    .points
+                    .iter()
+                    .map(|p| (p.sim_time_us, p))
+                    .collect()
+            })
+            .collect();
+        let edges: Vec<HashMap<u64, &EdgeMetricPoint>> = edge_keys
+            .iter()
+            .map(|key| {
+                self.edges[key]
+                    .points
+                    .iter()
+                    .map(|p| (p.sim_time_us, p))
+                    .collect()
+            })
+            .collect();
+
+        for point in &self.history {
+            let t = point.sim_time_us;
+            write!(out, "{}", t)?;
+            for &p in &self.percentiles {
+                match point.latency_ms(p) {
+                    Some(ms) => write!(out, ",{}", ms)?,
+                    None => write!(out, ",")?,
+                }
+            }
+            write!(out, ",{},{}", point.success_rps, point.failure_rps)?;
+            for series in &nodes {
+                match series.get(&t) {
+                    Some(p) => write!(
+                        out,
+                        ",{},{},{},{},{},{}",
+                        p.utilization,
+                        p.queue_depth,
+                        p.rps,
+                        p.error_rps,
+                        p.queue_wait_ms,
+                        p.service_ms
+                    )?,
+                    None => write!(out, ",,,,,,")?,
+                }
+            }
+            for series in &edges {
+                match series.get(&t) {
+                    Some(p) => write!(out, ",{},{},{}", p.rps, p.drop_rps, p.avg_latency_ms)?,
+                    None => write!(out, ",,,")?,
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = Vec::new();
+        // Writing to a Vec cannot fail
+        let _ = self.write_csv(&mut out);
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    pub fn save_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_csv(&mut file)?;
+        file.flush()
+    }
+
+    /// Renders the latest sample and the simulation's counters in the
+    /// Prometheus text exposition format, for serving on `/metrics`.
+    pub fn to_prometheus(&self, sim: &Simulation) -> String {
+        use std::fmt::Write as _;
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "{}{} {}", name, labels, value);
+            }
+        };
+        let last = self.history.back();
+
+        metric(
+            "slay_sim_time_seconds",
+            "gauge",
+            "Virtual time of the simulation.",
+            &[(String::new(), sim.time as f64 / 1_000_000.0)],
+        );
+        metric(
+            "slay_requests_total",
+            "counter",
+            "Completed requests by outcome.",
+            &[
+                ("{outcome=\"success\"}".into(), sim.success_count as f64),
+                ("{outcome=\"failure\"}".into(), sim.failure_count as f64),
+            ],
+        );
+        metric(
+            "slay_failures_total",
+            "counter",
+            "Failed requests by reason.",
+            &sim.failure_reasons
+                .iter()
+                .map(|(reason, &count)| (format!("{{reason=\"{}\"}}", reason.key()), count as f64))
+                .collect::<Vec<_>>(),
+        );
+        metric(
+            "slay_latency_seconds",
+            "gauge",
+            "End-to-end latency percentiles over the stats window.",
+            &self
+                .percentiles
+                .iter()
+                .filter_map(|&p| {
+                    let ms = last?.latency_ms(p)?;
+                    Some((
+                        format!("{{quantile=\"{}\"}}", p / 100.0),
+                        ms as f64 / 1000.0,
+                    ))
+                })
+                .collect::<Vec<_>>(),
+        );
+        metric(
+            "slay_requests_per_second",
+            "gauge",
+            "Smoothed completion rate by outcome.",
+            &[
+                (
+                    "{outcome=\"success\"}".into(),
+                    last.map_or(0.0, |p| p.success_rps as f64),
+                ),
+                (
+                    "{outcome=\"failure\"}".into(),
+                    last.map_or(0.0, |p| p.failure_rps as f64),
+                ),
+            ],
+        );
+        if sim.costs.is_priced() {
+            metric(
+                "slay_cost_dollars_total",
+                "counter",
+                "Cost accrued by priced nodes.",
+                &[(String::new(), sim.total_cost())],
+            );
+        }
+
+        let mut node_ids: Vec<_> = self.nodes.keys().copied().collect();
+        node_ids.sort_unstable();
+        let node_samples = |value: fn(&NodeMetricPoint) -> f64| -> Vec<(String, f64)> {
+            node_ids
+                .iter()
+                .filter_map(|id| {
+                    let point = self.nodes[id].points.back()?;
+                    let comp = sim.components.get(id)?;
+                    let labels = format!(
+                        "{{node=\"{}\",kind=\"{}\",name=\"{}\"}}",
+                        id,
+                        escape_label(comp.kind()),
+                        escape_label(comp.name())
+                    );
+                    Some((labels, value(point)))
+                })
+                .collect()
+        };
+        metric(
+            "slay_node_utilization",
+            "gauge",
+            "Fraction of the node's capacity in use.",
+            &node_samples(|p| p.utilization as f64),
+        );
+        metric(
+            "slay_node_queue_depth",
+            "gauge",
+            "Requests waiting at the node.",
+            &node_samples(|p| p.queue_depth as f64),
+        );
+        metric(
+            "slay_node_requests_per_second",
+            "gauge",
+            "Smoothed rate of requests received by the node.",
+            &node_samples(|p| p.rps as f64),
+        );
+        metric(
+            "slay_node_errors_per_second",
+            "gauge",
+            "Smoothed rate of errors at the node.",
+            &node_samples(|p| p.error_rps as f64),
+        );
+        metric(
+            "slay_node_queue_wait_seconds",
+            "gauge",
+            "Smoothed time requests waited at the node before processing.",
+            &node_samples(|p| p.queue_wait_ms as f64 / 1000.0),
+        );
+        metric(
+            "slay_node_service_seconds",
+            "gauge",
+            "Smoothed processing time at the node, waiting excluded.",
+            &node_samples(|p| p.service_ms as f64 / 1000.0),
+        );
+
+        let mut edge_keys: Vec<_> = sim.edge_stats.keys().copied().collect();
+        edge_keys.sort_unstable();
+        let edge_label =
+            |(from, to): (NodeId, NodeId)| format!("{{from=\"{}\",to=\"{}\"}}", from, to);
+        let edge_counters = |value: fn(&EdgeCounters) -> u64| -> Vec<(String, f64)> {
+            edge_keys
+                .iter()
+                .map(|&key| (edge_label(key), value(&sim.edge_stats[&key]) as f64))
+                .collect()
+        };
+        metric(
+            "slay_edge_messages_total",
+            "counter",
+            "Messages sent over a directed edge.",
+            &edge_counters(|c| c.sent),
+        );
+        metric(
+            "slay_edge_dropped_total",
+            "counter",
+            "Messages lost on a directed edge.",
+            &edge_counters(|c| c.dropped),
+        );
+        metric(
+            "slay_edge_retries_total",
+            "counter",
+            "Retried requests sent over a directed edge.",
+            &edge_counters(|c| c.retries),
+        );
+        metric(
+            "slay_edge_latency_seconds",
+            "gauge",
+            "Mean latency the link recently added to delivered messages.",
+            &edge_keys
+                .iter()
+                .filter_map(|&(from, to)| {
+                    let point = self.edge_history(from, to)?.back()?;
+                    Some((edge_label((from, to)), point.avg_latency_ms as f64 / 1000.0))
+                })
+                .collect::<Vec<_>>(),
+        );
+        out
+    }
+
     pub fn reset(&mut self) {
         self.history.clear();
+        self.nodes.clear();
+        self.edges.clear();
+        self.kinds.clear();
+        self.annotations.clear();
+        self.coarse_step_us = 2 * SAMPLE_STEP_US;
+        self.scenario_seen = None;
         self.last_sample_time_us = 0;
         self.last_success_count = 0;
         self.last_failure_count = 0;
@@ -81,3 +805,191 @@ impl MetricsCollector {
         self.current_failure_rps = 0.0;
     }
 }
+
+/// A sample that can be merged with the one after it when history is thinned.
+trait Sample {
+    fn time(&self) -> u64;
+    /// Folds `next` into `self`, weighting each by the time it covers.
+    /// Rates and levels are averaged; latencies and peaks keep the maximum
+    /// so spikes survive.
+    fn absorb(&mut self, next: &Self, w_self: f32, w_next: f32);
+}
+
+fn mix(a: f32, b: f32, wa: f32, wb: f32) -> f32 {
+    (a * wa + b * wb) / (wa + wb)
+}
+
+impl Sample for MetricPoint {
+    fn time(&self) -> u64 {
+        self.sim_time_us
+    }
+    fn absorb(&mut self, next: &Self, wa: f32, wb: f32) {
+        self.sim_time_us = next.sim_time_us;
+        for (p, ms) in self.latency_ms.iter_mut() {
+            if let Some(other) = next.latency_ms(*p) {
+                *ms = ms.max(other);
+            }
+        }
+        self.success_rps = mix(self.success_rps, next.success_rps, wa, wb);
+        self.failure_rps = mix(self.failure_rps, next.failure_rps, wa, wb);
+    }
+}
+
+impl Sample for NodeMetricPoint {
+    fn time(&self) -> u64 {
+        self.sim_time_us
+    }
+    fn absorb(&mut self, next: &Self, wa: f32, wb: f32) {
+        self.sim_time_us = next.sim_time_us;
+        self.utilization = mix(self.utilization, next.utilization, wa, wb);
+        self.queue_depth =
+            mix(self.queue_depth as f32, next.queue_depth as f32, wa, wb).round() as u32;
+        self.error_rps = mix(self.error_rps, next.error_rps, wa, wb);
+        self.rps = mix(self.rps, next.rps, wa, wb);
+        self.queue_wait_ms = mix(self.queue_wait_ms, next.queue_wait_ms, wa, wb);
+        self.service_ms = mix(self.service_ms, next.service_ms, wa, wb);
+    }
+}
+
+impl Sample for KindMetricPoint {
+    fn time(&self) -> u64 {
+        self.sim_time_us
+    }
+    fn absorb(&mut self, next: &Self, wa: f32, wb: f32) {
+        self.sim_time_us = next.sim_time_us;
+        self.nodes = next.nodes;
+        self.healthy = next.healthy;
+        self.utilization = mix(self.utilization, next.utilization, wa, wb);
+        self.max_utilization = self.max_utilization.max(next.max_utilization);
+        self.queue_depth =
+            mix(self.queue_depth as f32, next.queue_depth as f32, wa, wb).round() as u32;
+        self.error_rps = mix(self.error_rps, next.error_rps, wa, wb);
+        self.rps = mix(self.rps, next.rps, wa, wb);
+    }
+}
+
+impl Sample for EdgeMetricPoint {
+    fn time(&self) -> u64 {
+        self.sim_time_us
+    }
+    fn absorb(&mut self, next: &Self, wa: f32, wb: f32) {
+        self.sim_time_us = next.sim_time_us;
+        self.rps = mix(self.rps, next.rps, wa, wb);
+        self.drop_rps = mix(self.drop_rps, next.drop_rps, wa, wb);
+        self.avg_latency_ms = mix(self.avg_latency_ms, next.avg_latency_ms, wa, wb);
+        self.request_rps = mix(self.request_rps, next.request_rps, wa, wb);
+        self.retry_rps = mix(self.retry_rps, next.retry_rps, wa, wb);
+    }
+}
+
+/// Merges consecutive samples older than `fine_since` that fall into the
+/// same `step_us` bucket; a merged sample is stamped with the time of the
+/// last one it contains. Returns how many samples older than `fine_since`
+/// remain.
+fn thin<T: Sample>(points: &mut VecDeque<T>, fine_since: u64, step_us: u64) -> usize {
+    let mut out: VecDeque<T> = VecDeque::with_capacity(points.len());
+    let mut coarse = 0;
+    let mut prev_time = None;
+    // Start of the time covered by the last sample in `out`
+    let mut back_start = 0;
+    for point in points.drain(..) {
+        let time = point.time();
+        let start = prev_time.unwrap_or(time);
+        prev_time = Some(time);
+        if time < fine_since {
+            if let Some(back) = out.back_mut() {
+                if back.time() / step_us == time / step_us {
+                    let w_back = (back.time() - back_start).max(1) as f32;
+                    let w_next = (time - start).max(1) as f32;
+                    back.absorb(&point, w_back, w_next);
+                    continue;
+                }
+            }
+            coarse += 1;
+        }
+        back_start = start;
+        out.push_back(point);
+    }
+    *points = out;
+    coarse
+}
+
+/// Escapes a Prometheus label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Latency histograms bucketed by completion time, so percentiles can be
+/// computed over a trailing window instead of the whole run.
+///
+/// Samples are grouped into fixed `interval_us` slices; a query covers every
+/// slice overlapping `[now - window, now]`, so the effective window is rounded
+/// up to the slice size. Slices older than `max_window_us` are dropped.
+#[derive(Debug, Clone)]
+pub struct WindowedHistogram {
+    pub interval_us: u64,
+    pub max_window_us: u64,
+    slices: VecDeque<(u64, Histogram<u64>)>,
+}
+
+impl WindowedHistogram {
+    pub fn new(interval_us: u64, max_window_us: u64) -> Self {
+        Self {
+            interval_us: interval_us.max(1),
+            max_window_us,
+            slices: VecDeque::new(),
+        }
+    }
+
+    fn new_slice() -> Histogram<u64> {
+        // Two significant digits keeps each slice small enough to merge per frame
+        Histogram::<u64>::new_with_bounds(1, 60_000_000, 2).unwrap()
+    }
+
+    pub fn record(&mut self, now: u64, value: u64) {
+        self.record_n(now, value, 1);
+    }
+
+    /// Records `count` samples of the same `value` at once.
+    pub fn record_n(&mut self, now: u64, value: u64, count: u64) {
+        let start = now - now % self.interval_us;
+        if self.slices.back().is_none_or(|(s, _)| *s < start) {
+            self.slices.push_back((start, Self::new_slice()));
+        }
+        if let Some((_, h)) = self.slices.back_mut() {
+            h.saturating_record_n(value, count);
+        }
+
+        let oldest = start.saturating_sub(self.max_window_us);
+        while self.slices.front().is_some_and(|(s, _)| *s < oldest) {
+            self.slices.pop_front();
+        }
+    }
+
+    /// Percentile `p` over samples recorded in the last `window_us` before `now`.
+    pub fn percentile(&self, now: u64, window_us: u64, p: f64) -> Option<u64> {
+        self.percentiles(now, window_us, &[p])[0]
+    }
+
+    /// Several percentiles over the same window, merging the slices once.
+    pub fn percentiles(&self, now: u64, window_us: u64, ps: &[f64]) -> Vec<Option<u64>> {
+        let from = now.saturating_sub(window_us.min(self.max_window_us));
+        let mut merged = Self::new_slice();
+        for (start, h) in self.slices.iter().rev() {
+            if start + self.interval_us <= from {
+                break;
+            }
+            merged.add(h).ok();
+        }
+        ps.iter()
+            .map(|&p| (!merged.is_empty()).then(|| merged.value_at_percentile(p)))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.slices.clear();
+    }
+}
@@ -0,0 +1,28 @@
+use crate::engine::Simulation;
+
+/// Default for `Simulation::max_pending_events`: far more than any
+/// realistic topology keeps in flight, and still a few hundred megabytes.
+pub const DEFAULT_MAX_PENDING_EVENTS: usize = 1_000_000;
+
+/// How long after the last shed request `is_shedding` stays true.
+const SHEDDING_SHOWN_US: u64 = 1_000_000;
+
+impl Simulation {
+    /// Whether new requests were shed at the pending-event cap within the
+    /// last second of virtual time.
+    pub fn is_shedding(&self) -> bool {
+        self.last_shed_us
+            .is_some_and(|t| self.time < t + SHEDDING_SHOWN_US)
+    }
+
+    /// Whether a request generated now has to be dropped to keep the
+    /// pending events under `max_pending_events`. Counts it if so.
+    pub(crate) fn shed_generated_request(&mut self) -> bool {
+        if self.events.len() < self.max_pending_events {
+            return false;
+        }
+        self.shed_requests += 1;
+        self.last_shed_us = Some(self.time);
+        true
+    }
+}
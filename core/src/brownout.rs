@@ -0,0 +1,123 @@
+use crate::engine::{EventType, ScheduleCmd, Simulation};
+use crate::scenario::ScenarioAction;
+use crate::traits::{Health, NodeId};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A node running slow without being down. Every piece of work it
+/// schedules for itself, such as a server's service time, takes
+/// `slowdown` times as long plus `extra_latency_us`, and fails with
+/// probability `error_rate`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Brownout {
+    pub slowdown: f32,
+    #[serde(default)]
+    pub extra_latency_us: u64,
+    #[serde(default)]
+    pub error_rate: f32,
+    /// Virtual time the brownout wears off; `None` lasts until cleared
+    #[serde(default)]
+    pub until_us: Option<u64>,
+}
+
+impl Brownout {
+    /// How long work that normally takes `service_us` takes instead.
+    pub fn stretch(&self, service_us: u64) -> u64 {
+        (service_us as f64 * self.slowdown.max(0.0) as f64) as u64 + self.extra_latency_us
+    }
+
+    pub(crate) fn active_at(&self, time_us: u64) -> bool {
+        self.until_us.is_none_or(|until| time_us < until)
+    }
+}
+
+impl Simulation {
+    /// Slows `node_id` down as described by `brownout`, replacing any
+    /// brownout already on it. The node stays healthy.
+    pub fn inject_latency(&mut self, node_id: NodeId, brownout: Brownout) {
+        self.brownouts.insert(node_id, brownout);
+    }
+
+    /// Ends a brownout on `node_id` early. Work already scheduled keeps
+    /// its stretched duration.
+    pub fn clear_latency(&mut self, node_id: NodeId) {
+        self.brownouts.remove(&node_id);
+    }
+
+    /// How well `node_id` is working right now; `Down` if it is gone.
+    pub fn health(&self, node_id: NodeId) -> Health {
+        match self.components.get(&node_id) {
+            Some(comp) if comp.is_healthy() => health_of(self.brownout(node_id)),
+            _ => Health::Down,
+        }
+    }
+
+    /// Puts `node_id` into `health`: `Down` kills it, `Degraded` slows it
+    /// down and makes it flaky until changed, `Healthy` revives it and
+    /// lifts any degradation.
+    pub fn set_health(&mut self, node_id: NodeId, health: Health) {
+        match health {
+            Health::Healthy => {
+                self.clear_latency(node_id);
+                self.apply_action(ScenarioAction::Revive { node_id });
+            }
+            Health::Degraded {
+                slowdown,
+                error_rate,
+            } => {
+                self.apply_action(ScenarioAction::Revive { node_id });
+                self.inject_latency(
+                    node_id,
+                    Brownout {
+                        slowdown,
+                        extra_latency_us: 0,
+                        error_rate,
+                        until_us: None,
+                    },
+                );
+            }
+            Health::Down => self.set_healthy(node_id, false),
+        }
+    }
+
+    /// The brownout slowing `node_id` right now, if any.
+    pub fn brownout(&self, node_id: NodeId) -> Option<&Brownout> {
+        self.brownouts
+            .get(&node_id)
+            .filter(|b| b.active_at(self.time))
+    }
+
+    /// Stretches `cmd`, and may fail it, if it is work `node_id` schedules
+    /// for itself while browned out. Expired brownouts are dropped on the
+    /// way.
+    pub(crate) fn apply_brownout(&mut self, node_id: NodeId, cmd: &mut ScheduleCmd) {
+        let EventType::ProcessComplete { success, .. } = &mut cmd.event_type else {
+            return;
+        };
+        if cmd.node_id != node_id {
+            return;
+        }
+        let Some(brownout) = self.brownouts.get(&node_id).copied() else {
+            return;
+        };
+        if !brownout.active_at(self.time) {
+            self.brownouts.remove(&node_id);
+            return;
+        }
+        cmd.delay = brownout.stretch(cmd.delay);
+        if *success && brownout.error_rate > 0.0 && self.rng.gen::<f32>() < brownout.error_rate {
+            *success = false;
+        }
+    }
+}
+
+/// The health a node that is up has with `brownout` on it.
+pub(crate) fn health_of(brownout: Option<&Brownout>) -> Health {
+    match brownout {
+        Some(b) => Health::Degraded {
+            slowdown: b.slowdown,
+            error_rate: b.error_rate,
+        },
+        None => Health::Healthy,
+    }
+}
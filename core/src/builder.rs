@@ -0,0 +1,178 @@
+use crate::components::client::ClientConfig;
+use crate::components::load_balancer::LoadBalancerConfig;
+use crate::components::server::ServerConfig;
+use crate::engine::Simulation;
+use crate::network::{canonical_key, Link};
+use crate::topology::{LinkSpec, NodeSpec, Topology};
+use crate::traits::NodeId;
+use serde::Serialize;
+
+/// Fluent construction of a topology by node name, for embedding and tests.
+///
+/// Ids are handed out in the order nodes are added. `servers` adds a group
+/// named `name-1`, `name-2`, ...; anywhere a name is expected, the group's
+/// name stands for all of its members.
+///
+/// ```
+/// use slay_core::Topology;
+///
+/// let topology = Topology::new()
+///     .client("web", 100.0)
+///     .lb("lb")
+///     .servers("api", 3, |s| s.service_time = 20)
+///     .connect("web", "lb")
+///     .connect("lb", "api");
+/// assert_eq!(topology.ids("api").len(), 3);
+/// let mut sim = topology.build(42);
+/// sim.advance_by(1_000_000);
+/// assert!(sim.success_count > 0);
+/// ```
+impl Topology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a client sending `rps` requests per second.
+    pub fn client(self, name: &str, rps: f32) -> Self {
+        self.client_with(name, |c| c.arrival_rate = rps)
+    }
+
+    pub fn client_with(self, name: &str, configure: impl FnOnce(&mut ClientConfig)) -> Self {
+        let mut config = ClientConfig::default();
+        configure(&mut config);
+        self.node(name, "Client", &config)
+    }
+
+    /// Adds a load balancer with the default settings.
+    pub fn lb(self, name: &str) -> Self {
+        self.lb_with(name, |_| {})
+    }
+
+    pub fn lb_with(self, name: &str, configure: impl FnOnce(&mut LoadBalancerConfig)) -> Self {
+        let mut config = LoadBalancerConfig::default();
+        configure(&mut config);
+        self.node(name, "LoadBalancer", &config)
+    }
+
+    pub fn server(self, name: &str, configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        let mut config = ServerConfig::default();
+        configure(&mut config);
+        self.node(name, "Server", &config)
+    }
+
+    /// Adds `count` identical servers named `name-1` to `name-<count>`.
+    pub fn servers(
+        mut self,
+        name: &str,
+        count: usize,
+        configure: impl Fn(&mut ServerConfig),
+    ) -> Self {
+        for i in 1..=count {
+            self = self.server(&format!("{}-{}", name, i), &configure);
+        }
+        self
+    }
+
+    /// Adds a node of any registered kind with `config` as its settings.
+    pub fn node(mut self, name: &str, kind: &str, config: &impl Serialize) -> Self {
+        let id = self.max_node_id() + 1;
+        self.nodes.push(NodeSpec {
+            id,
+            kind: kind.to_string(),
+            name: name.to_string(),
+            config: serde_json::to_value(config).unwrap_or(serde_json::Value::Null),
+            targets: Vec::new(),
+            services: Vec::new(),
+            cost: Default::default(),
+        });
+        self
+    }
+
+    /// Connects every node called `from` to every node called `to`.
+    ///
+    /// # Panics
+    /// If either name matches no node.
+    pub fn connect(mut self, from: &str, to: &str) -> Self {
+        let targets = self.expect_ids(to);
+        for from in self.expect_ids(from) {
+            let node = self.nodes.iter_mut().find(|n| n.id == from).unwrap();
+            for &to in &targets {
+                if !node.targets.contains(&to) {
+                    node.targets.push(to);
+                }
+            }
+        }
+        self
+    }
+
+    /// Like `connect`, with `link` as the network between each pair.
+    pub fn connect_with(mut self, from: &str, to: &str, link: Link) -> Self {
+        for a in self.expect_ids(from) {
+            for b in self.expect_ids(to) {
+                let (a, b) = canonical_key(a, b);
+                self.links.retain(|l| (l.a, l.b) != (a, b));
+                self.links.push(LinkSpec {
+                    a,
+                    b,
+                    link: link.clone(),
+                });
+            }
+        }
+        self.links.sort_by_key(|l| (l.a, l.b));
+        self.connect(from, to)
+    }
+
+    /// Registers the nodes called `name` as members of `service`.
+    pub fn service(mut self, name: &str, service: &str) -> Self {
+        for id in self.expect_ids(name) {
+            let node = self.nodes.iter_mut().find(|n| n.id == id).unwrap();
+            if !node.services.iter().any(|s| s == service) {
+                node.services.push(service.to_string());
+            }
+        }
+        self
+    }
+
+    /// Id of the node called `name`, if exactly one node has that name.
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        match self.ids(name)[..] {
+            [id] => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Ids of the node called `name`, or of the members of the group of
+    /// that name, in the order they were added.
+    pub fn ids(&self, name: &str) -> Vec<NodeId> {
+        let exact: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .filter(|n| n.name == name)
+            .map(|n| n.id)
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+        self.nodes
+            .iter()
+            .filter(|n| {
+                n.name
+                    .strip_prefix(name)
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .is_some_and(|i| i.parse::<usize>().is_ok())
+            })
+            .map(|n| n.id)
+            .collect()
+    }
+
+    fn expect_ids(&self, name: &str) -> Vec<NodeId> {
+        let ids = self.ids(name);
+        assert!(!ids.is_empty(), "no node or group named '{}'", name);
+        ids
+    }
+
+    /// A simulation of this topology with client traffic already scheduled.
+    pub fn build(&self, seed: u64) -> Simulation {
+        Simulation::from_topology(self, seed)
+    }
+}
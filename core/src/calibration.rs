@@ -0,0 +1,297 @@
+use crate::network::{canonical_key, EdgeConfig};
+use crate::topology::{LinkSpec, NodeSpec, Topology};
+use crate::traits::NodeId;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Why a latency table could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationError {
+    /// 1-based line the problem was found on
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+fn error<T>(line: usize, message: impl Into<String>) -> Result<T, CalibrationError> {
+    Err(CalibrationError {
+        line,
+        message: message.into(),
+    })
+}
+
+const FROM_COLUMNS: [&str; 3] = ["from", "source", "src"];
+const TO_COLUMNS: [&str; 5] = ["to", "destination", "dest", "dst", "target"];
+const RTT_MS_COLUMNS: [&str; 3] = ["rtt_ms", "rtt", "latency_ms"];
+
+/// Round trips measured between two endpoints, in either direction.
+#[derive(Debug, Clone, Default)]
+struct Measured {
+    rtt_us: Vec<u64>,
+    /// Probes that got no answer; `None` when the table does not record
+    /// losses, as with region matrices
+    lost: Option<u32>,
+}
+
+impl Measured {
+    /// One-way latency from the fastest round trip, jitter from how far the
+    /// median strays from it, and loss from the unanswered probes. Settings
+    /// the measurements say nothing about are kept from `base`.
+    fn edge(&self, base: EdgeConfig) -> EdgeConfig {
+        let mut rtt = self.rtt_us.clone();
+        rtt.sort_unstable();
+        let mut edge = base;
+        if let (Some(&fastest), Some(&median)) = (rtt.first(), rtt.get(rtt.len() / 2)) {
+            edge.latency_us = fastest / 2;
+            edge.jitter_us = median - fastest;
+        }
+        if let Some(lost) = self.lost {
+            let probes = rtt.len() as f32 + lost as f32;
+            edge.packet_loss_rate = lost as f32 / probes.max(1.0);
+        }
+        edge
+    }
+}
+
+/// Measured round-trip times between named endpoints, for setting link
+/// latencies from real data instead of guesses.
+///
+/// Endpoints are node names, replica groups (`api` for `api-1`, `api-2`,
+/// ...), services or zones; nodes are placed in zones with `place`, so a
+/// table of cloud-region latencies can stand for every link between nodes
+/// of two regions.
+///
+/// ```
+/// use slay_core::{LatencyTable, Topology};
+///
+/// let mut topology = Topology::new()
+///     .client("web", 50.0)
+///     .servers("api", 2, |_| {})
+///     .connect("web", "api");
+/// let table = LatencyTable::from_csv("from,to,rtt_ms\nweb,api,24\nweb,api,30\n")
+///     .unwrap();
+/// let calibration = table.calibrate(&mut topology);
+/// assert_eq!(calibration.links.len(), 2);
+/// assert_eq!(topology.links[0].link.min_to_max.latency_us, 12_000);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LatencyTable {
+    /// Keyed by endpoint names in sorted order
+    measured: BTreeMap<(String, String), Measured>,
+    /// Zone of the nodes called by each name
+    zones: BTreeMap<String, String>,
+}
+
+/// What `LatencyTable::calibrate` changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calibration {
+    /// Links set from measurements, keyed canonically (`a < b`)
+    pub links: Vec<(NodeId, NodeId)>,
+    /// Endpoints of the table matching no node, service or populated zone
+    pub unmatched: Vec<String>,
+}
+
+impl LatencyTable {
+    /// Reads a CSV table in one of two shapes.
+    ///
+    /// Measurements have a header naming `from` (or `source`, `src`), `to`
+    /// (or `destination`, `dst`, `target`) and `rtt_ms` (or `rtt`,
+    /// `latency_ms`, or `rtt_us` in microseconds) columns, and one row per
+    /// probe; a probe with an empty, `timeout` or `lost` RTT went
+    /// unanswered. Other columns are ignored.
+    ///
+    /// A matrix, as cloud providers publish between regions, has the
+    /// endpoint names across the header after a corner cell, and one row
+    /// per endpoint starting with its name, holding RTTs in milliseconds;
+    /// empty or `-` cells are unknown.
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn from_csv(csv: &str) -> Result<Self, CalibrationError> {
+        let mut rows = csv
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line, text)| (line, split_row(text)));
+        let Some((header_line, header)) = rows.next() else {
+            return error(1, "the table is empty");
+        };
+        let column = |names: &[&str]| {
+            header
+                .iter()
+                .position(|h| names.contains(&h.to_ascii_lowercase().as_str()))
+        };
+        let mut table = LatencyTable::default();
+        match (column(&FROM_COLUMNS), column(&TO_COLUMNS)) {
+            (Some(from), Some(to)) => {
+                let (rtt, scale) = match (column(&RTT_MS_COLUMNS), column(&["rtt_us"])) {
+                    (Some(rtt), _) => (rtt, 1000.0),
+                    (None, Some(rtt)) => (rtt, 1.0),
+                    (None, None) => return error(header_line, "no rtt_ms or rtt_us column"),
+                };
+                for (line, row) in rows {
+                    let cell = |i: usize| row.get(i).map_or("", String::as_str);
+                    if cell(from).is_empty() || cell(to).is_empty() {
+                        return error(line, "missing endpoint");
+                    }
+                    let measured = table.entry(cell(from), cell(to));
+                    let lost = measured.lost.get_or_insert(0);
+                    match cell(rtt) {
+                        "" | "timeout" | "lost" => *lost += 1,
+                        value => measured.rtt_us.push(parse_rtt(line, value, scale)?),
+                    }
+                }
+            }
+            _ => {
+                let names = &header[1..];
+                for (line, row) in rows {
+                    if row.len() > header.len() {
+                        return error(line, format!("more than {} cells", header.len()));
+                    }
+                    for (to, value) in names.iter().zip(row.iter().skip(1)) {
+                        if value.is_empty() || value == "-" {
+                            continue;
+                        }
+                        let rtt_us = parse_rtt(line, value, 1000.0)?;
+                        table.entry(&row[0], to).rtt_us.push(rtt_us);
+                    }
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let csv = std::fs::read_to_string(path)?;
+        Self::from_csv(&csv).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn entry(&mut self, a: &str, b: &str) -> &mut Measured {
+        let key = if a <= b { (a, b) } else { (b, a) };
+        self.measured
+            .entry((key.0.to_string(), key.1.to_string()))
+            .or_default()
+    }
+
+    /// Places the nodes called `name` (a node, replica group or service)
+    /// in `zone`, an endpoint of the table.
+    pub fn place(mut self, name: &str, zone: &str) -> Self {
+        self.zones.insert(name.to_string(), zone.to_string());
+        self
+    }
+
+    /// Measured one-way latency between two endpoints, from the fastest
+    /// round trip.
+    pub fn latency_us(&self, a: &str, b: &str) -> Option<u64> {
+        self.find(a, b)
+            .map(|m| m.edge(EdgeConfig::default()).latency_us)
+    }
+
+    fn find(&self, a: &str, b: &str) -> Option<&Measured> {
+        let key = if a <= b { (a, b) } else { (b, a) };
+        self.measured
+            .get(&(key.0.to_string(), key.1.to_string()))
+            .filter(|m| !m.rtt_us.is_empty())
+    }
+
+    /// Sets the latency, jitter and (when probes were lost) packet loss of
+    /// every connection in `topology` whose ends were measured, in both
+    /// directions.
+    ///
+    /// A node is looked up by its name, then its replica group, then its
+    /// services, then its zone, so measurements between nodes win over
+    /// those between their zones. Links between nodes of one zone take the
+    /// zone's entry with itself, if the table has one.
+    pub fn calibrate(&self, topology: &mut Topology) -> Calibration {
+        let endpoints: BTreeMap<NodeId, Vec<String>> = topology
+            .nodes
+            .iter()
+            .map(|n| (n.id, self.endpoints(n)))
+            .collect();
+        let mut pairs: Vec<(NodeId, NodeId)> = topology
+            .nodes
+            .iter()
+            .flat_map(|n| n.targets.iter().map(|&t| canonical_key(n.id, t)))
+            .chain(topology.links.iter().map(|l| (l.a, l.b)))
+            .filter(|(a, b)| a != b && endpoints.contains_key(a) && endpoints.contains_key(b))
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let mut calibration = Calibration::default();
+        for (a, b) in pairs {
+            let measured = endpoints[&a]
+                .iter()
+                .flat_map(|x| endpoints[&b].iter().map(move |y| (x, y)))
+                .find_map(|(x, y)| self.find(x, y));
+            let Some(measured) = measured else {
+                continue;
+            };
+            let index = match topology.links.iter().position(|l| (l.a, l.b) == (a, b)) {
+                Some(index) => index,
+                None => {
+                    topology.links.push(LinkSpec {
+                        a,
+                        b,
+                        link: Default::default(),
+                    });
+                    topology.links.len() - 1
+                }
+            };
+            let link = &mut topology.links[index].link;
+            link.min_to_max = measured.edge(link.min_to_max);
+            link.max_to_min = measured.edge(link.max_to_min);
+            calibration.links.push((a, b));
+        }
+        topology.links.sort_by_key(|l| (l.a, l.b));
+
+        let known: Vec<&String> = endpoints.values().flatten().collect();
+        let mut unmatched: Vec<String> = self
+            .measured
+            .keys()
+            .flat_map(|(a, b)| [a, b])
+            .filter(|name| !known.contains(name))
+            .cloned()
+            .collect();
+        unmatched.sort_unstable();
+        unmatched.dedup();
+        calibration.unmatched = unmatched;
+        calibration
+    }
+
+    /// Names `node` can be found under in the table, most specific first.
+    fn endpoints(&self, node: &NodeSpec) -> Vec<String> {
+        let mut names = vec![node.label().to_string()];
+        if let Some((group, index)) = node.name.rsplit_once('-') {
+            if index.parse::<usize>().is_ok() {
+                names.push(group.to_string());
+            }
+        }
+        names.extend(node.services.iter().cloned());
+        let zone = names.iter().find_map(|name| self.zones.get(name)).cloned();
+        names.extend(zone);
+        names
+    }
+}
+
+/// Cells of a CSV row, trimmed and unquoted.
+fn split_row(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|cell| cell.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+fn parse_rtt(line: usize, value: &str, scale: f64) -> Result<u64, CalibrationError> {
+    match value.parse::<f64>() {
+        Ok(rtt) if rtt >= 0.0 => Ok((rtt * scale).round() as u64),
+        _ => error(line, format!("invalid round-trip time '{}'", value)),
+    }
+}
@@ -0,0 +1,297 @@
+use crate::engine::Simulation;
+use crate::experiment::Experiment;
+use crate::runner::RunCondition;
+use crate::scenario::{Scenario, ScenarioAction};
+use crate::topology::Topology;
+use crate::traits::{Health, NodeId};
+use serde::{Deserialize, Serialize};
+
+/// Virtual time between samples of a chaos run; detection and recovery
+/// times are measured to this resolution.
+pub const CHAOS_SAMPLE_US: u64 = 100_000;
+/// How far before and after a fault its "before" and "after" metrics reach.
+pub const CHAOS_WINDOW_US: u64 = 5_000_000;
+
+/// Requests completed over one sampling interval of a chaos run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ChaosSample {
+    pub start_us: u64,
+    pub end_us: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub p99_us: Option<u64>,
+}
+
+impl ChaosSample {
+    fn meets(&self, slo_percent: f64) -> bool {
+        sla(self.successes, self.failures) >= slo_percent
+    }
+}
+
+/// Successful requests as a percentage of completed ones; 100 without
+/// traffic, since nothing failed.
+fn sla(successes: u64, failures: u64) -> f64 {
+    let total = successes + failures;
+    if total == 0 {
+        100.0
+    } else {
+        successes as f64 * 100.0 / total as f64
+    }
+}
+
+/// Metrics over one stretch of a chaos run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PhaseMetrics {
+    pub requests: u64,
+    pub failures: u64,
+    pub sla_percent: f64,
+    /// Worst p99 of any sample in the stretch
+    pub worst_p99_us: Option<u64>,
+}
+
+impl PhaseMetrics {
+    fn over<'a>(samples: impl Iterator<Item = &'a ChaosSample>) -> Self {
+        let (mut successes, mut failures, mut worst_p99_us) = (0, 0, None);
+        for s in samples {
+            successes += s.successes;
+            failures += s.failures;
+            worst_p99_us = worst_p99_us.max(s.p99_us);
+        }
+        Self {
+            requests: successes + failures,
+            failures,
+            sla_percent: sla(successes, failures),
+            worst_p99_us,
+        }
+    }
+}
+
+/// How the SLO fared around one injected fault.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FaultReport {
+    pub description: String,
+    pub injected_at_us: u64,
+    /// When a later step lifted the fault; `None` if it lasted to the end
+    pub cleared_at_us: Option<u64>,
+    pub before: PhaseMetrics,
+    pub during: PhaseMetrics,
+    pub after: PhaseMetrics,
+    /// Time from injection until the SLO was first seen breached; `None`
+    /// if the fault was absorbed
+    pub detection_us: Option<u64>,
+    /// Time from the fault being lifted until the SLO held again; `None`
+    /// if it was never lifted or never recovered
+    pub recovery_us: Option<u64>,
+    /// Requests failed from injection until recovery
+    pub failures: u64,
+    /// Share of the run's error budget those failures used, in percent
+    pub budget_consumed_percent: f64,
+}
+
+/// SLO metrics before, during and after every fault of a scripted chaos
+/// run, with detection and recovery times and error budget spent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChaosReport {
+    pub scenario: String,
+    /// Target share of successful requests, in percent
+    pub slo_percent: f64,
+    pub overall: PhaseMetrics,
+    /// Share of the error budget used over the whole run, in percent
+    pub budget_consumed_percent: f64,
+    pub faults: Vec<FaultReport>,
+}
+
+/// What a fault acts on, so the step that lifts it can be found.
+#[derive(Clone, Copy, PartialEq)]
+enum FaultTarget {
+    Node(NodeId),
+    Link(NodeId, NodeId),
+}
+
+/// The target `action` breaks, and when it wears off by itself.
+fn fault_of(action: &ScenarioAction, at_us: u64) -> Option<(FaultTarget, Option<u64>)> {
+    match *action {
+        ScenarioAction::Kill { node_id } => Some((FaultTarget::Node(node_id), None)),
+        ScenarioAction::InjectLatency {
+            node_id,
+            duration_us,
+            ..
+        } => Some((FaultTarget::Node(node_id), duration_us.map(|d| at_us + d))),
+        ScenarioAction::SetHealth { node_id, health } if health != Health::Healthy => {
+            Some((FaultTarget::Node(node_id), None))
+        }
+        ScenarioAction::Partition { a, b } => Some((FaultTarget::Link(a.min(b), a.max(b)), None)),
+        _ => None,
+    }
+}
+
+/// Whether `action` lifts a fault on `target`.
+fn clears(action: &ScenarioAction, target: FaultTarget) -> bool {
+    match (action, target) {
+        (ScenarioAction::Revive { node_id }, FaultTarget::Node(id))
+        | (ScenarioAction::ClearLatency { node_id }, FaultTarget::Node(id)) => *node_id == id,
+        (
+            ScenarioAction::SetHealth {
+                node_id,
+                health: Health::Healthy,
+            },
+            FaultTarget::Node(id),
+        ) => *node_id == id,
+        (ScenarioAction::Heal { a, b }, FaultTarget::Link(lo, hi)) => {
+            (*a.min(b), *a.max(b)) == (lo, hi)
+        }
+        _ => false,
+    }
+}
+
+impl ChaosReport {
+    /// Builds the report for `scenario` from consecutive samples of its run.
+    /// Faults are kills, slowdowns, degradations and partitions; each is
+    /// lifted by the matching revive, restore or heal.
+    pub fn from_samples(scenario: &Scenario, samples: &[ChaosSample], slo_percent: f64) -> Self {
+        let overall = PhaseMetrics::over(samples.iter());
+        // At least one failure is always allowed, so a 100% SLO stays finite
+        let allowed = ((1.0 - slo_percent / 100.0) * overall.requests as f64).max(1.0);
+        let end_us = samples.last().map_or(0, |s| s.end_us);
+        let steps: Vec<_> = scenario.steps.iter().filter(|s| s.enabled).collect();
+
+        let mut faults = Vec::new();
+        for (i, step) in steps.iter().enumerate() {
+            let Some((target, expires_us)) = fault_of(&step.action, step.at_us) else {
+                continue;
+            };
+            let injected = step.at_us;
+            if injected >= end_us {
+                continue;
+            }
+            let lifted = steps[i + 1..]
+                .iter()
+                .find(|s| clears(&s.action, target))
+                .map(|s| s.at_us);
+            let cleared_at_us = match (lifted, expires_us) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+            .filter(|&t| t < end_us);
+            let until = cleared_at_us.unwrap_or(end_us);
+            let within = |from: u64, to: u64| {
+                samples
+                    .iter()
+                    .filter(move |s| s.start_us >= from && s.start_us < to)
+            };
+
+            let detection_us = within(injected, until)
+                .find(|s| !s.meets(slo_percent))
+                .map(|s| s.end_us - injected);
+            let recovered_at = cleared_at_us.and_then(|cleared| {
+                within(cleared, end_us)
+                    .find(|s| s.meets(slo_percent))
+                    .map(|s| s.start_us)
+            });
+            let failures: u64 = within(injected, recovered_at.unwrap_or(end_us))
+                .map(|s| s.failures)
+                .sum();
+            faults.push(FaultReport {
+                description: step.action.describe(),
+                injected_at_us: injected,
+                cleared_at_us,
+                before: PhaseMetrics::over(within(
+                    injected.saturating_sub(CHAOS_WINDOW_US),
+                    injected,
+                )),
+                during: PhaseMetrics::over(within(injected, until)),
+                after: PhaseMetrics::over(within(until, until + CHAOS_WINDOW_US)),
+                detection_us,
+                recovery_us: recovered_at
+                    .zip(cleared_at_us)
+                    .map(|(recovered, cleared)| recovered - cleared),
+                failures,
+                budget_consumed_percent: failures as f64 * 100.0 / allowed,
+            });
+        }
+
+        Self {
+            scenario: scenario.name.clone(),
+            slo_percent,
+            overall,
+            budget_consumed_percent: overall.failures as f64 * 100.0 / allowed,
+            faults,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// The report as a Markdown document with one table row per fault.
+    pub fn to_markdown(&self) -> String {
+        let secs = |us: u64| format!("{:.1}s", us as f64 / 1_000_000.0);
+        let ms = |us: Option<u64>| {
+            us.map_or("n/a".to_string(), |v| format!("{:.1}ms", v as f64 / 1000.0))
+        };
+        let or_dash = |us: Option<u64>| us.map_or("—".to_string(), secs);
+
+        let mut out = format!("# Chaos report: {}\n\n", self.scenario);
+        out.push_str(&format!(
+            "SLO {:.2}%: {:.2}% of {} requests succeeded, {:.1}% of the error budget used.\n\n",
+            self.slo_percent,
+            self.overall.sla_percent,
+            self.overall.requests,
+            self.budget_consumed_percent
+        ));
+        if self.faults.is_empty() {
+            out.push_str("No faults were injected.\n");
+            return out;
+        }
+        out.push_str(
+            "| Fault | Injected | Lifted | SLA before | SLA during | SLA after | Worst p99 during | Detected after | Recovered after | Budget used |\n",
+        );
+        out.push_str("|---|---|---|---|---|---|---|---|---|---|\n");
+        for f in &self.faults {
+            out.push_str(&format!(
+                "| {} | {} | {} | {:.2}% | {:.2}% | {:.2}% | {} | {} | {} | {:.1}% |\n",
+                f.description,
+                secs(f.injected_at_us),
+                or_dash(f.cleared_at_us),
+                f.before.sla_percent,
+                f.during.sla_percent,
+                f.after.sla_percent,
+                ms(f.during.worst_p99_us),
+                or_dash(f.detection_us),
+                or_dash(f.recovery_us),
+                f.budget_consumed_percent
+            ));
+        }
+        out
+    }
+}
+
+impl Experiment {
+    /// Runs `topology` under the experiment's scenario, sampling it every
+    /// `CHAOS_SAMPLE_US` after the warm-up, and reports each fault against
+    /// `slo_percent`.
+    pub fn chaos_report(&self, topology: &Topology, slo_percent: f64) -> ChaosReport {
+        let mut sim = Simulation::from_topology(topology, self.seed);
+        sim.load_scenario(self.scenario.clone());
+        sim.warmup_us = self.warmup_us;
+        sim.run_until(&RunCondition::Duration {
+            duration_us: self.warmup_us,
+        });
+
+        let end_us = self.warmup_us + self.duration_us;
+        let mut samples = Vec::new();
+        while sim.time < end_us {
+            let (start_us, successes, failures) = (sim.time, sim.success_count, sim.failure_count);
+            let step = CHAOS_SAMPLE_US.min(end_us - sim.time);
+            sim.run_until(&RunCondition::Duration { duration_us: step });
+            samples.push(ChaosSample {
+                start_us,
+                end_us: sim.time,
+                successes: sim.success_count.saturating_sub(successes),
+                failures: sim.failure_count.saturating_sub(failures),
+                p99_us: sim.get_percentile(99.0, step),
+            });
+        }
+        ChaosReport::from_samples(&self.scenario, &samples, slo_percent)
+    }
+}
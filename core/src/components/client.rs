@@ -1,7 +1,10 @@
-use crate::engine::{Event, EventType, ScheduleCmd, SystemInspector};
+use crate::engine::{Commands, Event, EventType, ScheduleCmd, SystemInspector};
+use crate::metadata::{Metadata, PAYLOAD_BYTES};
+use crate::payload::SizeDistribution;
 use crate::traits::{Component, NodeId, VisualState};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
+use smallvec::smallvec;
 use std::collections::VecDeque;
 use std::sync::{Arc, RwLock};
 
@@ -15,6 +18,33 @@ pub struct ClientConfig {
     pub arrival_rate: f32,
     pub timeout: u64,
     pub generation_id: u64,
+    /// Maximum number of hops before a request is dropped (loop protection)
+    #[serde(default = "default_max_hops")]
+    pub max_hops: u32,
+    /// Entries stamped on every request this client sends (e.g. tenant)
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
+    /// Plans arrivals up to this many microseconds ahead, and at most
+    /// `MAX_BATCH_ARRIVALS` of them, from a single generator event instead
+    /// of one generator event per request; 0 turns batching off. Worth it
+    /// at extreme rates, where handling a generator event per request costs
+    /// as much as the request itself. Planned arrivals wait as pending
+    /// events, hence the cap. Arrivals keep the same spacing either way, but
+    /// ones already planned still go out after a config change or a kill.
+    #[serde(default)]
+    pub batch_us: u64,
+    /// Size of each request body; unsized requests cross links in no time
+    /// whatever their bandwidth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_size: Option<SizeDistribution>,
+}
+
+/// Most arrivals one generator event plans, so a batching client holds
+/// only a few pending arrivals however high its rate.
+pub const MAX_BATCH_ARRIVALS: u32 = 64;
+
+fn default_max_hops() -> u32 {
+    32
 }
 
 impl Default for ClientConfig {
@@ -23,6 +53,10 @@ impl Default for ClientConfig {
             arrival_rate: 5.0,
             timeout: 5000,
             generation_id: 1,
+            max_hops: default_max_hops(),
+            metadata: Metadata::default(),
+            batch_us: 0,
+            request_size: None,
         }
     }
 }
@@ -41,8 +75,10 @@ pub struct Client {
 
 impl Client {
     pub fn new(name: &str, rps: f32) -> Self {
-        let mut cfg = ClientConfig::default();
-        cfg.arrival_rate = rps;
+        let cfg = ClientConfig {
+            arrival_rate: rps,
+            ..ClientConfig::default()
+        };
         Self {
             name: name.to_string(),
             config: Arc::new(RwLock::new(cfg)),
@@ -75,14 +111,14 @@ impl Default for Client {
 }
 
 impl Component for Client {
-    fn on_event(&mut self, event: Event, _inspector: &dyn SystemInspector) -> Vec<ScheduleCmd> {
+    fn on_event(&mut self, event: Event, _inspector: &dyn SystemInspector) -> Commands {
         self.update_window(event.time);
         let config = self.config.read().unwrap();
 
         match event.event_type {
             EventType::GenerateNext { generation_id } => {
                 if !self.healthy || generation_id != config.generation_id {
-                    return vec![];
+                    return smallvec![];
                 }
 
                 let interval_us = if config.arrival_rate > 0.0 {
@@ -90,41 +126,65 @@ impl Component for Client {
                 } else {
                     1_000_000_000
                 };
-                let jitter = self.rng.gen_range(0.95..1.05);
-                let next_delay_us = (interval_us as f64 * jitter) as u64;
-
-                let mut cmds = vec![ScheduleCmd {
-                    delay: next_delay_us,
-                    node_id: event.node_id,
-                    event_type: EventType::GenerateNext { generation_id },
-                }];
 
-                if let Some(target) = self.target_id {
-                    self.request_counter += 1;
-                    // Structure: [NodeId: 32 bits] [Random Salt: 32 bits] [Counter: 64 bits]
-                    let rid = ((event.node_id as u128) << 96)
-                        | ((self.rng.next_u32() as u128) << 64)
-                        | (self.request_counter as u128);
-                    self.window.push_back(event.time);
-                    cmds.push(ScheduleCmd {
-                        delay: 0,
-                        node_id: target,
-                        event_type: EventType::Arrival {
-                            request_id: rid,
-                            path: vec![event.node_id],
-                            start_time: event.time,
-                            timeout: config.timeout * 1000,
-                        },
-                    });
+                // Arrivals from now until the batch is planned, then the
+                // next generator event one gap after the last of them
+                let mut cmds: Commands = smallvec![];
+                let mut offset = 0;
+                let mut planned = 0;
+                loop {
+                    let jitter = self.rng.gen_range(0.95..1.05);
+                    let gap = ((interval_us as f64 * jitter) as u64).max(1);
+                    if let Some(target) = self.target_id {
+                        self.request_counter += 1;
+                        // Structure: [NodeId: 32 bits] [Random Salt: 32 bits] [Counter: 64 bits]
+                        let rid = ((event.node_id as u128) << 96)
+                            | ((self.rng.next_u32() as u128) << 64)
+                            | (self.request_counter as u128);
+                        self.window.push_back(event.time + offset);
+                        let mut metadata = config.metadata.clone();
+                        if let Some(size) = &config.request_size {
+                            size.stamp(&mut self.rng, &mut metadata, PAYLOAD_BYTES);
+                        }
+                        cmds.push(ScheduleCmd {
+                            delay: offset,
+                            node_id: target,
+                            event_type: EventType::Arrival {
+                                request_id: rid,
+                                path: smallvec![event.node_id],
+                                start_time: event.time + offset,
+                                timeout: config.timeout * 1000,
+                                max_hops: config.max_hops,
+                                metadata,
+                            },
+                        });
+                    }
+                    offset += gap;
+                    planned += 1;
+                    if offset >= config.batch_us || planned >= MAX_BATCH_ARRIVALS {
+                        break;
+                    }
                 }
+                cmds.insert(
+                    0,
+                    ScheduleCmd {
+                        delay: offset,
+                        node_id: event.node_id,
+                        event_type: EventType::GenerateNext { generation_id },
+                    },
+                );
                 cmds
             }
-            _ => vec![],
+            _ => smallvec![],
         }
     }
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
     fn kind(&self) -> &str {
         "Client"
     }
@@ -156,11 +216,9 @@ impl Component for Client {
 
     fn sync_display_stats(&mut self, current_time_us: u64) {
         self.update_window(current_time_us);
-        self.display_throughput = if self.healthy {
-            self.window.len() as f32
-        } else {
-            0.0
-        };
+        // A batch plans arrivals ahead of time; only count those already sent
+        let sent = self.window.partition_point(|&t| t <= current_time_us);
+        self.display_throughput = if self.healthy { sent as f32 } else { 0.0 };
 
         let config = self.config.read().unwrap();
         self.display_snapshot = VisualState::Client(ClientStats {
@@ -1,7 +1,10 @@
-use crate::engine::{Event, EventType, ScheduleCmd, SystemInspector};
-use crate::traits::{Component, NodeId, VisualState};
+use crate::engine::{Commands, Event, EventType, ScheduleCmd, SystemInspector};
+use crate::failure::FailureReason;
+use crate::metadata::ATTEMPT;
+use crate::traits::{Component, Health, NodeId, VisualState};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
+use smallvec::smallvec;
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
 
@@ -65,6 +68,19 @@ pub struct LoadBalancerConfig {
     pub min_retry_rate: u32,
     /// Maximum number of tokens that can be accumulated (burst limit)
     pub retry_budget_max_tokens: f32,
+    /// Fail requests whose deadline has passed instead of forwarding them,
+    /// and skip retries that could not complete before the deadline
+    #[serde(default)]
+    pub deadline_aware: bool,
+    /// Steer traffic away from degraded backends: Random and Round Robin
+    /// skip them while a fully healthy one is left, Least Connections
+    /// counts their connections scaled by their slowdown
+    #[serde(default)]
+    pub avoid_degraded: bool,
+    /// Route to the current members of this logical service instead of the
+    /// connected targets
+    #[serde(default)]
+    pub service: Option<String>,
 }
 
 impl Default for LoadBalancerConfig {
@@ -77,6 +93,9 @@ impl Default for LoadBalancerConfig {
             retry_budget_ratio: 0.2,
             min_retry_rate: 10,
             retry_budget_max_tokens: 10.0,
+            deadline_aware: false,
+            avoid_degraded: false,
+            service: None,
         }
     }
 }
@@ -96,6 +115,8 @@ pub struct LoadBalancer {
     pub rng: StdRng,
     /// Rolling window of request timestamps for RPS calculation
     pub arrival_window: VecDeque<u64>,
+    /// Requests received since the last stats reset
+    pub received: u64,
     /// Component health status
     pub is_healthy: bool,
     /// Map of RequestId to selected Target NodeId
@@ -112,6 +133,8 @@ pub struct LoadBalancer {
     pub display_snapshot: VisualState,
     /// Total failed requests (exhausted retries or no targets)
     pub failure_count: u64,
+    /// Requests failed because their deadline could not be met
+    pub deadline_exceeded: u64,
 }
 
 impl LoadBalancer {
@@ -124,6 +147,7 @@ impl LoadBalancer {
             active_loads: HashMap::new(),
             rng: StdRng::from_entropy(),
             arrival_window: VecDeque::new(),
+            received: 0,
             is_healthy: true,
             state_table: HashMap::new(),
             in_flight_retries: HashMap::new(),
@@ -132,47 +156,80 @@ impl LoadBalancer {
             display_throughput: 0.0,
             display_snapshot: VisualState::None,
             failure_count: 0,
+            deadline_exceeded: 0,
         }
     }
 
+    /// Total number of retries performed since start.
+    pub fn total_retries(&self) -> u64 {
+        self.total_retries
+    }
+
     fn select_target(
         &mut self,
         strategy: BalancingStrategy,
+        service: Option<&str>,
+        avoid_degraded: bool,
         inspector: &dyn SystemInspector,
         exclusions: &[NodeId],
     ) -> Option<NodeId> {
-        let healthy_targets: Vec<NodeId> = self
-            .targets
-            .iter()
-            .copied()
-            .filter(|&id| inspector.is_node_healthy(id) && !exclusions.contains(&id))
-            .collect();
-
-        if healthy_targets.is_empty() {
-            return None;
-        }
+        let candidates: &[NodeId] = match service {
+            Some(name) => inspector.service_members_ref(name),
+            None => &self.targets,
+        };
+        // Filtered in place rather than collected, so routing a request
+        // does not allocate
+        let healthy = |id: &NodeId| inspector.is_node_healthy(*id) && !exclusions.contains(id);
+        let prefer_fully_healthy = avoid_degraded
+            && strategy != BalancingStrategy::LeastConnections
+            && candidates
+                .iter()
+                .any(|id| healthy(id) && inspector.node_health(*id) == Health::Healthy);
+        let eligible = |id: &NodeId| {
+            healthy(id) && (!prefer_fully_healthy || inspector.node_health(*id) == Health::Healthy)
+        };
 
         match strategy {
             BalancingStrategy::Random => {
-                let idx = self.rng.gen_range(0..healthy_targets.len());
-                Some(healthy_targets[idx])
+                let count = candidates.iter().filter(|id| eligible(id)).count();
+                if count == 0 {
+                    return None;
+                }
+                let idx = self.rng.gen_range(0..count);
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|id| eligible(id))
+                    .nth(idx)
             }
             BalancingStrategy::RoundRobin => {
                 // Pick the next valid target in sequence to handle exclusions
-                for i in 0..self.targets.len() {
-                    let idx = (self.next_rr_idx + i) % self.targets.len();
-                    let target = self.targets[idx];
-                    if healthy_targets.contains(&target) {
-                        self.next_rr_idx = (idx + 1) % self.targets.len();
+                for i in 0..candidates.len() {
+                    let idx = (self.next_rr_idx + i) % candidates.len();
+                    let target = candidates[idx];
+                    if eligible(&target) {
+                        self.next_rr_idx = (idx + 1) % candidates.len();
                         return Some(target);
                     }
                 }
                 None
             }
-            BalancingStrategy::LeastConnections => healthy_targets
-                .iter()
-                .min_by_key(|&&id| self.active_loads.get(&id).unwrap_or(&0))
-                .copied(),
+            BalancingStrategy::LeastConnections => {
+                let load = |id: NodeId| {
+                    let active = *self.active_loads.get(&id).unwrap_or(&0) as f32;
+                    match inspector.node_health(id) {
+                        Health::Degraded { slowdown, .. } if avoid_degraded => {
+                            (active + 1.0) * slowdown.max(1.0)
+                        }
+                        _ => active,
+                    }
+                };
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|id| eligible(id))
+                    .min_by(|&a, &b| load(a).total_cmp(&load(b)))
+            }
         }
     }
     fn update_rps_window(&mut self, current_time_us: u64) {
@@ -194,7 +251,7 @@ impl Default for LoadBalancer {
 }
 
 impl Component for LoadBalancer {
-    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Vec<ScheduleCmd> {
+    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Commands {
         self.update_rps_window(event.time);
         match event.event_type {
             EventType::Arrival {
@@ -202,7 +259,10 @@ impl Component for LoadBalancer {
                 mut path,
                 start_time,
                 timeout,
+                max_hops,
+                metadata,
             } => {
+                self.received += 1;
                 {
                     let config = self.config.read().unwrap();
                     self.retry_token_balance += config.retry_budget_ratio;
@@ -213,7 +273,30 @@ impl Component for LoadBalancer {
 
                 if !self.is_healthy {
                     if let Some(&prev) = path.last() {
-                        return vec![ScheduleCmd {
+                        return smallvec![ScheduleCmd {
+                            delay: 0,
+                            node_id: prev,
+                            event_type: EventType::Response {
+                                request_id,
+                                path,
+                                start_time,
+                                success: false,
+                                failure: Some(FailureReason::Unavailable),
+                                timeout,
+                                max_hops,
+                                metadata,
+                            },
+                        }];
+                    }
+                    return smallvec![];
+                }
+
+                let deadline_aware = self.config.read().unwrap().deadline_aware;
+                if deadline_aware && event.time >= start_time + timeout {
+                    self.deadline_exceeded += 1;
+                    self.failure_count += 1;
+                    if let Some(&prev) = path.last() {
+                        return smallvec![ScheduleCmd {
                             delay: 0,
                             node_id: prev,
                             event_type: EventType::Response {
@@ -221,22 +304,34 @@ impl Component for LoadBalancer {
                                 path,
                                 start_time,
                                 success: false,
+                                failure: Some(FailureReason::DeadlineExceeded),
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         }];
                     }
-                    return vec![];
+                    return smallvec![];
                 }
                 // Process the new request
                 self.arrival_window.push_back(event.time);
-                let strategy = self.config.read().unwrap().strategy;
+                let (strategy, service, avoid_degraded) = {
+                    let config = self.config.read().unwrap();
+                    (
+                        config.strategy,
+                        config.service.clone(),
+                        config.avoid_degraded,
+                    )
+                };
 
-                if let Some(target_id) = self.select_target(strategy, inspector, &[]) {
+                if let Some(target_id) =
+                    self.select_target(strategy, service.as_deref(), avoid_degraded, inspector, &[])
+                {
                     let entry = self.active_loads.entry(target_id).or_insert(0);
                     *entry += 1;
                     self.state_table.insert(request_id, target_id);
                     path.push(event.node_id);
-                    vec![ScheduleCmd {
+                    smallvec![ScheduleCmd {
                         delay: crate::PROCESS_OVERHEAD_US,
                         node_id: target_id,
                         event_type: EventType::Arrival {
@@ -244,11 +339,13 @@ impl Component for LoadBalancer {
                             path,
                             start_time,
                             timeout,
+                            max_hops,
+                            metadata,
                         },
                     }]
                 } else {
                     if let Some(&prev) = path.last() {
-                        vec![ScheduleCmd {
+                        smallvec![ScheduleCmd {
                             delay: 0,
                             node_id: prev,
                             event_type: EventType::Response {
@@ -256,12 +353,15 @@ impl Component for LoadBalancer {
                                 path,
                                 start_time,
                                 success: false,
+                                failure: Some(FailureReason::NoHealthyTargets),
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         }]
                     } else {
                         self.failure_count += 1;
-                        vec![]
+                        smallvec![]
                     }
                 }
             }
@@ -270,7 +370,10 @@ impl Component for LoadBalancer {
                 mut path,
                 start_time,
                 success,
+                mut failure,
                 timeout,
+                max_hops,
+                mut metadata,
             } => {
                 // Cleanup load tracking
                 if let Some(server_id) = self.state_table.remove(&request_id) {
@@ -290,18 +393,28 @@ impl Component for LoadBalancer {
                                 });
 
                         let has_budget = self.retry_token_balance >= 1.0;
+                        let can_retry = retry_state.retry_count < config.max_retries && has_budget;
+                        let meets_deadline = !config.deadline_aware
+                            || event.time + config.retry_backoff_ms * 1000 < start_time + timeout;
+                        if can_retry && !meets_deadline {
+                            self.deadline_exceeded += 1;
+                            failure = Some(FailureReason::DeadlineExceeded);
+                        }
 
-                        if retry_state.retry_count < config.max_retries && has_budget {
+                        if can_retry && meets_deadline {
                             retry_state.failed_targets.push(server_id);
 
                             if let Some(new_target) = self.select_target(
                                 config.strategy,
+                                config.service.as_deref(),
+                                config.avoid_degraded,
                                 inspector,
                                 &retry_state.failed_targets,
                             ) {
                                 self.retry_token_balance -= 1.0;
 
                                 retry_state.retry_count += 1;
+                                metadata.insert(ATTEMPT, retry_state.retry_count);
                                 self.in_flight_retries.insert(request_id, retry_state);
                                 self.total_retries += 1;
 
@@ -312,7 +425,7 @@ impl Component for LoadBalancer {
                                 let jitter = self.rng.gen_range(0..=(delay_us / 10).max(1));
                                 delay_us += jitter;
 
-                                return vec![ScheduleCmd {
+                                return smallvec![ScheduleCmd {
                                     delay: delay_us,
                                     node_id: new_target,
                                     event_type: EventType::Arrival {
@@ -320,6 +433,8 @@ impl Component for LoadBalancer {
                                         path,
                                         start_time,
                                         timeout,
+                                        max_hops,
+                                        metadata,
                                     },
                                 }];
                             }
@@ -334,7 +449,7 @@ impl Component for LoadBalancer {
                 // Return response up the stack
                 path.pop();
                 if let Some(&prev_node) = path.last() {
-                    vec![ScheduleCmd {
+                    smallvec![ScheduleCmd {
                         delay: crate::PROCESS_OVERHEAD_US,
                         node_id: prev_node,
                         event_type: EventType::Response {
@@ -342,19 +457,26 @@ impl Component for LoadBalancer {
                             path,
                             start_time,
                             success,
+                            failure,
                             timeout,
+                            max_hops,
+                            metadata,
                         },
                     }]
                 } else {
-                    vec![]
+                    smallvec![]
                 }
             }
-            _ => vec![],
+            _ => smallvec![],
         }
     }
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
     fn kind(&self) -> &str {
         "LoadBalancer"
     }
@@ -398,9 +520,15 @@ impl Component for LoadBalancer {
     fn display_throughput(&self) -> f32 {
         self.display_throughput
     }
+    fn received_count(&self) -> u64 {
+        self.received
+    }
     fn error_count(&self) -> u64 {
         self.failure_count
     }
+    fn deadline_exceeded_count(&self) -> u64 {
+        self.deadline_exceeded
+    }
     fn set_healthy(&mut self, h: bool) {
         self.is_healthy = h;
     }
@@ -429,6 +557,7 @@ impl Component for LoadBalancer {
     }
     fn reset_internal_stats(&mut self) {
         self.arrival_window.clear();
+        self.received = 0;
         self.active_loads.clear();
         self.state_table.clear();
         self.in_flight_retries.clear();
@@ -436,6 +565,7 @@ impl Component for LoadBalancer {
         self.display_throughput = 0.0;
         self.display_snapshot = VisualState::None;
         self.failure_count = 0;
+        self.deadline_exceeded = 0;
     }
 
     fn set_seed(&mut self, seed: u64) {
@@ -1,5 +1,6 @@
 use crate::traits::Component;
 use serde_json::Value;
+use std::sync::RwLock;
 
 pub mod client;
 pub mod load_balancer;
@@ -20,22 +21,174 @@ macro_rules! register_components {
                         Some(Box::new(obj))
                     }
                 )*
-                _ => None,
+                _ => {
+                    #[cfg(feature = "plugins")]
+                    if let Some(plugin) = crate::plugin::create(kind, &data) {
+                        return Some(plugin);
+                    }
+                    registered_components()
+                        .into_iter()
+                        .find(|r| r.kind == kind)
+                        .and_then(|r| (r.factory)(data))
+                }
             }
         }
 
+        /// Kinds compiled into the core crate.
+        pub const BUILTIN_KINDS: &[&str] = &[$(stringify!($variant)),*];
+
         #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
         pub enum VisualState {
             None,
             $(
                 $variant($stats),
             )*
+            /// Snapshot of a component registered at runtime
+            Custom(CustomStats),
         }
     };
 }
 
+/// What a custom component shows on its node: the host's counters, then
+/// any named values the component reports itself.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomStats {
+    pub rps: f32,
+    /// Requests held between arrival and response
+    pub active: u32,
+    pub errors: u64,
+    /// Reported values in the order the component gave them
+    pub fields: Vec<(String, StatValue)>,
+    /// Why the component's logic last failed, if it did
+    pub last_error: Option<String>,
+}
+
+impl CustomStats {
+    /// Appends a reported value.
+    pub fn with(mut self, name: &str, value: impl Into<StatValue>) -> Self {
+        self.fields.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StatValue> {
+        self.fields.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// A reported value, if it is a number.
+    pub fn number(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            StatValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// One value in `CustomStats::fields`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum StatValue {
+    Number(f64),
+    Flag(bool),
+    Text(String),
+}
+
+impl std::fmt::Display for StatValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatValue::Number(n) => write!(f, "{}", n),
+            StatValue::Flag(b) => write!(f, "{}", b),
+            StatValue::Text(t) => f.write_str(t),
+        }
+    }
+}
+
+impl From<f64> for StatValue {
+    fn from(n: f64) -> Self {
+        StatValue::Number(n)
+    }
+}
+
+impl From<u64> for StatValue {
+    fn from(n: u64) -> Self {
+        StatValue::Number(n as f64)
+    }
+}
+
+impl From<bool> for StatValue {
+    fn from(b: bool) -> Self {
+        StatValue::Flag(b)
+    }
+}
+
+impl From<&str> for StatValue {
+    fn from(t: &str) -> Self {
+        StatValue::Text(t.to_string())
+    }
+}
+
 register_components!(
     Client => client::Client, client::ClientStats,
     Server => server::Server, server::ServerStats,
     LoadBalancer => load_balancer::LoadBalancer, load_balancer::LBStats,
 );
+
+/// Builds a component from its JSON config (`Value::Null` means defaults).
+pub type ComponentFactory = fn(Value) -> Option<Box<dyn Component>>;
+
+/// A component kind provided by a downstream crate.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentRegistration {
+    /// Unique kind string, as returned by `Component::kind`
+    pub kind: &'static str,
+    /// Human readable name shown in the palette
+    pub label: &'static str,
+    pub description: &'static str,
+    pub factory: ComponentFactory,
+}
+
+static REGISTRY: RwLock<Vec<ComponentRegistration>> = RwLock::new(Vec::new());
+
+/// Kinds built into the core behind cargo features. They are listed with
+/// the custom kinds and can be replaced like them.
+const FEATURE_COMPONENTS: &[ComponentRegistration] = &[
+    #[cfg(feature = "scripting")]
+    ComponentRegistration {
+        kind: crate::script::SCRIPTED_KIND,
+        label: "Scripted",
+        description: "Behavior written as a Rhai script in the inspector",
+        factory: crate::script::create,
+    },
+];
+
+/// Makes a custom component kind available to `create_component`, topology
+/// loading and the palette. Registering a kind again replaces the previous
+/// entry. Returns `false` (and does nothing) if the kind is built in.
+pub fn register_component(registration: ComponentRegistration) -> bool {
+    if BUILTIN_KINDS.contains(&registration.kind) {
+        return false;
+    }
+    let mut registry = REGISTRY.write().unwrap();
+    registry.retain(|r| r.kind != registration.kind);
+    registry.push(registration);
+    true
+}
+
+/// Custom kinds: those built in behind features, then those registered so
+/// far, in registration order.
+pub fn registered_components() -> Vec<ComponentRegistration> {
+    let registry = REGISTRY.read().unwrap();
+    FEATURE_COMPONENTS
+        .iter()
+        .filter(|f| !registry.iter().any(|r| r.kind == f.kind))
+        .chain(registry.iter())
+        .copied()
+        .collect()
+}
+
+/// Every kind `create_component` understands: built-ins first, then custom.
+pub fn component_kinds() -> Vec<&'static str> {
+    BUILTIN_KINDS
+        .iter()
+        .copied()
+        .chain(registered_components().into_iter().map(|r| r.kind))
+        .collect()
+}
@@ -1,8 +1,14 @@
-use crate::engine::{Event, EventType, ScheduleCmd, SystemInspector};
+use crate::engine::{Commands, Event, EventType, Path, ScheduleCmd, SystemInspector};
+use crate::failure::FailureReason;
+use crate::metadata::{Metadata, PAYLOAD_BYTES, RESPONSE_BYTES};
+use crate::payload::SizeDistribution;
 use crate::traits::{Component, NodeId, VisualState};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use serde_json::Value;
+use smallvec::smallvec;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, RwLock};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -12,6 +18,15 @@ pub struct ServerStats {
     pub concurrency: u32,
     pub queue_len: usize,
     pub saturation_penalty: f32,
+    /// Smoothed time requests waited in the backlog
+    #[serde(default)]
+    pub queue_wait_ms: f32,
+    /// Smoothed processing time once a worker picked a request up
+    #[serde(default)]
+    pub service_time_ms: f32,
+    /// Share of requests answered from the cache, if the server has one
+    #[serde(default)]
+    pub cache_hit_rate: Option<f32>,
 }
 
 /// Configuration for the Server component
@@ -36,6 +51,48 @@ pub struct ServerConfig {
     /// - `load_factor = 1.0` (100% load)
     /// - `penalty = 1.0 + (1.0 * 0.5) = 1.5` (50% slower)
     pub saturation_penalty: f32,
+    /// Reject requests that cannot finish before their deadline
+    /// (`start_time + timeout`) instead of wasting a thread on them
+    #[serde(default)]
+    pub deadline_aware: bool,
+    /// Chance that a finished request calls each target, in connection
+    /// order; requests left over are answered here. Empty sends every
+    /// request on, split evenly over the targets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub route_probabilities: Vec<f32>,
+    /// Share of requests answered from a response cache (0.0 - 1.0): a hit
+    /// takes `cache_hit_time` instead of the service time and calls no
+    /// target
+    #[serde(default)]
+    pub cache_hit_ratio: f32,
+    /// Time taken to answer a cache hit (in milliseconds)
+    #[serde(default = "default_cache_hit_time")]
+    pub cache_hit_time: u64,
+    /// Metadata entry naming the request's class. When set, a successful
+    /// response is cached for its class for `cache_ttl_ms` and requests hit
+    /// whenever their class is cached, instead of at `cache_hit_ratio`.
+    /// Fluid traffic carries no metadata and still hits at the ratio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<String>,
+    /// How long a class stays cached (in milliseconds)
+    #[serde(default = "default_cache_ttl_ms")]
+    pub cache_ttl_ms: u64,
+    /// Size of the request bodies of the calls it makes; calls carry the
+    /// incoming request's size if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_size: Option<SizeDistribution>,
+    /// Size of its successful responses; responses from downstream pass
+    /// through as they are if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_size: Option<SizeDistribution>,
+}
+
+fn default_cache_hit_time() -> u64 {
+    1
+}
+
+fn default_cache_ttl_ms() -> u64 {
+    60_000
 }
 
 impl Default for ServerConfig {
@@ -46,10 +103,41 @@ impl Default for ServerConfig {
             backlog_limit: 50,
             failure_probability: 0.0,
             saturation_penalty: 0.0,
+            deadline_aware: false,
+            route_probabilities: Vec::new(),
+            cache_hit_ratio: 0.0,
+            cache_hit_time: default_cache_hit_time(),
+            cache_key: None,
+            cache_ttl_ms: default_cache_ttl_ms(),
+            request_size: None,
+            response_size: None,
         }
     }
 }
 
+impl ServerConfig {
+    /// Chance of calling each of `targets` targets. Negative chances count
+    /// as zero, and chances adding up to more than one are scaled down.
+    pub fn route_shares(&self, targets: usize) -> Vec<f32> {
+        if self.route_probabilities.is_empty() {
+            return vec![1.0 / targets as f32; targets];
+        }
+        let mut shares: Vec<f32> = (0..targets)
+            .map(|i| self.route_probabilities.get(i).map_or(0.0, |p| p.max(0.0)))
+            .collect();
+        let total: f32 = shares.iter().sum();
+        if total > 1.0 {
+            shares.iter_mut().for_each(|p| *p /= total);
+        }
+        shares
+    }
+
+    /// Whether the server has a response cache.
+    pub fn has_cache(&self) -> bool {
+        self.cache_key.is_some() || self.cache_hit_ratio > 0.0
+    }
+}
+
 /// Represents a backend server that processes requests
 pub struct Server {
     /// Component name
@@ -58,22 +146,50 @@ pub struct Server {
     pub config: Arc<RwLock<ServerConfig>>,
     /// Current number of requests being processed
     pub active_threads: u32,
-    /// Queue of pending requests (RequestID, Path, StartTime, Timeout)
-    pub queue: VecDeque<(u128, Vec<NodeId>, u64, u64)>, // RID, Path, Start, Timeout
-    /// Next node to forward requests to (if any)
-    pub next_hop: Option<NodeId>,
+    /// Queue of pending requests (RequestID, Path, StartTime, Timeout, MaxHops, Metadata)
+    pub queue: VecDeque<(u128, Path, u64, u64, u32, Metadata)>,
+    /// Nodes finished requests are forwarded to, in connection order
+    pub targets: Vec<NodeId>,
     /// Total number of errors (failures + dropped requests)
     pub errors: u64,
+    /// Requests skipped because they could not finish before their deadline
+    pub deadline_exceeded: u64,
     /// Health status (Maintenance mode)
     pub healthy: bool,
     /// Random number generator for jitter and failure simulation
     pub rng: StdRng,
     /// Rolling window of request timestamps for RPS calculation
     pub arrival_window: VecDeque<u64>,
+    /// Requests received since the last stats reset
+    pub received: u64,
     /// Cached throughput for UI display
     pub display_throughput: f32,
     /// Cached visual snapshot for UI display
     pub display_snapshot: VisualState,
+    /// Arrival time of every request currently held (processing or queued)
+    pub arrived_at: HashMap<u128, u64>,
+    /// Exponentially smoothed time requests spent in this server
+    pub latency_ewma_us: Option<f64>,
+    /// Exponentially smoothed time requests waited before a worker took them
+    pub queue_wait_ewma_us: Option<f64>,
+    /// Exponentially smoothed processing time, excluding the wait
+    pub service_ewma_us: Option<f64>,
+    /// When each cached request class expires
+    pub cache: HashMap<String, u64>,
+    /// Requests held that are being answered from the cache
+    pub cached_requests: HashSet<u128>,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+}
+
+/// Weight of the newest sample in the `*_ewma_us` fields.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+fn smooth(ewma: &mut Option<f64>, sample: f64) {
+    *ewma = Some(match *ewma {
+        Some(prev) => prev + LATENCY_SMOOTHING * (sample - prev),
+        None => sample,
+    });
 }
 
 impl Server {
@@ -86,16 +202,28 @@ impl Server {
                 backlog_limit: backlog,
                 failure_probability: 0.0,
                 saturation_penalty: 0.5,
+                deadline_aware: false,
+                ..ServerConfig::default()
             })),
             active_threads: 0,
             queue: VecDeque::new(),
-            next_hop: None,
+            targets: Vec::new(),
             errors: 0,
+            deadline_exceeded: 0,
             healthy: true,
             rng: StdRng::from_entropy(),
             arrival_window: VecDeque::new(),
+            received: 0,
             display_throughput: 0.0,
             display_snapshot: VisualState::None,
+            arrived_at: HashMap::new(),
+            latency_ewma_us: None,
+            queue_wait_ewma_us: None,
+            service_ewma_us: None,
+            cache: HashMap::new(),
+            cached_requests: HashSet::new(),
+            cache_hits: 0,
+            cache_misses: 0,
         }
     }
     fn update_rps_window(&mut self, current_time_us: u64) {
@@ -118,6 +246,105 @@ impl Server {
         let jitter = rng.gen_range(0.95..1.05);
         (config.service_time as f64 * 1000.0 * jitter * penalty as f64) as u64
     }
+
+    /// Time to answer a cache hit: `cache_hit_time` with the usual jitter
+    /// but no saturation penalty.
+    fn cache_hit_delay(rng: &mut StdRng, config: &ServerConfig) -> u64 {
+        let jitter = rng.gen_range(0.95..1.05);
+        (config.cache_hit_time as f64 * 1000.0 * jitter) as u64
+    }
+
+    /// Whether work started at `now` can finish by `deadline`, assuming the
+    /// fastest possible service time at `load_factor` (lower jitter bound).
+    fn can_meet_deadline(
+        config: &ServerConfig,
+        now: u64,
+        deadline: u64,
+        load_factor: f32,
+        cached: bool,
+    ) -> bool {
+        let min_service_us = if cached {
+            (config.cache_hit_time as f64 * 1000.0 * 0.95) as u64
+        } else {
+            let penalty = 1.0 + (load_factor * load_factor * config.saturation_penalty);
+            (config.service_time as f64 * 1000.0 * 0.95 * penalty as f64) as u64
+        };
+        now + min_service_us <= deadline
+    }
+
+    /// Whether a request arriving at `now` is answered from the cache, or
+    /// `None` if the server has no cache. An expired class is dropped.
+    fn cache_lookup(
+        rng: &mut StdRng,
+        config: &ServerConfig,
+        cache: &mut HashMap<String, u64>,
+        metadata: &Metadata,
+        now: u64,
+    ) -> Option<bool> {
+        match &config.cache_key {
+            Some(key) => {
+                let Some(class) = metadata.get(key).map(cache_class) else {
+                    return Some(false);
+                };
+                match cache.get(class.as_ref()) {
+                    Some(&expires) if now < expires => Some(true),
+                    Some(_) => {
+                        cache.remove(class.as_ref());
+                        Some(false)
+                    }
+                    None => Some(false),
+                }
+            }
+            None if config.cache_hit_ratio > 0.0 => Some(rng.gen::<f32>() < config.cache_hit_ratio),
+            None => None,
+        }
+    }
+
+    /// Caches the response to a request of `metadata`'s class until
+    /// `cache_ttl_ms` from `now`, if the cache is keyed.
+    fn fill_cache(
+        cache: &mut HashMap<String, u64>,
+        config: &ServerConfig,
+        metadata: &Metadata,
+        now: u64,
+    ) {
+        if let Some(class) = config.cache_key.as_ref().and_then(|k| metadata.get(k)) {
+            // Classes nobody asks for again would otherwise stay forever
+            cache.retain(|_, &mut expires| now < expires);
+            cache.insert(
+                cache_class(class).into_owned(),
+                now + config.cache_ttl_ms * 1000,
+            );
+        }
+    }
+
+    /// The target a finished request calls next, or `None` to answer it
+    /// here.
+    fn pick_target(rng: &mut StdRng, config: &ServerConfig, targets: &[NodeId]) -> Option<NodeId> {
+        if targets.len() <= 1 && config.route_probabilities.is_empty() {
+            return targets.first().copied();
+        }
+        let shares = config.route_shares(targets.len());
+        let mut roll = rng.gen::<f32>();
+        for (&target, &share) in targets.iter().zip(&shares) {
+            if roll < share {
+                return Some(target);
+            }
+            roll -= share;
+        }
+        // Rounding can leave a sliver of a full split unassigned
+        let total: f32 = shares.iter().sum();
+        targets.last().copied().filter(|_| total > 0.999)
+    }
+}
+
+/// The cache key of a request class: strings as they are, other values
+/// as JSON.
+fn cache_class(value: &Value) -> Cow<'_, str> {
+    match value.as_str() {
+        Some(class) => Cow::Borrowed(class),
+        None => Cow::Owned(value.to_string()),
+    }
 }
 
 impl Default for Server {
@@ -133,7 +360,7 @@ impl Default for Server {
 }
 
 impl Component for Server {
-    fn on_event(&mut self, event: Event, _inspector: &dyn SystemInspector) -> Vec<ScheduleCmd> {
+    fn on_event(&mut self, event: Event, _inspector: &dyn SystemInspector) -> Commands {
         self.update_rps_window(event.time);
         let config = self.config.read().unwrap();
 
@@ -143,12 +370,15 @@ impl Component for Server {
                 path,
                 start_time,
                 timeout,
+                max_hops,
+                metadata,
             } => {
                 self.arrival_window.push_back(event.time);
+                self.received += 1;
                 if !self.healthy {
                     self.errors += 1;
                     if let Some(&prev) = path.last() {
-                        return vec![ScheduleCmd {
+                        return smallvec![ScheduleCmd {
                             delay: 0,
                             node_id: prev,
                             event_type: EventType::Response {
@@ -156,11 +386,14 @@ impl Component for Server {
                                 path,
                                 start_time,
                                 success: false,
+                                failure: Some(FailureReason::Unavailable),
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         }];
                     }
-                    return vec![];
+                    return smallvec![];
                 }
 
                 if config.failure_probability > 0.0
@@ -168,7 +401,47 @@ impl Component for Server {
                 {
                     self.errors += 1;
                     if let Some(&prev) = path.last() {
-                        return vec![ScheduleCmd {
+                        return smallvec![ScheduleCmd {
+                            delay: 0,
+                            node_id: prev,
+                            event_type: EventType::Response {
+                                request_id,
+                                path,
+                                start_time,
+                                success: false,
+                                failure: Some(FailureReason::BackendError),
+                                timeout,
+                                max_hops,
+                                metadata,
+                            },
+                        }];
+                    }
+                    return smallvec![];
+                }
+
+                let lookup = Self::cache_lookup(
+                    &mut self.rng,
+                    &config,
+                    &mut self.cache,
+                    &metadata,
+                    event.time,
+                );
+                let cached = lookup == Some(true);
+
+                let start_load = (self.active_threads + 1).min(config.concurrency) as f32
+                    / config.concurrency as f32;
+                if config.deadline_aware
+                    && !Self::can_meet_deadline(
+                        &config,
+                        event.time,
+                        start_time + timeout,
+                        start_load,
+                        cached,
+                    )
+                {
+                    self.deadline_exceeded += 1;
+                    if let Some(&prev) = path.last() {
+                        return smallvec![ScheduleCmd {
                             delay: 0,
                             node_id: prev,
                             event_type: EventType::Response {
@@ -176,20 +449,41 @@ impl Component for Server {
                                 path,
                                 start_time,
                                 success: false,
+                                failure: Some(FailureReason::DeadlineExceeded),
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         }];
                     }
-                    return vec![];
+                    return smallvec![];
+                }
+
+                // Hits and misses count only for requests started or queued
+                let accepted = self.active_threads < config.concurrency
+                    || self.queue.len() < config.backlog_limit as usize;
+                match lookup {
+                    Some(true) if accepted => self.cache_hits += 1,
+                    Some(false) if accepted => self.cache_misses += 1,
+                    _ => {}
                 }
 
                 if self.active_threads < config.concurrency {
                     self.active_threads += 1;
+                    self.arrived_at.insert(request_id, event.time);
+                    if cached {
+                        self.cached_requests.insert(request_id);
+                    }
 
                     let load_factor = self.active_threads as f32 / config.concurrency as f32;
-                    let delay_us =
-                        Self::calculate_processing_delay(&mut self.rng, &config, load_factor);
-                    vec![ScheduleCmd {
+                    let delay_us = if cached {
+                        Self::cache_hit_delay(&mut self.rng, &config)
+                    } else {
+                        Self::calculate_processing_delay(&mut self.rng, &config, load_factor)
+                    };
+                    smooth(&mut self.queue_wait_ewma_us, 0.0);
+                    smooth(&mut self.service_ewma_us, delay_us as f64);
+                    smallvec![ScheduleCmd {
                         delay: delay_us,
                         node_id: event.node_id,
                         event_type: EventType::ProcessComplete {
@@ -198,13 +492,15 @@ impl Component for Server {
                             start_time,
                             path,
                             timeout,
+                            max_hops,
+                            metadata,
                         },
                     }]
                 } else {
                     if self.queue.len() >= config.backlog_limit as usize {
                         self.errors += 1;
                         if let Some(&prev) = path.last() {
-                            return vec![ScheduleCmd {
+                            return smallvec![ScheduleCmd {
                                 delay: 0,
                                 node_id: prev,
                                 event_type: EventType::Response {
@@ -212,15 +508,22 @@ impl Component for Server {
                                     path,
                                     start_time,
                                     success: false,
+                                    failure: Some(FailureReason::QueueFull),
                                     timeout,
+                                    max_hops,
+                                    metadata,
                                 },
                             }];
                         }
-                        vec![]
+                        smallvec![]
                     } else {
+                        self.arrived_at.insert(request_id, event.time);
+                        if cached {
+                            self.cached_requests.insert(request_id);
+                        }
                         self.queue
-                            .push_back((request_id, path, start_time, timeout));
-                        vec![]
+                            .push_back((request_id, path, start_time, timeout, max_hops, metadata));
+                        smallvec![]
                     }
                 }
             }
@@ -228,35 +531,61 @@ impl Component for Server {
                 request_id,
                 success,
                 start_time,
-                path,
+                mut path,
                 timeout,
+                max_hops,
+                mut metadata,
             } => {
-                let mut cmds = Vec::new();
+                if let Some(arrived) = self.arrived_at.remove(&request_id) {
+                    let sample = event.time.saturating_sub(arrived) as f64;
+                    smooth(&mut self.latency_ewma_us, sample);
+                }
+                let mut cmds = Commands::new();
+                let cached =
+                    !self.cached_requests.is_empty() && self.cached_requests.remove(&request_id);
+                let hop = if success && !cached {
+                    Self::pick_target(&mut self.rng, &config, &self.targets)
+                } else {
+                    None
+                };
                 if success {
-                    if let Some(hop) = self.next_hop {
-                        let mut p = path.clone();
-                        p.push(event.node_id);
+                    if let Some(hop) = hop {
+                        if let Some(size) = &config.request_size {
+                            size.stamp(&mut self.rng, &mut metadata, PAYLOAD_BYTES);
+                        }
+                        path.push(event.node_id);
                         cmds.push(ScheduleCmd {
                             delay: 0,
                             node_id: hop,
                             event_type: EventType::Arrival {
                                 request_id,
-                                path: p,
+                                path,
                                 start_time,
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         });
                     } else {
+                        if !cached {
+                            Self::fill_cache(&mut self.cache, &config, &metadata, event.time);
+                        }
+                        if let Some(size) = &config.response_size {
+                            size.stamp(&mut self.rng, &mut metadata, RESPONSE_BYTES);
+                        }
                         if let Some(&prev) = path.last() {
                             cmds.push(ScheduleCmd {
                                 delay: 0,
                                 node_id: prev,
                                 event_type: EventType::Response {
                                     request_id,
-                                    path: path.clone(),
+                                    path,
                                     start_time,
                                     success: true,
+                                    failure: None,
                                     timeout,
+                                    max_hops,
+                                    metadata,
                                 },
                             });
                         }
@@ -268,20 +597,78 @@ impl Component for Server {
                             node_id: prev,
                             event_type: EventType::Response {
                                 request_id,
-                                path: path.clone(),
+                                path,
                                 start_time,
                                 success: false,
+                                failure: Some(FailureReason::BackendError),
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         });
                     }
                 }
-                if let Some((next_rid, next_path, next_start, next_timeout)) =
+                let mut next = None;
+                let queue_load = self.active_threads as f32 / config.concurrency as f32;
+                while let Some((rid, qpath, qstart, qtimeout, qmax_hops, qmetadata)) =
                     self.queue.pop_front()
                 {
-                    let load_factor = self.active_threads as f32 / config.concurrency as f32;
-                    let delay_us =
-                        Self::calculate_processing_delay(&mut self.rng, &config, load_factor);
+                    let cached =
+                        !self.cached_requests.is_empty() && self.cached_requests.contains(&rid);
+                    if config.deadline_aware
+                        && !Self::can_meet_deadline(
+                            &config,
+                            event.time,
+                            qstart + qtimeout,
+                            queue_load,
+                            cached,
+                        )
+                    {
+                        self.deadline_exceeded += 1;
+                        self.arrived_at.remove(&rid);
+                        self.cached_requests.remove(&rid);
+                        if let Some(&prev) = qpath.last() {
+                            cmds.push(ScheduleCmd {
+                                delay: 0,
+                                node_id: prev,
+                                event_type: EventType::Response {
+                                    request_id: rid,
+                                    path: qpath,
+                                    start_time: qstart,
+                                    success: false,
+                                    failure: Some(FailureReason::DeadlineExceeded),
+                                    timeout: qtimeout,
+                                    max_hops: qmax_hops,
+                                    metadata: qmetadata,
+                                },
+                            });
+                        }
+                        continue;
+                    }
+                    next = Some((rid, qpath, qstart, qtimeout, qmax_hops, qmetadata));
+                    break;
+                }
+                if let Some((
+                    next_rid,
+                    next_path,
+                    next_start,
+                    next_timeout,
+                    next_max_hops,
+                    next_metadata,
+                )) = next
+                {
+                    let delay_us = if self.cached_requests.contains(&next_rid) {
+                        Self::cache_hit_delay(&mut self.rng, &config)
+                    } else {
+                        Self::calculate_processing_delay(&mut self.rng, &config, queue_load)
+                    };
+                    if let Some(&arrived) = self.arrived_at.get(&next_rid) {
+                        smooth(
+                            &mut self.queue_wait_ewma_us,
+                            event.time.saturating_sub(arrived) as f64,
+                        );
+                    }
+                    smooth(&mut self.service_ewma_us, delay_us as f64);
                     cmds.push(ScheduleCmd {
                         delay: delay_us,
                         node_id: event.node_id,
@@ -291,6 +678,8 @@ impl Component for Server {
                             start_time: next_start,
                             path: next_path,
                             timeout: next_timeout,
+                            max_hops: next_max_hops,
+                            metadata: next_metadata,
                         },
                     });
                 } else {
@@ -303,11 +692,20 @@ impl Component for Server {
                 mut path,
                 start_time,
                 success,
+                failure,
                 timeout,
+                max_hops,
+                mut metadata,
             } => {
+                if success {
+                    Self::fill_cache(&mut self.cache, &config, &metadata, event.time);
+                    if let Some(size) = &config.response_size {
+                        size.stamp(&mut self.rng, &mut metadata, RESPONSE_BYTES);
+                    }
+                }
                 if let Some(_prev_node) = path.pop() {
                     if let Some(actual_prev) = path.last() {
-                        vec![ScheduleCmd {
+                        smallvec![ScheduleCmd {
                             delay: 0,
                             node_id: *actual_prev,
                             event_type: EventType::Response {
@@ -315,22 +713,29 @@ impl Component for Server {
                                 path,
                                 start_time,
                                 success,
+                                failure,
                                 timeout,
+                                max_hops,
+                                metadata,
                             },
                         }]
                     } else {
-                        vec![]
+                        smallvec![]
                     }
                 } else {
-                    vec![]
+                    smallvec![]
                 }
             }
-            _ => vec![],
+            _ => smallvec![],
         }
     }
     fn name(&self) -> &str {
         &self.name
     }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
     fn kind(&self) -> &str {
         "Server"
     }
@@ -366,18 +771,58 @@ impl Component for Server {
             concurrency: config.concurrency,
             queue_len: self.queue.len(),
             saturation_penalty: current_penalty,
+            queue_wait_ms: self.queue_wait_ewma_us.unwrap_or(0.0) as f32 / 1000.0,
+            service_time_ms: self.service_ewma_us.unwrap_or(0.0) as f32 / 1000.0,
+            cache_hit_rate: config.has_cache().then(|| {
+                let lookups = self.cache_hits + self.cache_misses;
+                if lookups > 0 {
+                    self.cache_hits as f32 / lookups as f32
+                } else {
+                    0.0
+                }
+            }),
         });
     }
     fn active_requests(&self) -> u32 {
         self.active_threads + self.queue.len() as u32
     }
 
+    fn queue_depth(&self) -> u32 {
+        self.queue.len() as u32
+    }
+
+    fn recent_latency_us(&self) -> Option<u64> {
+        self.latency_ewma_us.map(|l| l as u64)
+    }
+
+    fn queue_wait_us(&self) -> Option<u64> {
+        self.queue_wait_ewma_us.map(|w| w as u64)
+    }
+
+    fn service_time_us(&self) -> Option<u64> {
+        self.service_ewma_us.map(|s| s as u64)
+    }
+
     fn display_throughput(&self) -> f32 {
         self.display_throughput
     }
+    fn received_count(&self) -> u64 {
+        self.received
+    }
+    fn utilization(&self) -> f32 {
+        let concurrency = self.config.read().unwrap().concurrency;
+        if concurrency > 0 {
+            self.active_threads as f32 / concurrency as f32
+        } else {
+            0.0
+        }
+    }
     fn error_count(&self) -> u64 {
         self.errors
     }
+    fn deadline_exceeded_count(&self) -> u64 {
+        self.deadline_exceeded
+    }
     fn set_healthy(&mut self, h: bool) {
         self.healthy = h;
     }
@@ -385,23 +830,40 @@ impl Component for Server {
         self.healthy
     }
     fn add_target(&mut self, target: NodeId) {
-        self.next_hop = Some(target);
+        if !self.targets.contains(&target) {
+            self.targets.push(target);
+        }
     }
     fn remove_target(&mut self, target: NodeId) {
-        if self.next_hop == Some(target) {
-            self.next_hop = None;
+        if let Some(index) = self.targets.iter().position(|&t| t == target) {
+            self.targets.remove(index);
+            // Later targets keep their chances
+            let mut config = self.config.write().unwrap();
+            if index < config.route_probabilities.len() {
+                config.route_probabilities.remove(index);
+            }
         }
     }
     fn get_targets(&self) -> Vec<NodeId> {
-        self.next_hop.map(|id| vec![id]).unwrap_or_default()
+        self.targets.clone()
     }
     fn clear_targets(&mut self) {
-        self.next_hop = None;
+        self.targets.clear();
     }
     fn reset_internal_stats(&mut self) {
         self.errors = 0;
+        self.deadline_exceeded = 0;
         self.arrival_window.clear();
+        self.received = 0;
         self.queue.clear();
+        self.arrived_at.clear();
+        self.latency_ewma_us = None;
+        self.queue_wait_ewma_us = None;
+        self.service_ewma_us = None;
+        self.cache.clear();
+        self.cached_requests.clear();
+        self.cache_hits = 0;
+        self.cache_misses = 0;
         self.active_threads = 0;
         self.display_throughput = 0.0;
         self.display_snapshot = VisualState::None;
@@ -410,6 +872,33 @@ impl Component for Server {
     fn set_seed(&mut self, seed: u64) {
         self.rng = StdRng::seed_from_u64(seed);
     }
+
+    fn abort_pending(&mut self, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        self.arrived_at.clear();
+        self.cached_requests.clear();
+        self.queue
+            .drain(..)
+            .filter_map(
+                |(request_id, path, start_time, timeout, max_hops, metadata)| {
+                    let prev = *path.last()?;
+                    Some(ScheduleCmd {
+                        delay: 0,
+                        node_id: prev,
+                        event_type: EventType::Response {
+                            request_id,
+                            path,
+                            start_time,
+                            success: false,
+                            failure: Some(FailureReason::Unavailable),
+                            timeout,
+                            max_hops,
+                            metadata,
+                        },
+                    })
+                },
+            )
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -425,18 +914,19 @@ mod tests {
             backlog_limit: 10,
             failure_probability: 0.0,
             saturation_penalty: 1.0,
+            ..ServerConfig::default()
         };
 
         // 1. Zero load: No penalty (approx 10ms)
         let delay_0 = Server::calculate_processing_delay(&mut rng, &config, 0.0);
-        assert!(delay_0 >= 9_500 && delay_0 <= 10_500);
+        assert!((9_500..=10_500).contains(&delay_0));
 
         // 2. 50% load: Small penalty (1.0 + 0.25*1.0 = 1.25x -> approx 12.5ms)
         let delay_50 = Server::calculate_processing_delay(&mut rng, &config, 0.5);
-        assert!(delay_50 >= 11_800 && delay_50 <= 13_200);
+        assert!((11_800..=13_200).contains(&delay_50));
 
         // 3. 100% load: Max penalty (1.0 + 1.0*1.0 = 2.0x -> approx 20ms)
         let delay_100 = Server::calculate_processing_delay(&mut rng, &config, 1.0);
-        assert!(delay_100 >= 19_000 && delay_100 <= 21_000);
+        assert!((19_000..=21_000).contains(&delay_100));
     }
 }
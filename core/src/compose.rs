@@ -0,0 +1,197 @@
+use crate::kubernetes::{import_clients, ManifestError};
+use crate::topology::Topology;
+use crate::traits::NodeId;
+use serde::Deserialize;
+use serde_yaml::Value;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ComposeFile {
+    /// Kept as a mapping so services stay in file order
+    services: serde_yaml::Mapping,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ComposeService {
+    /// A list of names, or a map from name to start condition
+    depends_on: Value,
+    /// Legacy `name` or `name:alias` entries
+    links: Vec<String>,
+    ports: Vec<Value>,
+    deploy: Deploy,
+    /// Replica count of the v2 file format
+    scale: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Deploy {
+    replicas: Option<u32>,
+}
+
+impl ComposeService {
+    fn dependencies(&self) -> Vec<String> {
+        let named = match &self.depends_on {
+            Value::Sequence(names) => names.iter().filter_map(Value::as_str).collect(),
+            Value::Mapping(conditions) => conditions.keys().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let linked = self.links.iter().map(|l| l.split(':').next().unwrap_or(l));
+        let mut names: Vec<String> = Vec::new();
+        for name in named.into_iter().chain(linked) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+}
+
+fn error(message: String) -> ManifestError {
+    ManifestError {
+        document: 0,
+        message,
+    }
+}
+
+impl Topology {
+    /// A starter topology from a docker-compose file.
+    ///
+    /// A service with one replica becomes a default server named after
+    /// it; one with more (`deploy.replicas` or `scale`) becomes a load
+    /// balancer of that name in front of servers `name-1`, `name-2`, ...
+    /// Its servers call the first service named in `depends_on` or
+    /// `links`. A `users` client sends traffic to each service that
+    /// publishes `ports`, or, if none do, to each one nothing calls, and to
+    /// any service left out of reach; with several, the clients are named
+    /// `users-<service>`. Service times, concurrency and rates are defaults
+    /// to tune afterwards.
+    ///
+    /// ```
+    /// use slay_core::Topology;
+    ///
+    /// let topology = Topology::from_compose(r#"
+    /// services:
+    ///   web:
+    ///     image: shop/web
+    ///     ports: ["8080:80"]
+    ///     depends_on: [db]
+    ///   db:
+    ///     image: postgres:16
+    /// "#).unwrap();
+    /// // users -> web -> db
+    /// assert_eq!(topology.nodes[1].targets, vec![3]);
+    /// ```
+    pub fn from_compose(yaml: &str) -> Result<Topology, ManifestError> {
+        let file: ComposeFile = serde_yaml::from_str(yaml).map_err(|e| error(e.to_string()))?;
+        let mut services: Vec<(String, ComposeService)> = Vec::new();
+        for (name, service) in file.services {
+            let name = name
+                .as_str()
+                .ok_or_else(|| error(format!("service name {:?} is not a string", name)))?
+                .to_string();
+            let service = if service.is_null() {
+                ComposeService::default()
+            } else {
+                serde_yaml::from_value(service)
+                    .map_err(|e| error(format!("service '{}': {}", name, e)))?
+            };
+            services.push((name, service));
+        }
+        if services.is_empty() {
+            return Err(error("no services found".to_string()));
+        }
+
+        // The service each one calls; a server forwards each request to
+        // one target, so a service with several dependencies calls the first
+        let index = |name: &str| services.iter().position(|(n, _)| n == name);
+        let mut calls: Vec<Option<usize>> = Vec::new();
+        for (name, service) in &services {
+            let mut first = None;
+            for dependency in service.dependencies() {
+                let Some(i) = index(&dependency) else {
+                    return Err(error(format!(
+                        "service '{}' depends on unknown service '{}'",
+                        name, dependency
+                    )));
+                };
+                first = first.or(Some(i));
+            }
+            calls.push(first);
+        }
+
+        // Outside traffic goes to the services publishing ports, or to
+        // those nothing calls, then to whatever is still out of reach
+        let published: Vec<usize> = (0..services.len())
+            .filter(|&i| !services[i].1.ports.is_empty())
+            .collect();
+        let roots: Vec<usize> = (0..services.len())
+            .filter(|&i| !calls.contains(&Some(i)))
+            .collect();
+        let mut entry_points: Vec<usize> = Vec::new();
+        let mut reached = vec![false; services.len()];
+        let candidates = if published.is_empty() {
+            roots
+        } else {
+            published.into_iter().chain(roots).collect()
+        };
+        for i in candidates.into_iter().chain(0..services.len()) {
+            if reached[i] {
+                continue;
+            }
+            entry_points.push(i);
+            let mut next = Some(i);
+            while let Some(j) = next.filter(|&j| !reached[j]) {
+                reached[j] = true;
+                next = calls[j];
+            }
+        }
+
+        let entries: Vec<&str> = entry_points
+            .iter()
+            .map(|&i| services[i].0.as_str())
+            .collect();
+        let mut topology = import_clients(Topology::new(), &entries);
+        let mut entry_ids: Vec<NodeId> = Vec::new();
+        let mut member_ids: Vec<Vec<NodeId>> = Vec::new();
+        for (name, service) in &services {
+            let replicas = service.deploy.replicas.or(service.scale).unwrap_or(1) as usize;
+            if replicas == 1 {
+                topology = topology.server(name, |_| {});
+                entry_ids.push(topology.max_node_id());
+                member_ids.push(vec![topology.max_node_id()]);
+                continue;
+            }
+            topology = topology.lb(name);
+            let lb = topology.max_node_id();
+            topology = topology.servers(name, replicas, |_| {});
+            let members: Vec<NodeId> = (lb + 1..=topology.max_node_id()).collect();
+            for node in topology
+                .nodes
+                .iter_mut()
+                .filter(|n| members.contains(&n.id))
+            {
+                node.services.push(name.clone());
+            }
+            topology.nodes[lb as usize - 1].targets = members.clone();
+            entry_ids.push(lb);
+            member_ids.push(members);
+        }
+
+        // The clients come first, one per entry point
+        for (client, &i) in entry_points.iter().enumerate() {
+            topology.nodes[client].targets.push(entry_ids[i]);
+        }
+        for (i, called) in calls.iter().enumerate() {
+            if let Some(used) = *called {
+                for &member in &member_ids[i] {
+                    topology.nodes[member as usize - 1]
+                        .targets
+                        .push(entry_ids[used]);
+                }
+            }
+        }
+        Ok(topology)
+    }
+}
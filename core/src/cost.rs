@@ -0,0 +1,154 @@
+use crate::engine::Simulation;
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const US_PER_HOUR: f64 = 3_600_000_000.0;
+
+/// What running one node costs, in dollars.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct CostRate {
+    /// Charged for every hour of virtual time the node exists (instances)
+    #[serde(default)]
+    pub per_hour: f64,
+    /// Charged per million requests the node receives (managed services)
+    #[serde(default)]
+    pub per_million_requests: f64,
+}
+
+impl CostRate {
+    pub fn is_zero(&self) -> bool {
+        self.per_hour == 0.0 && self.per_million_requests == 0.0
+    }
+}
+
+/// Cost accrued by every node since statistics were last reset.
+///
+/// Time-based cost accrues from when a node was added (or the end of the
+/// warm-up, if later); request-based cost counts arrivals after the warm-up
+/// while the node has a per-request rate.
+/// Removed or repriced nodes keep contributing what they accrued before.
+#[derive(Debug, Clone, Default)]
+pub struct CostLedger {
+    rates: BTreeMap<NodeId, CostRate>,
+    since: HashMap<NodeId, u64>,
+    requests: HashMap<NodeId, u64>,
+    retired: f64,
+}
+
+impl CostLedger {
+    pub fn rate(&self, id: NodeId) -> CostRate {
+        self.rates.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Whether any node has a non-zero rate.
+    pub fn is_priced(&self) -> bool {
+        !self.rates.is_empty()
+    }
+
+    /// Cost of `id` for the time between `from` (or when it was added, if
+    /// later) and `now`.
+    pub fn node_cost(&self, id: NodeId, from: u64, now: u64) -> f64 {
+        let rate = self.rate(id);
+        let hours = self.since.get(&id).map_or(0.0, |&since| {
+            now.saturating_sub(since.max(from)) as f64 / US_PER_HOUR
+        });
+        let requests = self.requests.get(&id).copied().unwrap_or(0) as f64;
+        rate.per_hour * hours + rate.per_million_requests * requests / 1_000_000.0
+    }
+
+    pub fn total(&self, from: u64, now: u64) -> f64 {
+        self.retired
+            + self
+                .rates
+                .keys()
+                .map(|&id| self.node_cost(id, from, now))
+                .sum::<f64>()
+    }
+
+    /// Banks what the old rate accrued so a new one only applies from now.
+    fn set_rate(&mut self, id: NodeId, rate: CostRate, from: u64, now: u64) {
+        self.retired += self.node_cost(id, from, now);
+        self.since.insert(id, now);
+        self.requests.remove(&id);
+        if rate.is_zero() {
+            self.rates.remove(&id);
+        } else {
+            self.rates.insert(id, rate);
+        }
+    }
+
+    fn node_added(&mut self, id: NodeId, now: u64) {
+        self.since.insert(id, now);
+    }
+
+    fn node_removed(&mut self, id: NodeId, from: u64, now: u64) {
+        self.retired += self.node_cost(id, from, now);
+        self.rates.remove(&id);
+        self.since.remove(&id);
+        self.requests.remove(&id);
+    }
+
+    fn record_requests(&mut self, id: NodeId, count: u64) {
+        if self
+            .rates
+            .get(&id)
+            .is_some_and(|r| r.per_million_requests > 0.0)
+        {
+            *self.requests.entry(id).or_default() += count;
+        }
+    }
+
+    fn reset(&mut self, now: u64) {
+        for since in self.since.values_mut() {
+            *since = now;
+        }
+        self.requests.clear();
+        self.retired = 0.0;
+    }
+}
+
+impl Simulation {
+    /// Prices an existing node; a zero rate makes it free again.
+    pub fn set_cost_rate(&mut self, id: NodeId, rate: CostRate) {
+        if self.components.contains_key(&id) {
+            self.costs.set_rate(id, rate, self.warmup_end(), self.time);
+        }
+    }
+
+    /// Dollars spent since statistics were last reset, excluding the warm-up.
+    pub fn total_cost(&self) -> f64 {
+        self.costs.total(self.warmup_end(), self.time)
+    }
+
+    pub fn node_cost(&self, id: NodeId) -> f64 {
+        self.costs.node_cost(id, self.warmup_end(), self.time)
+    }
+
+    /// Dollars spent per successful request, once any has succeeded.
+    pub fn cost_per_success(&self) -> Option<f64> {
+        (self.success_count > 0).then(|| self.total_cost() / self.success_count as f64)
+    }
+
+    pub(crate) fn cost_node_added(&mut self, id: NodeId) {
+        self.costs.node_added(id, self.time);
+    }
+
+    pub(crate) fn cost_node_removed(&mut self, id: NodeId) {
+        self.costs.node_removed(id, self.warmup_end(), self.time);
+    }
+
+    pub(crate) fn cost_request(&mut self, id: NodeId) {
+        self.cost_requests(id, 1);
+    }
+
+    pub(crate) fn cost_requests(&mut self, id: NodeId, count: u64) {
+        if !self.in_warmup() {
+            self.costs.record_requests(id, count);
+        }
+    }
+
+    pub(crate) fn cost_reset(&mut self) {
+        self.costs.reset(self.time);
+    }
+}
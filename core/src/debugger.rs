@@ -0,0 +1,114 @@
+use crate::engine::{Event, EventType, Simulation};
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+
+/// Condition checked against the next event before it is processed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Breakpoint {
+    /// Pause when `node_id` is about to receive an `Arrival`
+    ArrivalAt { node_id: NodeId },
+    /// Pause on any event delivered to `node_id`
+    EventAt { node_id: NodeId },
+    /// Pause when a request completes unsuccessfully at its client
+    /// (error or timeout). `None` matches any request.
+    RequestFailed { request_id: Option<u128> },
+    /// Pause once virtual time reaches `time_us`
+    TimeReached { time_us: u64 },
+}
+
+impl Breakpoint {
+    pub fn matches(&self, event: &Event) -> bool {
+        match self {
+            Breakpoint::ArrivalAt { node_id } => {
+                event.node_id == *node_id && matches!(event.event_type, EventType::Arrival { .. })
+            }
+            Breakpoint::EventAt { node_id } => event.node_id == *node_id,
+            Breakpoint::RequestFailed { request_id } => match &event.event_type {
+                EventType::Response {
+                    request_id: rid,
+                    path,
+                    start_time,
+                    success,
+                    timeout,
+                    ..
+                } => {
+                    let is_final = path.len() == 1;
+                    let failed = !*success || event.time.saturating_sub(*start_time) > *timeout;
+                    is_final && failed && request_id.is_none_or(|id| id == *rid)
+                }
+                _ => false,
+            },
+            Breakpoint::TimeReached { time_us } => event.time >= *time_us,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Breakpoint::ArrivalAt { node_id } => format!("Arrival at #{}", node_id),
+            Breakpoint::EventAt { node_id } => format!("Any event at #{}", node_id),
+            Breakpoint::RequestFailed { request_id: None } => "Any request fails".to_string(),
+            Breakpoint::RequestFailed {
+                request_id: Some(id),
+            } => format!("Request {:x} fails", id),
+            Breakpoint::TimeReached { time_us } => {
+                format!("t >= {:.3}s", *time_us as f64 / 1_000_000.0)
+            }
+        }
+    }
+}
+
+/// Outcome of `Simulation::step_checked`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepResult {
+    /// One event was processed
+    Stepped,
+    /// The queue is empty
+    Idle,
+    /// The next event matches the breakpoint at this index and was not processed
+    Breakpoint(usize),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Debugger {
+    pub breakpoints: Vec<Breakpoint>,
+    /// Breakpoint the engine is currently paused on
+    pub hit: Option<usize>,
+    /// Most recent event processed through `step_checked`
+    pub last_event: Option<Event>,
+}
+
+impl Simulation {
+    /// Next event in the queue, without processing it.
+    pub fn peek_event(&mut self) -> Option<&Event> {
+        self.skip_cancelled();
+        self.events.peek()
+    }
+
+    /// Like `step`, but stops in front of events matching a breakpoint.
+    ///
+    /// When paused on a breakpoint the next call processes that event
+    /// unconditionally, so callers can simply keep stepping to resume.
+    pub fn step_checked(&mut self) -> StepResult {
+        self.skip_cancelled();
+        let Some(next) = self.events.peek() else {
+            return StepResult::Idle;
+        };
+
+        if self.debugger.hit.is_none() {
+            if let Some(idx) = self
+                .debugger
+                .breakpoints
+                .iter()
+                .position(|bp| bp.matches(next))
+            {
+                self.debugger.hit = Some(idx);
+                return StepResult::Breakpoint(idx);
+            }
+        }
+
+        self.debugger.hit = None;
+        self.debugger.last_event = Some(next.clone());
+        self.step();
+        StepResult::Stepped
+    }
+}
@@ -0,0 +1,728 @@
+use crate::components::{component_kinds, create_component};
+use crate::cost::CostRate;
+use crate::dot::default_config;
+use crate::network::{canonical_key, EdgeConfig};
+use crate::scenario::{Scenario, ScenarioAction};
+use crate::topology::{LinkSpec, NodeSpec, Topology, TOPOLOGY_VERSION};
+use crate::traits::{Health, NodeId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Kind of components that do not name one.
+const DEFAULT_KIND: &str = "Server";
+
+/// Why a YAML design could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DesignError {
+    pub message: String,
+}
+
+impl fmt::Display for DesignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for DesignError {}
+
+fn error<T>(message: String) -> Result<T, DesignError> {
+    Err(DesignError { message })
+}
+
+/// A point or span of virtual time in microseconds, written `10s`,
+/// `250ms`, `1.5m` or as a plain number of seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Time(u64);
+
+impl Time {
+    fn parse(value: &serde_yaml::Value) -> Option<u64> {
+        let seconds = |s: f64| (s >= 0.0).then(|| (s * 1_000_000.0).round() as u64);
+        if let Some(s) = value.as_f64() {
+            return seconds(s);
+        }
+        let text = value.as_str()?.trim();
+        let split = text
+            .find(|c: char| c.is_alphabetic() || c == 'µ')
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+        let number: f64 = number.trim().parse().ok()?;
+        let scale = match unit {
+            "us" | "µs" => 1e-6,
+            "ms" => 1e-3,
+            "" | "s" => 1.0,
+            "m" | "min" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        seconds(number * scale)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let us = self.0;
+        if us.is_multiple_of(1_000_000) {
+            write!(f, "{}s", us / 1_000_000)
+        } else if us.is_multiple_of(1000) {
+            write!(f, "{}ms", us / 1000)
+        } else {
+            write!(f, "{}us", us)
+        }
+    }
+}
+
+impl Serialize for Time {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_yaml::Value::deserialize(deserializer)?;
+        Time::parse(&value).map(Time).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid time {:?}, expected e.g. 10s, 250ms or 2m",
+                value
+            ))
+        })
+    }
+}
+
+fn ms(us: u64) -> f64 {
+    us as f64 / 1000.0
+}
+
+fn us(ms: f64) -> u64 {
+    (ms * 1000.0).round() as u64
+}
+
+/// A link direction's settings as written in a design, each left out
+/// when it is the default.
+struct EdgeFields {
+    latency_ms: Option<f64>,
+    jitter_ms: Option<f64>,
+    loss: Option<f32>,
+    bandwidth_mbps: Option<f32>,
+}
+
+impl EdgeFields {
+    /// The link direction: defaults for what is left out.
+    fn edge(self) -> EdgeConfig {
+        let default = EdgeConfig::default();
+        EdgeConfig {
+            latency_us: self.latency_ms.map(us).unwrap_or(default.latency_us),
+            jitter_us: self.jitter_ms.map(us).unwrap_or(default.jitter_us),
+            packet_loss_rate: self.loss.unwrap_or(default.packet_loss_rate),
+            bandwidth_mbps: self.bandwidth_mbps.unwrap_or(default.bandwidth_mbps),
+        }
+    }
+
+    /// The fields of `edge` that differ from the defaults.
+    fn of(edge: &EdgeConfig) -> Self {
+        let default = EdgeConfig::default();
+        Self {
+            latency_ms: (edge.latency_us != default.latency_us).then(|| ms(edge.latency_us)),
+            jitter_ms: (edge.jitter_us != default.jitter_us).then(|| ms(edge.jitter_us)),
+            loss: (edge.packet_loss_rate != default.packet_loss_rate)
+                .then_some(edge.packet_loss_rate),
+            bandwidth_mbps: (edge.bandwidth_mbps != default.bandwidth_mbps)
+                .then_some(edge.bandwidth_mbps),
+        }
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn is_true(b: &bool) -> bool {
+    *b
+}
+
+fn yes() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ComponentSpec {
+    /// Registered kind, in any case, `_` and `-` ignored (`load_balancer`)
+    kind: String,
+    /// Only needed to keep ids stable; otherwise one more than the last
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<NodeId>,
+    /// Display name, when it is not the key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Fields laid over the kind's defaults
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    config: serde_json::Map<String, Value>,
+    /// Components requests are forwarded to, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    calls: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    services: Vec<String>,
+    #[serde(skip_serializing_if = "CostRate::is_zero")]
+    cost: CostRate,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LinkEntry {
+    from: String,
+    to: String,
+    /// Only the `from` → `to` direction; both otherwise
+    #[serde(default, skip_serializing_if = "is_false")]
+    one_way: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    jitter_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    loss: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bandwidth_mbps: Option<f32>,
+}
+
+/// A client's request rate from `at` on.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateChange {
+    at: Time,
+    rps: f32,
+}
+
+/// One timed action: `at`, the key naming the action and its arguments.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct StepSpec {
+    at: Time,
+    #[serde(default = "yes", skip_serializing_if = "is_true")]
+    enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kill: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revive: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_config: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_edge: Option<[String; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    jitter_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    loss: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bandwidth_mbps: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slowdown: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extra_latency_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error_rate: Option<f32>,
+    #[serde(rename = "for", default, skip_serializing_if = "Option::is_none")]
+    duration: Option<Time>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    clear_latency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_health: Option<String>,
+    /// `healthy`, `down` or `degraded` (with `slowdown` and `error_rate`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    health: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partition: Option<[String; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    heal: Option<[String; 2]>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ScenarioSpec {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    name: String,
+    steps: Vec<StepSpec>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct DesignFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<u32>,
+    /// Keyed by name, kept as a mapping so components stay in file order
+    components: serde_yaml::Mapping,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    links: Vec<LinkEntry>,
+    /// Request rate changes per client, compiled into scenario steps
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    traffic: BTreeMap<String, Vec<RateChange>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scenario: Option<ScenarioSpec>,
+}
+
+/// The kind registered under `name`, ignoring case, `_` and `-`.
+fn resolve_kind(name: &str) -> Option<&'static str> {
+    let name = name.replace(['_', '-'], "");
+    component_kinds()
+        .into_iter()
+        .find(|k| k.eq_ignore_ascii_case(&name))
+}
+
+/// `LoadBalancer` as `load_balancer`.
+fn snake_case(kind: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in kind.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// `fields` laid over `kind`'s defaults, as the component reads them back.
+fn component_config(
+    name: &str,
+    kind: &str,
+    fields: serde_json::Map<String, Value>,
+) -> Result<Value, DesignError> {
+    let defaults = default_config(kind);
+    let mut config = defaults.clone();
+    for (key, value) in &fields {
+        config[key] = value.clone();
+    }
+    // A config that fails to deserialize silently falls back to defaults
+    let accepted = create_component(kind, config.clone())
+        .map(|c| c.encode_config())
+        .unwrap_or(Value::Null);
+    if let Some(key) = fields.keys().find(|k| accepted.get(k.as_str()).is_none()) {
+        return error(format!(
+            "'{}': {} has no config field '{}'",
+            name, kind, key
+        ));
+    }
+    if config != defaults && accepted == defaults {
+        return error(format!(
+            "'{}': the config is not a valid {} config",
+            name, kind
+        ));
+    }
+    Ok(accepted)
+}
+
+/// A topology with the scenario to run on it, in the YAML format meant
+/// to be written by hand and kept under version control.
+///
+/// Components are keyed by name and everything else refers to them by
+/// it; configs list only what differs from the kind's defaults. Times are
+/// written `10s`, `250ms` or `2m`, and link latencies in milliseconds.
+/// `traffic` changes a client's request rate at given times and becomes
+/// `SetConfig` steps of the scenario.
+///
+/// ```yaml
+/// components:
+///   users:
+///     kind: client
+///     config: { arrival_rate: 50 }
+///     calls: [edge]
+///   edge:
+///     kind: load_balancer
+///     calls: [api-1, api-2]
+///   api-1: { kind: server, services: [api] }
+///   api-2: { kind: server, services: [api], config: { concurrency: 2 } }
+/// links:
+///   - { from: edge, to: api-2, latency_ms: 25, loss: 0.01 }
+/// traffic:
+///   users:
+///     - { at: 30s, rps: 200 }
+/// scenario:
+///   name: Zone outage
+///   steps:
+///     - { at: 10s, kill: api-1 }
+///     - { at: 20s, partition: [edge, api-2] }
+///     - { at: 40s, slow: api-2, slowdown: 3, for: 5s }
+/// ```
+///
+/// Scenario steps name one action each: `kill`, `revive`, `set_config`
+/// (with `config`), `set_edge` (a pair, with `latency_ms`, `jitter_ms`,
+/// `loss`, `bandwidth_mbps`), `slow` (with `slowdown`, `extra_latency_ms`, `error_rate`,
+/// `for`), `clear_latency`, `set_health` (with `health`), `partition` and
+/// `heal` (pairs). `enabled: false` keeps a step without running it.
+#[derive(Debug, Clone, Default)]
+pub struct Design {
+    pub topology: Topology,
+    pub scenario: Scenario,
+}
+
+impl Design {
+    pub fn from_yaml(yaml: &str) -> Result<Design, DesignError> {
+        let file: DesignFile = match serde_yaml::from_str(yaml) {
+            Ok(file) => file,
+            Err(e) => return error(e.to_string()),
+        };
+        let mut topology = Topology {
+            version: file.version.unwrap_or(TOPOLOGY_VERSION),
+            ..Topology::default()
+        };
+
+        let mut components: Vec<(String, ComponentSpec)> = Vec::new();
+        for (key, value) in file.components {
+            let Some(key) = key.as_str().map(str::to_string) else {
+                return error(format!("component name {:?} is not a string", key));
+            };
+            let spec = if value.is_null() {
+                ComponentSpec::default()
+            } else {
+                match serde_yaml::from_value(value) {
+                    Ok(spec) => spec,
+                    Err(e) => return error(format!("component '{}': {}", key, e)),
+                }
+            };
+            components.push((key, spec));
+        }
+        let mut ids: HashMap<String, NodeId> = HashMap::new();
+        for (key, spec) in &components {
+            let id = spec.id.unwrap_or(topology.max_node_id() + 1);
+            if topology.nodes.iter().any(|n| n.id == id) {
+                return error(format!("'{}': id {} is taken", key, id));
+            }
+            let kind = match spec.kind.as_str() {
+                "" => DEFAULT_KIND,
+                name => match resolve_kind(name) {
+                    Some(kind) => kind,
+                    None => return error(format!("'{}': unknown kind '{}'", key, name)),
+                },
+            };
+            ids.insert(key.clone(), id);
+            topology.nodes.push(NodeSpec {
+                id,
+                kind: kind.to_string(),
+                name: spec.name.clone().unwrap_or_else(|| key.clone()),
+                config: component_config(key, kind, spec.config.clone())?,
+                targets: Vec::new(),
+                services: spec.services.clone(),
+                cost: spec.cost,
+            });
+        }
+        let id = |name: &str| match ids.get(name) {
+            Some(&id) => Ok(id),
+            None => error(format!("no component named '{}'", name)),
+        };
+        for (i, (_, spec)) in components.iter().enumerate() {
+            let targets = spec
+                .calls
+                .iter()
+                .map(|name| id(name))
+                .collect::<Result<_, _>>()?;
+            topology.nodes[i].targets = targets;
+        }
+
+        for entry in &file.links {
+            let (from, to) = (id(&entry.from)?, id(&entry.to)?);
+            let (a, b) = canonical_key(from, to);
+            let config = EdgeFields {
+                latency_ms: entry.latency_ms,
+                jitter_ms: entry.jitter_ms,
+                loss: entry.loss,
+                bandwidth_mbps: entry.bandwidth_mbps,
+            }
+            .edge();
+            let index = match topology.links.iter().position(|l| (l.a, l.b) == (a, b)) {
+                Some(index) => index,
+                None => {
+                    topology.links.push(LinkSpec {
+                        a,
+                        b,
+                        link: Default::default(),
+                    });
+                    topology.links.len() - 1
+                }
+            };
+            let link = &mut topology.links[index].link;
+            if !entry.one_way || from < to {
+                link.min_to_max = config;
+            }
+            if !entry.one_way || from > to {
+                link.max_to_min = config;
+            }
+        }
+
+        let spec = file.scenario.unwrap_or_default();
+        let mut scenario = Scenario::new(&spec.name);
+        for step in &spec.steps {
+            let index = scenario.insert(step.at.0, step.action(&id)?);
+            scenario.steps[index].enabled = step.enabled;
+        }
+        for (client, changes) in &file.traffic {
+            let node_id = id(client)?;
+            for change in changes {
+                let config = serde_json::json!({ "arrival_rate": change.rps });
+                scenario.insert(change.at.0, ScenarioAction::SetConfig { node_id, config });
+            }
+        }
+        Ok(Design { topology, scenario })
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let nodes = &self.topology.nodes;
+        let keys: HashMap<NodeId, String> = nodes
+            .iter()
+            .map(|node| {
+                let unique = nodes.iter().filter(|n| n.name == node.name).count() == 1;
+                let key = if unique && !node.name.is_empty() {
+                    node.name.clone()
+                } else {
+                    format!("{}-{}", snake_case(&node.kind), node.id)
+                };
+                (node.id, key)
+            })
+            .collect();
+        let key = |id: &NodeId| keys.get(id).cloned().unwrap_or_else(|| format!("#{}", id));
+
+        let mut file = DesignFile {
+            version: Some(self.topology.version),
+            ..DesignFile::default()
+        };
+        let mut last_id = 0;
+        for node in nodes {
+            let defaults = default_config(&node.kind);
+            let config = node
+                .config
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(k, v)| defaults.get(k.as_str()) != Some(v))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            let spec = ComponentSpec {
+                kind: snake_case(&node.kind),
+                id: (node.id != last_id + 1).then_some(node.id),
+                name: (key(&node.id) != node.name).then(|| node.name.clone()),
+                config,
+                calls: node.targets.iter().map(key).collect(),
+                services: node.services.clone(),
+                cost: node.cost,
+            };
+            last_id = last_id.max(node.id);
+            let value = serde_yaml::to_value(spec).unwrap_or_default();
+            file.components.insert(key(&node.id).into(), value);
+        }
+
+        for spec in &self.topology.links {
+            let link = &spec.link;
+            let directions = if link.min_to_max == link.max_to_min {
+                vec![(spec.a, spec.b, &link.min_to_max, false)]
+            } else {
+                vec![
+                    (spec.a, spec.b, &link.min_to_max, true),
+                    (spec.b, spec.a, &link.max_to_min, true),
+                ]
+            };
+            for (from, to, config, one_way) in directions {
+                let fields = EdgeFields::of(config);
+                file.links.push(LinkEntry {
+                    from: key(&from),
+                    to: key(&to),
+                    one_way,
+                    latency_ms: fields.latency_ms,
+                    jitter_ms: fields.jitter_ms,
+                    loss: fields.loss,
+                    bandwidth_mbps: fields.bandwidth_mbps,
+                });
+            }
+        }
+
+        let mut steps = Vec::new();
+        for step in &self.scenario.steps {
+            if let Some((node_id, rps)) = rate_change(step.enabled, &step.action) {
+                file.traffic
+                    .entry(key(&node_id))
+                    .or_default()
+                    .push(RateChange {
+                        at: Time(step.at_us),
+                        rps,
+                    });
+                continue;
+            }
+            let mut spec = StepSpec::from_action(&step.action, key);
+            spec.at = Time(step.at_us);
+            spec.enabled = step.enabled;
+            steps.push(spec);
+        }
+        if !steps.is_empty() || !self.scenario.name.is_empty() {
+            file.scenario = Some(ScenarioSpec {
+                name: self.scenario.name.clone(),
+                steps,
+            });
+        }
+        serde_yaml::to_string(&file).unwrap_or_default()
+    }
+}
+
+/// The client and rate if `action` only changes a request rate, so it
+/// can be written under `traffic`.
+fn rate_change(enabled: bool, action: &ScenarioAction) -> Option<(NodeId, f32)> {
+    let ScenarioAction::SetConfig { node_id, config } = action else {
+        return None;
+    };
+    let fields = config.as_object()?;
+    let rps = fields.get("arrival_rate")?.as_f64()?;
+    (enabled && fields.len() == 1).then_some((*node_id, rps as f32))
+}
+
+impl StepSpec {
+    fn action(
+        &self,
+        id: &impl Fn(&str) -> Result<NodeId, DesignError>,
+    ) -> Result<ScenarioAction, DesignError> {
+        let pair = |names: &[String; 2]| Ok::<_, DesignError>((id(&names[0])?, id(&names[1])?));
+        let at = self.at;
+        let mut actions = Vec::new();
+        if let Some(node) = &self.kill {
+            actions.push(ScenarioAction::Kill { node_id: id(node)? });
+        }
+        if let Some(node) = &self.revive {
+            actions.push(ScenarioAction::Revive { node_id: id(node)? });
+        }
+        if let Some(node) = &self.set_config {
+            let Some(config) = self.config.clone().filter(Value::is_object) else {
+                return error(format!("step at {}: set_config needs a config map", at));
+            };
+            actions.push(ScenarioAction::SetConfig {
+                node_id: id(node)?,
+                config,
+            });
+        }
+        if let Some(names) = &self.set_edge {
+            let (from, to) = pair(names)?;
+            actions.push(ScenarioAction::SetEdge {
+                from,
+                to,
+                edge: EdgeFields {
+                    latency_ms: self.latency_ms,
+                    jitter_ms: self.jitter_ms,
+                    loss: self.loss,
+                    bandwidth_mbps: self.bandwidth_mbps,
+                }
+                .edge(),
+            });
+        }
+        if let Some(node) = &self.slow {
+            actions.push(ScenarioAction::InjectLatency {
+                node_id: id(node)?,
+                slowdown: self.slowdown.unwrap_or(1.0),
+                extra_latency_us: self.extra_latency_ms.map(us).unwrap_or(0),
+                error_rate: self.error_rate.unwrap_or(0.0),
+                duration_us: self.duration.map(|d| d.0),
+            });
+        }
+        if let Some(node) = &self.clear_latency {
+            actions.push(ScenarioAction::ClearLatency { node_id: id(node)? });
+        }
+        if let Some(node) = &self.set_health {
+            let health = match self.health.as_deref() {
+                Some("healthy") => Health::Healthy,
+                Some("down") => Health::Down,
+                Some("degraded") => Health::Degraded {
+                    slowdown: self.slowdown.unwrap_or(1.0),
+                    error_rate: self.error_rate.unwrap_or(0.0),
+                },
+                other => {
+                    return error(format!(
+                        "step at {}: health {:?} is not healthy, down or degraded",
+                        at, other
+                    ))
+                }
+            };
+            actions.push(ScenarioAction::SetHealth {
+                node_id: id(node)?,
+                health,
+            });
+        }
+        if let Some(names) = &self.partition {
+            let (a, b) = pair(names)?;
+            actions.push(ScenarioAction::Partition { a, b });
+        }
+        if let Some(names) = &self.heal {
+            let (a, b) = pair(names)?;
+            actions.push(ScenarioAction::Heal { a, b });
+        }
+        match actions.len() {
+            1 => Ok(actions.remove(0)),
+            0 => error(format!("step at {} has no action", at)),
+            _ => error(format!("step at {} has more than one action", at)),
+        }
+    }
+
+    fn from_action(action: &ScenarioAction, key: impl Fn(&NodeId) -> String) -> StepSpec {
+        let mut spec = StepSpec::default();
+        match action {
+            ScenarioAction::Kill { node_id } => spec.kill = Some(key(node_id)),
+            ScenarioAction::Revive { node_id } => spec.revive = Some(key(node_id)),
+            ScenarioAction::SetConfig { node_id, config } => {
+                spec.set_config = Some(key(node_id));
+                spec.config = Some(config.clone());
+            }
+            ScenarioAction::SetEdge { from, to, edge } => {
+                spec.set_edge = Some([key(from), key(to)]);
+                let fields = EdgeFields::of(edge);
+                spec.latency_ms = fields.latency_ms;
+                spec.jitter_ms = fields.jitter_ms;
+                spec.loss = fields.loss;
+                spec.bandwidth_mbps = fields.bandwidth_mbps;
+            }
+            ScenarioAction::InjectLatency {
+                node_id,
+                slowdown,
+                extra_latency_us,
+                error_rate,
+                duration_us,
+            } => {
+                spec.slow = Some(key(node_id));
+                spec.slowdown = Some(*slowdown);
+                spec.extra_latency_ms = (*extra_latency_us > 0).then(|| ms(*extra_latency_us));
+                spec.error_rate = (*error_rate > 0.0).then_some(*error_rate);
+                spec.duration = duration_us.map(Time);
+            }
+            ScenarioAction::ClearLatency { node_id } => spec.clear_latency = Some(key(node_id)),
+            ScenarioAction::SetHealth { node_id, health } => {
+                spec.set_health = Some(key(node_id));
+                spec.health = Some(health.label().to_lowercase());
+                if let Health::Degraded {
+                    slowdown,
+                    error_rate,
+                } = health
+                {
+                    spec.slowdown = Some(*slowdown);
+                    spec.error_rate = Some(*error_rate);
+                }
+            }
+            ScenarioAction::Partition { a, b } => spec.partition = Some([key(a), key(b)]),
+            ScenarioAction::Heal { a, b } => spec.heal = Some([key(a), key(b)]),
+        }
+        spec
+    }
+}
+
+impl Topology {
+    /// A topology from a YAML design, ignoring its scenario; see `Design`.
+    pub fn from_yaml(yaml: &str) -> Result<Topology, DesignError> {
+        Design::from_yaml(yaml).map(|d| d.topology)
+    }
+
+    /// The topology as a YAML design without a scenario; see `Design`.
+    pub fn to_yaml(&self) -> String {
+        Design {
+            topology: self.clone(),
+            scenario: Scenario::default(),
+        }
+        .to_yaml()
+    }
+}
@@ -0,0 +1,109 @@
+use crate::engine::Simulation;
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Logical service names and the nodes currently providing them.
+///
+/// Routers resolve a name such as `"checkout"` to its members at the time of
+/// each request, so nodes can join or leave a service without rewiring the
+/// callers. Members are kept sorted by id for deterministic routing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ServiceRegistry {
+    /// Members of each service, sorted by id
+    services: BTreeMap<String, Vec<NodeId>>,
+}
+
+impl ServiceRegistry {
+    /// Adds `node_id` to `service`. Returns false if it already was a member.
+    pub fn register(&mut self, service: &str, node_id: NodeId) -> bool {
+        let members = self.services.entry(service.to_string()).or_default();
+        match members.binary_search(&node_id) {
+            Ok(_) => false,
+            Err(at) => {
+                members.insert(at, node_id);
+                true
+            }
+        }
+    }
+
+    /// Removes `node_id` from `service`. Returns false if it was not a member.
+    pub fn deregister(&mut self, service: &str, node_id: NodeId) -> bool {
+        let Some(members) = self.services.get_mut(service) else {
+            return false;
+        };
+        let removed = match members.binary_search(&node_id) {
+            Ok(at) => {
+                members.remove(at);
+                true
+            }
+            Err(_) => false,
+        };
+        if members.is_empty() {
+            self.services.remove(service);
+        }
+        removed
+    }
+
+    /// Drops `node_id` from every service.
+    pub fn remove_node(&mut self, node_id: NodeId) {
+        self.services.retain(|_, members| {
+            members.retain(|&m| m != node_id);
+            !members.is_empty()
+        });
+    }
+
+    /// Members of `service`, in id order.
+    pub fn members(&self, service: &str) -> Vec<NodeId> {
+        self.members_ref(service).to_vec()
+    }
+
+    /// Like `members`, without copying them.
+    pub fn members_ref(&self, service: &str) -> &[NodeId] {
+        self.services.get(service).map_or(&[], Vec::as_slice)
+    }
+
+    /// Services `node_id` belongs to, in name order.
+    pub fn services_of(&self, node_id: NodeId) -> Vec<String> {
+        self.services
+            .iter()
+            .filter(|(_, members)| members.contains(&node_id))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// All registered service names, in name order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.services.keys().map(String::as_str)
+    }
+}
+
+/// Change to the registry requested by a component through
+/// `SystemInspector`; applied once its event has been handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Membership {
+    Join(String),
+    Leave(String),
+}
+
+impl Simulation {
+    /// Adds an existing node to a logical service.
+    pub fn register_service(&mut self, service: &str, node_id: NodeId) -> bool {
+        self.components.contains_key(&node_id) && self.services.register(service, node_id)
+    }
+
+    pub fn deregister_service(&mut self, service: &str, node_id: NodeId) -> bool {
+        self.services.deregister(service, node_id)
+    }
+
+    pub(crate) fn apply_membership(&mut self, node_id: NodeId, change: Membership) {
+        match change {
+            Membership::Join(service) => {
+                self.register_service(&service, node_id);
+            }
+            Membership::Leave(service) => {
+                self.deregister_service(&service, node_id);
+            }
+        }
+    }
+}
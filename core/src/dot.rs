@@ -0,0 +1,654 @@
+use crate::components::{component_kinds, create_component};
+use crate::network::{canonical_key, EdgeConfig, Link};
+use crate::topology::{LinkSpec, NodeSpec, Topology};
+use crate::traits::NodeId;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Kind given to DOT nodes without a `kind` attribute.
+pub const DOT_DEFAULT_KIND: &str = "Server";
+
+/// Why a DOT document could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotError {
+    /// 1-based line the problem was found on
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DotError {}
+
+fn error<T>(line: usize, message: impl Into<String>) -> Result<T, DotError> {
+    Err(DotError {
+        line,
+        message: message.into(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Semi,
+    Comma,
+    Colon,
+    Edge,
+}
+
+fn tokenize(dot: &str) -> Result<Vec<(Token, usize)>, DotError> {
+    let chars: Vec<char> = dot.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line) = (0, 1);
+    let mut line_start = true;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // `#` lines are preprocessor output, skipped like comments
+        if (c == '#' && line_start) || (c == '/' && chars.get(i + 1) == Some(&'/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        line_start = false;
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                line += usize::from(chars[i] == '\n');
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        let token_line = line;
+        let token = match c {
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '=' => Token::Equals,
+            ';' => Token::Semi,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '-' if matches!(chars.get(i + 1), Some('>') | Some('-')) => {
+                i += 1;
+                Token::Edge
+            }
+            '"' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return error(token_line, "unterminated string"),
+                        Some('"') => break,
+                        Some('\\') if chars.get(i + 1) == Some(&'"') => {
+                            text.push('"');
+                            i += 1;
+                        }
+                        // A backslash-newline continues the string
+                        Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                            line += 1;
+                            i += 1;
+                        }
+                        Some(&ch) => {
+                            line += usize::from(ch == '\n');
+                            text.push(ch);
+                        }
+                    }
+                    i += 1;
+                }
+                Token::Id(text)
+            }
+            '<' => {
+                let (mut depth, start) = (0, i);
+                loop {
+                    match chars.get(i) {
+                        None => return error(token_line, "unterminated HTML string"),
+                        Some('<') => depth += 1,
+                        Some('>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some(&ch) => line += usize::from(ch == '\n'),
+                    }
+                    i += 1;
+                }
+                Token::Id(chars[start + 1..i].iter().collect())
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let start = i;
+                while i + 1 < chars.len()
+                    && (chars[i + 1].is_alphanumeric() || matches!(chars[i + 1], '_' | '.'))
+                {
+                    i += 1;
+                }
+                Token::Id(chars[start..=i].iter().collect())
+            }
+            c => return error(token_line, format!("unexpected character '{}'", c)),
+        };
+        tokens.push((token, token_line));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+type Attrs = Vec<(String, String)>;
+
+struct DotNode {
+    name: String,
+    attrs: Attrs,
+    line: usize,
+}
+
+struct DotEdge {
+    from: usize,
+    to: usize,
+    attrs: Attrs,
+    line: usize,
+}
+
+/// Attributes applied to nodes and edges created further on in a scope
+/// (`node [...]` and `edge [...]` statements).
+#[derive(Clone, Default)]
+struct Defaults {
+    node: Attrs,
+    edge: Attrs,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    nodes: Vec<DotNode>,
+    index: HashMap<String, usize>,
+    edges: Vec<DotEdge>,
+    /// Every node operand parsed so far, to collect subgraph members
+    mentioned: Vec<usize>,
+}
+
+fn is_keyword(token: Option<&Token>, keyword: &str) -> bool {
+    matches!(token, Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        self.pos += usize::from(found);
+        found
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), DotError> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            error(self.line(), format!("expected {}", what))
+        }
+    }
+
+    fn id(&mut self, what: &str) -> Result<String, DotError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Ok(id)
+            }
+            _ => error(self.line(), format!("expected {}", what)),
+        }
+    }
+
+    fn graph(&mut self) -> Result<(), DotError> {
+        if is_keyword(self.peek(), "strict") {
+            self.pos += 1;
+        }
+        if !(is_keyword(self.peek(), "digraph") || is_keyword(self.peek(), "graph")) {
+            return error(self.line(), "expected 'digraph' or 'graph'");
+        }
+        self.pos += 1;
+        if matches!(self.peek(), Some(Token::Id(_))) {
+            self.pos += 1;
+        }
+        self.expect(Token::LBrace, "'{'")?;
+        self.statements(&mut Defaults::default())?;
+        self.expect(Token::RBrace, "'}'")?;
+        if self.pos < self.tokens.len() {
+            return error(self.line(), "unexpected input after the graph");
+        }
+        Ok(())
+    }
+
+    /// Statements up to the closing brace of the current scope.
+    fn statements(&mut self, defaults: &mut Defaults) -> Result<(), DotError> {
+        while !matches!(self.peek(), None | Some(Token::RBrace)) {
+            self.statement(defaults)?;
+            while self.eat(&Token::Semi) {}
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, defaults: &mut Defaults) -> Result<(), DotError> {
+        let next_is_list = matches!(self.tokens.get(self.pos + 1), Some((Token::LBracket, _)));
+        if next_is_list && is_keyword(self.peek(), "node") {
+            self.pos += 1;
+            defaults.node.extend(self.attr_lists()?);
+            return Ok(());
+        }
+        if next_is_list && is_keyword(self.peek(), "edge") {
+            self.pos += 1;
+            defaults.edge.extend(self.attr_lists()?);
+            return Ok(());
+        }
+        if next_is_list && is_keyword(self.peek(), "graph") {
+            self.pos += 1;
+            self.attr_lists()?;
+            return Ok(());
+        }
+        if matches!(self.tokens.get(self.pos + 1), Some((Token::Equals, _))) {
+            // Graph attribute such as `rankdir=LR`
+            self.pos += 2;
+            self.id("a value")?;
+            return Ok(());
+        }
+
+        let line = self.line();
+        let mut operands = vec![self.operand(defaults)?];
+        while self.eat(&Token::Edge) {
+            operands.push(self.operand(defaults)?);
+        }
+        let attrs = if self.peek() == Some(&Token::LBracket) {
+            self.attr_lists()?
+        } else {
+            Vec::new()
+        };
+        if operands.len() == 1 {
+            // A node statement; attributes of a subgraph operand are dropped
+            if let [node] = operands[0][..] {
+                self.nodes[node].attrs.extend(attrs);
+            }
+            return Ok(());
+        }
+        for pair in operands.windows(2) {
+            for &from in &pair[0] {
+                for &to in &pair[1] {
+                    let mut edge_attrs = defaults.edge.clone();
+                    edge_attrs.extend(attrs.iter().cloned());
+                    self.edges.push(DotEdge {
+                        from,
+                        to,
+                        attrs: edge_attrs,
+                        line,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A node id or a subgraph, as the indices of the nodes it names.
+    fn operand(&mut self, defaults: &Defaults) -> Result<Vec<usize>, DotError> {
+        if is_keyword(self.peek(), "subgraph") || self.peek() == Some(&Token::LBrace) {
+            if is_keyword(self.peek(), "subgraph") {
+                self.pos += 1;
+                if matches!(self.peek(), Some(Token::Id(_))) {
+                    self.pos += 1;
+                }
+            }
+            self.expect(Token::LBrace, "'{'")?;
+            let first = self.mentioned.len();
+            self.statements(&mut defaults.clone())?;
+            self.expect(Token::RBrace, "'}'")?;
+            // Members are the nodes named anywhere inside the braces
+            let mut members = self.mentioned[first..].to_vec();
+            members.sort_unstable();
+            members.dedup();
+            return Ok(members);
+        }
+        let line = self.line();
+        let name = self.id("a node name")?;
+        // Ports (`node:port:compass`) do not matter to the simulation
+        while self.eat(&Token::Colon) {
+            self.id("a port")?;
+        }
+        let node = *self.index.entry(name.clone()).or_insert_with(|| {
+            self.nodes.push(DotNode {
+                name,
+                attrs: defaults.node.clone(),
+                line,
+            });
+            self.nodes.len() - 1
+        });
+        self.mentioned.push(node);
+        Ok(vec![node])
+    }
+
+    fn attr_lists(&mut self) -> Result<Attrs, DotError> {
+        let mut attrs = Vec::new();
+        while self.eat(&Token::LBracket) {
+            while !self.eat(&Token::RBracket) {
+                let key = self.id("an attribute name")?;
+                self.expect(Token::Equals, "'='")?;
+                let value = self.id("an attribute value")?;
+                attrs.push((key, value));
+                if !self.eat(&Token::Comma) {
+                    self.eat(&Token::Semi);
+                }
+            }
+        }
+        Ok(attrs)
+    }
+}
+
+/// The kind registered under `name`, ignoring case.
+fn resolve_kind(name: &str) -> Option<&'static str> {
+    component_kinds()
+        .into_iter()
+        .find(|k| k.eq_ignore_ascii_case(name))
+}
+
+/// `value` read with the JSON type of `default`: strings stay strings,
+/// anything else is parsed as JSON and must match the default's type.
+fn typed(value: &str, default: &Value) -> Option<Value> {
+    if default.is_string() {
+        return Some(Value::String(value.to_string()));
+    }
+    let parsed = serde_json::from_str::<Value>(value);
+    if default.is_null() {
+        // Unset optional field: take JSON if it is JSON, else the text
+        return Some(parsed.unwrap_or_else(|_| Value::String(value.to_string())));
+    }
+    let parsed = parsed.ok()?;
+    let fits = match default {
+        Value::Bool(_) => parsed.is_boolean(),
+        Value::Number(n) if n.is_u64() => parsed.is_u64(),
+        Value::Number(n) if n.is_i64() => parsed.is_i64(),
+        Value::Number(_) => parsed.is_number(),
+        Value::Array(_) => parsed.is_array(),
+        Value::Object(_) => parsed.is_object(),
+        _ => true,
+    };
+    fits.then_some(parsed)
+}
+
+/// Config of a freshly created `kind`; `Null` for unknown kinds.
+pub(crate) fn default_config(kind: &str) -> Value {
+    create_component(kind, Value::Null)
+        .map(|c| c.encode_config())
+        .unwrap_or(Value::Null)
+}
+
+/// `kind`'s default config with the attributes naming its fields laid
+/// over it. Attributes that are not config fields are left alone, since
+/// diagrams carry presentation attributes (`shape`, `color`, ...) too.
+fn node_config(node: &DotNode, kind: &str) -> Result<Value, DotError> {
+    let defaults = default_config(kind);
+    let mut config = defaults.clone();
+    for (key, value) in &node.attrs {
+        let Some(default) = defaults.get(key) else {
+            continue;
+        };
+        let Some(value) = typed(value, default) else {
+            return error(
+                node.line,
+                format!("'{}': {}={} is not a valid value", node.name, key, value),
+            );
+        };
+        config[key] = value;
+    }
+    // A config that fails to deserialize silently falls back to defaults
+    let accepted = create_component(kind, config.clone())
+        .map(|c| c.encode_config())
+        .unwrap_or(Value::Null);
+    if config != defaults && accepted == defaults {
+        return error(
+            node.line,
+            format!(
+                "'{}': the attributes are not a valid {} config",
+                node.name, kind
+            ),
+        );
+    }
+    Ok(config)
+}
+
+/// Overrides `latency_us`, `jitter_us` and `packet_loss_rate` of both
+/// directions of `link` from edge attributes.
+fn apply_link_attrs(link: &mut Link, edge: &DotEdge) -> Result<bool, DotError> {
+    let mut config = serde_json::to_value(link.min_to_max).unwrap_or_default();
+    let mut changed = false;
+    for (key, value) in &edge.attrs {
+        let Some(default) = config.get(key).cloned() else {
+            continue;
+        };
+        let Some(value) = typed(value, &default) else {
+            return error(edge.line, format!("{}={} is not a valid value", key, value));
+        };
+        config[key] = value;
+        changed = true;
+    }
+    let edge_config: EdgeConfig = serde_json::from_value(config).map_err(|e| DotError {
+        line: edge.line,
+        message: e.to_string(),
+    })?;
+    link.min_to_max = edge_config;
+    link.max_to_min = edge_config;
+    Ok(changed)
+}
+
+impl Topology {
+    /// Imports an architecture diagram written in Graphviz DOT.
+    ///
+    /// Each node becomes a component of the kind named by its `kind`
+    /// attribute (case-insensitive, `Server` if absent), called by its
+    /// `name`, `label` or DOT id, with any attributes matching fields of
+    /// that kind's config (`arrival_rate`, `service_time`, ...) overriding
+    /// the defaults. `services="api,web"` registers it under services, as
+    /// does `service` on kinds without a config field of that name. Each edge
+    /// `a -> b` connects `a` to `b`; `latency_us`, `jitter_us` and
+    /// `packet_loss_rate` on an edge set its link. Other attributes are
+    /// presentation and ignored.
+    ///
+    /// ```
+    /// use slay_core::Topology;
+    ///
+    /// let topology = Topology::from_dot(r#"
+    ///     digraph shop {
+    ///         web [kind=Client, arrival_rate=50];
+    ///         lb  [kind=LoadBalancer];
+    ///         node [kind=Server, service_time=20];
+    ///         web -> lb -> {api1 api2} [latency_us=2000];
+    ///     }
+    /// "#).unwrap();
+    /// assert_eq!(topology.nodes.len(), 4);
+    /// ```
+    pub fn from_dot(dot: &str) -> Result<Topology, DotError> {
+        let mut parser = Parser {
+            tokens: tokenize(dot)?,
+            pos: 0,
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+            mentioned: Vec::new(),
+        };
+        parser.graph()?;
+
+        let mut topology = Topology::new();
+        for (i, node) in parser.nodes.iter().enumerate() {
+            let attr = |key: &str| {
+                node.attrs
+                    .iter()
+                    .rev()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.as_str())
+            };
+            let kind_name = attr("kind").unwrap_or(DOT_DEFAULT_KIND);
+            let Some(kind) = resolve_kind(kind_name) else {
+                return error(
+                    node.line,
+                    format!(
+                        "'{}': unknown kind '{}'; expected one of {}",
+                        node.name,
+                        kind_name,
+                        component_kinds().join(", ")
+                    ),
+                );
+            };
+            let config = node_config(node, kind)?;
+            let services = attr("services")
+                .or(attr("service").filter(|_| config.get("service").is_none()))
+                .map(|s| s.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default();
+            topology.nodes.push(NodeSpec {
+                id: i as NodeId + 1,
+                kind: kind.to_string(),
+                name: attr("name")
+                    .or(attr("label"))
+                    .unwrap_or(&node.name)
+                    .to_string(),
+                config,
+                targets: Vec::new(),
+                services,
+                cost: Default::default(),
+            });
+        }
+
+        let mut links: HashMap<(NodeId, NodeId), Link> = HashMap::new();
+        for edge in &parser.edges {
+            let (from, to) = (edge.from as NodeId + 1, edge.to as NodeId + 1);
+            let targets = &mut topology.nodes[edge.from].targets;
+            if !targets.contains(&to) {
+                targets.push(to);
+            }
+            let key = canonical_key(from, to);
+            let mut link = links.get(&key).cloned().unwrap_or_default();
+            if apply_link_attrs(&mut link, edge)? {
+                links.insert(key, link);
+            }
+        }
+        topology.links = links
+            .into_iter()
+            .map(|((a, b), link)| LinkSpec { a, b, link })
+            .collect();
+        topology.links.sort_by_key(|l| (l.a, l.b));
+        Ok(topology)
+    }
+}
+
+/// `text` as a quoted DOT string.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\\\""))
+}
+
+fn attr_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => quoted(s),
+        Value::Number(_) | Value::Bool(_) => value.to_string(),
+        _ => quoted(&value.to_string()),
+    }
+}
+
+/// Graphviz shape of a kind, where it differs from the rounded box.
+fn shape(kind: &str) -> Option<&'static str> {
+    match kind {
+        "Client" => Some("oval"),
+        "LoadBalancer" => Some("hexagon"),
+        _ => None,
+    }
+}
+
+impl Topology {
+    /// The topology as a Graphviz digraph for design docs. Labels show
+    /// each node's key settings and each non-default link; config that
+    /// differs from the kind's defaults is kept as attributes, so
+    /// `from_dot` reads the output back.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph slay {\n    rankdir=LR;\n");
+        out.push_str("    node [shape=box, style=rounded];\n\n");
+        for node in &self.nodes {
+            let name = node.label();
+            let label = match node.summary() {
+                Some(summary) => format!("{}\\n{}", name, summary),
+                None => name.to_string(),
+            };
+            let mut attrs = vec![
+                format!("kind={}", quoted(&node.kind)),
+                format!("name={}", quoted(name)),
+                format!("label={}", quoted(&label)),
+            ];
+            if let Some(shape) = shape(&node.kind) {
+                attrs.push(format!("shape={}", shape));
+            }
+            if !node.services.is_empty() {
+                attrs.push(format!("services={}", quoted(&node.services.join(","))));
+            }
+            let defaults = default_config(&node.kind);
+            for (key, value) in node.config.as_object().into_iter().flatten() {
+                if defaults.get(key) != Some(value) {
+                    attrs.push(format!("{}={}", key, attr_value(value)));
+                }
+            }
+            let _ = writeln!(out, "    n{} [{}];", node.id, attrs.join(", "));
+        }
+        out.push('\n');
+        for node in &self.nodes {
+            for &to in &node.targets {
+                let mut attrs = Vec::new();
+                let key = canonical_key(node.id, to);
+                if let Some(spec) = self.links.iter().find(|l| (l.a, l.b) == key) {
+                    let edge = spec.link.get_config(node.id, to);
+                    let default = EdgeConfig::default();
+                    if edge.latency_us != default.latency_us {
+                        attrs.push(format!("latency_us={}", edge.latency_us));
+                    }
+                    if edge.jitter_us != default.jitter_us {
+                        attrs.push(format!("jitter_us={}", edge.jitter_us));
+                    }
+                    if edge.packet_loss_rate != default.packet_loss_rate {
+                        attrs.push(format!("packet_loss_rate={}", edge.packet_loss_rate));
+                    }
+                    if !attrs.is_empty() {
+                        attrs.push(format!("label={}", quoted(&edge.summary())));
+                    }
+                }
+                let _ = if attrs.is_empty() {
+                    writeln!(out, "    n{} -> n{};", node.id, to)
+                } else {
+                    writeln!(out, "    n{} -> n{} [{}];", node.id, to, attrs.join(", "))
+                };
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
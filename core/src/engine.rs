@@ -1,32 +1,72 @@
-use crate::network::{canonical_key, Link};
-use crate::traits::{Component, NodeId};
+use crate::analytics::WindowedHistogram;
+use crate::backpressure::DEFAULT_MAX_PENDING_EVENTS;
+use crate::brownout::{health_of, Brownout};
+use crate::cost::CostLedger;
+use crate::debugger::Debugger;
+use crate::discovery::{Membership, ServiceRegistry};
+use crate::failure::FailureReason;
+use crate::fluid::FluidState;
+use crate::metadata::Metadata;
+use crate::network::{canonical_key, EdgeCounters, Link};
+use crate::payload::message_bytes;
+use crate::profiler::Profiler;
+use crate::queue::PendingEvents;
+use crate::recorder::MacroRecorder;
+use crate::replay::EventLog;
+use crate::scenario::{partitioned_link, Scenario, ScenarioAction};
+use crate::trace::Tracer;
+use crate::traits::{Component, Health, NodeId};
+use crate::transit::Transit;
 use hdrhistogram::Histogram;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use smallvec::SmallVec;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Nodes a request has visited, oldest first. Stored inline for typical
+/// depths so forwarding a request does not allocate.
+pub type Path = SmallVec<[NodeId; 8]>;
+
+/// Commands returned by `Component::on_event`. Most events produce at most
+/// two, which are kept inline to avoid a heap allocation per event.
+pub type Commands = SmallVec<[ScheduleCmd; 2]>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EventType {
     Arrival {
         request_id: u128,
-        path: Vec<NodeId>,
+        path: Path,
         start_time: u64,
         timeout: u64,
+        /// Maximum path length before the engine drops the request
+        max_hops: u32,
+        /// Per-request data set by components along the way
+        #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+        metadata: Metadata,
     },
     ProcessComplete {
         request_id: u128,
         success: bool,
         start_time: u64,
-        path: Vec<NodeId>,
+        path: Path,
         timeout: u64,
+        max_hops: u32,
+        #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+        metadata: Metadata,
     },
     Response {
         request_id: u128,
-        path: Vec<NodeId>,
+        path: Path,
         start_time: u64,
         success: bool,
+        /// Why the request failed; `None` on success
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        failure: Option<FailureReason>,
         timeout: u64,
+        max_hops: u32,
+        #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+        metadata: Metadata,
     },
     GenerateNext {
         generation_id: u64,
@@ -36,24 +76,39 @@ pub enum EventType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
     pub time: u64,
+    /// Monotonic insertion counter used to break ties between events
+    /// scheduled for the same virtual time (FIFO order).
+    pub seq: u64,
     pub node_id: NodeId,
     pub event_type: EventType,
 }
 
+impl Event {
+    /// Full structural comparison. `==` only compares the ordering key.
+    pub fn same_as(&self, other: &Self) -> bool {
+        self.time == other.time
+            && self.seq == other.seq
+            && self.node_id == other.node_id
+            && self.event_type == other.event_type
+    }
+}
+
 impl PartialEq for Event {
     fn eq(&self, other: &Self) -> bool {
-        self.time == other.time
+        self.time == other.time && self.seq == other.seq
     }
 }
 impl Eq for Event {}
 impl PartialOrd for Event {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.time.partial_cmp(&other.time)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Event {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.time.cmp(&other.time)
+        self.time
+            .cmp(&other.time)
+            .then_with(|| self.seq.cmp(&other.seq))
     }
 }
 
@@ -63,22 +118,170 @@ pub struct ScheduleCmd {
     pub event_type: EventType,
 }
 
+/// Identifies a scheduled event so it can be cancelled before it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EventHandle(pub u64);
+
 pub trait SystemInspector {
     fn is_node_healthy(&self, id: NodeId) -> bool;
+
+    /// Finer-grained health of a node, telling slow and flaky apart from
+    /// fully working.
+    fn node_health(&self, id: NodeId) -> Health {
+        if self.is_node_healthy(id) {
+            Health::Healthy
+        } else {
+            Health::Down
+        }
+    }
+
+    /// Requests the node is currently holding (processing or queued).
+    fn active_requests(&self, _id: NodeId) -> u32 {
+        0
+    }
+
+    /// Requests waiting in the node's queue.
+    fn queue_depth(&self, _id: NodeId) -> u32 {
+        0
+    }
+
+    /// Smoothed time recent requests spent inside the node, if it tracks one.
+    fn recent_latency(&self, _id: NodeId) -> Option<u64> {
+        None
+    }
+
+    /// Current members of a logical service, in id order.
+    fn service_members(&self, service: &str) -> Vec<NodeId> {
+        self.service_members_ref(service).to_vec()
+    }
+
+    /// Like `service_members`, without copying them.
+    fn service_members_ref(&self, _service: &str) -> &[NodeId] {
+        &[]
+    }
+
+    /// Adds the node handling the current event to `service`. Takes effect
+    /// once the event has been handled.
+    fn register_service(&self, _service: &str) {}
+
+    /// Removes the node handling the current event from `service`.
+    fn deregister_service(&self, _service: &str) {}
+
+    /// Cancels a pending event. Takes effect once the current event has
+    /// been handled; cancelling an event that already fired is a no-op.
+    fn cancel(&self, _handle: EventHandle) {}
+}
+
+/// Load of one node as seen by `SystemInspector`, captured after each of
+/// its events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeLoad {
+    pub active_requests: u32,
+    pub queue_depth: u32,
+    pub recent_latency_us: Option<u64>,
+}
+
+impl NodeLoad {
+    fn of(comp: &dyn Component) -> Self {
+        Self {
+            active_requests: comp.active_requests(),
+            queue_depth: comp.queue_depth(),
+            recent_latency_us: comp.recent_latency_us(),
+        }
+    }
 }
 
 pub struct Simulation {
     pub time: u64,
     pub components: HashMap<NodeId, Box<dyn Component>>,
-    pub events: BinaryHeap<Reverse<Event>>,
+    pub events: PendingEvents,
+    /// Sequence numbers of cancelled events still sitting in `events`
+    pub cancelled: HashSet<u64>,
     pub success_count: u64,
     pub failure_count: u64,
+    /// Requests dropped because their path exceeded `max_hops` (routing loops)
+    pub hop_limit_exceeded: u64,
     pub latencies: VecDeque<(u64, u64)>,
+    /// Requests started within this long after `stats_epoch_us` are not
+    /// counted, so steady-state metrics skip the empty-system transient
+    pub warmup_us: u64,
+    /// Virtual time at which statistics were last (re)started
+    pub stats_epoch_us: u64,
+    /// All-time latency histogram of successful requests
     pub histogram: Histogram<u64>,
+    /// Recent latencies bucketed by time, used for windowed percentiles
+    pub windowed_histogram: WindowedHistogram,
     pub links: HashMap<(NodeId, NodeId), Link>,
+    /// Traffic per directed edge `(from, to)` since the last stats reset
+    pub edge_stats: HashMap<(NodeId, NodeId), EdgeCounters>,
+    /// Health of every node as seen by `SystemInspector`. Kept in sync by
+    /// `add_component`, `remove_node` and `set_healthy`; call
+    /// `refresh_node` after toggling a component directly.
     pub health_buffer: HashMap<NodeId, bool>,
+    /// Load of every node as seen by `SystemInspector`, maintained alongside
+    /// `health_buffer`
+    pub load_buffer: HashMap<NodeId, NodeLoad>,
+    /// Logical service names and their member nodes
+    pub services: ServiceRegistry,
+    /// `failure_count` split by reason
+    pub failure_reasons: BTreeMap<FailureReason, u64>,
+    /// Per-node cost rates and what they have accrued
+    pub costs: CostLedger,
     pub rng: StdRng,
     pub seed: u64,
+    /// Sequence number assigned to the next scheduled event
+    pub next_seq: u64,
+    /// Scripted actions executed as virtual time advances
+    pub scenario: Scenario,
+    /// Index of the next scenario step to fire
+    pub scenario_cursor: usize,
+    /// Manual actions being captured (see `start_macro`)
+    pub macro_recorder: Option<MacroRecorder>,
+    /// Original links saved while a `Partition` is active
+    pub partitioned_links: HashMap<(NodeId, NodeId), Link>,
+    /// Nodes running slow without being down (see `inject_latency`)
+    pub brownouts: HashMap<NodeId, Brownout>,
+    /// Optional log of every processed event (see `record_events`)
+    pub event_log: Option<EventLog>,
+    /// Breakpoints and pause state used by `step_checked`
+    pub debugger: Debugger,
+    /// Per-hop span collection for sampled requests (see `enable_tracing`)
+    pub tracer: Option<Tracer>,
+    /// Messages crossing links, keyed by the sequence number of the event
+    /// that delivers them (see `in_transit`)
+    pub transit: BTreeMap<u64, Transit>,
+    /// Bumped whenever `apply_action` changes a component's config, so
+    /// views holding a copy of one know to read it again
+    pub config_revision: u64,
+    /// Fluid approximation of high-rate clients (see `enable_fluid`)
+    pub fluid: Option<FluidState>,
+    /// Wall-clock cost of processing events (see `enable_profiling`)
+    pub profiler: Option<Profiler>,
+    /// Pending events at which requests from generator events are shed
+    /// rather than scheduled, so runaway load cannot exhaust memory
+    pub max_pending_events: usize,
+    /// Requests shed at `max_pending_events` since the last stats reset
+    pub shed_requests: u64,
+    /// Virtual time a request was last shed (see `is_shedding`)
+    pub last_shed_us: Option<u64>,
+    /// When each link direction is done sending the messages handed to it
+    /// (see `EdgeConfig::bandwidth_mbps`)
+    pub link_busy_until: HashMap<(NodeId, NodeId), u64>,
+}
+
+/// Seed of a component's private RNG stream.
+///
+/// Derived from the node's identity rather than from insertion order, so
+/// adding or removing one node leaves every other node's randomness intact.
+pub fn stream_seed(seed: u64, id: NodeId, kind: &str) -> u64 {
+    // FNV-1a over the kind, then splitmix64 finalisation of the mix
+    let kind_hash = kind.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    let mut z = seed ^ (id as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ kind_hash.rotate_left(32);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
 impl Simulation {
@@ -86,30 +289,173 @@ impl Simulation {
         Self {
             time: 0,
             components: HashMap::new(),
-            events: BinaryHeap::new(),
+            events: PendingEvents::default(),
+            cancelled: HashSet::new(),
             success_count: 0,
             failure_count: 0,
+            hop_limit_exceeded: 0,
             latencies: VecDeque::new(),
+            warmup_us: 0,
+            stats_epoch_us: 0,
             histogram: Histogram::<u64>::new_with_bounds(1, 60_000_000, 3).unwrap(),
+            windowed_histogram: WindowedHistogram::new(500_000, 60_000_000),
             links: HashMap::new(),
+            edge_stats: HashMap::new(),
             health_buffer: HashMap::new(),
+            load_buffer: HashMap::new(),
+            services: ServiceRegistry::default(),
+            failure_reasons: BTreeMap::new(),
+            costs: CostLedger::default(),
             rng: StdRng::seed_from_u64(seed),
             seed,
+            next_seq: 0,
+            scenario: Scenario::default(),
+            scenario_cursor: 0,
+            macro_recorder: None,
+            partitioned_links: HashMap::new(),
+            brownouts: HashMap::new(),
+            event_log: None,
+            debugger: Debugger::default(),
+            tracer: None,
+            transit: BTreeMap::new(),
+            config_revision: 0,
+            fluid: None,
+            profiler: None,
+            max_pending_events: DEFAULT_MAX_PENDING_EVENTS,
+            shed_requests: 0,
+            last_shed_us: None,
+            link_busy_until: HashMap::new(),
         }
     }
 
     pub fn add_component(&mut self, id: NodeId, mut component: Box<dyn Component>) {
-        let component_seed = self.rng.next_u64();
-        component.set_seed(component_seed);
+        component.set_seed(stream_seed(self.seed, id, component.kind()));
+        self.health_buffer.insert(id, component.is_healthy());
+        self.load_buffer
+            .insert(id, NodeLoad::of(component.as_ref()));
         self.components.insert(id, component);
+        self.cost_node_added(id);
+    }
+
+    /// Marks a node healthy or unhealthy.
+    pub fn set_healthy(&mut self, id: NodeId, healthy: bool) {
+        if let Some(comp) = self.components.get_mut(&id) {
+            comp.set_healthy(healthy);
+        }
+        self.refresh_node(id);
+    }
+
+    /// Re-reads one node's health and load into the inspector buffers.
+    pub fn refresh_node(&mut self, id: NodeId) {
+        match self.components.get(&id) {
+            Some(comp) => {
+                self.health_buffer.insert(id, comp.is_healthy());
+                self.load_buffer.insert(id, NodeLoad::of(comp.as_ref()));
+            }
+            None => {
+                self.health_buffer.remove(&id);
+                self.load_buffer.remove(&id);
+            }
+        }
     }
 
+    /// Removes a node and everything addressed to it.
+    ///
+    /// Pending events for the node are cancelled; requests it held (in flight
+    /// or queued) are failed back to their callers so client-side accounting
+    /// stays consistent.
     pub fn remove_node(&mut self, id: NodeId) {
-        self.components.remove(&id);
+        let mut pending = Vec::new();
+        if let Some(mut comp) = self.components.remove(&id) {
+            pending = comp.abort_pending(id);
+        }
+        self.health_buffer.remove(&id);
+        self.load_buffer.remove(&id);
+        self.brownouts.remove(&id);
+        self.services.remove_node(id);
+        self.cost_node_removed(id);
         for comp in self.components.values_mut() {
             comp.remove_target(id);
         }
         self.links.retain(|(min, max), _| *min != id && *max != id);
+        self.partitioned_links
+            .retain(|(min, max), _| *min != id && *max != id);
+        self.edge_stats
+            .retain(|(from, to), _| *from != id && *to != id);
+        self.link_busy_until
+            .retain(|(from, to), _| *from != id && *to != id);
+
+        let orphaned = self.events.remove_where(|e| e.node_id == id);
+        for event in orphaned {
+            self.transit_ended(event.seq);
+            if !self.cancelled.remove(&event.seq) {
+                self.fail_orphaned(event);
+            }
+        }
+        for cmd in pending {
+            self.schedule(self.time + cmd.delay, cmd.node_id, cmd.event_type);
+        }
+    }
+
+    /// Turns an event addressed to a missing node into a failed response
+    /// for the nearest upstream caller. Generator ticks are simply dropped.
+    fn fail_orphaned(&mut self, event: Event) {
+        let (request_id, mut path, start_time, timeout, max_hops, metadata) = match event.event_type
+        {
+            EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            }
+            | EventType::ProcessComplete {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+                ..
+            } => (request_id, path, start_time, timeout, max_hops, metadata),
+            EventType::Response {
+                request_id,
+                mut path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+                ..
+            } => {
+                // The missing node is the tail of the return path; skip it
+                if path.last() == Some(&event.node_id) {
+                    path.pop();
+                }
+                (request_id, path, start_time, timeout, max_hops, metadata)
+            }
+            EventType::GenerateNext { .. } => return,
+        };
+        while let Some(&prev) = path.last() {
+            if self.components.contains_key(&prev) {
+                self.schedule(
+                    self.time,
+                    prev,
+                    EventType::Response {
+                        request_id,
+                        path,
+                        start_time,
+                        success: false,
+                        failure: Some(FailureReason::Unavailable),
+                        timeout,
+                        max_hops,
+                        metadata,
+                    },
+                );
+                return;
+            }
+            path.pop();
+        }
     }
 
     pub fn connect_node(&mut self, from: NodeId, to: NodeId, link: Link) {
@@ -121,35 +467,261 @@ impl Simulation {
         }
     }
 
-    pub fn schedule(&mut self, time: u64, node_id: NodeId, event_type: EventType) {
-        self.events.push(Reverse(Event {
+    pub fn schedule(&mut self, time: u64, node_id: NodeId, event_type: EventType) -> EventHandle {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.events.push(Event {
             time,
+            seq,
             node_id,
             event_type,
-        }));
+        });
+        EventHandle(seq)
+    }
+
+    /// Moves the pending events into `queue` and schedules through it from
+    /// then on, e.g. to compare queue structures on the same run.
+    pub fn set_event_queue(&mut self, mut queue: PendingEvents) {
+        for event in self.events.remove_where(|_| true) {
+            queue.push(event);
+        }
+        self.events = queue;
+    }
+
+    /// Cancels a pending event. The event stays in the queue as a tombstone
+    /// and is discarded when it reaches the front. Returns `false` if the
+    /// handle was never issued or is already cancelled.
+    pub fn cancel(&mut self, handle: EventHandle) -> bool {
+        if handle.0 >= self.next_seq || !self.cancelled.insert(handle.0) {
+            return false;
+        }
+        // Handles of events that already fired never reach the front;
+        // prune them once tombstones outnumber queued events.
+        if self.cancelled.len() > self.events.len() {
+            let live: HashSet<u64> = self.events.iter().map(|e| e.seq).collect();
+            self.cancelled.retain(|seq| live.contains(seq));
+        }
+        true
+    }
+
+    /// Drops cancelled events from the front of the queue.
+    pub(crate) fn skip_cancelled(&mut self) {
+        if self.cancelled.is_empty() {
+            return;
+        }
+        while let Some(seq) = self.events.peek().map(|e| e.seq) {
+            if !self.cancelled.remove(&seq) {
+                break;
+            }
+            self.events.pop();
+            self.transit_ended(seq);
+        }
+    }
+
+    /// Installs a scenario. Steps already in the past fire on the next `step`.
+    pub fn load_scenario(&mut self, scenario: Scenario) {
+        self.scenario = scenario;
+        self.scenario_cursor = 0;
+    }
+
+    pub fn clear_scenario(&mut self) {
+        self.scenario = Scenario::default();
+        self.scenario_cursor = 0;
+    }
+
+    /// Schedules an action on the loaded scenario. One timed before the
+    /// steps already fired counts as passed and never fires.
+    pub fn add_scenario_step(&mut self, at_us: u64, action: ScenarioAction) -> usize {
+        let index = self.scenario.insert(at_us, action);
+        if index < self.scenario_cursor {
+            self.scenario_cursor += 1;
+        }
+        index
+    }
+
+    /// Schedules node `node_id` to go down, or come back up when `healthy`,
+    /// at `at_us`. The change is a step of the loaded scenario, so it shows
+    /// on the timeline and can be disabled like any other.
+    pub fn schedule_health(&mut self, node_id: NodeId, at_us: u64, healthy: bool) -> usize {
+        let action = if healthy {
+            ScenarioAction::Revive { node_id }
+        } else {
+            ScenarioAction::Kill { node_id }
+        };
+        self.add_scenario_step(at_us, action)
+    }
+
+    /// Takes `node_id` down at `down_at_us` and brings it back at
+    /// `up_at_us`.
+    pub fn schedule_outage(&mut self, node_id: NodeId, down_at_us: u64, up_at_us: u64) {
+        self.schedule_health(node_id, down_at_us, false);
+        self.schedule_health(node_id, up_at_us.max(down_at_us), true);
+    }
+
+    /// Removes a step from the loaded scenario, fired or not.
+    pub fn remove_scenario_step(&mut self, index: usize) {
+        if index >= self.scenario.steps.len() {
+            return;
+        }
+        self.scenario.steps.remove(index);
+        if index < self.scenario_cursor {
+            self.scenario_cursor -= 1;
+        }
+    }
+
+    /// Fires every pending enabled scenario step scheduled at or before
+    /// `until`; disabled ones are passed over.
+    pub fn apply_scenario_until(&mut self, until: u64) {
+        while let Some(step) = self.scenario.steps.get(self.scenario_cursor) {
+            if step.at_us > until {
+                break;
+            }
+            let action = step.enabled.then(|| step.action.clone());
+            self.scenario_cursor += 1;
+            if let Some(action) = action {
+                self.apply_action(action);
+            }
+        }
+    }
+
+    pub fn apply_action(&mut self, action: ScenarioAction) {
+        match action {
+            ScenarioAction::Kill { node_id } => {
+                self.set_healthy(node_id, false);
+            }
+            ScenarioAction::Revive { node_id } => {
+                if let Some(comp) = self.components.get_mut(&node_id) {
+                    if !comp.is_healthy() {
+                        comp.set_healthy(true);
+                        let current_conf = comp.encode_config();
+                        let cmds = comp.apply_config(current_conf, node_id);
+                        self.refresh_node(node_id);
+                        for cmd in cmds {
+                            self.schedule(self.time + cmd.delay, cmd.node_id, cmd.event_type);
+                        }
+                    }
+                }
+            }
+            ScenarioAction::SetConfig { node_id, config } => {
+                if let Some(comp) = self.components.get_mut(&node_id) {
+                    let mut merged = comp.encode_config();
+                    if let (Some(dst), Some(src)) = (merged.as_object_mut(), config.as_object()) {
+                        for (k, v) in src {
+                            dst.insert(k.clone(), v.clone());
+                        }
+                        // Invalidate the running generator so it is not duplicated
+                        if let Some(gen_id) = dst.get_mut("generation_id") {
+                            let current = gen_id.as_u64().unwrap_or(1);
+                            *gen_id = serde_json::Value::from(current + 1);
+                        }
+                    }
+                    let cmds = comp.apply_config(merged, node_id);
+                    self.config_revision += 1;
+                    for cmd in cmds {
+                        self.schedule(self.time + cmd.delay, cmd.node_id, cmd.event_type);
+                    }
+                }
+            }
+            ScenarioAction::SetEdge { from, to, edge } => {
+                let link = self.links.entry(canonical_key(from, to)).or_default();
+                *link.get_config_mut(from, to) = edge;
+            }
+            ScenarioAction::InjectLatency {
+                node_id,
+                slowdown,
+                extra_latency_us,
+                error_rate,
+                duration_us,
+            } => {
+                let until_us = duration_us.map(|d| self.time + d);
+                self.inject_latency(
+                    node_id,
+                    Brownout {
+                        slowdown,
+                        extra_latency_us,
+                        error_rate,
+                        until_us,
+                    },
+                );
+            }
+            ScenarioAction::ClearLatency { node_id } => self.clear_latency(node_id),
+            ScenarioAction::SetHealth { node_id, health } => self.set_health(node_id, health),
+            ScenarioAction::Partition { a, b } => {
+                let key = canonical_key(a, b);
+                if !self.partitioned_links.contains_key(&key) {
+                    let original = self.links.get(&key).cloned().unwrap_or_default();
+                    self.partitioned_links.insert(key, original);
+                    self.links.insert(key, partitioned_link());
+                }
+            }
+            ScenarioAction::Heal { a, b } => {
+                let key = canonical_key(a, b);
+                if let Some(original) = self.partitioned_links.remove(&key) {
+                    self.links.insert(key, original);
+                }
+            }
+        }
+    }
+
+    /// Starts recording processed events, keeping at most `max_events`.
+    pub fn record_events(&mut self, max_events: usize) {
+        self.event_log = Some(EventLog::new(max_events));
+    }
+
+    /// Starts collecting spans for one in every `sample_every` requests.
+    pub fn enable_tracing(&mut self, sample_every: u64, max_traces: usize) {
+        self.tracer = Some(Tracer::new(sample_every, max_traces));
     }
 
     pub fn step(&mut self) -> bool {
-        if let Some(Reverse(event)) = self.events.pop() {
+        let Some(started_ns) = self.profiler.as_ref().map(Profiler::now) else {
+            return self.process_next();
+        };
+        let processed = self.process_next();
+        if processed {
+            let pending = self.events.len();
+            if let Some(profiler) = &mut self.profiler {
+                profiler.end_step(started_ns, pending);
+            }
+        }
+        processed
+    }
+
+    fn process_next(&mut self) -> bool {
+        self.skip_cancelled();
+        if let Some(next_time) = self.events.peek().map(|e| e.time) {
+            self.apply_scenario_until(next_time);
+            self.advance_fluid_until(next_time);
+        }
+        if let Some(event) = self.events.pop() {
             self.time = event.time;
+            self.transit_ended(event.seq);
+            if self.park_fluid_generator(&event) {
+                return true;
+            }
+            if let Some(log) = &mut self.event_log {
+                log.record(&event);
+            }
             let node_id = event.node_id;
 
             if let EventType::Response {
                 success,
+                failure,
                 start_time,
                 timeout,
                 path,
                 ..
             } = &event.event_type
             {
-                if path.len() == 1 {
+                if path.len() == 1 && *start_time >= self.warmup_end() {
                     let total_time_us = self.time.saturating_sub(*start_time);
                     if total_time_us > *timeout {
-                        self.failure_count += 1;
+                        self.record_failure(FailureReason::Timeout);
                     } else if *success {
                         self.success_count += 1;
                         self.latencies.push_back((self.time, total_time_us));
                         self.histogram.record(total_time_us).ok();
+                        self.windowed_histogram.record(self.time, total_time_us);
 
                         let cutoff = self.time.saturating_sub(60_000_000);
                         while let Some((t, _)) = self.latencies.front() {
@@ -160,54 +732,183 @@ impl Simulation {
                             }
                         }
                     } else {
-                        self.failure_count += 1;
+                        self.record_failure(failure.unwrap_or(FailureReason::BackendError));
+                    }
+                }
+            }
+
+            if let Some(tracer) = &mut self.tracer {
+                tracer.observe_event(&event);
+            }
+
+            if let EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            } = &event.event_type
+            {
+                if path.len() > *max_hops as usize {
+                    // Bounce a failure to the sender instead of delivering the
+                    // request, so loops terminate and upstream state is released.
+                    if *start_time >= self.warmup_end() {
+                        self.hop_limit_exceeded += 1;
+                    }
+                    if let Some(&prev) = path.last() {
+                        self.schedule(
+                            self.time,
+                            prev,
+                            EventType::Response {
+                                request_id: *request_id,
+                                path: path.clone(),
+                                start_time: *start_time,
+                                success: false,
+                                failure: Some(FailureReason::HopLimit),
+                                timeout: *timeout,
+                                max_hops: *max_hops,
+                                metadata: metadata.clone(),
+                            },
+                        );
                     }
+                    return true;
                 }
             }
 
-            self.health_buffer.clear();
-            for (id, comp) in &self.components {
-                self.health_buffer.insert(*id, comp.is_healthy());
+            if !self.components.contains_key(&node_id) {
+                self.fail_orphaned(event);
+                return true;
+            }
+
+            if let EventType::Arrival { .. } = event.event_type {
+                self.cost_request(node_id);
             }
 
             if let Some(comp) = self.components.get_mut(&node_id) {
-                let cmds = comp.on_event(
-                    event,
-                    &StaticInspector {
-                        health_map: &self.health_buffer,
-                    },
-                );
-                for cmd in cmds {
+                let inspector = StaticInspector {
+                    health_map: &self.health_buffer,
+                    brownouts: &self.brownouts,
+                    time: self.time,
+                    load_map: &self.load_buffer,
+                    services: &self.services,
+                    cancellations: RefCell::new(Vec::new()),
+                    memberships: RefCell::new(Vec::new()),
+                };
+                let generated = matches!(event.event_type, EventType::GenerateNext { .. });
+                let timer = self.profiler.as_ref().map(Profiler::start_handler);
+                let cmds = comp.on_event(event, &inspector);
+                if let (Some(profiler), Some(timer)) = (&mut self.profiler, timer) {
+                    profiler.end_handler(node_id, timer);
+                }
+                let cancellations = inspector.cancellations.into_inner();
+                let memberships = inspector.memberships.into_inner();
+                // A component may change its own health while handling an event
+                self.health_buffer.insert(node_id, comp.is_healthy());
+                self.load_buffer
+                    .insert(node_id, NodeLoad::of(comp.as_ref()));
+                for handle in cancellations {
+                    self.cancel(handle);
+                }
+                for change in memberships {
+                    self.apply_membership(node_id, change);
+                }
+                let mut handles: SmallVec<[Option<EventHandle>; 2]> = SmallVec::new();
+                for mut cmd in cmds {
+                    // Over the cap, new load is shed before anything in flight
+                    if generated
+                        && matches!(cmd.event_type, EventType::Arrival { .. })
+                        && self.shed_generated_request()
+                    {
+                        handles.push(None);
+                        continue;
+                    }
+                    self.apply_brownout(node_id, &mut cmd);
+                    if let Some(tracer) = &mut self.tracer {
+                        tracer.observe_cmd(node_id, self.time, &cmd);
+                    }
                     let mut delay = cmd.delay;
                     let mut should_schedule = true;
+                    let mut crosses_link = false;
 
                     if matches!(
                         cmd.event_type,
                         EventType::Arrival { .. } | EventType::Response { .. }
-                    ) {
-                        if cmd.node_id != node_id {
-                            let key = canonical_key(node_id, cmd.node_id);
-                            let link = self.links.entry(key).or_insert(Link::default());
-                            let edge = link.get_config(node_id, cmd.node_id);
-
-                            if edge.packet_loss_rate > 0.0
-                                && self.rng.gen::<f32>() < edge.packet_loss_rate
-                            {
-                                should_schedule = false;
-                                self.failure_count += 1;
-                            } else {
-                                let jitter = if edge.jitter_us > 0 {
-                                    self.rng.gen_range(0..=edge.jitter_us)
-                                } else {
-                                    0
-                                };
-                                delay += edge.latency_us + jitter;
+                    ) && cmd.node_id != node_id
+                    {
+                        // Losses of requests sent during warm-up aren't counted
+                        let measured = match &cmd.event_type {
+                            EventType::Arrival { start_time, .. }
+                            | EventType::Response { start_time, .. } => {
+                                *start_time >= self.warmup_end()
+                            }
+                            _ => true,
+                        };
+                        let key = canonical_key(node_id, cmd.node_id);
+                        let link = self.links.entry(key).or_default();
+                        let edge = link.get_config(node_id, cmd.node_id);
+                        let counters = self.edge_stats.entry((node_id, cmd.node_id)).or_default();
+                        let bytes = message_bytes(&cmd.event_type);
+                        counters.sent += 1;
+                        counters.bytes += bytes;
+                        if let EventType::Arrival { metadata, .. } = &cmd.event_type {
+                            counters.requests += 1;
+                            if metadata.get(crate::metadata::ATTEMPT).is_some() {
+                                counters.retries += 1;
+                            }
+                        }
+
+                        if edge.packet_loss_rate > 0.0
+                            && self.rng.gen::<f32>() < edge.packet_loss_rate
+                        {
+                            should_schedule = false;
+                            counters.dropped += 1;
+                            if measured {
+                                self.record_failure(FailureReason::PacketLoss);
+                            }
+                        } else {
+                            // A message waits for the link to finish sending
+                            // the ones handed to it earlier
+                            let transmit_us = edge.transmit_us(bytes);
+                            if transmit_us > 0 {
+                                let busy = self
+                                    .link_busy_until
+                                    .entry((node_id, cmd.node_id))
+                                    .or_default();
+                                *busy = (*busy).max(self.time + delay) + transmit_us;
+                                delay = *busy - self.time;
                             }
+                            let jitter = if edge.jitter_us > 0 {
+                                self.rng.gen_range(0..=edge.jitter_us)
+                            } else {
+                                0
+                            };
+                            delay += edge.latency_us + jitter;
+                            counters.added_latency_us += edge.latency_us + jitter;
+                            crosses_link = true;
                         }
                     }
 
-                    if should_schedule {
-                        self.schedule(self.time + delay, cmd.node_id, cmd.event_type);
+                    if !should_schedule {
+                        handles.push(None);
+                        continue;
+                    }
+                    let transit = crosses_link.then(|| Transit {
+                        from: node_id,
+                        to: cmd.node_id,
+                        sent_us: self.time + cmd.delay,
+                        arrive_us: self.time + delay,
+                        is_response: matches!(cmd.event_type, EventType::Response { .. }),
+                    });
+                    let handle = self.schedule(self.time + delay, cmd.node_id, cmd.event_type);
+                    if let Some(transit) = transit {
+                        self.transit_sent(handle.0, transit);
+                    }
+                    handles.push(Some(handle));
+                }
+                if !handles.is_empty() {
+                    if let Some(comp) = self.components.get_mut(&node_id) {
+                        comp.on_scheduled(&handles);
                     }
                 }
             }
@@ -216,26 +917,113 @@ impl Simulation {
         false
     }
 
+    /// Virtual time at which the current warm-up period ends.
+    pub fn warmup_end(&self) -> u64 {
+        self.stats_epoch_us + self.warmup_us
+    }
+
+    pub fn in_warmup(&self) -> bool {
+        self.time < self.warmup_end()
+    }
+
+    /// Clears all statistics and restarts the warm-up period.
     pub fn reset_stats(&mut self) {
+        self.stats_epoch_us = self.time;
         self.success_count = 0;
         self.failure_count = 0;
+        self.failure_reasons.clear();
+        self.hop_limit_exceeded = 0;
+        self.shed_requests = 0;
         self.latencies.clear();
         self.histogram.reset();
+        self.windowed_histogram.clear();
+        self.edge_stats.clear();
+        self.cost_reset();
     }
 
-    pub fn get_percentile(&self, p: f32, _window_us: u64) -> Option<u64> {
-        if self.histogram.len() == 0 {
-            return None;
-        }
-        Some(self.histogram.value_at_percentile(p as f64))
+    /// The component at `id` as its concrete type, or `None` if it is
+    /// missing or of a different type.
+    pub fn get_component_as<T: Component>(&self, id: NodeId) -> Option<&T> {
+        self.components.get(&id)?.downcast_ref::<T>()
+    }
+
+    pub fn get_component_as_mut<T: Component>(&mut self, id: NodeId) -> Option<&mut T> {
+        self.components.get_mut(&id)?.downcast_mut::<T>()
+    }
+
+    /// Total requests failed by components for missing their deadline.
+    pub fn deadline_exceeded_count(&self) -> u64 {
+        self.components
+            .values()
+            .map(|c| c.deadline_exceeded_count())
+            .sum()
+    }
+
+    /// Latency percentile over successful requests completed in the last
+    /// `window_us` of virtual time. Returns `None` if there were none.
+    pub fn get_percentile(&self, p: f32, window_us: u64) -> Option<u64> {
+        self.windowed_histogram
+            .percentile(self.time, window_us, p as f64)
+    }
+
+    /// Like `get_percentile` for several percentiles at once.
+    pub fn get_percentiles(&self, ps: &[f32], window_us: u64) -> Vec<Option<u64>> {
+        let ps: Vec<f64> = ps.iter().map(|&p| p as f64).collect();
+        self.windowed_histogram
+            .percentiles(self.time, window_us, &ps)
     }
 }
 
 struct StaticInspector<'a> {
     health_map: &'a HashMap<NodeId, bool>,
+    brownouts: &'a HashMap<NodeId, Brownout>,
+    time: u64,
+    load_map: &'a HashMap<NodeId, NodeLoad>,
+    services: &'a ServiceRegistry,
+    cancellations: RefCell<Vec<EventHandle>>,
+    memberships: RefCell<Vec<Membership>>,
 }
 impl<'a> SystemInspector for StaticInspector<'a> {
     fn is_node_healthy(&self, id: NodeId) -> bool {
         *self.health_map.get(&id).unwrap_or(&false)
     }
+
+    fn node_health(&self, id: NodeId) -> Health {
+        if !self.is_node_healthy(id) {
+            return Health::Down;
+        }
+        health_of(self.brownouts.get(&id).filter(|b| b.active_at(self.time)))
+    }
+
+    fn active_requests(&self, id: NodeId) -> u32 {
+        self.load_map.get(&id).map_or(0, |l| l.active_requests)
+    }
+
+    fn queue_depth(&self, id: NodeId) -> u32 {
+        self.load_map.get(&id).map_or(0, |l| l.queue_depth)
+    }
+
+    fn recent_latency(&self, id: NodeId) -> Option<u64> {
+        self.load_map.get(&id).and_then(|l| l.recent_latency_us)
+    }
+
+    fn cancel(&self, handle: EventHandle) {
+        self.cancellations.borrow_mut().push(handle);
+    }
+
+    fn service_members_ref(&self, service: &str) -> &[NodeId] {
+        self.services.members_ref(service)
+    }
+
+    fn register_service(&self, service: &str) {
+        self.memberships
+            .borrow_mut()
+            .push(Membership::Join(service.to_string()));
+    }
+
+    fn deregister_service(&self, service: &str) {
+        self.memberships
+            .borrow_mut()
+            .push(Membership::Leave(service.to_string()));
+    }
 }
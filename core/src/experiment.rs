@@ -0,0 +1,265 @@
+use crate::engine::Simulation;
+use crate::runner::RunCondition;
+use crate::scenario::Scenario;
+use crate::topology::Topology;
+use serde::{Deserialize, Serialize};
+
+/// Headline metrics of one finished run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RunMetrics {
+    pub success_count: u64,
+    pub failure_count: u64,
+    /// Successful requests as a percentage of completed ones
+    pub sla_percent: f64,
+    pub p50_us: Option<u64>,
+    pub p99_us: Option<u64>,
+    /// Successful requests per second of measured virtual time
+    pub throughput_rps: f64,
+    /// Dollars spent over the measured time
+    #[serde(default)]
+    pub cost_dollars: f64,
+}
+
+impl RunMetrics {
+    /// Reads the metrics of `sim` after `measured_us` of recorded time.
+    pub fn from_simulation(sim: &Simulation, measured_us: u64) -> Self {
+        let total = sim.success_count + sim.failure_count;
+        let percentile =
+            |p: f64| (!sim.histogram.is_empty()).then(|| sim.histogram.value_at_percentile(p));
+        Self {
+            success_count: sim.success_count,
+            failure_count: sim.failure_count,
+            sla_percent: if total > 0 {
+                sim.success_count as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            },
+            p50_us: percentile(50.0),
+            p99_us: percentile(99.0),
+            throughput_rps: if measured_us > 0 {
+                sim.success_count as f64 / (measured_us as f64 / 1_000_000.0)
+            } else {
+                0.0
+            },
+            cost_dollars: sim.total_cost(),
+        }
+    }
+
+    /// Dollars per successful request; `None` without successes.
+    pub fn cost_per_success(&self) -> Option<f64> {
+        (self.success_count > 0).then(|| self.cost_dollars / self.success_count as f64)
+    }
+}
+
+/// Metrics of a baseline and a variant run under identical conditions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Comparison {
+    pub baseline: RunMetrics,
+    pub variant: RunMetrics,
+}
+
+impl Comparison {
+    /// Change in SLA, in percentage points.
+    pub fn delta_sla(&self) -> f64 {
+        self.variant.sla_percent - self.baseline.sla_percent
+    }
+
+    /// Change in p99 latency; `None` if either side had no successes.
+    pub fn delta_p99_us(&self) -> Option<i64> {
+        Some(self.variant.p99_us? as i64 - self.baseline.p99_us? as i64)
+    }
+
+    pub fn delta_throughput(&self) -> f64 {
+        self.variant.throughput_rps - self.baseline.throughput_rps
+    }
+
+    pub fn delta_cost(&self) -> f64 {
+        self.variant.cost_dollars - self.baseline.cost_dollars
+    }
+
+    pub fn summary(&self) -> String {
+        let p99 = self.delta_p99_us().map_or("n/a".to_string(), |d| {
+            format!("{:+.1}ms", d as f64 / 1000.0)
+        });
+        let mut summary = format!(
+            "ΔSLA {:+.2}pp, Δp99 {}, Δthroughput {:+.1} rps",
+            self.delta_sla(),
+            p99,
+            self.delta_throughput()
+        );
+        if self.baseline.cost_dollars > 0.0 || self.variant.cost_dollars > 0.0 {
+            summary.push_str(&format!(", Δcost {:+.4} $", self.delta_cost()));
+        }
+        summary
+    }
+}
+
+/// Settings shared by every run of an A/B comparison.
+///
+/// Each run builds a fresh simulation from a topology with the same seed,
+/// so differences come only from the topologies themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub seed: u64,
+    /// Measured virtual time, after the warm-up
+    pub duration_us: u64,
+    /// Initial period excluded from the metrics
+    pub warmup_us: u64,
+    /// Optional script applied to both runs
+    pub scenario: Scenario,
+}
+
+impl Experiment {
+    pub fn new(seed: u64, duration_us: u64) -> Self {
+        Self {
+            seed,
+            duration_us,
+            warmup_us: 0,
+            scenario: Scenario::default(),
+        }
+    }
+
+    pub fn with_warmup(mut self, warmup_us: u64) -> Self {
+        self.warmup_us = warmup_us;
+        self
+    }
+
+    pub fn with_scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = scenario;
+        self
+    }
+
+    pub fn run(&self, topology: &Topology) -> RunMetrics {
+        let mut sim = Simulation::from_topology(topology, self.seed);
+        sim.load_scenario(self.scenario.clone());
+        sim.warmup_us = self.warmup_us;
+        sim.run_until(&RunCondition::Duration {
+            duration_us: self.warmup_us + self.duration_us,
+        });
+        RunMetrics::from_simulation(&sim, self.duration_us)
+    }
+
+    /// Runs `topology` once per offered load in `rates_rps`, each until p99
+    /// has converged or `duration_us` has passed after the warm-up.
+    pub fn sweep_load(
+        &self,
+        topology: &Topology,
+        rates_rps: impl IntoIterator<Item = f64>,
+    ) -> LoadCurve {
+        let steady = RunCondition::Any(vec![
+            RunCondition::p99_converged(),
+            RunCondition::Duration {
+                duration_us: self.duration_us,
+            },
+        ]);
+        let points = rates_rps
+            .into_iter()
+            .map(|offered_rps| {
+                let mut sim =
+                    Simulation::from_topology(&topology.with_offered_load(offered_rps), self.seed);
+                sim.load_scenario(self.scenario.clone());
+                sim.warmup_us = self.warmup_us;
+                sim.run_until(&RunCondition::Duration {
+                    duration_us: self.warmup_us,
+                });
+                sim.run_until(&steady);
+                CurvePoint {
+                    offered_rps,
+                    metrics: RunMetrics::from_simulation(&sim, sim.time - self.warmup_us),
+                }
+            })
+            .collect();
+        LoadCurve::new(points)
+    }
+
+    pub fn compare(&self, baseline: &Topology, variant: &Topology) -> Comparison {
+        Comparison {
+            baseline: self.run(baseline),
+            variant: self.run(variant),
+        }
+    }
+}
+
+/// Evenly spaced offered loads from `from_rps` to `to_rps` inclusive.
+pub fn linear_rates(from_rps: f64, to_rps: f64, steps: usize) -> Vec<f64> {
+    match steps {
+        0 => Vec::new(),
+        1 => vec![from_rps],
+        _ => (0..steps)
+            .map(|i| from_rps + (to_rps - from_rps) * i as f64 / (steps - 1) as f64)
+            .collect(),
+    }
+}
+
+/// One point of a load sweep.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CurvePoint {
+    /// Total request rate the clients were set to
+    pub offered_rps: f64,
+    pub metrics: RunMetrics,
+}
+
+/// Throughput and latency as a function of offered load, plus the knee
+/// where the system stops keeping up.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoadCurve {
+    /// Points in sweep order
+    pub points: Vec<CurvePoint>,
+    /// Index of the last point before the knee, if any point was healthy
+    pub knee: Option<usize>,
+}
+
+impl LoadCurve {
+    /// p99 may grow to this multiple of its value at the lightest load
+    /// before the system counts as past the knee.
+    pub const KNEE_LATENCY_FACTOR: f64 = 2.0;
+    /// Share of the offered load that must still be served successfully.
+    pub const KNEE_MIN_GOODPUT: f64 = 0.95;
+
+    pub fn new(points: Vec<CurvePoint>) -> Self {
+        let knee = Self::find_knee(&points);
+        Self { points, knee }
+    }
+
+    /// The last point, in order of offered load, that still serves nearly
+    /// all of its load with p99 close to the unloaded p99.
+    fn find_knee(points: &[CurvePoint]) -> Option<usize> {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by(|&a, &b| points[a].offered_rps.total_cmp(&points[b].offered_rps));
+        let base_p99 = order.iter().find_map(|&i| points[i].metrics.p99_us)? as f64;
+        order
+            .into_iter()
+            .take_while(|&i| {
+                let p = &points[i];
+                let keeps_up = p.metrics.throughput_rps >= p.offered_rps * Self::KNEE_MIN_GOODPUT;
+                let fast = p
+                    .metrics
+                    .p99_us
+                    .is_some_and(|v| v as f64 <= base_p99 * Self::KNEE_LATENCY_FACTOR);
+                keeps_up && fast
+            })
+            .last()
+    }
+
+    pub fn knee_point(&self) -> Option<&CurvePoint> {
+        self.knee.map(|i| &self.points[i])
+    }
+
+    /// The curve as CSV, one row per point with the knee flagged.
+    pub fn to_csv(&self) -> String {
+        let ms = |us: Option<u64>| us.map_or(String::new(), |v| (v as f64 / 1000.0).to_string());
+        let mut out = String::from("offered_rps,throughput_rps,sla_percent,p50_ms,p99_ms,knee\n");
+        for (i, p) in self.points.iter().enumerate() {
+            out.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                p.offered_rps,
+                p.metrics.throughput_rps,
+                p.metrics.sla_percent,
+                ms(p.metrics.p50_us),
+                ms(p.metrics.p99_us),
+                self.knee == Some(i)
+            ));
+        }
+        out
+    }
+}
@@ -0,0 +1,70 @@
+use crate::engine::Simulation;
+use serde::{Deserialize, Serialize};
+
+/// Why a request failed, carried back on its `Response`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum FailureReason {
+    /// The response reached the client after its timeout
+    Timeout,
+    /// The backend returned an error (5xx)
+    BackendError,
+    /// The backlog was full when the request arrived
+    QueueFull,
+    /// The request or its response was dropped on a link
+    PacketLoss,
+    /// A load balancer had nowhere healthy to send the request
+    NoHealthyTargets,
+    /// Dropped early because it could no longer finish in time
+    DeadlineExceeded,
+    /// The request travelled more hops than allowed
+    HopLimit,
+    /// The node handling it was down or removed
+    Unavailable,
+}
+
+impl FailureReason {
+    pub const ALL: [FailureReason; 8] = [
+        FailureReason::Timeout,
+        FailureReason::BackendError,
+        FailureReason::QueueFull,
+        FailureReason::PacketLoss,
+        FailureReason::NoHealthyTargets,
+        FailureReason::DeadlineExceeded,
+        FailureReason::HopLimit,
+        FailureReason::Unavailable,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureReason::Timeout => "timeout",
+            FailureReason::BackendError => "backend error",
+            FailureReason::QueueFull => "queue full",
+            FailureReason::PacketLoss => "packet loss",
+            FailureReason::NoHealthyTargets => "no healthy targets",
+            FailureReason::DeadlineExceeded => "deadline exceeded",
+            FailureReason::HopLimit => "hop limit",
+            FailureReason::Unavailable => "unavailable",
+        }
+    }
+
+    /// Stable snake_case name, for metric labels and CSV.
+    pub fn key(self) -> &'static str {
+        match self {
+            FailureReason::Timeout => "timeout",
+            FailureReason::BackendError => "backend_error",
+            FailureReason::QueueFull => "queue_full",
+            FailureReason::PacketLoss => "packet_loss",
+            FailureReason::NoHealthyTargets => "no_healthy_targets",
+            FailureReason::DeadlineExceeded => "deadline_exceeded",
+            FailureReason::HopLimit => "hop_limit",
+            FailureReason::Unavailable => "unavailable",
+        }
+    }
+}
+
+impl Simulation {
+    pub(crate) fn record_failure(&mut self, reason: FailureReason) {
+        self.failure_count += 1;
+        *self.failure_reasons.entry(reason).or_default() += 1;
+    }
+}
@@ -0,0 +1,571 @@
+use crate::components::client::Client;
+use crate::components::load_balancer::{BalancingStrategy, LoadBalancer};
+use crate::components::server::{Server, ServerConfig};
+use crate::engine::{stream_seed, Event, EventType, Simulation};
+use crate::failure::FailureReason;
+use crate::network::canonical_key;
+use crate::traits::NodeId;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bands a completed flow's latency is spread over, so percentiles of
+/// fluid traffic are not all its mean.
+const LATENCY_BANDS: usize = 20;
+/// Flows thinner than this many requests a second are not followed.
+const MIN_FLOW_RPS: f64 = 1e-6;
+
+/// When and how often traffic is modelled as flow rather than requests
+/// (see `Simulation::enable_fluid`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FluidMode {
+    /// Clients sending at least this many requests a second switch to
+    /// flow; slower ones keep sending discrete requests
+    pub threshold_rps: f64,
+    /// Virtual time between flow updates
+    pub tick_us: u64,
+}
+
+impl Default for FluidMode {
+    fn default() -> Self {
+        Self {
+            threshold_rps: 10_000.0,
+            tick_us: 10_000,
+        }
+    }
+}
+
+/// Fluid traffic through one server over the last update.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FluidNode {
+    /// Requests a second accepted for processing
+    pub arrival_rps: f64,
+    /// Requests a second the workers finished
+    pub served_rps: f64,
+    /// Requests a second turned away because the backlog was full
+    pub dropped_rps: f64,
+    /// Requests waiting for a worker
+    pub backlog: f64,
+    /// Fraction of worker time in use
+    pub utilization: f64,
+    /// Time a request waits for a worker, by Little's law
+    pub wait_us: f64,
+    /// Time a worker spends on one request, saturation included
+    pub service_us: f64,
+}
+
+/// A running fluid approximation: its settings and per-server state.
+#[derive(Debug, Clone)]
+pub struct FluidState {
+    pub mode: FluidMode,
+    /// Virtual time of the next flow update
+    pub next_tick_us: u64,
+    pub nodes: HashMap<NodeId, FluidNode>,
+    /// Rounds fractional request counts up or down without bias
+    rng: StdRng,
+}
+
+/// Requests following one route, merged per node as routes meet.
+#[derive(Debug, Clone, Copy)]
+struct Flow {
+    rps: f64,
+    /// Service times and link latency along the route, both ways
+    fixed_us: f64,
+    /// Mean time spent waiting in backlogs along the route
+    wait_us: f64,
+    /// Share of responses that make it back over lossy links
+    returns: f64,
+}
+
+impl Flow {
+    fn merge(&mut self, other: Flow) {
+        let total = self.rps + other.rps;
+        if total <= 0.0 {
+            return;
+        }
+        let mix = |a: f64, b: f64| (a * self.rps + b * other.rps) / total;
+        self.fixed_us = mix(self.fixed_us, other.fixed_us);
+        self.wait_us = mix(self.wait_us, other.wait_us);
+        self.returns = mix(self.returns, other.returns);
+        self.rps = total;
+    }
+}
+
+/// What one update did, in requests a second, before it is counted.
+#[derive(Default)]
+struct TickOutcome {
+    /// Requests accepted by each server
+    accepted: BTreeMap<NodeId, f64>,
+    /// Requests received by each node, for request-priced costs
+    received: BTreeMap<NodeId, f64>,
+    /// Routes that finished, with the timeout of the client that sent them
+    completed: Vec<(Flow, u64)>,
+    failures: BTreeMap<FailureReason, f64>,
+    /// Requests and dropped requests per directed edge
+    requests: BTreeMap<(NodeId, NodeId), (f64, f64)>,
+    /// Responses per directed edge
+    responses: BTreeMap<(NodeId, NodeId), f64>,
+}
+
+impl TickOutcome {
+    fn fail(&mut self, reason: FailureReason, rps: f64) {
+        if rps > 0.0 {
+            *self.failures.entry(reason).or_default() += rps;
+        }
+    }
+}
+
+/// A client sending fluid traffic.
+struct Source {
+    id: NodeId,
+    target: NodeId,
+    rps: f64,
+    timeout_us: u64,
+    max_hops: u32,
+}
+
+impl FluidState {
+    fn new(mode: FluidMode, now: u64, seed: u64) -> Self {
+        Self {
+            mode,
+            next_tick_us: now + mode.tick_us.max(1),
+            nodes: HashMap::new(),
+            rng: StdRng::seed_from_u64(stream_seed(seed, 0, "fluid")),
+        }
+    }
+
+    /// Whether `client`'s traffic is carried as flow.
+    fn carries(&self, client: &Client) -> bool {
+        client.healthy
+            && client.target_id.is_some()
+            && client.config.read().unwrap().arrival_rate as f64 >= self.mode.threshold_rps
+    }
+
+    /// `expected` as a whole number of requests, rounded at random so the
+    /// fractions add up over many updates.
+    fn count(&mut self, expected: f64) -> u64 {
+        if expected <= 0.0 {
+            return 0;
+        }
+        let whole = expected.floor();
+        whole as u64 + u64::from(self.rng.gen::<f64>() < expected - whole)
+    }
+}
+
+impl Simulation {
+    /// Models clients sending at least `mode.threshold_rps` as a fluid:
+    /// instead of one event per request, the flow through every node is
+    /// updated once per `mode.tick_us`, so rates far beyond what discrete
+    /// events can keep up with cost the same to simulate as small ones.
+    ///
+    /// Clients switch back and forth on their own as their rate crosses
+    /// the threshold, and slower ones keep sending discrete requests,
+    /// which still queue and fail as usual. Fluid traffic is an
+    /// approximation: balancers split it evenly over their healthy targets
+    /// whatever their strategy, servers drain it at their configured
+    /// capacity, and latency percentiles come from the mean wait assuming
+    /// it is exponentially distributed. It adds to the run's counters,
+    /// latency histograms, edge traffic and costs, but not to what the
+    /// components themselves report; read it with `fluid_load`.
+    pub fn enable_fluid(&mut self, mode: FluidMode) {
+        self.fluid = Some(FluidState::new(mode, self.time, self.seed));
+    }
+
+    /// Returns every client to discrete requests. Fluid backlogs are
+    /// dropped.
+    pub fn disable_fluid(&mut self) {
+        self.fluid = None;
+    }
+
+    /// Whether `node_id` is a client whose traffic is currently fluid.
+    pub fn is_fluid_source(&self, node_id: NodeId) -> bool {
+        let Some(fluid) = &self.fluid else {
+            return false;
+        };
+        self.get_component_as::<Client>(node_id)
+            .is_some_and(|client| fluid.carries(client))
+    }
+
+    /// Fluid traffic through server `node_id` over the last update, if it
+    /// received any.
+    pub fn fluid_load(&self, node_id: NodeId) -> Option<&FluidNode> {
+        self.fluid.as_ref()?.nodes.get(&node_id)
+    }
+
+    /// Holds back the generator tick of a client whose traffic is fluid,
+    /// re-checking at the next update in case its rate dropped. Returns
+    /// `true` if `event` was held back.
+    pub(crate) fn park_fluid_generator(&mut self, event: &Event) -> bool {
+        let EventType::GenerateNext { generation_id } = event.event_type else {
+            return false;
+        };
+        let Some(fluid) = &self.fluid else {
+            return false;
+        };
+        let tick_us = fluid.mode.tick_us.max(1);
+        let parked = self
+            .get_component_as::<Client>(event.node_id)
+            .is_some_and(|client| {
+                // A stale tick is left for the client to discard
+                fluid.carries(client)
+                    && client.config.read().unwrap().generation_id == generation_id
+            });
+        if parked {
+            self.schedule(self.time + tick_us, event.node_id, event.event_type.clone());
+        }
+        parked
+    }
+
+    /// Runs every fluid update due at or before `until`.
+    pub(crate) fn advance_fluid_until(&mut self, until: u64) {
+        while let Some(fluid) = self.fluid.as_mut() {
+            let now = fluid.next_tick_us;
+            if now > until {
+                break;
+            }
+            fluid.next_tick_us += fluid.mode.tick_us.max(1);
+            self.fluid_tick(now);
+        }
+    }
+
+    fn fluid_tick(&mut self, now: u64) {
+        let Some(mut fluid) = self.fluid.take() else {
+            return;
+        };
+        let mut sources: Vec<Source> = self
+            .components
+            .iter()
+            .filter_map(|(&id, comp)| {
+                let client = comp.downcast_ref::<Client>()?;
+                if !fluid.carries(client) {
+                    return None;
+                }
+                let config = client.config.read().unwrap();
+                Some(Source {
+                    id,
+                    target: client.target_id?,
+                    rps: config.arrival_rate as f64,
+                    timeout_us: config.timeout * 1000,
+                    max_hops: config.max_hops,
+                })
+            })
+            .collect();
+        sources.sort_by_key(|s| s.id);
+
+        // Settle the servers on this update's arrivals first, then follow
+        // the flow again through their new state to count what happened
+        let dt = fluid.mode.tick_us.max(1) as f64 / 1_000_000.0;
+        let mut arrivals = TickOutcome::default();
+        for source in &sources {
+            self.follow_flow(&fluid, source, &mut arrivals);
+        }
+        self.update_fluid_servers(&mut fluid, &arrivals.accepted, dt);
+        let mut outcome = TickOutcome::default();
+        for source in &sources {
+            self.follow_flow(&fluid, source, &mut outcome);
+        }
+        self.count_fluid_outcome(&mut fluid, outcome, now, dt);
+        self.fluid = Some(fluid);
+    }
+
+    /// Follows `source`'s traffic hop by hop until it completes or fails.
+    fn follow_flow(&self, fluid: &FluidState, source: &Source, outcome: &mut TickOutcome) {
+        let start = Flow {
+            rps: source.rps,
+            fixed_us: 0.0,
+            wait_us: 0.0,
+            returns: 1.0,
+        };
+        let mut level = BTreeMap::new();
+        self.send_flow(source.id, source.target, start, &mut level, outcome);
+        let mut hops = 1;
+        while !level.is_empty() {
+            if hops > source.max_hops {
+                let lost = level.values().map(|f: &Flow| f.rps).sum();
+                outcome.fail(FailureReason::HopLimit, lost);
+                break;
+            }
+            let mut next = BTreeMap::new();
+            for (node_id, flow) in level {
+                self.visit_flow(fluid, node_id, flow, source, &mut next, outcome);
+            }
+            level = next;
+            hops += 1;
+        }
+    }
+
+    /// Puts `flow` on the link from `from` to `to`.
+    fn send_flow(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        mut flow: Flow,
+        next: &mut BTreeMap<NodeId, Flow>,
+        outcome: &mut TickOutcome,
+    ) {
+        if flow.rps < MIN_FLOW_RPS {
+            return;
+        }
+        let link = self.links.get(&canonical_key(from, to));
+        let there = link.map(|l| *l.get_config(from, to)).unwrap_or_default();
+        let back = link.map(|l| *l.get_config(to, from)).unwrap_or_default();
+
+        let lost = flow.rps * there.packet_loss_rate.clamp(0.0, 1.0) as f64;
+        let edge = outcome.requests.entry((from, to)).or_default();
+        edge.0 += flow.rps;
+        edge.1 += lost;
+        outcome.fail(FailureReason::PacketLoss, lost);
+        flow.rps -= lost;
+        *outcome.responses.entry((to, from)).or_default() += flow.rps;
+
+        // Mean jitter is half its amplitude
+        flow.fixed_us += (there.latency_us + back.latency_us) as f64
+            + (there.jitter_us + back.jitter_us) as f64 / 2.0;
+        flow.returns *= 1.0 - back.packet_loss_rate.clamp(0.0, 1.0) as f64;
+        match next.get_mut(&to) {
+            Some(merged) => merged.merge(flow),
+            None => {
+                next.insert(to, flow);
+            }
+        }
+    }
+
+    /// Passes `flow` arriving at `node_id` on to its next hops.
+    fn visit_flow(
+        &self,
+        fluid: &FluidState,
+        node_id: NodeId,
+        mut flow: Flow,
+        source: &Source,
+        next: &mut BTreeMap<NodeId, Flow>,
+        outcome: &mut TickOutcome,
+    ) {
+        *outcome.received.entry(node_id).or_default() += flow.rps;
+        let Some(comp) = self.components.get(&node_id) else {
+            outcome.fail(FailureReason::Unavailable, flow.rps);
+            return;
+        };
+        if !self.health_buffer.get(&node_id).copied().unwrap_or(false) {
+            outcome.fail(FailureReason::Unavailable, flow.rps);
+            return;
+        }
+
+        if let Some(server) = comp.downcast_ref::<Server>() {
+            let config = server.config.read().unwrap();
+            let brownout_errors = self.brownout(node_id).map_or(0.0, |b| b.error_rate);
+            let ok = (1.0 - config.failure_probability.clamp(0.0, 1.0) as f64)
+                * (1.0 - brownout_errors.clamp(0.0, 1.0) as f64);
+            outcome.fail(FailureReason::BackendError, flow.rps * (1.0 - ok));
+            let accepted = flow.rps * ok;
+            *outcome.accepted.entry(node_id).or_default() += accepted;
+
+            // Servers new to fluid traffic pass it straight through until
+            // their first update
+            let (passed, dropped, wait_us, service_us) = match fluid.nodes.get(&node_id) {
+                Some(state) if state.arrival_rps > 0.0 => (
+                    state.served_rps / state.arrival_rps,
+                    state.dropped_rps / state.arrival_rps,
+                    state.wait_us,
+                    state.service_us,
+                ),
+                _ => (1.0, 0.0, 0.0, self.fluid_service_us(node_id, &config)),
+            };
+            outcome.fail(FailureReason::QueueFull, accepted * dropped);
+            flow.rps = accepted * passed;
+            flow.fixed_us += service_us;
+            flow.wait_us += wait_us;
+            // Cache hits are answered here
+            let misses = 1.0 - config.cache_hit_ratio.clamp(0.0, 1.0) as f64;
+            let shares = config.route_shares(server.targets.len());
+            let mut answered = 1.0;
+            for (&hop, &share) in server.targets.iter().zip(&shares) {
+                let share = share as f64 * misses;
+                answered -= share;
+                let called = Flow {
+                    rps: flow.rps * share,
+                    ..flow
+                };
+                self.send_flow(node_id, hop, called, next, outcome);
+            }
+            if answered > 1e-6 {
+                let answered = Flow {
+                    rps: flow.rps * answered,
+                    ..flow
+                };
+                outcome.completed.push((answered, source.timeout_us));
+            }
+            return;
+        }
+
+        let healthy: Vec<NodeId> = match comp.downcast_ref::<LoadBalancer>() {
+            Some(lb) => self.fluid_lb_targets(lb),
+            None => comp
+                .get_targets()
+                .into_iter()
+                .filter(|t| self.health_buffer.get(t).copied().unwrap_or(false))
+                .collect(),
+        };
+        if healthy.is_empty() {
+            if comp.kind() == "LoadBalancer" {
+                outcome.fail(FailureReason::NoHealthyTargets, flow.rps);
+            } else {
+                outcome.completed.push((flow, source.timeout_us));
+            }
+            return;
+        }
+        let share = Flow {
+            rps: flow.rps / healthy.len() as f64,
+            ..flow
+        };
+        for target in healthy {
+            self.send_flow(node_id, target, share, next, outcome);
+        }
+    }
+
+    /// The targets a load balancer spreads flow over, picked as its
+    /// `select_target` does: service members if it routes by service, and
+    /// only fully healthy ones when it avoids degraded nodes and has some.
+    fn fluid_lb_targets(&self, lb: &LoadBalancer) -> Vec<NodeId> {
+        let config = lb.config.read().unwrap();
+        let candidates = match &config.service {
+            Some(name) => self.services.members(name),
+            None => lb.targets.clone(),
+        };
+        let mut healthy: Vec<NodeId> = candidates
+            .into_iter()
+            .filter(|t| self.health_buffer.get(t).copied().unwrap_or(false))
+            .collect();
+        if config.avoid_degraded
+            && config.strategy != BalancingStrategy::LeastConnections
+            && healthy.iter().any(|&t| self.brownout(t).is_none())
+        {
+            healthy.retain(|&t| self.brownout(t).is_none());
+        }
+        healthy
+    }
+
+    /// Mean service time of one request at `node_id` with no saturation,
+    /// counting cache hits.
+    fn fluid_service_us(&self, node_id: NodeId, config: &ServerConfig) -> f64 {
+        let service_us = config.service_time * 1000;
+        let miss_us = match self.brownout(node_id) {
+            Some(brownout) => brownout.stretch(service_us) as f64,
+            None => service_us as f64,
+        };
+        let hits = config.cache_hit_ratio.clamp(0.0, 1.0) as f64;
+        hits * (config.cache_hit_time * 1000) as f64 + (1.0 - hits) * miss_us
+    }
+
+    /// Drains every server's backlog by what its workers finish in `dt`
+    /// seconds. Servers that received nothing drop their fluid state.
+    fn update_fluid_servers(
+        &self,
+        fluid: &mut FluidState,
+        accepted: &BTreeMap<NodeId, f64>,
+        dt: f64,
+    ) {
+        let mut nodes = HashMap::with_capacity(accepted.len());
+        for (&node_id, &arrival_rps) in accepted {
+            let Some(server) = self.get_component_as::<Server>(node_id) else {
+                continue;
+            };
+            let config = server.config.read().unwrap();
+            let backlog = fluid.nodes.get(&node_id).map_or(0.0, |s| s.backlog);
+            let base_us = self.fluid_service_us(node_id, &config).max(1.0);
+            let workers = config.concurrency.max(1) as f64;
+
+            // Share of workers busy, which sets the saturation penalty
+            let load = if backlog > 0.0 {
+                1.0
+            } else {
+                (arrival_rps * base_us / 1_000_000.0 / workers).min(1.0)
+            };
+            let service_us = base_us * (1.0 + load * load * config.saturation_penalty as f64);
+            let capacity = workers * dt * 1_000_000.0 / service_us;
+            let waiting = backlog + arrival_rps * dt;
+            let served = waiting.min(capacity);
+            let dropped = (waiting - served - config.backlog_limit as f64).max(0.0);
+            let backlog = waiting - served - dropped;
+            let served_rps = served / dt;
+            nodes.insert(
+                node_id,
+                FluidNode {
+                    arrival_rps,
+                    served_rps,
+                    dropped_rps: dropped / dt,
+                    backlog,
+                    utilization: served / capacity,
+                    wait_us: if served_rps > 0.0 {
+                        backlog / served_rps * 1_000_000.0
+                    } else {
+                        0.0
+                    },
+                    service_us,
+                },
+            );
+        }
+        fluid.nodes = nodes;
+    }
+
+    /// Adds one update's traffic to the run's counters and histograms.
+    fn count_fluid_outcome(
+        &mut self,
+        fluid: &mut FluidState,
+        outcome: TickOutcome,
+        now: u64,
+        dt: f64,
+    ) {
+        for (&(from, to), &(requests, dropped)) in &outcome.requests {
+            let requests = fluid.count(requests * dt);
+            let dropped = fluid.count(dropped * dt).min(requests);
+            let counters = self.edge_stats.entry((from, to)).or_default();
+            counters.sent += requests;
+            counters.requests += requests;
+            counters.dropped += dropped;
+        }
+        for (&edge, &responses) in &outcome.responses {
+            self.edge_stats.entry(edge).or_default().sent += fluid.count(responses * dt);
+        }
+        if now < self.warmup_end() {
+            return;
+        }
+        for (&node_id, &rps) in &outcome.received {
+            let count = fluid.count(rps * dt);
+            self.cost_requests(node_id, count);
+        }
+        for (&reason, &rps) in &outcome.failures {
+            let count = fluid.count(rps * dt);
+            self.failure_count += count;
+            *self.failure_reasons.entry(reason).or_default() += count;
+        }
+        for (flow, timeout_us) in outcome.completed {
+            let lost = fluid.count(flow.rps * (1.0 - flow.returns) * dt);
+            self.failure_count += lost;
+            *self
+                .failure_reasons
+                .entry(FailureReason::PacketLoss)
+                .or_default() += lost;
+            let per_band = flow.rps * flow.returns * dt / LATENCY_BANDS as f64;
+            for band in 0..LATENCY_BANDS {
+                let count = fluid.count(per_band);
+                if count == 0 {
+                    continue;
+                }
+                // Midpoint of the band under an exponential wait
+                let q = (band as f64 + 0.5) / LATENCY_BANDS as f64;
+                let latency_us = (flow.fixed_us - flow.wait_us * (1.0 - q).ln()) as u64;
+                if latency_us > timeout_us {
+                    self.failure_count += count;
+                    *self
+                        .failure_reasons
+                        .entry(FailureReason::Timeout)
+                        .or_default() += count;
+                } else {
+                    self.success_count += count;
+                    self.histogram.saturating_record_n(latency_us, count);
+                    self.windowed_histogram.record_n(now, latency_us, count);
+                }
+            }
+        }
+    }
+}
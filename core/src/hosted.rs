@@ -0,0 +1,542 @@
+use crate::components::{CustomStats, StatValue, VisualState};
+use crate::engine::{Commands, Event, EventType, Path, ScheduleCmd, SystemInspector};
+use crate::failure::FailureReason;
+use crate::metadata::Metadata;
+use crate::traits::{Component, NodeId};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// What guest logic decides to do with a request.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Decision {
+    /// Answers the caller after `delay_us` of work
+    Respond {
+        success: bool,
+        #[serde(default)]
+        delay_us: u64,
+    },
+    /// Sends the request on to `to`, one of the node's targets, or to the
+    /// first healthy target if omitted
+    Forward {
+        #[serde(default)]
+        to: Option<NodeId>,
+        #[serde(default)]
+        delay_us: u64,
+    },
+}
+
+/// What guest logic is shown about a request. `event` comes first, so a
+/// guest reading the JSON can tell arrivals from responses by the prefix.
+#[derive(Debug, Serialize)]
+pub struct EventInput<'a> {
+    /// `"arrival"`, or `"response"` for a request this node forwarded
+    pub event: &'a str,
+    /// The request id, as a string since it may not fit a double
+    pub request: String,
+    pub time_us: u64,
+    pub deadline_us: u64,
+    /// How the request went downstream, for responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    pub targets: &'a [NodeId],
+    /// The targets currently up
+    pub healthy: Vec<NodeId>,
+    pub metadata: &'a Metadata,
+}
+
+/// Request handling written outside the core, such as a WebAssembly
+/// plugin or a script. Errors fail the request at hand and are shown on
+/// the node.
+pub trait GuestLogic: Send + 'static {
+    /// What to do with a request. `None` fails an arrival and passes a
+    /// response back unchanged.
+    fn decide(&mut self, input: &EventInput) -> Result<Option<Decision>, String>;
+
+    /// Called with the full config whenever it changes; may answer with a
+    /// corrected one.
+    fn configure(&mut self, _config: &Value) -> Result<Option<Value>, String> {
+        Ok(None)
+    }
+
+    /// Fields shown next to the host's own counters.
+    fn snapshot(&mut self) -> Result<Option<Value>, String> {
+        Ok(None)
+    }
+
+    fn seed(&mut self, _seed: u64) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A node running guest logic. The host keeps what every component has
+/// to: the request's path back, targets, health and counters; the guest
+/// only decides what to do with each request.
+pub struct HostedComponent<L> {
+    kind: &'static str,
+    pub name: String,
+    /// Config fields the guest accepts, with their defaults
+    defaults: Map<String, Value>,
+    config: Value,
+    logic: L,
+    targets: Vec<NodeId>,
+    healthy: bool,
+    /// Requests between arrival and the response sent back
+    held: u32,
+    received: u64,
+    errors: u64,
+    arrival_window: VecDeque<u64>,
+    display_throughput: f32,
+    display_snapshot: VisualState,
+    last_error: Option<String>,
+}
+
+impl<L: GuestLogic> HostedComponent<L> {
+    /// A node of `kind` whose config holds the fields of `defaults`. The
+    /// guest sees the config once one is applied.
+    pub fn new(kind: &'static str, name: &str, defaults: Map<String, Value>, logic: L) -> Self {
+        HostedComponent {
+            kind,
+            name: name.to_string(),
+            config: Value::Object(defaults.clone()),
+            defaults,
+            logic,
+            targets: Vec::new(),
+            healthy: true,
+            held: 0,
+            received: 0,
+            errors: 0,
+            arrival_window: VecDeque::new(),
+            display_throughput: 0.0,
+            display_snapshot: VisualState::None,
+            last_error: None,
+        }
+    }
+
+    /// Why the guest last failed to handle a call, if it ever did.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Keeps the known fields of `data` over the current config and lets
+    /// the guest correct the result.
+    fn configure(&mut self, data: &Value) {
+        if let Some(fields) = data.as_object() {
+            for (key, value) in fields {
+                if self.defaults.contains_key(key) {
+                    self.config[key] = value.clone();
+                }
+            }
+        }
+        // A config the guest accepts gives it a fresh start
+        match self.logic.configure(&self.config) {
+            Ok(config) => {
+                self.last_error = None;
+                if let Some(config) = config.filter(Value::is_object) {
+                    self.config = config;
+                }
+            }
+            Err(err) => self.last_error = Some(err),
+        }
+    }
+
+    fn decide(
+        &mut self,
+        event: &str,
+        success: Option<bool>,
+        time_us: u64,
+        request: &Request,
+        inspector: &dyn SystemInspector,
+    ) -> Option<Decision> {
+        let input = EventInput {
+            event,
+            request: request.request_id.to_string(),
+            time_us,
+            deadline_us: request.start_time + request.timeout,
+            success,
+            targets: &self.targets,
+            healthy: self
+                .targets
+                .iter()
+                .copied()
+                .filter(|&t| inspector.is_node_healthy(t))
+                .collect(),
+            metadata: &request.metadata,
+        };
+        match self.logic.decide(&input) {
+            Ok(decision) => decision,
+            Err(err) => {
+                self.last_error = Some(err);
+                None
+            }
+        }
+    }
+
+    /// Forwards the request, or fails it if `to` is not a target.
+    fn forward(
+        &mut self,
+        event: &Event,
+        inspector: &dyn SystemInspector,
+        to: Option<NodeId>,
+        delay_us: u64,
+        request: Request,
+    ) -> Commands {
+        let hop = match to {
+            Some(to) => self.targets.contains(&to).then_some(to),
+            None => self
+                .targets
+                .iter()
+                .copied()
+                .find(|&t| inspector.is_node_healthy(t)),
+        };
+        let Some(hop) = hop else {
+            self.last_error = Some(match to {
+                Some(to) => format!("#{} is not a target of this node", to),
+                None => "no healthy target to forward to".to_string(),
+            });
+            return self.respond(event.node_id, false, 0, request);
+        };
+        let Request {
+            request_id,
+            mut path,
+            start_time,
+            timeout,
+            max_hops,
+            metadata,
+        } = request;
+        if path.last() != Some(&event.node_id) {
+            path.push(event.node_id);
+        }
+        smallvec![ScheduleCmd {
+            delay: delay_us,
+            node_id: hop,
+            event_type: EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            },
+        }]
+    }
+
+    /// Answers the caller, now or once `delay_us` of work is done.
+    /// `request.path` must end with the caller.
+    fn respond(
+        &mut self,
+        node_id: NodeId,
+        success: bool,
+        delay_us: u64,
+        request: Request,
+    ) -> Commands {
+        let Request {
+            request_id,
+            path,
+            start_time,
+            timeout,
+            max_hops,
+            metadata,
+        } = request;
+        if delay_us > 0 {
+            return smallvec![ScheduleCmd {
+                delay: delay_us,
+                node_id,
+                event_type: EventType::ProcessComplete {
+                    request_id,
+                    success,
+                    start_time,
+                    path,
+                    timeout,
+                    max_hops,
+                    metadata,
+                },
+            }];
+        }
+        self.held = self.held.saturating_sub(1);
+        if !success {
+            self.errors += 1;
+        }
+        let Some(&prev) = path.last() else {
+            return smallvec![];
+        };
+        smallvec![ScheduleCmd {
+            delay: 0,
+            node_id: prev,
+            event_type: EventType::Response {
+                request_id,
+                path,
+                start_time,
+                success,
+                failure: (!success).then_some(FailureReason::BackendError),
+                timeout,
+                max_hops,
+                metadata,
+            },
+        }]
+    }
+}
+
+/// The fields of a request the host carries for the guest.
+struct Request {
+    request_id: u128,
+    path: Path,
+    start_time: u64,
+    timeout: u64,
+    max_hops: u32,
+    metadata: Metadata,
+}
+
+impl<L: GuestLogic> Component for HostedComponent<L> {
+    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Commands {
+        let node_id = event.node_id;
+        match event.event_type.clone() {
+            EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                let request = Request {
+                    request_id,
+                    path,
+                    start_time,
+                    timeout,
+                    max_hops,
+                    metadata,
+                };
+                self.received += 1;
+                self.arrival_window.push_back(event.time);
+                self.held += 1;
+                if !self.healthy {
+                    self.held -= 1;
+                    self.errors += 1;
+                    let Some(&prev) = request.path.last() else {
+                        return smallvec![];
+                    };
+                    return smallvec![ScheduleCmd {
+                        delay: 0,
+                        node_id: prev,
+                        event_type: EventType::Response {
+                            request_id,
+                            path: request.path,
+                            start_time,
+                            success: false,
+                            failure: Some(FailureReason::Unavailable),
+                            timeout,
+                            max_hops,
+                            metadata: request.metadata,
+                        },
+                    }];
+                }
+                match self.decide("arrival", None, event.time, &request, inspector) {
+                    Some(Decision::Respond { success, delay_us }) => {
+                        self.respond(node_id, success, delay_us, request)
+                    }
+                    Some(Decision::Forward { to, delay_us }) => {
+                        self.forward(&event, inspector, to, delay_us, request)
+                    }
+                    None => self.respond(node_id, false, 0, request),
+                }
+            }
+            EventType::Response {
+                request_id,
+                path,
+                start_time,
+                success,
+                failure,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                let mut request = Request {
+                    request_id,
+                    path,
+                    start_time,
+                    timeout,
+                    max_hops,
+                    metadata,
+                };
+                let decision =
+                    self.decide("response", Some(success), event.time, &request, inspector);
+                if let Some(Decision::Forward { to, delay_us }) = decision {
+                    return self.forward(&event, inspector, to, delay_us, request);
+                }
+                // Back to the caller: this node leaves the path
+                request.path.pop();
+                match decision {
+                    Some(Decision::Respond { success, delay_us }) => {
+                        self.respond(node_id, success, delay_us, request)
+                    }
+                    _ => {
+                        self.held = self.held.saturating_sub(1);
+                        let Some(&prev) = request.path.last() else {
+                            return smallvec![];
+                        };
+                        smallvec![ScheduleCmd {
+                            delay: 0,
+                            node_id: prev,
+                            event_type: EventType::Response {
+                                request_id,
+                                path: request.path,
+                                start_time,
+                                success,
+                                failure,
+                                timeout,
+                                max_hops,
+                                metadata: request.metadata,
+                            },
+                        }]
+                    }
+                }
+            }
+            EventType::ProcessComplete {
+                request_id,
+                success,
+                start_time,
+                path,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                let request = Request {
+                    request_id,
+                    path,
+                    start_time,
+                    timeout,
+                    max_hops,
+                    metadata,
+                };
+                self.respond(node_id, success, 0, request)
+            }
+            EventType::GenerateNext { .. } => smallvec![],
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    fn kind(&self) -> &str {
+        self.kind
+    }
+
+    fn encode_config(&self) -> Value {
+        self.config.clone()
+    }
+
+    fn apply_config(&mut self, config: Value, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        self.configure(&config);
+        vec![]
+    }
+
+    fn active_requests(&self) -> u32 {
+        self.held
+    }
+
+    fn display_throughput(&self) -> f32 {
+        self.display_throughput
+    }
+
+    fn received_count(&self) -> u64 {
+        self.received
+    }
+
+    fn error_count(&self) -> u64 {
+        self.errors
+    }
+
+    fn set_healthy(&mut self, healthy: bool) {
+        self.healthy = healthy;
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy
+    }
+
+    fn add_target(&mut self, target: NodeId) {
+        if !self.targets.contains(&target) {
+            self.targets.push(target);
+        }
+    }
+
+    fn remove_target(&mut self, target: NodeId) {
+        self.targets.retain(|&t| t != target);
+    }
+
+    fn get_targets(&self) -> Vec<NodeId> {
+        self.targets.clone()
+    }
+
+    fn clear_targets(&mut self) {
+        self.targets.clear();
+    }
+
+    fn get_visual_snapshot(&self) -> VisualState {
+        self.display_snapshot.clone()
+    }
+
+    fn sync_display_stats(&mut self, current_time_us: u64) {
+        while let Some(&t) = self.arrival_window.front() {
+            if current_time_us > t + 1_000_000 {
+                self.arrival_window.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.display_throughput = self.arrival_window.len() as f32;
+
+        let mut stats = CustomStats {
+            rps: self.display_throughput,
+            active: self.held,
+            errors: self.errors,
+            ..Default::default()
+        };
+        match self.logic.snapshot() {
+            Ok(Some(Value::Object(fields))) => {
+                stats.fields = fields
+                    .into_iter()
+                    .map(|(key, value)| (key, stat_value(value)))
+                    .collect();
+            }
+            Ok(_) => {}
+            Err(err) => self.last_error = Some(err),
+        }
+        stats.last_error = self.last_error.clone();
+        self.display_snapshot = VisualState::Custom(stats);
+    }
+
+    fn reset_internal_stats(&mut self) {
+        self.held = 0;
+        self.received = 0;
+        self.errors = 0;
+        self.arrival_window.clear();
+        self.display_throughput = 0.0;
+        self.display_snapshot = VisualState::None;
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        if let Err(err) = self.logic.seed(seed) {
+            self.last_error = Some(err);
+        }
+    }
+}
+
+/// A value guest logic reported for display; structured values show as
+/// their JSON text.
+fn stat_value(value: Value) -> StatValue {
+    match value {
+        Value::Number(n) => StatValue::Number(n.as_f64().unwrap_or_default()),
+        Value::Bool(b) => StatValue::Flag(b),
+        Value::String(t) => StatValue::Text(t),
+        other => StatValue::Text(other.to_string()),
+    }
+}
@@ -0,0 +1,421 @@
+use crate::topology::Topology;
+use crate::traits::NodeId;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Name of the client that stands in for outside traffic in topologies
+/// imported from manifests.
+pub const IMPORT_CLIENT: &str = "users";
+
+/// Why a set of manifests could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestError {
+    /// 1-based position of the YAML document in the stream; 0 when the
+    /// problem is with the manifests as a whole or with a single-document
+    /// file such as docker-compose
+    pub document: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.document == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "document {}: {}", self.document, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+type Labels = BTreeMap<String, String>;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ObjectMeta {
+    name: String,
+    labels: Labels,
+}
+
+/// Any object; `spec` is read once the kind is known. `List` objects
+/// carry theirs in `items`.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Manifest {
+    kind: String,
+    metadata: ObjectMeta,
+    spec: serde_yaml::Value,
+    items: Vec<Manifest>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct WorkloadSpec {
+    replicas: Option<u32>,
+    template: PodTemplate,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PodTemplate {
+    metadata: ObjectMeta,
+    spec: PodSpec,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct PodSpec {
+    containers: Vec<Container>,
+    init_containers: Vec<Container>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Container {
+    env: Vec<EnvVar>,
+    args: Vec<String>,
+    command: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct EnvVar {
+    value: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ServiceSpec {
+    selector: Labels,
+    #[serde(rename = "type")]
+    service_type: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct IngressSpec {
+    default_backend: Option<IngressBackend>,
+    rules: Vec<IngressRule>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct IngressRule {
+    http: Option<IngressHttp>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct IngressHttp {
+    paths: Vec<IngressPath>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct IngressPath {
+    backend: IngressBackend,
+}
+
+/// `service.name` in networking.k8s.io/v1, `serviceName` before it.
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct IngressBackend {
+    service: Option<IngressService>,
+    service_name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct IngressService {
+    name: String,
+}
+
+impl IngressBackend {
+    fn service_name(&self) -> Option<&str> {
+        self.service
+            .as_ref()
+            .map(|s| s.name.as_str())
+            .or(self.service_name.as_deref())
+    }
+}
+
+/// A Deployment, StatefulSet or ReplicaSet.
+struct Workload {
+    name: String,
+    replicas: usize,
+    labels: Labels,
+    /// Env values and arguments, searched for the names of services the
+    /// pods call
+    settings: Vec<String>,
+}
+
+struct Service {
+    name: String,
+    selector: Labels,
+    /// Reachable from outside the cluster
+    exposed: bool,
+}
+
+impl Service {
+    fn selects(&self, workload: &Workload) -> bool {
+        !self.selector.is_empty()
+            && self
+                .selector
+                .iter()
+                .all(|(k, v)| workload.labels.get(k) == Some(v))
+    }
+}
+
+/// Whether `text` mentions `service` as a host, as in `http://orders:8080`
+/// or `orders.shop.svc.cluster.local`.
+fn mentions_host(text: &str, service: &str) -> bool {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '.'))
+        .any(|host| {
+            host == service
+                || host
+                    .strip_prefix(service)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+}
+
+#[derive(Default)]
+struct Cluster {
+    workloads: Vec<Workload>,
+    services: Vec<Service>,
+    ingress_backends: Vec<String>,
+}
+
+impl Cluster {
+    fn add(&mut self, manifest: Manifest, document: usize) -> Result<(), ManifestError> {
+        let fail = |e: serde_yaml::Error| ManifestError {
+            document,
+            message: format!("{} '{}': {}", manifest.kind, manifest.metadata.name, e),
+        };
+        match manifest.kind.as_str() {
+            "List" => {
+                for item in manifest.items {
+                    self.add(item, document)?;
+                }
+            }
+            "Deployment" | "StatefulSet" | "ReplicaSet" => {
+                let spec: WorkloadSpec =
+                    serde_yaml::from_value(manifest.spec.clone()).map_err(fail)?;
+                let containers = spec
+                    .template
+                    .spec
+                    .containers
+                    .iter()
+                    .chain(&spec.template.spec.init_containers);
+                let settings = containers
+                    .flat_map(|c| {
+                        let env = c.env.iter().filter_map(|e| e.value.clone());
+                        env.chain(c.args.iter().cloned())
+                            .chain(c.command.iter().cloned())
+                    })
+                    .collect();
+                self.workloads.push(Workload {
+                    name: manifest.metadata.name,
+                    replicas: spec.replicas.unwrap_or(1) as usize,
+                    labels: spec.template.metadata.labels,
+                    settings,
+                });
+            }
+            "Service" => {
+                let spec: ServiceSpec =
+                    serde_yaml::from_value(manifest.spec.clone()).map_err(fail)?;
+                self.services.push(Service {
+                    name: manifest.metadata.name,
+                    selector: spec.selector,
+                    exposed: matches!(
+                        spec.service_type.as_deref(),
+                        Some("LoadBalancer") | Some("NodePort")
+                    ),
+                });
+            }
+            "Ingress" => {
+                let spec: IngressSpec =
+                    serde_yaml::from_value(manifest.spec.clone()).map_err(fail)?;
+                let paths = spec.rules.iter().flat_map(|r| r.http.iter());
+                let backends = paths
+                    .flat_map(|h| h.paths.iter().map(|p| &p.backend))
+                    .chain(spec.default_backend.as_ref());
+                for name in backends.filter_map(IngressBackend::service_name) {
+                    self.ingress_backends.push(name.to_string());
+                }
+            }
+            // ConfigMaps, RBAC and the like do not shape the traffic
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Adds the clients standing in for outside traffic to a topology being
+/// imported, one per entry point since a client has a single target:
+/// `users` when there is one entry point, `users-<entry>` otherwise. With
+/// none, a lone `users` client is left to connect by hand.
+pub(crate) fn import_clients(mut topology: Topology, entries: &[&str]) -> Topology {
+    if entries.is_empty() {
+        return topology.client(IMPORT_CLIENT, 10.0);
+    }
+    for entry in entries {
+        let name = if entries.len() == 1 {
+            IMPORT_CLIENT.to_string()
+        } else {
+            format!("{}-{}", IMPORT_CLIENT, entry)
+        };
+        topology = topology.client(&name, 10.0);
+    }
+    topology
+}
+
+impl Topology {
+    /// A starter topology with the shape of a cluster, from Kubernetes
+    /// manifests (several YAML documents, `List`s allowed).
+    ///
+    /// Every Deployment, StatefulSet or ReplicaSet becomes a group of
+    /// default servers, one per replica, named `name-1`, `name-2`, ...
+    /// Every Service becomes a load balancer in front of the servers its
+    /// selector matches, which are registered under the service. Pods call
+    /// the first service an env value, argument or command names as a
+    /// host. A `users` client sends traffic to each service exposed by an
+    /// Ingress or as `LoadBalancer`/`NodePort`, or, if none are, to each
+    /// one no pod calls; with several, the clients are named
+    /// `users-<service>`. Service times, concurrency and rates are
+    /// defaults to tune afterwards.
+    ///
+    /// ```
+    /// use slay_core::Topology;
+    ///
+    /// let topology = Topology::from_kubernetes(r#"
+    /// apiVersion: apps/v1
+    /// kind: Deployment
+    /// metadata: { name: web }
+    /// spec:
+    ///   replicas: 3
+    ///   template:
+    ///     metadata: { labels: { app: web } }
+    /// ---
+    /// apiVersion: v1
+    /// kind: Service
+    /// metadata: { name: web }
+    /// spec: { type: LoadBalancer, selector: { app: web } }
+    /// "#).unwrap();
+    /// // users -> web (load balancer) -> web-1, web-2, web-3
+    /// assert_eq!(topology.nodes.len(), 5);
+    /// assert_eq!(topology.nodes[1].targets, vec![3, 4, 5]);
+    /// ```
+    pub fn from_kubernetes(yaml: &str) -> Result<Topology, ManifestError> {
+        let mut cluster = Cluster::default();
+        for (i, document) in serde_yaml::Deserializer::from_str(yaml).enumerate() {
+            let error = |e: serde_yaml::Error| ManifestError {
+                document: i + 1,
+                message: e.to_string(),
+            };
+            let value = serde_yaml::Value::deserialize(document).map_err(error)?;
+            if value.is_null() {
+                continue;
+            }
+            let manifest: Manifest = serde_yaml::from_value(value).map_err(error)?;
+            cluster.add(manifest, i + 1)?;
+        }
+        if cluster.workloads.is_empty() && cluster.services.is_empty() {
+            return Err(ManifestError {
+                document: 0,
+                message: "no Deployments, StatefulSets or Services found".to_string(),
+            });
+        }
+
+        // The service each workload calls; a server forwards each request
+        // to one target, so pods call the first service they name
+        let calls: Vec<Option<usize>> = cluster
+            .workloads
+            .iter()
+            .map(|w| {
+                (0..cluster.services.len()).find(|&s| {
+                    let service = &cluster.services[s];
+                    !service.selects(w)
+                        && w.settings.iter().any(|v| mentions_host(v, &service.name))
+                })
+            })
+            .collect();
+        let exposed: Vec<usize> = (0..cluster.services.len())
+            .filter(|&s| {
+                let service = &cluster.services[s];
+                service.exposed || cluster.ingress_backends.contains(&service.name)
+            })
+            .collect();
+        let entry_services = if exposed.is_empty() {
+            (0..cluster.services.len())
+                .filter(|&s| !calls.contains(&Some(s)))
+                .collect()
+        } else {
+            exposed
+        };
+        // With nothing fronting the pods, traffic goes straight to them
+        let pod_names: Vec<String> = cluster
+            .workloads
+            .iter()
+            .flat_map(|w| (1..=w.replicas).map(move |i| format!("{}-{}", w.name, i)))
+            .collect();
+        let entries: Vec<&str> = if cluster.services.is_empty() {
+            pod_names.iter().map(String::as_str).collect()
+        } else {
+            entry_services
+                .iter()
+                .map(|&s| cluster.services[s].name.as_str())
+                .collect()
+        };
+
+        let mut topology = import_clients(Topology::new(), &entries);
+        let mut service_ids: Vec<NodeId> = Vec::new();
+        for service in &cluster.services {
+            topology = topology.lb(&service.name);
+            service_ids.push(topology.max_node_id());
+        }
+        let mut workload_ids: Vec<Vec<NodeId>> = Vec::new();
+        for workload in &cluster.workloads {
+            let first = topology.max_node_id() + 1;
+            topology = topology.servers(&workload.name, workload.replicas, |_| {});
+            workload_ids.push((first..=topology.max_node_id()).collect());
+        }
+
+        let connect = |topology: &mut Topology, from: NodeId, to: NodeId| {
+            let node = topology.nodes.iter_mut().find(|n| n.id == from).unwrap();
+            if !node.targets.contains(&to) {
+                node.targets.push(to);
+            }
+        };
+        // The clients come first, one per entry point
+        let entry_ids: Vec<NodeId> = if cluster.services.is_empty() {
+            workload_ids.concat()
+        } else {
+            entry_services.iter().map(|&s| service_ids[s]).collect()
+        };
+        for (client, &id) in entry_ids.iter().enumerate() {
+            connect(&mut topology, client as NodeId + 1, id);
+        }
+        for (s, service) in cluster.services.iter().enumerate() {
+            for (w, workload) in cluster.workloads.iter().enumerate() {
+                if !service.selects(workload) {
+                    continue;
+                }
+                for &id in &workload_ids[w] {
+                    connect(&mut topology, service_ids[s], id);
+                    let node = topology.nodes.iter_mut().find(|n| n.id == id).unwrap();
+                    node.services.push(service.name.clone());
+                }
+            }
+        }
+        for (w, called) in calls.iter().enumerate() {
+            if let Some(s) = *called {
+                for &id in &workload_ids[w] {
+                    connect(&mut topology, id, service_ids[s]);
+                }
+            }
+        }
+        Ok(topology)
+    }
+}
@@ -1,16 +1,102 @@
 pub mod analytics;
+pub mod backpressure;
+pub mod brownout;
+pub mod builder;
+pub mod calibration;
+pub mod chaos;
 pub mod components;
+pub mod compose;
+pub mod cost;
+pub mod debugger;
+pub mod design;
+pub mod discovery;
+pub mod dot;
 pub mod engine;
+pub mod experiment;
+pub mod failure;
+pub mod fluid;
+pub mod hosted;
+pub mod kubernetes;
+pub mod mermaid;
+pub mod metadata;
+pub mod otlp;
+pub mod payload;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod profiler;
+pub mod queue;
+pub mod queueing;
+pub mod recorder;
+pub mod replay;
+pub mod report;
+pub mod runner;
+pub mod scenario;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod stress;
+pub mod templates;
+pub mod topology;
+pub mod trace;
 pub mod traits;
+pub mod transit;
+pub mod validation;
 
-pub use analytics::{MetricPoint, MetricsCollector};
-pub use components::client::{Client, ClientConfig};
-pub use components::create_component;
+pub use analytics::{
+    percentile_label, Annotation, EdgeMetricPoint, KindMetricPoint, MetricPoint, MetricsCollector,
+    NodeMetricPoint, RetryAmplification, WindowedHistogram, STANDARD_PERCENTILES,
+};
+pub use backpressure::DEFAULT_MAX_PENDING_EVENTS;
+pub use brownout::Brownout;
+pub use calibration::{Calibration, CalibrationError, LatencyTable};
+pub use chaos::{ChaosReport, ChaosSample, FaultReport, PhaseMetrics};
+pub use components::client::{Client, ClientConfig, MAX_BATCH_ARRIVALS};
 pub use components::load_balancer::{LoadBalancer, LoadBalancerConfig};
 pub use components::server::{Server, ServerConfig};
-pub use engine::{Event, EventType, ScheduleCmd, Simulation, SystemInspector};
-pub use network::{canonical_key, EdgeConfig, Link};
-pub use traits::{Component, NodeId};
+pub use components::{
+    component_kinds, create_component, register_component, registered_components, ComponentFactory,
+    ComponentRegistration, CustomStats, StatValue,
+};
+pub use cost::{CostLedger, CostRate};
+pub use debugger::{Breakpoint, StepResult};
+pub use design::{Design, DesignError};
+pub use discovery::ServiceRegistry;
+pub use dot::DotError;
+pub use engine::{
+    stream_seed, Commands, Event, EventHandle, EventType, NodeLoad, Path, ScheduleCmd, Simulation,
+    SystemInspector,
+};
+pub use experiment::{linear_rates, Comparison, CurvePoint, Experiment, LoadCurve, RunMetrics};
+pub use failure::FailureReason;
+pub use fluid::{FluidMode, FluidNode, FluidState};
+pub use hosted::{Decision, EventInput, GuestLogic, HostedComponent};
+pub use kubernetes::ManifestError;
+pub use metadata::Metadata;
+pub use network::{canonical_key, EdgeConfig, EdgeCounters, Link};
+pub use payload::SizeDistribution;
+#[cfg(feature = "plugins")]
+pub use plugin::{load_plugin, plugin_kinds, PluginComponent, PluginError};
+pub use profiler::{
+    CountingAllocator, HandlerCost, NodeProfile, ProfileReport, Profiler, WallClock,
+};
+pub use queue::{CalendarQueue, PendingEvents};
+pub use queueing::{LiveLoad, MmcModel, QueueingCheck};
+pub use recorder::MacroRecorder;
+pub use replay::{EventLog, Replay};
+pub use report::{FailureCount, NodeReport, RunReport, RunSettings, TopologySummary};
+pub use runner::{RunCondition, RunOutcome};
+pub use scenario::{Scenario, ScenarioAction, ScenarioStep};
+#[cfg(feature = "scripting")]
+pub use script::{Script, ScriptedComponent, DEFAULT_SCRIPT, SCRIPTED_KIND};
+pub use smallvec::smallvec;
+pub use stress::stress_topology;
+pub use templates::{
+    fault_template, template, FaultTemplate, Template, FAULT_TEMPLATES, TEMPLATES,
+};
+pub use topology::{LinkSpec, NodeSpec, Topology};
+pub use trace::{Hop, LatencyBreakdown, LatencySplit, Segment, Span, Trace, Tracer};
+pub use traits::{Component, Health, NodeId};
+pub use transit::Transit;
+pub use validation::{Diagnostic, Severity};
 pub mod network;
 
 pub const PROCESS_OVERHEAD_US: u64 = 2_000;
@@ -0,0 +1,79 @@
+use crate::network::{canonical_key, EdgeConfig};
+use crate::topology::{NodeSpec, Topology};
+use std::fmt::Write as _;
+
+/// `text` safe inside a quoted Mermaid label.
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// Opening and closing brackets of a kind's node shape.
+fn brackets(kind: &str) -> (&'static str, &'static str) {
+    match kind {
+        "Client" => ("([", "])"),
+        "LoadBalancer" => ("{{", "}}"),
+        _ => ("[", "]"),
+    }
+}
+
+fn write_node(out: &mut String, node: &NodeSpec, indent: &str) {
+    let mut label = escape(node.label());
+    if let Some(summary) = node.summary() {
+        label.push_str("<br/>");
+        label.push_str(&escape(&summary));
+    }
+    let (open, close) = brackets(&node.kind);
+    let _ = writeln!(out, "{}n{}{}\"{}\"{}", indent, node.id, open, label, close);
+}
+
+impl Topology {
+    /// The topology as a Mermaid flowchart, for Markdown design docs and
+    /// READMEs. Labels show each node's key settings and each non-default
+    /// link; nodes of a service are drawn inside a box named after it.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        // A node is boxed with the first service it belongs to
+        let mut services: Vec<&str> = Vec::new();
+        for service in self.nodes.iter().filter_map(|n| n.services.first()) {
+            if !services.contains(&service.as_str()) {
+                services.push(service);
+            }
+        }
+        for node in self.nodes.iter().filter(|n| n.services.is_empty()) {
+            write_node(&mut out, node, "    ");
+        }
+        for (i, service) in services.iter().enumerate() {
+            let _ = writeln!(out, "    subgraph s{}[\"{}\"]", i + 1, escape(service));
+            for node in &self.nodes {
+                if node.services.first().map(String::as_str) == Some(service) {
+                    write_node(&mut out, node, "        ");
+                }
+            }
+            out.push_str("    end\n");
+        }
+        for node in &self.nodes {
+            for &to in &node.targets {
+                let key = canonical_key(node.id, to);
+                let edge = self
+                    .links
+                    .iter()
+                    .find(|l| (l.a, l.b) == key)
+                    .map(|l| *l.link.get_config(node.id, to))
+                    .filter(|e| *e != EdgeConfig::default());
+                let _ = match edge {
+                    Some(edge) => writeln!(
+                        out,
+                        "    n{} -->|\"{}\"| n{}",
+                        node.id,
+                        escape(&edge.summary()),
+                        to
+                    ),
+                    None => writeln!(out, "    n{} --> n{}", node.id, to),
+                };
+            }
+        }
+        out
+    }
+}
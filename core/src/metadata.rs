@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Session identifier, for sticky routing.
+pub const SESSION_ID: &str = "session_id";
+/// Tenant the request belongs to.
+pub const TENANT: &str = "tenant";
+/// Scheduling priority; higher is more important.
+pub const PRIORITY: &str = "priority";
+/// Request body size in bytes.
+pub const PAYLOAD_BYTES: &str = "payload_bytes";
+/// Response body size in bytes, set by whoever answered the request.
+pub const RESPONSE_BYTES: &str = "response_bytes";
+/// Which retry of the request this is, set by the load balancer that
+/// retried it. Absent on first attempts; calls made downstream on behalf
+/// of a retry carry it too.
+pub const ATTEMPT: &str = "attempt";
+
+/// Per-request key/value data carried on `Arrival`, `ProcessComplete` and
+/// `Response` events.
+///
+/// Components may read and write entries as a request passes through them;
+/// whatever is set travels on with the request and back with its response.
+/// An empty bag does not allocate, and copies share storage until written.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Metadata(Option<Arc<BTreeMap<String, Value>>>);
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.0.as_ref().is_none_or(|m| m.is_empty())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.as_ref()?.get(key)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key)?.as_u64()
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key)?.as_i64()
+    }
+
+    /// Sets `key`, returning the previous value.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        Arc::make_mut(self.0.get_or_insert_with(Default::default)).insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let map = self.0.as_mut()?;
+        let removed = Arc::make_mut(map).remove(key);
+        if map.is_empty() {
+            self.0 = None;
+        }
+        removed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0
+            .iter()
+            .flat_map(|m| m.iter().map(|(k, v)| (k.as_str(), v)))
+    }
+
+    /// Builder-style `insert`.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.insert(key, value);
+        self
+    }
+}
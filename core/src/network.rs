@@ -7,6 +7,8 @@ pub struct EdgeConfig {
     pub latency_us: u64,       // Base one-way latency
     pub jitter_us: u64,        // Amplitude of random variation
     pub packet_loss_rate: f32, // Probability of packet drop (0.0 - 1.0)
+    #[serde(default)]
+    pub bandwidth_mbps: f32, // Megabits sent per second (0.0 - unlimited)
 }
 
 impl Default for EdgeConfig {
@@ -15,28 +17,61 @@ impl Default for EdgeConfig {
             latency_us: 10_000,
             jitter_us: 0,
             packet_loss_rate: 0.0,
+            bandwidth_mbps: 0.0,
         }
     }
 }
 
+impl EdgeConfig {
+    /// Latency, jitter and loss in a few words, e.g. "2ms ±1ms · 1% loss".
+    pub fn summary(&self) -> String {
+        let mut text = format!("{}ms", trim_number(self.latency_us as f64 / 1000.0));
+        if self.jitter_us > 0 {
+            text.push_str(&format!(
+                " ±{}ms",
+                trim_number(self.jitter_us as f64 / 1000.0)
+            ));
+        }
+        if self.packet_loss_rate > 0.0 {
+            text.push_str(&format!(
+                " · {}% loss",
+                trim_number(self.packet_loss_rate as f64 * 100.0)
+            ));
+        }
+        if self.bandwidth_mbps > 0.0 {
+            text.push_str(&format!(
+                " · {} Mbps",
+                trim_number(self.bandwidth_mbps as f64)
+            ));
+        }
+        text
+    }
+
+    /// Time to put `bytes` on the wire at `bandwidth_mbps`; zero on an
+    /// unlimited link.
+    pub fn transmit_us(&self, bytes: u64) -> u64 {
+        if self.bandwidth_mbps <= 0.0 {
+            return 0;
+        }
+        (bytes as f64 * 8.0 / self.bandwidth_mbps as f64).ceil() as u64
+    }
+}
+
+/// `value` with at most one decimal and no trailing ".0".
+pub(crate) fn trim_number(value: f64) -> String {
+    let text = format!("{:.1}", value);
+    text.strip_suffix(".0").map_or(text.clone(), str::to_string)
+}
+
 /// A physical link between two nodes.
 /// It contains configuration for both directions.
 /// Stored by canonical key (min_id, max_id).
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Link {
     pub min_to_max: EdgeConfig,
     pub max_to_min: EdgeConfig,
 }
 
-impl Default for Link {
-    fn default() -> Self {
-        Self {
-            min_to_max: EdgeConfig::default(),
-            max_to_min: EdgeConfig::default(),
-        }
-    }
-}
-
 impl Link {
     pub fn get_config(&self, from: NodeId, to: NodeId) -> &EdgeConfig {
         if from < to {
@@ -55,6 +90,26 @@ impl Link {
     }
 }
 
+/// Running traffic totals for one direction of a link.
+#[derive(Serialize, Deserialize, Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub struct EdgeCounters {
+    /// Messages (requests and responses) put on the link
+    pub sent: u64,
+    /// Messages lost to `packet_loss_rate`
+    pub dropped: u64,
+    /// Requests put on the link, first attempts and retries
+    #[serde(default)]
+    pub requests: u64,
+    /// Requests that retried an earlier, failed attempt
+    #[serde(default)]
+    pub retries: u64,
+    /// Latency plus jitter added to delivered messages, summed
+    pub added_latency_us: u64,
+    /// Payload bytes put on the link
+    #[serde(default)]
+    pub bytes: u64,
+}
+
 pub fn canonical_key(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
     if a < b {
         (a, b)
@@ -0,0 +1,134 @@
+use crate::engine::{stream_seed, Simulation};
+use crate::trace::{Span, Trace};
+use crate::traits::NodeId;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+// OTLP enum values
+const SPAN_KIND_SERVER: u32 = 2;
+const SPAN_KIND_CLIENT: u32 = 3;
+const STATUS_UNSET: u32 = 0;
+const STATUS_OK: u32 = 1;
+const STATUS_ERROR: u32 = 2;
+
+impl Simulation {
+    /// Sampled traces as an OTLP/JSON `ExportTraceServiceRequest`, ready to
+    /// POST to a collector's `/v1/traces` endpoint (Jaeger, Tempo, ...).
+    ///
+    /// Every node becomes a service named after the component, every span a
+    /// span of that service, parented to the span of the node that called
+    /// it. Virtual time is offset by `epoch_unix_ns` so traces land at a
+    /// sensible wall-clock date. Returns `None` when tracing is disabled.
+    pub fn otlp_traces(&self, epoch_unix_ns: u64) -> Option<Value> {
+        let tracer = self.tracer.as_ref()?;
+        let mut by_node: BTreeMap<NodeId, Vec<Value>> = BTreeMap::new();
+        for trace in &tracer.completed {
+            let ids: Vec<u64> = (0..trace.spans.len()).map(|i| span_id(trace, i)).collect();
+            for (i, span) in trace.spans.iter().enumerate() {
+                let mut otlp = json!({
+                    "traceId": format!("{:032x}", trace.request_id),
+                    "spanId": format!("{:016x}", ids[i]),
+                    "name": self.kind_of(span.node_id),
+                    "kind": if i == 0 { SPAN_KIND_CLIENT } else { SPAN_KIND_SERVER },
+                    "startTimeUnixNano": unix_ns(epoch_unix_ns, span.start_us),
+                    "endTimeUnixNano": unix_ns(
+                        epoch_unix_ns,
+                        span.end_us.or(trace.end_us).unwrap_or(span.start_us),
+                    ),
+                    "attributes": span_attributes(span),
+                    "status": { "code": match span.success {
+                        Some(true) => STATUS_OK,
+                        Some(false) => STATUS_ERROR,
+                        None => STATUS_UNSET,
+                    }},
+                });
+                if let Some(parent) = parent_index(trace, i) {
+                    otlp["parentSpanId"] = json!(format!("{:016x}", ids[parent]));
+                }
+                by_node.entry(span.node_id).or_default().push(otlp);
+            }
+        }
+
+        let resource_spans: Vec<Value> = by_node
+            .into_iter()
+            .map(|(node_id, spans)| {
+                json!({
+                    "resource": { "attributes": [
+                        string_attr("service.name", &self.name_of(node_id)),
+                        int_attr("slay.node_id", node_id as u64),
+                    ]},
+                    "scopeSpans": [{
+                        "scope": { "name": "slay", "version": env!("CARGO_PKG_VERSION") },
+                        "spans": spans,
+                    }],
+                })
+            })
+            .collect();
+        Some(json!({ "resourceSpans": resource_spans }))
+    }
+
+    fn name_of(&self, node_id: NodeId) -> String {
+        self.components
+            .get(&node_id)
+            .map_or_else(|| format!("node-{}", node_id), |c| c.name().to_string())
+    }
+
+    fn kind_of(&self, node_id: NodeId) -> String {
+        self.components
+            .get(&node_id)
+            .map_or_else(|| "request".to_string(), |c| c.kind().to_string())
+    }
+}
+
+/// Span ids only need to be unique within a trace; derive them from the
+/// request so exports are reproducible.
+fn span_id(trace: &Trace, index: usize) -> u64 {
+    stream_seed(
+        (trace.request_id ^ (trace.request_id >> 64)) as u64,
+        index as NodeId,
+        "otlp-span",
+    )
+    .max(1)
+}
+
+/// The caller of span `index`: the latest earlier span of the node whose
+/// message arrived when the span started.
+fn parent_index(trace: &Trace, index: usize) -> Option<usize> {
+    let span = trace.spans.get(index)?;
+    let caller = trace
+        .hops
+        .iter()
+        .find(|h| h.to == span.node_id && h.arrived_us == Some(span.start_us))
+        .map(|h| h.from)
+        .or_else(|| (index > 0).then(|| trace.spans[0].node_id))?;
+    trace.spans[..index]
+        .iter()
+        .rposition(|s| s.node_id == caller)
+}
+
+fn span_attributes(span: &Span) -> Vec<Value> {
+    let mut attrs = vec![int_attr("slay.node_id", span.node_id as u64)];
+    for (key, value) in [
+        ("slay.queue_us", span.queue_us),
+        ("slay.service_us", span.service_us),
+        ("slay.processing_us", span.processing_us),
+    ] {
+        if value > 0 {
+            attrs.push(int_attr(key, value));
+        }
+    }
+    attrs
+}
+
+fn unix_ns(epoch_unix_ns: u64, sim_us: u64) -> String {
+    // OTLP/JSON encodes 64-bit integers as strings
+    (epoch_unix_ns + sim_us * 1000).to_string()
+}
+
+fn string_attr(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn int_attr(key: &str, value: u64) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
@@ -0,0 +1,53 @@
+use crate::engine::EventType;
+use crate::metadata::{Metadata, PAYLOAD_BYTES, RESPONSE_BYTES};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Sizes of the messages a component sends: log-normal around
+/// `median_bytes`, the long-tailed shape payloads usually have.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizeDistribution {
+    pub median_bytes: u64,
+    /// Standard deviation of the size's logarithm; 0 makes every message
+    /// the median size
+    #[serde(default)]
+    pub spread: f32,
+}
+
+impl SizeDistribution {
+    /// Every message `bytes` long.
+    pub fn fixed(bytes: u64) -> Self {
+        Self {
+            median_bytes: bytes,
+            spread: 0.0,
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> u64 {
+        if self.spread <= 0.0 {
+            return self.median_bytes;
+        }
+        // Box-Muller transform for a standard normal
+        let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = rng.gen();
+        let normal = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        (self.median_bytes as f64 * (self.spread as f64 * normal).exp()).round() as u64
+    }
+
+    /// Sets `key` on `metadata` to a sampled size.
+    pub(crate) fn stamp(&self, rng: &mut impl Rng, metadata: &mut Metadata, key: &str) {
+        metadata.insert(key, self.sample(rng));
+    }
+}
+
+/// Bytes the message `event_type` puts on a link: the request body of an
+/// arrival, the response body of a response, nothing for anything else or
+/// for messages without a size.
+pub fn message_bytes(event_type: &EventType) -> u64 {
+    match event_type {
+        EventType::Arrival { metadata, .. } => metadata.get_u64(PAYLOAD_BYTES),
+        EventType::Response { metadata, .. } => metadata.get_u64(RESPONSE_BYTES),
+        _ => None,
+    }
+    .unwrap_or(0)
+}
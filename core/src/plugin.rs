@@ -0,0 +1,315 @@
+use crate::components::{register_component, ComponentRegistration, BUILTIN_KINDS};
+use crate::hosted::{Decision, EventInput, GuestLogic, HostedComponent};
+use crate::traits::Component;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use wasmi::{Engine, Instance, Linker, Memory, Module, Store, TypedFunc};
+
+/// Instructions a guest may execute per call before it is cut off, so a
+/// runaway plugin fails requests instead of hanging the simulation.
+pub const FUEL_PER_CALL: u64 = 10_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginError {
+    pub message: String,
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+impl From<wasmi::Error> for PluginError {
+    fn from(err: wasmi::Error) -> Self {
+        PluginError {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn error<T>(message: impl Into<String>) -> Result<T, PluginError> {
+    Err(PluginError {
+        message: message.into(),
+    })
+}
+
+/// What `slay_info` describes.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginInfo {
+    kind: String,
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    description: String,
+    /// Default config; its keys are the only ones the plugin accepts
+    #[serde(default)]
+    config: serde_json::Map<String, Value>,
+}
+
+/// A compiled plugin, instantiated once per node.
+struct Plugin {
+    kind: &'static str,
+    label: &'static str,
+    defaults: serde_json::Map<String, Value>,
+    engine: Engine,
+    module: Arc<Module>,
+}
+
+static PLUGINS: RwLock<Vec<Arc<Plugin>>> = RwLock::new(Vec::new());
+
+/// Loads a component compiled to WebAssembly and registers its kind, so it
+/// can be placed from the palette, used in topologies and created with
+/// `create_component`. Loading a kind again replaces the previous module
+/// for nodes created from then on. Returns the kind.
+///
+/// # Guest ABI
+///
+/// Data crosses the boundary as UTF-8 JSON in the guest's linear memory.
+/// The host passes a buffer as `(ptr, len)` after allocating it with
+/// `slay_alloc`; the guest answers with an `i64` packing `ptr << 32 | len`
+/// (`0` for no answer). Either buffer may be reused by the guest on its
+/// next call. The module imports nothing and exports:
+///
+/// - `memory`
+/// - `slay_alloc(len: i32) -> i32`
+/// - `slay_info() -> i64`: `{"kind", "label", "description", "config"}`,
+///   where `config` holds the defaults of every config field
+/// - `slay_on_event(ptr: i32, len: i32) -> i64`: called when a request
+///   arrives and when a request this node forwarded comes back, with
+///   `{"event": "arrival" | "response", "request", "time_us",
+///   "deadline_us", "success", "targets", "healthy", "metadata"}`, in
+///   that order; `success` is only set on responses.
+///   It answers `{"respond": {"success", "delay_us"}}` or
+///   `{"forward": {"to", "delay_us"}}`. No answer fails an arrival and
+///   passes a response back unchanged.
+/// - optionally `slay_configure(ptr: i32, len: i32) -> i64`: receives the
+///   full config and may answer with a corrected one
+/// - optionally `slay_snapshot() -> i64`: an object shown in the
+///   inspector next to the host's own counters
+/// - optionally `slay_seed(seed: i64)`: for plugins using randomness
+///
+/// Requests whose handler traps or runs out of fuel fail with a backend
+/// error; `HostedComponent::last_error` keeps the reason.
+pub fn load_plugin(wasm: &[u8]) -> Result<&'static str, PluginError> {
+    let mut config = wasmi::Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Arc::new(Module::new(&engine, wasm)?);
+    let mut guest = WasmGuest::instantiate(&engine, &module)?;
+    let info = guest.call_info()?.ok_or_else(|| PluginError {
+        message: "slay_info returned nothing".to_string(),
+    })?;
+    let info: PluginInfo = serde_json::from_slice(&info).map_err(|e| PluginError {
+        message: format!("invalid slay_info: {}", e),
+    })?;
+    if info.kind.is_empty() || BUILTIN_KINDS.contains(&info.kind.as_str()) {
+        return error(format!("a plugin cannot provide kind '{}'", info.kind));
+    }
+
+    let leak = |s: String| -> &'static str { Box::leak(s.into_boxed_str()) };
+    let kind = leak(info.kind.clone());
+    let label = leak(info.label.unwrap_or(info.kind));
+    let description = leak(info.description);
+    let mut plugins = PLUGINS.write().unwrap();
+    plugins.retain(|p| p.kind != kind);
+    plugins.push(Arc::new(Plugin {
+        kind,
+        label,
+        defaults: info.config,
+        engine,
+        module,
+    }));
+    drop(plugins);
+    register_component(ComponentRegistration {
+        kind,
+        label,
+        description,
+        factory: |_| None,
+    });
+    Ok(kind)
+}
+
+/// Kinds loaded with `load_plugin`, in load order.
+pub fn plugin_kinds() -> Vec<&'static str> {
+    PLUGINS.read().unwrap().iter().map(|p| p.kind).collect()
+}
+
+/// A node of a plugin kind; `None` for other kinds.
+pub(crate) fn create(kind: &str, data: &Value) -> Option<Box<dyn Component>> {
+    let plugin = PLUGINS
+        .read()
+        .unwrap()
+        .iter()
+        .find(|p| p.kind == kind)
+        .cloned()?;
+    let guest = WasmGuest::instantiate(&plugin.engine, &plugin.module).ok()?;
+    let mut component =
+        PluginComponent::new(plugin.kind, plugin.label, plugin.defaults.clone(), guest);
+    component.apply_config(data.clone(), 0);
+    Some(Box::new(component))
+}
+
+/// A node running a plugin.
+pub type PluginComponent = HostedComponent<WasmGuest>;
+
+/// One instance of a plugin module and its exports.
+pub struct WasmGuest {
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    info: TypedFunc<(), i64>,
+    on_event: TypedFunc<(i32, i32), i64>,
+    configure: Option<TypedFunc<(i32, i32), i64>>,
+    snapshot: Option<TypedFunc<(), i64>>,
+    seed: Option<TypedFunc<i64, ()>>,
+}
+
+impl WasmGuest {
+    fn instantiate(engine: &Engine, module: &Module) -> Result<Self, PluginError> {
+        let mut store = Store::new(engine, ());
+        // Start functions and data segments run on the first allowance
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| PluginError {
+            message: e.to_string(),
+        })?;
+        let instance: Instance = Linker::<()>::new(engine)
+            .instantiate(&mut store, module)?
+            .start(&mut store)?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| PluginError {
+                message: "the module exports no memory".to_string(),
+            })?;
+        let required = |name: &str| PluginError {
+            message: format!(
+                "the module does not export {} with the expected signature",
+                name
+            ),
+        };
+        Ok(WasmGuest {
+            alloc: instance
+                .get_typed_func(&store, "slay_alloc")
+                .map_err(|_| required("slay_alloc"))?,
+            info: instance
+                .get_typed_func(&store, "slay_info")
+                .map_err(|_| required("slay_info"))?,
+            on_event: instance
+                .get_typed_func(&store, "slay_on_event")
+                .map_err(|_| required("slay_on_event"))?,
+            configure: instance.get_typed_func(&store, "slay_configure").ok(),
+            snapshot: instance.get_typed_func(&store, "slay_snapshot").ok(),
+            seed: instance.get_typed_func(&store, "slay_seed").ok(),
+            store,
+            memory,
+        })
+    }
+
+    fn refuel(&mut self) -> Result<(), PluginError> {
+        self.store.set_fuel(FUEL_PER_CALL).map_err(|e| PluginError {
+            message: e.to_string(),
+        })
+    }
+
+    /// Copies `input` into a buffer allocated by the guest.
+    fn write(&mut self, input: &[u8]) -> Result<(i32, i32), PluginError> {
+        let len = input.len() as i32;
+        let ptr = self.alloc.call(&mut self.store, len)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, input)
+            .map_err(|e| PluginError {
+                message: format!("slay_alloc returned a bad buffer: {}", e),
+            })?;
+        Ok((ptr, len))
+    }
+
+    /// The buffer a guest answered with, `None` if it had no answer.
+    fn read(&self, packed: i64) -> Result<Option<Vec<u8>>, PluginError> {
+        let (ptr, len) = ((packed as u64 >> 32) as usize, packed as u32 as usize);
+        if len == 0 {
+            return Ok(None);
+        }
+        // Check the range before copying, so a bogus length can't make the
+        // host allocate gigabytes
+        let buf = ptr
+            .checked_add(len)
+            .and_then(|end| self.memory.data(&self.store).get(ptr..end))
+            .ok_or_else(|| PluginError {
+                message: format!(
+                    "the plugin answered with a bad buffer: {} bytes at {} are out of bounds",
+                    len, ptr
+                ),
+            })?;
+        Ok(Some(buf.to_vec()))
+    }
+
+    fn call_info(&mut self) -> Result<Option<Vec<u8>>, PluginError> {
+        self.refuel()?;
+        let packed = self.info.call(&mut self.store, ())?;
+        self.read(packed)
+    }
+
+    fn call_with(
+        &mut self,
+        func: TypedFunc<(i32, i32), i64>,
+        input: &[u8],
+    ) -> Result<Option<Value>, PluginError> {
+        self.refuel()?;
+        let (ptr, len) = self.write(input)?;
+        let packed = func.call(&mut self.store, (ptr, len))?;
+        match self.read(packed)? {
+            Some(answer) => serde_json::from_slice(&answer)
+                .map(Some)
+                .map_err(|e| PluginError {
+                    message: format!("the plugin answered with invalid JSON: {}", e),
+                }),
+            None => Ok(None),
+        }
+    }
+}
+
+impl GuestLogic for WasmGuest {
+    fn decide(&mut self, input: &EventInput) -> Result<Option<Decision>, String> {
+        let input = serde_json::to_vec(input).map_err(|e| e.to_string())?;
+        let answer = self
+            .call_with(self.on_event, &input)
+            .map_err(|e| e.message)?;
+        answer
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| format!("invalid slay_on_event answer: {}", e))
+    }
+
+    fn configure(&mut self, config: &Value) -> Result<Option<Value>, String> {
+        let Some(configure) = self.configure else {
+            return Ok(None);
+        };
+        self.call_with(configure, config.to_string().as_bytes())
+            .map_err(|e| e.message)
+    }
+
+    fn snapshot(&mut self) -> Result<Option<Value>, String> {
+        let Some(snapshot) = self.snapshot else {
+            return Ok(None);
+        };
+        self.refuel().map_err(|e| e.message)?;
+        let packed = snapshot
+            .call(&mut self.store, ())
+            .map_err(|e| e.to_string())?;
+        let answer = self.read(packed).map_err(|e| e.message)?;
+        Ok(answer.and_then(|bytes| serde_json::from_slice(&bytes).ok()))
+    }
+
+    fn seed(&mut self, seed: u64) -> Result<(), String> {
+        let Some(func) = self.seed else {
+            return Ok(());
+        };
+        self.refuel().map_err(|e| e.message)?;
+        func.call(&mut self.store, seed as i64)
+            .map_err(|e| e.to_string())
+    }
+}
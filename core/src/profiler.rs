@@ -0,0 +1,317 @@
+use crate::engine::Simulation;
+use crate::traits::NodeId;
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Reads a wall clock in nanoseconds. The engine has no clock of its own
+/// so the profiler works wherever the caller has one, e.g. in a browser.
+pub type WallClock = fn() -> u64;
+
+/// Wall time between samples of the event rate.
+const RATE_SAMPLE_NS: u64 = 100_000_000;
+/// Samples kept, covering about the last second.
+const RATE_SAMPLES: usize = 11;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static ALLOCATIONS: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Global allocator that counts heap allocations per thread, so the
+/// profiler can tell which components allocate while handling events.
+/// Install it in a binary to get allocation figures:
+///
+/// ```
+/// #[global_allocator]
+/// static ALLOC: slay_core::CountingAllocator = slay_core::CountingAllocator;
+/// # fn main() {}
+/// ```
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// Allocations and bytes requested by the current thread so far, or
+    /// `None` if this allocator is not the global one.
+    pub fn thread_counts() -> Option<(u64, u64)> {
+        if !COUNTING.load(Ordering::Relaxed) {
+            return None;
+        }
+        ALLOCATIONS.try_with(Cell::get).ok()
+    }
+
+    fn count(size: usize) {
+        COUNTING.store(true, Ordering::Relaxed);
+        let _ = ALLOCATIONS.try_with(|c| {
+            let (n, bytes) = c.get();
+            c.set((n + 1, bytes + size as u64));
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// What handling one node's events has cost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct HandlerCost {
+    pub events: u64,
+    /// Wall time spent in the component's `on_event`
+    pub busy_ns: u64,
+    /// Slowest single event
+    pub max_ns: u64,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+/// Measures where the engine's wall time goes (see
+/// `Simulation::enable_profiling`).
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    clock: WallClock,
+    started_ns: u64,
+    /// Events processed since profiling started
+    pub events: u64,
+    /// Wall time spent inside `step`
+    pub step_ns: u64,
+    /// Wall time spent inside components' `on_event`
+    pub handler_ns: u64,
+    /// Most events pending at once
+    pub peak_pending: usize,
+    pub nodes: HashMap<NodeId, HandlerCost>,
+    /// `(wall time, events)` samples for the recent event rate
+    rate: VecDeque<(u64, u64)>,
+}
+
+/// A handler being timed: when it started and the thread's allocation
+/// counts at that moment.
+pub(crate) struct HandlerTimer {
+    started_ns: u64,
+    allocations: Option<(u64, u64)>,
+}
+
+impl Profiler {
+    pub fn new(clock: WallClock) -> Self {
+        let now = clock();
+        Self {
+            clock,
+            started_ns: now,
+            events: 0,
+            step_ns: 0,
+            handler_ns: 0,
+            peak_pending: 0,
+            nodes: HashMap::new(),
+            rate: VecDeque::from([(now, 0)]),
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    pub(crate) fn start_handler(&self) -> HandlerTimer {
+        HandlerTimer {
+            started_ns: self.now(),
+            allocations: CountingAllocator::thread_counts(),
+        }
+    }
+
+    pub(crate) fn end_handler(&mut self, node_id: NodeId, timer: HandlerTimer) {
+        let elapsed = self.now().saturating_sub(timer.started_ns);
+        let cost = self.nodes.entry(node_id).or_default();
+        cost.events += 1;
+        cost.busy_ns += elapsed;
+        cost.max_ns = cost.max_ns.max(elapsed);
+        if let (Some((n0, b0)), Some((n1, b1))) =
+            (timer.allocations, CountingAllocator::thread_counts())
+        {
+            cost.allocations += n1 - n0;
+            cost.allocated_bytes += b1 - b0;
+        }
+        self.handler_ns += elapsed;
+    }
+
+    pub(crate) fn end_step(&mut self, started_ns: u64, pending: usize) {
+        let now = self.now();
+        self.events += 1;
+        self.step_ns += now.saturating_sub(started_ns);
+        self.peak_pending = self.peak_pending.max(pending);
+        if self
+            .rate
+            .back()
+            .is_none_or(|&(t, _)| now >= t + RATE_SAMPLE_NS)
+        {
+            self.rate.push_back((now, self.events));
+            if self.rate.len() > RATE_SAMPLES {
+                self.rate.pop_front();
+            }
+        }
+    }
+
+    /// Events processed per wall-clock second over about the last second,
+    /// counting time the engine sat idle.
+    pub fn events_per_sec(&self) -> f64 {
+        let now = self.now();
+        let Some(&(since, events)) = self.rate.front() else {
+            return 0.0;
+        };
+        let elapsed = now.saturating_sub(since);
+        if elapsed == 0 {
+            return 0.0;
+        }
+        (self.events - events) as f64 * 1e9 / elapsed as f64
+    }
+}
+
+/// A snapshot of a `Profiler`, with nodes named and sorted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileReport {
+    /// Wall time since profiling started
+    pub wall_secs: f64,
+    pub events: u64,
+    /// Events processed per wall-clock second recently
+    pub events_per_sec: f64,
+    /// Events per second of wall time spent stepping: how fast the engine
+    /// could go if nothing else held it back
+    pub capacity_per_sec: f64,
+    /// Share of stepping time spent inside components
+    pub handler_share: f64,
+    pub pending: usize,
+    pub peak_pending: usize,
+    /// Total allocations while handling events, if `CountingAllocator`
+    /// is installed
+    pub allocations: Option<u64>,
+    /// Nodes by time spent handling their events, busiest first
+    pub nodes: Vec<NodeProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeProfile {
+    pub node_id: NodeId,
+    pub name: String,
+    pub kind: String,
+    #[serde(flatten)]
+    pub cost: HandlerCost,
+}
+
+impl NodeProfile {
+    /// Mean wall time of one event.
+    pub fn mean_ns(&self) -> f64 {
+        if self.cost.events == 0 {
+            return 0.0;
+        }
+        self.cost.busy_ns as f64 / self.cost.events as f64
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    /// A plain-text summary listing the ten busiest nodes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} events in {:.2}s: {:.0} events/s, {:.0} events/s while stepping",
+            self.events, self.wall_secs, self.events_per_sec, self.capacity_per_sec
+        )?;
+        writeln!(
+            f,
+            "{:.0}% of stepping in components; {} events pending, {} at most",
+            self.handler_share * 100.0,
+            self.pending,
+            self.peak_pending
+        )?;
+        match self.allocations {
+            Some(n) => writeln!(f, "{} allocations while handling events", n)?,
+            None => writeln!(f, "allocations not counted")?,
+        }
+        writeln!(
+            f,
+            "{:<24} {:<14} {:>10} {:>10} {:>10} {:>10}",
+            "node", "kind", "events", "total ms", "mean µs", "allocs"
+        )?;
+        for node in self.nodes.iter().take(10) {
+            writeln!(
+                f,
+                "{:<24} {:<14} {:>10} {:>10.1} {:>10.2} {:>10}",
+                format!("{} (#{})", node.name, node.node_id),
+                node.kind,
+                node.cost.events,
+                node.cost.busy_ns as f64 / 1e6,
+                node.mean_ns() / 1e3,
+                node.cost.allocations
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Simulation {
+    /// Starts measuring the engine against `clock`: events per wall-clock
+    /// second, pending events, and the time and allocations each node's
+    /// events take to handle. Costs a few clock reads per event until
+    /// `disable_profiling`.
+    pub fn enable_profiling(&mut self, clock: WallClock) {
+        self.profiler = Some(Profiler::new(clock));
+    }
+
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// What the profiler has measured so far, if it is on.
+    pub fn profile(&self) -> Option<ProfileReport> {
+        let profiler = self.profiler.as_ref()?;
+        let mut nodes: Vec<NodeProfile> = profiler
+            .nodes
+            .iter()
+            .map(|(&node_id, &cost)| {
+                let comp = self.components.get(&node_id);
+                NodeProfile {
+                    node_id,
+                    name: comp.map_or("(removed)", |c| c.name()).to_string(),
+                    kind: comp.map_or("", |c| c.kind()).to_string(),
+                    cost,
+                }
+            })
+            .collect();
+        nodes.sort_by(|a, b| {
+            b.cost
+                .busy_ns
+                .cmp(&a.cost.busy_ns)
+                .then(a.node_id.cmp(&b.node_id))
+        });
+        let ratio = |part: f64, whole: f64| if whole > 0.0 { part / whole } else { 0.0 };
+        Some(ProfileReport {
+            wall_secs: profiler.now().saturating_sub(profiler.started_ns) as f64 / 1e9,
+            events: profiler.events,
+            events_per_sec: profiler.events_per_sec(),
+            capacity_per_sec: ratio(profiler.events as f64 * 1e9, profiler.step_ns as f64),
+            handler_share: ratio(profiler.handler_ns as f64, profiler.step_ns as f64),
+            pending: self.events.len(),
+            peak_pending: profiler.peak_pending,
+            allocations: CountingAllocator::thread_counts()
+                .map(|_| nodes.iter().map(|n| n.cost.allocations).sum()),
+            nodes,
+        })
+    }
+}
@@ -0,0 +1,295 @@
+use crate::engine::Event;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Fewest buckets a `CalendarQueue` shrinks to.
+const MIN_BUCKETS: usize = 16;
+/// Bucket width of a new `CalendarQueue`, before it has events to size by.
+const DEFAULT_WIDTH_US: u64 = 1000;
+/// Earliest events sampled to size buckets on a resize.
+const WIDTH_SAMPLE: usize = 64;
+
+fn key(event: &Event) -> (u64, u64) {
+    (event.time, event.seq)
+}
+
+/// Events waiting to be processed, handed out earliest first with ties in
+/// schedule (`seq`) order, whichever structure holds them.
+///
+/// Both structures pop in exactly the same order, so a run does not depend
+/// on which one it uses; they differ only in speed. The calendar queue is
+/// the default and keeps up as hundreds of thousands of events pile up;
+/// the binary heap is kept for comparison (see `benches/event_queue.rs`).
+#[derive(Debug)]
+pub enum PendingEvents {
+    Heap(BinaryHeap<Reverse<Event>>),
+    Calendar(CalendarQueue),
+}
+
+impl Default for PendingEvents {
+    fn default() -> Self {
+        PendingEvents::Calendar(CalendarQueue::new())
+    }
+}
+
+impl PendingEvents {
+    pub fn heap() -> Self {
+        PendingEvents::Heap(BinaryHeap::new())
+    }
+
+    pub fn calendar() -> Self {
+        PendingEvents::Calendar(CalendarQueue::new())
+    }
+
+    pub fn push(&mut self, event: Event) {
+        match self {
+            PendingEvents::Heap(heap) => heap.push(Reverse(event)),
+            PendingEvents::Calendar(calendar) => calendar.push(event),
+        }
+    }
+
+    /// The next event to process. Takes `&mut self` because finding it
+    /// moves the calendar's cursor.
+    pub fn peek(&mut self) -> Option<&Event> {
+        match self {
+            PendingEvents::Heap(heap) => heap.peek().map(|e| &e.0),
+            PendingEvents::Calendar(calendar) => calendar.peek(),
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        match self {
+            PendingEvents::Heap(heap) => heap.pop().map(|e| e.0),
+            PendingEvents::Calendar(calendar) => calendar.pop(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            PendingEvents::Heap(heap) => heap.len(),
+            PendingEvents::Calendar(calendar) => calendar.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every pending event, in no particular order.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &Event> + '_> {
+        match self {
+            PendingEvents::Heap(heap) => Box::new(heap.iter().map(|e| &e.0)),
+            PendingEvents::Calendar(calendar) => Box::new(calendar.iter()),
+        }
+    }
+
+    /// Takes the events matching `remove` out of the queue, earliest first.
+    pub fn remove_where(&mut self, mut remove: impl FnMut(&Event) -> bool) -> Vec<Event> {
+        let mut removed = match self {
+            PendingEvents::Heap(heap) => {
+                let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(heap)
+                    .into_vec()
+                    .into_iter()
+                    .partition(|e| remove(&e.0));
+                *heap = kept.into();
+                removed.into_iter().map(|e| e.0).collect()
+            }
+            PendingEvents::Calendar(calendar) => calendar.remove_where(remove),
+        };
+        removed.sort_unstable_by_key(key);
+        removed
+    }
+}
+
+/// A calendar queue (Brown, 1988): events are hashed by time into a ring
+/// of buckets, each `width` microseconds of one "year" long, so pushing and
+/// popping take constant time on average however many events are pending.
+/// The ring doubles or halves as the queue grows or shrinks, resizing the
+/// buckets to the spacing of the earliest events.
+///
+/// Buckets hold only the ordering key and a slot; the events themselves
+/// stay put in a slab, so neither inserting into a bucket nor resizing the
+/// ring moves them.
+#[derive(Debug)]
+pub struct CalendarQueue {
+    /// Keys by `time / width` modulo the bucket count, earliest first
+    buckets: Vec<VecDeque<Entry>>,
+    width: u64,
+    slots: Vec<Option<Event>>,
+    /// Slots of `slots` free for reuse
+    free: Vec<usize>,
+    len: usize,
+    /// Bucket the search for the earliest event starts from
+    current: usize,
+    /// Start of the year-slice `current` covers; no event is earlier
+    window_start: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    time: u64,
+    seq: u64,
+    slot: usize,
+}
+
+impl Entry {
+    fn key(&self) -> (u64, u64) {
+        (self.time, self.seq)
+    }
+}
+
+impl Default for CalendarQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalendarQueue {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..MIN_BUCKETS).map(|_| VecDeque::new()).collect(),
+            width: DEFAULT_WIDTH_US,
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            current: 0,
+            window_start: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bucket_of(&self, time: u64) -> usize {
+        (time / self.width) as usize & (self.buckets.len() - 1)
+    }
+
+    pub fn push(&mut self, event: Event) {
+        let entry = Entry {
+            time: event.time,
+            seq: event.seq,
+            slot: self.free.pop().unwrap_or(self.slots.len()),
+        };
+        if entry.slot == self.slots.len() {
+            self.slots.push(Some(event));
+        } else {
+            self.slots[entry.slot] = Some(event);
+        }
+        self.insert(entry);
+        self.len += 1;
+        if self.len > 2 * self.buckets.len() {
+            self.resize(2 * self.buckets.len());
+        }
+    }
+
+    fn insert(&mut self, entry: Entry) {
+        if self.len == 0 || entry.time < self.window_start {
+            self.window_start = entry.time - entry.time % self.width;
+            self.current = self.bucket_of(entry.time);
+        }
+        let bucket = self.bucket_of(entry.time);
+        let bucket = &mut self.buckets[bucket];
+        // New events are usually the latest in their bucket
+        let at = bucket.partition_point(|e| e.key() < entry.key());
+        bucket.insert(at, entry);
+    }
+
+    /// Moves the cursor to the bucket holding the earliest event.
+    fn locate(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        for _ in 0..self.buckets.len() {
+            let window_end = self.window_start.saturating_add(self.width);
+            if let Some(first) = self.buckets[self.current].front() {
+                if first.time < window_end {
+                    return Some(self.current);
+                }
+            }
+            self.current = (self.current + 1) & (self.buckets.len() - 1);
+            self.window_start = window_end;
+        }
+        // A whole year without events: jump straight to the earliest one
+        let (earliest, time) = self
+            .buckets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.front().map(|e| (i, e.key())))
+            .min_by_key(|&(_, key)| key)?;
+        self.current = earliest;
+        self.window_start = time.0 - time.0 % self.width;
+        Some(earliest)
+    }
+
+    pub fn peek(&mut self) -> Option<&Event> {
+        let bucket = self.locate()?;
+        let slot = self.buckets[bucket].front()?.slot;
+        self.slots[slot].as_ref()
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        let bucket = self.locate()?;
+        let slot = self.buckets[bucket].pop_front()?.slot;
+        self.free.push(slot);
+        self.len -= 1;
+        if self.buckets.len() > MIN_BUCKETS && self.len < self.buckets.len() / 2 {
+            self.resize(self.buckets.len() / 2);
+        }
+        self.slots[slot].take()
+    }
+
+    /// Every pending event, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.slots.iter().flatten()
+    }
+
+    fn remove_where(&mut self, mut remove: impl FnMut(&Event) -> bool) -> Vec<Event> {
+        let mut removed = Vec::new();
+        for bucket in &mut self.buckets {
+            bucket.retain(|entry| {
+                let slot = &mut self.slots[entry.slot];
+                if !slot.as_ref().is_some_and(&mut remove) {
+                    return true;
+                }
+                removed.extend(slot.take());
+                self.free.push(entry.slot);
+                false
+            });
+        }
+        self.len -= removed.len();
+        removed
+    }
+
+    /// Rebuilds the ring with `buckets` buckets, sized so that the earliest
+    /// events fall about three to a bucket.
+    fn resize(&mut self, buckets: usize) {
+        let entries: Vec<Entry> = self.buckets.iter_mut().flat_map(|b| b.drain(..)).collect();
+        self.width = sample_width(&entries).unwrap_or(self.width);
+        self.buckets = (0..buckets).map(|_| VecDeque::new()).collect();
+        let len = std::mem::take(&mut self.len);
+        for entry in entries {
+            self.insert(entry);
+            self.len += 1;
+        }
+        debug_assert_eq!(self.len, len);
+    }
+}
+
+/// Three times the mean gap between the earliest distinct event times, if
+/// there are two or more.
+fn sample_width(entries: &[Entry]) -> Option<u64> {
+    let mut times: Vec<u64> = entries.iter().map(|e| e.time).collect();
+    if times.len() > WIDTH_SAMPLE {
+        times.select_nth_unstable(WIDTH_SAMPLE);
+        times.truncate(WIDTH_SAMPLE);
+    }
+    times.sort_unstable();
+    times.dedup();
+    let (first, last) = (*times.first()?, *times.last()?);
+    (times.len() > 1).then(|| (3 * (last - first) / (times.len() as u64 - 1)).max(1))
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+/// Analytical M/M/c queue: Poisson arrivals, exponentially distributed
+/// service times and `servers` identical workers sharing one FIFO queue.
+///
+/// Predictions only exist for a stable queue (utilization below 1). The
+/// simulated servers differ from this model on purpose in a few ways (near
+/// constant service times, saturation slowdown, bounded backlog, client
+/// timeouts and retries), and comparing the two shows where each matters.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MmcModel {
+    pub arrival_rps: f64,
+    /// Mean service time of one request
+    pub service_us: f64,
+    pub servers: u32,
+}
+
+impl MmcModel {
+    /// Offered load in erlangs (λ / μ).
+    fn offered_load(&self) -> f64 {
+        self.arrival_rps * self.service_us / 1_000_000.0
+    }
+
+    /// Fraction of worker capacity in use (ρ = λ / cμ); above 1 the queue
+    /// grows without bound.
+    pub fn utilization(&self) -> f64 {
+        if self.servers == 0 {
+            return f64::INFINITY;
+        }
+        self.offered_load() / self.servers as f64
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.utilization() < 1.0
+    }
+
+    /// Probability that an arriving request has to queue (Erlang C).
+    pub fn wait_probability(&self) -> Option<f64> {
+        if !self.is_stable() {
+            return None;
+        }
+        let a = self.offered_load();
+        // Erlang B by recurrence, which stays stable for large `servers`
+        let erlang_b = (1..=self.servers).fold(1.0, |b, k| a * b / (k as f64 + a * b));
+        let rho = self.utilization();
+        Some(erlang_b / (1.0 - rho * (1.0 - erlang_b)))
+    }
+
+    /// Mean time spent queueing before service starts (Wq).
+    pub fn mean_wait_us(&self) -> Option<f64> {
+        let c = self.servers as f64;
+        let spare_rps = c * 1_000_000.0 / self.service_us - self.arrival_rps;
+        Some(self.wait_probability()? / spare_rps * 1_000_000.0)
+    }
+
+    /// Mean time from arrival to completion (W = Wq + S).
+    pub fn mean_response_us(&self) -> Option<f64> {
+        Some(self.mean_wait_us()? + self.service_us)
+    }
+
+    /// Mean number of requests waiting (Lq = λ Wq).
+    pub fn mean_queue_len(&self) -> Option<f64> {
+        Some(self.arrival_rps * self.mean_wait_us()? / 1_000_000.0)
+    }
+}
+
+/// Measured counterparts of the `MmcModel` predictions for one node.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct QueueingCheck {
+    pub model: MmcModel,
+    /// Mean fraction of workers busy
+    pub utilization: f64,
+    /// Mean number of requests waiting
+    pub queue_len: f64,
+    /// Smoothed time from arrival to completion, if any request completed
+    pub response_us: Option<f64>,
+}
+
+/// The load of one server right now: the figures usually reasoned about
+/// on a whiteboard, read from its live state rather than a model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct LiveLoad {
+    /// Workers processing a request
+    pub busy: u32,
+    pub workers: u32,
+    /// Requests arriving per second (λ)
+    pub arrival_rps: f64,
+    /// Requests per second the workers can finish at the configured
+    /// service time (cμ = c / S)
+    pub capacity_rps: f64,
+    /// Smoothed time from arrival to completion (W), if any completed
+    pub response_us: Option<f64>,
+}
+
+impl LiveLoad {
+    /// Share of workers busy at this instant.
+    pub fn utilization(&self) -> f64 {
+        if self.workers == 0 {
+            return 0.0;
+        }
+        self.busy as f64 / self.workers as f64
+    }
+
+    /// Arrival rate over service capacity (ρ = λ / cμ); above 1 the
+    /// backlog grows until it overflows.
+    pub fn rho(&self) -> f64 {
+        if self.capacity_rps <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.arrival_rps / self.capacity_rps
+    }
+
+    /// Mean requests held by the server, queued or in service, by Little's
+    /// law (L = λW).
+    pub fn mean_in_system(&self) -> Option<f64> {
+        Some(self.arrival_rps * self.response_us? / 1_000_000.0)
+    }
+}
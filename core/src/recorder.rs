@@ -0,0 +1,76 @@
+use crate::engine::Simulation;
+use crate::scenario::{Scenario, ScenarioAction};
+
+/// Manual actions captured as they are made, so an ad-hoc experiment can
+/// be replayed as a scenario.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRecorder {
+    /// Virtual time recording started; steps are timed from here
+    pub started_us: u64,
+    pub scenario: Scenario,
+}
+
+impl Simulation {
+    /// Starts capturing actions passed to `record_action`, replacing any
+    /// recording in progress.
+    pub fn start_macro(&mut self, name: &str) {
+        self.macro_recorder = Some(MacroRecorder {
+            started_us: self.time,
+            scenario: Scenario::new(name),
+        });
+    }
+
+    /// Notes an action just applied by hand. Does nothing unless a macro
+    /// is being recorded. Edits to the same node or link at the same
+    /// moment, like the frames of one slider drag while paused, fold into
+    /// a single step.
+    pub fn record_action(&mut self, action: ScenarioAction) {
+        let time = self.time;
+        let Some(recorder) = &mut self.macro_recorder else {
+            return;
+        };
+        let at_us = time.saturating_sub(recorder.started_us);
+        if let Some(last) = recorder.scenario.steps.last_mut() {
+            if last.at_us == at_us && fold(&mut last.action, &action) {
+                return;
+            }
+        }
+        recorder.scenario.insert(at_us, action);
+    }
+
+    /// Ends the recording and returns it, timed from its start. Shift it
+    /// with `Scenario::shifted` to replay it later in a run.
+    pub fn stop_macro(&mut self) -> Option<Scenario> {
+        self.macro_recorder.take().map(|r| r.scenario)
+    }
+}
+
+/// Merges `next` into `last` when both edit the same target.
+fn fold(last: &mut ScenarioAction, next: &ScenarioAction) -> bool {
+    match (last, next) {
+        (
+            ScenarioAction::SetConfig { node_id, config },
+            ScenarioAction::SetConfig {
+                node_id: next_id,
+                config: next_config,
+            },
+        ) if node_id == next_id => {
+            if let (Some(dst), Some(src)) = (config.as_object_mut(), next_config.as_object()) {
+                dst.extend(src.clone());
+            }
+            true
+        }
+        (
+            ScenarioAction::SetEdge { from, to, edge },
+            ScenarioAction::SetEdge {
+                from: next_from,
+                to: next_to,
+                edge: next_edge,
+            },
+        ) if from == next_from && to == next_to => {
+            *edge = *next_edge;
+            true
+        }
+        _ => false,
+    }
+}
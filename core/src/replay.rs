@@ -0,0 +1,145 @@
+use crate::engine::{Event, Simulation};
+use crate::runner::RunCondition;
+use crate::scenario::Scenario;
+use crate::topology::Topology;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// Append-only log of every event processed by `Simulation::step`.
+///
+/// Once `max_events` is reached further events are not recorded and
+/// `truncated` is set, so a long run cannot exhaust memory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventLog {
+    pub events: Vec<Event>,
+    pub max_events: usize,
+    pub truncated: bool,
+}
+
+impl EventLog {
+    pub fn new(max_events: usize) -> Self {
+        Self {
+            events: Vec::new(),
+            max_events,
+            truncated: false,
+        }
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        if self.events.len() < self.max_events {
+            self.events.push(event.clone());
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Streams the log as JSON Lines (one event per line).
+    pub fn write_jsonl(&self, mut writer: impl Write) -> std::io::Result<()> {
+        for event in &self.events {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    pub fn read_jsonl(reader: impl BufRead) -> std::io::Result<Self> {
+        let mut events = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(&line)?);
+        }
+        Ok(Self {
+            max_events: events.len(),
+            events,
+            truncated: false,
+        })
+    }
+}
+
+/// Everything needed to reproduce a run from t=0.
+///
+/// A run is fully determined by its starting topology, seed and scenario, so
+/// any point in time can be reconstructed by re-simulating from scratch.
+/// Manual edits made during the live run (UI sliders, kill buttons) are not
+/// captured; script them as a `Scenario` to keep replays faithful.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub topology: Topology,
+    pub seed: u64,
+    #[serde(default)]
+    pub scenario: Scenario,
+    #[serde(default)]
+    pub warmup_us: u64,
+}
+
+impl Replay {
+    /// Snapshots the baseline of `sim`. Must be taken before the first step.
+    pub fn capture(sim: &Simulation) -> Self {
+        Self {
+            topology: sim.to_topology(),
+            seed: sim.seed,
+            scenario: sim.scenario.clone(),
+            warmup_us: sim.warmup_us,
+        }
+    }
+
+    /// Rebuilds the simulation as it was at virtual time `time_us`.
+    pub fn simulation_at(&self, time_us: u64) -> Simulation {
+        self.simulation_at_sampled(time_us, u64::MAX, |_| {})
+    }
+
+    /// Like `simulation_at`, calling `on_sample` every `every_us` of virtual
+    /// time on the way (and at `time_us`), e.g. to refill metric history.
+    pub fn simulation_at_sampled(
+        &self,
+        time_us: u64,
+        every_us: u64,
+        mut on_sample: impl FnMut(&Simulation),
+    ) -> Simulation {
+        let mut sim = self.start();
+        while sim.time < time_us {
+            let next = time_us.min(sim.time.saturating_add(every_us.max(1)));
+            sim.run_until(&RunCondition::Duration {
+                duration_us: next - sim.time,
+            });
+            on_sample(&sim);
+        }
+        sim
+    }
+
+    /// Re-runs the recording and returns the index of the first event that
+    /// differs from `log`, or `None` if the replay reproduces it exactly.
+    pub fn first_divergence(&self, log: &EventLog) -> Option<usize> {
+        let mut sim = self.start();
+        sim.record_events(log.len());
+        for _ in 0..log.len() {
+            if !sim.step() {
+                break;
+            }
+        }
+        let replayed = sim.event_log.take().unwrap_or_default();
+        log.events
+            .iter()
+            .zip(replayed.events.iter())
+            .position(|(a, b)| !a.same_as(b))
+            .or_else(|| (replayed.len() < log.len()).then_some(replayed.len()))
+    }
+
+    fn start(&self) -> Simulation {
+        let mut sim = Simulation::from_topology(&self.topology, self.seed);
+        sim.load_scenario(self.scenario.clone());
+        sim.warmup_us = self.warmup_us;
+        sim
+    }
+}
@@ -0,0 +1,259 @@
+use crate::brownout::health_of;
+use crate::engine::Simulation;
+use crate::experiment::{Experiment, RunMetrics};
+use crate::failure::FailureReason;
+use crate::runner::RunCondition;
+use crate::scenario::Scenario;
+use crate::topology::Topology;
+use crate::traits::{Health, NodeId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What a run was made with, so it can be reproduced.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct RunSettings {
+    pub seed: u64,
+    pub warmup_us: u64,
+    /// Virtual time measured after the warm-up
+    pub measured_us: u64,
+}
+
+/// The shape of the design at the end of the run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopologySummary {
+    pub nodes: usize,
+    pub links: usize,
+    /// Node count per kind
+    pub kinds: BTreeMap<String, usize>,
+}
+
+/// How many requests failed for one reason.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FailureCount {
+    pub reason: FailureReason,
+    pub count: u64,
+    /// Share of all failures, in percent
+    pub percent: f64,
+}
+
+/// One node's counters over the measured time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeReport {
+    pub id: NodeId,
+    pub name: String,
+    pub kind: String,
+    pub health: Health,
+    pub received: u64,
+    pub errors: u64,
+    pub deadline_exceeded: u64,
+    /// Fraction of capacity in use when the run ended (0.0..=1.0)
+    pub utilization: f32,
+    /// Smoothed time recent requests spent in the node
+    pub latency_us: Option<u64>,
+    pub cost_dollars: f64,
+}
+
+/// Everything about one run in a single document: how it was set up, what
+/// happened to it and how each node fared. Written once per run, e.g. as a
+/// CI artifact.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub settings: RunSettings,
+    pub summary: TopologySummary,
+    /// The design as it stood at the end, configs included
+    pub topology: Topology,
+    pub scenario: Scenario,
+    pub metrics: RunMetrics,
+    /// Failures by reason, most frequent first
+    pub failures: Vec<FailureCount>,
+    /// Nodes in id order
+    pub nodes: Vec<NodeReport>,
+}
+
+impl RunReport {
+    /// Reports on `sim` after `measured_us` of recorded time.
+    pub fn from_simulation(sim: &Simulation, measured_us: u64) -> Self {
+        let topology = sim.to_topology();
+        let mut kinds = BTreeMap::new();
+        for node in &topology.nodes {
+            *kinds.entry(node.kind.clone()).or_default() += 1;
+        }
+
+        let mut failures: Vec<FailureCount> = sim
+            .failure_reasons
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|(&reason, &count)| FailureCount {
+                reason,
+                count,
+                percent: count as f64 * 100.0 / sim.failure_count.max(1) as f64,
+            })
+            .collect();
+        failures.sort_by_key(|f| std::cmp::Reverse(f.count));
+
+        let nodes = topology
+            .nodes
+            .iter()
+            .filter_map(|spec| {
+                let node = sim.components.get(&spec.id)?;
+                let health = if node.is_healthy() {
+                    health_of(
+                        sim.brownouts
+                            .get(&spec.id)
+                            .filter(|b| b.active_at(sim.time)),
+                    )
+                } else {
+                    Health::Down
+                };
+                Some(NodeReport {
+                    id: spec.id,
+                    name: node.name().to_string(),
+                    kind: spec.kind.clone(),
+                    health,
+                    received: node.received_count(),
+                    errors: node.error_count(),
+                    deadline_exceeded: node.deadline_exceeded_count(),
+                    utilization: node.utilization(),
+                    latency_us: node.recent_latency_us(),
+                    cost_dollars: sim.node_cost(spec.id),
+                })
+            })
+            .collect();
+
+        RunReport {
+            settings: RunSettings {
+                seed: sim.seed,
+                warmup_us: sim.warmup_us,
+                measured_us,
+            },
+            summary: TopologySummary {
+                nodes: topology.nodes.len(),
+                links: topology.links.len(),
+                kinds,
+            },
+            scenario: sim.scenario.clone(),
+            metrics: RunMetrics::from_simulation(sim, measured_us),
+            failures,
+            nodes,
+            topology,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// The report as a Markdown document: settings, headline metrics, then
+    /// tables of failures, nodes and scenario steps.
+    pub fn to_markdown(&self) -> String {
+        let secs = |us: u64| format!("{:.1}s", us as f64 / 1_000_000.0);
+        let ms = |us: Option<u64>| {
+            us.map_or("n/a".to_string(), |v| format!("{:.1}ms", v as f64 / 1000.0))
+        };
+        let s = &self.settings;
+        let m = &self.metrics;
+
+        let mut out = String::from("# Run report\n\n");
+        out.push_str(&format!(
+            "Seed {}, {} warm-up, {} measured.\n\n",
+            s.seed,
+            secs(s.warmup_us),
+            secs(s.measured_us)
+        ));
+        let kinds: Vec<String> = self
+            .summary
+            .kinds
+            .iter()
+            .map(|(kind, count)| format!("{} {}", count, kind))
+            .collect();
+        out.push_str(&format!(
+            "{} nodes ({}) and {} links.\n\n",
+            self.summary.nodes,
+            kinds.join(", "),
+            self.summary.links
+        ));
+
+        out.push_str("## Results\n\n");
+        out.push_str("| Succeeded | Failed | SLA | p50 | p99 | Throughput | Cost |\n");
+        out.push_str("|---|---|---|---|---|---|---|\n");
+        out.push_str(&format!(
+            "| {} | {} | {:.2}% | {} | {} | {:.1} rps | ${:.2} |\n\n",
+            m.success_count,
+            m.failure_count,
+            m.sla_percent,
+            ms(m.p50_us),
+            ms(m.p99_us),
+            m.throughput_rps,
+            m.cost_dollars
+        ));
+
+        out.push_str("## Failures\n\n");
+        if self.failures.is_empty() {
+            out.push_str("No request failed.\n\n");
+        } else {
+            out.push_str("| Reason | Requests | Share |\n|---|---|---|\n");
+            for f in &self.failures {
+                out.push_str(&format!(
+                    "| {} | {} | {:.1}% |\n",
+                    f.reason.label(),
+                    f.count,
+                    f.percent
+                ));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("## Nodes\n\n");
+        out.push_str(
+            "| # | Node | Kind | Health | Received | Errors | Utilization | Latency | Cost |\n",
+        );
+        out.push_str("|---|---|---|---|---|---|---|---|---|\n");
+        for n in &self.nodes {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {:.0}% | {} | ${:.2} |\n",
+                n.id,
+                n.name,
+                n.kind,
+                n.health.label(),
+                n.received,
+                n.errors,
+                n.utilization * 100.0,
+                ms(n.latency_us),
+                n.cost_dollars
+            ));
+        }
+
+        out.push_str("\n## Scenario\n\n");
+        if self.scenario.steps.is_empty() {
+            out.push_str("No scenario ran.\n");
+            return out;
+        }
+        if !self.scenario.name.is_empty() {
+            out.push_str(&format!("{}\n\n", self.scenario.name));
+        }
+        out.push_str("| At | Action |\n|---|---|\n");
+        for step in &self.scenario.steps {
+            let skipped = if step.enabled { "" } else { " (disabled)" };
+            out.push_str(&format!(
+                "| {} | {}{} |\n",
+                secs(step.at_us),
+                step.action.describe(),
+                skipped
+            ));
+        }
+        out
+    }
+}
+
+impl Experiment {
+    /// Runs `topology` like `run`, reporting on the run in full.
+    pub fn report(&self, topology: &Topology) -> RunReport {
+        let mut sim = Simulation::from_topology(topology, self.seed);
+        sim.load_scenario(self.scenario.clone());
+        sim.warmup_us = self.warmup_us;
+        sim.run_until(&RunCondition::Duration {
+            duration_us: self.warmup_us + self.duration_us,
+        });
+        RunReport::from_simulation(&sim, self.duration_us)
+    }
+}
@@ -0,0 +1,222 @@
+use crate::debugger::StepResult;
+use crate::engine::Simulation;
+use serde::{Deserialize, Serialize};
+
+/// Termination condition for `Simulation::run_until`.
+///
+/// Durations and request counts are measured from the moment `run_until`
+/// is called, not from the start of the simulation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RunCondition {
+    /// Advance virtual time by `duration_us`
+    Duration { duration_us: u64 },
+    /// Stop once `count` more requests have completed (success or failure)
+    CompletedRequests { count: u64 },
+    /// Stop once the latency `percentile` over `window_us` has stayed within
+    /// `tolerance` (relative, e.g. 0.01 = 1%) of a reference value for
+    /// `stable_for_us`. Sampled every `sample_every_us` of virtual time.
+    Converged {
+        percentile: f32,
+        window_us: u64,
+        tolerance: f64,
+        stable_for_us: u64,
+        sample_every_us: u64,
+    },
+    /// Stop as soon as any of the inner conditions is met
+    Any(Vec<RunCondition>),
+}
+
+impl RunCondition {
+    /// p99 stable within 1% for 10s, the usual steady-state criterion.
+    pub fn p99_converged() -> Self {
+        RunCondition::Converged {
+            percentile: 99.0,
+            window_us: 5_000_000,
+            tolerance: 0.01,
+            stable_for_us: 10_000_000,
+            sample_every_us: 500_000,
+        }
+    }
+
+    /// Earliest absolute time at which a `Duration` condition fires.
+    fn deadline(&self, start_us: u64) -> Option<u64> {
+        match self {
+            RunCondition::Duration { duration_us } => Some(start_us + duration_us),
+            RunCondition::Any(inner) => inner.iter().filter_map(|c| c.deadline(start_us)).min(),
+            _ => None,
+        }
+    }
+}
+
+/// Why `Simulation::run_until` returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The condition was satisfied
+    ConditionMet,
+    /// The event queue ran dry before the condition was satisfied
+    Idle,
+    /// The progress callback asked to stop
+    Interrupted,
+    /// The next event matches the breakpoint at this index
+    Breakpoint(usize),
+}
+
+/// Per-call progress for one condition (mirrors the `RunCondition` tree).
+enum Progress {
+    None,
+    Converged {
+        next_sample_us: u64,
+        reference: Option<u64>,
+        stable_since_us: u64,
+    },
+    Any(Vec<Progress>),
+}
+
+impl Progress {
+    fn new(condition: &RunCondition, now: u64) -> Self {
+        match condition {
+            RunCondition::Converged { .. } => Progress::Converged {
+                next_sample_us: now,
+                reference: None,
+                stable_since_us: now,
+            },
+            RunCondition::Any(inner) => {
+                Progress::Any(inner.iter().map(|c| Progress::new(c, now)).collect())
+            }
+            _ => Progress::None,
+        }
+    }
+
+    fn is_met(
+        &mut self,
+        condition: &RunCondition,
+        sim: &Simulation,
+        start_us: u64,
+        start_completed: u64,
+    ) -> bool {
+        match (condition, self) {
+            (RunCondition::Duration { duration_us }, _) => sim.time >= start_us + duration_us,
+            (RunCondition::CompletedRequests { count }, _) => {
+                sim.success_count + sim.failure_count >= start_completed + count
+            }
+            (
+                RunCondition::Converged {
+                    percentile,
+                    window_us,
+                    tolerance,
+                    stable_for_us,
+                    sample_every_us,
+                },
+                Progress::Converged {
+                    next_sample_us,
+                    reference,
+                    stable_since_us,
+                },
+            ) => {
+                if sim.time < *next_sample_us {
+                    return false;
+                }
+                *next_sample_us = sim.time + (*sample_every_us).max(1);
+                let Some(value) = sim.get_percentile(*percentile, *window_us) else {
+                    *reference = None;
+                    return false;
+                };
+                let stable = reference
+                    .is_some_and(|r| (value as f64 - r as f64).abs() <= r as f64 * tolerance);
+                if !stable {
+                    *reference = Some(value);
+                    *stable_since_us = sim.time;
+                    return false;
+                }
+                sim.time - *stable_since_us >= *stable_for_us
+            }
+            (RunCondition::Any(inner), Progress::Any(progress)) => inner
+                .iter()
+                .zip(progress.iter_mut())
+                .fold(false, |met, (c, p)| {
+                    p.is_met(c, sim, start_us, start_completed) || met
+                }),
+            _ => false,
+        }
+    }
+}
+
+impl Simulation {
+    /// Processes events until `condition` holds or the queue is empty.
+    ///
+    /// Events scheduled after a `Duration` deadline are left in the queue and
+    /// the clock is advanced exactly to the deadline.
+    pub fn run_until(&mut self, condition: &RunCondition) -> RunOutcome {
+        let start_us = self.time;
+        let start_completed = self.success_count + self.failure_count;
+        let deadline = condition.deadline(start_us);
+        let mut progress = Progress::new(condition, start_us);
+
+        loop {
+            if progress.is_met(condition, self, start_us, start_completed) {
+                return RunOutcome::ConditionMet;
+            }
+            self.skip_cancelled();
+            let next_time = self.events.peek().map(|e| e.time);
+            match (next_time, deadline) {
+                (Some(t), Some(d)) if t > d => {
+                    self.time = d;
+                    self.apply_scenario_until(d);
+                    self.advance_fluid_until(d);
+                }
+                (Some(_), _) => {
+                    self.step();
+                }
+                (None, Some(d)) => {
+                    self.time = self.time.max(d);
+                    self.apply_scenario_until(d);
+                    self.advance_fluid_until(d);
+                }
+                (None, None) => return RunOutcome::Idle,
+            }
+        }
+    }
+
+    /// Executes events as fast as possible up to absolute time `target_us`,
+    /// then moves the clock there.
+    ///
+    /// `progress` is called with the current virtual time every
+    /// `report_every` events; returning `false` stops early with
+    /// `Interrupted` so a caller can yield (the run can simply be resumed).
+    /// Breakpoints are honoured when any are set.
+    pub fn fast_forward(
+        &mut self,
+        target_us: u64,
+        report_every: usize,
+        mut progress: impl FnMut(u64) -> bool,
+    ) -> RunOutcome {
+        let report_every = report_every.max(1);
+        let use_breakpoints = !self.debugger.breakpoints.is_empty();
+        let mut processed = 0usize;
+        while self.peek_event().is_some_and(|e| e.time <= target_us) {
+            if processed.is_multiple_of(report_every) && !progress(self.time) {
+                return RunOutcome::Interrupted;
+            }
+            if use_breakpoints {
+                if let StepResult::Breakpoint(idx) = self.step_checked() {
+                    return RunOutcome::Breakpoint(idx);
+                }
+            } else {
+                self.step();
+            }
+            processed += 1;
+        }
+        self.time = self.time.max(target_us);
+        self.apply_scenario_until(target_us);
+        self.advance_fluid_until(target_us);
+        progress(self.time);
+        RunOutcome::ConditionMet
+    }
+
+    /// Processes every event due in the next `duration_us` of virtual time
+    /// and leaves the clock exactly that far ahead, for stepping a paused
+    /// run. Stops early on a breakpoint.
+    pub fn advance_by(&mut self, duration_us: u64) -> RunOutcome {
+        self.fast_forward(self.time + duration_us, usize::MAX, |_| true)
+    }
+}
@@ -0,0 +1,260 @@
+use crate::network::{EdgeConfig, Link};
+use crate::traits::{Health, NodeId};
+use serde::{Deserialize, Serialize};
+
+/// A single change applied to the running topology.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ScenarioAction {
+    /// Mark a node as unhealthy (same as the KILL button)
+    Kill { node_id: NodeId },
+    /// Bring a node back and re-apply its config (same as the REVIVE button)
+    Revive { node_id: NodeId },
+    /// Merge the given fields into the node's config and hot-apply it
+    SetConfig {
+        node_id: NodeId,
+        config: serde_json::Value,
+    },
+    /// Replace the configuration of one direction of a link
+    SetEdge {
+        from: NodeId,
+        to: NodeId,
+        edge: EdgeConfig,
+    },
+    /// Slow a node down without marking it unhealthy: its service time is
+    /// multiplied by `slowdown` and `extra_latency_us` is added, for
+    /// `duration_us` or until cleared
+    InjectLatency {
+        node_id: NodeId,
+        slowdown: f32,
+        #[serde(default)]
+        extra_latency_us: u64,
+        /// Share of the node's work that fails while it lasts
+        #[serde(default)]
+        error_rate: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duration_us: Option<u64>,
+    },
+    /// End a brownout started by `InjectLatency`
+    ClearLatency { node_id: NodeId },
+    /// Put a node into a health state (see `Simulation::set_health`)
+    SetHealth { node_id: NodeId, health: Health },
+    /// Drop all packets in both directions between two nodes
+    Partition { a: NodeId, b: NodeId },
+    /// Restore a link previously cut by `Partition`
+    Heal { a: NodeId, b: NodeId },
+}
+
+impl ScenarioAction {
+    /// The config fields of a node that differ between `before` and
+    /// `after`, as a `SetConfig` step; `None` if nothing changed. The
+    /// client's `generation_id` is left out because applying the step
+    /// bumps it anyway.
+    pub fn config_change(
+        node_id: NodeId,
+        before: &serde_json::Value,
+        after: &serde_json::Value,
+    ) -> Option<Self> {
+        let after = after.as_object()?;
+        let changed: serde_json::Map<String, serde_json::Value> = after
+            .iter()
+            .filter(|(k, v)| k.as_str() != "generation_id" && before.get(k.as_str()) != Some(v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if changed.is_empty() {
+            return None;
+        }
+        Some(ScenarioAction::SetConfig {
+            node_id,
+            config: serde_json::Value::Object(changed),
+        })
+    }
+
+    /// The nodes the action touches.
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        match self {
+            ScenarioAction::Kill { node_id }
+            | ScenarioAction::Revive { node_id }
+            | ScenarioAction::SetConfig { node_id, .. }
+            | ScenarioAction::InjectLatency { node_id, .. }
+            | ScenarioAction::ClearLatency { node_id }
+            | ScenarioAction::SetHealth { node_id, .. } => vec![*node_id],
+            ScenarioAction::SetEdge { from, to, .. } => vec![*from, *to],
+            ScenarioAction::Partition { a, b } | ScenarioAction::Heal { a, b } => vec![*a, *b],
+        }
+    }
+
+    /// Short human-readable description used by the UI timeline.
+    pub fn describe(&self) -> String {
+        match self {
+            ScenarioAction::Kill { node_id } => format!("Kill #{}", node_id),
+            ScenarioAction::Revive { node_id } => format!("Revive #{}", node_id),
+            ScenarioAction::SetConfig { node_id, config } => {
+                format!("Configure #{}: {}", node_id, config)
+            }
+            ScenarioAction::SetEdge { from, to, edge } => format!(
+                "Edge {} -> {}: {:.1}ms",
+                from,
+                to,
+                edge.latency_us as f64 / 1000.0
+            ),
+            ScenarioAction::InjectLatency {
+                node_id,
+                slowdown,
+                extra_latency_us,
+                error_rate,
+                duration_us,
+            } => {
+                let mut text = format!("Slow #{} ×{:.1}", node_id, slowdown);
+                if *extra_latency_us > 0 {
+                    text += &format!(" +{:.0}ms", *extra_latency_us as f64 / 1000.0);
+                }
+                if *error_rate > 0.0 {
+                    text += &format!(", {:.0}% errors", error_rate * 100.0);
+                }
+                if let Some(d) = duration_us {
+                    text += &format!(" for {:.1}s", *d as f64 / 1_000_000.0);
+                }
+                text
+            }
+            ScenarioAction::ClearLatency { node_id } => format!("Restore speed of #{}", node_id),
+            ScenarioAction::SetHealth { node_id, health } => match health {
+                Health::Degraded {
+                    slowdown,
+                    error_rate,
+                } => format!(
+                    "Degrade #{} ×{:.1}, {:.0}% errors",
+                    node_id,
+                    slowdown,
+                    error_rate * 100.0
+                ),
+                _ => format!("Mark #{} {}", node_id, health.label().to_lowercase()),
+            },
+            ScenarioAction::Partition { a, b } => format!("Partition {} <-> {}", a, b),
+            ScenarioAction::Heal { a, b } => format!("Heal {} <-> {}", a, b),
+        }
+    }
+}
+
+/// An action scheduled at an absolute virtual time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScenarioStep {
+    /// Virtual time at which the action fires (in microseconds)
+    pub at_us: u64,
+    pub action: ScenarioAction,
+    /// Disabled steps stay on the timeline but are skipped when due
+    #[serde(default = "enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+/// A scripted timeline of actions executed by the engine during a run.
+///
+/// Example (JSON):
+/// ```json
+/// {
+///   "name": "Backend outage",
+///   "steps": [
+///     { "at_us": 10000000, "action": { "Kill": { "node_id": 3 } } },
+///     { "at_us": 20000000, "action": { "SetConfig": { "node_id": 4, "config": { "concurrency": 2 } } } },
+///     { "at_us": 30000000, "action": { "Partition": { "a": 2, "b": 4 } } }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+}
+
+impl Scenario {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Appends an action at `at_us`, keeping steps ordered by time.
+    pub fn at(mut self, at_us: u64, action: ScenarioAction) -> Self {
+        self.insert(at_us, action);
+        self
+    }
+
+    /// Adds an enabled action at `at_us` after any others at that time and
+    /// returns its index.
+    pub fn insert(&mut self, at_us: u64, action: ScenarioAction) -> usize {
+        let index = self.steps.partition_point(|s| s.at_us <= at_us);
+        self.steps.insert(
+            index,
+            ScenarioStep {
+                at_us,
+                action,
+                enabled: true,
+            },
+        );
+        index
+    }
+
+    /// The same steps, `by_us` later.
+    pub fn shifted(&self, by_us: u64) -> Self {
+        let mut scenario = self.clone();
+        for step in &mut scenario.steps {
+            step.at_us += by_us;
+        }
+        scenario
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut scenario: Scenario = serde_json::from_str(json)?;
+        scenario.sort();
+        Ok(scenario)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Reads the YAML form of the JSON above, actions written the same way
+    /// (`action: { Kill: { node_id: 3 } }`) rather than as YAML tags.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        let mut scenario: Scenario = serde_yaml::with::singleton_map_recursive::deserialize(
+            serde_yaml::Deserializer::from_str(yaml),
+        )?;
+        scenario.sort();
+        Ok(scenario)
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let mut out = Vec::new();
+        let mut serializer = serde_yaml::Serializer::new(&mut out);
+        if serde_yaml::with::singleton_map_recursive::serialize(self, &mut serializer).is_err() {
+            return String::new();
+        }
+        String::from_utf8(out).unwrap_or_default()
+    }
+
+    /// Stable sort so actions sharing a timestamp keep their declared order.
+    fn sort(&mut self) {
+        self.steps.sort_by_key(|s| s.at_us);
+    }
+}
+
+/// Builds the fully cut link used for `Partition`.
+pub(crate) fn partitioned_link() -> Link {
+    let cut = EdgeConfig {
+        packet_loss_rate: 1.0,
+        ..EdgeConfig::default()
+    };
+    Link {
+        min_to_max: cut,
+        max_to_min: cut,
+    }
+}
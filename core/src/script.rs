@@ -0,0 +1,195 @@
+use crate::hosted::{Decision, EventInput, GuestLogic, HostedComponent};
+use crate::traits::Component;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+pub const SCRIPTED_KIND: &str = "Scripted";
+
+/// Operations a script may run per call before it is stopped, so a
+/// runaway loop fails the request instead of hanging the simulation.
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// What a new `Scripted` node runs: it passes requests on to its first
+/// healthy target, or answers them itself after 5ms of work.
+pub const DEFAULT_SCRIPT: &str = r#"// Called for each request that arrives, and for each response to a
+// request this node forwarded. `ev` has: event ("arrival" or "response"),
+// request, time_us, deadline_us, success (responses only), targets,
+// healthy (the targets that are up) and metadata.
+//
+// Answer with respond(success), respond(success, delay_us), forward(),
+// forward(to) or forward(to, delay_us); answering nothing fails an
+// arrival and passes a response back unchanged. rand() is in [0, 1).
+// State kept between calls goes on `this`, set up in an optional init().
+fn init() {
+    this.served = 0;
+}
+
+fn on_event(ev) {
+    if ev.event == "response" {
+        return;
+    }
+    if ev.healthy.is_empty() {
+        this.served += 1;
+        return respond(true, 5000);
+    }
+    forward()
+}
+"#;
+
+fn respond(success: bool, delay_us: i64) -> rhai::Map {
+    let mut fields = rhai::Map::new();
+    fields.insert("success".into(), success.into());
+    fields.insert("delay_us".into(), delay_us.max(0).into());
+    rhai::Map::from([("respond".into(), fields.into())])
+}
+
+fn forward(to: Dynamic, delay_us: i64) -> rhai::Map {
+    let mut fields = rhai::Map::new();
+    fields.insert("to".into(), to);
+    fields.insert("delay_us".into(), delay_us.max(0).into());
+    rhai::Map::from([("forward".into(), fields.into())])
+}
+
+/// A Rhai script deciding what a `Scripted` node does with requests. The
+/// script is the node's `script` config field, so it is edited in the
+/// inspector and recompiled whenever it changes.
+pub struct Script {
+    engine: Engine,
+    source: String,
+    ast: Option<AST>,
+    /// Why `source` does not compile or its `init` failed
+    compile_error: Option<String>,
+    /// `this` inside the script's functions
+    state: Dynamic,
+    rng: Arc<Mutex<StdRng>>,
+    /// The last line the script printed
+    printed: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        let rng = Arc::new(Mutex::new(StdRng::seed_from_u64(0)));
+        let printed = Arc::new(Mutex::new(None));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine
+            .register_fn("respond", |success: bool| respond(success, 0))
+            .register_fn("respond", respond)
+            .register_fn("forward", || forward(Dynamic::UNIT, 0))
+            .register_fn("forward", |to: i64| forward(to.into(), 0))
+            .register_fn("forward", |to: i64, delay_us: i64| {
+                forward(to.into(), delay_us)
+            });
+        let shared = Arc::clone(&rng);
+        engine.register_fn("rand", move || shared.lock().unwrap().gen::<f64>());
+        let shared = Arc::clone(&printed);
+        engine.on_print(move |line| *shared.lock().unwrap() = Some(line.to_string()));
+        Script {
+            engine,
+            source: String::new(),
+            ast: None,
+            compile_error: None,
+            state: Dynamic::UNIT,
+            rng,
+            printed,
+        }
+    }
+}
+
+impl Script {
+    /// Compiles `source` and runs its `init`, starting from fresh state.
+    fn compile(&mut self, source: &str) -> Result<(), String> {
+        self.source = source.to_string();
+        self.state = rhai::Map::new().into();
+        self.ast = None;
+        let ast = self.engine.compile(source).map_err(|e| {
+            let message = format!("script error: {}", e);
+            self.compile_error = Some(message.clone());
+            message
+        })?;
+        self.compile_error = None;
+        let has_init = ast
+            .iter_functions()
+            .any(|f| f.name == "init" && f.params.is_empty());
+        if has_init {
+            let options = CallFnOptions::new()
+                .eval_ast(false)
+                .bind_this_ptr(&mut self.state);
+            let init: Result<Dynamic, _> =
+                self.engine
+                    .call_fn_with_options(options, &mut Scope::new(), &ast, "init", ());
+            if let Err(err) = init {
+                let message = format!("init failed: {}", err);
+                self.compile_error = Some(message.clone());
+                return Err(message);
+            }
+        }
+        self.ast = Some(ast);
+        Ok(())
+    }
+}
+
+impl GuestLogic for Script {
+    fn decide(&mut self, input: &EventInput) -> Result<Option<Decision>, String> {
+        let Some(ast) = &self.ast else {
+            return Err(self
+                .compile_error
+                .clone()
+                .unwrap_or_else(|| "no script".to_string()));
+        };
+        let ev = rhai::serde::to_dynamic(input).map_err(|e| e.to_string())?;
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let answer: Dynamic = self
+            .engine
+            .call_fn_with_options(options, &mut Scope::new(), ast, "on_event", (ev,))
+            .map_err(|e| format!("on_event failed: {}", e))?;
+        if answer.is_unit() {
+            return Ok(None);
+        }
+        rhai::serde::from_dynamic(&answer)
+            .map(Some)
+            .map_err(|e| format!("on_event answered {}: {}", answer, e))
+    }
+
+    fn configure(&mut self, config: &Value) -> Result<Option<Value>, String> {
+        let source = config["script"].as_str().unwrap_or_default();
+        if source != self.source || self.compile_error.is_some() {
+            self.compile(source)?;
+        }
+        Ok(None)
+    }
+
+    fn snapshot(&mut self) -> Result<Option<Value>, String> {
+        let mut snapshot: Value = rhai::serde::from_dynamic(&self.state).unwrap_or_default();
+        if let (Value::Object(fields), Some(line)) =
+            (&mut snapshot, self.printed.lock().unwrap().clone())
+        {
+            fields.insert("print".to_string(), Value::String(line));
+        }
+        Ok(Some(snapshot))
+    }
+
+    fn seed(&mut self, seed: u64) -> Result<(), String> {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        Ok(())
+    }
+}
+
+/// A node whose behavior is a Rhai script.
+pub type ScriptedComponent = HostedComponent<Script>;
+
+/// Builds a `Scripted` node; the config's `script` replaces the default.
+pub fn create(data: Value) -> Option<Box<dyn Component>> {
+    let defaults = serde_json::Map::from_iter([(
+        "script".to_string(),
+        Value::String(DEFAULT_SCRIPT.to_string()),
+    )]);
+    let mut node = ScriptedComponent::new(SCRIPTED_KIND, "Scripted", defaults, Script::default());
+    node.apply_config(data, 0);
+    Some(Box::new(node))
+}
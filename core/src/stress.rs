@@ -0,0 +1,56 @@
+use crate::topology::Topology;
+
+/// Services behind each region's edge balancer; their servers call one
+/// backend service each.
+const FRONTEND_SERVICES: usize = 4;
+/// Replicas behind every service balancer.
+const REPLICAS: usize = 20;
+/// Nodes in one region: client, edge balancer, and a balancer plus its
+/// replicas for each frontend and backend service.
+const REGION_NODES: usize = 2 + 2 * FRONTEND_SERVICES * (1 + REPLICAS);
+/// Traffic each region's client sends.
+const REGION_RPS: f32 = 1_000.0;
+
+/// A production-shaped topology of at least `nodes` nodes, for finding
+/// where the engine and the canvas stop keeping up.
+///
+/// It is made of identical regions: a client sending 1,000 requests a
+/// second to an edge balancer, which spreads them over frontend services;
+/// each frontend server calls a backend service. Every service is a
+/// balancer in front of 20 replicas with plenty of spare capacity, so the
+/// work grows with the node count rather than with queues building up.
+pub fn stress_topology(nodes: usize) -> Topology {
+    let regions = nodes.div_ceil(REGION_NODES).max(1);
+    let mut topology = Topology::new();
+    for r in 1..=regions {
+        let region = format!("r{}", r);
+        let edge = format!("{}-edge", region);
+        topology = topology
+            .client_with(&format!("{}-users", region), |c| {
+                c.arrival_rate = REGION_RPS;
+                c.timeout = 2_000;
+            })
+            .lb(&edge)
+            .connect(&format!("{}-users", region), &edge);
+        for s in 1..=FRONTEND_SERVICES {
+            let frontend = format!("{}-fe{}", region, s);
+            let backend = format!("{}-be{}", region, s);
+            topology = topology
+                .lb(&format!("{}-lb", frontend))
+                .servers(&frontend, REPLICAS, |c| {
+                    c.service_time = 20;
+                    c.concurrency = 8;
+                })
+                .lb(&format!("{}-lb", backend))
+                .servers(&backend, REPLICAS, |c| {
+                    c.service_time = 10;
+                    c.concurrency = 8;
+                })
+                .connect(&edge, &format!("{}-lb", frontend))
+                .connect(&format!("{}-lb", frontend), &frontend)
+                .connect(&frontend, &format!("{}-lb", backend))
+                .connect(&format!("{}-lb", backend), &backend);
+        }
+    }
+    topology
+}
@@ -0,0 +1,266 @@
+use crate::network::{canonical_key, EdgeConfig};
+use crate::scenario::{Scenario, ScenarioAction};
+use crate::topology::{NodeSpec, Topology};
+use crate::traits::NodeId;
+use serde_json::json;
+
+/// A ready-made design to start from, stored as a topology file.
+#[derive(Debug, Clone, Copy)]
+pub struct Template {
+    pub name: &'static str,
+    pub description: &'static str,
+    json: &'static str,
+}
+
+impl Template {
+    pub fn topology(&self) -> Topology {
+        Topology::from_json(self.json).expect("built-in template is a valid topology")
+    }
+}
+
+/// The built-in templates, simplest first. The data lives in
+/// `core/templates/*.json` in the regular topology format.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "Single server",
+        description: "One client calling one server",
+        json: include_str!("../templates/single_server.json"),
+    },
+    Template {
+        name: "Load-balanced replicas",
+        description: "A least-connections balancer in front of three replicas",
+        json: include_str!("../templates/load_balanced.json"),
+    },
+    Template {
+        name: "Cache-aside",
+        description: "A fast cache tier answering most reads; one pick in five \
+                      falls through to the slow database",
+        json: include_str!("../templates/cache_aside.json"),
+    },
+    Template {
+        name: "Queue-based worker",
+        description: "A small worker pool behind a deep backlog, so bursts wait \
+                      instead of being rejected",
+        json: include_str!("../templates/queue_worker.json"),
+    },
+    Template {
+        name: "Multi-region",
+        description: "A global balancer splitting traffic between a near and a \
+                      far region, each with its own balancer and replicas",
+        json: include_str!("../templates/multi_region.json"),
+    },
+];
+
+/// Looks a template up by its name.
+pub fn template(name: &str) -> Option<&'static Template> {
+    TEMPLATES.iter().find(|t| t.name == name)
+}
+
+/// A ready-made chaos scenario, fitted to the topology it is built for.
+#[derive(Debug, Clone, Copy)]
+pub struct FaultTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    build: fn(&Topology, u64, u64) -> Option<Scenario>,
+}
+
+impl FaultTemplate {
+    /// The scenario for `topology`, with the fault starting at `start_us`
+    /// and over after `duration_us`; `None` if the topology has nothing the
+    /// fault applies to.
+    pub fn scenario(
+        &self,
+        topology: &Topology,
+        start_us: u64,
+        duration_us: u64,
+    ) -> Option<Scenario> {
+        (self.build)(topology, start_us, duration_us)
+    }
+}
+
+/// The built-in chaos scenarios.
+pub const FAULT_TEMPLATES: &[FaultTemplate] = &[
+    FaultTemplate {
+        name: "Zone outage",
+        description: "Takes down one region's balancer and replicas, or half the \
+                      replicas behind the widest balancer, then brings them back",
+        build: zone_outage,
+    },
+    FaultTemplate {
+        name: "Dependency brownout",
+        description: "The most shared server turns five times slower and fails \
+                      one request in twenty, without going down",
+        build: dependency_brownout,
+    },
+    FaultTemplate {
+        name: "Cache flush stampede",
+        description: "The fastest tier is flushed, so every request falls through \
+                      to the slower servers while clients pile on at three times \
+                      their rate",
+        build: cache_flush_stampede,
+    },
+    FaultTemplate {
+        name: "Creeping packet loss",
+        description: "Loss on the busiest client's link climbs from 1% to 20%, \
+                      then the link recovers",
+        build: creeping_packet_loss,
+    },
+];
+
+/// Looks a fault template up by its name.
+pub fn fault_template(name: &str) -> Option<&'static FaultTemplate> {
+    FAULT_TEMPLATES.iter().find(|t| t.name == name)
+}
+
+fn of_kind<'a>(topology: &'a Topology, kind: &'a str) -> impl Iterator<Item = &'a NodeSpec> {
+    topology.nodes.iter().filter(move |n| n.kind == kind)
+}
+
+fn node(topology: &Topology, id: NodeId) -> Option<&NodeSpec> {
+    topology.nodes.iter().find(|n| n.id == id)
+}
+
+fn callers(topology: &Topology, id: NodeId) -> usize {
+    topology
+        .nodes
+        .iter()
+        .filter(|n| n.targets.contains(&id))
+        .count()
+}
+
+fn service_time(node: &NodeSpec) -> u64 {
+    node.config["service_time"].as_u64().unwrap_or(0)
+}
+
+/// `root` and everything downstream of it.
+fn downstream(topology: &Topology, root: NodeId) -> Vec<NodeId> {
+    let mut seen = vec![root];
+    let mut i = 0;
+    while i < seen.len() {
+        for &t in node(topology, seen[i]).map_or(&[][..], |n| &n.targets) {
+            if !seen.contains(&t) {
+                seen.push(t);
+            }
+        }
+        i += 1;
+    }
+    seen
+}
+
+fn zone_outage(topology: &Topology, start_us: u64, duration_us: u64) -> Option<Scenario> {
+    let is_balancer = |id: &NodeId| node(topology, *id).is_some_and(|n| n.kind == "LoadBalancer");
+    // A balancer fanning out to balancers splits traffic between regions;
+    // otherwise the replicas of the widest balancer are spread over zones
+    let zone = match of_kind(topology, "LoadBalancer")
+        .find(|lb| lb.targets.len() >= 2 && lb.targets.iter().all(is_balancer))
+    {
+        Some(global) => downstream(topology, *global.targets.last()?),
+        None => {
+            let widest = of_kind(topology, "LoadBalancer")
+                .filter(|lb| lb.targets.len() >= 2)
+                .max_by_key(|lb| lb.targets.len())?;
+            widest.targets[widest.targets.len() - widest.targets.len() / 2..].to_vec()
+        }
+    };
+    let mut scenario = Scenario::new("Zone outage");
+    for &node_id in &zone {
+        scenario.insert(start_us, ScenarioAction::Kill { node_id });
+    }
+    for &node_id in &zone {
+        scenario.insert(start_us + duration_us, ScenarioAction::Revive { node_id });
+    }
+    Some(scenario)
+}
+
+fn dependency_brownout(topology: &Topology, start_us: u64, duration_us: u64) -> Option<Scenario> {
+    // Most callers first, then the slowest, which is likely the database
+    let dependency =
+        of_kind(topology, "Server").max_by_key(|n| (callers(topology, n.id), service_time(n)))?;
+    Some(Scenario::new("Dependency brownout").at(
+        start_us,
+        ScenarioAction::InjectLatency {
+            node_id: dependency.id,
+            slowdown: 5.0,
+            extra_latency_us: 0,
+            error_rate: 0.05,
+            duration_us: Some(duration_us),
+        },
+    ))
+}
+
+fn cache_flush_stampede(topology: &Topology, start_us: u64, duration_us: u64) -> Option<Scenario> {
+    let servers: Vec<&NodeSpec> = of_kind(topology, "Server").collect();
+    let fastest = servers.iter().map(|n| service_time(n)).min()?;
+    let slowest = servers.iter().map(|n| service_time(n)).max()?;
+    // Without a slower tier behind it there is nothing to fall through to
+    if fastest == slowest {
+        return None;
+    }
+    let mut scenario = Scenario::new("Cache flush stampede");
+    for cache in servers.iter().filter(|n| service_time(n) == fastest) {
+        scenario.insert(start_us, ScenarioAction::Kill { node_id: cache.id });
+        scenario.insert(
+            start_us + duration_us,
+            ScenarioAction::Revive { node_id: cache.id },
+        );
+    }
+    // Clients retrying on cold caches: the surge lasts the first quarter
+    for client in of_kind(topology, "Client") {
+        let rate = client.config["arrival_rate"].as_f64().unwrap_or(0.0);
+        scenario.insert(
+            start_us,
+            ScenarioAction::SetConfig {
+                node_id: client.id,
+                config: json!({ "arrival_rate": rate * 3.0 }),
+            },
+        );
+        scenario.insert(
+            start_us + duration_us / 4,
+            ScenarioAction::SetConfig {
+                node_id: client.id,
+                config: json!({ "arrival_rate": rate }),
+            },
+        );
+    }
+    Some(scenario)
+}
+
+/// Loss rates the link goes through, evenly spaced over the fault.
+const CREEPING_LOSS: [f32; 5] = [0.01, 0.02, 0.05, 0.1, 0.2];
+
+fn creeping_packet_loss(topology: &Topology, start_us: u64, duration_us: u64) -> Option<Scenario> {
+    let client = of_kind(topology, "Client")
+        .filter(|n| !n.targets.is_empty())
+        .max_by(|a, b| {
+            let rate = |n: &NodeSpec| n.config["arrival_rate"].as_f64().unwrap_or(0.0);
+            rate(a).total_cmp(&rate(b))
+        })?;
+    let (from, to) = (client.id, client.targets[0]);
+    let original = topology
+        .links
+        .iter()
+        .find(|l| (l.a, l.b) == canonical_key(from, to))
+        .map_or_else(EdgeConfig::default, |l| *l.link.get_config(from, to));
+
+    let mut scenario = Scenario::new("Creeping packet loss");
+    let step_us = duration_us / CREEPING_LOSS.len() as u64;
+    for (i, &loss) in CREEPING_LOSS.iter().enumerate() {
+        let edge = EdgeConfig {
+            packet_loss_rate: loss,
+            ..original
+        };
+        scenario.insert(
+            start_us + step_us * i as u64,
+            ScenarioAction::SetEdge { from, to, edge },
+        );
+    }
+    scenario.insert(
+        start_us + duration_us,
+        ScenarioAction::SetEdge {
+            from,
+            to,
+            edge: original,
+        },
+    );
+    Some(scenario)
+}
@@ -0,0 +1,328 @@
+use crate::components::create_component;
+use crate::cost::CostRate;
+use crate::engine::Simulation;
+use crate::network::{canonical_key, trim_number, Link};
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub const TOPOLOGY_VERSION: u32 = 1;
+
+/// A single component in a saved design.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NodeSpec {
+    pub id: NodeId,
+    /// Component kind as registered in `register_components!` (e.g. "Server")
+    pub kind: String,
+    /// Display name; empty keeps the default name of the kind
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Output of `Component::encode_config`
+    pub config: serde_json::Value,
+    /// Downstream nodes, in connection order
+    #[serde(default)]
+    pub targets: Vec<NodeId>,
+    /// Logical services the node is registered under
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<String>,
+    /// What the node costs to run
+    #[serde(default, skip_serializing_if = "CostRate::is_zero")]
+    pub cost: CostRate,
+}
+
+impl NodeSpec {
+    /// Display name, falling back to the kind when unnamed.
+    pub fn label(&self) -> &str {
+        if self.name.is_empty() {
+            &self.kind
+        } else {
+            &self.name
+        }
+    }
+
+    /// The settings that matter most for the kind in a few words, e.g.
+    /// "20ms × 8" for a server; `None` for kinds without a summary.
+    pub fn summary(&self) -> Option<String> {
+        let config = &self.config;
+        match self.kind.as_str() {
+            "Client" => Some(format!(
+                "{} rps",
+                trim_number(config["arrival_rate"].as_f64()?)
+            )),
+            "Server" => Some(format!(
+                "{}ms × {}",
+                config["service_time"].as_u64()?,
+                config["concurrency"].as_u64()?
+            )),
+            "LoadBalancer" => {
+                let strategy = config["strategy"].as_str()?;
+                Some(match config["service"].as_str() {
+                    Some(service) => format!("{} → {}", strategy, service),
+                    None => strategy.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A link between two nodes, keyed canonically (`a < b`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSpec {
+    pub a: NodeId,
+    pub b: NodeId,
+    pub link: Link,
+}
+
+/// Portable description of a simulated system.
+///
+/// This is the single on-disk format shared by the UI, tests and headless runs.
+/// It captures structure and configuration only; runtime state (queues,
+/// in-flight events, statistics) is not persisted.
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "nodes": [
+///     { "id": 1, "kind": "Client", "config": { "arrival_rate": 5.0, "timeout": 5000, "generation_id": 1 }, "targets": [2] },
+///     { "id": 2, "kind": "Server", "config": { "service_time": 200, "concurrency": 4, ... }, "targets": [] }
+///   ],
+///   "links": [
+///     { "a": 1, "b": 2, "link": { "min_to_max": { ... }, "max_to_min": { ... } } }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Topology {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub nodes: Vec<NodeSpec>,
+    #[serde(default)]
+    pub links: Vec<LinkSpec>,
+}
+
+fn default_version() -> u32 {
+    TOPOLOGY_VERSION
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self {
+            version: TOPOLOGY_VERSION,
+            nodes: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+}
+
+impl Topology {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(Self::from_json(&json)?)
+    }
+
+    /// Highest node id in use, handy for allocating new ids.
+    pub fn max_node_id(&self) -> NodeId {
+        self.nodes.iter().map(|n| n.id).max().unwrap_or(0)
+    }
+
+    /// Copy of this topology with `patch` merged into one node's config,
+    /// e.g. to build the variant side of an A/B comparison.
+    pub fn with_node_config(&self, node_id: NodeId, patch: &serde_json::Value) -> Topology {
+        let mut topology = self.clone();
+        if let Some(node) = topology.nodes.iter_mut().find(|n| n.id == node_id) {
+            if let (Some(dst), Some(src)) = (node.config.as_object_mut(), patch.as_object()) {
+                for (k, v) in src {
+                    dst.insert(k.clone(), v.clone());
+                }
+            }
+        }
+        topology
+    }
+
+    /// The nodes in `ids` with only the connections and links among them,
+    /// e.g. to copy part of a design.
+    pub fn subgraph(&self, ids: &[NodeId]) -> Topology {
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|n| ids.contains(&n.id))
+            .map(|n| NodeSpec {
+                targets: n
+                    .targets
+                    .iter()
+                    .copied()
+                    .filter(|t| ids.contains(t))
+                    .collect(),
+                ..n.clone()
+            })
+            .collect();
+        let links = self
+            .links
+            .iter()
+            .filter(|l| ids.contains(&l.a) && ids.contains(&l.b))
+            .cloned()
+            .collect();
+        Topology {
+            version: self.version,
+            nodes,
+            links,
+        }
+    }
+
+    /// Copy of this topology with client rates scaled so that together they
+    /// offer `total_rps`, keeping their relative shares (equal shares if all
+    /// are idle).
+    pub fn with_offered_load(&self, total_rps: f64) -> Topology {
+        let mut topology = self.clone();
+        let mut clients: Vec<&mut NodeSpec> = topology
+            .nodes
+            .iter_mut()
+            .filter(|n| n.kind == "Client")
+            .collect();
+        let rate = |n: &NodeSpec| n.config["arrival_rate"].as_f64().unwrap_or(0.0);
+        let current: f64 = clients.iter().map(|n| rate(n)).sum();
+        let count = clients.len() as f64;
+        for node in clients.iter_mut() {
+            let share = if current > 0.0 {
+                rate(node) / current
+            } else {
+                1.0 / count
+            };
+            node.config["arrival_rate"] = serde_json::json!(total_rps * share);
+        }
+        topology
+    }
+}
+
+impl Simulation {
+    /// Captures the current structure and configuration as a `Topology`.
+    /// Nodes and links are sorted by id so the output is stable.
+    pub fn to_topology(&self) -> Topology {
+        let mut nodes: Vec<NodeSpec> = self
+            .components
+            .iter()
+            .map(|(id, comp)| NodeSpec {
+                id: *id,
+                kind: comp.kind().to_string(),
+                name: comp.name().to_string(),
+                config: comp.encode_config(),
+                targets: comp.get_targets(),
+                services: self.services.services_of(*id),
+                cost: self.costs.rate(*id),
+            })
+            .collect();
+        nodes.sort_by_key(|n| n.id);
+
+        let mut links: Vec<LinkSpec> = self
+            .links
+            .iter()
+            .map(|(&(a, b), link)| LinkSpec {
+                a,
+                b,
+                link: link.clone(),
+            })
+            .collect();
+        links.sort_by_key(|l| (l.a, l.b));
+
+        Topology {
+            version: TOPOLOGY_VERSION,
+            nodes,
+            links,
+        }
+    }
+
+    /// Builds a ready-to-run simulation from a `Topology`.
+    ///
+    /// Components are created in id order (so per-node seeds are reproducible)
+    /// and their configs are applied, which kicks off client traffic.
+    /// Unknown component kinds are skipped.
+    pub fn from_topology(topology: &Topology, seed: u64) -> Self {
+        let mut sim = Simulation::new(seed);
+        let mut nodes: Vec<&NodeSpec> = topology.nodes.iter().collect();
+        nodes.sort_by_key(|n| n.id);
+
+        for node in nodes {
+            sim.add_node_spec(node);
+        }
+
+        for spec in &topology.links {
+            sim.links
+                .insert(canonical_key(spec.a, spec.b), spec.link.clone());
+        }
+        sim
+    }
+
+    /// Adds copies of the nodes in `topology` under fresh ids counting up
+    /// from `first_id`, with their configs, services, costs and the
+    /// connections and links among them. Copies start sending traffic right
+    /// away. Returns the new id of every copied node.
+    pub fn paste_topology(
+        &mut self,
+        topology: &Topology,
+        first_id: NodeId,
+    ) -> BTreeMap<NodeId, NodeId> {
+        let mut nodes: Vec<&NodeSpec> = topology.nodes.iter().collect();
+        nodes.sort_by_key(|n| n.id);
+        let new_ids: BTreeMap<NodeId, NodeId> = nodes
+            .iter()
+            .zip(first_id..)
+            .map(|(node, new_id)| (node.id, new_id))
+            .collect();
+
+        for node in nodes {
+            self.add_node_spec(&NodeSpec {
+                id: new_ids[&node.id],
+                targets: node
+                    .targets
+                    .iter()
+                    .filter_map(|t| new_ids.get(t).copied())
+                    .collect(),
+                ..node.clone()
+            });
+        }
+        for spec in &topology.links {
+            if let (Some(&a), Some(&b)) = (new_ids.get(&spec.a), new_ids.get(&spec.b)) {
+                self.links.insert(canonical_key(a, b), spec.link.clone());
+            }
+        }
+        new_ids
+    }
+
+    /// Creates, configures and adds the component described by `node`.
+    /// Unknown kinds are skipped.
+    fn add_node_spec(&mut self, node: &NodeSpec) {
+        let Some(mut comp) = create_component(&node.kind, node.config.clone()) else {
+            return;
+        };
+        if !node.name.is_empty() {
+            comp.set_name(&node.name);
+        }
+        for &t in &node.targets {
+            comp.add_target(t);
+        }
+        let current_conf = comp.encode_config();
+        let cmds = comp.apply_config(current_conf, node.id);
+        for cmd in cmds {
+            self.schedule(self.time + cmd.delay, cmd.node_id, cmd.event_type);
+        }
+        self.add_component(node.id, comp);
+        for service in &node.services {
+            self.register_service(service, node.id);
+        }
+        self.set_cost_rate(node.id, node.cost);
+    }
+}
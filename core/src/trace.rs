@@ -0,0 +1,370 @@
+use crate::engine::{Event, EventType, ScheduleCmd};
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Time a request spent at a single node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Span {
+    pub node_id: NodeId,
+    /// When the request arrived at the node
+    pub start_us: u64,
+    /// When the node sent its response upstream (`None` while open or if lost)
+    pub end_us: Option<u64>,
+    /// Outcome reported by the node (`None` while open or if lost)
+    pub success: Option<bool>,
+    /// Time between arriving and starting service
+    #[serde(default)]
+    pub queue_us: u64,
+    /// Time spent in service (`ProcessComplete` delays)
+    #[serde(default)]
+    pub service_us: u64,
+    /// Delay the node added before forwarding or answering (overheads,
+    /// retry backoff)
+    #[serde(default)]
+    pub processing_us: u64,
+}
+
+impl Span {
+    fn open(node_id: NodeId, start_us: u64) -> Self {
+        Self {
+            node_id,
+            start_us,
+            end_us: None,
+            success: None,
+            queue_us: 0,
+            service_us: 0,
+            processing_us: 0,
+        }
+    }
+
+    pub fn duration_us(&self) -> Option<u64> {
+        self.end_us.map(|e| e.saturating_sub(self.start_us))
+    }
+}
+
+/// One message of a sampled request crossing a link.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Hop {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub sent_us: u64,
+    /// `None` while in flight or if the message was lost
+    pub arrived_us: Option<u64>,
+}
+
+impl Hop {
+    pub fn transit_us(&self) -> Option<u64> {
+        self.arrived_us.map(|a| a.saturating_sub(self.sent_us))
+    }
+}
+
+/// All spans recorded for one sampled request, in arrival order.
+/// The first span always belongs to the originating client.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trace {
+    pub request_id: u128,
+    pub start_us: u64,
+    pub end_us: Option<u64>,
+    pub success: Option<bool>,
+    pub spans: Vec<Span>,
+    /// Every link crossing, requests and responses, in send order
+    #[serde(default)]
+    pub hops: Vec<Hop>,
+    timeout_us: u64,
+}
+
+impl Trace {
+    pub fn duration_us(&self) -> Option<u64> {
+        self.end_us.map(|e| e.saturating_sub(self.start_us))
+    }
+
+    fn open_span(&mut self, node_id: NodeId) -> Option<&mut Span> {
+        self.spans
+            .iter_mut()
+            .rev()
+            .find(|s| s.node_id == node_id && s.end_us.is_none())
+    }
+
+    fn close_span(&mut self, node_id: NodeId, end_us: u64, success: bool) {
+        if let Some(span) = self.open_span(node_id) {
+            span.end_us = Some(end_us);
+            span.success = Some(success);
+        }
+    }
+
+    /// Marks the message in flight to `node_id` as delivered.
+    fn deliver(&mut self, node_id: NodeId, now: u64) {
+        if let Some(hop) = self
+            .hops
+            .iter_mut()
+            .rev()
+            .find(|h| h.to == node_id && h.arrived_us.is_none())
+        {
+            hop.arrived_us = Some(now);
+        }
+    }
+}
+
+/// Collects per-hop spans for a deterministic sample of requests.
+///
+/// Sampling uses the per-client request counter embedded in the request id,
+/// so enabling tracing never perturbs the simulation's random streams.
+#[derive(Debug, Clone)]
+pub struct Tracer {
+    /// Trace one out of every `sample_every` requests
+    pub sample_every: u64,
+    /// Maximum number of completed traces kept (oldest are dropped)
+    pub max_traces: usize,
+    active: HashMap<u128, Trace>,
+    pub completed: VecDeque<Trace>,
+}
+
+impl Tracer {
+    pub fn new(sample_every: u64, max_traces: usize) -> Self {
+        Self {
+            sample_every: sample_every.max(1),
+            max_traces,
+            active: HashMap::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    pub fn is_sampled(&self, request_id: u128) -> bool {
+        (request_id as u64).is_multiple_of(self.sample_every)
+    }
+
+    /// Called by the engine for every event before it is delivered.
+    pub fn observe_event(&mut self, event: &Event) {
+        match &event.event_type {
+            EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                ..
+            } => {
+                if !self.is_sampled(*request_id) {
+                    return;
+                }
+                if !self.active.contains_key(request_id) {
+                    self.expire(event.time);
+                    let client = path.first().copied().unwrap_or(event.node_id);
+                    self.active.insert(
+                        *request_id,
+                        Trace {
+                            request_id: *request_id,
+                            start_us: *start_time,
+                            end_us: None,
+                            success: None,
+                            spans: vec![Span::open(client, *start_time)],
+                            // Clients send as soon as a request is generated
+                            hops: vec![Hop {
+                                from: client,
+                                to: event.node_id,
+                                sent_us: *start_time,
+                                arrived_us: None,
+                            }],
+                            timeout_us: *timeout,
+                        },
+                    );
+                }
+                if let Some(trace) = self.active.get_mut(request_id) {
+                    trace.deliver(event.node_id, event.time);
+                    trace.spans.push(Span::open(event.node_id, event.time));
+                }
+            }
+            EventType::Response {
+                request_id,
+                path,
+                start_time,
+                success,
+                timeout,
+                ..
+            } if path.len() == 1 => {
+                if let Some(mut trace) = self.active.remove(request_id) {
+                    trace.deliver(event.node_id, event.time);
+                    let ok = *success && event.time.saturating_sub(*start_time) <= *timeout;
+                    trace.close_span(event.node_id, event.time, ok);
+                    trace.end_us = Some(event.time);
+                    trace.success = Some(ok);
+                    self.push_completed(trace);
+                }
+            }
+            EventType::Response { request_id, .. } => {
+                if let Some(trace) = self.active.get_mut(request_id) {
+                    trace.deliver(event.node_id, event.time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Called by the engine for every command emitted by `node_id` at `now`.
+    /// Records service time and link crossings; a response leaving a node
+    /// closes that node's span.
+    pub fn observe_cmd(&mut self, node_id: NodeId, now: u64, cmd: &ScheduleCmd) {
+        let request_id = match &cmd.event_type {
+            EventType::Arrival { request_id, .. }
+            | EventType::ProcessComplete { request_id, .. }
+            | EventType::Response { request_id, .. } => request_id,
+            EventType::GenerateNext { .. } => return,
+        };
+        let Some(trace) = self.active.get_mut(request_id) else {
+            return;
+        };
+
+        if cmd.node_id == node_id {
+            if let EventType::ProcessComplete { .. } = cmd.event_type {
+                if let Some(span) = trace.open_span(node_id) {
+                    if span.service_us == 0 {
+                        span.queue_us = now.saturating_sub(span.start_us);
+                    }
+                    span.service_us += cmd.delay;
+                }
+            }
+            return;
+        }
+
+        if let Some(span) = trace.open_span(node_id) {
+            span.processing_us += cmd.delay;
+        }
+        trace.hops.push(Hop {
+            from: node_id,
+            to: cmd.node_id,
+            sent_us: now + cmd.delay,
+            arrived_us: None,
+        });
+        if let EventType::Response { success, .. } = &cmd.event_type {
+            trace.close_span(node_id, now + cmd.delay, *success);
+        }
+    }
+
+    /// Where the latency of successful sampled requests went, per node and
+    /// per directed link.
+    pub fn latency_breakdown(&self) -> LatencyBreakdown {
+        let mut breakdown = LatencyBreakdown::default();
+        for trace in self.completed.iter().filter(|t| t.success == Some(true)) {
+            breakdown.requests += 1;
+            for span in &trace.spans {
+                let t = breakdown
+                    .segments
+                    .entry(Segment::Node(span.node_id))
+                    .or_default();
+                t.queue_us += span.queue_us;
+                t.service_us += span.service_us;
+                t.processing_us += span.processing_us;
+            }
+            for hop in &trace.hops {
+                if let Some(transit) = hop.transit_us() {
+                    let t = breakdown
+                        .segments
+                        .entry(Segment::Link(hop.from, hop.to))
+                        .or_default();
+                    t.transit_us += transit;
+                }
+            }
+        }
+        breakdown
+    }
+
+    /// Moves traces whose deadline has long passed (lost packets) to completed.
+    fn expire(&mut self, now: u64) {
+        let expired: Vec<u128> = self
+            .active
+            .iter()
+            .filter(|(_, t)| now > t.start_us + t.timeout_us * 2)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            if let Some(mut trace) = self.active.remove(&id) {
+                trace.success = Some(false);
+                self.push_completed(trace);
+            }
+        }
+    }
+
+    fn push_completed(&mut self, trace: Trace) {
+        self.completed.push_back(trace);
+        while self.completed.len() > self.max_traces {
+            self.completed.pop_front();
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+        self.completed.clear();
+    }
+}
+
+/// A place on the request path where time is spent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Segment {
+    Node(NodeId),
+    /// Directed link `from -> to`
+    Link(NodeId, NodeId),
+}
+
+/// Latency split into where it was spent.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LatencySplit {
+    pub transit_us: u64,
+    pub queue_us: u64,
+    pub service_us: u64,
+    pub processing_us: u64,
+}
+
+impl LatencySplit {
+    pub fn total_us(&self) -> u64 {
+        self.transit_us + self.queue_us + self.service_us + self.processing_us
+    }
+
+    fn add(&mut self, other: &LatencySplit) {
+        self.transit_us += other.transit_us;
+        self.queue_us += other.queue_us;
+        self.service_us += other.service_us;
+        self.processing_us += other.processing_us;
+    }
+
+    fn div(&self, n: u64) -> LatencySplit {
+        let n = n.max(1);
+        LatencySplit {
+            transit_us: self.transit_us / n,
+            queue_us: self.queue_us / n,
+            service_us: self.service_us / n,
+            processing_us: self.processing_us / n,
+        }
+    }
+}
+
+/// Latency of sampled requests summed per path segment; see
+/// `Tracer::latency_breakdown`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LatencyBreakdown {
+    /// Number of requests aggregated
+    pub requests: u64,
+    /// Total time per segment over all aggregated requests
+    pub segments: BTreeMap<Segment, LatencySplit>,
+}
+
+impl LatencyBreakdown {
+    /// Mean time per request spent in one segment.
+    pub fn mean_for(&self, segment: Segment) -> LatencySplit {
+        self.segments
+            .get(&segment)
+            .map_or_else(LatencySplit::default, |t| t.div(self.requests))
+    }
+
+    /// Mean time per request across the whole path, for a stacked bar.
+    pub fn mean_total(&self) -> LatencySplit {
+        let mut sum = LatencySplit::default();
+        for t in self.segments.values() {
+            sum.add(t);
+        }
+        sum.div(self.requests)
+    }
+}
@@ -1,14 +1,51 @@
-use crate::engine::{Event, ScheduleCmd, SystemInspector};
+use crate::engine::{Commands, Event, EventHandle, ScheduleCmd, SystemInspector};
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 
 // Re-export VisualState from components where it is generated by macro
-pub use crate::components::VisualState;
+pub use crate::components::{CustomStats, StatValue, VisualState};
 
 pub type NodeId = u32;
 
-pub trait Component: Any {
-    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Vec<ScheduleCmd>;
+/// How well a node is working. Components themselves only know whether
+/// they are up; degradation is applied by the engine around them.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Health {
+    #[default]
+    Healthy,
+    /// Up but slow and flaky: work takes `slowdown` times as long and
+    /// fails with probability `error_rate`
+    Degraded { slowdown: f32, error_rate: f32 },
+    /// Not serving at all
+    Down,
+}
+
+impl Health {
+    /// Whether the node takes traffic at all.
+    pub fn is_up(&self) -> bool {
+        !matches!(self, Health::Down)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Health::Healthy => "Healthy",
+            Health::Degraded { .. } => "Degraded",
+            Health::Down => "Down",
+        }
+    }
+}
+
+pub trait Component: Any + Send {
+    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Commands;
+
+    /// Called after the commands returned by `on_event` were scheduled, with
+    /// one entry per command in the same order (`None` if the network dropped
+    /// it). Store a handle to cancel the event later via
+    /// `SystemInspector::cancel`.
+    fn on_scheduled(&mut self, _handles: &[Option<EventHandle>]) {}
     fn name(&self) -> &str;
+    /// Changes the display name. Components with a fixed name ignore it.
+    fn set_name(&mut self, _name: &str) {}
     fn kind(&self) -> &str;
 
     // Serialization & Hot Update
@@ -19,8 +56,39 @@ pub trait Component: Any {
 
     // Metrics
     fn active_requests(&self) -> u32;
+    /// Requests waiting for a free worker.
+    fn queue_depth(&self) -> u32 {
+        0
+    }
+    /// Smoothed time recent requests spent inside this node, queueing
+    /// included. `None` until the node has completed a request.
+    fn recent_latency_us(&self) -> Option<u64> {
+        None
+    }
+    /// Smoothed time recent requests waited before processing started.
+    fn queue_wait_us(&self) -> Option<u64> {
+        None
+    }
+    /// Smoothed processing time of recent requests, waiting excluded.
+    fn service_time_us(&self) -> Option<u64> {
+        None
+    }
     fn display_throughput(&self) -> f32;
+    /// Requests received since the last stats reset.
+    fn received_count(&self) -> u64 {
+        0
+    }
+    /// Fraction of capacity in use (0.0..=1.0), for nodes with a fixed
+    /// capacity.
+    fn utilization(&self) -> f32 {
+        0.0
+    }
     fn error_count(&self) -> u64;
+    /// Requests this node failed because their deadline could not be met.
+    /// Reported separately from `error_count`.
+    fn deadline_exceeded_count(&self) -> u64 {
+        0
+    }
 
     fn set_healthy(&mut self, healthy: bool);
     fn is_healthy(&self) -> bool;
@@ -37,4 +105,30 @@ pub trait Component: Any {
     fn reset_internal_stats(&mut self);
 
     fn set_seed(&mut self, seed: u64);
+
+    /// Called when the node is removed from the simulation. Returns failure
+    /// responses for requests held internally (e.g. queued) so callers are
+    /// notified. Events already in the engine queue are handled by the engine.
+    fn abort_pending(&mut self, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        vec![]
+    }
+}
+
+impl dyn Component {
+    pub fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    pub fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    /// Returns the concrete component if it is a `T`.
+    pub fn downcast_ref<T: Component>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    pub fn downcast_mut<T: Component>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut::<T>()
+    }
 }
@@ -0,0 +1,50 @@
+use crate::engine::Simulation;
+use crate::traits::NodeId;
+
+/// A request or response currently travelling over a link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transit {
+    pub from: NodeId,
+    pub to: NodeId,
+    /// Virtual time the message left `from`
+    pub sent_us: u64,
+    /// Virtual time it reaches `to`
+    pub arrive_us: u64,
+    /// A response on its way back rather than a request
+    pub is_response: bool,
+}
+
+impl Transit {
+    /// How far along the link the message is at `now`, from 0.0 (just
+    /// sent) to 1.0 (arriving).
+    pub fn progress(&self, now_us: u64) -> f32 {
+        let span = self.arrive_us.saturating_sub(self.sent_us);
+        if span == 0 {
+            return 1.0;
+        }
+        (now_us.saturating_sub(self.sent_us) as f64 / span as f64).min(1.0) as f32
+    }
+}
+
+impl Simulation {
+    /// Messages on the wire between two different nodes, oldest first.
+    pub fn in_transit(&self) -> impl Iterator<Item = &Transit> {
+        self.transit.values()
+    }
+
+    /// Messages on the wire from `from` to `to`, oldest first.
+    pub fn in_transit_on(&self, from: NodeId, to: NodeId) -> impl Iterator<Item = &Transit> {
+        self.in_transit()
+            .filter(move |t| t.from == from && t.to == to)
+    }
+
+    pub(crate) fn transit_sent(&mut self, seq: u64, transit: Transit) {
+        self.transit.insert(seq, transit);
+    }
+
+    /// Forgets the message carried by event `seq` once it is delivered,
+    /// cancelled or dropped.
+    pub(crate) fn transit_ended(&mut self, seq: u64) {
+        self.transit.remove(&seq);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::components::load_balancer::LoadBalancer;
+use crate::engine::Simulation;
+use crate::traits::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A structural problem found by `Simulation::validate`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Nodes forming a routing loop (requests will hit the hop limit)
+    Cycle { nodes: Vec<NodeId> },
+    /// A client that has nowhere to send traffic
+    ClientWithoutTarget { node_id: NodeId },
+    /// A load balancer with zero backends
+    NoBackends { node_id: NodeId },
+    /// A node no client can reach; it will never receive traffic
+    Unreachable { node_id: NodeId },
+    /// A connection pointing at a node that no longer exists
+    DanglingTarget { from: NodeId, to: NodeId },
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::Cycle { .. }
+            | Diagnostic::NoBackends { .. }
+            | Diagnostic::DanglingTarget { .. } => Severity::Error,
+            Diagnostic::ClientWithoutTarget { .. } | Diagnostic::Unreachable { .. } => {
+                Severity::Warning
+            }
+        }
+    }
+
+    /// Nodes the diagnostic refers to, for highlighting.
+    pub fn nodes(&self) -> Vec<NodeId> {
+        match self {
+            Diagnostic::Cycle { nodes } => nodes.clone(),
+            Diagnostic::ClientWithoutTarget { node_id }
+            | Diagnostic::NoBackends { node_id }
+            | Diagnostic::Unreachable { node_id } => vec![*node_id],
+            Diagnostic::DanglingTarget { from, .. } => vec![*from],
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Diagnostic::Cycle { nodes } => {
+                let ids: Vec<String> = nodes.iter().map(|n| format!("#{}", n)).collect();
+                format!("Nodes in a routing loop: {}", ids.join(", "))
+            }
+            Diagnostic::ClientWithoutTarget { node_id } => {
+                format!("Client #{} has no target", node_id)
+            }
+            Diagnostic::NoBackends { node_id } => {
+                format!("Load balancer #{} has no backends", node_id)
+            }
+            Diagnostic::Unreachable { node_id } => {
+                format!("#{} is not reachable from any client", node_id)
+            }
+            Diagnostic::DanglingTarget { from, to } => {
+                format!("#{} targets missing node #{}", from, to)
+            }
+        }
+    }
+}
+
+impl Simulation {
+    /// Checks the topology for structural problems.
+    /// Results are ordered by node id so the output is stable between frames.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let graph: BTreeMap<NodeId, Vec<NodeId>> = self
+            .components
+            .iter()
+            .map(|(id, comp)| {
+                let mut targets = comp.get_targets();
+                // Service-routed balancers reach whatever is registered now
+                if let Some(lb) = comp.downcast_ref::<LoadBalancer>() {
+                    if let Some(service) = &lb.config.read().unwrap().service {
+                        targets.extend(self.services.members(service));
+                    }
+                }
+                (*id, targets)
+            })
+            .collect();
+        let mut diagnostics = Vec::new();
+
+        for (&id, targets) in &graph {
+            let kind = self.components[&id].kind();
+            if kind == "Client" && targets.is_empty() {
+                diagnostics.push(Diagnostic::ClientWithoutTarget { node_id: id });
+            }
+            if kind == "LoadBalancer" && targets.is_empty() {
+                diagnostics.push(Diagnostic::NoBackends { node_id: id });
+            }
+            for &t in targets {
+                if !graph.contains_key(&t) {
+                    diagnostics.push(Diagnostic::DanglingTarget { from: id, to: t });
+                }
+            }
+        }
+
+        for nodes in find_cycles(&graph) {
+            diagnostics.push(Diagnostic::Cycle { nodes });
+        }
+
+        // Reachability from every client
+        let mut reached: HashSet<NodeId> = HashSet::new();
+        let mut stack: Vec<NodeId> = graph
+            .keys()
+            .copied()
+            .filter(|id| self.components[id].kind() == "Client")
+            .collect();
+        while let Some(id) = stack.pop() {
+            if reached.insert(id) {
+                if let Some(targets) = graph.get(&id) {
+                    stack.extend(targets.iter().copied());
+                }
+            }
+        }
+        for &id in graph.keys() {
+            if !reached.contains(&id) {
+                diagnostics.push(Diagnostic::Unreachable { node_id: id });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Strongly connected components with more than one node (or a self-loop),
+/// found with Tarjan's algorithm. Each cycle is sorted by node id.
+fn find_cycles(graph: &BTreeMap<NodeId, Vec<NodeId>>) -> Vec<Vec<NodeId>> {
+    struct Tarjan<'a> {
+        graph: &'a BTreeMap<NodeId, Vec<NodeId>>,
+        index: BTreeMap<NodeId, usize>,
+        low: BTreeMap<NodeId, usize>,
+        on_stack: HashSet<NodeId>,
+        stack: Vec<NodeId>,
+        next: usize,
+        out: Vec<Vec<NodeId>>,
+    }
+
+    impl Tarjan<'_> {
+        fn open(&mut self, v: NodeId) {
+            self.index.insert(v, self.next);
+            self.low.insert(v, self.next);
+            self.next += 1;
+            self.stack.push(v);
+            self.on_stack.insert(v);
+        }
+
+        fn lower(&mut self, v: NodeId, to: usize) {
+            let lv = self.low.get_mut(&v).unwrap();
+            *lv = (*lv).min(to);
+        }
+
+        fn close(&mut self, v: NodeId) {
+            if self.low[&v] == self.index[&v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(&w);
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                let self_loop = self.graph[&v].contains(&v);
+                if component.len() > 1 || self_loop {
+                    component.sort();
+                    self.out.push(component);
+                }
+            }
+        }
+
+        /// Depth-first search with an explicit stack of (node, next target
+        /// index), so long chains can't overflow the call stack.
+        fn visit(&mut self, root: NodeId) {
+            self.open(root);
+            let mut work = vec![(root, 0)];
+            while let Some((v, i)) = work.last_mut() {
+                let v = *v;
+                let next = self.graph.get(&v).and_then(|t| t.get(*i)).copied();
+                *i += 1;
+                match next {
+                    Some(w) if !self.graph.contains_key(&w) => {}
+                    Some(w) if !self.index.contains_key(&w) => {
+                        self.open(w);
+                        work.push((w, 0));
+                    }
+                    Some(w) => {
+                        if self.on_stack.contains(&w) {
+                            self.lower(v, self.index[&w]);
+                        }
+                    }
+                    None => {
+                        work.pop();
+                        if let Some(&(parent, _)) = work.last() {
+                            self.lower(parent, self.low[&v]);
+                        }
+                        self.close(v);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut t = Tarjan {
+        graph,
+        index: BTreeMap::new(),
+        low: BTreeMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        next: 0,
+        out: Vec::new(),
+    };
+    for &v in graph.keys() {
+        if !t.index.contains_key(&v) {
+            t.visit(v);
+        }
+    }
+    t.out.sort();
+    t.out
+}
@@ -0,0 +1,59 @@
+use slay_core::{EdgeConfig, Link, ScenarioAction, Topology, DEFAULT_MAX_PENDING_EVENTS};
+
+/// `rps` over a 100ms link, so a tenth of a second of requests is in
+/// flight each way.
+fn far_away(rps: f32) -> Topology {
+    let slow = EdgeConfig {
+        latency_us: 100_000,
+        ..EdgeConfig::default()
+    };
+    Topology::new()
+        .client("users", rps)
+        .server("api", |s| {
+            s.service_time = 1;
+            s.concurrency = 1_000;
+        })
+        .connect_with(
+            "users",
+            "api",
+            Link {
+                min_to_max: slow,
+                max_to_min: slow,
+            },
+        )
+}
+
+#[test]
+fn test_generators_are_shed_at_the_pending_cap() {
+    let topology = far_away(50_000.0);
+    let users = topology.id("users").unwrap();
+    let mut sim = topology.build(2);
+    assert_eq!(sim.max_pending_events, DEFAULT_MAX_PENDING_EVENTS);
+    sim.max_pending_events = 2_000;
+
+    for _ in 0..10 {
+        sim.advance_by(100_000);
+        // Generator ticks and responses can still be scheduled past the cap
+        assert!(sim.events.len() < 2_500, "{}", sim.events.len());
+    }
+    assert!(sim.is_shedding());
+    assert!(sim.shed_requests > 10_000);
+    // What was let through still completes
+    assert!(sim.success_count > 1_000);
+    assert_eq!(sim.failure_count, 0);
+
+    sim.apply_action(ScenarioAction::SetConfig {
+        node_id: users,
+        config: serde_json::json!({ "arrival_rate": 100.0 }),
+    });
+    sim.advance_by(2_000_000);
+    assert!(!sim.is_shedding());
+}
+
+#[test]
+fn test_runs_under_the_cap_shed_nothing() {
+    let mut sim = far_away(5_000.0).build(2);
+    sim.advance_by(1_000_000);
+    assert_eq!(sim.shed_requests, 0);
+    assert!(!sim.is_shedding());
+}
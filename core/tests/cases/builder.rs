@@ -0,0 +1,71 @@
+use crate::common::TestHarness;
+use slay_core::components::load_balancer::BalancingStrategy;
+use slay_core::{EdgeConfig, Link, Topology};
+
+#[test]
+fn test_builder_wires_nodes_by_name() {
+    let topology = Topology::new()
+        .client("web", 100.0)
+        .lb_with("lb", |c| c.strategy = BalancingStrategy::LeastConnections)
+        .servers("api", 3, |s| {
+            s.service_time = 20;
+            s.concurrency = 8;
+        })
+        .server("db", |s| {
+            s.service_time = 50;
+            s.concurrency = 16;
+        })
+        .connect("web", "lb")
+        .connect("lb", "api")
+        .connect("api", "db")
+        .service("api", "api");
+
+    assert_eq!(topology.id("web"), Some(1));
+    assert_eq!(topology.id("lb"), Some(2));
+    assert_eq!(topology.ids("api"), vec![3, 4, 5]);
+    assert_eq!(topology.id("api"), None, "a group is not a single node");
+    assert_eq!(topology.id("api-2"), Some(4));
+    assert_eq!(topology.nodes[1].targets, vec![3, 4, 5]);
+    assert!(topology.nodes[2..5].iter().all(|n| n.targets == vec![6]));
+    assert_eq!(topology.nodes[1].config["strategy"], "LeastConnections");
+    assert_eq!(topology.nodes[3].config["service_time"], 20);
+    assert_eq!(topology.nodes[3].services, vec!["api".to_string()]);
+
+    let mut h = TestHarness::from_topology(&topology, 1);
+    assert_eq!(h.sim.validate(), vec![]);
+    h.run_for(2000);
+    assert!(h.sim.success_count > 150, "{} served", h.sim.success_count);
+    assert_eq!(h.sim.components[&4].name(), "api-2");
+}
+
+#[test]
+fn test_builder_links_and_round_trip() {
+    let slow = EdgeConfig {
+        latency_us: 40_000,
+        ..EdgeConfig::default()
+    };
+    let topology = Topology::new()
+        .client("web", 10.0)
+        .server("api", |_| {})
+        .connect_with(
+            "web",
+            "api",
+            Link {
+                min_to_max: slow,
+                max_to_min: slow,
+            },
+        );
+    assert_eq!(topology.links.len(), 1);
+    assert_eq!(topology.nodes[0].targets, vec![2]);
+
+    let sim = topology.build(7);
+    let saved = sim.to_topology();
+    assert_eq!(saved.nodes, topology.nodes);
+    assert_eq!(saved.links[0].link.get_config(1, 2).latency_us, 40_000);
+}
+
+#[test]
+#[should_panic(expected = "no node or group named 'nope'")]
+fn test_builder_rejects_unknown_names() {
+    let _ = Topology::new().client("web", 1.0).connect("web", "nope");
+}
@@ -0,0 +1,146 @@
+use slay_core::{FluidMode, Metadata, Server, ServerConfig, Simulation, Topology};
+
+/// An api server in front of a 20ms database.
+fn cached_api(rps: f32, cache: impl FnOnce(&mut ServerConfig)) -> Topology {
+    Topology::new()
+        .client_with("users", |c| {
+            c.arrival_rate = rps;
+            c.metadata = Metadata::default().with("tenant", "acme");
+        })
+        .server("api", |s| {
+            s.service_time = 10;
+            s.concurrency = 1_000;
+            cache(s);
+        })
+        .server("db", |s| {
+            s.service_time = 20;
+            s.concurrency = 1_000;
+        })
+        .connect("users", "api")
+        .connect("api", "db")
+}
+
+fn api(sim: &Simulation, topology: &Topology) -> (u64, u64) {
+    let server = sim
+        .get_component_as::<Server>(topology.id("api").unwrap())
+        .unwrap();
+    (server.cache_hits, server.cache_misses)
+}
+
+fn received(sim: &Simulation, topology: &Topology, name: &str) -> u64 {
+    sim.components[&topology.id(name).unwrap()].received_count()
+}
+
+#[test]
+fn test_hits_skip_downstream_calls_and_service_time() {
+    let uncached = cached_api(500.0, |_| {});
+    let mut before = uncached.build(6);
+    before.advance_by(10_000_000);
+
+    let topology = cached_api(500.0, |s| s.cache_hit_ratio = 0.8);
+    let mut sim = topology.build(6);
+    sim.advance_by(10_000_000);
+
+    let (hits, misses) = api(&sim, &topology);
+    let hit_rate = hits as f64 / (hits + misses) as f64;
+    assert!((0.78..0.82).contains(&hit_rate), "{}", hit_rate);
+    let db = received(&sim, &topology, "db") as f64 / received(&sim, &topology, "api") as f64;
+    assert!((0.18..0.22).contains(&db), "{}", db);
+    assert_eq!(sim.failure_count, 0);
+
+    // Most requests now take a millisecond at the api and nothing else
+    let p50 = |sim: &Simulation| sim.histogram.value_at_quantile(0.5);
+    assert!(
+        p50(&sim) * 2 < p50(&before),
+        "{} {}",
+        p50(&sim),
+        p50(&before)
+    );
+}
+
+#[test]
+fn test_keyed_cache_misses_once_per_ttl() {
+    let topology = cached_api(200.0, |s| {
+        s.cache_key = Some("tenant".to_string());
+        s.cache_ttl_ms = 1_000;
+    });
+    let mut sim = topology.build(6);
+    sim.advance_by(10_000_000);
+
+    // Each expiry costs the misses sent before the refill comes back
+    let (hits, misses) = api(&sim, &topology);
+    assert!((10..150).contains(&misses), "{}", misses);
+    assert!(hits > 1_800, "{}", hits);
+    assert_eq!(received(&sim, &topology, "db"), misses);
+}
+
+#[test]
+fn test_requests_without_a_class_always_miss() {
+    let topology = cached_api(200.0, |s| s.cache_key = Some("endpoint".to_string()));
+    let mut sim = topology.build(6);
+    sim.advance_by(5_000_000);
+
+    let (hits, misses) = api(&sim, &topology);
+    assert_eq!(hits, 0);
+    assert!(misses > 900);
+}
+
+#[test]
+fn test_servers_without_a_cache_count_nothing() {
+    let topology = cached_api(200.0, |_| {});
+    let mut sim = topology.build(6);
+    sim.advance_by(1_000_000);
+    assert_eq!(api(&sim, &topology), (0, 0));
+}
+
+#[test]
+fn test_rejected_requests_count_neither_hit_nor_miss() {
+    // One slow thread and a short backlog turn most requests away
+    let topology = cached_api(100.0, |s| {
+        s.cache_hit_ratio = 0.5;
+        s.concurrency = 1;
+        s.backlog_limit = 1;
+        s.service_time = 100;
+    });
+    let mut sim = topology.build(6);
+    sim.advance_by(5_000_000);
+
+    let (hits, misses) = api(&sim, &topology);
+    let api_node = &sim.components[&topology.id("api").unwrap()];
+    assert!(api_node.error_count() > 200, "{}", api_node.error_count());
+    assert_eq!(
+        hits + misses + api_node.error_count(),
+        api_node.received_count()
+    );
+}
+
+#[test]
+fn test_expired_classes_leave_the_cache() {
+    let topology = cached_api(200.0, |s| {
+        s.cache_key = Some("tenant".to_string());
+        s.cache_ttl_ms = 1_000;
+    });
+    let api_id = topology.id("api").unwrap();
+    let mut sim = topology.build(6);
+    sim.advance_by(100_000);
+    let server = sim.get_component_as_mut::<Server>(api_id).unwrap();
+    server.cache.insert("gone".to_string(), 50_000);
+    server.cache.insert("stale".to_string(), 1_000);
+
+    sim.advance_by(3_000_000);
+    let server = sim.get_component_as::<Server>(api_id).unwrap();
+    assert_eq!(server.cache.keys().collect::<Vec<_>>(), ["acme"]);
+}
+
+#[test]
+fn test_fluid_hits_skip_downstream_calls() {
+    let topology = cached_api(50_000.0, |s| s.cache_hit_ratio = 0.75);
+    let db = topology.id("db").unwrap();
+    let mut sim = topology.build(6);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(2_000_000);
+
+    let calls = sim.fluid_load(db).unwrap().arrival_rps;
+    assert!((12_000.0..13_000.0).contains(&calls), "{}", calls);
+    assert_eq!(sim.failure_count, 0);
+}
@@ -0,0 +1,130 @@
+use slay_core::{canonical_key, LatencyTable, Topology};
+
+fn link(topology: &Topology, a: u32, b: u32) -> Option<&slay_core::Link> {
+    let key = canonical_key(a, b);
+    topology
+        .links
+        .iter()
+        .find(|l| (l.a, l.b) == key)
+        .map(|l| &l.link)
+}
+
+#[test]
+fn test_probes_set_latency_jitter_and_loss() {
+    let mut topology = Topology::new()
+        .client("web", 50.0)
+        .lb("lb")
+        .servers("api", 2, |_| {})
+        .connect("web", "lb")
+        .connect("lb", "api");
+    let table = LatencyTable::from_csv(
+        "# probes from the load balancer host\n\
+         source,destination,rtt_ms,probe\n\
+         lb,api,2.0,1\n\
+         lb,api,3.0,2\n\
+         lb,api,9.0,3\n\
+         lb,api,timeout,4\n\
+         \n\
+         web,lb,40,1\n\
+         db,lb,1,1\n",
+    )
+    .unwrap();
+    assert_eq!(table.latency_us("api", "lb"), Some(1000));
+    assert_eq!(table.latency_us("lb", "cache"), None);
+
+    let calibration = table.calibrate(&mut topology);
+    assert_eq!(calibration.links, vec![(1, 2), (2, 3), (2, 4)]);
+    assert_eq!(calibration.unmatched, vec!["db".to_string()]);
+
+    // The replica group's probes apply to each replica, both ways
+    for api in [3, 4] {
+        let link = link(&topology, 2, api).unwrap();
+        for edge in [link.min_to_max, link.max_to_min] {
+            assert_eq!(edge.latency_us, 1000);
+            assert_eq!(edge.jitter_us, 1000);
+            assert_eq!(edge.packet_loss_rate, 0.25);
+        }
+    }
+    let web = link(&topology, 1, 2).unwrap().min_to_max;
+    assert_eq!((web.latency_us, web.jitter_us), (20_000, 0));
+    assert_eq!(web.packet_loss_rate, 0.0);
+
+    // The lossy links fail some of the run's requests
+    let mut sim = topology.build(1);
+    sim.advance_by(2_000_000);
+    assert!(sim.success_count > 20);
+    assert!(sim.failure_count > 0);
+}
+
+#[test]
+fn test_region_matrix_applies_through_zones() {
+    let mut topology = Topology::new()
+        .client("users", 50.0)
+        .lb("global")
+        .lb("eu")
+        .lb("us")
+        .servers("eu-api", 2, |_| {})
+        .servers("us-api", 2, |_| {})
+        .connect("users", "global")
+        .connect("global", "eu")
+        .connect("global", "us")
+        .connect("eu", "eu-api")
+        .connect("us", "us-api")
+        .service("us-api", "orders");
+    let before = link(&topology, 1, 2).is_none();
+    let table = LatencyTable::from_csv(
+        "region,eu-west-1,us-east-1,ap-south-1\n\
+         eu-west-1,1.2,72,-\n\
+         us-east-1,70,0.8,\n",
+    )
+    .unwrap()
+    .place("global", "eu-west-1")
+    .place("eu", "eu-west-1")
+    .place("eu-api", "eu-west-1")
+    .place("us", "us-east-1")
+    .place("orders", "us-east-1");
+
+    let calibration = table.calibrate(&mut topology);
+    assert!(before);
+    // The client has no zone, so its link is left alone
+    assert!(link(&topology, 1, 2).is_none());
+    assert_eq!(calibration.links.len(), 6);
+    assert!(calibration.unmatched.is_empty());
+
+    let ocean = link(&topology, 2, 4).unwrap().min_to_max;
+    assert_eq!((ocean.latency_us, ocean.jitter_us), (35_000, 2000));
+    let local = link(&topology, 3, 5).unwrap().max_to_min;
+    assert_eq!((local.latency_us, local.jitter_us), (600, 0));
+    assert_eq!(link(&topology, 4, 8).unwrap().min_to_max.latency_us, 400);
+}
+
+#[test]
+fn test_node_measurements_win_over_zones() {
+    let mut topology = Topology::new()
+        .client("web", 10.0)
+        .server("db", |_| {})
+        .connect("web", "db");
+    let table = LatencyTable::from_csv(
+        "from,to,rtt_us\n\
+         web,db,500\n\
+         dc-a,dc-b,40000\n",
+    )
+    .unwrap()
+    .place("web", "dc-a")
+    .place("db", "dc-b");
+    let calibration = table.calibrate(&mut topology);
+    assert_eq!(calibration.links, vec![(1, 2)]);
+    assert_eq!(link(&topology, 1, 2).unwrap().min_to_max.latency_us, 250);
+}
+
+#[test]
+fn test_invalid_tables_are_rejected() {
+    assert!(LatencyTable::from_csv("").is_err());
+    let err = LatencyTable::from_csv("from,to,hops\na,b,3\n").unwrap_err();
+    assert_eq!(err.line, 1);
+    let err = LatencyTable::from_csv("from,to,rtt_ms\na,b,1\n\na,b,fast\n").unwrap_err();
+    assert_eq!(err.line, 4);
+    assert!(err.message.contains("fast"), "{}", err);
+    let err = LatencyTable::from_csv(",a,b\na,1,2,3\n").unwrap_err();
+    assert_eq!(err.line, 2);
+}
@@ -0,0 +1,171 @@
+use crate::common::TestHarness;
+use slay_core::traits::VisualState;
+use slay_core::*;
+
+fn arrival(request_id: u128) -> EventType {
+    EventType::Arrival {
+        request_id,
+        path: smallvec![1],
+        start_time: 0,
+        timeout: 1_000_000,
+        max_hops: 8,
+        metadata: Metadata::default(),
+    }
+}
+
+#[test]
+fn test_cancelled_event_never_fires() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 1.0);
+    h.add_server(2, "S1", 10, 4, 10);
+
+    let keep = h.sim.schedule(1_000, 2, arrival(1));
+    let drop = h.sim.schedule(2_000, 2, arrival(2));
+    assert_ne!(keep, drop);
+    assert!(h.sim.cancel(drop));
+    assert!(!h.sim.cancel(drop), "Cancelling twice is a no-op");
+
+    h.sim.record_events(100);
+    h.run_for(100);
+
+    let log = h.sim.event_log.as_ref().unwrap();
+    let delivered: Vec<u128> = log
+        .events
+        .iter()
+        .filter_map(|e| match e.event_type {
+            EventType::Arrival { request_id, .. } if e.node_id == 2 => Some(request_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(delivered, vec![1]);
+    assert!(
+        h.sim.cancelled.is_empty(),
+        "Tombstones are discarded when popped"
+    );
+}
+
+#[test]
+fn test_cancelled_head_does_not_skip_ahead() {
+    let mut h = TestHarness::new();
+    h.add_server(2, "S1", 10, 4, 10);
+
+    let early = h.sim.schedule(1_000, 2, arrival(1));
+    h.sim.schedule(50_000, 2, arrival(2));
+    h.sim.cancel(early);
+
+    h.run_for(10);
+    assert_eq!(h.sim.events.len(), 1, "Later event must stay queued");
+    assert_eq!(h.sim.peek_event().unwrap().time, 50_000);
+}
+
+#[test]
+fn test_unknown_handle_is_rejected() {
+    let mut h = TestHarness::new();
+    let handle = h
+        .sim
+        .schedule(10, 1, EventType::GenerateNext { generation_id: 1 });
+    assert!(!h.sim.cancel(EventHandle(handle.0 + 100)));
+}
+
+#[test]
+fn test_stale_tombstones_are_pruned() {
+    let mut h = TestHarness::new();
+    h.add_server(2, "S1", 10, 4, 10);
+    let fired = h.sim.schedule(0, 2, arrival(1));
+    h.run_for(100);
+
+    // Cancelling after the fact leaves a tombstone that can never pop
+    h.sim.cancel(fired);
+    for i in 0..10 {
+        let handle = h.sim.schedule(1_000_000 + i, 2, arrival(10 + i as u128));
+        h.sim.cancel(handle);
+    }
+    assert!(h.sim.cancelled.len() <= h.sim.events.len());
+    assert!(!h.sim.cancelled.contains(&fired.0));
+}
+
+/// Arms a timer on every arrival, cancelling the previous one (a debounce).
+#[derive(Default)]
+struct Debounce {
+    pending: Option<EventHandle>,
+    fired: u32,
+}
+
+impl Component for Debounce {
+    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Commands {
+        match event.event_type {
+            EventType::Arrival { .. } => {
+                if let Some(handle) = self.pending.take() {
+                    inspector.cancel(handle);
+                }
+                smallvec![ScheduleCmd {
+                    delay: 10_000,
+                    node_id: event.node_id,
+                    event_type: EventType::GenerateNext { generation_id: 0 },
+                }]
+            }
+            EventType::GenerateNext { .. } => {
+                self.pending = None;
+                self.fired += 1;
+                Commands::new()
+            }
+            _ => Commands::new(),
+        }
+    }
+    fn on_scheduled(&mut self, handles: &[Option<EventHandle>]) {
+        self.pending = handles[0];
+    }
+    fn name(&self) -> &str {
+        "Debounce"
+    }
+    fn kind(&self) -> &str {
+        "Debounce"
+    }
+    fn encode_config(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+    fn apply_config(&mut self, _config: serde_json::Value, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        vec![]
+    }
+    fn active_requests(&self) -> u32 {
+        0
+    }
+    fn display_throughput(&self) -> f32 {
+        0.0
+    }
+    fn error_count(&self) -> u64 {
+        0
+    }
+    fn set_healthy(&mut self, _healthy: bool) {}
+    fn is_healthy(&self) -> bool {
+        true
+    }
+    fn add_target(&mut self, _target: NodeId) {}
+    fn remove_target(&mut self, _target: NodeId) {}
+    fn get_targets(&self) -> Vec<NodeId> {
+        vec![]
+    }
+    fn clear_targets(&mut self) {}
+    fn get_visual_snapshot(&self) -> VisualState {
+        VisualState::None
+    }
+    fn sync_display_stats(&mut self, _current_time_us: u64) {}
+    fn reset_internal_stats(&mut self) {}
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+#[test]
+fn test_component_cancels_its_own_timer() {
+    let mut h = TestHarness::new();
+    h.add(5, Box::new(Debounce::default()));
+
+    // Three arrivals 1ms apart: only the last timer survives
+    for (i, t) in [0, 1_000, 2_000].into_iter().enumerate() {
+        h.sim.schedule(t, 5, arrival(i as u128));
+    }
+    h.run_for(50);
+
+    let debounce = h.sim.get_component_as::<Debounce>(5).unwrap();
+    assert_eq!(debounce.fired, 1);
+    assert!(debounce.pending.is_none());
+}
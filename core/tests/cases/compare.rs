@@ -0,0 +1,130 @@
+use crate::common::TestHarness;
+use serde_json::json;
+use slay_core::{linear_rates, Experiment, Scenario, ScenarioAction, Topology};
+
+fn reference() -> Topology {
+    Topology::new()
+        .client("client", 50.0)
+        .lb("lb")
+        .servers("s", 2, |s| {
+            s.service_time = 10;
+            s.concurrency = 4;
+            s.backlog_limit = 50;
+        })
+        .connect("client", "lb")
+        .connect("lb", "s")
+}
+
+#[test]
+fn test_identical_variants_have_zero_delta() {
+    let topology = reference();
+    let cmp = Experiment::new(42, 5_000_000).compare(&topology, &topology);
+
+    assert!(cmp.baseline.success_count > 0);
+    assert_eq!(cmp.baseline, cmp.variant);
+    assert_eq!(cmp.delta_sla(), 0.0);
+    assert_eq!(cmp.delta_p99_us(), Some(0));
+    assert_eq!(cmp.delta_throughput(), 0.0);
+}
+
+#[test]
+fn test_failing_variant_lowers_sla() {
+    let baseline = reference();
+    let flaky = json!({ "failure_probability": 0.5 });
+    let variant = baseline
+        .with_node_config(3, &flaky)
+        .with_node_config(4, &flaky);
+    assert_eq!(variant.nodes[2].config["failure_probability"], json!(0.5));
+    assert_eq!(baseline.nodes[2].config["failure_probability"], json!(0.0));
+
+    let cmp = Experiment::new(42, 5_000_000)
+        .with_warmup(1_000_000)
+        .compare(&baseline, &variant);
+    assert!(cmp.delta_sla() < -5.0, "{}", cmp.summary());
+    assert!(cmp.delta_throughput() < 0.0);
+    assert!(cmp.summary().starts_with("ΔSLA -"));
+}
+
+#[test]
+fn test_throughput_uses_measured_time_only() {
+    let topology = reference();
+    let metrics = Experiment::new(7, 10_000_000)
+        .with_warmup(2_000_000)
+        .run(&topology);
+    // 50 rps offered; warm-up requests are not counted
+    assert!(
+        (metrics.throughput_rps - 50.0).abs() < 10.0,
+        "{}",
+        metrics.throughput_rps
+    );
+}
+
+#[test]
+fn test_load_sweep_finds_knee() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    // 4 workers slow down under load and saturate well below 350 rps
+    h.add_server(2, "S1", 10, 4, 50);
+    h.connect(1, 2);
+    let topology = h.sim.to_topology();
+    assert_eq!(
+        topology.with_offered_load(120.0).nodes[0].config["arrival_rate"],
+        json!(120.0)
+    );
+
+    let rates = linear_rates(50.0, 800.0, 6);
+    assert_eq!(rates, vec![50.0, 200.0, 350.0, 500.0, 650.0, 800.0]);
+    let curve = Experiment::new(7, 10_000_000)
+        .with_warmup(1_000_000)
+        .sweep_load(&topology, rates);
+    assert_eq!(curve.points.len(), 6);
+    let light = &curve.points[0].metrics;
+    assert!((light.throughput_rps - 50.0).abs() < 5.0);
+    // Throughput flattens at capacity while latency climbs
+    let heavy = &curve.points[5].metrics;
+    assert!(heavy.throughput_rps < 350.0);
+    assert!(heavy.p99_us.unwrap() > 2 * light.p99_us.unwrap());
+    assert_eq!(curve.knee_point().unwrap().offered_rps, 200.0);
+
+    let csv = curve.to_csv();
+    assert_eq!(csv.lines().count(), 7);
+    assert!(csv.lines().nth(2).unwrap().ends_with(",true"));
+}
+
+#[test]
+fn test_chaos_report_scores_an_outage() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    let topology = h.sim.to_topology();
+    let scenario = Scenario::new("Outage")
+        .at(3_000_000, ScenarioAction::Kill { node_id: 2 })
+        .at(5_000_000, ScenarioAction::Revive { node_id: 2 });
+
+    let report = Experiment::new(42, 10_000_000)
+        .with_scenario(scenario)
+        .chaos_report(&topology, 99.0);
+
+    assert_eq!(report.faults.len(), 1);
+    let outage = &report.faults[0];
+    assert_eq!(outage.description, "Kill #2");
+    assert_eq!(outage.cleared_at_us, Some(5_000_000));
+    assert_eq!(outage.before.failures, 0);
+    assert!(outage.during.sla_percent < 50.0);
+    assert!(outage.after.sla_percent > 99.0);
+    let detection = outage.detection_us.expect("outage breaches the SLO");
+    assert!(detection <= 1_000_000, "detected after {}us", detection);
+    assert!(outage.recovery_us.is_some_and(|us| us <= 1_000_000));
+    assert!(outage.budget_consumed_percent > 100.0);
+    assert_eq!(outage.failures, report.overall.failures);
+
+    let markdown = report.to_markdown();
+    assert!(
+        markdown.contains("| Kill #2 | 3.0s | 5.0s |"),
+        "{}",
+        markdown
+    );
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["faults"][0]["injected_at_us"], 3_000_000);
+}
@@ -0,0 +1,104 @@
+use crate::common::TestHarness;
+use slay_core::Topology;
+
+const SHOP: &str = r#"
+version: "3.9"
+services:
+  proxy:
+    image: nginx:1.27
+    ports:
+      - "80:80"
+    depends_on: [web]
+  web:
+    image: shop/web:2.1
+    deploy:
+      replicas: 3
+    depends_on:
+      cache:
+        condition: service_started
+      db:
+        condition: service_healthy
+  cache:
+    image: redis:7
+  db:
+    image: postgres:16
+    environment:
+      POSTGRES_PASSWORD: example
+  worker:
+    image: shop/worker
+    scale: 2
+    links:
+      - "db:database"
+volumes:
+  data: {}
+"#;
+
+#[test]
+fn test_compose_services_and_dependencies() {
+    let topology = Topology::from_compose(SHOP).unwrap();
+    let names: Vec<(&str, &str)> = topology
+        .nodes
+        .iter()
+        .map(|n| (n.name.as_str(), n.kind.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("users-proxy", "Client"),
+            ("users-worker", "Client"),
+            ("proxy", "Server"),
+            ("web", "LoadBalancer"),
+            ("web-1", "Server"),
+            ("web-2", "Server"),
+            ("web-3", "Server"),
+            ("cache", "Server"),
+            ("db", "Server"),
+            ("worker", "LoadBalancer"),
+            ("worker-1", "Server"),
+            ("worker-2", "Server"),
+        ]
+    );
+    let targets = |name: &str| {
+        topology.nodes[topology.id(name).unwrap() as usize - 1]
+            .targets
+            .clone()
+    };
+    assert_eq!(targets("users-proxy"), vec![3], "the published service");
+    assert_eq!(
+        targets("users-worker"),
+        vec![10],
+        "nothing else calls the worker"
+    );
+    assert_eq!(targets("proxy"), vec![4]);
+    assert_eq!(targets("web"), vec![5, 6, 7]);
+    assert_eq!(targets("web-2"), vec![8], "the first dependency");
+    assert_eq!(targets("worker-1"), vec![9], "links count as dependencies");
+    assert_eq!(topology.nodes[5].services, vec!["web".to_string()]);
+
+    let mut h = TestHarness::from_topology(&topology, 2);
+    assert_eq!(h.sim.validate(), vec![]);
+    h.run_for(5000);
+    assert!(h.sim.success_count > 30, "{} served", h.sim.success_count);
+}
+
+#[test]
+fn test_compose_without_published_ports_sends_traffic_to_roots() {
+    let topology =
+        Topology::from_compose("services:\n  api:\n    depends_on: [db]\n  db:\n  batch:\n")
+            .unwrap();
+    assert_eq!(topology.nodes[0].name, "users-api");
+    assert_eq!(topology.nodes[0].targets, vec![3]);
+    assert_eq!(topology.nodes[1].targets, vec![5]);
+    assert_eq!(topology.nodes[2].targets, vec![4]);
+}
+
+#[test]
+fn test_compose_rejects_unknown_dependencies() {
+    let err = Topology::from_compose("services:\n  api:\n    depends_on: [dbx]\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "service 'api' depends on unknown service 'dbx'"
+    );
+    let err = Topology::from_compose("volumes: {}\n").unwrap_err();
+    assert_eq!(err.to_string(), "no services found");
+}
@@ -0,0 +1,95 @@
+use crate::common::TestHarness;
+use slay_core::{CostRate, Experiment, Simulation, Topology};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9 * b.abs().max(1.0)
+}
+
+fn instance(per_hour: f64) -> CostRate {
+    CostRate {
+        per_hour,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_hourly_rate_accrues_with_virtual_time() {
+    let mut h = TestHarness::new();
+    h.add_server(1, "S", 10, 4, 10);
+    h.sim.set_cost_rate(1, instance(3.6));
+
+    h.run_for(1_000);
+    assert!(close(h.sim.node_cost(1), 0.001));
+    assert!(close(h.sim.total_cost(), 0.001));
+}
+
+#[test]
+fn test_request_rate_and_cost_per_success() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S", 5, 8, 50);
+    h.connect(1, 2);
+    h.sim.set_cost_rate(
+        2,
+        CostRate {
+            per_million_requests: 1_000_000.0,
+            ..Default::default()
+        },
+    );
+    h.start();
+    h.run_for(2_000);
+
+    // A dollar per request served, so each success costs at least a dollar
+    let cost = h.sim.node_cost(2);
+    assert!(cost >= h.sim.success_count as f64);
+    assert!(h.sim.success_count > 0);
+    let per_success = h.sim.cost_per_success().unwrap();
+    assert!(close(per_success, cost / h.sim.success_count as f64));
+    assert_eq!(h.sim.node_cost(1), 0.0);
+}
+
+#[test]
+fn test_removed_and_repriced_nodes_keep_their_cost() {
+    let mut h = TestHarness::new();
+    h.add_server(1, "A", 10, 4, 10);
+    h.add_server(2, "B", 10, 4, 10);
+    h.sim.set_cost_rate(1, instance(3.6));
+    h.sim.set_cost_rate(2, instance(3.6));
+
+    h.run_for(1_000);
+    h.sim.remove_node(2);
+    h.sim.set_cost_rate(1, instance(7.2));
+    h.run_for(1_000);
+
+    // A: 0.001 + 0.002, B: 0.001 before removal
+    assert!(close(h.sim.node_cost(1), 0.002));
+    assert!(close(h.sim.total_cost(), 0.004));
+
+    h.sim.reset_stats();
+    assert_eq!(h.sim.total_cost(), 0.0);
+    h.run_for(500);
+    assert!(close(h.sim.total_cost(), 0.001));
+}
+
+#[test]
+fn test_cost_rates_survive_topology_round_trip() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_server(2, "S", 10, 4, 10);
+    h.connect(1, 2);
+    h.sim.set_cost_rate(2, instance(0.5));
+
+    let json = h.sim.to_topology().to_json();
+    let topology = Topology::from_json(&json).unwrap();
+    assert!(topology.nodes[0].cost.is_zero());
+    assert_eq!(topology.nodes[1].cost, instance(0.5));
+
+    let sim = Simulation::from_topology(&topology, 1);
+    assert_eq!(sim.costs.rate(2), instance(0.5));
+
+    // Only the measured period is charged
+    let metrics = Experiment::new(1, 3_600_000_000 / 100)
+        .with_warmup(1_000_000)
+        .run(&topology);
+    assert!(close(metrics.cost_dollars, 0.5 / 100.0));
+}
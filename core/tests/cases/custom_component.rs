@@ -0,0 +1,170 @@
+use crate::common::TestHarness;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use slay_core::traits::{CustomStats, VisualState};
+use slay_core::*;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EchoConfig {
+    succeed: bool,
+}
+
+/// Answers every request immediately.
+#[derive(Default)]
+struct Echo {
+    config: EchoConfig,
+    handled: u64,
+}
+
+impl Component for Echo {
+    fn on_event(&mut self, event: Event, _inspector: &dyn SystemInspector) -> Commands {
+        match event.event_type {
+            EventType::Arrival {
+                request_id,
+                path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                self.handled += 1;
+                let prev = *path.last().unwrap();
+                smallvec![ScheduleCmd {
+                    delay: 0,
+                    node_id: prev,
+                    event_type: EventType::Response {
+                        request_id,
+                        path,
+                        start_time,
+                        success: self.config.succeed,
+                        failure: (!self.config.succeed).then_some(FailureReason::BackendError),
+                        timeout,
+                        max_hops,
+                        metadata,
+                    },
+                }]
+            }
+            _ => Commands::new(),
+        }
+    }
+    fn name(&self) -> &str {
+        "Echo"
+    }
+    fn kind(&self) -> &str {
+        "Echo"
+    }
+    fn encode_config(&self) -> Value {
+        serde_json::to_value(&self.config).unwrap()
+    }
+    fn apply_config(&mut self, config: Value, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        if let Ok(cfg) = serde_json::from_value(config) {
+            self.config = cfg;
+        }
+        vec![]
+    }
+    fn active_requests(&self) -> u32 {
+        0
+    }
+    fn display_throughput(&self) -> f32 {
+        0.0
+    }
+    fn error_count(&self) -> u64 {
+        0
+    }
+    fn set_healthy(&mut self, _healthy: bool) {}
+    fn is_healthy(&self) -> bool {
+        true
+    }
+    fn add_target(&mut self, _target: NodeId) {}
+    fn remove_target(&mut self, _target: NodeId) {}
+    fn get_targets(&self) -> Vec<NodeId> {
+        vec![]
+    }
+    fn clear_targets(&mut self) {}
+    fn get_visual_snapshot(&self) -> VisualState {
+        VisualState::Custom(CustomStats::default().with("handled", self.handled))
+    }
+    fn sync_display_stats(&mut self, _current_time_us: u64) {}
+    fn reset_internal_stats(&mut self) {
+        self.handled = 0;
+    }
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+fn echo_factory(data: Value) -> Option<Box<dyn Component>> {
+    let mut echo = Echo::default();
+    if !data.is_null() {
+        echo.config = serde_json::from_value(data).ok()?;
+    }
+    Some(Box::new(echo))
+}
+
+fn register_echo() {
+    register_component(ComponentRegistration {
+        kind: "Echo",
+        label: "Echo",
+        description: "Answers instantly",
+        factory: echo_factory,
+    });
+}
+
+#[test]
+fn test_registered_component_is_creatable() {
+    register_echo();
+    assert!(component_kinds().contains(&"Echo"));
+    assert!(registered_components().iter().any(|r| r.kind == "Echo"));
+
+    let comp = create_component("Echo", serde_json::json!({ "succeed": true })).unwrap();
+    assert_eq!(comp.kind(), "Echo");
+    assert_eq!(comp.encode_config()["succeed"], true);
+}
+
+#[test]
+fn test_builtin_kinds_cannot_be_overridden() {
+    assert!(!register_component(ComponentRegistration {
+        kind: "Server",
+        label: "Fake",
+        description: "",
+        factory: echo_factory,
+    }));
+    let comp = create_component("Server", Value::Null).unwrap();
+    assert_eq!(comp.kind(), "Server");
+}
+
+#[test]
+fn test_custom_component_serves_traffic_from_topology() {
+    register_echo();
+    let mut h = TestHarness::new();
+    h.add_client(1, 20.0);
+    h.add(
+        2,
+        create_component("Echo", serde_json::json!({ "succeed": true })).unwrap(),
+    );
+    h.connect(1, 2);
+
+    let topology = h.sim.to_topology();
+    assert!(topology.nodes.iter().any(|n| n.kind == "Echo"));
+
+    let mut h = TestHarness::from_topology(&topology, 7);
+    h.run_for(1000);
+    assert!(h.sim.success_count > 10);
+    assert_eq!(h.sim.failure_count, 0);
+}
+
+#[test]
+fn test_get_component_as_downcasts() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_server(2, "S1", 10, 4, 10);
+    h.add(3, Box::new(Echo::default()));
+
+    assert_eq!(h.sim.get_component_as::<Server>(2).unwrap().name, "S1");
+    assert!(h.sim.get_component_as::<Client>(2).is_none());
+    assert!(h.sim.get_component_as::<Server>(99).is_none());
+
+    h.sim.get_component_as_mut::<Echo>(3).unwrap().handled = 5;
+    assert_eq!(
+        h.sim.components[&3].downcast_ref::<Echo>().unwrap().handled,
+        5
+    );
+}
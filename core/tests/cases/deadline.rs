@@ -0,0 +1,66 @@
+use crate::common::TestHarness;
+use slay_core::{FailureReason, LoadBalancer};
+use std::sync::Arc;
+
+fn overloaded_run(deadline_aware: bool) -> TestHarness {
+    let mut h = TestHarness::new();
+    let client = h.add_client(1, 15.0);
+    client.config.write().unwrap().timeout = 300;
+    let server = h.add_server(2, "S1", 100, 1, 100);
+    server._config.write().unwrap().deadline_aware = deadline_aware;
+    h.connect(1, 2);
+    h.start();
+    h.run_for(10_000);
+    h
+}
+
+#[test]
+fn test_deadline_aware_server_skips_doomed_work() {
+    let naive = overloaded_run(false);
+    let aware = overloaded_run(true);
+
+    assert_eq!(naive.sim.deadline_exceeded_count(), 0);
+    assert!(aware.sim.deadline_exceeded_count() > 0);
+    assert!(
+        aware.sim.success_count > naive.sim.success_count * 2,
+        "Shedding expired work must leave capacity for live requests ({} vs {})",
+        aware.sim.success_count,
+        naive.sim.success_count
+    );
+    // Deadline rejections are tracked separately from server errors
+    assert_eq!(aware.sim.components[&2].error_count(), 0);
+}
+
+#[test]
+fn test_deadline_aware_lb_skips_late_retries() {
+    let mut h = TestHarness::new();
+    let client = h.add_client(1, 50.0);
+    client.config.write().unwrap().timeout = 100;
+    let lb = LoadBalancer::new("LB");
+    let lb_config = Arc::clone(&lb.config);
+    h.add(2, Box::new(lb));
+    let s1 = h.add_server(3, "Flaky", 10, 10, 10);
+    s1._config.write().unwrap().failure_probability = 1.0;
+    h.add_server(4, "Healthy", 10, 10, 10);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    {
+        let mut cfg = lb_config.write().unwrap();
+        cfg.retry_backoff_ms = 500;
+        cfg.deadline_aware = true;
+    }
+    h.start();
+    h.run_for(2000);
+
+    let lb_stats = &h.sim.components[&2];
+    assert!(lb_stats.deadline_exceeded_count() > 0);
+    // The client sees those requests fail for their deadline
+    let reported = h.sim.failure_reasons[&FailureReason::DeadlineExceeded];
+    assert!(
+        reported.abs_diff(lb_stats.deadline_exceeded_count()) <= 2,
+        "{} vs {}",
+        reported,
+        lb_stats.deadline_exceeded_count()
+    );
+}
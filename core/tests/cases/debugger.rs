@@ -0,0 +1,76 @@
+use crate::common::TestHarness;
+use slay_core::{Breakpoint, EventType, StepResult};
+
+fn run_until_break(h: &mut TestHarness, max_steps: usize) -> Option<usize> {
+    for _ in 0..max_steps {
+        match h.sim.step_checked() {
+            StepResult::Breakpoint(idx) => return Some(idx),
+            StepResult::Idle => return None,
+            StepResult::Stepped => {}
+        }
+    }
+    None
+}
+
+#[test]
+fn test_breakpoint_on_arrival_pauses_before_processing() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_server(2, "S1", 10, 1, 10);
+    h.connect(1, 2);
+    h.sim
+        .debugger
+        .breakpoints
+        .push(Breakpoint::ArrivalAt { node_id: 2 });
+    h.start();
+
+    assert_eq!(run_until_break(&mut h, 100), Some(0));
+    let next = h.sim.peek_event().unwrap();
+    assert_eq!(next.node_id, 2);
+    assert!(matches!(next.event_type, EventType::Arrival { .. }));
+    assert_eq!(h.sim.components[&2].active_requests(), 0);
+
+    // Resuming processes the paused event instead of re-hitting it
+    assert_eq!(h.sim.step_checked(), StepResult::Stepped);
+    assert_eq!(h.sim.components[&2].active_requests(), 1);
+    assert_eq!(h.sim.debugger.last_event.as_ref().unwrap().node_id, 2);
+}
+
+#[test]
+fn test_breakpoint_on_request_failure() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 100, 1, 0);
+    h.connect(1, 2);
+    h.sim
+        .debugger
+        .breakpoints
+        .push(Breakpoint::RequestFailed { request_id: None });
+    h.start();
+
+    assert_eq!(run_until_break(&mut h, 10_000), Some(0));
+    let failures_before = h.sim.failure_count;
+    h.sim.step_checked();
+    assert_eq!(h.sim.failure_count, failures_before + 1);
+}
+
+#[test]
+fn test_step_checked_without_breakpoints_matches_step() {
+    let mut h1 = TestHarness::new();
+    h1.add_client(1, 100.0);
+    h1.add_server(2, "S1", 10, 2, 10);
+    h1.connect(1, 2);
+    h1.start();
+    let mut h2 = TestHarness::new();
+    h2.add_client(1, 100.0);
+    h2.add_server(2, "S1", 10, 2, 10);
+    h2.connect(1, 2);
+    h2.start();
+
+    for _ in 0..500 {
+        h1.sim.step();
+        assert_eq!(h2.sim.step_checked(), StepResult::Stepped);
+    }
+    assert_eq!(h1.sim.success_count, h2.sim.success_count);
+    assert_eq!(h1.sim.time, h2.sim.time);
+}
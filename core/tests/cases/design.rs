@@ -0,0 +1,181 @@
+use crate::common::TestHarness;
+use slay_core::{
+    Design, EdgeConfig, Health, Link, ScenarioAction, Simulation, Topology, TEMPLATES,
+};
+
+const OUTAGE: &str = r#"
+components:
+  users:
+    kind: client
+    config: { arrival_rate: 50 }
+    calls: [edge]
+  edge:
+    kind: load_balancer
+    calls: [api-1, api-2]
+  api-1: { kind: server, services: [api] }
+  api-2: { kind: Server, services: [api], config: { concurrency: 2 } }
+links:
+  - { from: edge, to: api-2, latency_ms: 25, loss: 0.01 }
+  - { from: api-1, to: edge, one_way: true, jitter_ms: 1.5 }
+traffic:
+  users:
+    - { at: 30s, rps: 200 }
+scenario:
+  name: Zone outage
+  steps:
+    - { at: 10s, kill: api-1 }
+    - { at: 20s, partition: [edge, api-2] }
+    - { at: 40s, slow: api-2, slowdown: 3, for: 5s }
+    - { at: 50s, set_health: api-1, health: degraded, error_rate: 0.5 }
+    - { at: 1m, revive: api-1, enabled: false }
+"#;
+
+#[test]
+fn test_design_from_yaml() {
+    let design = Design::from_yaml(OUTAGE).unwrap();
+    let topology = &design.topology;
+    let nodes: Vec<(u32, &str, &str, Vec<u32>)> = topology
+        .nodes
+        .iter()
+        .map(|n| (n.id, n.name.as_str(), n.kind.as_str(), n.targets.clone()))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            (1, "users", "Client", vec![2]),
+            (2, "edge", "LoadBalancer", vec![3, 4]),
+            (3, "api-1", "Server", vec![]),
+            (4, "api-2", "Server", vec![]),
+        ]
+    );
+    assert_eq!(topology.nodes[0].config["arrival_rate"], 50.0);
+    assert_eq!(topology.nodes[3].config["concurrency"], 2);
+    assert_eq!(
+        topology.nodes[2].config["concurrency"], 4,
+        "left-out fields keep the defaults"
+    );
+    assert_eq!(topology.nodes[3].services, vec!["api".to_string()]);
+
+    let link = |a, b| {
+        &topology
+            .links
+            .iter()
+            .find(|l| (l.a, l.b) == (a, b))
+            .unwrap()
+            .link
+    };
+    assert_eq!(link(2, 4).min_to_max.latency_us, 25_000);
+    assert_eq!(link(2, 4).max_to_min.packet_loss_rate, 0.01);
+    assert_eq!(link(2, 3).max_to_min.jitter_us, 1500);
+    assert_eq!(link(2, 3).min_to_max, EdgeConfig::default(), "one way only");
+
+    let scenario = &design.scenario;
+    assert_eq!(scenario.name, "Zone outage");
+    let steps: Vec<(u64, &ScenarioAction, bool)> = scenario
+        .steps
+        .iter()
+        .map(|s| (s.at_us / 1_000_000, &s.action, s.enabled))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            (10, &ScenarioAction::Kill { node_id: 3 }, true),
+            (20, &ScenarioAction::Partition { a: 2, b: 4 }, true),
+            (
+                30,
+                &ScenarioAction::SetConfig {
+                    node_id: 1,
+                    config: serde_json::json!({ "arrival_rate": 200.0 }),
+                },
+                true
+            ),
+            (
+                40,
+                &ScenarioAction::InjectLatency {
+                    node_id: 4,
+                    slowdown: 3.0,
+                    extra_latency_us: 0,
+                    error_rate: 0.0,
+                    duration_us: Some(5_000_000),
+                },
+                true
+            ),
+            (
+                50,
+                &ScenarioAction::SetHealth {
+                    node_id: 3,
+                    health: Health::Degraded {
+                        slowdown: 1.0,
+                        error_rate: 0.5,
+                    },
+                },
+                true
+            ),
+            (60, &ScenarioAction::Revive { node_id: 3 }, false),
+        ]
+    );
+
+    let mut h = TestHarness::from_topology(topology, 4);
+    h.sim.load_scenario(design.scenario.clone());
+    h.run_for(35_000);
+    assert!(h.sim.success_count > 100, "{} served", h.sim.success_count);
+}
+
+#[test]
+fn test_design_round_trips_through_json() {
+    for template in TEMPLATES {
+        // Normalise the configs the way the UI saves them
+        let topology = Simulation::from_topology(&template.topology(), 1).to_topology();
+        let back = Topology::from_yaml(&topology.to_yaml()).unwrap();
+        assert_eq!(back.to_json(), topology.to_json(), "{}", template.name);
+    }
+
+    // Gaps in the ids, unnamed and same-named nodes, lopsided links
+    let mut topology = Topology::new()
+        .client("users", 5.0)
+        .server("db", |c| c.service_time = 40);
+    topology.nodes[1].id = 6;
+    let mut topology =
+        topology
+            .server("db", |_| {})
+            .lb("")
+            .connect_with("users", "db", Link::default());
+    topology.links[0].link.max_to_min.latency_us = 3_456;
+    let yaml = topology.to_yaml();
+    assert!(yaml.contains("server-7:"), "{}", yaml);
+    assert!(yaml.contains("load_balancer-8:"), "{}", yaml);
+    let back = Topology::from_yaml(&yaml).unwrap();
+    assert_eq!(back.to_json(), topology.to_json());
+
+    let design = Design::from_yaml(OUTAGE).unwrap();
+    let again = Design::from_yaml(&design.to_yaml()).unwrap();
+    assert_eq!(again.topology.to_json(), design.topology.to_json());
+    assert_eq!(again.scenario, design.scenario);
+    assert!(design.to_yaml().contains("traffic:"));
+}
+
+#[test]
+fn test_design_errors() {
+    let message = |yaml: &str| Design::from_yaml(yaml).unwrap_err().to_string();
+    assert_eq!(
+        message("components:\n  a: { calls: [b] }\n"),
+        "no component named 'b'"
+    );
+    assert_eq!(
+        message("components:\n  a: { kind: database }\n"),
+        "'a': unknown kind 'database'"
+    );
+    assert_eq!(
+        message("components:\n  a: { config: { concurency: 2 } }\n"),
+        "'a': Server has no config field 'concurency'"
+    );
+    assert_eq!(
+        message("components:\n  a:\nscenario:\n  steps:\n    - { at: 5s, kill: a, revive: a }\n"),
+        "step at 5s has more than one action"
+    );
+    assert!(
+        message("components:\n  a:\nscenario:\n  steps:\n    - { at: soon, kill: a }\n")
+            .contains("invalid time"),
+    );
+    assert!(message("components:\n  a: { knd: server }\n").contains("unknown field `knd`"));
+}
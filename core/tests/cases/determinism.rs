@@ -1,4 +1,5 @@
 use crate::common::TestHarness;
+use slay_core::{EventType, Simulation};
 
 #[test]
 fn test_determinism_across_runs() {
@@ -70,3 +71,51 @@ fn test_determinism_with_different_seeds() {
         "Different seeds should produce different results"
     );
 }
+
+#[test]
+fn test_same_time_events_pop_in_schedule_order() {
+    let mut sim = Simulation::new(1);
+    for generation_id in 0..50 {
+        sim.schedule(1_000, 1, EventType::GenerateNext { generation_id });
+    }
+
+    let mut popped = Vec::new();
+    while let Some(event) = sim.events.pop() {
+        if let EventType::GenerateNext { generation_id } = event.event_type {
+            popped.push(generation_id);
+        }
+    }
+
+    let expected: Vec<u64> = (0..50).collect();
+    assert_eq!(popped, expected, "Ties must resolve in FIFO schedule order");
+}
+
+#[test]
+fn test_unrelated_node_does_not_shift_rng_streams() {
+    let run = |extra_first: bool| {
+        let mut h = TestHarness::new_with_seed(99);
+        if extra_first {
+            // Isolated node added before the rest; it receives no traffic
+            h.add_server(7, "Idle", 10, 1, 10);
+        }
+        h.add_client(1, 100.0);
+        h.add_server(2, "Server", 10, 4, 100);
+        h.connect(1, 2);
+        h.start();
+        h.run_for(500);
+        h.sim.latencies.iter().copied().collect::<Vec<_>>()
+    };
+
+    let plain = run(false);
+    assert!(!plain.is_empty());
+    assert_eq!(plain, run(true));
+}
+
+#[test]
+fn test_stream_seed_depends_on_identity() {
+    use slay_core::stream_seed;
+    assert_eq!(stream_seed(1, 2, "Server"), stream_seed(1, 2, "Server"));
+    assert_ne!(stream_seed(1, 2, "Server"), stream_seed(1, 3, "Server"));
+    assert_ne!(stream_seed(1, 2, "Server"), stream_seed(1, 2, "Client"));
+    assert_ne!(stream_seed(1, 2, "Server"), stream_seed(2, 2, "Server"));
+}
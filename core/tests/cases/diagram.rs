@@ -0,0 +1,72 @@
+use slay_core::components::load_balancer::BalancingStrategy;
+use slay_core::{EdgeConfig, Link, Topology};
+
+fn shop() -> Topology {
+    let wan = EdgeConfig {
+        latency_us: 2_000,
+        jitter_us: 500,
+        packet_loss_rate: 0.01,
+        bandwidth_mbps: 0.0,
+    };
+    Topology::new()
+        .client_with("web \"edge\"", |c| {
+            c.arrival_rate = 120.0;
+            c.timeout = 3000;
+        })
+        .lb_with("lb", |c| c.strategy = BalancingStrategy::LeastConnections)
+        .servers("api", 2, |s| {
+            s.service_time = 20;
+            s.concurrency = 8;
+        })
+        .connect_with(
+            "web \"edge\"",
+            "lb",
+            Link {
+                min_to_max: wan,
+                max_to_min: wan,
+            },
+        )
+        .connect("lb", "api")
+        .service("api", "api")
+}
+
+#[test]
+fn test_dot_export_reads_back() {
+    let topology = shop();
+    let dot = topology.to_dot();
+    assert!(dot.contains(r#"label="api-1\n20ms × 8""#), "{}", dot);
+    assert!(dot.contains(r#"label="2ms ±0.5ms · 1% loss""#), "{}", dot);
+    assert!(dot.contains("shape=hexagon"), "{}", dot);
+    assert!(!dot.contains("backlog_limit"), "defaults are left out");
+
+    let back = Topology::from_dot(&dot).unwrap();
+    assert_eq!(back.nodes, topology.nodes);
+    let links = |t: &Topology| {
+        t.links
+            .iter()
+            .map(|l| (l.a, l.b, l.link.min_to_max))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(links(&back), links(&topology));
+}
+
+#[test]
+fn test_mermaid_export_labels_and_boxes_services() {
+    let mermaid = shop().to_mermaid();
+    let lines: Vec<&str> = mermaid.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "flowchart LR",
+            r#"    n1(["web #quot;edge#quot;<br/>120 rps"])"#,
+            r#"    n2{{"lb<br/>LeastConnections"}}"#,
+            r#"    subgraph s1["api"]"#,
+            r#"        n3["api-1<br/>20ms × 8"]"#,
+            r#"        n4["api-2<br/>20ms × 8"]"#,
+            "    end",
+            r#"    n1 -->|"2ms ±0.5ms · 1% loss"| n2"#,
+            "    n2 --> n3",
+            "    n2 --> n4",
+        ]
+    );
+}
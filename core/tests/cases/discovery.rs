@@ -0,0 +1,80 @@
+use crate::common::TestHarness;
+use slay_core::{LoadBalancer, Server, ServiceRegistry, Simulation, Topology};
+
+fn served(h: &TestHarness, id: u32) -> bool {
+    h.sim
+        .get_component_as::<Server>(id)
+        .unwrap()
+        .latency_ewma_us
+        .is_some()
+}
+
+fn service_routed() -> TestHarness {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    let lb = LoadBalancer::new("LB");
+    lb.config.write().unwrap().service = Some("api".into());
+    h.add(2, Box::new(lb));
+    h.add_server(3, "Wired", 10, 4, 50);
+    h.add_server(4, "Api1", 10, 4, 50);
+    h.add_server(5, "Api2", 10, 4, 50);
+    h.connect(1, 2);
+    // Wired but not a member: must not receive traffic
+    h.connect(2, 3);
+    h.sim.register_service("api", 4);
+    h
+}
+
+#[test]
+fn test_registry_membership() {
+    let mut reg = ServiceRegistry::default();
+    assert!(reg.register("api", 7));
+    assert!(reg.register("api", 3));
+    assert!(!reg.register("api", 3));
+    assert!(reg.register("db", 3));
+    assert_eq!(reg.members("api"), vec![3, 7]);
+    assert_eq!(reg.services_of(3), vec!["api", "db"]);
+
+    reg.remove_node(3);
+    assert_eq!(reg.members("api"), vec![7]);
+    assert_eq!(reg.names().collect::<Vec<_>>(), vec!["api"]);
+    assert!(reg.deregister("api", 7));
+    assert!(reg.members("api").is_empty());
+    assert!(!reg.deregister("api", 7));
+}
+
+#[test]
+fn test_lb_routes_to_service_members() {
+    let mut h = service_routed();
+    h.start();
+    h.run_for(1000);
+    assert!(served(&h, 4));
+    assert!(!served(&h, 3));
+    assert!(!served(&h, 5));
+
+    // New members join without rewiring the balancer
+    assert!(h.sim.register_service("api", 5));
+    h.run_for(1000);
+    assert!(served(&h, 5));
+    assert_eq!(h.sim.failure_count, 0);
+
+    h.sim.remove_node(4);
+    assert_eq!(h.sim.services.members("api"), vec![5]);
+    assert!(
+        !h.sim.register_service("api", 99),
+        "unknown nodes are rejected"
+    );
+}
+
+#[test]
+fn test_services_round_trip_through_topology() {
+    let h = service_routed();
+    let topology = Topology::from_json(&h.sim.to_topology().to_json()).unwrap();
+    assert_eq!(topology.nodes[3].services, vec!["api"]);
+    assert!(topology.nodes[2].services.is_empty());
+
+    let sim = Simulation::from_topology(&topology, 1);
+    assert_eq!(sim.services.members("api"), vec![4]);
+    // Service members count as reachable from the balancer
+    assert!(sim.validate().iter().all(|d| !d.nodes().contains(&4)));
+}
@@ -0,0 +1,101 @@
+use crate::common::TestHarness;
+use slay_core::Topology;
+
+const SHOP: &str = r#"
+// Checkout path of the shop, as drawn in the architecture docs
+digraph shop {
+    rankdir=LR;
+    node [shape=box, style=rounded];
+
+    web [kind=client, label="Web users", arrival_rate=80, timeout=3000];
+    lb  [kind=LoadBalancer, strategy=LeastConnections, color=blue];
+
+    subgraph cluster_api {
+        label="API";
+        node [kind=Server, service_time=20, concurrency=8, service=api];
+        api1; api2;
+    }
+    db [service_time=40, concurrency=32];
+
+    web -> lb;
+    lb -> {api1 api2} [latency_us=2000, label="http"];
+    api1 -> db; api2 -> db [packet_loss_rate=0.0];
+}
+"#;
+
+#[test]
+fn test_dot_maps_nodes_attributes_and_edges() {
+    let topology = Topology::from_dot(SHOP).unwrap();
+
+    let kinds: Vec<&str> = topology.nodes.iter().map(|n| n.kind.as_str()).collect();
+    assert_eq!(
+        kinds,
+        vec!["Client", "LoadBalancer", "Server", "Server", "Server"]
+    );
+    assert_eq!(topology.id("Web users"), Some(1), "label names the node");
+    assert_eq!(topology.id("db"), Some(5));
+    assert_eq!(topology.nodes[0].config["arrival_rate"], 80.0);
+    assert_eq!(topology.nodes[0].config["timeout"], 3000);
+    assert_eq!(topology.nodes[1].config["strategy"], "LeastConnections");
+    assert_eq!(topology.nodes[2].config["service_time"], 20);
+    assert_eq!(topology.nodes[3].services, vec!["api".to_string()]);
+    assert_eq!(topology.nodes[4].config["concurrency"], 32);
+    assert!(topology.nodes[4].services.is_empty(), "defaults are scoped");
+
+    assert_eq!(topology.nodes[0].targets, vec![2]);
+    assert_eq!(topology.nodes[1].targets, vec![3, 4]);
+    assert_eq!(topology.nodes[2].targets, vec![5]);
+    let links: Vec<(u32, u32, u64)> = topology
+        .links
+        .iter()
+        .map(|l| (l.a, l.b, l.link.min_to_max.latency_us))
+        .collect();
+    assert_eq!(links, vec![(2, 3, 2000), (2, 4, 2000), (4, 5, 10_000)]);
+
+    let mut h = TestHarness::from_topology(&topology, 3);
+    assert_eq!(h.sim.validate(), vec![]);
+    h.run_for(2000);
+    assert!(h.sim.success_count > 120, "{} served", h.sim.success_count);
+    assert_eq!(h.sim.failure_count, 0);
+}
+
+#[test]
+fn test_dot_undirected_chains_and_comments() {
+    let topology = Topology::from_dot(
+        "strict graph {\n\
+         # generated\n\
+         a [kind=\"Client\"] /* spans\n two lines */\n\
+         a -- b -- c\n\
+         }",
+    )
+    .unwrap();
+    assert_eq!(topology.nodes.len(), 3);
+    assert_eq!(topology.nodes[0].targets, vec![2]);
+    assert_eq!(topology.nodes[1].targets, vec![3]);
+    assert_eq!(topology.nodes[2].kind, "Server", "kind defaults to Server");
+}
+
+#[test]
+fn test_dot_errors_name_the_line() {
+    let err = Topology::from_dot("digraph {\n  a [kind=Toaster]\n}").unwrap_err();
+    assert_eq!(err.line, 2);
+    assert!(err.message.contains("unknown kind 'Toaster'"), "{}", err);
+
+    let err = Topology::from_dot("digraph {\n\n  a [service_time=fast]\n}").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert!(err.message.contains("service_time=fast"), "{}", err);
+
+    let err = Topology::from_dot("digraph {\n  lb [kind=lb, strategy=Fastest]\n}").unwrap_err();
+    assert!(err.message.contains("unknown kind 'lb'"), "{}", err);
+
+    let err =
+        Topology::from_dot("digraph {\n  lb [kind=LoadBalancer, strategy=Fastest]\n}").unwrap_err();
+    assert!(
+        err.message.contains("not a valid LoadBalancer config"),
+        "{}",
+        err
+    );
+
+    let err = Topology::from_dot("digraph {\n  a -> \n}").unwrap_err();
+    assert_eq!(err.to_string(), "line 3: expected a node name");
+}
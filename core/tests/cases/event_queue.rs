@@ -0,0 +1,105 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use slay_core::{Event, EventType, PendingEvents, Topology};
+
+fn event(time: u64, seq: u64) -> Event {
+    Event {
+        time,
+        seq,
+        node_id: (seq % 7) as u32,
+        event_type: EventType::GenerateNext { generation_id: seq },
+    }
+}
+
+fn order(event: &Event) -> (u64, u64) {
+    (event.time, event.seq)
+}
+
+#[test]
+fn test_calendar_pops_like_the_heap() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut heap = PendingEvents::heap();
+    let mut calendar = PendingEvents::calendar();
+    let (mut now, mut seq) = (0, 0);
+
+    // Grow to thousands of events, churn, then drain: the ring resizes
+    // both ways, and bursts, ties and far-future events all occur
+    for round in 0..30_000 {
+        let pushes = match round {
+            0..=9_999 => 2,
+            10_000..=19_999 => 1,
+            _ => 0,
+        };
+        for _ in 0..pushes {
+            let delay = match rng.gen_range(0..10) {
+                0 => 0,
+                1 => rng.gen_range(10_000_000..60_000_000),
+                _ => rng.gen_range(1..5_000),
+            };
+            heap.push(event(now + delay, seq));
+            calendar.push(event(now + delay, seq));
+            seq += 1;
+        }
+        assert_eq!(calendar.len(), heap.len());
+        assert_eq!(calendar.peek().map(order), heap.peek().map(order));
+        let (Some(a), Some(b)) = (calendar.pop(), heap.pop()) else {
+            break;
+        };
+        assert_eq!(order(&a), order(&b), "round {}", round);
+        now = a.time;
+    }
+    assert!(calendar.is_empty());
+    assert!(heap.is_empty());
+}
+
+#[test]
+fn test_calendar_takes_events_out_of_order() {
+    let mut calendar = PendingEvents::calendar();
+    for seq in 0..100 {
+        calendar.push(event(seq * 1_000, seq));
+    }
+    assert_eq!(calendar.pop().map(|e| e.seq), Some(0));
+
+    // An event earlier than the cursor still comes out first
+    calendar.push(event(500, 100));
+    assert_eq!(calendar.peek().map(|e| e.seq), Some(100));
+
+    let removed = calendar.remove_where(|e| e.node_id == 3);
+    assert!(removed.windows(2).all(|w| order(&w[0]) < order(&w[1])));
+    assert!(removed.iter().all(|e| e.node_id == 3));
+    assert_eq!(calendar.len() + removed.len(), 100);
+    assert_eq!(calendar.iter().count(), calendar.len());
+    assert!(calendar.iter().all(|e| e.node_id != 3));
+
+    let mut last = (0, 0);
+    while let Some(e) = calendar.pop() {
+        assert!(order(&e) > last);
+        last = order(&e);
+    }
+}
+
+#[test]
+fn test_queue_does_not_change_a_run() {
+    let topology = Topology::new()
+        .client("web", 400.0)
+        .lb("lb")
+        .servers("api", 3, |s| {
+            s.service_time = 30;
+            s.concurrency = 4;
+        })
+        .connect("web", "lb")
+        .connect("lb", "api");
+    let mut calendar = topology.build(5);
+    let mut heap = topology.build(5);
+    heap.set_event_queue(PendingEvents::heap());
+    assert!(matches!(heap.events, PendingEvents::Heap(_)));
+    assert_eq!(heap.events.len(), calendar.events.len());
+
+    calendar.advance_by(3_000_000);
+    heap.advance_by(3_000_000);
+    assert!(calendar.success_count > 500);
+    assert_eq!(calendar.success_count, heap.success_count);
+    assert_eq!(calendar.failure_count, heap.failure_count);
+    assert_eq!(calendar.latencies, heap.latencies);
+    assert_eq!(calendar.time, heap.time);
+}
@@ -0,0 +1,91 @@
+use crate::common::TestHarness;
+use slay_core::{FailureReason, Link, LoadBalancer};
+
+fn count(h: &TestHarness, reason: FailureReason) -> u64 {
+    h.sim.failure_reasons.get(&reason).copied().unwrap_or(0)
+}
+
+fn assert_sums_to_failure_count(h: &TestHarness) {
+    assert_eq!(
+        h.sim.failure_reasons.values().sum::<u64>(),
+        h.sim.failure_count
+    );
+}
+
+#[test]
+fn test_full_backlog_is_queue_full() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add_server(2, "S", 50, 1, 0);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(1_000);
+
+    assert!(count(&h, FailureReason::QueueFull) > 0);
+    assert_eq!(count(&h, FailureReason::QueueFull), h.sim.failure_count);
+    assert_sums_to_failure_count(&h);
+}
+
+#[test]
+fn test_dropped_packets_are_packet_loss() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S", 10, 100, 100);
+    let mut link = Link::default();
+    link.get_config_mut(1, 2).packet_loss_rate = 1.0;
+    h.connect_with_link(1, 2, link);
+    h.start();
+    h.run_for(1_000);
+
+    assert_eq!(h.sim.success_count, 0);
+    assert_eq!(count(&h, FailureReason::PacketLoss), h.sim.failure_count);
+}
+
+#[test]
+fn test_late_responses_are_timeouts() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    // Slower than the client's 5s timeout
+    h.add_server(2, "S", 6_000, 100, 100);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(10_000);
+
+    assert!(h.sim.failure_count > 0);
+    assert_eq!(count(&h, FailureReason::Timeout), h.sim.failure_count);
+}
+
+#[test]
+fn test_dead_backend_behind_lb() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S", 10, 4, 10);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.sim.components.get_mut(&3).unwrap().set_healthy(false);
+    h.start();
+    h.run_for(1_000);
+
+    // The health map marks the only target down, so the LB has nowhere to go
+    assert!(count(&h, FailureReason::NoHealthyTargets) > 0);
+    assert_sums_to_failure_count(&h);
+
+    h.sim.reset_stats();
+    assert!(h.sim.failure_reasons.is_empty());
+}
+
+#[test]
+fn test_removed_node_is_unavailable() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S", 200, 100, 100);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(500);
+    h.sim.remove_node(2);
+    h.run_for(100);
+
+    assert!(count(&h, FailureReason::Unavailable) > 0);
+    assert_sums_to_failure_count(&h);
+}
@@ -0,0 +1,150 @@
+use slay_core::{FailureReason, FluidMode, ScenarioAction, Topology};
+
+/// `rps` spread over four servers that can finish 40k a second.
+fn half_loaded(rps: f32) -> Topology {
+    Topology::new()
+        .client_with("users", |c| c.arrival_rate = rps)
+        .lb("lb")
+        .servers("api", 4, |s| {
+            s.service_time = 1;
+            s.concurrency = 10;
+            s.saturation_penalty = 0.0;
+        })
+        .connect("users", "lb")
+        .connect("lb", "api")
+}
+
+#[test]
+fn test_fluid_run_matches_discrete_run() {
+    let topology = half_loaded(20_000.0);
+
+    let mut discrete = topology.build(3);
+    discrete.advance_by(2_000_000);
+
+    let mut fluid = topology.build(3);
+    fluid.enable_fluid(FluidMode::default());
+    fluid.advance_by(2_000_000);
+
+    let ratio = fluid.success_count as f64 / discrete.success_count as f64;
+    assert!((0.97..1.03).contains(&ratio), "{}", ratio);
+    assert_eq!(fluid.failure_count, 0);
+    let p50 = |sim: &slay_core::Simulation| sim.histogram.value_at_quantile(0.5) as f64;
+    let latency = p50(&fluid) / p50(&discrete);
+    assert!((0.9..1.1).contains(&latency), "{}", latency);
+    // One generator tick per update instead of several events per request
+    assert!(fluid.next_seq * 100 < discrete.next_seq);
+}
+
+#[test]
+fn test_fluid_overload_fills_backlog_and_drops() {
+    let topology = Topology::new()
+        .client_with("users", |c| c.arrival_rate = 100_000.0)
+        .server("api", |s| {
+            s.service_time = 1;
+            s.concurrency = 10;
+            s.backlog_limit = 50;
+            s.saturation_penalty = 0.0;
+        })
+        .connect("users", "api");
+    let api = topology.id("api").unwrap();
+    let mut sim = topology.build(1);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(1_000_000);
+
+    // The server finishes 10k a second and turns the rest away
+    assert!(
+        (9_500..10_500).contains(&sim.success_count),
+        "{}",
+        sim.success_count
+    );
+    let full = sim.failure_reasons[&FailureReason::QueueFull];
+    assert!((85_000..92_000).contains(&full), "{}", full);
+    let load = sim.fluid_load(api).unwrap();
+    assert!((load.backlog - 50.0).abs() < 1e-6);
+    assert!(load.utilization > 0.99);
+}
+
+#[test]
+fn test_clients_switch_modes_with_their_rate() {
+    let topology = half_loaded(2_000.0);
+    let users = topology.id("users").unwrap();
+    let mut sim = topology.build(5);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(500_000);
+    assert!(!sim.is_fluid_source(users));
+    let discrete = sim.success_count;
+    assert!(discrete > 800);
+
+    let set_rate = |rps: f32| ScenarioAction::SetConfig {
+        node_id: users,
+        config: serde_json::json!({ "arrival_rate": rps }),
+    };
+    sim.apply_action(set_rate(50_000.0));
+    let seq = sim.next_seq;
+    sim.advance_by(500_000);
+    assert!(sim.is_fluid_source(users));
+    assert!(sim.success_count - discrete > 20_000);
+    assert!(sim.next_seq - seq < 5_000);
+
+    sim.apply_action(set_rate(2_000.0));
+    sim.advance_by(100_000);
+    assert!(!sim.is_fluid_source(users));
+    let before = sim.success_count;
+    sim.advance_by(500_000);
+    let after = sim.success_count - before;
+    assert!((900..1_100).contains(&after), "{}", after);
+}
+
+#[test]
+fn test_fluid_handles_rates_beyond_discrete_events() {
+    let mut sim = Topology::new()
+        .client_with("users", |c| c.arrival_rate = 50_000_000.0)
+        .lb("lb")
+        .servers("api", 100, |s| {
+            s.service_time = 5;
+            s.concurrency = 5_000;
+        })
+        .connect("users", "lb")
+        .connect("lb", "api")
+        .build(8);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(10_000_000);
+
+    let served = sim.success_count as f64 / 10.0;
+    assert!(
+        (0.98..1.02).contains(&(served / 50_000_000.0)),
+        "{}",
+        served
+    );
+    assert!(sim.next_seq < 5_000);
+}
+
+#[test]
+fn test_fluid_lb_routes_to_service_members() {
+    let topology = Topology::new()
+        .client_with("users", |c| c.arrival_rate = 20_000.0)
+        .lb_with("lb", |l| l.service = Some("api".into()))
+        .servers("api", 2, |s| {
+            s.service_time = 1;
+            s.concurrency = 100;
+        })
+        .server("wired", |s| s.service_time = 1)
+        .connect("users", "lb")
+        // Wired but not a member: gets no traffic
+        .connect("lb", "wired")
+        .service("api-1", "api")
+        .service("api-2", "api");
+    let mut sim = topology.build(3);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(1_000_000);
+
+    assert_eq!(sim.failure_count, 0);
+    assert!(sim.success_count > 19_000, "{}", sim.success_count);
+    let arrivals = |name: &str| {
+        sim.fluid_load(topology.id(name).unwrap())
+            .map_or(0.0, |l| l.arrival_rps)
+    };
+    assert_eq!(arrivals("wired"), 0.0);
+    assert!((9_500.0..10_500.0).contains(&arrivals("api-1")));
+    assert!((9_500.0..10_500.0).contains(&arrivals("api-2")));
+}
@@ -0,0 +1,45 @@
+use crate::common::TestHarness;
+
+#[test]
+fn test_routing_loop_is_cut_by_hop_limit() {
+    let mut h = TestHarness::new();
+    let client = h.add_client(1, 20.0);
+    client.config.write().unwrap().max_hops = 8;
+    h.add_server(2, "A", 1, 100, 100);
+    h.add_server(3, "B", 1, 100, 100);
+    // A -> B -> A forms a loop
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(3, 2);
+
+    h.start();
+    h.run_for(2000);
+
+    assert!(h.sim.hop_limit_exceeded > 0, "Loop must be detected");
+    assert_eq!(h.sim.success_count, 0);
+    assert!(
+        h.sim.failure_count > 0,
+        "Dropped requests count as failures"
+    );
+    assert!(
+        h.sim.events.len() < 100,
+        "Event queue must stay bounded, got {}",
+        h.sim.events.len()
+    );
+}
+
+#[test]
+fn test_hop_limit_allows_normal_chains() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 20.0);
+    h.add_server(2, "A", 1, 100, 100);
+    h.add_server(3, "B", 1, 100, 100);
+    h.connect(1, 2);
+    h.connect(2, 3);
+
+    h.start();
+    h.run_for(1000);
+
+    assert_eq!(h.sim.hop_limit_exceeded, 0);
+    assert!(h.sim.success_count > 0);
+}
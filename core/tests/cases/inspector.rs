@@ -0,0 +1,159 @@
+use crate::common::TestHarness;
+use serde_json::Value;
+use slay_core::traits::VisualState;
+use slay_core::*;
+
+/// Forwards every request to a fixed backend and records what the
+/// inspector reports about it.
+struct Probe {
+    backend: NodeId,
+    seen: Vec<(u32, u32, Option<u64>)>,
+}
+
+impl Component for Probe {
+    fn on_event(&mut self, event: Event, inspector: &dyn SystemInspector) -> Commands {
+        self.seen.push((
+            inspector.active_requests(self.backend),
+            inspector.queue_depth(self.backend),
+            inspector.recent_latency(self.backend),
+        ));
+        match event.event_type {
+            EventType::Arrival {
+                request_id,
+                mut path,
+                start_time,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                path.push(event.node_id);
+                smallvec![ScheduleCmd {
+                    delay: 0,
+                    node_id: self.backend,
+                    event_type: EventType::Arrival {
+                        request_id,
+                        path,
+                        start_time,
+                        timeout,
+                        max_hops,
+                        metadata,
+                    },
+                }]
+            }
+            EventType::Response {
+                request_id,
+                mut path,
+                start_time,
+                success,
+                failure,
+                timeout,
+                max_hops,
+                metadata,
+            } => {
+                path.pop();
+                let prev = *path.last().unwrap();
+                smallvec![ScheduleCmd {
+                    delay: 0,
+                    node_id: prev,
+                    event_type: EventType::Response {
+                        request_id,
+                        path,
+                        start_time,
+                        success,
+                        failure,
+                        timeout,
+                        max_hops,
+                        metadata,
+                    },
+                }]
+            }
+            _ => Commands::new(),
+        }
+    }
+    fn name(&self) -> &str {
+        "Probe"
+    }
+    fn kind(&self) -> &str {
+        "Probe"
+    }
+    fn encode_config(&self) -> Value {
+        Value::Null
+    }
+    fn apply_config(&mut self, _config: Value, _node_id: NodeId) -> Vec<ScheduleCmd> {
+        vec![]
+    }
+    fn active_requests(&self) -> u32 {
+        0
+    }
+    fn display_throughput(&self) -> f32 {
+        0.0
+    }
+    fn error_count(&self) -> u64 {
+        0
+    }
+    fn set_healthy(&mut self, _healthy: bool) {}
+    fn is_healthy(&self) -> bool {
+        true
+    }
+    fn add_target(&mut self, _target: NodeId) {}
+    fn remove_target(&mut self, _target: NodeId) {}
+    fn get_targets(&self) -> Vec<NodeId> {
+        vec![self.backend]
+    }
+    fn clear_targets(&mut self) {}
+    fn get_visual_snapshot(&self) -> VisualState {
+        VisualState::None
+    }
+    fn sync_display_stats(&mut self, _current_time_us: u64) {}
+    fn reset_internal_stats(&mut self) {}
+    fn set_seed(&mut self, _seed: u64) {}
+}
+
+#[test]
+fn test_inspector_reports_backend_load() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add(
+        2,
+        Box::new(Probe {
+            backend: 3,
+            seen: Vec::new(),
+        }),
+    );
+    // 100ms service with 4 workers cannot keep up with 200 rps
+    h.add_server(3, "S1", 100, 4, 1000);
+    h.connect(1, 2);
+    h.connect(2, 3);
+
+    h.start();
+    h.run_for(2000);
+
+    let seen = &h.sim.get_component_as::<Probe>(2).unwrap().seen;
+    assert!(seen.iter().any(|&(_, queued, _)| queued > 0));
+    assert!(seen.iter().all(|&(active, queued, _)| active >= queued));
+    assert!(seen.first().unwrap().2.is_none());
+    assert!(seen.last().unwrap().2.unwrap() > 100_000);
+}
+
+#[test]
+fn test_load_buffer_tracks_components() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add_server(2, "S1", 100, 4, 1000);
+    h.connect(1, 2);
+    assert_eq!(h.sim.load_buffer.get(&2), Some(&NodeLoad::default()));
+
+    h.start();
+    h.run_for(1000);
+    let server = h.sim.get_component_as::<Server>(2).unwrap();
+    let expected = NodeLoad {
+        active_requests: server.active_threads + server.queue.len() as u32,
+        queue_depth: server.queue.len() as u32,
+        recent_latency_us: server.latency_ewma_us.map(|l| l as u64),
+    };
+    assert!(expected.queue_depth > 0);
+    assert_eq!(h.sim.load_buffer[&2], expected);
+
+    h.sim.remove_node(2);
+    assert!(!h.sim.load_buffer.contains_key(&2));
+}
@@ -0,0 +1,157 @@
+use crate::common::TestHarness;
+use slay_core::Topology;
+
+const SHOP: &str = r#"
+apiVersion: networking.k8s.io/v1
+kind: Ingress
+metadata:
+  name: shop
+spec:
+  rules:
+    - host: shop.example.com
+      http:
+        paths:
+          - path: /
+            pathType: Prefix
+            backend:
+              service:
+                name: frontend
+                port: { number: 80 }
+---
+apiVersion: v1
+kind: ConfigMap
+metadata: { name: settings }
+data: { LOG_LEVEL: debug }
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata: { name: frontend }
+spec:
+  replicas: 2
+  selector: { matchLabels: { app: frontend } }
+  template:
+    metadata: { labels: { app: frontend, tier: web } }
+    spec:
+      containers:
+        - name: frontend
+          image: shop/frontend:1.4
+          env:
+            - { name: ORDERS_URL, value: "http://orders:8080/api" }
+            - { name: POD_IP, valueFrom: { fieldRef: { fieldPath: status.podIP } } }
+---
+apiVersion: v1
+kind: List
+items:
+  - apiVersion: apps/v1
+    kind: Deployment
+    metadata: { name: orders }
+    spec:
+      replicas: 3
+      template:
+        metadata: { labels: { app: orders } }
+        spec:
+          containers:
+            - name: orders
+              args: ["--db", "postgres.shop.svc.cluster.local:5432"]
+  - apiVersion: v1
+    kind: Service
+    metadata: { name: orders }
+    spec: { selector: { app: orders }, ports: [{ port: 8080 }] }
+---
+apiVersion: v1
+kind: Service
+metadata: { name: frontend }
+spec: { selector: { app: frontend } }
+---
+apiVersion: apps/v1
+kind: StatefulSet
+metadata: { name: postgres }
+spec:
+  template:
+    metadata: { labels: { app: postgres } }
+---
+apiVersion: v1
+kind: Service
+metadata: { name: postgres }
+spec: { clusterIP: None, selector: { app: postgres } }
+---
+"#;
+
+#[test]
+fn test_kubernetes_manifests_shape_the_topology() {
+    let topology = Topology::from_kubernetes(SHOP).unwrap();
+    let names: Vec<(&str, &str)> = topology
+        .nodes
+        .iter()
+        .map(|n| (n.name.as_str(), n.kind.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("users", "Client"),
+            ("orders", "LoadBalancer"),
+            ("frontend", "LoadBalancer"),
+            ("postgres", "LoadBalancer"),
+            ("frontend-1", "Server"),
+            ("frontend-2", "Server"),
+            ("orders-1", "Server"),
+            ("orders-2", "Server"),
+            ("orders-3", "Server"),
+            ("postgres-1", "Server"),
+        ]
+    );
+    let targets = |name: &str| {
+        topology.nodes[topology.id(name).unwrap() as usize - 1]
+            .targets
+            .clone()
+    };
+    assert_eq!(
+        targets("users"),
+        vec![3],
+        "only the Ingress backend is public"
+    );
+    assert_eq!(targets("frontend"), vec![5, 6]);
+    assert_eq!(targets("orders"), vec![7, 8, 9]);
+    assert_eq!(targets("frontend-2"), vec![2], "found through the env URL");
+    assert_eq!(targets("orders-1"), vec![4], "found through the argument");
+    assert_eq!(targets("postgres-1"), Vec::<u32>::new());
+    assert_eq!(topology.nodes[6].services, vec!["orders".to_string()]);
+
+    let mut h = TestHarness::from_topology(&topology, 5);
+    assert_eq!(h.sim.validate(), vec![]);
+    h.run_for(5000);
+    assert!(h.sim.success_count > 30, "{} served", h.sim.success_count);
+}
+
+#[test]
+fn test_kubernetes_entry_points_without_exposed_services() {
+    let topology = Topology::from_kubernetes(
+        "kind: Service\nmetadata: {name: api}\nspec: {selector: {app: api}}\n---\n\
+         kind: Deployment\nmetadata: {name: api}\nspec:\n  replicas: 0\n",
+    )
+    .unwrap();
+    assert_eq!(topology.nodes.len(), 2, "no replicas, no servers");
+    assert_eq!(
+        topology.nodes[0].targets,
+        vec![2],
+        "uncalled services get the traffic"
+    );
+}
+
+#[test]
+fn test_kubernetes_errors_name_the_document() {
+    let err = Topology::from_kubernetes("kind: ConfigMap\n").unwrap_err();
+    assert_eq!(err.document, 0);
+    assert!(err.message.contains("no Deployments"), "{}", err);
+
+    let err = Topology::from_kubernetes(
+        "kind: Service\nmetadata: {name: a}\n---\nkind: Deployment\nmetadata: {name: b}\nspec: {replicas: many}\n",
+    )
+    .unwrap_err();
+    assert_eq!(err.document, 2);
+    assert!(
+        err.to_string().starts_with("document 2: Deployment 'b'"),
+        "{}",
+        err
+    );
+}
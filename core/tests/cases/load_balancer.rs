@@ -1,6 +1,6 @@
 use crate::common::TestHarness;
 use slay_core::components::load_balancer::BalancingStrategy;
-use slay_core::LoadBalancer;
+use slay_core::{Health, LoadBalancer};
 use std::sync::Arc;
 
 #[test]
@@ -60,3 +60,65 @@ fn test_load_balancer_least_connections() {
         "Least Connections must favor the Fast server when Slow is bogged down"
     );
 }
+
+#[test]
+fn test_load_balancer_skips_node_killed_mid_run() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 10, 100, 100);
+    h.add_server(4, "S2", 10, 100, 100);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    assert_eq!(h.sim.health_buffer.get(&3), Some(&true));
+
+    h.start();
+    h.run_for(1000);
+    h.sim.set_healthy(3, false);
+    assert_eq!(h.sim.health_buffer.get(&3), Some(&false));
+
+    let failures_before = h.sim.failure_count;
+    h.run_for(1000);
+    assert!(
+        h.sim.failure_count - failures_before <= 1,
+        "LB should route around the dead node once health is updated"
+    );
+
+    h.sim.remove_node(3);
+    assert!(!h.sim.health_buffer.contains_key(&3));
+}
+
+#[test]
+fn test_load_balancer_avoids_degraded_backend() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    let lb = LoadBalancer::new("LB");
+    let lb_config = Arc::clone(&lb.config);
+    h.add(2, Box::new(lb));
+    h.add_server(3, "Degraded", 10, 100, 100);
+    h.add_server(4, "Healthy", 10, 100, 100);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    lb_config.write().unwrap().avoid_degraded = true;
+    h.start();
+    h.sim.set_health(
+        3,
+        Health::Degraded {
+            slowdown: 3.0,
+            error_rate: 0.5,
+        },
+    );
+    h.run_for(1000);
+
+    let degraded_rps = h.sim.components.get(&3).unwrap().display_throughput();
+    let healthy_rps = h.sim.components.get(&4).unwrap().display_throughput();
+    assert!(
+        degraded_rps < 1.0 && healthy_rps > 80.0,
+        "{} rps to the degraded backend, {} to the healthy one",
+        degraded_rps,
+        healthy_rps
+    );
+    assert_eq!(h.sim.failure_count, 0);
+}
@@ -1,7 +1,6 @@
 use crate::common::TestHarness;
 use slay_core::components::load_balancer::BalancingStrategy;
 use slay_core::components::server::Server;
-use slay_core::traits::VisualState;
 use slay_core::LoadBalancer;
 use std::sync::Arc;
 
@@ -55,12 +54,11 @@ fn test_retry_on_failure() {
     );
 
     // Check total retries metric on LB
-    let lb_snapshot = h.sim.components.get(&2).unwrap().get_visual_snapshot();
-    let total_retries = if let VisualState::LoadBalancer(stats) = lb_snapshot {
-        stats.total_retries
-    } else {
-        0
-    };
+    let total_retries = h
+        .sim
+        .get_component_as::<LoadBalancer>(2)
+        .unwrap()
+        .total_retries();
     println!("Total Retries: {}", total_retries);
     assert!(total_retries > 0, "LB should have performed retries");
 }
@@ -107,12 +105,11 @@ fn test_retry_exhaustion() {
         "Should have near 0% success as all servers fail"
     );
 
-    let lb_snapshot = h.sim.components.get(&2).unwrap().get_visual_snapshot();
-    let total_retries = if let VisualState::LoadBalancer(stats) = lb_snapshot {
-        stats.total_retries
-    } else {
-        0
-    };
+    let total_retries = h
+        .sim
+        .get_component_as::<LoadBalancer>(2)
+        .unwrap()
+        .total_retries();
     assert!(
         total_retries > 0,
         "Retries should happen even if they eventually fail"
@@ -160,12 +157,11 @@ fn test_retry_budget_exhaustion() {
     // 10 retries dropped.
     h.run_for(5000);
 
-    let snapshot = h.sim.components.get(&2).unwrap().get_visual_snapshot();
-    let total_retries = if let VisualState::LoadBalancer(stats) = snapshot {
-        stats.total_retries
-    } else {
-        0
-    };
+    let total_retries = h
+        .sim
+        .get_component_as::<LoadBalancer>(2)
+        .unwrap()
+        .total_retries();
 
     println!("Total Retries: {}", total_retries);
 
@@ -180,3 +176,57 @@ fn test_retry_budget_exhaustion() {
         total_retries
     );
 }
+
+#[test]
+fn test_retries_are_counted_per_edge() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+
+    let lb = LoadBalancer::new("LB");
+    let lb_config = Arc::clone(&lb.config);
+    h.add(2, Box::new(lb));
+    h.connect(1, 2);
+    // Every backend fails, so each request is tried on all three
+    for id in 3..=5 {
+        let server = Server::new("Bad", 10, 100, 100);
+        server.config.write().unwrap().failure_probability = 1.0;
+        h.add(id, Box::new(server));
+        h.connect(2, id);
+    }
+    {
+        let mut cfg = lb_config.write().unwrap();
+        cfg.max_retries = 2;
+        cfg.retry_backoff_ms = 10;
+        // No budget to speak of: the storm is let loose
+        cfg.retry_budget_ratio = 10.0;
+        cfg.retry_budget_max_tokens = 1_000.0;
+    }
+
+    h.start();
+    let mut metrics = slay_core::MetricsCollector::new(100);
+    for _ in 0..25 {
+        h.run_for(200);
+        metrics.update(&h.sim, 5_000_000);
+    }
+
+    let from_client = h.sim.edge_stats[&(1, 2)];
+    assert!(from_client.requests > 0);
+    assert_eq!(from_client.retries, 0);
+    let (requests, retries) = (3..=5)
+        .map(|id| h.sim.edge_stats[&(2, id)])
+        .fold((0, 0), |(req, ret), c| (req + c.requests, ret + c.retries));
+    assert!(
+        retries > requests - retries,
+        "{} retries for {} requests",
+        retries,
+        requests
+    );
+
+    let client_edge = *metrics.edge_history(1, 2).unwrap().back().unwrap();
+    assert!(!client_edge.is_retry_storm());
+    assert_eq!(client_edge.amplification(), Some(1.0));
+    let lb = metrics.retry_amplification(2).expect("LB retries");
+    assert!(lb.storm);
+    assert!((lb.factor - 3.0).abs() < 0.5, "amplification {}", lb.factor);
+    assert!(metrics.retry_amplification(1).is_none());
+}
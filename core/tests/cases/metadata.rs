@@ -0,0 +1,68 @@
+use crate::common::TestHarness;
+use serde_json::json;
+use slay_core::metadata::{PRIORITY, TENANT};
+use slay_core::{EventType, LoadBalancer, Metadata};
+
+#[test]
+fn test_metadata_bag_operations() {
+    let mut meta = Metadata::default();
+    assert!(meta.is_empty());
+    assert_eq!(meta.insert(TENANT, "acme"), None);
+    assert_eq!(meta.insert(PRIORITY, 3), None);
+    assert_eq!(meta.get_str(TENANT), Some("acme"));
+    assert_eq!(meta.get_u64(PRIORITY), Some(3));
+
+    // Copies share storage until one of them is written
+    let mut copy = meta.clone();
+    copy.insert(TENANT, "globex");
+    assert_eq!(meta.get_str(TENANT), Some("acme"));
+    assert_eq!(copy.get_str(TENANT), Some("globex"));
+
+    assert_eq!(meta.remove(PRIORITY), Some(json!(3)));
+    assert_eq!(meta.remove(TENANT), Some(json!("acme")));
+    assert!(meta.is_empty());
+    assert_eq!(meta, Metadata::default());
+}
+
+#[test]
+fn test_empty_metadata_is_not_serialized() {
+    let event = EventType::GenerateNext { generation_id: 1 };
+    let json = serde_json::to_string(&event).unwrap();
+    assert!(!json.contains("metadata"));
+
+    // Logs written before metadata existed still parse
+    let legacy =
+        r#"{"Arrival":{"request_id":1,"path":[1],"start_time":0,"timeout":10,"max_hops":8}}"#;
+    let parsed: EventType = serde_json::from_str(legacy).unwrap();
+    assert!(matches!(parsed, EventType::Arrival { metadata, .. } if metadata.is_empty()));
+}
+
+#[test]
+fn test_client_metadata_travels_with_requests() {
+    let mut h = TestHarness::new();
+    let client = h.add_client(1, 50.0);
+    client.config.write().unwrap().metadata = Metadata::default().with(TENANT, "acme");
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 10, 4, 50);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.sim.record_events(10_000);
+
+    h.start();
+    h.run_for(500);
+
+    let log = h.sim.event_log.as_ref().unwrap();
+    let mut requests = 0;
+    for event in &log.events {
+        match &event.event_type {
+            EventType::Arrival { metadata, .. }
+            | EventType::ProcessComplete { metadata, .. }
+            | EventType::Response { metadata, .. } => {
+                assert_eq!(metadata.get_str(TENANT), Some("acme"));
+                requests += 1;
+            }
+            EventType::GenerateNext { .. } => {}
+        }
+    }
+    assert!(requests > 0);
+}
@@ -1,6 +1,47 @@
+pub mod backpressure;
+pub mod builder;
+pub mod cache;
+pub mod calibration;
+pub mod cancellation;
+pub mod compare;
+pub mod compose;
+pub mod cost;
+pub mod custom_component;
+pub mod deadline;
+pub mod debugger;
+pub mod design;
 pub mod determinism;
+pub mod diagram;
+pub mod discovery;
+pub mod dot;
+pub mod event_queue;
+pub mod failure_reasons;
+pub mod fluid;
+pub mod hop_limit;
+pub mod inspector;
+pub mod kubernetes;
 pub mod load_balancer;
 mod load_balancer_retry;
+pub mod metadata;
 pub mod network_physics;
+pub mod node_metrics;
+pub mod node_removal;
+pub mod payload;
+pub mod plugin;
 pub mod process_logic;
+pub mod profiler;
+pub mod queueing;
 pub mod realism;
+pub mod replay;
+pub mod report;
+pub mod routing;
+pub mod run_until;
+pub mod scenario;
+pub mod script;
+pub mod stats_window;
+pub mod stress;
+pub mod templates;
+pub mod topology;
+pub mod tracing;
+pub mod validation;
+pub mod warmup;
@@ -87,6 +87,7 @@ fn test_jitter_variability() {
         latency_us: base,
         jitter_us: jitter,
         packet_loss_rate: 0.0,
+        bandwidth_mbps: 0.0,
     };
     h.connect_with_link(1, 2, link);
 
@@ -175,11 +176,13 @@ fn test_asymmetric_jitter() {
         latency_us: 10_000,
         jitter_us: 40_000,
         packet_loss_rate: 0.0,
+        bandwidth_mbps: 0.0,
     };
     *link.get_config_mut(2, 1) = EdgeConfig {
         latency_us: 100_000,
         jitter_us: 0,
         packet_loss_rate: 0.0,
+        bandwidth_mbps: 0.0,
     };
     h.connect_with_link(1, 2, link);
 
@@ -193,3 +196,75 @@ fn test_asymmetric_jitter() {
     assert!(p_min >= 100_000, "Min RTT should include return latency");
     assert!(p_max >= 140_000, "Max RTT should include jitter");
 }
+
+#[test]
+fn test_edge_stats_count_traffic_per_direction() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 8, 100);
+
+    let mut link = Link::default();
+    link.get_config_mut(1, 2).latency_us = 20_000;
+    link.get_config_mut(2, 1).latency_us = 5_000;
+    link.get_config_mut(2, 1).packet_loss_rate = 0.5;
+    h.connect_with_link(1, 2, link);
+
+    h.start();
+    let mut metrics = slay_core::MetricsCollector::new(100);
+    for _ in 0..25 {
+        h.run_for(200);
+        metrics.update(&h.sim, 5_000_000);
+    }
+
+    let forward = h.sim.edge_stats[&(1, 2)];
+    let back = h.sim.edge_stats[&(2, 1)];
+    assert!(forward.sent > 0);
+    assert_eq!(forward.dropped, 0);
+    assert_eq!(forward.added_latency_us, forward.sent * 20_000);
+    assert!(back.dropped > 0 && back.dropped < back.sent);
+    assert_eq!(back.added_latency_us, (back.sent - back.dropped) * 5_000);
+
+    let fwd = *metrics.edge_history(1, 2).unwrap().back().unwrap();
+    assert!((fwd.rps - 100.0).abs() < 25.0, "rps {}", fwd.rps);
+    assert_eq!(fwd.drop_rps, 0.0);
+    assert_eq!(fwd.avg_latency_ms, 20.0);
+    let ret = *metrics.edge_history(2, 1).unwrap().back().unwrap();
+    assert!(ret.drop_rps > 0.0);
+    assert_eq!(ret.avg_latency_ms, 5.0);
+
+    h.sim.remove_node(2);
+    assert!(h.sim.edge_stats.is_empty());
+}
+
+#[test]
+fn test_in_transit_messages_follow_link_latency() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 10);
+
+    // Given: 100ms each way, so ~10 requests and ~10 responses on the wire
+    let mut link = Link::default();
+    link.get_config_mut(1, 2).latency_us = 100_000;
+    link.get_config_mut(2, 1).latency_us = 100_000;
+    h.connect_with_link(1, 2, link);
+
+    h.start();
+    h.run_for(1000);
+
+    let now = h.sim.time;
+    let requests: Vec<_> = h.sim.in_transit_on(1, 2).copied().collect();
+    let responses: Vec<_> = h.sim.in_transit_on(2, 1).copied().collect();
+    assert!((3..=25).contains(&requests.len()), "{}", requests.len());
+    assert!((3..=25).contains(&responses.len()), "{}", responses.len());
+    assert!(requests.iter().all(|t| !t.is_response));
+    assert!(responses.iter().all(|t| t.is_response));
+    for t in requests.iter().chain(&responses) {
+        assert_eq!(t.arrive_us - t.sent_us, 100_000);
+        assert!(t.arrive_us >= now && t.sent_us <= now);
+        assert!((0.0..=1.0).contains(&t.progress(now)));
+    }
+
+    // When: the server goes away, nothing is left heading to it
+    h.sim.remove_node(2);
+    assert_eq!(h.sim.in_transit_on(1, 2).count(), 0);
+}
@@ -0,0 +1,316 @@
+use crate::common::TestHarness;
+use slay_core::MetricsCollector;
+
+fn sample_for(h: &mut TestHarness, metrics: &mut MetricsCollector, ms: u64) {
+    for _ in 0..ms / 200 {
+        h.run_for(200);
+        metrics.update(&h.sim, 5_000_000);
+    }
+}
+
+#[test]
+fn test_per_node_series() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    // 50ms service with 2 workers handles ~40 rps: saturated and queueing
+    h.add_server(2, "Slow", 50, 2, 1000);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    sample_for(&mut h, &mut metrics, 10_000);
+
+    let series = metrics.node_history(2).expect("server is sampled");
+    assert_eq!(series.len(), metrics.history.len());
+    let last = series.back().unwrap();
+    assert_eq!(last.sim_time_us, h.sim.time);
+    assert_eq!(last.utilization, 1.0);
+    assert!(last.queue_depth > 0);
+    assert!((last.rps - 100.0).abs() < 20.0, "rps {}", last.rps);
+    assert_eq!(last.error_rps, 0.0);
+
+    // The client has no capacity of its own
+    let client = metrics.node_history(1).unwrap().back().unwrap();
+    assert_eq!(client.utilization, 0.0);
+    assert_eq!(client.queue_depth, 0);
+}
+
+#[test]
+fn test_node_series_follow_topology() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 1);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(10);
+    sample_for(&mut h, &mut metrics, 4000);
+    let series = metrics.node_history(2).unwrap();
+    assert!(series.len() <= 10);
+    assert_eq!(series.len(), metrics.history.len());
+
+    h.sim.remove_node(2);
+    sample_for(&mut h, &mut metrics, 200);
+    assert!(metrics.node_history(2).is_none());
+
+    metrics.reset();
+    assert!(metrics.node_history(1).is_none());
+}
+
+#[test]
+fn test_csv_export_includes_node_and_edge_series() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    sample_for(&mut h, &mut metrics, 2_000);
+
+    let csv = metrics.to_csv();
+    let mut lines = csv.lines();
+    let header: Vec<_> = lines.next().unwrap().split(',').collect();
+    assert_eq!(
+        &header[..5],
+        &[
+            "sim_time_us",
+            "p50_ms",
+            "p99_ms",
+            "success_rps",
+            "failure_rps"
+        ]
+    );
+    assert!(header.contains(&"node1_rps"));
+    assert!(header.contains(&"node2_utilization"));
+    assert!(header.contains(&"edge1_2_rps"));
+    assert!(header.contains(&"edge2_1_avg_latency_ms"));
+
+    let rows: Vec<Vec<_>> = lines.map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows.len(), metrics.history.len());
+    assert!(rows.iter().all(|r| r.len() == header.len()));
+    let last = rows.last().unwrap();
+    assert_eq!(last[0], h.sim.time.to_string());
+    let col = header.iter().position(|c| *c == "node2_rps").unwrap();
+    let rps: f32 = last[col].parse().unwrap();
+    assert!(rps > 0.0);
+}
+
+#[test]
+fn test_prometheus_exposition() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    sample_for(&mut h, &mut metrics, 2_000);
+
+    let text = metrics.to_prometheus(&h.sim);
+    let value = |series: &str| -> f64 {
+        let line = text
+            .lines()
+            .find(|l| l.starts_with(series) && l[series.len()..].starts_with(' '))
+            .unwrap_or_else(|| panic!("missing {}", series));
+        line[series.len() + 1..].parse().unwrap()
+    };
+    assert_eq!(value("slay_sim_time_seconds"), 2.0);
+    assert_eq!(
+        value("slay_requests_total{outcome=\"success\"}"),
+        h.sim.success_count as f64
+    );
+    assert!(value("slay_node_requests_per_second{node=\"2\",kind=\"Server\",name=\"S1\"}") > 0.0);
+    assert!(value("slay_edge_messages_total{from=\"1\",to=\"2\"}") > 100.0);
+    assert!(text.contains("# TYPE slay_requests_total counter"));
+}
+
+#[test]
+fn test_configurable_percentiles() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 2, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300).with_percentiles([99.9, 90.0, 50.0, 90.0]);
+    assert_eq!(metrics.percentiles, vec![50.0, 90.0, 99.9]);
+    sample_for(&mut h, &mut metrics, 2_000);
+
+    let last = metrics.history.back().unwrap();
+    let p50 = last.latency_ms(50.0).unwrap();
+    let p90 = last.latency_ms(90.0).unwrap();
+    let p999 = last.latency_ms(99.9).unwrap();
+    assert!(p50 >= 9.0 && p50 <= p90 && p90 <= p999);
+    assert_eq!(last.latency_ms(99.0), None);
+    assert_eq!(
+        p999 as u64,
+        h.sim.get_percentile(99.9, 5_000_000).unwrap() / 1000
+    );
+
+    let header = metrics.to_csv().lines().next().unwrap().to_string();
+    assert!(header.starts_with("sim_time_us,p50_ms,p90_ms,p99.9_ms,success_rps"));
+    assert!(metrics
+        .to_prometheus(&h.sim)
+        .contains("slay_latency_seconds{quantile=\"0.999\"}"));
+
+    // Points keep the percentiles they were recorded with
+    metrics.set_percentiles([99.0]);
+    sample_for(&mut h, &mut metrics, 200);
+    assert!(metrics.history.back().unwrap().latency_ms(99.0).is_some());
+    assert!(metrics.history[0].latency_ms(99.0).is_none());
+}
+
+#[test]
+fn test_queue_wait_separated_from_service_time() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "Light", 10, 8, 1000);
+    h.add_client(3, 100.0);
+    // ~40 rps of capacity: the backlog grows while service time stays put
+    h.add_server(4, "Saturated", 50, 2, 1000);
+    h.connect(1, 2);
+    h.connect(3, 4);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    sample_for(&mut h, &mut metrics, 5_000);
+
+    let light = metrics.node_history(2).unwrap().back().unwrap();
+    assert_eq!(light.queue_wait_ms, 0.0);
+    assert!(light.service_ms >= 9.5 && light.service_ms < 20.0);
+
+    let saturated = metrics.node_history(4).unwrap().back().unwrap();
+    // Full load: 50ms with the default 1.5x saturation penalty
+    assert!((saturated.service_ms - 75.0).abs() < 5.0);
+    assert!(saturated.queue_wait_ms > 10.0 * saturated.service_ms);
+
+    let header = metrics.to_csv().lines().next().unwrap().to_string();
+    assert!(header.contains("node4_queue_wait_ms,node4_service_ms"));
+}
+
+#[test]
+fn test_per_kind_aggregates() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add(2, Box::new(slay_core::LoadBalancer::new("LB")));
+    // One saturated server, one idle-ish server behind round robin
+    h.add_server(3, "Slow", 50, 2, 1000);
+    h.add_server(4, "Fast", 1, 8, 1000);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    sample_for(&mut h, &mut metrics, 5_000);
+
+    let kinds: Vec<&str> = metrics.kinds().collect();
+    assert_eq!(kinds, ["Client", "LoadBalancer", "Server"]);
+
+    let fleet = *metrics.kind_history("Server").unwrap().back().unwrap();
+    let slow = *metrics.node_history(3).unwrap().back().unwrap();
+    let fast = *metrics.node_history(4).unwrap().back().unwrap();
+    assert_eq!(fleet.sim_time_us, h.sim.time);
+    assert_eq!((fleet.nodes, fleet.healthy), (2, 2));
+    assert_eq!(
+        fleet.utilization,
+        (slow.utilization + fast.utilization) / 2.0
+    );
+    assert_eq!(fleet.max_utilization, slow.utilization);
+    assert_eq!(fleet.queue_depth, slow.queue_depth + fast.queue_depth);
+    assert_eq!(fleet.rps, slow.rps + fast.rps);
+
+    h.sim.remove_node(1);
+    sample_for(&mut h, &mut metrics, 200);
+    assert!(metrics.kind_history("Client").is_none());
+}
+
+#[test]
+fn test_annotations_mark_scenario_steps_and_manual_actions() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.sim.load_scenario(
+        slay_core::Scenario::new("Outage")
+            .at(1_100_000, slay_core::ScenarioAction::Kill { node_id: 2 })
+            .at(2_100_000, slay_core::ScenarioAction::Revive { node_id: 2 }),
+    );
+    h.start();
+
+    let mut metrics = MetricsCollector::new(20);
+    sample_for(&mut h, &mut metrics, 3_000);
+    let marks: Vec<(u64, &str)> = metrics
+        .annotations()
+        .iter()
+        .map(|a| (a.sim_time_us, a.text.as_str()))
+        .collect();
+    assert_eq!(marks, [(1_100_000, "Kill #2"), (2_100_000, "Revive #2")]);
+
+    // Dragging a slider repeats the same annotation; it is kept once
+    metrics.annotate(3_000_000, "Configure #2");
+    metrics.annotate(3_400_000, "Configure #2");
+    assert_eq!(metrics.annotations().len(), 3);
+    assert_eq!(metrics.annotations().back().unwrap().sim_time_us, 3_400_000);
+
+    // Thinning the history keeps annotations from the start of the run
+    sample_for(&mut h, &mut metrics, 10_000);
+    assert_eq!(metrics.annotations().len(), 3);
+
+    metrics.reset();
+    sample_for(&mut h, &mut metrics, 200);
+    assert!(metrics.annotations().is_empty());
+}
+
+#[test]
+fn test_long_runs_are_downsampled_not_truncated() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(40);
+    sample_for(&mut h, &mut metrics, 120_000);
+
+    let history = &metrics.history;
+    assert!(history.len() <= 40);
+    // The whole run is still covered
+    assert!(history.front().unwrap().sim_time_us <= 10_000_000);
+    assert_eq!(history.back().unwrap().sim_time_us, h.sim.time);
+
+    // Recent samples at full resolution, older ones progressively coarser
+    let times: Vec<u64> = history.iter().map(|p| p.sim_time_us).collect();
+    let gaps: Vec<u64> = times.windows(2).map(|w| w[1] - w[0]).collect();
+    assert!(gaps[gaps.len() - 10..].iter().all(|&g| g == 200_000));
+    assert!(gaps[0] > 1_000_000, "oldest gap {}", gaps[0]);
+
+    // Merged rates stay rates, not sums
+    let oldest = &history[1];
+    assert!(
+        (oldest.success_rps - 50.0).abs() < 15.0,
+        "{}",
+        oldest.success_rps
+    );
+
+    // Node and edge series are thinned onto the same timestamps
+    let node: Vec<u64> = metrics
+        .node_history(2)
+        .unwrap()
+        .iter()
+        .map(|p| p.sim_time_us)
+        .collect();
+    assert_eq!(node, times);
+    let edge: Vec<u64> = metrics
+        .edge_history(1, 2)
+        .unwrap()
+        .iter()
+        .map(|p| p.sim_time_us)
+        .collect();
+    assert_eq!(edge, times);
+
+    let csv = metrics.to_csv();
+    assert_eq!(csv.lines().count(), history.len() + 1);
+    assert!(!csv.contains(",,"));
+}
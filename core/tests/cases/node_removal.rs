@@ -0,0 +1,96 @@
+use crate::common::TestHarness;
+use slay_core::{smallvec, EventType, Link, LoadBalancer, Metadata, ScenarioAction};
+
+#[test]
+fn test_remove_node_cancels_its_events() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 200, 2, 50);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(500);
+
+    let busy = h.sim.components[&2].active_requests();
+    assert!(busy > 2, "Server should have in-flight and queued work");
+    let failures_before = h.sim.failure_count;
+
+    h.sim.remove_node(2);
+    assert!(
+        h.sim.events.iter().all(|e| e.node_id != 2),
+        "No event may remain addressed to the removed node"
+    );
+
+    // Let the failure responses travel back to the client
+    h.run_for(100);
+    assert!(
+        h.sim.failure_count >= failures_before + busy as u64,
+        "Every request held by the removed node must fail upstream"
+    );
+}
+
+#[test]
+fn test_remove_backend_releases_lb_state() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 100, 10, 50);
+    h.add_server(4, "S2", 100, 10, 50);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    h.start();
+    h.run_for(500);
+
+    h.sim.remove_node(4);
+    h.run_for(2000);
+
+    assert!(h.sim.success_count > 0);
+    let total_sent = h.sim.success_count + h.sim.failure_count;
+    let in_flight = h.sim.components[&2].active_requests() as u64
+        + h.sim.components[&3].active_requests() as u64;
+    assert!(total_sent > 80);
+    assert!(
+        in_flight < 20,
+        "LB must not leak load for the removed backend"
+    );
+}
+
+#[test]
+fn test_events_for_missing_node_fail_back() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 1.0);
+    h.sim.schedule(
+        0,
+        99,
+        EventType::Arrival {
+            request_id: 1,
+            path: smallvec![1],
+            start_time: 0,
+            timeout: 1_000_000,
+            max_hops: 8,
+            metadata: Metadata::default(),
+        },
+    );
+    h.run_for(10);
+    assert_eq!(h.sim.failure_count, 1);
+}
+
+#[test]
+fn test_remove_node_forgets_its_partitions() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_server(2, "S1", 10, 1, 10);
+    h.connect(1, 2);
+    h.sim.links.entry((1, 2)).or_default().min_to_max.latency_us = 50_000;
+    h.sim.apply_action(ScenarioAction::Partition { a: 1, b: 2 });
+
+    h.sim.remove_node(2);
+    assert!(h.sim.partitioned_links.is_empty());
+
+    // A node reusing the id starts with a fresh link, even after a heal
+    h.add_server(2, "S2", 10, 1, 10);
+    h.connect(1, 2);
+    h.sim.apply_action(ScenarioAction::Heal { a: 1, b: 2 });
+    let link = h.sim.links.get(&(1, 2)).cloned().unwrap_or_default();
+    assert_eq!(link.min_to_max, Link::default().min_to_max);
+}
@@ -0,0 +1,154 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use slay_core::{EdgeConfig, Link, SizeDistribution, Topology};
+
+/// A client sending 1 Mbit requests over 100 Mbps links to an api that
+/// answers with 2 Mbit.
+fn uploads(rps: f32, bandwidth_mbps: f32) -> Topology {
+    let edge = EdgeConfig {
+        latency_us: 1_000,
+        bandwidth_mbps,
+        ..EdgeConfig::default()
+    };
+    Topology::new()
+        .client_with("users", |c| {
+            c.arrival_rate = rps;
+            c.request_size = Some(SizeDistribution::fixed(125_000));
+        })
+        .server("api", |s| {
+            s.service_time = 10;
+            s.concurrency = 100;
+            s.response_size = Some(SizeDistribution::fixed(250_000));
+        })
+        .connect_with(
+            "users",
+            "api",
+            Link {
+                min_to_max: edge,
+                max_to_min: edge,
+            },
+        )
+}
+
+#[test]
+fn test_sizes_take_time_to_send() {
+    let fast = uploads(10.0, 0.0);
+    let mut unlimited = fast.build(3);
+    unlimited.advance_by(10_000_000);
+
+    let topology = uploads(10.0, 100.0);
+    let (users, api) = (topology.id("users").unwrap(), topology.id("api").unwrap());
+    let mut sim = topology.build(3);
+    sim.advance_by(10_000_000);
+
+    // 1ms + 10ms to send, 10ms of work, 1ms + 20ms back
+    let p50 = |sim: &slay_core::Simulation| sim.histogram.value_at_quantile(0.5);
+    assert!(
+        (11_000..13_000).contains(&p50(&unlimited)),
+        "{}",
+        p50(&unlimited)
+    );
+    assert!((41_000..43_500).contains(&p50(&sim)), "{}", p50(&sim));
+
+    let up = sim.edge_stats[&(users, api)];
+    let down = sim.edge_stats[&(api, users)];
+    assert_eq!(up.bytes, up.sent * 125_000);
+    assert_eq!(down.bytes, down.sent * 250_000);
+}
+
+#[test]
+fn test_a_saturated_link_queues_messages() {
+    // The responses alone need 2 Mbit each: 50 a second fill the link
+    let mut steady = uploads(40.0, 100.0).build(3);
+    steady.advance_by(20_000_000);
+    assert_eq!(steady.failure_count, 0);
+    assert!(steady.histogram.value_at_quantile(0.99) < 100_000);
+
+    let mut saturated = uploads(60.0, 100.0).build(3);
+    saturated.advance_by(20_000_000);
+    // Responses pile up behind each other, later ones waiting seconds
+    assert!(saturated.histogram.value_at_quantile(0.99) > 1_000_000);
+}
+
+#[test]
+fn test_removed_nodes_leave_no_busy_links() {
+    let topology = uploads(60.0, 100.0);
+    let api = topology.id("api").unwrap();
+    let mut sim = topology.build(3);
+    sim.advance_by(5_000_000);
+    assert!(sim.link_busy_until.keys().any(|&(_, to)| to == api));
+
+    sim.remove_node(api);
+    assert!(sim
+        .link_busy_until
+        .keys()
+        .all(|&(from, to)| from != api && to != api));
+}
+
+#[test]
+fn test_servers_size_their_calls_and_pass_responses_through() {
+    let topology = Topology::new()
+        .client_with("users", |c| {
+            c.arrival_rate = 50.0;
+            c.request_size = Some(SizeDistribution::fixed(2_000));
+        })
+        .server("api", |s| {
+            s.service_time = 1;
+            s.request_size = Some(SizeDistribution::fixed(500));
+        })
+        .server("db", |s| {
+            s.service_time = 1;
+            s.response_size = Some(SizeDistribution::fixed(30_000));
+        })
+        .connect("users", "api")
+        .connect("api", "db");
+    let (users, api, db) = (
+        topology.id("users").unwrap(),
+        topology.id("api").unwrap(),
+        topology.id("db").unwrap(),
+    );
+    let mut sim = topology.build(3);
+    sim.advance_by(5_000_000);
+
+    let bytes_per_message = |from, to| {
+        let counters = sim.edge_stats[&(from, to)];
+        counters.bytes / counters.sent
+    };
+    assert_eq!(bytes_per_message(users, api), 2_000);
+    assert_eq!(bytes_per_message(api, db), 500);
+    assert_eq!(bytes_per_message(db, api), 30_000);
+    assert_eq!(bytes_per_message(api, users), 30_000);
+}
+
+#[test]
+fn test_sizes_spread_around_their_median() {
+    let size = SizeDistribution {
+        median_bytes: 10_000,
+        spread: 1.0,
+    };
+    let mut rng = StdRng::seed_from_u64(9);
+    let mut samples: Vec<u64> = (0..20_000).map(|_| size.sample(&mut rng)).collect();
+    samples.sort();
+    let median = samples[samples.len() / 2] as f64;
+    assert!((9_500.0..10_500.0).contains(&median), "{}", median);
+    // A long tail: the 95th percentile is about e^1.645 times the median
+    let p95 = samples[samples.len() * 95 / 100] as f64 / median;
+    assert!((4.6..5.8).contains(&p95), "{}", p95);
+}
+
+#[test]
+fn test_designs_keep_link_bandwidth() {
+    let yaml = "
+components:
+  users: { kind: client, calls: [api] }
+  api: { kind: server }
+links:
+  - { from: users, to: api, latency_ms: 5, bandwidth_mbps: 250 }
+";
+    let design = slay_core::Design::from_yaml(yaml).unwrap();
+    let link = &design.topology.links[0].link;
+    assert_eq!(link.min_to_max.bandwidth_mbps, 250.0);
+    assert_eq!(link.max_to_min.bandwidth_mbps, 250.0);
+    let again = slay_core::Design::from_yaml(&design.to_yaml()).unwrap();
+    assert_eq!(again.topology.links[0].link.min_to_max, link.min_to_max);
+}
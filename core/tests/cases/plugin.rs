@@ -0,0 +1,150 @@
+#![cfg(feature = "plugins")]
+
+use crate::common::TestHarness;
+use serde_json::json;
+use slay_core::traits::VisualState;
+use slay_core::*;
+
+const ANSWER_AT: u64 = 1024;
+
+/// A guest that describes itself with `info` and runs `on_event` as the
+/// body of `slay_on_event`. `answer` is placed where `on_event` can
+/// return it with `answer_packed`.
+fn guest(info: &str, answer: &str, on_event: &str) -> Vec<u8> {
+    let escape = |s: &str| s.replace('"', "\\\"");
+    wat::parse_str(format!(
+        r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "{info}")
+            (data (i32.const {answer_at}) "{answer}")
+            (func (export "slay_alloc") (param i32) (result i32) (i32.const 4096))
+            (func (export "slay_info") (result i64) (i64.const {info_len}))
+            (func (export "slay_on_event") (param i32 i32) (result i64) {on_event}))"#,
+        info = escape(info),
+        info_len = info.len(),
+        answer_at = ANSWER_AT,
+        answer = escape(answer),
+    ))
+    .unwrap()
+}
+
+fn answer_packed(answer: &str) -> String {
+    format!("(i64.const {})", ANSWER_AT << 32 | answer.len() as u64)
+}
+
+fn answering(kind: &str, answer: &str) -> &'static str {
+    let info = json!({ "kind": kind, "config": { "delay_us": 1000 } }).to_string();
+    load_plugin(&guest(&info, answer, &answer_packed(answer))).unwrap()
+}
+
+#[test]
+fn test_plugin_serves_traffic() {
+    let answer = r#"{"respond": {"success": true, "delay_us": 1000}}"#;
+    let kind = answering("WatEcho", answer);
+    assert_eq!(kind, "WatEcho");
+    assert!(component_kinds().contains(&"WatEcho"));
+    assert!(plugin_kinds().contains(&"WatEcho"));
+
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, create_component(kind, serde_json::Value::Null).unwrap());
+    h.connect(1, 2);
+    h.start();
+    h.run_for(2000);
+
+    assert!(h.sim.success_count > 50);
+    assert_eq!(h.sim.failure_count, 0);
+    let plugin = &h.sim.components[&2];
+    assert!(plugin.received_count() > 50);
+    let VisualState::Custom(snapshot) = plugin.get_visual_snapshot() else {
+        panic!("plugins show a custom snapshot");
+    };
+    assert_eq!(snapshot.errors, 0);
+
+    // A plugin node survives a topology round trip
+    let topology = h.sim.to_topology();
+    let h = TestHarness::from_topology(&topology, 3);
+    assert_eq!(h.sim.components[&2].kind(), "WatEcho");
+}
+
+#[test]
+fn test_plugin_config_keeps_declared_fields() {
+    let kind = answering("WatConfig", r#"{"respond": {"success": true}}"#);
+    let comp = create_component(kind, json!({ "delay_us": 5, "bogus": 1 })).unwrap();
+    assert_eq!(comp.encode_config(), json!({ "delay_us": 5 }));
+    let comp = create_component(kind, serde_json::Value::Null).unwrap();
+    assert_eq!(comp.encode_config(), json!({ "delay_us": 1000 }));
+}
+
+#[test]
+fn test_plugin_forwards_to_targets() {
+    // Forwards arrivals (`{"event":"a...`) and passes responses back
+    let answer = r#"{"forward": {}}"#;
+    let on_event = format!(
+        "(if (result i64) (i32.eq (i32.load8_u offset=10 (local.get 0)) (i32.const 97))
+            (then {}) (else (i64.const 0)))",
+        answer_packed(answer)
+    );
+    let kind = load_plugin(&guest(r#"{"kind": "WatProxy"}"#, answer, &on_event)).unwrap();
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, create_component(kind, serde_json::Value::Null).unwrap());
+    h.add_server(3, "Backend", 10, 8, 100);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.start();
+    h.run_for(2000);
+
+    assert!(h.sim.success_count > 50);
+    assert_eq!(h.sim.failure_count, 0);
+    assert!(h.sim.components[&3].received_count() > 50);
+    // Forwarded requests are held until the backend answers
+    assert!(h.sim.components[&2].active_requests() <= 2);
+}
+
+#[test]
+fn test_misbehaving_plugin_fails_requests() {
+    let info = r#"{"kind": "WatTrap"}"#;
+    let trap = load_plugin(&guest(info, "", "unreachable")).unwrap();
+    let info = r#"{"kind": "WatSpin"}"#;
+    let spin = load_plugin(&guest(info, "", "(loop $spin (br $spin)) (i64.const 0)")).unwrap();
+    // Answers with a 4 GiB buffer in a 64 KiB memory
+    let info = r#"{"kind": "WatHuge"}"#;
+    let huge = load_plugin(&guest(info, "", &format!("(i64.const {})", u32::MAX))).unwrap();
+
+    for kind in [trap, spin, huge] {
+        let mut h = TestHarness::new();
+        h.add_client(1, 20.0);
+        h.add(2, create_component(kind, serde_json::Value::Null).unwrap());
+        h.connect(1, 2);
+        h.start();
+        h.run_for(500);
+
+        assert_eq!(h.sim.success_count, 0, "{}", kind);
+        assert!(h.sim.failure_count > 0, "{}", kind);
+        let plugin = h.sim.get_component_as::<PluginComponent>(2).unwrap();
+        assert!(plugin.last_error().is_some(), "{}", kind);
+        assert_eq!(plugin.error_count(), plugin.received_count());
+    }
+}
+
+#[test]
+fn test_invalid_plugins_are_rejected() {
+    let builtin = guest(r#"{"kind": "Server"}"#, "", "(i64.const 0)");
+    assert!(load_plugin(&builtin).is_err());
+    assert!(create_component("Server", serde_json::Value::Null)
+        .unwrap()
+        .downcast_ref::<Server>()
+        .is_some());
+
+    let no_handler = wat::parse_str(
+        r#"(module
+            (memory (export "memory") 1)
+            (func (export "slay_alloc") (param i32) (result i32) (i32.const 0))
+            (func (export "slay_info") (result i64) (i64.const 0)))"#,
+    )
+    .unwrap();
+    let err = load_plugin(&no_handler).unwrap_err();
+    assert!(err.message.contains("slay_on_event"), "{}", err);
+    assert!(load_plugin(b"not wasm").is_err());
+}
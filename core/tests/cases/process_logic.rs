@@ -1,5 +1,6 @@
 use crate::common::TestHarness;
 use slay_core::LoadBalancer;
+use slay_core::{Topology, MAX_BATCH_ARRIVALS};
 
 #[test]
 fn test_chained_response_path() {
@@ -55,3 +56,105 @@ fn test_rps_accounting_precision() {
         total
     );
 }
+
+#[test]
+fn test_batched_arrivals_keep_the_rate() {
+    let run = |batch_us: u64| {
+        let mut sim = Topology::new()
+            .client_with("users", |c| {
+                c.arrival_rate = 50_000.0;
+                c.batch_us = batch_us;
+            })
+            .server("api", |s| {
+                s.service_time = 1;
+                s.concurrency = 1_000;
+            })
+            .connect("users", "api")
+            .build(9);
+        sim.advance_by(1_000_000);
+        (sim.success_count, sim.next_seq)
+    };
+    let (one_by_one, events) = run(0);
+    let (batched, batched_events) = run(10_000);
+
+    assert!(one_by_one > 49_000, "{}", one_by_one);
+    assert!(
+        batched.abs_diff(one_by_one) < 500,
+        "{} vs {}",
+        batched,
+        one_by_one
+    );
+    // Generator events drop from one per request to one per 10ms batch
+    assert!(
+        batched_events + 45_000 < events,
+        "{} vs {}",
+        batched_events,
+        events
+    );
+}
+
+#[test]
+fn test_batched_arrivals_keep_the_displayed_rate() {
+    let run = |batch_us: u64| {
+        let mut sim = Topology::new()
+            .client_with("users", |c| {
+                c.arrival_rate = 1_000.0;
+                c.batch_us = batch_us;
+            })
+            .server("api", |s| {
+                s.service_time = 1;
+                s.concurrency = 100;
+            })
+            .connect("users", "api")
+            .build(9);
+        // Halfway through a one second batch
+        sim.advance_by(2_500_000);
+        let now = sim.time;
+        let client = sim
+            .components
+            .values_mut()
+            .find(|c| c.kind() == "Client")
+            .unwrap();
+        client.sync_display_stats(now);
+        client.display_throughput()
+    };
+    let one_by_one = run(0);
+    let batched = run(1_000_000);
+
+    assert!((one_by_one - 1_000.0).abs() < 100.0, "{}", one_by_one);
+    assert!(
+        (batched - one_by_one).abs() < 100.0,
+        "{} vs {}",
+        batched,
+        one_by_one
+    );
+}
+
+#[test]
+fn test_batches_hold_few_pending_arrivals() {
+    let pending = |batch_us: u64| {
+        let mut sim = Topology::new()
+            .client_with("users", |c| {
+                c.arrival_rate = 500_000.0;
+                c.batch_us = batch_us;
+            })
+            .server("api", |s| {
+                s.service_time = 1;
+                s.concurrency = 10_000;
+            })
+            .connect("users", "api")
+            .build(9);
+        sim.advance_by(100_000);
+        sim.events.len()
+    };
+    let one_by_one = pending(0);
+    let batched = pending(1_000_000);
+
+    // A second of arrivals would be 500k pending events
+    assert!(
+        batched <= one_by_one + MAX_BATCH_ARRIVALS as usize,
+        "{} vs {}",
+        batched,
+        one_by_one
+    );
+}
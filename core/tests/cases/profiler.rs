@@ -0,0 +1,57 @@
+use slay_core::Topology;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A clock that moves 1µs every time it is read.
+fn ticking_clock() -> u64 {
+    static NOW: AtomicU64 = AtomicU64::new(0);
+    NOW.fetch_add(1_000, Ordering::Relaxed)
+}
+
+fn topology() -> Topology {
+    Topology::new()
+        .client("users", 200.0)
+        .lb("lb")
+        .servers("api", 2, |s| s.service_time = 20)
+        .connect("users", "lb")
+        .connect("lb", "api")
+}
+
+#[test]
+fn test_profile_counts_events_per_node() {
+    let mut sim = topology().build(4);
+    sim.enable_profiling(ticking_clock);
+    sim.advance_by(2_000_000);
+
+    let profile = sim.profile().unwrap();
+    assert!(profile.events > 1_000);
+    let handled: u64 = profile.nodes.iter().map(|n| n.cost.events).sum();
+    assert_eq!(handled, profile.events);
+    assert_eq!(profile.nodes.len(), 4);
+    assert!(profile
+        .nodes
+        .windows(2)
+        .all(|w| w[0].cost.busy_ns >= w[1].cost.busy_ns));
+    assert!(profile.nodes.iter().any(|n| n.kind == "LoadBalancer"));
+    assert!(profile.capacity_per_sec > 0.0);
+    assert!(profile.handler_share > 0.0 && profile.handler_share < 1.0);
+    assert!(profile.peak_pending >= profile.pending);
+    // The test binary keeps the system allocator
+    assert_eq!(profile.allocations, None);
+    assert!(profile.to_string().contains("events/s"));
+
+    sim.disable_profiling();
+    assert!(sim.profile().is_none());
+}
+
+#[test]
+fn test_profiling_leaves_the_run_unchanged() {
+    let mut plain = topology().build(4);
+    plain.advance_by(2_000_000);
+    let mut profiled = topology().build(4);
+    profiled.enable_profiling(ticking_clock);
+    profiled.advance_by(2_000_000);
+
+    assert_eq!(plain.success_count, profiled.success_count);
+    assert_eq!(plain.next_seq, profiled.next_seq);
+    assert_eq!(plain.latencies, profiled.latencies);
+}
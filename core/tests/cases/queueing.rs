@@ -0,0 +1,115 @@
+use crate::common::TestHarness;
+use slay_core::{LiveLoad, MetricsCollector, MmcModel};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6 * b.abs().max(1.0)
+}
+
+#[test]
+fn test_mm1_matches_closed_form() {
+    let m = MmcModel {
+        arrival_rps: 50.0,
+        service_us: 10_000.0,
+        servers: 1,
+    };
+    assert!(close(m.utilization(), 0.5));
+    assert!(close(m.wait_probability().unwrap(), 0.5));
+    // Wq = ρ / (μ - λ) = 0.5 / 50/s
+    assert!(close(m.mean_wait_us().unwrap(), 10_000.0));
+    assert!(close(m.mean_response_us().unwrap(), 20_000.0));
+    assert!(close(m.mean_queue_len().unwrap(), 0.5));
+}
+
+#[test]
+fn test_mmc_erlang_c_and_instability() {
+    let m = MmcModel {
+        arrival_rps: 100.0,
+        service_us: 10_000.0,
+        servers: 2,
+    };
+    assert!(close(m.utilization(), 0.5));
+    assert!(close(m.wait_probability().unwrap(), 1.0 / 3.0));
+    assert!(close(m.mean_wait_us().unwrap(), 10_000.0 / 3.0));
+
+    let overloaded = MmcModel {
+        arrival_rps: 250.0,
+        ..m
+    };
+    assert!(!overloaded.is_stable());
+    assert_eq!(overloaded.mean_wait_us(), None);
+}
+
+#[test]
+fn test_queueing_check_compares_model_with_simulation() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    for _ in 0..50 {
+        h.run_for(200);
+        metrics.update(&h.sim, 5_000_000);
+    }
+
+    assert!(metrics.queueing_check(&h.sim, 1, 5_000_000).is_none());
+    let check = metrics.queueing_check(&h.sim, 2, 5_000_000).unwrap();
+    assert_eq!(check.model.servers, 4);
+    assert!((check.model.arrival_rps - 200.0).abs() < 20.0);
+    assert!((check.model.utilization() - 0.5).abs() < 0.06);
+    // The saturation penalty makes the simulated server somewhat busier
+    // than the model, but in the same regime
+    assert!(check.utilization > 0.3 && check.utilization < 0.8);
+    assert!(check.response_us.unwrap() >= 9_500.0);
+}
+
+#[test]
+fn test_live_load_derives_rho_and_littles_law() {
+    let load = LiveLoad {
+        busy: 3,
+        workers: 4,
+        arrival_rps: 200.0,
+        capacity_rps: 400.0,
+        response_us: Some(15_000.0),
+    };
+    assert!(close(load.utilization(), 0.75));
+    assert!(close(load.rho(), 0.5));
+    // L = 200/s × 15ms
+    assert!(close(load.mean_in_system().unwrap(), 3.0));
+
+    let idle = LiveLoad {
+        workers: 0,
+        capacity_rps: 0.0,
+        response_us: None,
+        ..load
+    };
+    assert_eq!(idle.utilization(), 0.0);
+    assert!(idle.rho().is_infinite());
+    assert_eq!(idle.mean_in_system(), None);
+}
+
+#[test]
+fn test_live_load_reads_running_server() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add_server(2, "S1", 10, 4, 100);
+    h.connect(1, 2);
+    h.start();
+
+    let mut metrics = MetricsCollector::new(300);
+    for _ in 0..20 {
+        h.run_for(200);
+        metrics.update(&h.sim, 5_000_000);
+    }
+
+    assert!(metrics.live_load(&h.sim, 1).is_none());
+    let load = metrics.live_load(&h.sim, 2).unwrap();
+    assert_eq!(load.workers, 4);
+    assert!(load.busy <= 4);
+    assert!(close(load.capacity_rps, 400.0));
+    assert!((load.rho() - 0.5).abs() < 0.06);
+    // About 200/s × ~10ms of service each
+    let l = load.mean_in_system().unwrap();
+    assert!(l > 1.5 && l < 4.0, "{}", l);
+}
@@ -0,0 +1,106 @@
+use slay_core::{EventLog, Replay, Scenario, ScenarioAction, Simulation, Topology};
+
+fn reference_topology() -> Topology {
+    let mut sim = Simulation::new(1);
+    sim.add_component(1, Box::new(slay_core::Client::new("Client", 100.0)));
+    sim.add_component(2, Box::new(slay_core::Server::new("S1", 10, 2, 20)));
+    sim.connect_node(1, 2, slay_core::Link::default());
+    sim.to_topology()
+}
+
+fn recorded_run() -> (Replay, EventLog, Simulation) {
+    let mut sim = Simulation::from_topology(&reference_topology(), 5);
+    sim.load_scenario(Scenario::new("Blip").at(300_000, ScenarioAction::Kill { node_id: 2 }));
+    let replay = Replay::capture(&sim);
+    sim.record_events(100_000);
+    while sim.time < 500_000 && sim.step() {}
+    let log = sim.event_log.take().unwrap();
+    (replay, log, sim)
+}
+
+#[test]
+fn test_replay_reproduces_recorded_events() {
+    let (replay, log, _) = recorded_run();
+    assert!(log.len() > 50);
+    assert!(!log.truncated);
+    assert_eq!(replay.first_divergence(&log), None);
+}
+
+#[test]
+fn test_replay_detects_divergence() {
+    let (mut replay, log, _) = recorded_run();
+    replay.seed += 1;
+    assert!(replay.first_divergence(&log).is_some());
+}
+
+#[test]
+fn test_replay_rebuilds_state_at_time() {
+    let (replay, _, live) = recorded_run();
+    let rebuilt = replay.simulation_at(live.time);
+
+    assert_eq!(rebuilt.success_count, live.success_count);
+    assert_eq!(rebuilt.failure_count, live.failure_count);
+
+    // Scrubbing back to before the kill shows the server still healthy
+    let earlier = replay.simulation_at(200_000);
+    assert!(earlier.components[&2].is_healthy());
+    assert!(!live.components[&2].is_healthy());
+    assert!(earlier.success_count < live.success_count);
+}
+
+#[test]
+fn test_replay_samples_on_the_way_to_time() {
+    let (replay, _, _) = recorded_run();
+    let mut samples = Vec::new();
+    let rebuilt = replay.simulation_at_sampled(500_000, 100_000, |sim| {
+        samples.push((sim.time, sim.success_count))
+    });
+
+    assert_eq!(
+        samples.iter().map(|s| s.0).collect::<Vec<_>>(),
+        vec![100_000, 200_000, 300_000, 400_000, 500_000]
+    );
+    assert!(samples.windows(2).all(|w| w[0].1 <= w[1].1));
+    assert_eq!(
+        rebuilt.success_count,
+        replay.simulation_at(500_000).success_count
+    );
+}
+
+#[test]
+fn test_replay_keeps_warmup() {
+    let mut sim = Simulation::from_topology(&reference_topology(), 5);
+    sim.warmup_us = 200_000;
+    let replay = Replay::capture(&sim);
+    while sim.time < 500_000 && sim.step() {}
+
+    let rebuilt = replay.simulation_at(sim.time);
+    assert_eq!(rebuilt.warmup_us, 200_000);
+    assert_eq!(rebuilt.success_count, sim.success_count);
+}
+
+#[test]
+fn test_event_log_jsonl_round_trip() {
+    let (_, log, _) = recorded_run();
+    let mut buf = Vec::new();
+    log.write_jsonl(&mut buf).unwrap();
+    let decoded = EventLog::read_jsonl(buf.as_slice()).unwrap();
+    assert_eq!(decoded.len(), log.len());
+    assert!(decoded
+        .events
+        .iter()
+        .zip(log.events.iter())
+        .all(|(a, b)| a.same_as(b)));
+}
+
+#[test]
+fn test_event_log_respects_cap() {
+    let mut sim = Simulation::from_topology(&reference_topology(), 5);
+    sim.record_events(10);
+    for _ in 0..100 {
+        sim.step();
+    }
+    let log = sim.event_log.unwrap();
+    assert_eq!(log.len(), 10);
+    assert!(log.truncated);
+}
@@ -0,0 +1,85 @@
+use serde_json::json;
+use slay_core::{Experiment, FailureReason, Health, RunReport, Scenario, ScenarioAction, Topology};
+
+fn shop() -> Topology {
+    Topology::new()
+        .client("users", 40.0)
+        .lb("lb")
+        .servers("api", 2, |s| {
+            s.service_time = 10;
+            s.concurrency = 4;
+        })
+        .connect("users", "lb")
+        .connect("lb", "api")
+}
+
+fn run() -> RunReport {
+    let scenario =
+        Scenario::new("lose an api node").at(2_000_000, ScenarioAction::Kill { node_id: 3 });
+    Experiment::new(9, 5_000_000)
+        .with_warmup(1_000_000)
+        .with_scenario(scenario)
+        .report(&shop())
+}
+
+#[test]
+fn test_report_covers_the_run() {
+    let report = run();
+    assert_eq!(report.settings.seed, 9);
+    assert_eq!(report.settings.warmup_us, 1_000_000);
+    assert_eq!(report.settings.measured_us, 5_000_000);
+    assert_eq!(report.summary.nodes, 4);
+    assert_eq!(report.summary.kinds["Server"], 2);
+    assert_eq!(report.topology.nodes.len(), 4);
+    assert_eq!(report.scenario.steps.len(), 1);
+
+    // Matches the plain metrics of the same run
+    let scenario =
+        Scenario::new("lose an api node").at(2_000_000, ScenarioAction::Kill { node_id: 3 });
+    let metrics = Experiment::new(9, 5_000_000)
+        .with_warmup(1_000_000)
+        .with_scenario(scenario)
+        .run(&shop());
+    assert_eq!(report.metrics, metrics);
+
+    let ids: Vec<u32> = report.nodes.iter().map(|n| n.id).collect();
+    assert_eq!(ids, vec![1, 2, 3, 4]);
+    assert_eq!(report.nodes[2].health, Health::Down);
+    assert_eq!(report.nodes[3].health, Health::Healthy);
+    assert!(report.nodes[3].received > report.nodes[2].received);
+
+    let counted: u64 = report.failures.iter().map(|f| f.count).sum();
+    assert_eq!(counted, report.metrics.failure_count);
+    assert!(report
+        .failures
+        .windows(2)
+        .all(|pair| pair[0].count >= pair[1].count));
+}
+
+#[test]
+fn test_report_documents() {
+    let report = run();
+    let parsed: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(parsed["settings"]["seed"], json!(9));
+    assert_eq!(parsed["nodes"].as_array().unwrap().len(), 4);
+    let reparsed: RunReport = serde_json::from_value(parsed).unwrap();
+    assert_eq!(reparsed.metrics, report.metrics);
+
+    let md = report.to_markdown();
+    for section in [
+        "# Run report",
+        "## Results",
+        "## Failures",
+        "## Nodes",
+        "## Scenario",
+    ] {
+        assert!(md.contains(section), "{}", section);
+    }
+    assert!(md.contains("Seed 9, 1.0s warm-up, 5.0s measured."));
+    assert!(md.contains("lose an api node"));
+    assert!(md.contains("| 3 | api-1 | Server | Down |"), "{}", md);
+    if let Some(top) = report.failures.first() {
+        assert!(md.contains(top.reason.label()));
+    }
+    assert!(FailureReason::ALL.len() >= report.failures.len());
+}
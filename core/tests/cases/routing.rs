@@ -0,0 +1,104 @@
+use slay_core::{FluidMode, Simulation, Topology};
+
+/// A server calling `b` and `c` with the given chances.
+fn conditional_calls(probabilities: Vec<f32>) -> Topology {
+    Topology::new()
+        .client("users", 1_000.0)
+        .server("api", |s| {
+            s.service_time = 1;
+            s.concurrency = 1_000;
+            s.route_probabilities = probabilities;
+        })
+        .server("b", |s| {
+            s.service_time = 1;
+            s.concurrency = 1_000;
+        })
+        .server("c", |s| {
+            s.service_time = 1;
+            s.concurrency = 1_000;
+        })
+        .connect("users", "api")
+        .connect("api", "b")
+        .connect("api", "c")
+}
+
+fn received(sim: &Simulation, topology: &Topology, name: &str) -> u64 {
+    sim.components[&topology.id(name).unwrap()].received_count()
+}
+
+#[test]
+fn test_server_calls_targets_by_chance() {
+    let topology = conditional_calls(vec![0.95, 0.05]);
+    let mut sim = topology.build(4);
+    sim.advance_by(10_000_000);
+
+    let api = received(&sim, &topology, "api") as f64;
+    let b = received(&sim, &topology, "b") as f64 / api;
+    let c = received(&sim, &topology, "c") as f64 / api;
+    assert!((0.94..0.96).contains(&b), "{}", b);
+    assert!((0.04..0.06).contains(&c), "{}", c);
+    assert_eq!(sim.failure_count, 0);
+}
+
+#[test]
+fn test_requests_left_over_are_answered_by_the_server() {
+    let topology = conditional_calls(vec![0.0, 0.2]);
+    let mut sim = topology.build(4);
+    sim.advance_by(10_000_000);
+
+    let api = received(&sim, &topology, "api") as f64;
+    assert_eq!(received(&sim, &topology, "b"), 0);
+    let c = received(&sim, &topology, "c") as f64 / api;
+    assert!((0.18..0.22).contains(&c), "{}", c);
+    assert!(sim.success_count as f64 > api * 0.99);
+}
+
+#[test]
+fn test_targets_without_chances_split_evenly() {
+    let topology = conditional_calls(Vec::new());
+    let mut sim = topology.build(4);
+    sim.advance_by(10_000_000);
+
+    let api = received(&sim, &topology, "api") as f64;
+    let b = received(&sim, &topology, "b") as f64;
+    let c = received(&sim, &topology, "c") as f64;
+    assert!((0.9..1.1).contains(&(b / c)), "{} {}", b, c);
+    // Every request calls one of them
+    assert!((b + c) / api > 0.99);
+}
+
+#[test]
+fn test_chances_follow_their_targets() {
+    let topology = conditional_calls(vec![0.9, 0.1]);
+    let (api, b, c) = (
+        topology.id("api").unwrap(),
+        topology.id("b").unwrap(),
+        topology.id("c").unwrap(),
+    );
+    let mut sim = topology.build(4);
+    sim.components.get_mut(&api).unwrap().remove_target(b);
+
+    let saved = sim.to_topology();
+    let node = saved.nodes.iter().find(|n| n.id == api).unwrap();
+    assert_eq!(node.targets, vec![c]);
+    assert_eq!(
+        node.config["route_probabilities"],
+        serde_json::json!([0.1f32])
+    );
+}
+
+#[test]
+fn test_fluid_traffic_splits_by_the_same_chances() {
+    let topology = conditional_calls(vec![0.95, 0.05])
+        .client_with("crowd", |c| c.arrival_rate = 100_000.0)
+        .connect("crowd", "api");
+    let (b, c) = (topology.id("b").unwrap(), topology.id("c").unwrap());
+    let mut sim = topology.build(4);
+    sim.enable_fluid(FluidMode::default());
+    sim.advance_by(2_000_000);
+
+    let b = sim.fluid_load(b).unwrap().arrival_rps;
+    let c = sim.fluid_load(c).unwrap().arrival_rps;
+    assert!((94_000.0..96_000.0).contains(&b), "{}", b);
+    assert!((4_500.0..5_500.0).contains(&c), "{}", c);
+}
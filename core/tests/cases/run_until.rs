@@ -0,0 +1,118 @@
+use crate::common::TestHarness;
+use slay_core::{Breakpoint, RunCondition, RunOutcome};
+
+fn steady_system() -> TestHarness {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 20, 8, 50);
+    h.connect(1, 2);
+    h.start();
+    h
+}
+
+#[test]
+fn test_run_until_duration_stops_exactly() {
+    let mut h = steady_system();
+    let outcome = h.sim.run_until(&RunCondition::Duration {
+        duration_us: 1_234_567,
+    });
+    assert_eq!(outcome, RunOutcome::ConditionMet);
+    assert_eq!(h.sim.time, 1_234_567);
+    // Nothing past the deadline was processed
+    assert!(h.sim.events.iter().all(|e| e.time >= 1_234_567));
+}
+
+#[test]
+fn test_run_until_completed_requests() {
+    let mut h = steady_system();
+    h.sim
+        .run_until(&RunCondition::CompletedRequests { count: 250 });
+    assert_eq!(h.sim.success_count + h.sim.failure_count, 250);
+
+    h.sim
+        .run_until(&RunCondition::CompletedRequests { count: 10 });
+    assert_eq!(h.sim.success_count + h.sim.failure_count, 260);
+}
+
+#[test]
+fn test_run_until_converged() {
+    let mut h = steady_system();
+    let outcome = h.sim.run_until(&RunCondition::Any(vec![
+        RunCondition::Converged {
+            percentile: 50.0,
+            window_us: 5_000_000,
+            tolerance: 0.01,
+            stable_for_us: 5_000_000,
+            sample_every_us: 500_000,
+        },
+        RunCondition::Duration {
+            duration_us: 120_000_000,
+        },
+    ]));
+    assert_eq!(outcome, RunOutcome::ConditionMet);
+    assert!(
+        h.sim.time < 120_000_000,
+        "A steady system should converge before the time limit"
+    );
+    assert!(h.sim.time >= 5_000_000);
+}
+
+#[test]
+fn test_run_until_idle() {
+    let mut h = TestHarness::new();
+    h.add_server(2, "S1", 20, 8, 50);
+    let outcome = h
+        .sim
+        .run_until(&RunCondition::CompletedRequests { count: 1 });
+    assert_eq!(outcome, RunOutcome::Idle);
+}
+
+#[test]
+fn test_fast_forward_reports_progress() {
+    let mut h = steady_system();
+    let mut reports = Vec::new();
+    let outcome = h.sim.fast_forward(600_000_000, 5_000, |t| {
+        reports.push(t);
+        true
+    });
+    assert_eq!(outcome, RunOutcome::ConditionMet);
+    assert_eq!(h.sim.time, 600_000_000);
+    assert!(reports.len() > 10);
+    assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(*reports.last().unwrap(), 600_000_000);
+}
+
+#[test]
+fn test_fast_forward_can_be_interrupted_and_resumed() {
+    let mut h = steady_system();
+    let mut calls = 0;
+    let outcome = h.sim.fast_forward(10_000_000, 100, |_| {
+        calls += 1;
+        calls < 3
+    });
+    assert_eq!(outcome, RunOutcome::Interrupted);
+    assert!(h.sim.time < 10_000_000);
+
+    let outcome = h.sim.fast_forward(10_000_000, 100, |_| true);
+    assert_eq!(outcome, RunOutcome::ConditionMet);
+    assert_eq!(h.sim.time, 10_000_000);
+}
+
+#[test]
+fn test_advance_by_steps_a_paused_run() {
+    let mut h = steady_system();
+    assert_eq!(h.sim.advance_by(100_000), RunOutcome::ConditionMet);
+    assert_eq!(h.sim.time, 100_000);
+    let completed = h.sim.success_count;
+    assert_eq!(h.sim.advance_by(100_000), RunOutcome::ConditionMet);
+    assert_eq!(h.sim.time, 200_000);
+    assert!(h.sim.success_count > completed);
+    assert!(h.sim.events.iter().all(|e| e.time >= 200_000));
+
+    h.sim
+        .debugger
+        .breakpoints
+        .push(Breakpoint::ArrivalAt { node_id: 2 });
+    assert_eq!(h.sim.advance_by(1_000_000), RunOutcome::Breakpoint(0));
+    assert!(h.sim.time < 1_200_000);
+}
@@ -0,0 +1,382 @@
+use crate::common::TestHarness;
+use slay_core::{Health, Scenario, ScenarioAction};
+
+#[test]
+fn test_scenario_kill_and_revive() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+
+    h.sim.load_scenario(
+        Scenario::new("Outage")
+            .at(1_500_000, ScenarioAction::Kill { node_id: 2 })
+            .at(2_500_000, ScenarioAction::Revive { node_id: 2 }),
+    );
+
+    h.start();
+    h.run_for(1000);
+    assert_eq!(h.sim.failure_count, 0, "No failures before the outage");
+    assert!(h.sim.components[&2].is_healthy());
+
+    h.run_for(1000);
+    assert!(!h.sim.components[&2].is_healthy());
+    assert!(h.sim.failure_count > 30, "Outage must produce failures");
+
+    h.run_for(1000);
+    assert!(h.sim.components[&2].is_healthy());
+    let failures_after_outage = h.sim.failure_count;
+    h.run_for(1000);
+    assert!(
+        h.sim.failure_count - failures_after_outage < 5,
+        "Revived server should serve traffic again"
+    );
+}
+
+#[test]
+fn test_scheduled_outage() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    h.sim.schedule_outage(2, 1_500_000, 2_500_000);
+    assert_eq!(h.sim.scenario.steps.len(), 2);
+
+    h.start();
+    h.run_for(1000);
+    assert!(h.sim.components[&2].is_healthy());
+    h.run_for(1000);
+    assert!(!h.sim.components[&2].is_healthy());
+
+    // Scheduled while running, from the current time on
+    h.run_for(1000);
+    assert!(h.sim.components[&2].is_healthy());
+    h.sim.schedule_health(2, 3_200_000, false);
+    h.run_for(500);
+    assert!(!h.sim.components[&2].is_healthy());
+    assert_eq!(h.sim.scenario_cursor, 3);
+}
+
+#[test]
+fn test_scenario_partition_and_heal() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+
+    h.sim.load_scenario(
+        Scenario::new("Split brain")
+            .at(500_000, ScenarioAction::Partition { a: 1, b: 2 })
+            .at(1_000_000, ScenarioAction::Heal { a: 1, b: 2 }),
+    );
+
+    h.start();
+    h.run_for(1000);
+    assert!(h.sim.failure_count > 30, "Partition must drop requests");
+    assert_eq!(
+        h.sim.links[&(1, 2)].min_to_max.packet_loss_rate,
+        0.0,
+        "Heal must restore the original link"
+    );
+}
+
+#[test]
+fn test_scenario_set_config_from_json() {
+    let json = r#"{
+        "name": "Throttle",
+        "steps": [
+            { "at_us": 500000, "action": { "SetConfig": { "node_id": 2, "config": { "concurrency": 1, "backlog_limit": 0 } } } }
+        ]
+    }"#;
+    let scenario = Scenario::from_json(json).expect("valid scenario");
+
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    let server = h.add_server(2, "S1", 20, 50, 100);
+    h.connect(1, 2);
+    h.sim.load_scenario(scenario);
+
+    h.start();
+    h.run_for(1000);
+
+    assert_eq!(server._config.read().unwrap().concurrency, 1);
+    assert!(
+        h.sim.failure_count > 0,
+        "Reduced capacity should reject traffic"
+    );
+}
+
+#[test]
+fn test_scenario_from_yaml() {
+    let yaml = r#"
+name: Throttle
+steps:
+  - at_us: 900000
+    action: { Heal: { a: 1, b: 2 } }
+  - at_us: 500000
+    action:
+      SetConfig: { node_id: 2, config: { concurrency: 1, backlog_limit: 0 } }
+  - at_us: 700000
+    action: { SetHealth: { node_id: 2, health: Down } }
+    enabled: false
+"#;
+    let scenario = Scenario::from_yaml(yaml).expect("valid scenario");
+    assert_eq!(scenario.steps[0].at_us, 500_000);
+    assert_eq!(
+        scenario.steps[0].action,
+        ScenarioAction::SetConfig {
+            node_id: 2,
+            config: serde_json::json!({ "concurrency": 1, "backlog_limit": 0 }),
+        }
+    );
+    assert!(!scenario.steps[1].enabled);
+    assert_eq!(Scenario::from_yaml(&scenario.to_yaml()).unwrap(), scenario);
+    assert!(scenario.to_yaml().contains("Heal:"));
+}
+
+#[test]
+fn test_set_config_bumps_revision_and_generation() {
+    let mut h = TestHarness::new();
+    let client = h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(200);
+    let generation = client.config.read().unwrap().generation_id;
+    let revision = h.sim.config_revision;
+
+    h.sim.apply_action(ScenarioAction::SetConfig {
+        node_id: 1,
+        config: serde_json::json!({ "arrival_rate": 50.0 }),
+    });
+    assert_eq!(h.sim.config_revision, revision + 1);
+    assert_eq!(client.config.read().unwrap().generation_id, generation + 1);
+    assert_eq!(client.config.read().unwrap().arrival_rate, 50.0);
+}
+
+#[test]
+fn test_disabled_scenario_step_is_skipped() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+
+    let mut scenario = Scenario::new("Outage").at(500_000, ScenarioAction::Kill { node_id: 2 });
+    scenario.steps[0].enabled = false;
+    assert!(scenario.to_json().contains("\"enabled\": false"));
+    h.sim
+        .load_scenario(Scenario::from_json(&scenario.to_json()).unwrap());
+
+    h.start();
+    h.run_for(1000);
+    assert!(h.sim.components[&2].is_healthy());
+    assert_eq!(h.sim.scenario_cursor, 1, "The step is still passed");
+    assert_eq!(h.sim.failure_count, 0);
+}
+
+#[test]
+fn test_scenario_steps_added_during_run() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    h.sim
+        .load_scenario(Scenario::new("Chaos").at(200_000, ScenarioAction::Kill { node_id: 2 }));
+
+    h.start();
+    h.run_for(500);
+    assert_eq!(h.sim.scenario_cursor, 1);
+
+    // A step in the past counts as passed, one ahead fires when due
+    h.sim
+        .add_scenario_step(100_000, ScenarioAction::Revive { node_id: 2 });
+    let revive = h
+        .sim
+        .add_scenario_step(800_000, ScenarioAction::Revive { node_id: 2 });
+    assert_eq!(revive, 2);
+    assert_eq!(h.sim.scenario_cursor, 2);
+    assert!(!h.sim.components[&2].is_healthy());
+
+    h.sim.remove_scenario_step(0);
+    assert_eq!(h.sim.scenario_cursor, 1);
+    h.run_for(500);
+    assert!(h.sim.components[&2].is_healthy());
+    assert_eq!(h.sim.scenario_cursor, 2);
+}
+
+#[test]
+fn test_recorded_macro_replays_as_scenario() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    let server = h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    h.start();
+    h.run_for(300);
+
+    h.sim.start_macro("Manual outage");
+    h.run_for(200);
+    h.sim.set_healthy(2, false);
+    h.sim.record_action(ScenarioAction::Kill { node_id: 2 });
+    h.run_for(300);
+    let before = serde_json::json!({ "concurrency": 10, "backlog_limit": 100 });
+    let after = serde_json::json!({ "concurrency": 2, "backlog_limit": 100 });
+    // Every frame of a drag records, but one moment keeps a single step
+    let dragging = serde_json::json!({ "concurrency": 5, "backlog_limit": 100 });
+    let change = ScenarioAction::config_change(2, &before, &dragging).unwrap();
+    h.sim.record_action(change);
+    let change = ScenarioAction::config_change(2, &dragging, &after).unwrap();
+    h.sim.record_action(change);
+    let recorded = h.sim.stop_macro().expect("a recording");
+    h.sim.record_action(ScenarioAction::Revive { node_id: 2 });
+
+    let times: Vec<u64> = recorded.steps.iter().map(|s| s.at_us).collect();
+    assert_eq!(times, vec![200_000, 500_000], "Timed from the start");
+    assert_eq!(
+        recorded.steps[1].action,
+        ScenarioAction::SetConfig {
+            node_id: 2,
+            config: serde_json::json!({ "concurrency": 2 })
+        },
+        "Only the changed fields are kept"
+    );
+
+    // Replaying from t=0 kills the server and throttles it on schedule
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    let replayed = h.add_server(2, "S1", 10, 10, 100);
+    h.connect(1, 2);
+    h.sim.load_scenario(recorded.shifted(1_000_000));
+    h.start();
+    h.run_for(1100);
+    assert!(h.sim.components[&2].is_healthy());
+    h.run_for(200);
+    assert!(!h.sim.components[&2].is_healthy());
+    assert_eq!(replayed._config.read().unwrap().concurrency, 10);
+    h.run_for(300);
+    assert_eq!(replayed._config.read().unwrap().concurrency, 2);
+    assert_eq!(server._config.read().unwrap().concurrency, 10);
+}
+
+#[test]
+fn test_unchanged_config_is_not_recorded() {
+    let config = serde_json::json!({ "rps": 5.0, "generation_id": 1 });
+    let bumped = serde_json::json!({ "rps": 5.0, "generation_id": 2 });
+    assert_eq!(ScenarioAction::config_change(1, &config, &bumped), None);
+}
+
+#[test]
+fn test_injected_latency_slows_node_for_a_while() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 20, 100);
+    h.connect(1, 2);
+    h.sim.load_scenario(Scenario::new("Brownout").at(
+        1_100_000,
+        ScenarioAction::InjectLatency {
+            node_id: 2,
+            slowdown: 4.0,
+            extra_latency_us: 20_000,
+            error_rate: 0.0,
+            duration_us: Some(1_000_000),
+        },
+    ));
+
+    h.start();
+    h.run_for(1000);
+    let before = h.sim.get_percentile(50.0, 500_000).unwrap();
+    assert!(h.sim.brownout(2).is_none());
+
+    h.run_for(1000);
+    assert!(h.sim.brownout(2).is_some());
+    assert!(
+        h.sim.components[&2].is_healthy(),
+        "A brownout is not an outage"
+    );
+    let during = h.sim.get_percentile(50.0, 500_000).unwrap();
+    // 10ms of service becomes 4 × 10ms + 20ms
+    assert!(
+        during > before + 45_000,
+        "{}us before, {}us during",
+        before,
+        during
+    );
+
+    h.run_for(1000);
+    assert!(h.sim.brownout(2).is_none());
+    let after = h.sim.get_percentile(50.0, 500_000).unwrap();
+    assert!(after < during / 2, "{}us during, {}us after", during, after);
+    assert_eq!(h.sim.failure_count, 0);
+}
+
+#[test]
+fn test_cleared_latency_ends_brownout() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 20, 100);
+    h.connect(1, 2);
+    h.start();
+    h.sim.apply_action(ScenarioAction::InjectLatency {
+        node_id: 2,
+        slowdown: 1.0,
+        extra_latency_us: 50_000,
+        error_rate: 0.0,
+        duration_us: None,
+    });
+    h.run_for(1000);
+    assert!(h.sim.get_percentile(50.0, 500_000).unwrap() > 50_000);
+
+    h.sim
+        .apply_action(ScenarioAction::ClearLatency { node_id: 2 });
+    h.run_for(1000);
+    assert!(h.sim.brownout(2).is_none());
+    assert!(h.sim.get_percentile(50.0, 500_000).unwrap() < 50_000);
+}
+
+#[test]
+fn test_degraded_node_stays_up_but_fails_some_requests() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 200.0);
+    h.add_server(2, "S1", 10, 50, 100);
+    h.connect(1, 2);
+    h.start();
+    assert_eq!(h.sim.health(2), Health::Healthy);
+
+    let degraded = Health::Degraded {
+        slowdown: 2.0,
+        error_rate: 0.3,
+    };
+    h.sim.apply_action(ScenarioAction::SetHealth {
+        node_id: 2,
+        health: degraded,
+    });
+    h.run_for(2000);
+    assert_eq!(h.sim.health(2), degraded);
+    assert!(h.sim.components[&2].is_healthy());
+    let total = (h.sim.success_count + h.sim.failure_count) as f32;
+    let failed = h.sim.failure_count as f32 / total;
+    assert!(
+        (0.2..0.4).contains(&failed),
+        "{:.2} of requests failed",
+        failed
+    );
+
+    h.sim.set_health(2, Health::Down);
+    assert_eq!(h.sim.health(2), Health::Down);
+    h.sim.set_health(2, Health::Healthy);
+    assert_eq!(h.sim.health(2), Health::Healthy);
+    assert!(h.sim.brownout(2).is_none());
+}
+
+#[test]
+fn test_scenario_action_node_ids() {
+    assert_eq!(ScenarioAction::Kill { node_id: 4 }.node_ids(), vec![4]);
+    assert_eq!(
+        ScenarioAction::Partition { a: 2, b: 5 }.node_ids(),
+        vec![2, 5]
+    );
+    let config = ScenarioAction::SetConfig {
+        node_id: 3,
+        config: serde_json::json!({ "concurrency": 2 }),
+    };
+    assert_eq!(config.node_ids(), vec![3]);
+}
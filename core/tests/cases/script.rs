@@ -0,0 +1,131 @@
+#![cfg(feature = "scripting")]
+
+use crate::common::TestHarness;
+use serde_json::json;
+use slay_core::traits::VisualState;
+use slay_core::*;
+
+fn scripted(script: &str) -> Box<dyn Component> {
+    create_component(SCRIPTED_KIND, json!({ "script": script })).unwrap()
+}
+
+const TOKEN_BUCKET: &str = r#"
+fn init() {
+    this.tokens = 5.0;
+    this.last_us = 0;
+    this.rejected = 0;
+}
+
+fn on_event(ev) {
+    // 10 tokens a second, bursts of 5
+    this.tokens = min(5.0, this.tokens + (ev.time_us - this.last_us) / 100000.0);
+    this.last_us = ev.time_us;
+    if this.tokens < 1.0 {
+        this.rejected += 1;
+        return respond(false);
+    }
+    this.tokens -= 1.0;
+    respond(true, 1000)
+}
+"#;
+
+#[test]
+fn test_default_script_answers_without_targets() {
+    assert!(component_kinds().contains(&SCRIPTED_KIND));
+    let mut h = TestHarness::new();
+    h.add_client(1, 20.0);
+    h.add(
+        2,
+        create_component(SCRIPTED_KIND, serde_json::Value::Null).unwrap(),
+    );
+    h.connect(1, 2);
+    h.start();
+    h.run_for(2000);
+
+    assert!(h.sim.success_count > 20);
+    assert_eq!(h.sim.failure_count, 0);
+    let VisualState::Custom(snapshot) = h.sim.components[&2].get_visual_snapshot() else {
+        panic!("scripted nodes show a custom snapshot");
+    };
+    assert_eq!(snapshot.number("served"), Some(h.sim.success_count as f64));
+}
+
+#[test]
+fn test_script_keeps_state_between_calls() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, scripted(TOKEN_BUCKET));
+    h.connect(1, 2);
+    h.start();
+    h.run_for(4000);
+
+    // About 10 of the 50 requests a second get through
+    assert!(h.sim.success_count > 30, "{}", h.sim.success_count);
+    assert!(h.sim.success_count < 60, "{}", h.sim.success_count);
+    let VisualState::Custom(snapshot) = h.sim.components[&2].get_visual_snapshot() else {
+        panic!("scripted nodes show a custom snapshot");
+    };
+    assert_eq!(
+        snapshot.number("rejected"),
+        Some(h.sim.failure_count as f64)
+    );
+}
+
+#[test]
+fn test_script_forwards_and_sees_responses() {
+    let script = r#"
+        fn init() { this.ok = 0; }
+        fn on_event(ev) {
+            if ev.event == "arrival" { return forward(ev.targets[0]); }
+            if ev.success { this.ok += 1; }
+        }
+    "#;
+    let mut h = TestHarness::new();
+    h.add_client(1, 20.0);
+    h.add(2, scripted(script));
+    h.add_server(3, "Backend", 10, 8, 100);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.start();
+    h.run_for(2000);
+
+    assert!(h.sim.success_count > 20);
+    assert_eq!(h.sim.failure_count, 0);
+    let VisualState::Custom(snapshot) = h.sim.components[&2].get_visual_snapshot() else {
+        panic!("scripted nodes show a custom snapshot");
+    };
+    assert_eq!(snapshot.number("ok"), Some(h.sim.success_count as f64));
+}
+
+#[test]
+fn test_broken_script_fails_requests_until_fixed() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 20.0);
+    h.add(2, scripted("fn on_event(ev) { respond(true"));
+    h.connect(1, 2);
+    h.start();
+    h.run_for(500);
+    assert_eq!(h.sim.success_count, 0);
+    assert!(h.sim.failure_count > 0);
+    let node = h.sim.get_component_as::<ScriptedComponent>(2).unwrap();
+    assert!(node.last_error().unwrap().contains("script error"));
+
+    // A runaway loop is stopped too
+    h.sim.apply_action(ScenarioAction::SetConfig {
+        node_id: 2,
+        config: json!({ "script": "fn on_event(ev) { loop {} }" }),
+    });
+    let failures = h.sim.failure_count;
+    h.run_for(500);
+    assert_eq!(h.sim.success_count, 0);
+    assert!(h.sim.failure_count > failures);
+
+    h.sim.apply_action(ScenarioAction::SetConfig {
+        node_id: 2,
+        config: json!({ "script": "fn on_event(ev) { respond(true) }" }),
+    });
+    h.run_for(500);
+    assert!(h.sim.success_count > 0);
+    let node = h.sim.get_component_as::<ScriptedComponent>(2).unwrap();
+    assert_eq!(node.last_error(), None);
+}
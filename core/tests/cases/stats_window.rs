@@ -0,0 +1,49 @@
+use crate::common::TestHarness;
+
+#[test]
+fn test_windowed_p99_reflects_recovery() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    let server = h.add_server(2, "S1", 300, 10, 100);
+    h.connect(1, 2);
+
+    // Incident: slow backend for the first 5 seconds
+    h.start();
+    h.run_for(5000);
+    assert!(h.sim.get_percentile(99.0, 2_000_000).unwrap() > 300_000);
+
+    // Recovery
+    server._config.write().unwrap().service_time = 10;
+    h.run_for(5000);
+
+    let recent = h.sim.get_percentile(99.0, 2_000_000).unwrap();
+    let all_time = h.sim.get_percentile(99.0, 60_000_000).unwrap();
+    assert!(
+        recent < 100_000,
+        "Recent p99 {} should drop after recovery",
+        recent
+    );
+    assert!(
+        all_time > 300_000,
+        "Long window p99 {} should still see the incident",
+        all_time
+    );
+}
+
+#[test]
+fn test_window_without_samples_is_empty() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_server(2, "S1", 10, 1, 10);
+    h.connect(1, 2);
+
+    h.start();
+    h.run_for(1000);
+    assert!(h.sim.get_percentile(50.0, 1_000_000).is_some());
+
+    // Stop traffic and let the window slide past every sample
+    h.sim.set_healthy(1, false);
+    h.run_for(3000);
+    assert!(h.sim.get_percentile(50.0, 1_000_000).is_none());
+    assert!(h.sim.get_percentile(50.0, 10_000_000).is_some());
+}
@@ -0,0 +1,15 @@
+use slay_core::stress_topology;
+
+#[test]
+fn test_stress_topology_is_sized_and_healthy() {
+    let topology = stress_topology(1_000);
+    assert!(topology.nodes.len() >= 1_000);
+    assert!(topology.nodes.len() < 1_200, "{}", topology.nodes.len());
+
+    let mut sim = topology.build(3);
+    assert!(sim.validate().is_empty(), "{:?}", sim.validate());
+    sim.advance_by(500_000);
+    // Six regions of 1,000 rps, minus what is still in flight
+    assert!(sim.success_count > 2_000, "{}", sim.success_count);
+    assert_eq!(sim.failure_count, 0);
+}
@@ -0,0 +1,91 @@
+use crate::common::TestHarness;
+use slay_core::{fault_template, template, Experiment, ScenarioAction, FAULT_TEMPLATES, TEMPLATES};
+
+#[test]
+fn test_templates_load_and_serve_traffic() {
+    assert!(TEMPLATES.len() >= 5);
+    for t in TEMPLATES {
+        let topology = t.topology();
+        assert!(!topology.nodes.is_empty(), "{} is empty", t.name);
+
+        let mut h = TestHarness::from_topology(&topology, 1);
+        assert_eq!(h.sim.validate(), vec![], "{} has problems", t.name);
+        h.run_for(5_000);
+        assert!(h.sim.success_count > 0, "{} served nothing", t.name);
+        let sla = h.sim.success_count as f64 / (h.sim.success_count + h.sim.failure_count) as f64;
+        assert!(
+            sla > 0.95,
+            "{} is overloaded out of the box: {}",
+            t.name,
+            sla
+        );
+    }
+}
+
+#[test]
+fn test_template_lookup_by_name() {
+    let t = template("Multi-region").expect("built in");
+    assert_eq!(t.topology().nodes.len(), 8);
+    assert!(template("No such design").is_none());
+}
+
+fn killed(scenario: &slay_core::Scenario) -> Vec<u32> {
+    scenario
+        .steps
+        .iter()
+        .filter_map(|s| match s.action {
+            ScenarioAction::Kill { node_id } => Some(node_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_fault_templates_fit_the_topology() {
+    let multi_region = template("Multi-region").unwrap().topology();
+    let load_balanced = template("Load-balanced replicas").unwrap().topology();
+    let cache_aside = template("Cache-aside").unwrap().topology();
+
+    let zone = fault_template("Zone outage").unwrap();
+    let outage = zone.scenario(&multi_region, 1_000_000, 2_000_000).unwrap();
+    assert_eq!(killed(&outage), vec![4, 7, 8], "the far region goes down");
+    assert_eq!(outage.steps.last().unwrap().at_us, 3_000_000);
+    let outage = zone.scenario(&load_balanced, 0, 1).unwrap();
+    assert_eq!(killed(&outage), vec![5]);
+
+    let stampede = fault_template("Cache flush stampede").unwrap();
+    assert!(
+        stampede.scenario(&load_balanced, 0, 1).is_none(),
+        "no cache tier to flush"
+    );
+    let flush = stampede.scenario(&cache_aside, 0, 4_000_000).unwrap();
+    assert_eq!(killed(&flush), vec![3, 4, 5, 6]);
+
+    let brownout = fault_template("Dependency brownout").unwrap();
+    let slow = brownout.scenario(&cache_aside, 0, 1).unwrap();
+    assert!(
+        matches!(
+            slow.steps[0].action,
+            ScenarioAction::InjectLatency { node_id: 7, .. }
+        ),
+        "the database is the shared dependency"
+    );
+}
+
+#[test]
+fn test_fault_templates_run_as_chaos_experiments() {
+    let topology = template("Load-balanced replicas").unwrap().topology();
+    for t in FAULT_TEMPLATES {
+        let Some(scenario) = t.scenario(&topology, 2_000_000, 3_000_000) else {
+            continue;
+        };
+        let report = Experiment::new(1, 8_000_000)
+            .with_scenario(scenario)
+            .chaos_report(&topology, 99.0);
+        assert!(report.overall.requests > 0, "{} served nothing", t.name);
+        // Link edits are configuration changes, not scored faults
+        if t.name != "Creeping packet loss" {
+            assert!(!report.faults.is_empty(), "{} injected no fault", t.name);
+        }
+    }
+}
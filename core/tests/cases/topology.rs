@@ -0,0 +1,121 @@
+use crate::common::TestHarness;
+use slay_core::{LoadBalancer, Topology};
+
+fn build_reference() -> TestHarness {
+    let mut h = TestHarness::new_with_seed(7);
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 10, 4, 50);
+    h.add_server(4, "S2", 20, 4, 50);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.connect(2, 4);
+    h.sim
+        .links
+        .get_mut(&(2, 4))
+        .unwrap()
+        .get_config_mut(2, 4)
+        .latency_us = 42_000;
+    h
+}
+
+#[test]
+fn test_topology_json_round_trip() {
+    let h = build_reference();
+    let topology = h.sim.to_topology();
+
+    assert_eq!(topology.nodes.len(), 4);
+    assert_eq!(topology.nodes[1].kind, "LoadBalancer");
+    assert_eq!(topology.nodes[1].targets, vec![3, 4]);
+
+    let decoded = Topology::from_json(&topology.to_json()).expect("valid json");
+    assert_eq!(decoded.nodes, topology.nodes);
+
+    let restored = TestHarness::from_topology(&decoded, 7);
+    let link = &restored.sim.links[&(2, 4)];
+    assert_eq!(link.get_config(2, 4).latency_us, 42_000);
+    assert_eq!(restored.sim.to_topology().to_json(), topology.to_json());
+}
+
+#[test]
+fn test_topology_save_and_load_file() {
+    let topology = build_reference().sim.to_topology();
+    let path = std::env::temp_dir().join(format!("slay-topology-{}.json", std::process::id()));
+
+    topology.save(&path).expect("save");
+    let loaded = Topology::load(&path).expect("load");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded.nodes, topology.nodes);
+    assert_eq!(loaded.links.len(), topology.links.len());
+}
+
+#[test]
+fn test_topology_restored_simulation_runs_deterministically() {
+    let topology = build_reference().sim.to_topology();
+
+    let mut h1 = TestHarness::from_topology(&topology, 99);
+    h1.run_for(1000);
+    let mut h2 = TestHarness::from_topology(&topology, 99);
+    h2.run_for(1000);
+
+    assert!(h1.sim.success_count > 0);
+    assert_eq!(h1.sim.success_count, h2.sim.success_count);
+    assert_eq!(h1.sim.latencies, h2.sim.latencies);
+}
+
+#[test]
+fn test_paste_subgraph_under_new_ids() {
+    let mut h = build_reference();
+    h.sim.register_service("backend", 4);
+    let copied = h.sim.to_topology().subgraph(&[2, 4]);
+
+    // Only the connection and link inside the copied set survive
+    assert_eq!(copied.nodes.len(), 2);
+    assert_eq!(copied.nodes[0].targets, vec![4]);
+    assert_eq!(copied.links.len(), 1);
+
+    let new_ids = h.sim.paste_topology(&copied, 10);
+    assert_eq!(new_ids.into_iter().collect::<Vec<_>>(), [(2, 10), (4, 11)]);
+    assert_eq!(h.sim.components[&10].kind(), "LoadBalancer");
+    assert_eq!(h.sim.components[&10].get_targets(), vec![11]);
+    assert_eq!(
+        h.sim.components[&11].encode_config(),
+        h.sim.components[&4].encode_config()
+    );
+    assert_eq!(h.sim.links[&(10, 11)].min_to_max.latency_us, 42_000);
+    assert_eq!(h.sim.services.members("backend"), vec![4, 11]);
+
+    // The originals are untouched and the copies serve traffic
+    assert_eq!(h.sim.components[&2].get_targets(), vec![3, 4]);
+    h.sim
+        .paste_topology(&h.sim.to_topology().subgraph(&[1]), 12);
+    h.sim.components.get_mut(&12).unwrap().remove_target(2);
+    h.sim.components.get_mut(&12).unwrap().add_target(10);
+    h.start();
+    h.run_for(2_000);
+    assert!(h.sim.components[&11].received_count() > 0);
+}
+
+#[test]
+fn test_node_names_survive_round_trip() {
+    let mut h = build_reference();
+    h.sim
+        .components
+        .get_mut(&3)
+        .unwrap()
+        .set_name("checkout-svc");
+
+    let json = h.sim.to_topology().to_json();
+    assert!(json.contains("checkout-svc"));
+
+    let restored = TestHarness::from_topology(&Topology::from_json(&json).unwrap(), 7);
+    assert_eq!(restored.sim.components[&3].name(), "checkout-svc");
+    assert_eq!(restored.sim.components[&4].name(), "S2");
+
+    // Files without names get the kind's default
+    let mut unnamed = Topology::from_json(&json).unwrap();
+    unnamed.nodes[2].name.clear();
+    let restored = TestHarness::from_topology(&unnamed, 7);
+    assert_eq!(restored.sim.components[&3].name(), "Server");
+}
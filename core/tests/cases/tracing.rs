@@ -0,0 +1,159 @@
+use crate::common::TestHarness;
+use slay_core::{Link, LoadBalancer, Segment};
+
+#[test]
+fn test_traces_capture_every_hop() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 20, 10, 10);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    h.sim.enable_tracing(1, 100);
+    h.start();
+    h.run_for(1000);
+
+    let tracer = h.sim.tracer.as_ref().unwrap();
+    assert!(tracer.completed.len() > 30);
+
+    let trace = &tracer.completed[0];
+    assert_eq!(trace.success, Some(true));
+    let nodes: Vec<_> = trace.spans.iter().map(|s| s.node_id).collect();
+    assert_eq!(nodes, vec![1, 2, 3]);
+
+    // Spans nest: each downstream span lies within its caller's span
+    for pair in trace.spans.windows(2) {
+        let (outer, inner) = (&pair[0], &pair[1]);
+        assert!(inner.start_us >= outer.start_us);
+        assert!(inner.end_us.unwrap() <= outer.end_us.unwrap());
+    }
+    // Server time includes ~20ms of service
+    assert!(trace.spans[2].duration_us().unwrap() >= 19_000);
+    assert_eq!(trace.spans[0].end_us, trace.end_us);
+}
+
+#[test]
+fn test_trace_sampling_and_failures() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 100.0);
+    h.add_server(2, "S1", 100, 1, 0);
+    h.connect(1, 2);
+    h.sim.enable_tracing(10, 1000);
+    h.start();
+    h.run_for(2000);
+
+    let tracer = h.sim.tracer.as_ref().unwrap();
+    let total = h.sim.success_count + h.sim.failure_count;
+    let traced = tracer.completed.len() as u64;
+    assert!(traced > 0 && traced <= total / 10 + 1);
+    assert!(tracer
+        .completed
+        .iter()
+        .any(|t| t.success == Some(false) && t.spans[1].success == Some(false)));
+}
+
+#[test]
+fn test_tracing_does_not_change_results() {
+    let run = |trace: bool| {
+        let mut h = TestHarness::new_with_seed(3);
+        h.add_client(1, 100.0);
+        h.add_server(2, "S1", 10, 2, 10);
+        h.connect(1, 2);
+        if trace {
+            h.sim.enable_tracing(1, 10);
+        }
+        h.start();
+        h.run_for(1000);
+        h.sim.latencies.clone()
+    };
+    assert_eq!(run(false), run(true));
+}
+
+#[test]
+fn test_latency_breakdown_accounts_for_whole_request() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 20, 10, 10);
+    let mut front = Link::default();
+    front.get_config_mut(1, 2).latency_us = 3_000;
+    front.get_config_mut(2, 1).latency_us = 3_000;
+    h.connect_with_link(1, 2, front);
+    let mut back = Link::default();
+    back.get_config_mut(2, 3).latency_us = 7_000;
+    back.get_config_mut(3, 2).latency_us = 1_000;
+    h.connect_with_link(2, 3, back);
+    h.sim.enable_tracing(1, 100);
+    h.start();
+    h.run_for(1000);
+
+    let tracer = h.sim.tracer.as_ref().unwrap();
+    let trace = &tracer.completed[0];
+    let hops: Vec<_> = trace.hops.iter().map(|hop| (hop.from, hop.to)).collect();
+    assert_eq!(hops, vec![(1, 2), (2, 3), (3, 2), (2, 1)]);
+
+    let breakdown = tracer.latency_breakdown();
+    assert!(breakdown.requests > 30);
+    assert_eq!(breakdown.mean_for(Segment::Link(1, 2)).transit_us, 3_000);
+    assert_eq!(breakdown.mean_for(Segment::Link(2, 3)).transit_us, 7_000);
+    assert_eq!(breakdown.mean_for(Segment::Link(3, 2)).transit_us, 1_000);
+    assert!(breakdown.mean_for(Segment::Node(3)).service_us >= 19_000);
+
+    // Every microsecond of a successful request is attributed somewhere
+    let total: u64 = breakdown.segments.values().map(|s| s.total_us()).sum();
+    let durations: u64 = tracer
+        .completed
+        .iter()
+        .filter(|t| t.success == Some(true))
+        .filter_map(|t| t.duration_us())
+        .sum();
+    assert_eq!(total, durations);
+}
+
+#[test]
+fn test_otlp_export_links_spans_to_callers() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 20, 10, 10);
+    h.connect(1, 2);
+    h.connect(2, 3);
+    assert!(h.sim.otlp_traces(0).is_none());
+    h.sim.enable_tracing(1, 5);
+    h.start();
+    h.run_for(1000);
+
+    let export = h.sim.otlp_traces(1_700_000_000_000_000_000).unwrap();
+    let resources = export["resourceSpans"].as_array().unwrap();
+    let service = |r: &serde_json::Value| {
+        r["resource"]["attributes"][0]["value"]["stringValue"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let spans_of = |name: &str| {
+        resources.iter().find(|r| service(r) == name).unwrap()["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(resources.len(), 3);
+    let (client, lb, server) = (spans_of("Client"), spans_of("LB"), spans_of("S1"));
+    assert_eq!(client.len(), 5);
+
+    let trace_id = &client[0]["traceId"];
+    assert_eq!(trace_id.as_str().unwrap().len(), 32);
+    let lb_span = lb.iter().find(|s| &s["traceId"] == trace_id).unwrap();
+    let server_span = server.iter().find(|s| &s["traceId"] == trace_id).unwrap();
+    assert!(client[0].get("parentSpanId").is_none());
+    assert_eq!(lb_span["parentSpanId"], client[0]["spanId"]);
+    assert_eq!(server_span["parentSpanId"], lb_span["spanId"]);
+    assert_eq!(server_span["status"]["code"], 1);
+
+    let start: u64 = client[0]["startTimeUnixNano"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(start >= 1_700_000_000_000_000_000);
+}
@@ -0,0 +1,74 @@
+use crate::common::TestHarness;
+use slay_core::{Diagnostic, LoadBalancer, Severity};
+
+#[test]
+fn test_valid_topology_has_no_diagnostics() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add(2, Box::new(LoadBalancer::new("LB")));
+    h.add_server(3, "S1", 10, 1, 10);
+    h.connect(1, 2);
+    h.connect(2, 3);
+
+    assert!(h.sim.validate().is_empty());
+}
+
+#[test]
+fn test_validate_reports_structural_problems() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 10.0);
+    h.add_client(2, 10.0);
+    h.add(3, Box::new(LoadBalancer::new("LB")));
+    h.add_server(4, "A", 10, 1, 10);
+    h.add_server(5, "B", 10, 1, 10);
+    h.add_server(6, "Orphan", 10, 1, 10);
+    h.connect(1, 4);
+    h.connect(4, 5);
+    h.connect(5, 4);
+
+    let diags = h.sim.validate();
+
+    assert!(diags.contains(&Diagnostic::ClientWithoutTarget { node_id: 2 }));
+    assert!(diags.contains(&Diagnostic::NoBackends { node_id: 3 }));
+    assert!(diags.contains(&Diagnostic::Cycle { nodes: vec![4, 5] }));
+    assert!(diags.contains(&Diagnostic::Unreachable { node_id: 3 }));
+    assert!(diags.contains(&Diagnostic::Unreachable { node_id: 6 }));
+    assert!(!diags.contains(&Diagnostic::Unreachable { node_id: 5 }));
+    assert_eq!(
+        diags
+            .iter()
+            .filter(|d| d.severity() == Severity::Error)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_long_routing_loop_is_found() {
+    let mut h = TestHarness::new();
+    let n = 50_000;
+    h.add_client(0, 10.0);
+    h.connect(0, 1);
+    for id in 1..=n {
+        h.add_server(id, "S", 10, 1, 10);
+        h.connect(id, id % n + 1);
+    }
+
+    let cycles: Vec<Diagnostic> = h
+        .sim
+        .validate()
+        .into_iter()
+        .filter(|d| matches!(d, Diagnostic::Cycle { .. }))
+        .collect();
+
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].nodes(), (1..=n).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_routing_loop_message_lists_nodes() {
+    let diag = Diagnostic::Cycle {
+        nodes: vec![2, 3, 5],
+    };
+    assert_eq!(diag.message(), "Nodes in a routing loop: #2, #3, #5");
+}
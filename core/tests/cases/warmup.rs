@@ -0,0 +1,62 @@
+use crate::common::TestHarness;
+use slay_core::Link;
+
+#[test]
+fn test_warmup_excludes_initial_requests() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 10);
+    h.connect(1, 2);
+    h.sim.warmup_us = 2_000_000;
+
+    h.start();
+    h.run_for(1500);
+    assert!(h.sim.in_warmup());
+    assert_eq!(h.sim.success_count, 0);
+    assert!(h.sim.latencies.is_empty());
+    assert!(h.sim.get_percentile(99.0, 10_000_000).is_none());
+
+    h.run_for(2500);
+    assert!(!h.sim.in_warmup());
+    assert!(h.sim.success_count > 0);
+    // Only requests started after the 2s mark count
+    assert!(h.sim.success_count <= 2 * 50 + 5);
+}
+
+#[test]
+fn test_reset_stats_restarts_warmup() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 10);
+    h.connect(1, 2);
+    h.sim.warmup_us = 1_000_000;
+
+    h.start();
+    h.run_for(3000);
+    assert!(h.sim.success_count > 0);
+
+    h.sim.reset_stats();
+    assert!(h.sim.in_warmup());
+    h.run_for(500);
+    assert_eq!(h.sim.success_count, 0);
+    assert_eq!(h.sim.failure_count, 0);
+}
+
+#[test]
+fn test_warmup_excludes_packet_loss() {
+    let mut h = TestHarness::new();
+    h.add_client(1, 50.0);
+    h.add_server(2, "S1", 10, 4, 10);
+    let mut link = Link::default();
+    link.get_config_mut(1, 2).packet_loss_rate = 0.5;
+    h.connect_with_link(1, 2, link);
+    h.sim.warmup_us = 2_000_000;
+
+    h.start();
+    h.run_for(1900);
+    assert!(h.sim.in_warmup());
+    assert_eq!(h.sim.failure_count, 0);
+
+    h.run_for(2000);
+    assert!(h.sim.failure_count > 0);
+}
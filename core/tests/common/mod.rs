@@ -1,6 +1,17 @@
+// Shared by several test binaries; not every binary uses every helper.
+#![allow(dead_code)]
+
 use slay_core::*;
 use std::sync::{Arc, RwLock};
 
+/// A simulation assembled node by node, by id.
+///
+/// New tests that only need a topology to run should use the `Topology`
+/// builder (and `from_topology` where harness helpers are wanted). The
+/// harness stays id-based for tests that reach into live components: the
+/// handles share the configs of the running nodes, `add` takes components
+/// built by hand, and nodes come and go mid-run, none of which a built
+/// topology offers.
 pub struct TestHarness {
     pub sim: Simulation,
 }
@@ -26,6 +37,14 @@ impl TestHarness {
         }
     }
 
+    /// Builds a harness from a saved design. Client traffic is already
+    /// scheduled, so `start` must not be called afterwards.
+    pub fn from_topology(topology: &Topology, seed: u64) -> Self {
+        Self {
+            sim: Simulation::from_topology(topology, seed),
+        }
+    }
+
     pub fn add(&mut self, id: NodeId, component: Box<dyn Component>) {
         self.sim.add_component(id, component);
     }
@@ -77,21 +96,9 @@ impl TestHarness {
     }
 
     pub fn run_for(&mut self, duration_ms: u64) {
-        let duration_us = duration_ms * 1000;
-        let end_time = self.sim.time + duration_us;
-        while self.sim.time < end_time {
-            if !self.sim.step() {
-                if let Some(next_event) = self.sim.events.peek() {
-                    let next_time = next_event.0.time;
-                    if next_time < end_time {
-                        self.sim.time = next_time;
-                        continue;
-                    }
-                }
-                self.sim.time = end_time;
-                break;
-            }
-        }
+        self.sim.run_until(&RunCondition::Duration {
+            duration_us: duration_ms * 1000,
+        });
         self.sync_stats();
     }
 
@@ -0,0 +1,334 @@
+//! JavaScript bindings for the simulation core, for embedding a live
+//! simulation in a page and driving it from script instead of the egui
+//! canvas. Built with wasm-pack:
+//!
+//! ```text
+//! wasm-pack build js --target web --release
+//! ```
+//!
+//! ```js
+//! import init, { Simulation } from "./pkg/slay_js.js";
+//!
+//! await init();
+//! const sim = new Simulation(await (await fetch("design.json")).text(), 42);
+//! sim.on("request", (r) => plot(r.time_s, r.latency_ms));
+//! sim.runFor(10);
+//! console.log(sim.metrics().sla_percent);
+//! ```
+//!
+//! Results come back as plain objects; durations are in seconds and
+//! latencies in milliseconds unless a key says otherwise.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Function, JSON};
+use serde::Serialize;
+use slay_core::{
+    Event, EventType, FailureReason, NodeId, RunCondition, RunMetrics, Scenario, ScenarioAction,
+    ScenarioStep, Simulation as CoreSim, Topology,
+};
+use wasm_bindgen::prelude::*;
+
+/// Virtual time run between deliveries to subscribers, so a long
+/// `runFor` never buffers more than this much traffic.
+const DELIVERY_US: u64 = 100_000;
+/// Events kept per delivery; anything beyond is not delivered.
+const MAX_BUFFERED_EVENTS: usize = 200_000;
+
+fn us(seconds: f64) -> u64 {
+    (seconds * 1_000_000.0) as u64
+}
+
+fn ms(us: Option<u64>) -> Option<f64> {
+    us.map(|v| v as f64 / 1000.0)
+}
+
+/// `value` as a JavaScript object, by way of its JSON form.
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsError> {
+    let json = serde_json::to_string(value)?;
+    JSON::parse(&json).map_err(|_| JsError::new("result is not valid JSON"))
+}
+
+/// What a subscriber is told about.
+#[derive(Clone, Copy, PartialEq)]
+enum Topic {
+    /// A request finished back at its client
+    Request,
+    /// A scenario step fired
+    Step,
+    /// Any event the engine processed, as raw JSON
+    Event,
+}
+
+impl Topic {
+    fn parse(name: &str) -> Result<Self, JsError> {
+        match name {
+            "request" => Ok(Topic::Request),
+            "step" => Ok(Topic::Step),
+            "event" => Ok(Topic::Event),
+            _ => Err(JsError::new(&format!(
+                "unknown topic '{}'; expected 'request', 'step' or 'event'",
+                name
+            ))),
+        }
+    }
+}
+
+struct Subscription {
+    id: u32,
+    topic: Topic,
+    callback: Function,
+}
+
+/// A running simulation of a topology in the format saved by the UI.
+/// Time only moves when asked to.
+#[wasm_bindgen]
+pub struct Simulation {
+    sim: CoreSim,
+    topology: Topology,
+    subscriptions: Vec<Subscription>,
+    next_subscription: u32,
+}
+
+impl Simulation {
+    fn with_topology(topology: Topology, seed: Option<u32>, warmup_s: Option<f64>) -> Simulation {
+        let mut sim = CoreSim::from_topology(&topology, seed.unwrap_or(42).into());
+        sim.warmup_us = us(warmup_s.unwrap_or(0.0));
+        Simulation {
+            sim,
+            topology,
+            subscriptions: Vec::new(),
+            next_subscription: 1,
+        }
+    }
+
+    fn run_metrics(&self) -> RunMetrics {
+        let measured_us = self.sim.time.saturating_sub(self.sim.warmup_end());
+        RunMetrics::from_simulation(&self.sim, measured_us)
+    }
+
+    fn node_id(&self, node: &JsValue) -> Result<NodeId, JsError> {
+        if let Some(id) = node.as_f64() {
+            return Ok(id as NodeId);
+        }
+        let name = node
+            .as_string()
+            .ok_or_else(|| JsError::new("expected a node id or name"))?;
+        self.topology
+            .id(&name)
+            .ok_or_else(|| JsError::new(&format!("no single node named '{}'", name)))
+    }
+
+    fn subscribed(&self, topic: Topic) -> bool {
+        self.subscriptions.iter().any(|s| s.topic == topic)
+    }
+
+    fn publish(&self, topic: Topic, payload: &impl Serialize) -> Result<(), JsValue> {
+        if !self.subscribed(topic) {
+            return Ok(());
+        }
+        let value = to_js(payload)?;
+        for s in self.subscriptions.iter().filter(|s| s.topic == topic) {
+            s.callback.call1(&JsValue::NULL, &value)?;
+        }
+        Ok(())
+    }
+
+    /// Tells subscribers about the steps and events of the last stretch
+    /// of the run.
+    fn deliver(&self, steps: Vec<ScenarioStep>, events: Vec<Event>) -> Result<(), JsValue> {
+        for step in steps.iter().filter(|s| s.enabled) {
+            self.publish(
+                Topic::Step,
+                &serde_json::json!({
+                    "time_s": step.at_us as f64 / 1_000_000.0,
+                    "description": step.action.describe(),
+                    "action": step.action,
+                }),
+            )?;
+        }
+        let requests = self.subscribed(Topic::Request);
+        for event in &events {
+            if let Some(request) = self.completed_request(event).filter(|_| requests) {
+                self.publish(Topic::Request, &request)?;
+            }
+            self.publish(Topic::Event, event)?;
+        }
+        Ok(())
+    }
+
+    /// A request counted in the metrics, if `event` is one reaching its
+    /// client.
+    fn completed_request(&self, event: &Event) -> Option<serde_json::Value> {
+        let EventType::Response {
+            path,
+            start_time,
+            success,
+            failure,
+            timeout,
+            ..
+        } = &event.event_type
+        else {
+            return None;
+        };
+        if path.len() != 1 || *start_time < self.sim.warmup_end() {
+            return None;
+        }
+        let latency_us = event.time.saturating_sub(*start_time);
+        let failure = if latency_us > *timeout {
+            Some(FailureReason::Timeout)
+        } else if !success {
+            Some(failure.unwrap_or(FailureReason::BackendError))
+        } else {
+            None
+        };
+        let client = self.sim.components.get(&event.node_id).map(|c| c.name());
+        Some(serde_json::json!({
+            "time_s": event.time as f64 / 1_000_000.0,
+            "client": client,
+            "success": failure.is_none(),
+            "failure": failure.map(FailureReason::key),
+            "latency_ms": latency_us as f64 / 1000.0,
+        }))
+    }
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    /// Loads a topology from its JSON and schedules its client traffic.
+    /// Requests started in the first `warmup_s` are left out of the metrics.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        topology_json: &str,
+        seed: Option<u32>,
+        warmup_s: Option<f64>,
+    ) -> Result<Simulation, JsError> {
+        let topology = Topology::from_json(topology_json)?;
+        Ok(Self::with_topology(topology, seed, warmup_s))
+    }
+
+    /// One of the designs the UI offers under "New from template".
+    #[wasm_bindgen(js_name = fromTemplate)]
+    pub fn from_template(
+        name: &str,
+        seed: Option<u32>,
+        warmup_s: Option<f64>,
+    ) -> Result<Simulation, JsError> {
+        let template = slay_core::template(name)
+            .ok_or_else(|| JsError::new(&format!("no template named '{}'", name)))?;
+        Ok(Self::with_topology(template.topology(), seed, warmup_s))
+    }
+
+    /// Virtual time, in seconds.
+    #[wasm_bindgen(getter)]
+    pub fn time(&self) -> f64 {
+        self.sim.time as f64 / 1_000_000.0
+    }
+
+    /// Runs for `seconds` of virtual time, calling subscribers as it goes.
+    /// An exception thrown by a subscriber stops the run and is rethrown.
+    #[wasm_bindgen(js_name = runFor)]
+    pub fn run_for(&mut self, seconds: f64) -> Result<(), JsValue> {
+        let end_us = self.sim.time + us(seconds);
+        let listening = self.subscribed(Topic::Request) || self.subscribed(Topic::Event);
+        while self.sim.time < end_us {
+            if listening {
+                self.sim.record_events(MAX_BUFFERED_EVENTS);
+            }
+            let cursor = self.sim.scenario_cursor;
+            let step = DELIVERY_US.min(end_us - self.sim.time);
+            self.sim
+                .run_until(&RunCondition::Duration { duration_us: step });
+            let steps = self.sim.scenario.steps[cursor..self.sim.scenario_cursor].to_vec();
+            let events = self.sim.event_log.take().unwrap_or_default().events;
+            self.deliver(steps, events)?;
+        }
+        Ok(())
+    }
+
+    /// Calls `callback` with every `topic` ("request", "step" or "event")
+    /// published from now on; returns an id for `off`.
+    pub fn on(&mut self, topic: &str, callback: Function) -> Result<u32, JsError> {
+        let topic = Topic::parse(topic)?;
+        let id = self.next_subscription;
+        self.next_subscription += 1;
+        self.subscriptions.push(Subscription {
+            id,
+            topic,
+            callback,
+        });
+        Ok(id)
+    }
+
+    pub fn off(&mut self, id: u32) {
+        self.subscriptions.retain(|s| s.id != id);
+    }
+
+    /// Headline metrics since the warm-up ended.
+    pub fn metrics(&self) -> Result<JsValue, JsError> {
+        to_js(&self.run_metrics())
+    }
+
+    /// `metrics()` as a JSON string.
+    #[wasm_bindgen(js_name = metricsJson)]
+    pub fn metrics_json(&self) -> String {
+        serde_json::to_string(&self.run_metrics()).unwrap_or_default()
+    }
+
+    /// Latency at percentile `p` over the last `window_s`, in milliseconds.
+    pub fn percentile(&self, p: f32, window_s: f64) -> Option<f64> {
+        ms(self.sim.get_percentile(p, us(window_s)))
+    }
+
+    /// Takes a node, by id or name, down.
+    pub fn kill(&mut self, node: JsValue) -> Result<(), JsError> {
+        let node_id = self.node_id(&node)?;
+        self.sim.apply_action(ScenarioAction::Kill { node_id });
+        Ok(())
+    }
+
+    /// Brings a node, by id or name, back up.
+    pub fn revive(&mut self, node: JsValue) -> Result<(), JsError> {
+        let node_id = self.node_id(&node)?;
+        self.sim.apply_action(ScenarioAction::Revive { node_id });
+        Ok(())
+    }
+
+    /// Replaces the scenario with one in the UI's JSON format.
+    #[wasm_bindgen(js_name = loadScenario)]
+    pub fn load_scenario(&mut self, json: &str) -> Result<(), JsError> {
+        self.sim.load_scenario(Scenario::from_json(json)?);
+        Ok(())
+    }
+
+    /// Per-component counters, one object per node in id order.
+    pub fn nodes(&self) -> Result<JsValue, JsError> {
+        let mut ids: Vec<NodeId> = self.sim.components.keys().copied().collect();
+        ids.sort_unstable();
+        let rows: Vec<serde_json::Value> = ids
+            .into_iter()
+            .map(|id| {
+                let comp = &self.sim.components[&id];
+                serde_json::json!({
+                    "id": id,
+                    "name": comp.name(),
+                    "kind": comp.kind(),
+                    "healthy": comp.is_healthy(),
+                    "received": comp.received_count(),
+                    "errors": comp.error_count(),
+                    "utilization": comp.utilization(),
+                    "queue_depth": comp.queue_depth(),
+                })
+            })
+            .collect();
+        to_js(&rows)
+    }
+}
+
+/// Names of the templates `Simulation.fromTemplate` accepts.
+#[wasm_bindgen(js_name = templateNames)]
+pub fn template_names() -> Vec<String> {
+    slay_core::TEMPLATES
+        .iter()
+        .map(|t| t.name.to_string())
+        .collect()
+}
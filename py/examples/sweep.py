@@ -0,0 +1,37 @@
+"""Sweep the offered load on a three-replica service and plot the curve.
+
+    cd py && maturin develop --release && python examples/sweep.py
+"""
+
+import matplotlib.pyplot as plt
+
+import slay
+
+topology = (
+    slay.Topology()
+    .client("web", 100.0, timeout=2000)
+    .lb("lb", strategy="LeastConnections")
+    .servers("api", 3, service_time=20, concurrency=8)
+    .connect("web", "lb")
+    .connect("lb", "api")
+)
+
+rates = list(range(100, 1501, 100))
+curve = slay.sweep(topology, rates, duration_s=20, warmup_s=2)
+points = curve["points"]
+offered = [p["offered_rps"] for p in points]
+served = [p["metrics"]["throughput_rps"] for p in points]
+p99 = [(p["metrics"]["p99_us"] or 0) / 1000 for p in points]
+
+fig, (top, bottom) = plt.subplots(2, 1, sharex=True)
+top.plot(offered, served, marker="o")
+top.set_ylabel("served rps")
+bottom.plot(offered, p99, marker="o", color="tab:red")
+bottom.set_ylabel("p99 ms")
+bottom.set_xlabel("offered rps")
+if curve["knee"] is not None:
+    knee = offered[curve["knee"]]
+    for ax in (top, bottom):
+        ax.axvline(knee, linestyle="--", color="grey")
+fig.suptitle("Throughput and latency vs. offered load")
+plt.show()
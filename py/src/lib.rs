@@ -0,0 +1,436 @@
+//! Python bindings for the simulation core, built with maturin:
+//!
+//! ```text
+//! cd py && maturin develop --release
+//! ```
+//!
+//! ```python
+//! import slay
+//!
+//! topology = (slay.Topology()
+//!     .client("web", 100.0)
+//!     .lb("lb", strategy="LeastConnections")
+//!     .servers("api", 3, service_time=20, concurrency=8)
+//!     .connect("web", "lb")
+//!     .connect("lb", "api"))
+//! curve = slay.sweep(topology, [50, 100, 200, 400], duration_s=20)
+//! ```
+//!
+//! Results come back as plain dicts and lists, ready for pandas or
+//! matplotlib; durations are in seconds and latencies in milliseconds
+//! unless a key says otherwise.
+
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use slay_core::components::client::ClientConfig;
+use slay_core::components::load_balancer::LoadBalancerConfig;
+use slay_core::components::server::ServerConfig;
+use slay_core::{
+    Experiment, NodeId, RunCondition, RunMetrics, Scenario, ScenarioAction, Simulation as CoreSim,
+    Topology as CoreTopology,
+};
+
+fn us(seconds: f64) -> u64 {
+    (seconds * 1_000_000.0) as u64
+}
+
+fn ms(us: Option<u64>) -> Option<f64> {
+    us.map(|v| v as f64 / 1000.0)
+}
+
+/// `value` as Python objects, by way of its JSON form.
+fn to_py<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// The defaults of `T` with the keyword arguments laid over them,
+/// checked by deserializing into `T`.
+fn config<T: Default + Serialize + DeserializeOwned>(
+    overrides: Option<&Bound<'_, PyDict>>,
+) -> PyResult<serde_json::Value> {
+    let mut merged = serde_json::to_value(T::default()).unwrap_or_default();
+    if let Some(overrides) = overrides {
+        let json: String = overrides
+            .py()
+            .import("json")?
+            .call_method1("dumps", (overrides,))?
+            .extract()?;
+        let patch: serde_json::Value =
+            serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        if let (Some(dst), Some(src)) = (merged.as_object_mut(), patch.as_object()) {
+            dst.extend(src.clone());
+        }
+    }
+    serde_json::from_value::<T>(merged.clone())
+        .map_err(|e| PyValueError::new_err(format!("invalid config: {}", e)))?;
+    Ok(merged)
+}
+
+fn scenario(json: Option<&str>) -> PyResult<Scenario> {
+    json.map_or(Ok(Scenario::default()), |json| {
+        Scenario::from_json(json)
+            .map_err(|e| PyValueError::new_err(format!("invalid scenario: {}", e)))
+    })
+}
+
+/// A design to simulate, built node by node or loaded from a file saved
+/// by the UI. Builder methods return the topology so calls chain.
+#[pyclass]
+#[derive(Clone, Default)]
+struct Topology {
+    inner: CoreTopology,
+}
+
+impl Topology {
+    fn edit(&mut self, f: impl FnOnce(CoreTopology) -> CoreTopology) {
+        self.inner = f(std::mem::take(&mut self.inner));
+    }
+
+    fn resolve(&self, name: &str) -> PyResult<Vec<NodeId>> {
+        let ids = self.inner.ids(name);
+        if ids.is_empty() {
+            return Err(PyKeyError::new_err(format!(
+                "no node or group named '{}'",
+                name
+            )));
+        }
+        Ok(ids)
+    }
+}
+
+#[pymethods]
+impl Topology {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let inner =
+            CoreTopology::from_json(json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let inner = CoreTopology::load(path).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    /// One of the designs the UI offers under "New from template".
+    #[staticmethod]
+    fn template(name: &str) -> PyResult<Self> {
+        let template = slay_core::template(name)
+            .ok_or_else(|| PyKeyError::new_err(format!("no template named '{}'", name)))?;
+        Ok(Self {
+            inner: template.topology(),
+        })
+    }
+
+    fn to_json(&self) -> String {
+        self.inner.to_json()
+    }
+
+    fn save(&self, path: &str) -> PyResult<()> {
+        self.inner
+            .save(path)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Adds a client sending `rps` requests per second; other
+    /// `ClientConfig` fields may be given as keywords.
+    #[pyo3(signature = (name, rps, **overrides))]
+    fn client<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: &str,
+        rps: f32,
+        overrides: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let mut config = config::<ClientConfig>(overrides)?;
+        config["arrival_rate"] = rps.into();
+        slf.edit(|t| t.node(name, "Client", &config));
+        Ok(slf)
+    }
+
+    /// Adds a load balancer; `LoadBalancerConfig` fields may be given as
+    /// keywords, e.g. `strategy="LeastConnections"`.
+    #[pyo3(signature = (name, **overrides))]
+    fn lb<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: &str,
+        overrides: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let config = config::<LoadBalancerConfig>(overrides)?;
+        slf.edit(|t| t.node(name, "LoadBalancer", &config));
+        Ok(slf)
+    }
+
+    /// Adds a server; `ServerConfig` fields may be given as keywords, e.g.
+    /// `service_time=20` (ms).
+    #[pyo3(signature = (name, **overrides))]
+    fn server<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: &str,
+        overrides: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let config = config::<ServerConfig>(overrides)?;
+        slf.edit(|t| t.node(name, "Server", &config));
+        Ok(slf)
+    }
+
+    /// Adds `count` identical servers named `name-1`, `name-2`, ...
+    #[pyo3(signature = (name, count, **overrides))]
+    fn servers<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        name: &str,
+        count: usize,
+        overrides: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let config = config::<ServerConfig>(overrides)?;
+        slf.edit(|mut t| {
+            for i in 1..=count {
+                t = t.node(&format!("{}-{}", name, i), "Server", &config);
+            }
+            t
+        });
+        Ok(slf)
+    }
+
+    /// Connects every node called `source` to every node called `target`;
+    /// a group's name stands for all of its members.
+    fn connect<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        source: &str,
+        target: &str,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        slf.resolve(source)?;
+        slf.resolve(target)?;
+        slf.edit(|t| t.connect(source, target));
+        Ok(slf)
+    }
+
+    /// Ids of the node or group called `name`.
+    fn ids(&self, name: &str) -> PyResult<Vec<NodeId>> {
+        self.resolve(name)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.nodes.len()
+    }
+}
+
+/// A running simulation of a topology. Time only moves when asked to.
+#[pyclass(unsendable)]
+struct Simulation {
+    sim: CoreSim,
+    topology: CoreTopology,
+}
+
+impl Simulation {
+    fn node_id(&self, node: &Bound<'_, PyAny>) -> PyResult<NodeId> {
+        if let Ok(id) = node.extract::<NodeId>() {
+            return Ok(id);
+        }
+        let name: String = node.extract()?;
+        self.topology
+            .id(&name)
+            .ok_or_else(|| PyKeyError::new_err(format!("no single node named '{}'", name)))
+    }
+}
+
+#[pymethods]
+impl Simulation {
+    #[new]
+    #[pyo3(signature = (topology, seed = 42, warmup_s = 0.0, scenario = None))]
+    fn new(
+        topology: &Topology,
+        seed: u64,
+        warmup_s: f64,
+        scenario: Option<&str>,
+    ) -> PyResult<Self> {
+        let mut sim = CoreSim::from_topology(&topology.inner, seed);
+        sim.warmup_us = us(warmup_s);
+        sim.load_scenario(self::scenario(scenario)?);
+        Ok(Self {
+            sim,
+            topology: topology.inner.clone(),
+        })
+    }
+
+    /// Virtual time, in seconds.
+    #[getter]
+    fn time(&self) -> f64 {
+        self.sim.time as f64 / 1_000_000.0
+    }
+
+    fn run_for(&mut self, seconds: f64) {
+        self.sim.run_until(&RunCondition::Duration {
+            duration_us: us(seconds),
+        });
+    }
+
+    /// Runs for `seconds`, returning one row per `every_s` with the
+    /// requests completed in it and the latency over it.
+    #[pyo3(signature = (seconds, every_s = 0.2))]
+    fn run_sampled<'py>(
+        &mut self,
+        py: Python<'py>,
+        seconds: f64,
+        every_s: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let every_us = us(every_s).max(1);
+        let end_us = self.sim.time + us(seconds);
+        let mut rows = Vec::new();
+        while self.sim.time < end_us {
+            let (successes, failures) = (self.sim.success_count, self.sim.failure_count);
+            let step = every_us.min(end_us - self.sim.time);
+            self.sim
+                .run_until(&RunCondition::Duration { duration_us: step });
+            let dt = step as f64 / 1_000_000.0;
+            let succeeded = self.sim.success_count.saturating_sub(successes);
+            let failed = self.sim.failure_count.saturating_sub(failures);
+            rows.push(serde_json::json!({
+                "time_s": self.time(),
+                "success_rps": succeeded as f64 / dt,
+                "failure_rps": failed as f64 / dt,
+                "p50_ms": ms(self.sim.get_percentile(50.0, step)),
+                "p99_ms": ms(self.sim.get_percentile(99.0, step)),
+            }));
+        }
+        to_py(py, &rows)
+    }
+
+    /// Headline metrics since the warm-up ended.
+    fn metrics<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let measured_us = self.sim.time.saturating_sub(self.sim.warmup_end());
+        to_py(py, &RunMetrics::from_simulation(&self.sim, measured_us))
+    }
+
+    /// Latency at percentile `p` over the last `window_s`, in milliseconds.
+    #[pyo3(signature = (p, window_s = 5.0))]
+    fn percentile(&self, p: f32, window_s: f64) -> Option<f64> {
+        ms(self.sim.get_percentile(p, us(window_s)))
+    }
+
+    /// Takes a node, by id or name, down.
+    fn kill(&mut self, node: &Bound<'_, PyAny>) -> PyResult<()> {
+        let node_id = self.node_id(node)?;
+        self.sim.apply_action(ScenarioAction::Kill { node_id });
+        Ok(())
+    }
+
+    /// Brings a node, by id or name, back up.
+    fn revive(&mut self, node: &Bound<'_, PyAny>) -> PyResult<()> {
+        let node_id = self.node_id(node)?;
+        self.sim.apply_action(ScenarioAction::Revive { node_id });
+        Ok(())
+    }
+
+    /// Replaces the scenario with one in the UI's JSON format.
+    fn load_scenario(&mut self, json: &str) -> PyResult<()> {
+        self.sim.load_scenario(scenario(Some(json))?);
+        Ok(())
+    }
+
+    /// Per-component counters, one row per node in id order.
+    fn nodes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut ids: Vec<NodeId> = self.sim.components.keys().copied().collect();
+        ids.sort_unstable();
+        let rows: Vec<serde_json::Value> = ids
+            .into_iter()
+            .map(|id| {
+                let comp = &self.sim.components[&id];
+                serde_json::json!({
+                    "id": id,
+                    "name": comp.name(),
+                    "kind": comp.kind(),
+                    "healthy": comp.is_healthy(),
+                    "received": comp.received_count(),
+                    "errors": comp.error_count(),
+                    "utilization": comp.utilization(),
+                    "queue_depth": comp.queue_depth(),
+                })
+            })
+            .collect();
+        to_py(py, &rows)
+    }
+}
+
+fn experiment(
+    seed: u64,
+    duration_s: f64,
+    warmup_s: f64,
+    scenario_json: Option<&str>,
+) -> PyResult<Experiment> {
+    Ok(Experiment::new(seed, us(duration_s))
+        .with_warmup(us(warmup_s))
+        .with_scenario(scenario(scenario_json)?))
+}
+
+/// Runs `topology` at each total client rate in `rates_rps`, every point
+/// until p99 converges or `duration_s` passes, and returns the curve with
+/// its knee.
+#[pyfunction]
+#[pyo3(signature = (topology, rates_rps, seed = 42, duration_s = 30.0, warmup_s = 0.0, scenario = None))]
+fn sweep<'py>(
+    py: Python<'py>,
+    topology: &Topology,
+    rates_rps: Vec<f64>,
+    seed: u64,
+    duration_s: f64,
+    warmup_s: f64,
+    scenario: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let experiment = experiment(seed, duration_s, warmup_s, scenario)?;
+    let curve = py.allow_threads(|| experiment.sweep_load(&topology.inner, rates_rps));
+    to_py(py, &curve)
+}
+
+/// Runs two designs under identical conditions.
+#[pyfunction]
+#[pyo3(signature = (baseline, variant, seed = 42, duration_s = 30.0, warmup_s = 0.0, scenario = None))]
+fn compare<'py>(
+    py: Python<'py>,
+    baseline: &Topology,
+    variant: &Topology,
+    seed: u64,
+    duration_s: f64,
+    warmup_s: f64,
+    scenario: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let experiment = experiment(seed, duration_s, warmup_s, scenario)?;
+    let comparison = py.allow_threads(|| experiment.compare(&baseline.inner, &variant.inner));
+    to_py(py, &comparison)
+}
+
+/// Scores each fault of `scenario` against an SLO of `slo_percent`.
+#[pyfunction]
+#[pyo3(signature = (topology, scenario, slo_percent = 99.9, seed = 42, duration_s = 60.0, warmup_s = 0.0))]
+fn chaos_report<'py>(
+    py: Python<'py>,
+    topology: &Topology,
+    scenario: &str,
+    slo_percent: f64,
+    seed: u64,
+    duration_s: f64,
+    warmup_s: f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let experiment = experiment(seed, duration_s, warmup_s, Some(scenario))?;
+    let report = py.allow_threads(|| experiment.chaos_report(&topology.inner, slo_percent));
+    to_py(py, &report)
+}
+
+#[pymodule]
+fn slay(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Topology>()?;
+    m.add_class::<Simulation>()?;
+    m.add_function(wrap_pyfunction!(sweep, m)?)?;
+    m.add_function(wrap_pyfunction!(compare, m)?)?;
+    m.add_function(wrap_pyfunction!(chaos_report, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use crate::app::SlayApp;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::NodeId;
+
+/// Spacing of the background grid and of grid snapping, in world units.
+pub const GRID_SIZE: f32 = 50.0;
+const NODE_SIZE: egui::Vec2 = egui::vec2(180.0, 90.0);
+/// How close, in screen pixels, an edge or centre must come to another
+/// node's to snap to it.
+const GUIDE_SNAP_PX: f32 = 8.0;
+
+/// A line another node lines up with, drawn while dragging.
+#[derive(Clone, Copy)]
+pub struct Guide {
+    /// Along the y axis at world x `at`, rather than along the x axis
+    pub vertical: bool,
+    pub at: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Alignment {
+    Left,
+    CenterX,
+    Right,
+    Top,
+    CenterY,
+    Bottom,
+    /// Equal gaps between the nodes' centres, first and last kept in place
+    DistributeX,
+    DistributeY,
+}
+
+impl Alignment {
+    pub const ALL: [Alignment; 8] = [
+        Alignment::Left,
+        Alignment::CenterX,
+        Alignment::Right,
+        Alignment::Top,
+        Alignment::CenterY,
+        Alignment::Bottom,
+        Alignment::DistributeX,
+        Alignment::DistributeY,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Alignment::Left => "Align left",
+            Alignment::CenterX => "Align centres vertically",
+            Alignment::Right => "Align right",
+            Alignment::Top => "Align top",
+            Alignment::CenterY => "Align middles horizontally",
+            Alignment::Bottom => "Align bottom",
+            Alignment::DistributeX => "Distribute horizontally",
+            Alignment::DistributeY => "Distribute vertically",
+        }
+    }
+}
+
+/// Where a node's left, centre and right (or top, middle and bottom) lie
+/// along one axis, relative to its position.
+fn anchors(size: f32) -> [f32; 3] {
+    [0.0, size / 2.0, size]
+}
+
+/// The shift that brings one of `leader`'s anchors onto the closest anchor
+/// of `others` within `threshold`, and the coordinate it lines up on.
+fn closest_anchor(
+    leader: f32,
+    others: impl Iterator<Item = f32>,
+    size: f32,
+    threshold: f32,
+) -> Option<(f32, f32)> {
+    let mut best: Option<(f32, f32)> = None;
+    for other in others {
+        for a in anchors(size) {
+            for b in anchors(size) {
+                let shift = (other + b) - (leader + a);
+                if shift.abs() <= threshold && best.is_none_or(|(s, _)| shift.abs() < s.abs()) {
+                    best = Some((shift, other + b));
+                }
+            }
+        }
+    }
+    best
+}
+
+impl SlayApp {
+    /// Where the node `leader` should go when dragged to `raw`: lined up
+    /// with a still node if one is close, else on the grid when snapping
+    /// is on. Holding Alt places it freely. Guides for the chosen lines are
+    /// kept for `draw_guides`.
+    pub fn snap_position(
+        &mut self,
+        leader: NodeId,
+        raw: egui::Pos2,
+        moving: &[NodeId],
+        free: bool,
+    ) -> egui::Pos2 {
+        self.guides.clear();
+        if free {
+            return raw;
+        }
+        let still: Vec<egui::Pos2> = self
+            .node_states
+            .iter()
+            .filter(|(id, _)| **id != leader && !moving.contains(id))
+            .filter(|(id, _)| self.collapsed_group_of(**id).is_none())
+            .map(|(_, s)| egui::pos2(s.pos.x, s.pos.y))
+            .collect();
+        let threshold = GUIDE_SNAP_PX / self.zoom;
+        let grid = |v: f32| (v / GRID_SIZE).round() * GRID_SIZE;
+
+        let mut pos = raw;
+        let x = self
+            .alignment_guides
+            .then(|| closest_anchor(raw.x, still.iter().map(|p| p.x), NODE_SIZE.x, threshold))
+            .flatten();
+        match x {
+            Some((shift, at)) => {
+                pos.x += shift;
+                self.guides.push(Guide { vertical: true, at });
+            }
+            None if self.snap_to_grid => pos.x = grid(raw.x),
+            None => {}
+        }
+        let y = self
+            .alignment_guides
+            .then(|| closest_anchor(raw.y, still.iter().map(|p| p.y), NODE_SIZE.y, threshold))
+            .flatten();
+        match y {
+            Some((shift, at)) => {
+                pos.y += shift;
+                self.guides.push(Guide {
+                    vertical: false,
+                    at,
+                });
+            }
+            None if self.snap_to_grid => pos.y = grid(raw.y),
+            None => {}
+        }
+        pos
+    }
+
+    /// Lines across the canvas for the alignments found while dragging.
+    pub fn draw_guides(&self, ui: &egui::Ui, canvas: egui::Rect) {
+        let stroke = egui::Stroke::new(1.0, theme().accent.gamma_multiply(0.7));
+        for guide in &self.guides {
+            let at = self.world_to_screen(egui::pos2(guide.at, guide.at));
+            let line = if guide.vertical {
+                [
+                    egui::pos2(at.x, canvas.top()),
+                    egui::pos2(at.x, canvas.bottom()),
+                ]
+            } else {
+                [
+                    egui::pos2(canvas.left(), at.y),
+                    egui::pos2(canvas.right(), at.y),
+                ]
+            };
+            ui.painter().line_segment(line, stroke);
+        }
+    }
+
+    /// Lines up or evenly spaces the selected nodes.
+    pub fn align_selection(&mut self, alignment: Alignment) {
+        let ids = self.selected_nodes();
+        let mut nodes: Vec<(NodeId, egui::Pos2)> = ids
+            .iter()
+            .filter_map(|id| {
+                let s = self.node_states.get(id)?;
+                Some((*id, egui::pos2(s.pos.x, s.pos.y)))
+            })
+            .collect();
+        if nodes.len() < 2 {
+            return;
+        }
+        let xs = || nodes.iter().map(|(_, p)| p.x);
+        let ys = || nodes.iter().map(|(_, p)| p.y);
+        let (min_x, max_x) = (xs().fold(f32::MAX, f32::min), xs().fold(f32::MIN, f32::max));
+        let (min_y, max_y) = (ys().fold(f32::MAX, f32::min), ys().fold(f32::MIN, f32::max));
+
+        let placed: Vec<(NodeId, egui::Pos2)> = match alignment {
+            Alignment::Left => set_x(&nodes, |_| min_x),
+            Alignment::Right => set_x(&nodes, |_| max_x),
+            Alignment::CenterX => set_x(&nodes, |_| (min_x + max_x) / 2.0),
+            Alignment::Top => set_y(&nodes, |_| min_y),
+            Alignment::Bottom => set_y(&nodes, |_| max_y),
+            Alignment::CenterY => set_y(&nodes, |_| (min_y + max_y) / 2.0),
+            Alignment::DistributeX => {
+                nodes.sort_by(|a, b| a.1.x.total_cmp(&b.1.x));
+                let step = (max_x - min_x) / (nodes.len() - 1) as f32;
+                set_x(&nodes, |i| min_x + step * i as f32)
+            }
+            Alignment::DistributeY => {
+                nodes.sort_by(|a, b| a.1.y.total_cmp(&b.1.y));
+                let step = (max_y - min_y) / (nodes.len() - 1) as f32;
+                set_y(&nodes, |i| min_y + step * i as f32)
+            }
+        };
+        for (id, pos) in placed {
+            if let Some(state) = self.node_states.get_mut(&id) {
+                state.pos.x = pos.x;
+                state.pos.y = pos.y;
+            }
+        }
+        self.wrap_groups();
+    }
+}
+
+fn set_x(nodes: &[(NodeId, egui::Pos2)], x: impl Fn(usize) -> f32) -> Vec<(NodeId, egui::Pos2)> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (id, p))| (*id, egui::pos2(x(i), p.y)))
+        .collect()
+}
+
+fn set_y(nodes: &[(NodeId, egui::Pos2)], y: impl Fn(usize) -> f32) -> Vec<(NodeId, egui::Pos2)> {
+    nodes
+        .iter()
+        .enumerate()
+        .map(|(i, (id, p))| (*id, egui::pos2(p.x, y(i))))
+        .collect()
+}
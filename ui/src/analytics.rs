@@ -1,21 +1,28 @@
-use crate::theme::*;
+use crate::theme::theme;
 use eframe::egui;
-use slay_core::MetricPoint;
+use slay_core::{Annotation, FailureReason, MetricPoint};
+use std::collections::BTreeMap;
 
-pub struct SparklineWidget<'a> {
+type Extractor<'a, T> = Box<dyn Fn(&T) -> f32 + 'a>;
+
+pub struct SparklineWidget<'a, T = MetricPoint> {
     label: String,
-    data: &'a [MetricPoint],
-    field_extractor: Box<dyn Fn(&MetricPoint) -> f32 + 'a>,
+    data: &'a [T],
+    field_extractor: Extractor<'a, T>,
     color: egui::Color32,
+    /// Additional series drawn as plain lines on the same scale
+    extra_lines: Vec<(Extractor<'a, T>, egui::Color32)>,
     current_value_text: String,
+    /// Annotations as `(position along the series 0..=1, text)`
+    markers: Vec<(f32, &'a str)>,
     size: egui::Vec2,
 }
 
-impl<'a> SparklineWidget<'a> {
+impl<'a, T> SparklineWidget<'a, T> {
     pub fn new(
         label: &str,
-        data: &'a [MetricPoint],
-        extractor: impl Fn(&MetricPoint) -> f32 + 'a,
+        data: &'a [T],
+        extractor: impl Fn(&T) -> f32 + 'a,
         color: egui::Color32,
         value_text: String,
     ) -> Self {
@@ -24,24 +31,61 @@ impl<'a> SparklineWidget<'a> {
             data,
             field_extractor: Box::new(extractor),
             color,
+            extra_lines: Vec::new(),
             current_value_text: value_text,
+            markers: Vec::new(),
             size: egui::vec2(180.0, 45.0),
         }
     }
+
+    pub fn with_line(mut self, extractor: impl Fn(&T) -> f32 + 'a, color: egui::Color32) -> Self {
+        self.extra_lines.push((Box::new(extractor), color));
+        self
+    }
+
+    /// Draws a vertical marker for each annotation within the series. Older
+    /// samples may cover several seconds each, so an annotation is placed on
+    /// the first sample at or after it.
+    pub fn with_markers(
+        mut self,
+        time_of: impl Fn(&T) -> u64,
+        annotations: impl IntoIterator<Item = &'a Annotation>,
+    ) -> Self {
+        let (Some(first), Some(last)) = (self.data.first(), self.data.last()) else {
+            return self;
+        };
+        // The first sample covers roughly as long as the gap to the second
+        let first_span = self
+            .data
+            .get(1)
+            .map_or(0, |second| time_of(second).saturating_sub(time_of(first)));
+        let (from, to) = (time_of(first).saturating_sub(first_span), time_of(last));
+        let steps = (self.data.len() - 1).max(1) as f32;
+        for a in annotations {
+            if a.sim_time_us < from || a.sim_time_us > to {
+                continue;
+            }
+            // Samples are plotted evenly, so place the marker by sample index
+            let i = self.data.partition_point(|p| time_of(p) < a.sim_time_us);
+            self.markers.push((i as f32 / steps, &a.text));
+        }
+        self
+    }
 }
 
-impl egui::Widget for SparklineWidget<'_> {
+impl<'a, T> egui::Widget for SparklineWidget<'a, T> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::hover());
+        // Clickable so callers can attach context menus
+        let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::click());
 
         if ui.is_rect_visible(rect) {
             let painter = ui.painter();
 
-            painter.rect_filled(rect, 2.0, egui::Color32::from_black_alpha(40));
+            painter.rect_filled(rect, 2.0, theme().chart_bg);
             painter.rect_stroke(
                 rect,
                 2.0,
-                egui::Stroke::new(1.0, egui::Color32::from_gray(60)),
+                egui::Stroke::new(1.0, theme().edge.gamma_multiply(0.6)),
             );
 
             // Text Area (Top 15 pixels reserved for text)
@@ -52,24 +96,37 @@ impl egui::Widget for SparklineWidget<'_> {
             );
 
             if self.data.len() >= 2 {
-                let values: Vec<f32> = self
-                    .data
+                let series =
+                    |f: &Extractor<'a, T>| -> Vec<f32> { self.data.iter().map(f).collect() };
+                let values = series(&self.field_extractor);
+                let extra: Vec<(Vec<f32>, egui::Color32)> = self
+                    .extra_lines
                     .iter()
-                    .map(|p| (self.field_extractor)(p))
+                    .map(|(f, color)| (series(f), *color))
                     .collect();
-                let max_val = values.iter().copied().fold(0.0, f32::max).max(0.001);
-
-                let points: Vec<egui::Pos2> = values
+                let max_val = extra
                     .iter()
-                    .enumerate()
-                    .map(|(i, &v)| {
-                        let x = graph_rect.left()
-                            + (i as f32 / (values.len() - 1) as f32) * graph_rect.width();
-                        let y =
-                            graph_rect.bottom() - (v / max_val) * (graph_rect.height() * 0.8) - 2.0;
-                        egui::pos2(x, y)
-                    })
-                    .collect();
+                    .flat_map(|(v, _)| v)
+                    .chain(&values)
+                    .copied()
+                    .fold(0.0, f32::max)
+                    .max(0.001);
+
+                let to_points = |values: &[f32]| -> Vec<egui::Pos2> {
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &v)| {
+                            let x = graph_rect.left()
+                                + (i as f32 / (values.len() - 1) as f32) * graph_rect.width();
+                            let y = graph_rect.bottom()
+                                - (v / max_val) * (graph_rect.height() * 0.8)
+                                - 2.0;
+                            egui::pos2(x, y)
+                        })
+                        .collect()
+                };
+                let points = to_points(&values);
 
                 // Area Fill
                 let mut shape_points = points.clone();
@@ -81,18 +138,48 @@ impl egui::Widget for SparklineWidget<'_> {
                     egui::Stroke::NONE,
                 ));
 
+                for (values, color) in &extra {
+                    painter.add(egui::Shape::line(
+                        to_points(values),
+                        egui::Stroke::new(1.0, *color),
+                    ));
+                }
+
                 // Line
                 painter.add(egui::Shape::line(
                     points,
                     egui::Stroke::new(1.5, self.color),
                 ));
+
+                let hover_x = response.hover_pos().map(|p| p.x);
+                let mut hovered = Vec::new();
+                for &(at, text) in &self.markers {
+                    let x = graph_rect.left() + at * graph_rect.width();
+                    painter.vline(
+                        x,
+                        graph_rect.y_range(),
+                        egui::Stroke::new(1.0, theme().warn.gamma_multiply(0.6)),
+                    );
+                    if hover_x.is_some_and(|hx| (hx - x).abs() <= 3.0) {
+                        hovered.push(text);
+                    }
+                }
+                if !hovered.is_empty() {
+                    painter.text(
+                        graph_rect.left_bottom() + egui::vec2(4.0, -2.0),
+                        egui::Align2::LEFT_BOTTOM,
+                        hovered.join(", "),
+                        egui::FontId::proportional(9.0),
+                        theme().warn,
+                    );
+                }
             } else {
                 painter.text(
                     graph_rect.center(),
                     egui::Align2::CENTER_CENTER,
                     "ANALYZING...",
                     egui::FontId::proportional(10.0),
-                    COLOR_TEXT_DIM,
+                    theme().text_dim,
                 );
             }
 
@@ -102,7 +189,7 @@ impl egui::Widget for SparklineWidget<'_> {
                 egui::Align2::LEFT_TOP,
                 &self.label,
                 egui::FontId::proportional(10.0),
-                COLOR_TEXT_DIM,
+                theme().text_dim,
             );
             painter.text(
                 rect.right_top() + egui::vec2(-8.0, 4.0),
@@ -116,3 +203,94 @@ impl egui::Widget for SparklineWidget<'_> {
         response
     }
 }
+
+pub fn failure_color(reason: FailureReason) -> egui::Color32 {
+    match reason {
+        FailureReason::Timeout => theme().warn,
+        FailureReason::BackendError => theme().critical,
+        FailureReason::QueueFull => egui::Color32::from_rgb(208, 135, 112),
+        FailureReason::PacketLoss => egui::Color32::from_rgb(129, 161, 193),
+        FailureReason::NoHealthyTargets => egui::Color32::from_rgb(180, 142, 173),
+        FailureReason::DeadlineExceeded => egui::Color32::from_rgb(94, 129, 172),
+        FailureReason::HopLimit => egui::Color32::from_rgb(143, 188, 187),
+        FailureReason::Unavailable => egui::Color32::from_gray(120),
+    }
+}
+
+/// Stacked bar of failure counts by reason, sized like a sparkline.
+pub struct FailureBreakdownWidget<'a> {
+    counts: &'a BTreeMap<FailureReason, u64>,
+    size: egui::Vec2,
+}
+
+impl<'a> FailureBreakdownWidget<'a> {
+    pub fn new(counts: &'a BTreeMap<FailureReason, u64>) -> Self {
+        Self {
+            counts,
+            size: egui::vec2(180.0, 45.0),
+        }
+    }
+}
+
+impl egui::Widget for FailureBreakdownWidget<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::hover());
+
+        if ui.is_rect_visible(rect) {
+            let painter = ui.painter();
+            painter.rect_filled(rect, 2.0, theme().chart_bg);
+            painter.rect_stroke(
+                rect,
+                2.0,
+                egui::Stroke::new(1.0, theme().edge.gamma_multiply(0.6)),
+            );
+
+            let total: u64 = self.counts.values().sum();
+            let bar = egui::Rect::from_min_max(
+                rect.left_top() + egui::vec2(8.0, 24.0),
+                rect.right_bottom() - egui::vec2(8.0, 8.0),
+            );
+            let top = self.counts.iter().max_by_key(|(_, &n)| n);
+
+            if total > 0 {
+                let mut x = bar.left();
+                for (&reason, &count) in self.counts {
+                    let w = bar.width() * count as f32 / total as f32;
+                    let segment = egui::Rect::from_min_max(
+                        egui::pos2(x, bar.top()),
+                        egui::pos2(x + w, bar.bottom()),
+                    );
+                    painter.rect_filled(segment, 0.0, failure_color(reason));
+                    x += w;
+                }
+            } else {
+                painter.text(
+                    bar.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "NO FAILURES",
+                    egui::FontId::proportional(10.0),
+                    theme().text_dim,
+                );
+            }
+
+            painter.text(
+                rect.left_top() + egui::vec2(8.0, 4.0),
+                egui::Align2::LEFT_TOP,
+                "FAILURES",
+                egui::FontId::proportional(10.0),
+                theme().text_dim,
+            );
+            if let Some((&reason, _)) = top {
+                painter.text(
+                    rect.right_top() + egui::vec2(-8.0, 4.0),
+                    egui::Align2::RIGHT_TOP,
+                    reason.label(),
+                    egui::FontId::proportional(13.0),
+                    failure_color(reason),
+                );
+            }
+        }
+
+        response
+    }
+}
@@ -1,11 +1,27 @@
-use crate::analytics::SparklineWidget;
-use crate::inspector::render_inspector;
+use crate::align::Guide;
+use crate::analytics::{FailureBreakdownWidget, SparklineWidget};
+use crate::canvas::FitTarget;
+use crate::clipboard::Clipboard;
+use crate::debug_panel::{render_debug_panel, DebugForm};
+use crate::driver::{Pace, SimDriver};
+use crate::explain::{explain_mode, set_explain_mode, EXPLAIN_KEY};
+use crate::groups::Group;
+use crate::heatmap::Heatmap;
+use crate::inspector::{render_bulk_inspector, render_inspector, ConfigEdit};
+use crate::layout::{show_docked, PanelLayout, LAYOUT_KEY};
 use crate::palette::render_palette;
-use crate::theme::*;
+use crate::replay::Recording;
+use crate::theme::{theme, Theme, THEME_KEY};
+use crate::timeline::{render_timeline, ChaosForm};
+use crate::traces::render_traces;
+use crate::workspaces::{Workspace, WORKSPACES_KEY};
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use slay_core::{create_component, Link, MetricsCollector, NodeId, Simulation};
-use std::collections::HashMap;
+use slay_core::{
+    create_component, percentile_label, Diagnostic, MetricsCollector, NodeId, RunOutcome, Scenario,
+    Simulation, Topology, STANDARD_PERCENTILES,
+};
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Vec2Serde {
@@ -21,6 +37,8 @@ pub struct NodeVisualState {
 
 pub struct SlayApp {
     pub simulation: Simulation,
+    /// Advances `simulation` between frames while running
+    pub driver: SimDriver,
     pub node_states: HashMap<NodeId, NodeVisualState>,
     pub next_node_id: NodeId,
     pub pan: egui::Vec2,
@@ -28,27 +46,118 @@ pub struct SlayApp {
     pub zoom: f32,
     pub target_zoom: f32,
     pub selected_node: Option<NodeId>,
+    /// Further nodes selected with Shift+click, for copying subgraphs
+    pub selection: BTreeSet<NodeId>,
+    /// Text of the node search box; matching nodes are highlighted
+    pub search: String,
+    pub clipboard: Option<Clipboard>,
+    /// Boxes clustering nodes on the canvas
+    pub groups: Vec<Group>,
     pub selected_edge: Option<(NodeId, NodeId)>,
     pub linking_from: Option<NodeId>,
+    /// Linking was started by clicking the output port, so it ends with a
+    /// click on the target rather than on release
+    pub click_linking: bool,
     pub drag_node_kind: Option<String>,
-    pub should_fit_to_view: bool,
+    /// Framing the camera animates to on the next frame
+    pub fit_request: Option<FitTarget>,
+    /// The selected node's config while the inspector edits it
+    pub config_edit: Option<ConfigEdit>,
+    /// Dragged nodes land on the grid
+    pub snap_to_grid: bool,
+    /// Dragged nodes line up with nearby nodes' edges and centres
+    pub alignment_guides: bool,
+    /// Where the dragged node would be without snapping
+    pub drag_anchor: Option<egui::Pos2>,
+    /// Lines the dragged node currently snaps to
+    pub guides: Vec<Guide>,
+    /// Live metric node colours show instead of their kind
+    pub heatmap: Heatmap,
     pub is_running: bool,
     pub sim_speed: f32,
+    /// Virtual time the simulation is fast-forwarding to, if any
+    pub fast_forward_to: Option<u64>,
+    /// Target entered in the "run to" control, in seconds
+    pub fast_forward_input_s: f32,
+    /// Virtual time a real-time run pauses at, if any
+    pub pause_at: Option<u64>,
+    /// Length entered in the "run for" control, in seconds
+    pub run_for_input_s: f32,
     pub stats_window_seconds: f32,
     pub ui_refresh_rate: f32,
+    /// What `Simulation::validate` last found; re-checked every
+    /// `ui_refresh_rate` seconds rather than every frame
+    pub diagnostics: Vec<Diagnostic>,
+    pub diagnostics_checked_at: f64,
     pub last_frame_time: f64,
     pub frames_since_start: u32,
     pub is_initialized: bool,
 
     pub metrics: MetricsCollector,
+
+    pub show_timeline: bool,
+    pub show_replay: bool,
+    /// The run since t=0, for scrubbing back through it
+    pub recording: Option<Recording>,
+    pub show_debugger: bool,
+    pub show_traces: bool,
+    pub selected_trace: Option<u128>,
+    pub debug_form: DebugForm,
+    /// Profile the engine, re-enabled whenever the simulation is replaced
+    pub profiling: bool,
+    pub chaos_form: ChaosForm,
+    pub scenario_text: String,
+    pub scenario_error: Option<String>,
+    /// Last macro recorded by hand, timed from its start
+    pub recorded_macro: Option<Scenario>,
+    /// Whether exported images carry the latest node and edge load
+    pub export_with_stats: bool,
+    /// Why the last attempt to open a `.slay` file failed
+    pub project_error: Option<String>,
+    /// Open designs; the active one's state is on the app itself
+    pub tabs: Vec<Workspace>,
+    pub active_tab: usize,
+    /// Where the palette, inspector and dashboard are docked
+    pub layout: PanelLayout,
+    #[cfg(target_arch = "wasm32")]
+    pub pending_open: crate::project::PendingOpen,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub metrics_server: Option<crate::metrics_server::MetricsServer>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub control_server: Option<crate::control_server::ControlServer>,
 }
 
 impl SlayApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         let mut app = Self::default();
+        cc.storage
+            .and_then(|storage| eframe::get_value::<Theme>(storage, THEME_KEY))
+            .unwrap_or_default()
+            .apply(&cc.egui_ctx);
+        set_explain_mode(
+            cc.storage
+                .and_then(|storage| eframe::get_value(storage, EXPLAIN_KEY))
+                .unwrap_or(false),
+        );
+        app.layout = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, LAYOUT_KEY))
+            .unwrap_or_default();
+        #[cfg(target_arch = "wasm32")]
+        match crate::share::state_from_url() {
+            Some(Ok(state)) => {
+                app.apply_state(state);
+                return app;
+            }
+            Some(Err(err)) => app.project_error = Some(err),
+            None => {}
+        }
         if let Some(storage) = cc.storage {
             if let Some(state) = eframe::get_value::<PersistedState>(storage, eframe::APP_KEY) {
                 app.apply_state(state);
+                if let Some(tabs) = eframe::get_value(storage, WORKSPACES_KEY) {
+                    app.restore_tabs(tabs);
+                }
             } else {
                 app.setup_default_topology();
             }
@@ -60,34 +169,23 @@ impl SlayApp {
 
     pub fn apply_state(&mut self, state: PersistedState) {
         self.node_states = state.visuals;
+        self.groups = state.groups;
         // Reset sync timestamps — persisted values are from a previous session
         // and would block sync until real time catches up (potentially hours).
         for state in self.node_states.values_mut() {
             state.last_sync_time = 0.0;
         }
-        self.next_node_id = state.next_id;
-        for (id, kind, config_json, targets) in state.nodes {
-            if let Some(mut comp) = create_component(&kind, config_json) {
-                for t in targets {
-                    comp.add_target(t);
-                }
-                let current_conf = comp.encode_config();
-                let cmds = comp.apply_config(current_conf, id);
-                for cmd in cmds {
-                    self.simulation.schedule(
-                        self.simulation.time + cmd.delay,
-                        cmd.node_id,
-                        cmd.event_type,
-                    );
-                }
-                self.simulation.add_component(id, comp);
-            }
-        }
+        self.next_node_id = state.next_id.max(state.topology.max_node_id() + 1);
+        self.simulation = Simulation::from_topology(&state.topology, self.simulation.seed);
+        self.config_edit = None;
+    }
 
-        if !state.links.is_empty() {
-            for ((min, max), link) in state.links {
-                self.simulation.links.insert((min, max), link);
-            }
+    pub fn persisted_state(&self) -> PersistedState {
+        PersistedState {
+            topology: self.simulation.to_topology(),
+            visuals: self.node_states.clone(),
+            next_id: self.next_node_id,
+            groups: self.groups.clone(),
         }
     }
 
@@ -110,20 +208,61 @@ impl SlayApp {
 
     pub fn reset(&mut self) {
         self.simulation = Simulation::new(42);
+        self.config_edit = None;
         self.node_states.clear();
+        self.groups.clear();
         self.next_node_id = 1;
         self.linking_from = None;
+        self.click_linking = false;
         self.selected_node = None;
+        self.selection.clear();
         self.selected_edge = None;
         self.is_running = false;
+        self.pause_at = None;
         self.drag_node_kind = None;
-        self.should_fit_to_view = true;
+        self.fit_request = Some(FitTarget::All);
         self.pan = egui::Vec2::ZERO; // Reset pan to zero, will be overridden by fit_to_view
         self.target_pan = egui::Vec2::ZERO;
         self.zoom = 1.0;
         self.target_zoom = 1.0;
         self.is_initialized = false;
         self.metrics.reset();
+        self.recording = None;
+    }
+
+    /// Processes the next event of a paused run.
+    pub fn step_event(&mut self) {
+        self.is_running = false;
+        self.simulation.step_checked();
+        self.sample_metrics();
+    }
+
+    /// Moves a paused run `duration_us` of virtual time ahead at once.
+    pub fn step_by(&mut self, duration_us: u64) {
+        self.is_running = false;
+        if let RunOutcome::Breakpoint(_) = self.simulation.advance_by(duration_us) {
+            self.show_debugger = true;
+        }
+        self.sample_metrics();
+    }
+
+    /// Runs in real time for `seconds` of virtual time, then pauses.
+    pub fn run_for(&mut self, seconds: f32) {
+        self.pause_at = Some(self.simulation.time + (seconds as f64 * 1_000_000.0) as u64);
+        self.fast_forward_to = None;
+        self.is_running = true;
+    }
+
+    /// Takes a metrics sample over the chosen stats window.
+    fn sample_metrics(&mut self) {
+        let w_us = (self.stats_window_seconds * 1_000_000.0) as u64;
+        self.metrics.update(&self.simulation, w_us);
+    }
+
+    pub fn toggle_running(&mut self) {
+        self.is_running = !self.is_running;
+        self.fast_forward_to = None;
+        self.pause_at = None;
     }
 
     pub fn spawn_node(&mut self, world_pos: egui::Pos2, kind: &str) {
@@ -161,6 +300,7 @@ impl Default for SlayApp {
     fn default() -> Self {
         Self {
             simulation: Simulation::new(42),
+            driver: SimDriver::default(),
             node_states: HashMap::new(),
             next_node_id: 1,
             pan: egui::Vec2::ZERO,
@@ -168,82 +308,115 @@ impl Default for SlayApp {
             zoom: 1.0,
             target_zoom: 1.0,
             selected_node: None,
+            selection: BTreeSet::new(),
+            search: String::new(),
+            clipboard: None,
+            groups: Vec::new(),
             selected_edge: None,
             linking_from: None,
+            click_linking: false,
             drag_node_kind: None,
-            should_fit_to_view: true,
+            fit_request: Some(FitTarget::All),
+            config_edit: None,
+            snap_to_grid: false,
+            alignment_guides: true,
+            drag_anchor: None,
+            guides: Vec::new(),
+            heatmap: Heatmap::Off,
             is_running: false,
             sim_speed: 1.0,
+            fast_forward_to: None,
+            fast_forward_input_s: 600.0,
+            pause_at: None,
+            run_for_input_s: 10.0,
             stats_window_seconds: 10.0,
             ui_refresh_rate: 0.2,
+            diagnostics: Vec::new(),
+            diagnostics_checked_at: 0.0,
             last_frame_time: 0.0,
             frames_since_start: 0,
             is_initialized: false,
             metrics: MetricsCollector::new(300),
+            show_timeline: false,
+            show_replay: false,
+            recording: None,
+            show_debugger: false,
+            show_traces: false,
+            selected_trace: None,
+            debug_form: DebugForm::default(),
+            profiling: false,
+            chaos_form: ChaosForm::default(),
+            scenario_text: Scenario::new("New scenario").to_json(),
+            scenario_error: None,
+            recorded_macro: None,
+            export_with_stats: false,
+            project_error: None,
+            tabs: vec![Workspace::new("Design 1".to_string())],
+            active_tab: 0,
+            layout: PanelLayout::default(),
+            #[cfg(target_arch = "wasm32")]
+            pending_open: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            metrics_server: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            control_server: None,
         }
     }
 }
 
+/// UI session state: the core `Topology` plus canvas-only data.
 #[derive(Serialize, Deserialize)]
 pub struct PersistedState {
-    nodes: Vec<(NodeId, String, serde_json::Value, Vec<NodeId>)>,
+    topology: Topology,
     visuals: HashMap<NodeId, NodeVisualState>,
     next_id: NodeId,
     #[serde(default)]
-    links: Vec<((NodeId, NodeId), Link)>,
+    groups: Vec<Group>,
 }
 
 impl eframe::App for SlayApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        let mut nodes = Vec::new();
-        for (id, comp) in &self.simulation.components {
-            nodes.push((
-                *id,
-                comp.kind().to_string(),
-                comp.encode_config(),
-                comp.get_targets(),
-            ));
+        // The simulation may be out on the worker; it is lent again next frame
+        let report = self
+            .driver
+            .reclaim(&mut self.simulation, self.last_frame_time);
+        if report.hit_breakpoint {
+            self.is_running = false;
+            self.show_debugger = true;
         }
-        let state = PersistedState {
-            nodes,
-            visuals: self.node_states.clone(),
-            next_id: self.next_node_id,
-
-            links: self
-                .simulation
-                .links
-                .iter()
-                .map(|(k, v)| (*k, v.clone()))
-                .collect(),
-        };
-        eframe::set_value(storage, eframe::APP_KEY, &state);
+        eframe::set_value(storage, eframe::APP_KEY, &self.persisted_state());
+        eframe::set_value(storage, WORKSPACES_KEY, &self.saved_tabs());
+        eframe::set_value(storage, THEME_KEY, &theme());
+        eframe::set_value(storage, EXPLAIN_KEY, &explain_mode());
+        eframe::set_value(storage, LAYOUT_KEY, &self.layout);
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         let current_real_time = ctx.input(|i| i.time);
-        let dt = (current_real_time - self.last_frame_time).max(0.0);
         self.last_frame_time = current_real_time;
 
+        let report = self.driver.reclaim(&mut self.simulation, current_real_time);
+        self.poll_open_project();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.poll_control();
+        if report.hit_breakpoint {
+            self.is_running = false;
+            self.fast_forward_to = None;
+            self.pause_at = None;
+            self.show_debugger = true;
+        }
+        if report.reached_target {
+            self.is_running = false;
+            self.fast_forward_to = None;
+            self.pause_at = None;
+        }
+
         if self.is_running {
-            let virtual_dt = (dt as f32 * self.sim_speed * 1_000_000.0) as u64;
-            let target_virtual_time = self.simulation.time + virtual_dt;
-            let mut processed = 0;
-            let max_events_per_frame = 10000;
-            while let Some(event) = self.simulation.events.peek() {
-                if event.0.time <= target_virtual_time && processed < max_events_per_frame {
-                    self.simulation.step();
-                    processed += 1;
-                } else {
-                    break;
-                }
+            self.sample_metrics();
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(server) = &mut self.metrics_server {
+                server.publish(&self.metrics, &self.simulation);
             }
-            if processed < max_events_per_frame {
-                self.simulation.time = target_virtual_time;
-            }
-
-            // Sync metrics window with UI slider
-            let w_us = (self.stats_window_seconds * 1_000_000.0) as u64;
-            self.metrics.update(&self.simulation, w_us);
             ctx.request_repaint();
         }
 
@@ -255,43 +428,110 @@ impl eframe::App for SlayApp {
                 }
             }
         }
+        if current_real_time - self.diagnostics_checked_at >= self.ui_refresh_rate as f64 {
+            self.diagnostics = self.simulation.validate();
+            self.diagnostics_checked_at = current_real_time;
+        }
 
-        egui::TopBottomPanel::bottom("bottom_dashboard")
-            .frame(egui::Frame::none().fill(COLOR_PANEL).inner_margin(12.0))
+        egui::TopBottomPanel::top("workspace_tabs")
+            .frame(egui::Frame::none().fill(theme().panel).inner_margin(4.0))
             .show(ctx, |ui| {
+                self.render_tabs(ui);
+            });
+
+        // Copied out so the panels can borrow the app
+        let mut dashboard = self.layout.dashboard;
+        show_docked(
+            ctx,
+            "Dashboard",
+            &mut dashboard,
+            Some(egui::Frame::none().fill(theme().panel).inner_margin(12.0)),
+            70.0,
+            |ui| {
                 ui.horizontal(|ui| {
                     let history = self.metrics.history.as_slices().0;
+                    let annotations = self.metrics.annotations();
 
                     // 1. LATENCY
                     let w_us = (self.stats_window_seconds * 1_000_000.0) as u64;
-                    let p99_opt = self.simulation.get_percentile(99.0, w_us);
-                    let p99_ms = p99_opt.map_or(0.0, |v| v as f32 / 1000.0);
+                    let percentiles = self.metrics.percentiles.clone();
+                    let mut chosen_percentiles = None;
+                    let primary = percentiles.last().copied().unwrap_or(99.0);
+                    let primary_opt = self.simulation.get_percentile(primary, w_us);
+                    let primary_ms = primary_opt.map_or(0.0, |v| v as f32 / 1000.0);
 
-                    let lat_label = if p99_opt.is_none() && self.simulation.failure_count > 0 {
+                    let lat_label = if primary_opt.is_none() && self.simulation.failure_count > 0 {
                         "TIMEOUT".to_string()
                     } else {
-                        format!("{:.1}ms", p99_ms)
+                        format!("{} {:.1}ms", percentile_label(primary), primary_ms)
                     };
 
-                    ui.add(SparklineWidget::new(
+                    // Highest percentile in front, lower ones progressively fainter
+                    let mut latency = SparklineWidget::new(
                         "LATENCY",
                         history,
-                        |m| m.p99_ms,
-                        COLOR_ACCENT,
+                        move |m| m.latency_ms(primary).unwrap_or(0.0),
+                        theme().accent,
                         lat_label,
-                    ));
+                    )
+                    .with_markers(|m| m.sim_time_us, annotations);
+                    let lower = &percentiles[..percentiles.len().saturating_sub(1)];
+                    for (i, &p) in lower.iter().enumerate() {
+                        let fade = 0.3 + 0.4 * i as f32 / lower.len() as f32;
+                        latency = latency.with_line(
+                            move |m| m.latency_ms(p).unwrap_or(0.0),
+                            theme().accent.gamma_multiply(fade),
+                        );
+                    }
+                    let hover = self
+                        .simulation
+                        .get_percentiles(&percentiles, w_us)
+                        .iter()
+                        .zip(&percentiles)
+                        .map(|(v, &p)| {
+                            let value = v
+                                .map_or("-".to_string(), |v| format!("{:.1}ms", v as f32 / 1000.0));
+                            format!("{}: {}", percentile_label(p), value)
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.add(latency)
+                        .on_hover_text(format!("{}\n\nRight-click to choose percentiles", hover))
+                        .context_menu(|ui| {
+                            for p in STANDARD_PERCENTILES {
+                                let mut shown = percentiles.contains(&p);
+                                let only = shown && percentiles.len() == 1;
+                                if ui
+                                    .add_enabled(
+                                        !only,
+                                        egui::Checkbox::new(&mut shown, percentile_label(p)),
+                                    )
+                                    .changed()
+                                {
+                                    let mut next = percentiles.clone();
+                                    next.retain(|&q| q != p);
+                                    if shown {
+                                        next.push(p);
+                                    }
+                                    chosen_percentiles = Some(next);
+                                }
+                            }
+                        });
 
                     ui.add_space(8.0);
 
                     // 2. SUCCESS
                     let cur_success = history.last().map(|m| m.success_rps).unwrap_or(0.0);
-                    ui.add(SparklineWidget::new(
-                        "SUCCESS",
-                        history,
-                        |m| m.success_rps,
-                        COLOR_SUCCESS,
-                        format!("{:.0} RPS", cur_success),
-                    ));
+                    ui.add(
+                        SparklineWidget::new(
+                            "SUCCESS",
+                            history,
+                            |m| m.success_rps,
+                            theme().success,
+                            format!("{:.0} RPS", cur_success),
+                        )
+                        .with_markers(|m| m.sim_time_us, annotations),
+                    );
 
                     ui.add_space(8.0);
 
@@ -303,17 +543,100 @@ impl eframe::App for SlayApp {
                     } else {
                         100.0
                     };
-                    ui.add(SparklineWidget::new(
-                        "ERRORS",
-                        history,
-                        |m| m.failure_rps,
-                        COLOR_CRITICAL,
-                        format!("{:.0} / {:.1}%", cur_fail, sla),
-                    ));
+                    ui.add(
+                        SparklineWidget::new(
+                            "ERRORS",
+                            history,
+                            |m| m.failure_rps,
+                            theme().critical,
+                            format!("{:.0} / {:.1}%", cur_fail, sla),
+                        )
+                        .with_markers(|m| m.sim_time_us, annotations),
+                    );
+
+                    ui.add_space(8.0);
+
+                    // 4. FAILURE REASONS
+                    let failures = &self.simulation.failure_reasons;
+                    let breakdown = failures
+                        .iter()
+                        .map(|(reason, count)| {
+                            format!(
+                                "{}: {} ({:.1}%)",
+                                reason.label(),
+                                count,
+                                *count as f64 * 100.0 / self.simulation.failure_count.max(1) as f64
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.add(FailureBreakdownWidget::new(failures)).on_hover_text(
+                        if breakdown.is_empty() {
+                            "No failures since RESET".to_string()
+                        } else {
+                            breakdown
+                        },
+                    );
 
-                    ui.add_space(ui.available_width() - 320.0);
+                    // 5. COST (only once something has a price)
+                    if self.simulation.costs.is_priced() {
+                        ui.add_space(8.0);
+                        ui.vertical(|ui| {
+                            let per_request = self
+                                .simulation
+                                .cost_per_success()
+                                .map_or("-".to_string(), |c| format!("${:.2}/M", c * 1_000_000.0));
+                            ui.label(
+                                egui::RichText::new(per_request)
+                                    .strong()
+                                    .color(theme().text),
+                            )
+                            .on_hover_text("Cost per million successful requests");
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "COST ${:.4}",
+                                    self.simulation.total_cost()
+                                ))
+                                .small()
+                                .color(theme().text_dim),
+                            );
+                        });
+                    }
+
+                    if let Some(next) = chosen_percentiles {
+                        self.metrics.set_percentiles(next);
+                    }
+
+                    ui.add_space((ui.available_width() - 360.0).max(0.0));
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .selectable_label(explain_mode(), "❓")
+                            .on_hover_text("Explain mode: hover a setting to learn what it does")
+                            .clicked()
+                        {
+                            set_explain_mode(!explain_mode());
+                        }
+                        if ui.selectable_label(self.show_debugger, "DEBUG").clicked() {
+                            self.show_debugger = !self.show_debugger;
+                        }
+                        if ui.selectable_label(self.show_timeline, "CHAOS").clicked() {
+                            self.show_timeline = !self.show_timeline;
+                        }
+                        if ui.selectable_label(self.show_replay, "REPLAY").clicked() {
+                            self.show_replay = !self.show_replay;
+                        }
+                        if ui
+                            .button("CSV")
+                            .on_hover_text("Export the metric history")
+                            .clicked()
+                        {
+                            crate::export::save_file(
+                                &format!("slay-metrics-{}s.csv", self.simulation.time / 1_000_000),
+                                "text/csv",
+                                &self.metrics.to_csv(),
+                            );
+                        }
                         if ui.button("RESET").clicked() {
                             self.simulation.reset_stats();
                             self.metrics.reset();
@@ -329,40 +652,144 @@ impl eframe::App for SlayApp {
                                     self.simulation.time as f32 / 1_000_000.0
                                 ))
                                 .strong()
-                                .color(COLOR_TEXT),
+                                .color(theme().text),
+                            );
+                            ui.label(
+                                egui::RichText::new("V-CLOCK")
+                                    .small()
+                                    .color(theme().text_dim),
                             );
-                            ui.label(egui::RichText::new("V-CLOCK").small().color(COLOR_TEXT_DIM));
                         });
                         ui.add_space(15.0);
                         ui.add(
                             egui::Slider::new(&mut self.stats_window_seconds, 1.0..=60.0)
                                 .suffix("s wnd"),
                         );
+                        let mut warmup_s = self.simulation.warmup_us as f32 / 1_000_000.0;
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut warmup_s)
+                                    .range(0.0..=60.0)
+                                    .speed(0.1)
+                                    .suffix("s warm-up"),
+                            )
+                            .on_hover_text("Ignore requests started this long after RESET")
+                            .changed()
+                        {
+                            self.simulation.warmup_us = (warmup_s as f64 * 1_000_000.0) as u64;
+                        }
+                        if self.simulation.in_warmup() {
+                            ui.label(
+                                egui::RichText::new("WARMING UP")
+                                    .small()
+                                    .color(theme().warn),
+                            );
+                        }
+                        if self.simulation.is_shedding() {
+                            ui.label(
+                                egui::RichText::new("⚠ SHEDDING LOAD")
+                                    .small()
+                                    .color(theme().critical),
+                            )
+                            .on_hover_text(format!(
+                                "{} new requests dropped to keep under {} pending events",
+                                self.simulation.shed_requests, self.simulation.max_pending_events
+                            ));
+                        }
                     });
                 });
-            });
+            },
+        );
+        self.layout.dashboard = dashboard;
 
-        egui::SidePanel::left("palette")
-            .default_width(200.0)
-            .show(ctx, |ui| {
-                render_palette(ui, self);
-            });
-        egui::SidePanel::right("inspector")
-            .default_width(240.0)
-            .show(ctx, |ui| {
-                render_inspector(
-                    ui,
-                    &mut self.simulation,
-                    &mut self.selected_node,
-                    &mut self.selected_edge,
-                    &mut self.node_states,
-                    &mut self.should_fit_to_view,
-                );
+        if self.show_timeline {
+            egui::TopBottomPanel::bottom("scenario_timeline")
+                .frame(egui::Frame::none().fill(theme().panel).inner_margin(8.0))
+                .show(ctx, |ui| {
+                    render_timeline(ui, self);
+                });
+        }
+
+        if self.show_replay {
+            egui::TopBottomPanel::bottom("replay_scrubber")
+                .frame(egui::Frame::none().fill(theme().panel).inner_margin(8.0))
+                .show(ctx, |ui| {
+                    self.render_replay(ui);
+                });
+        }
+
+        if self.show_debugger {
+            let mut open = true;
+            egui::Window::new("Debugger")
+                .open(&mut open)
+                .default_width(320.0)
+                .show(ctx, |ui| {
+                    render_debug_panel(ui, self);
+                });
+            self.show_debugger = open;
+        }
+
+        if self.show_traces {
+            let mut open = true;
+            egui::Window::new("Traces")
+                .open(&mut open)
+                .default_width(360.0)
+                .show(ctx, |ui| {
+                    render_traces(ui, self);
+                });
+            self.show_traces = open;
+        }
+
+        let mut palette = self.layout.palette;
+        show_docked(ctx, "Palette", &mut palette, None, 200.0, |ui| {
+            render_palette(ui, self);
+        });
+        self.layout.palette = palette;
+        let mut inspector = self.layout.inspector;
+        show_docked(ctx, "Inspector", &mut inspector, None, 240.0, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let selected = self.selected_nodes();
+                if selected.len() > 1 {
+                    render_bulk_inspector(
+                        ui,
+                        &mut self.simulation,
+                        &mut self.metrics,
+                        &mut self.fit_request,
+                        &selected.into_iter().collect(),
+                        &mut self.config_edit,
+                    );
+                } else {
+                    render_inspector(
+                        ui,
+                        &mut self.simulation,
+                        &mut self.selected_node,
+                        &mut self.selected_edge,
+                        &mut self.node_states,
+                        &mut self.fit_request,
+                        &mut self.metrics,
+                        &mut self.config_edit,
+                    );
+                }
             });
+        });
+        self.layout.inspector = inspector;
         egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(COLOR_BG))
+            .frame(egui::Frame::none().fill(theme().bg))
             .show(ctx, |ui| {
                 self.render_canvas(ui, ctx);
             });
+
+        self.track_recording();
+        if self.is_running {
+            let pace = match self.fast_forward_to {
+                Some(target_us) => Pace::FastForward { target_us },
+                None => Pace::RealTime {
+                    speed: self.sim_speed,
+                    until_us: self.pause_at,
+                },
+            };
+            self.driver
+                .lend(&mut self.simulation, pace, current_real_time);
+        }
     }
 }
@@ -1,11 +1,58 @@
+use crate::align::{Alignment, GRID_SIZE};
 use crate::app::SlayApp;
-use crate::components::get_view;
-use crate::theme::*;
+use crate::components::{get_all_views, get_view};
+use crate::heatmap::heat_color;
+use crate::theme::theme;
 use eframe::egui;
-use slay_core::NodeId;
+use slay_core::{Diagnostic, NodeId, Severity};
+use std::collections::{BTreeMap, HashMap};
+
+/// Most in-flight messages drawn each way along one edge.
+const MAX_DOTS_PER_DIRECTION: usize = 40;
+
+/// Below this zoom nodes are drawn as plain boxes, without text, stats or
+/// badges, and edges without messages in flight. Nothing there is legible
+/// at that size, and skipping it keeps large canvases responsive.
+const DETAIL_ZOOM: f32 = 0.4;
+
+/// What a fit request frames.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FitTarget {
+    /// Every node and group
+    All,
+    /// The selected nodes only
+    Selection,
+}
+
+enum NodeMenuAction {
+    Duplicate,
+    Copy,
+    Group,
+    Rename(String),
+    ToggleHealth,
+    Align(Alignment),
+    Delete,
+}
+
+/// What a right-click on the canvas background was aimed at.
+#[derive(Clone, Copy)]
+enum CanvasMenu {
+    Edge(NodeId, NodeId),
+    /// Open canvas at this world position
+    Empty(egui::Pos2),
+}
+
+enum CanvasMenuAction {
+    Spawn(&'static str, egui::Pos2),
+    PasteAt(egui::Pos2),
+    AutoLayout,
+    EditEdge(NodeId, NodeId),
+    ReverseEdge(NodeId, NodeId),
+    DeleteEdge(NodeId, NodeId),
+}
 
 impl SlayApp {
-    fn world_to_screen(&self, pos: egui::Pos2) -> egui::Pos2 {
+    pub fn world_to_screen(&self, pos: egui::Pos2) -> egui::Pos2 {
         egui::pos2(
             pos.x * self.zoom + self.pan.x,
             pos.y * self.zoom + self.pan.y,
@@ -22,8 +69,9 @@ impl SlayApp {
     pub fn render_canvas(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
         let canvas_rect = ui.available_rect_before_wrap();
 
-        // 0. Handle Global Inputs (Delete)
-        self.handle_global_inputs(ctx);
+        // 0. Handle Global Inputs (Shortcuts, Copy, Paste)
+        self.handle_shortcuts(ctx, canvas_rect);
+        self.handle_clipboard_keys(ctx);
 
         // 1. Update Camera (Animation & Input)
         self.update_camera(ui, ctx, canvas_rect);
@@ -34,14 +82,27 @@ impl SlayApp {
         // 3. Draw Grid
         self.draw_grid(ui, canvas_rect);
 
+        // Right-clicks that miss every node and group land here
+        let background = ui.interact(
+            canvas_rect,
+            ui.id().with("canvas_background"),
+            egui::Sense::click(),
+        );
+
+        // 3.5 Draw Groups (underneath everything else)
+        self.draw_groups(ui);
+
         // 4. Draw Edges (Links)
-        self.draw_edges(ui, ctx, canvas_rect);
+        let hovered_edge = self.draw_edges(ui, ctx, canvas_rect);
 
         // 5. Draw Nodes
-        self.draw_nodes(ui, ctx);
+        self.draw_nodes(ui, ctx, canvas_rect);
+        self.canvas_context_menu(&background, hovered_edge);
+
+        self.draw_guides(ui, canvas_rect);
 
         // 6. Draw Interactive Linking
-        self.draw_linking_interaction(ui, ctx);
+        self.draw_linking_interaction(ui, ctx, &background);
 
         // 7. Loading Overlay
         if !self.is_initialized {
@@ -50,6 +111,9 @@ impl SlayApp {
 
         // 8. GitHub Link
         self.draw_github_link(ui, canvas_rect);
+
+        // 9. Node Search
+        self.draw_search(ui, canvas_rect);
     }
 
     fn update_camera(&mut self, ui: &egui::Ui, ctx: &egui::Context, _rect: egui::Rect) {
@@ -77,67 +141,78 @@ impl SlayApp {
         let scroll = ctx.input(|i| i.smooth_scroll_delta.y);
         if scroll != 0.0 {
             let mouse = ctx.input(|i| i.pointer.hover_pos().unwrap_or(egui::pos2(0., 0.)));
-            let old_zoom = self.target_zoom;
-            self.target_zoom *= (scroll * 0.001).exp();
-            self.target_zoom = self.target_zoom.clamp(0.1, 5.0);
-            let world_mouse = (mouse.to_vec2() - self.target_pan) / old_zoom;
-            self.target_pan = mouse.to_vec2() - world_mouse * self.target_zoom;
+            self.zoom_by((scroll * 0.001).exp(), mouse);
             ctx.request_repaint();
         }
     }
 
+    /// Scales the target zoom by `factor`, keeping the world point under
+    /// `screen_pos` in place.
+    pub fn zoom_by(&mut self, factor: f32, screen_pos: egui::Pos2) {
+        let old_zoom = self.target_zoom;
+        self.target_zoom = (self.target_zoom * factor).clamp(0.1, 5.0);
+        let world = (screen_pos.to_vec2() - self.target_pan) / old_zoom;
+        self.target_pan = screen_pos.to_vec2() - world * self.target_zoom;
+    }
+
+    /// World-space box around `ids` (as drawn, so hidden members count as
+    /// their collapsed group), plus every group box when `with_groups`.
+    pub fn world_bounds(&self, ids: &[NodeId], with_groups: bool) -> Option<egui::Rect> {
+        let nodes = ids
+            .iter()
+            .filter_map(|&id| self.node_anchor(id))
+            .map(|pos| egui::Rect::from_min_size(pos, egui::vec2(180.0, 90.0)));
+        let groups = self.groups.iter().filter(|_| with_groups).map(|g| g.rect());
+        nodes.chain(groups).reduce(|a, b| a.union(b))
+    }
+
     fn perform_auto_fit(&mut self, rect: egui::Rect) {
         self.frames_since_start += 1;
         let is_stabilized = self.frames_since_start > 5;
+        let Some(target) = self.fit_request else {
+            return;
+        };
+        // Keep the request until the canvas has a stable size
+        if !is_stabilized || rect.width() <= 10.0 || rect.height() <= 10.0 {
+            return;
+        }
+        self.fit_request = None;
 
-        if self.should_fit_to_view && is_stabilized && rect.width() > 10.0 && rect.height() > 10.0 {
-            if !self.node_states.is_empty() {
-                let mut min_x = f32::INFINITY;
-                let mut min_y = f32::INFINITY;
-                let mut max_x = f32::NEG_INFINITY;
-                let mut max_y = f32::NEG_INFINITY;
-
-                for s in self.node_states.values() {
-                    min_x = min_x.min(s.pos.x);
-                    min_y = min_y.min(s.pos.y);
-                    max_x = max_x.max(s.pos.x + 180.0);
-                    max_y = max_y.max(s.pos.y + 90.0);
-                }
-                let w = max_x - min_x;
-                let h = max_y - min_y;
-
-                if w > 0.0 && h > 0.0 {
-                    let padding = 100.0;
-                    let avail_w = (rect.width() - padding * 2.0).max(100.0);
-                    let avail_h = (rect.height() - padding * 2.0).max(100.0);
-                    let scale = (avail_w / w).min(avail_h / h).clamp(0.5, 1.0);
-
-                    self.target_zoom = scale;
-                    let content_cx = min_x + w / 2.0;
-                    let content_cy = min_y + h / 2.0;
-                    let screen_c = rect.center();
-
-                    self.target_pan.x = screen_c.x - content_cx * scale;
-                    self.target_pan.y = screen_c.y - content_cy * scale;
-
-                    self.is_initialized = true;
-                    log::info!(
-                        "Auto-Fit: Zoom={}, Pan={:?}",
-                        self.target_zoom,
-                        self.target_pan
-                    );
-                }
+        let (bounds, max_scale) = match target {
+            FitTarget::All => {
+                let ids: Vec<NodeId> = self.node_states.keys().copied().collect();
+                (self.world_bounds(&ids, true), 1.0)
             }
-            self.should_fit_to_view = false;
-        } else if self.should_fit_to_view && !is_stabilized {
-            // Keep requesting repaint until stabilized
-            // Note: external repaint request is handled by loop, or we can't do it easily here without ctx
-        }
+            // Allow zooming in on a single node
+            FitTarget::Selection => (self.world_bounds(&self.selected_nodes(), false), 2.0),
+        };
+        let Some(bounds) = bounds.filter(|b| b.width() > 0.0 && b.height() > 0.0) else {
+            return;
+        };
+
+        let padding = 100.0;
+        let avail_w = (rect.width() - padding * 2.0).max(100.0);
+        let avail_h = (rect.height() - padding * 2.0).max(100.0);
+        // Large canvases may zoom as far out as the wheel allows
+        let scale = (avail_w / bounds.width())
+            .min(avail_h / bounds.height())
+            .clamp(0.1, max_scale);
+
+        // The camera animates toward these in `update_camera`
+        self.target_zoom = scale;
+        self.target_pan = rect.center().to_vec2() - bounds.center().to_vec2() * scale;
+
+        self.is_initialized = true;
+        log::info!(
+            "Auto-Fit: Zoom={}, Pan={:?}",
+            self.target_zoom,
+            self.target_pan
+        );
     }
 
     fn draw_grid(&self, ui: &mut egui::Ui, rect: egui::Rect) {
-        let grid_sz = 50.0 * self.zoom;
-        let stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(35));
+        let grid_sz = GRID_SIZE * self.zoom;
+        let stroke = egui::Stroke::new(1.0, theme().grid);
 
         let start_x = ((rect.left() - self.pan.x) / grid_sz).floor() as i32;
         let end_x = ((rect.right() - self.pan.x) / grid_sz).ceil() as i32;
@@ -166,32 +241,58 @@ impl SlayApp {
 
     fn draw_loading_overlay(&self, ui: &mut egui::Ui, rect: egui::Rect) {
         ui.painter()
-            .rect_filled(rect, 0.0, egui::Color32::from_black_alpha(200));
+            .rect_filled(rect, 0.0, theme().bg.gamma_multiply(0.8));
         ui.centered_and_justified(|ui| {
             ui.add(egui::Spinner::new().size(32.0));
             ui.label(
                 egui::RichText::new(" Loading...")
                     .heading()
-                    .color(egui::Color32::WHITE),
+                    .color(theme().text),
             );
         });
     }
 
-    fn draw_edges(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, rect: egui::Rect) {
+    /// Draws every connection in view; returns the one under the pointer,
+    /// if any.
+    fn draw_edges(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        rect: egui::Rect,
+    ) -> Option<(NodeId, NodeId)> {
         let mouse_pos = ctx.input(|i| i.pointer.hover_pos().unwrap_or(egui::pos2(0., 0.)));
+        let mut hovered = None;
+
+        // How far along each message on the wire is, by the edge it
+        // travels, gathered in one pass rather than one per edge
+        let mut in_flight: HashMap<(NodeId, NodeId), Vec<f32>> = HashMap::new();
+        if self.zoom >= DETAIL_ZOOM {
+            let now = self.simulation.time;
+            for transit in self.simulation.in_transit() {
+                let dots = in_flight.entry((transit.from, transit.to)).or_default();
+                if dots.len() < MAX_DOTS_PER_DIRECTION {
+                    dots.push(transit.progress(now));
+                }
+            }
+        }
 
         for (id, comp) in &self.simulation.components {
-            let throughput = comp.display_throughput();
-            for target_id in comp.get_targets() {
-                if let (Some(source_state), Some(target_state)) =
-                    (self.node_states.get(id), self.node_states.get(&target_id))
+            // Servers calling their targets by chance label each edge
+            let shares = comp.downcast_ref::<slay_core::Server>().and_then(|server| {
+                let config = server.config.read().unwrap();
+                (!config.route_probabilities.is_empty())
+                    .then(|| config.route_shares(server.targets.len()))
+            });
+            for (index, target_id) in comp.get_targets().into_iter().enumerate() {
+                if let (Some(source), Some(target)) =
+                    (self.node_anchor(*id), self.node_anchor(target_id))
                 {
-                    let p1 = self.world_to_screen(egui::pos2(
-                        source_state.pos.x + 180.,
-                        source_state.pos.y + 45.,
-                    ));
-                    let p2 = self
-                        .world_to_screen(egui::pos2(target_state.pos.x, target_state.pos.y + 45.));
+                    // Both ends hidden in the same collapsed group
+                    if source == target && self.collapsed_group_of(*id).is_some() {
+                        continue;
+                    }
+                    let p1 = self.world_to_screen(source + egui::vec2(180., 45.));
+                    let p2 = self.world_to_screen(target + egui::vec2(0., 45.));
                     let cp_offset = (p2.x - p1.x).abs().max(40.0 * self.zoom) * 0.5;
                     let points = [
                         p1,
@@ -199,24 +300,47 @@ impl SlayApp {
                         p2 - egui::vec2(cp_offset, 0.),
                         p2,
                     ];
+                    // The curve stays within the box around its control points
+                    if !rect.intersects(egui::Rect::from_points(&points).expand(6.0 * self.zoom)) {
+                        continue;
+                    }
 
-                    let mut color = egui::Color32::from_gray(100);
-                    let mut width = 1.5 * self.zoom;
+                    let traffic = self
+                        .metrics
+                        .edge_history(*id, target_id)
+                        .and_then(|h| h.back().copied())
+                        .unwrap_or_default();
+                    let mut color = theme().edge;
+                    // Thicker with more live traffic
+                    let mut width = (1.5 + (traffic.rps / 50.0).min(3.0)) * self.zoom;
                     let edge_key = (*id, target_id);
                     let link_key = slay_core::canonical_key(*id, target_id);
 
-                    if let Some(link) = self.simulation.links.get(&link_key) {
-                        let conf = link.get_config(*id, target_id);
-                        if conf.packet_loss_rate > 0.0 {
-                            let t = conf.packet_loss_rate.min(1.0);
-                            let r = (100.0 + (155.0 * t)) as u8;
-                            let gb = (100.0 * (1.0 - t)) as u8;
-                            color = egui::Color32::from_rgb(r, gb, gb);
-                        }
+                    // Redder with more loss: observed drops once traffic flows,
+                    // otherwise the configured rate
+                    let loss = if traffic.rps > 0.0 {
+                        traffic.drop_rps / traffic.rps
+                    } else {
+                        self.simulation
+                            .links
+                            .get(&link_key)
+                            .map_or(0.0, |l| l.get_config(*id, target_id).packet_loss_rate)
+                    };
+                    if loss > 0.0 {
+                        color = color.lerp_to_gamma(theme().critical, loss.min(1.0));
+                    }
+
+                    // Retries outweighing first attempts pulse in warning colours
+                    if traffic.is_retry_storm() {
+                        let t = ctx.input(|i| i.time) as f32;
+                        let pulse = 0.5 + 0.5 * (t * 6.0).sin();
+                        color = theme().warn.lerp_to_gamma(theme().critical, pulse);
+                        width += 1.5 * pulse * self.zoom;
+                        ctx.request_repaint();
                     }
 
                     if self.selected_edge == Some(edge_key) {
-                        color = COLOR_ACCENT;
+                        color = theme().accent;
                         width = 3.0 * self.zoom;
                     }
 
@@ -226,11 +350,13 @@ impl SlayApp {
                         for i in 0..=20 {
                             let t = i as f32 / 20.0;
                             if self.sample_bezier(points, t).distance(mouse_pos) < hit_threshold {
+                                hovered = Some(edge_key);
                                 width = 3.0 * self.zoom;
                                 ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
                                 if ctx.input(|i| i.pointer.any_click()) {
                                     self.selected_edge = Some(edge_key);
                                     self.selected_node = None;
+                                    self.selection.clear();
                                 }
                                 break;
                             }
@@ -245,28 +371,53 @@ impl SlayApp {
                             stroke: egui::Stroke::new(width, color).into(),
                         }));
 
-                    // Traffic dots
-                    if throughput > 0.0 && comp.is_healthy() {
-                        let num_dots = (throughput / 5.0).clamp(1.0, 5.0) as i32;
-                        let time = ctx.input(|i| i.time);
-                        for i in 0..num_dots {
-                            let t = (time as f32 * 0.5 + (i as f32 / num_dots as f32)) % 1.0;
-                            ui.painter().circle_filled(
-                                self.sample_bezier(points, t),
-                                3.0 * self.zoom,
-                                egui::Color32::from_rgb(0, 255, 255),
-                            );
-                        }
+                    if self.zoom < DETAIL_ZOOM {
+                        continue;
+                    }
+                    // Requests in flight travel along the edge, responses
+                    // travel back along it
+                    let requests = in_flight
+                        .get(&(*id, target_id))
+                        .into_iter()
+                        .flatten()
+                        .map(|&t| (t, theme().traffic));
+                    let responses = in_flight
+                        .get(&(target_id, *id))
+                        .into_iter()
+                        .flatten()
+                        .map(|&t| (1.0 - t, theme().success));
+                    for (t, dot_color) in requests.chain(responses) {
+                        ui.painter().circle_filled(
+                            self.sample_bezier(points, t),
+                            3.0 * self.zoom,
+                            dot_color,
+                        );
+                    }
+                    if let Some(&share) = shares.as_ref().and_then(|s| s.get(index)) {
+                        let percent = share * 100.0;
+                        ui.painter().text(
+                            self.sample_bezier(points, 0.5) - egui::vec2(0., 6.0 * self.zoom),
+                            egui::Align2::CENTER_BOTTOM,
+                            if percent < 10.0 {
+                                format!("{:.1}%", percent)
+                            } else {
+                                format!("{:.0}%", percent)
+                            },
+                            egui::FontId::proportional(10.0 * self.zoom),
+                            theme().text_dim,
+                        );
                     }
                 }
             }
         }
+        hovered
     }
 
-    fn draw_nodes(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+    fn draw_nodes(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, canvas_rect: egui::Rect) {
         let mut node_ids: Vec<NodeId> = self.node_states.keys().cloned().collect();
         node_ids.sort();
         let mut pending_move = Vec::new();
+        let mut menu_action = None;
         let mouse_pos = ctx.input(|i| i.pointer.hover_pos().unwrap_or(egui::pos2(0., 0.)));
 
         // Node Spawning Ghost
@@ -276,23 +427,37 @@ impl SlayApp {
             ui.painter().rect_filled(
                 ghost_rect,
                 6.0 * self.zoom,
-                egui::Color32::from_rgba_unmultiplied(150, 150, 150, 80),
+                theme().text_dim.gamma_multiply(0.3),
             );
             ui.painter().rect_stroke(
                 ghost_rect,
                 6.0 * self.zoom,
-                egui::Stroke::new(1.5, egui::Color32::WHITE),
+                egui::Stroke::new(1.5, theme().text),
             );
             if ctx.input(|i| i.pointer.any_released()) {
-                let world_pos =
+                let mut world_pos =
                     self.screen_to_world(mouse_pos - egui::vec2(90.0, 45.0) * self.zoom);
+                if self.snap_to_grid {
+                    world_pos = (world_pos.to_vec2() / GRID_SIZE).round().to_pos2() * GRID_SIZE;
+                }
                 let k = kind.clone();
                 self.drag_node_kind = None;
                 self.spawn_node(world_pos, &k);
             }
         }
 
+        let matches = self.search_matches();
+        let mut issues: BTreeMap<NodeId, Vec<Diagnostic>> = BTreeMap::new();
+        for diagnostic in &self.diagnostics {
+            for node in diagnostic.nodes() {
+                issues.entry(node).or_default().push(diagnostic.clone());
+            }
+        }
+        let mut dropped = Vec::new();
         for id in node_ids {
+            if self.collapsed_group_of(id).is_some() {
+                continue;
+            }
             let comp = self.simulation.components.get(&id).unwrap(); // Safe unwrap due to keys source
             let visual = self.node_states.get(&id).unwrap();
             let screen_pos = self.world_to_screen(egui::pos2(visual.pos.x, visual.pos.y));
@@ -304,42 +469,116 @@ impl SlayApp {
             );
 
             let rect = egui::Rect::from_min_size(screen_pos, egui::vec2(180.0, 90.0) * self.zoom);
+            // Off-screen nodes are skipped, unless one is being dragged there
+            if !canvas_rect.intersects(drop_rect) && !ctx.is_being_dragged(egui::Id::new(id)) {
+                continue;
+            }
 
             let interact = ui.interact(rect, egui::Id::new(id), egui::Sense::click_and_drag());
-            if interact.clicked() {
-                self.selected_node = Some(id);
+            // A click that completes a click-to-connect link keeps the selection
+            let completes_link = self.click_linking && self.linking_from.is_some();
+            if interact.clicked() && !completes_link {
+                if ctx.input(|i| i.modifiers.shift) {
+                    // Shift+click adds or removes nodes from a multi-selection
+                    if let Some(primary) = self.selected_node {
+                        self.selection.insert(primary);
+                    }
+                    if self.selection.remove(&id) {
+                        self.selected_node = self.selection.iter().next().copied();
+                    } else {
+                        self.selection.insert(id);
+                        self.selected_node = Some(id);
+                    }
+                } else {
+                    self.selection.clear();
+                    self.selected_node = Some(id);
+                }
                 self.selected_edge = None;
             }
+            interact.context_menu(|ui| {
+                if ui.button("Duplicate").clicked() {
+                    menu_action = Some((id, NodeMenuAction::Duplicate));
+                    ui.close_menu();
+                }
+                if ui.button("Copy").clicked() {
+                    menu_action = Some((id, NodeMenuAction::Copy));
+                    ui.close_menu();
+                }
+                if ui.button("Group").clicked() {
+                    menu_action = Some((id, NodeMenuAction::Group));
+                    ui.close_menu();
+                }
+                if self.selected_nodes().len() > 1 && self.selected_nodes().contains(&id) {
+                    ui.menu_button("Align", |ui| {
+                        for alignment in Alignment::ALL {
+                            if ui.button(alignment.label()).clicked() {
+                                menu_action = Some((id, NodeMenuAction::Align(alignment)));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+                ui.separator();
+                let mut name = comp.name().to_string();
+                if ui.text_edit_singleline(&mut name).changed() {
+                    menu_action = Some((id, NodeMenuAction::Rename(name)));
+                }
+                let health = if comp.is_healthy() { "Kill" } else { "Revive" };
+                if ui.button(health).clicked() {
+                    menu_action = Some((id, NodeMenuAction::ToggleHealth));
+                    ui.close_menu();
+                }
+                if ui
+                    .button(egui::RichText::new("Delete").color(theme().critical))
+                    .clicked()
+                {
+                    menu_action = Some((id, NodeMenuAction::Delete));
+                    ui.close_menu();
+                }
+            });
             if interact.hovered() {
                 ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
             }
+            if interact.drag_started() {
+                self.drag_anchor = Some(egui::pos2(visual.pos.x, visual.pos.y));
+            }
             if interact.dragged() {
                 pending_move.push((id, interact.drag_delta() / self.zoom));
             }
+            if interact.drag_stopped() {
+                dropped.push(id);
+                self.drag_anchor = None;
+                self.guides.clear();
+            }
 
-            let base_col = if let Some(view) = get_view(comp.kind()) {
+            let heat = self.node_heat(id);
+            let base_col = if let Some(heat) = heat {
+                heat_color(heat)
+            } else if let Some(view) = get_view(comp.kind()) {
                 view.color()
             } else {
-                egui::Color32::GRAY
+                theme().custom
             };
             let is_healthy = comp.is_healthy();
-            let fill = if is_healthy {
-                base_col.gamma_multiply(0.1)
+            let fill = if !is_healthy {
+                theme().inactive
+            } else if heat.is_some() {
+                base_col.gamma_multiply(0.25)
             } else {
-                egui::Color32::from_gray(30)
+                base_col.gamma_multiply(0.1)
             };
 
-            let mut border = if self.selected_node == Some(id) {
-                COLOR_WARN
+            let mut border = if self.selected_node == Some(id) || self.selection.contains(&id) {
+                theme().warn
             } else if self.linking_from == Some(id) {
-                COLOR_ACCENT
-            } else if interact.hovered() {
+                theme().accent
+            } else if interact.hovered() || heat.is_some() {
                 base_col
             } else {
                 base_col.gamma_multiply(0.5)
             };
             if !is_healthy {
-                border = COLOR_CRITICAL;
+                border = theme().critical;
             }
 
             ui.painter().rect_filled(rect, 6.0 * self.zoom, fill);
@@ -349,17 +588,9 @@ impl SlayApp {
                 egui::Stroke::new(1.5 * self.zoom, border),
             );
 
-            ui.painter().text(
-                rect.left_top() + egui::vec2(10., 15.) * self.zoom,
-                egui::Align2::LEFT_TOP,
-                comp.name().to_uppercase(),
-                egui::FontId::proportional(11.0 * self.zoom),
-                COLOR_TEXT_DIM,
-            );
-
             if !is_healthy {
                 let m = 20.0 * self.zoom;
-                let s = egui::Stroke::new(2.0 * self.zoom, COLOR_CRITICAL.gamma_multiply(0.5));
+                let s = egui::Stroke::new(2.0 * self.zoom, theme().critical.gamma_multiply(0.5));
                 ui.painter().line_segment(
                     [
                         rect.left_top() + egui::vec2(m, m),
@@ -376,19 +607,103 @@ impl SlayApp {
                 );
             }
 
-            if let Some(view) = get_view(comp.kind()) {
-                view.render_canvas(ui, rect, &comp.get_visual_snapshot(), self.zoom);
-            }
-
-            let errs = comp.error_count();
-            if errs > 0 {
+            if self.zoom >= DETAIL_ZOOM {
                 ui.painter().text(
-                    rect.right_bottom() - egui::vec2(10., 10.) * self.zoom,
-                    egui::Align2::RIGHT_BOTTOM,
-                    format!("! {} ERR", errs),
+                    rect.left_top() + egui::vec2(10., 15.) * self.zoom,
+                    egui::Align2::LEFT_TOP,
+                    comp.name().to_uppercase(),
                     egui::FontId::proportional(11.0 * self.zoom),
-                    COLOR_CRITICAL,
+                    theme().text_dim,
                 );
+
+                if let Some(view) = get_view(comp.kind()) {
+                    view.render_canvas(ui, rect, &comp.get_visual_snapshot(), self.zoom);
+                }
+
+                if let Some(brownout) = self.simulation.brownout(id) {
+                    let mut label = format!("DEGRADED ×{:.1}", brownout.slowdown);
+                    if brownout.error_rate > 0.0 {
+                        label += &format!(" · {:.0}% err", brownout.error_rate * 100.0);
+                    }
+                    ui.painter().text(
+                        rect.left_bottom() + egui::vec2(10., -10.) * self.zoom,
+                        egui::Align2::LEFT_BOTTOM,
+                        label,
+                        egui::FontId::proportional(11.0 * self.zoom),
+                        theme().warn,
+                    );
+                }
+
+                let errs = comp.error_count();
+                if errs > 0 {
+                    ui.painter().text(
+                        rect.right_bottom() - egui::vec2(10., 10.) * self.zoom,
+                        egui::Align2::RIGHT_BOTTOM,
+                        format!("! {} ERR", errs),
+                        egui::FontId::proportional(11.0 * self.zoom),
+                        theme().critical,
+                    );
+                }
+
+                // Validation badge, with the problems on hover
+                if let Some(found) = issues.get(&id) {
+                    let color = match found.iter().map(Diagnostic::severity).max() {
+                        Some(Severity::Error) => theme().critical,
+                        _ => theme().warn,
+                    };
+                    let badge = egui::Rect::from_center_size(
+                        rect.right_top(),
+                        egui::vec2(16.0, 16.0) * self.zoom,
+                    );
+                    ui.painter()
+                        .circle_filled(badge.center(), 8.0 * self.zoom, color);
+                    ui.painter().text(
+                        badge.center(),
+                        egui::Align2::CENTER_CENTER,
+                        "!",
+                        egui::FontId::proportional(12.0 * self.zoom),
+                        theme().bg,
+                    );
+                    let messages: Vec<String> = found.iter().map(Diagnostic::message).collect();
+                    ui.interact(badge, egui::Id::new(("issues", id)), egui::Sense::hover())
+                        .on_hover_text(messages.join("\n"));
+                }
+
+                // Retry amplification badge, red in a retry storm
+                if let Some(amp) = self.metrics.retry_amplification(id) {
+                    let color = if amp.storm {
+                        theme().critical
+                    } else {
+                        theme().warn
+                    };
+                    let galley = ui.painter().layout_no_wrap(
+                        format!("×{:.1} amplification", amp.factor),
+                        egui::FontId::proportional(10.0 * self.zoom),
+                        theme().bg,
+                    );
+                    let badge = egui::Rect::from_center_size(
+                        rect.center_top(),
+                        galley.size() + egui::vec2(10.0, 4.0) * self.zoom,
+                    );
+                    ui.painter().rect_filled(badge, badge.height() / 2.0, color);
+                    ui.painter()
+                        .galley(badge.center() - galley.size() / 2.0, galley, theme().bg);
+                    ui.interact(
+                        badge,
+                        egui::Id::new(("amplification", id)),
+                        egui::Sense::hover(),
+                    )
+                    .on_hover_text(format!(
+                        "Sends {:.1} requests per first attempt; {:.1} retries/s.{}",
+                        amp.factor,
+                        amp.retry_rps,
+                        if amp.storm {
+                            "\nRetries outweigh first attempts: a retry storm."
+                        } else {
+                            ""
+                        }
+                    ));
+                }
             }
 
             // Ports
@@ -396,19 +711,48 @@ impl SlayApp {
             ui.painter().circle_filled(
                 screen_pos + egui::vec2(0., 45.) * self.zoom,
                 4.5 * self.zoom,
-                egui::Color32::from_gray(80),
+                theme().edge,
             );
             ui.painter().circle_filled(out_pos, 4.5 * self.zoom, border);
 
-            // Drag from output
+            // Search results stand out, everything else fades
+            match &matches {
+                Some(matches) if matches.contains(&id) => {
+                    ui.painter().rect_stroke(
+                        rect.expand(4.0 * self.zoom),
+                        8.0 * self.zoom,
+                        egui::Stroke::new(2.0 * self.zoom, theme().accent),
+                    );
+                }
+                Some(_) => {
+                    ui.painter().rect_filled(
+                        rect.expand(6.0 * self.zoom),
+                        6.0 * self.zoom,
+                        theme().bg.gamma_multiply(0.7),
+                    );
+                }
+                None => {}
+            }
+
+            // Drag from output, or click it and then click the target
             let out_rect =
                 egui::Rect::from_center_size(out_pos, egui::vec2(30.0, 30.0) * self.zoom);
-            let out_resp = ui.interact(out_rect, egui::Id::new(("out", id)), egui::Sense::drag());
+            let out_resp = ui.interact(
+                out_rect,
+                egui::Id::new(("out", id)),
+                egui::Sense::click_and_drag(),
+            );
             if out_resp.hovered() {
                 ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
             }
             if out_resp.drag_started() {
                 self.linking_from = Some(id);
+                self.click_linking = false;
+            }
+            if out_resp.clicked() {
+                let again = self.click_linking && self.linking_from == Some(id);
+                self.linking_from = (!again).then_some(id);
+                self.click_linking = !again;
             }
 
             if ui.rect_contains_pointer(drop_rect) && ctx.input(|i| i.pointer.any_released()) {
@@ -417,32 +761,108 @@ impl SlayApp {
                         if let Some(c) = self.simulation.components.get_mut(&src) {
                             c.add_target(id);
                         }
+                        self.linking_from = None;
+                        self.click_linking = false;
                     }
                 }
             }
         }
 
-        for (id, d) in pending_move {
-            if let Some(s) = self.node_states.get_mut(&id) {
-                s.pos.x += d.x;
-                s.pos.y += d.y;
+        if let Some((id, action)) = menu_action {
+            // The menu acts on the selection if it was opened on part of it
+            if !self.selected_nodes().contains(&id) {
+                self.selection.clear();
+                self.selected_node = Some(id);
+            }
+            match action {
+                NodeMenuAction::Duplicate => self.duplicate_selection(),
+                NodeMenuAction::Copy => self.copy_selection(),
+                NodeMenuAction::Group => self.group_selection(),
+                NodeMenuAction::Rename(name) => {
+                    if let Some(comp) = self.simulation.components.get_mut(&id) {
+                        comp.set_name(&name);
+                    }
+                }
+                NodeMenuAction::ToggleHealth => {
+                    crate::inspector::toggle_health(&mut self.simulation, &mut self.metrics, id)
+                }
+                NodeMenuAction::Align(alignment) => self.align_selection(alignment),
+                NodeMenuAction::Delete => {
+                    self.selected_edge = None;
+                    self.delete_selection();
+                }
             }
         }
-    }
 
-    fn draw_linking_interaction(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
-        if let Some(src) = self.linking_from {
-            if let Some(vis) = self.node_states.get(&src) {
-                let start = self.world_to_screen(egui::pos2(vis.pos.x + 180., vis.pos.y + 45.));
-                let end = ctx.input(|i| i.pointer.hover_pos().unwrap_or(egui::pos2(0., 0.)));
-                ui.painter().line_segment(
-                    [start, end],
-                    egui::Stroke::new(1.5 * self.zoom, COLOR_ACCENT),
-                );
+        let selected = self.selected_nodes();
+        let moved_with = |id: NodeId| {
+            // Dragging one node of a multi-selection moves all of it
+            if self.selection.contains(&id) {
+                selected.clone()
+            } else {
+                vec![id]
             }
-            if ctx.input(|i| i.pointer.any_released()) {
+        };
+        let dropped: Vec<NodeId> = dropped.into_iter().flat_map(moved_with).collect();
+        let pending_move: Vec<(NodeId, egui::Vec2, Vec<NodeId>)> = pending_move
+            .into_iter()
+            .map(|(id, d)| (id, d, moved_with(id)))
+            .collect();
+        let free = ctx.input(|i| i.modifiers.alt);
+        for (id, d, moved) in pending_move {
+            let Some(pos) = self
+                .node_states
+                .get(&id)
+                .map(|s| egui::pos2(s.pos.x, s.pos.y))
+            else {
+                continue;
+            };
+            // Snapping works from where the pointer alone would have put it
+            let raw = self.drag_anchor.unwrap_or(pos) + d;
+            self.drag_anchor = Some(raw);
+            let d = self.snap_position(id, raw, &moved, free) - pos;
+            for id in moved {
+                if let Some(s) = self.node_states.get_mut(&id) {
+                    s.pos.x += d.x;
+                    s.pos.y += d.y;
+                }
+            }
+        }
+        self.regroup(&dropped);
+    }
+
+    fn draw_linking_interaction(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        background: &egui::Response,
+    ) {
+        let Some(src) = self.linking_from else {
+            return;
+        };
+        // Touch screens have no hover, so follow the last touch
+        let end = ctx.input(|i| i.pointer.latest_pos().unwrap_or(egui::pos2(0., 0.)));
+        if let Some(vis) = self.node_states.get(&src) {
+            let start = self.world_to_screen(egui::pos2(vis.pos.x + 180., vis.pos.y + 45.));
+            ui.painter().line_segment(
+                [start, end],
+                egui::Stroke::new(1.5 * self.zoom, theme().accent),
+            );
+        }
+        if self.click_linking {
+            ui.painter().text(
+                end + egui::vec2(14.0, 14.0),
+                egui::Align2::LEFT_TOP,
+                "Click a target · Esc to cancel",
+                egui::FontId::proportional(11.0),
+                theme().text_dim,
+            );
+            if background.clicked() {
                 self.linking_from = None;
+                self.click_linking = false;
             }
+        } else if ctx.input(|i| i.pointer.any_released()) {
+            self.linking_from = None;
         }
     }
 
@@ -458,21 +878,138 @@ impl SlayApp {
         )
     }
 
-    fn handle_global_inputs(&mut self, ctx: &egui::Context) {
-        if ctx.input(|i| i.key_pressed(egui::Key::Backspace) || i.key_pressed(egui::Key::Delete)) {
-            if let Some((src, dst)) = self.selected_edge {
-                if let Some(comp) = self.simulation.components.get_mut(&src) {
-                    comp.remove_target(dst);
-                    self.simulation
-                        .links
-                        .remove(&slay_core::canonical_key(src, dst));
-                    self.selected_edge = None;
+    /// The menu for right-clicks on edges and empty canvas. What it offers
+    /// is decided when it opens, so it stays put while the pointer moves.
+    fn canvas_context_menu(
+        &mut self,
+        background: &egui::Response,
+        hovered_edge: Option<(NodeId, NodeId)>,
+    ) {
+        let memory_id = background.id.with("target");
+        if background.secondary_clicked() {
+            let target = match hovered_edge {
+                Some((from, to)) => CanvasMenu::Edge(from, to),
+                None => CanvasMenu::Empty(
+                    self.screen_to_world(background.interact_pointer_pos().unwrap_or_default()),
+                ),
+            };
+            background
+                .ctx
+                .data_mut(|d| d.insert_temp(memory_id, target));
+        }
+        let Some(target) = background.ctx.data(|d| d.get_temp::<CanvasMenu>(memory_id)) else {
+            return;
+        };
+
+        let mut action = None;
+        background.context_menu(|ui| match target {
+            CanvasMenu::Edge(from, to) => {
+                ui.label(egui::RichText::new(format!("#{} → #{}", from, to)).small());
+                if ui.button("Edit").clicked() {
+                    action = Some(CanvasMenuAction::EditEdge(from, to));
+                    ui.close_menu();
                 }
-            } else if let Some(id) = self.selected_node {
+                if ui.button("Swap direction").clicked() {
+                    action = Some(CanvasMenuAction::ReverseEdge(from, to));
+                    ui.close_menu();
+                }
+                if ui
+                    .button(egui::RichText::new("Delete").color(theme().critical))
+                    .clicked()
+                {
+                    action = Some(CanvasMenuAction::DeleteEdge(from, to));
+                    ui.close_menu();
+                }
+            }
+            CanvasMenu::Empty(pos) => {
+                ui.menu_button("Add component here", |ui| {
+                    for (kind, view) in get_all_views() {
+                        if ui.button(view.name()).clicked() {
+                            action = Some(CanvasMenuAction::Spawn(kind, pos));
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if ui
+                    .add_enabled(self.clipboard.is_some(), egui::Button::new("Paste here"))
+                    .clicked()
+                {
+                    action = Some(CanvasMenuAction::PasteAt(pos));
+                    ui.close_menu();
+                }
+                if ui.button("Auto-layout").clicked() {
+                    action = Some(CanvasMenuAction::AutoLayout);
+                    ui.close_menu();
+                }
+            }
+        });
+
+        match action {
+            Some(CanvasMenuAction::Spawn(kind, pos)) => self.spawn_node(pos, kind),
+            Some(CanvasMenuAction::PasteAt(pos)) => self.paste_at(pos),
+            Some(CanvasMenuAction::AutoLayout) => self.auto_layout(),
+            Some(CanvasMenuAction::EditEdge(from, to)) => {
+                self.selected_edge = Some((from, to));
+                self.selected_node = None;
+                self.selection.clear();
+            }
+            Some(CanvasMenuAction::ReverseEdge(from, to)) => self.reverse_edge(from, to),
+            Some(CanvasMenuAction::DeleteEdge(from, to)) => {
+                self.selected_edge = Some((from, to));
+                self.delete_selection();
+            }
+            None => {}
+        }
+    }
+
+    /// Makes `to` send to `from` instead, keeping each direction's link
+    /// settings with the direction requests travel in.
+    pub fn reverse_edge(&mut self, from: NodeId, to: NodeId) {
+        let already_back = self
+            .simulation
+            .components
+            .get(&to)
+            .is_some_and(|c| c.get_targets().contains(&from));
+        if already_back {
+            return;
+        }
+        if let Some(comp) = self.simulation.components.get_mut(&from) {
+            comp.remove_target(to);
+        }
+        if let Some(comp) = self.simulation.components.get_mut(&to) {
+            comp.add_target(from);
+        }
+        if let Some(link) = self
+            .simulation
+            .links
+            .get_mut(&slay_core::canonical_key(from, to))
+        {
+            std::mem::swap(&mut link.min_to_max, &mut link.max_to_min);
+        }
+        if self.selected_edge == Some((from, to)) {
+            self.selected_edge = Some((to, from));
+        }
+    }
+
+    /// Deletes the selected edge, or else every selected node.
+    pub fn delete_selection(&mut self) {
+        if let Some((src, dst)) = self.selected_edge {
+            if let Some(comp) = self.simulation.components.get_mut(&src) {
+                comp.remove_target(dst);
+                self.simulation
+                    .links
+                    .remove(&slay_core::canonical_key(src, dst));
+                self.selected_edge = None;
+            }
+        } else {
+            for id in self.selected_nodes() {
+                self.metrics
+                    .annotate(self.simulation.time, format!("Remove #{}", id));
                 self.simulation.remove_node(id);
                 self.node_states.remove(&id);
-                self.selected_node = None;
             }
+            self.selected_node = None;
+            self.selection.clear();
         }
     }
 
@@ -481,7 +1018,7 @@ impl SlayApp {
         // Calculate text size to position correctly
         let font_id = egui::FontId::proportional(14.0);
         let text_size = ui.fonts(|f| {
-            f.layout_no_wrap(link_text.to_string(), font_id.clone(), egui::Color32::WHITE)
+            f.layout_no_wrap(link_text.to_string(), font_id.clone(), theme().text)
                 .size()
         });
 
@@ -494,7 +1031,7 @@ impl SlayApp {
                 .link(
                     egui::RichText::new(link_text)
                         .font(font_id)
-                        .color(egui::Color32::from_gray(200)),
+                        .color(theme().text_dim),
                 )
                 .clicked()
             {
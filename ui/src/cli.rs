@@ -0,0 +1,523 @@
+//! Command-line options, and the headless mode that runs a saved topology
+//! without opening a window.
+//!
+//! ```text
+//! slay-ui [--metrics-addr HOST:PORT] [--control HOST:PORT] [--plugin PATH]...
+//! slay-ui --headless topology.json [--seed N] [--duration SECS]
+//!         [--warmup SECS] [--speed X] [--metrics-csv PATH]
+//!         [--metrics-addr HOST:PORT] [--traces-otlp PATH [--trace-every N]]
+//!         [--percentiles 50,90,99.9] [--scenario scenario.json]
+//!         [--control HOST:PORT] [--plugin PATH]... [--report PATH]
+//!         [--latencies rtt.csv [--zone NAME=ZONE]...] [--fluid RPS]
+//!         [--profile] [--max-pending N]
+//! slay-ui --headless topology.json --sweep FROM:TO:STEPS [--seed N]
+//!         [--duration SECS] [--warmup SECS]
+//! slay-ui --headless topology.json --scenario scenario.json
+//!         --chaos-report PATH [--slo PERCENT] [--seed N] [--duration SECS]
+//!         [--warmup SECS]
+//! ```
+//!
+//! The topology may also be a Graphviz diagram (`.dot` or `.gv`), imported
+//! with `Topology::from_dot`, or a YAML design, Kubernetes manifests or a
+//! docker-compose file (`.yaml` or `.yml`), read with `Design::from_yaml`,
+//! `Topology::from_kubernetes` or `Topology::from_compose`. A YAML design's
+//! scenario runs unless `--scenario` names another; `--scenario` takes a
+//! YAML design too.
+//!
+//! `--latencies` sets link latencies from measured round-trip times (see
+//! `slay_core::LatencyTable`) before any run, sweep or report; each
+//! `--zone` places the nodes called NAME in a zone of the table, e.g. a
+//! cloud region.
+//!
+//! `--fluid` models clients sending at least RPS requests a second as a
+//! flow updated every 10ms instead of one event per request (see
+//! `Simulation::enable_fluid`), for rates discrete events cannot keep up
+//! with.
+//!
+//! `--profile` prints where the engine spent its time once the run ends:
+//! events per second, pending events and the nodes whose events took
+//! longest to handle (see `slay_core::ProfileReport`).
+//!
+//! `--max-pending` caps the events waiting to be processed (a million by
+//! default); past it, clients' new requests are shed and counted instead
+//! of growing memory without bound.
+//!
+//! With `--sweep` the clients are run at each total rate in turn, every point
+//! until p99 converges or `--duration` passes, and the throughput/latency
+//! curve is printed as CSV.
+//!
+//! With `--control` commands sent over WebSocket (see `ControlCommand`) act
+//! on the run between samples; pair it with `--speed` so there is time to
+//! send them. Loading a topology restarts the run.
+//!
+//! `--plugin` loads a custom component compiled to WebAssembly (see
+//! `slay_core::load_plugin`) before anything else, so topologies can use its
+//! kind; repeat it for several.
+//!
+//! `--report` writes a summary of the whole run (settings, topology,
+//! scenario, results, failures and per-node counters; see
+//! `slay_core::RunReport`) as Markdown if PATH ends in `.md`, else JSON.
+//!
+//! With `--chaos-report` the scenario's faults are scored against the SLO
+//! and the report is written as Markdown if PATH ends in `.md`, else JSON.
+
+use crate::control_server::{ControlCommand, ControlServer};
+use crate::export::unix_time_ns;
+use crate::metrics_server::MetricsServer;
+use slay_core::{
+    linear_rates, Design, Experiment, FluidMode, LatencyTable, MetricsCollector, RunCondition,
+    RunMetrics, RunReport, Scenario, Simulation, Topology, DEFAULT_MAX_PENDING_EVENTS,
+};
+use std::time::{Duration, Instant};
+
+/// Virtual time between metric samples, matching the UI's sampling rate.
+const SAMPLE_EVERY_US: u64 = 200_000;
+const STATS_WINDOW_US: u64 = 5_000_000;
+/// Traces kept for `--traces-otlp`; the most recent ones win.
+const MAX_TRACES: usize = 10_000;
+
+pub struct Options {
+    /// Topology to run headless; the window opens when `None`
+    pub topology: Option<String>,
+    pub seed: u64,
+    pub duration_us: u64,
+    pub warmup_us: u64,
+    /// Real-time multiplier for headless runs; as fast as possible if `None`
+    pub speed: Option<f64>,
+    pub metrics_csv: Option<String>,
+    /// File to write the run's report to
+    pub report: Option<String>,
+    /// Address to serve Prometheus metrics on
+    pub metrics_addr: Option<String>,
+    /// Address to accept WebSocket control commands on
+    pub control_addr: Option<String>,
+    /// File to write sampled traces to as OTLP/JSON
+    pub traces_otlp: Option<String>,
+    /// Trace one out of every `trace_every` requests
+    pub trace_every: u64,
+    /// Offered loads to sweep instead of a single run
+    pub sweep: Option<Vec<f64>>,
+    /// Latency percentiles recorded in the metrics, if not the default
+    pub percentiles: Option<Vec<f32>>,
+    /// Scenario file run alongside the topology
+    pub scenario: Option<String>,
+    /// File to write a chaos report to instead of a single run's summary
+    pub chaos_report: Option<String>,
+    /// Target success rate of the chaos report, in percent
+    pub slo_percent: f64,
+    /// WebAssembly components to load
+    pub plugins: Vec<String>,
+    /// CSV of measured round-trip times to calibrate links with
+    pub latencies: Option<String>,
+    /// Zone of the nodes called by each name, for `latencies`
+    pub zones: Vec<(String, String)>,
+    /// Client rate from which traffic is modelled as a fluid
+    pub fluid_rps: Option<f64>,
+    /// Print a profile of the engine after the run
+    pub profile: bool,
+    /// Pending events beyond which generated requests are shed
+    pub max_pending: usize,
+}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter().skip(1);
+        let mut opts = Options {
+            topology: None,
+            seed: 42,
+            duration_us: 60_000_000,
+            warmup_us: 0,
+            speed: None,
+            metrics_csv: None,
+            report: None,
+            metrics_addr: None,
+            control_addr: None,
+            traces_otlp: None,
+            trace_every: 100,
+            sweep: None,
+            percentiles: None,
+            scenario: None,
+            chaos_report: None,
+            slo_percent: 99.9,
+            plugins: Vec::new(),
+            latencies: None,
+            zones: Vec::new(),
+            fluid_rps: None,
+            profile: false,
+            max_pending: DEFAULT_MAX_PENDING_EVENTS,
+        };
+        let seconds = |v: String| {
+            v.parse::<f64>()
+                .map(|s| (s * 1_000_000.0) as u64)
+                .map_err(|e| format!("invalid duration '{}': {}", v, e))
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--headless" => opts.topology = Some(value()?),
+                "--seed" => {
+                    let v = value()?;
+                    opts.seed = v
+                        .parse()
+                        .map_err(|e| format!("invalid seed '{}': {}", v, e))?;
+                }
+                "--duration" => opts.duration_us = seconds(value()?)?,
+                "--warmup" => opts.warmup_us = seconds(value()?)?,
+                "--speed" => {
+                    let v = value()?;
+                    let speed: f64 = v
+                        .parse()
+                        .map_err(|e| format!("invalid speed '{}': {}", v, e))?;
+                    opts.speed = Some(speed).filter(|s| *s > 0.0);
+                }
+                "--metrics-csv" => opts.metrics_csv = Some(value()?),
+                "--report" => opts.report = Some(value()?),
+                "--metrics-addr" => opts.metrics_addr = Some(value()?),
+                "--control" => opts.control_addr = Some(value()?),
+                "--traces-otlp" => opts.traces_otlp = Some(value()?),
+                "--trace-every" => {
+                    let v = value()?;
+                    opts.trace_every = v
+                        .parse()
+                        .map_err(|e| format!("invalid sampling rate '{}': {}", v, e))?;
+                }
+                "--sweep" => opts.sweep = Some(parse_sweep(&value()?)?),
+                "--scenario" => opts.scenario = Some(value()?),
+                "--chaos-report" => opts.chaos_report = Some(value()?),
+                "--plugin" => opts.plugins.push(value()?),
+                "--latencies" => opts.latencies = Some(value()?),
+                "--zone" => {
+                    let v = value()?;
+                    let (name, zone) = v
+                        .split_once('=')
+                        .ok_or(format!("invalid zone '{}', expected NAME=ZONE", v))?;
+                    opts.zones.push((name.to_string(), zone.to_string()));
+                }
+                "--fluid" => {
+                    let v = value()?;
+                    opts.fluid_rps = Some(
+                        v.parse()
+                            .ok()
+                            .filter(|rps: &f64| *rps > 0.0)
+                            .ok_or(format!("invalid fluid threshold '{}'", v))?,
+                    );
+                }
+                "--profile" => opts.profile = true,
+                "--max-pending" => {
+                    let v = value()?;
+                    opts.max_pending = v
+                        .parse()
+                        .ok()
+                        .filter(|&n: &usize| n > 0)
+                        .ok_or(format!("invalid event cap '{}'", v))?;
+                }
+                "--slo" => {
+                    let v = value()?;
+                    opts.slo_percent = v
+                        .trim_end_matches('%')
+                        .parse()
+                        .ok()
+                        .filter(|p| (0.0..=100.0).contains(p))
+                        .ok_or(format!("invalid SLO '{}'", v))?;
+                }
+                "--percentiles" => {
+                    let v = value()?;
+                    let ps = v
+                        .split(',')
+                        .map(|p| p.trim().trim_start_matches('p').parse::<f32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .ok()
+                        .filter(|ps| ps.iter().all(|p| (0.0..=100.0).contains(p)))
+                        .ok_or(format!("invalid percentiles '{}'", v))?;
+                    opts.percentiles = Some(ps);
+                }
+                other => return Err(format!("unknown argument '{}'", other)),
+            }
+        }
+        Ok(opts)
+    }
+
+    /// Registers the `--plugin` components.
+    pub fn load_plugins(&self) -> Result<(), String> {
+        for path in &self.plugins {
+            let wasm =
+                std::fs::read(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+            let kind = slay_core::load_plugin(&wasm)
+                .map_err(|e| format!("invalid plugin {}: {}", path, e))?;
+            log::info!("Loaded component '{}' from {}", kind, path);
+        }
+        Ok(())
+    }
+
+    /// `topology` with its links set from the `--latencies` table.
+    pub fn calibrate(&self, mut topology: Topology) -> Result<Topology, String> {
+        let Some(path) = &self.latencies else {
+            return Ok(topology);
+        };
+        let table =
+            LatencyTable::load(path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+        let table = self
+            .zones
+            .iter()
+            .fold(table, |table, (name, zone)| table.place(name, zone));
+        let calibration = table.calibrate(&mut topology);
+        log::info!("Calibrated {} links from {}", calibration.links.len(), path);
+        if !calibration.unmatched.is_empty() {
+            log::warn!(
+                "No node for {} in {}",
+                calibration.unmatched.join(", "),
+                path
+            );
+        }
+        Ok(topology)
+    }
+
+    pub fn start_metrics_server(&self) -> Result<Option<MetricsServer>, String> {
+        self.metrics_addr
+            .as_deref()
+            .map(|addr| {
+                MetricsServer::start(addr)
+                    .map_err(|e| format!("failed to listen on {}: {}", addr, e))
+            })
+            .transpose()
+    }
+
+    pub fn start_control_server(&self) -> Result<Option<ControlServer>, String> {
+        self.control_addr
+            .as_deref()
+            .map(|addr| {
+                ControlServer::start(addr)
+                    .map_err(|e| format!("failed to listen on {}: {}", addr, e))
+            })
+            .transpose()
+    }
+
+    /// The `--scenario` file (JSON, YAML, or the scenario of a YAML design), else
+    /// the scenario of the YAML design being run, else an empty scenario.
+    fn load_scenario(&self) -> Result<Scenario, String> {
+        let read = |path: &str| {
+            std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))
+        };
+        let from_design = |path: &str, text: &str| {
+            Design::from_yaml(text)
+                .map(|d| d.scenario)
+                .map_err(|e| format!("invalid scenario {}: {}", path, e))
+        };
+        if let Some(path) = &self.scenario {
+            let text = read(path)?;
+            if is_yaml(path) && crate::project::is_design(&text) {
+                return from_design(path, &text);
+            }
+            if is_yaml(path) {
+                return Scenario::from_yaml(&text)
+                    .map_err(|e| format!("invalid scenario {}: {}", path, e));
+            }
+            return Scenario::from_json(&text)
+                .map_err(|e| format!("invalid scenario {}: {}", path, e));
+        }
+        if let Some(path) = self.topology.as_deref().filter(|p| is_yaml(p)) {
+            let text = read(path)?;
+            if crate::project::is_design(&text) {
+                return from_design(path, &text);
+            }
+        }
+        Ok(Scenario::default())
+    }
+}
+
+fn is_yaml(path: &str) -> bool {
+    path.ends_with(".yaml") || path.ends_with(".yml")
+}
+
+/// `FROM:TO:STEPS`, e.g. `50:800:16`.
+fn parse_sweep(spec: &str) -> Result<Vec<f64>, String> {
+    let invalid = || format!("invalid sweep '{}', expected FROM:TO:STEPS", spec);
+    let parts: Vec<&str> = spec.split(':').collect();
+    let [from, to, steps] = parts[..] else {
+        return Err(invalid());
+    };
+    let from: f64 = from.parse().map_err(|_| invalid())?;
+    let to: f64 = to.parse().map_err(|_| invalid())?;
+    let steps: usize = steps.parse().map_err(|_| invalid())?;
+    Ok(linear_rates(from, to, steps))
+}
+
+/// Reads a saved topology, or imports a Graphviz diagram, Kubernetes
+/// manifests or a docker-compose file by their extension.
+fn load_topology(path: &str) -> Result<Topology, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("failed to load {}: {}", path, e);
+    let imported = [".dot", ".gv", ".yaml", ".yml"];
+    if imported.iter().any(|ext| path.ends_with(ext)) {
+        let text = std::fs::read_to_string(path).map_err(|e| fail(&e))?;
+        return crate::project::import_topology(&text).map_err(|e| fail(&e));
+    }
+    Topology::load(path).map_err(|e| fail(&e))
+}
+
+/// Runs `topology` to completion without a window.
+pub fn run_headless(topology: &str, opts: &Options) -> Result<(), String> {
+    let topology = opts.calibrate(load_topology(topology)?)?;
+    if let Some(rates) = &opts.sweep {
+        return run_sweep(&topology, rates, opts);
+    }
+    if let Some(path) = &opts.chaos_report {
+        return run_chaos_report(&topology, path, opts);
+    }
+    let mut server = opts.start_metrics_server()?;
+    let mut control = opts.start_control_server()?;
+    let mut sim = start_simulation(&topology, opts)?;
+    let end_us = opts.warmup_us + opts.duration_us;
+    let mut metrics = new_collector(end_us, opts);
+
+    let mut started = Instant::now();
+    while sim.time < end_us {
+        let step = SAMPLE_EVERY_US.min(end_us - sim.time);
+        sim.run_until(&RunCondition::Duration { duration_us: step });
+        metrics.update(&sim, STATS_WINDOW_US);
+        if let Some(server) = &mut server {
+            server.publish(&metrics, &sim);
+        }
+        if let Some(control) = &mut control {
+            control.poll(|command| match command {
+                ControlCommand::LoadTopology { topology } => {
+                    let topology = ControlCommand::parse_topology(topology)?;
+                    sim = start_simulation(&topology, opts)?;
+                    metrics = new_collector(end_us, opts);
+                    started = Instant::now();
+                    Ok(serde_json::json!({ "nodes": topology.nodes.len() }))
+                }
+                command => command.apply(&mut sim, &mut metrics),
+            });
+        }
+        if let Some(speed) = opts.speed {
+            let due = Duration::from_secs_f64(sim.time as f64 / 1_000_000.0 / speed);
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+    }
+
+    let result = RunMetrics::from_simulation(&sim, opts.duration_us);
+    println!(
+        "{} ok / {} failed, SLA {:.2}%, p50 {}, p99 {}, {:.1} rps",
+        result.success_count,
+        result.failure_count,
+        result.sla_percent,
+        format_ms(result.p50_us),
+        format_ms(result.p99_us),
+        result.throughput_rps
+    );
+    for (reason, count) in &sim.failure_reasons {
+        println!("  {:>6} {}", count, reason.label());
+    }
+    if sim.shed_requests > 0 {
+        eprintln!(
+            "warning: {} requests shed at {} pending events",
+            sim.shed_requests, sim.max_pending_events
+        );
+    }
+    if let Some(profile) = sim.profile() {
+        eprint!("{}", profile);
+    }
+
+    if let Some(path) = &opts.metrics_csv {
+        metrics
+            .save_csv(path)
+            .map_err(|e| format!("failed to write {}: {}", path, e))?;
+    }
+    if let Some(path) = &opts.report {
+        let report = RunReport::from_simulation(&sim, opts.duration_us);
+        let contents = if path.ends_with(".md") {
+            report.to_markdown()
+        } else {
+            report.to_json()
+        };
+        std::fs::write(path, contents).map_err(|e| format!("failed to write {}: {}", path, e))?;
+    }
+    if let Some(path) = &opts.traces_otlp {
+        // Place the run so that it ends now
+        let epoch_ns = unix_time_ns().saturating_sub(sim.time * 1000);
+        let otlp = sim.otlp_traces(epoch_ns).unwrap_or_default();
+        std::fs::write(path, otlp.to_string())
+            .map_err(|e| format!("failed to write {}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// A simulation of `topology` set up as the options ask.
+fn start_simulation(topology: &Topology, opts: &Options) -> Result<Simulation, String> {
+    let mut sim = Simulation::from_topology(topology, opts.seed);
+    sim.load_scenario(opts.load_scenario()?);
+    sim.warmup_us = opts.warmup_us;
+    sim.max_pending_events = opts.max_pending;
+    if opts.traces_otlp.is_some() {
+        sim.enable_tracing(opts.trace_every, MAX_TRACES);
+    }
+    if opts.profile {
+        sim.enable_profiling(unix_time_ns);
+    }
+    if let Some(threshold_rps) = opts.fluid_rps {
+        sim.enable_fluid(FluidMode {
+            threshold_rps,
+            ..FluidMode::default()
+        });
+    }
+    Ok(sim)
+}
+
+/// A collector keeping every sample of a run ending at `end_us`.
+fn new_collector(end_us: u64, opts: &Options) -> MetricsCollector {
+    let mut metrics = MetricsCollector::new((end_us / SAMPLE_EVERY_US) as usize + 1);
+    if let Some(ps) = &opts.percentiles {
+        metrics.set_percentiles(ps.iter().copied());
+    }
+    metrics
+}
+
+fn run_sweep(topology: &Topology, rates: &[f64], opts: &Options) -> Result<(), String> {
+    let curve = Experiment::new(opts.seed, opts.duration_us)
+        .with_warmup(opts.warmup_us)
+        .sweep_load(topology, rates.iter().copied());
+    print!("{}", curve.to_csv());
+    match curve.knee_point() {
+        Some(knee) => eprintln!(
+            "knee at {:.1} rps offered: {:.1} rps served, p99 {}",
+            knee.offered_rps,
+            knee.metrics.throughput_rps,
+            format_ms(knee.metrics.p99_us)
+        ),
+        None => eprintln!("no healthy point in the sweep"),
+    }
+    Ok(())
+}
+
+fn run_chaos_report(topology: &Topology, path: &str, opts: &Options) -> Result<(), String> {
+    let scenario = opts.load_scenario()?;
+    if scenario.steps.is_empty() {
+        return Err("--chaos-report needs a --scenario with faults to score".to_string());
+    }
+    let report = Experiment::new(opts.seed, opts.duration_us)
+        .with_warmup(opts.warmup_us)
+        .with_scenario(scenario)
+        .chaos_report(topology, opts.slo_percent);
+    let contents = if path.ends_with(".md") {
+        report.to_markdown()
+    } else {
+        report.to_json()
+    };
+    std::fs::write(path, contents).map_err(|e| format!("failed to write {}: {}", path, e))?;
+    eprintln!(
+        "{} fault{} scored, SLA {:.2}%, {:.1}% of the error budget used",
+        report.faults.len(),
+        if report.faults.len() == 1 { "" } else { "s" },
+        report.overall.sla_percent,
+        report.budget_consumed_percent
+    );
+    Ok(())
+}
+
+fn format_ms(us: Option<u64>) -> String {
+    us.map_or("n/a".to_string(), |us| {
+        format!("{:.1}ms", us as f64 / 1000.0)
+    })
+}
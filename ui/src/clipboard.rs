@@ -0,0 +1,138 @@
+use crate::app::{NodeVisualState, SlayApp, Vec2Serde};
+use eframe::egui;
+use slay_core::{NodeId, Topology};
+use std::collections::HashMap;
+
+/// How far each paste is shifted from the copied nodes, in world units.
+const PASTE_OFFSET: egui::Vec2 = egui::vec2(40.0, 40.0);
+
+/// Nodes copied from the canvas, with their positions at copy time.
+pub struct Clipboard {
+    topology: Topology,
+    positions: HashMap<NodeId, egui::Pos2>,
+    /// Pastes made so far, so repeated pastes cascade instead of stacking
+    pastes: u32,
+}
+
+impl SlayApp {
+    /// The primary selection plus any nodes added with Shift+click.
+    pub fn selected_nodes(&self) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self
+            .selection
+            .iter()
+            .copied()
+            .chain(self.selected_node)
+            .filter(|id| self.simulation.components.contains_key(id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Copies the selected nodes with their configs and the connections
+    /// and links among them.
+    pub fn copy_selection(&mut self) {
+        let ids = self.selected_nodes();
+        if ids.is_empty() {
+            return;
+        }
+        let positions = ids
+            .iter()
+            .filter_map(|id| {
+                let pos = &self.node_states.get(id)?.pos;
+                Some((*id, egui::pos2(pos.x, pos.y)))
+            })
+            .collect();
+        self.clipboard = Some(Clipboard {
+            topology: self.simulation.to_topology().subgraph(&ids),
+            positions,
+            pastes: 0,
+        });
+    }
+
+    /// Adds a fresh copy of the clipboard next to the original and selects it.
+    pub fn paste(&mut self) {
+        let Some(clipboard) = &mut self.clipboard else {
+            return;
+        };
+        clipboard.pastes += 1;
+        let offset = PASTE_OFFSET * clipboard.pastes as f32;
+        self.paste_with_offset(offset);
+    }
+
+    /// Pastes with the top-left copied node at `world_pos`.
+    pub fn paste_at(&mut self, world_pos: egui::Pos2) {
+        let Some(clipboard) = &self.clipboard else {
+            return;
+        };
+        let Some(origin) = clipboard
+            .positions
+            .values()
+            .copied()
+            .reduce(|a, b| a.min(b))
+        else {
+            return;
+        };
+        self.paste_with_offset(world_pos - origin);
+    }
+
+    fn paste_with_offset(&mut self, offset: egui::Vec2) {
+        let Some(clipboard) = &self.clipboard else {
+            return;
+        };
+        let new_ids = self
+            .simulation
+            .paste_topology(&clipboard.topology, self.next_node_id);
+
+        for (old, new) in &new_ids {
+            let pos = clipboard.positions.get(old).copied().unwrap_or_default() + offset;
+            self.node_states.insert(
+                *new,
+                NodeVisualState {
+                    pos: Vec2Serde { x: pos.x, y: pos.y },
+                    last_sync_time: 0.0,
+                },
+            );
+        }
+        if let Some(&max) = new_ids.values().max() {
+            self.next_node_id = max + 1;
+        }
+        self.selection = new_ids.values().copied().collect();
+        self.selected_node = new_ids.values().next().copied();
+        self.selected_edge = None;
+    }
+
+    /// Copy and paste in one step, leaving the clipboard as it was.
+    pub fn duplicate_selection(&mut self) {
+        let saved = self.clipboard.take();
+        self.copy_selection();
+        self.paste();
+        self.clipboard = saved;
+    }
+
+    /// Ctrl+C / Ctrl+V (Cmd on macOS), unless a text field has focus.
+    pub fn handle_clipboard_keys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (copy, paste) = ctx.input(|i| {
+            i.events.iter().fold((false, false), |(c, p), e| match e {
+                egui::Event::Copy => (true, p),
+                egui::Event::Paste(_) => (c, true),
+                egui::Event::Key {
+                    key: egui::Key::V,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if modifiers.command => (c, true),
+                _ => (c, p),
+            })
+        });
+        if copy {
+            self.copy_selection();
+        }
+        if paste {
+            self.paste();
+        }
+    }
+}
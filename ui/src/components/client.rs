@@ -1,4 +1,7 @@
 use crate::components::ComponentView;
+use crate::explain::Explain;
+use crate::theme::theme;
+use crate::widgets::SliderEntry;
 use eframe::egui;
 use serde_json::Value;
 
@@ -15,7 +18,7 @@ impl ComponentView for ClientView {
     }
 
     fn color(&self) -> egui::Color32 {
-        egui::Color32::from_rgb(163, 190, 140) // Green
+        theme().client
     }
 
     fn render_canvas(
@@ -26,6 +29,7 @@ impl ComponentView for ClientView {
         zoom: f32,
     ) {
         let painter = ui.painter();
+        let theme = theme();
         let f_m = egui::FontId::proportional(22.0 * zoom);
         let f_s = egui::FontId::proportional(9.0 * zoom);
 
@@ -37,14 +41,14 @@ impl ComponentView for ClientView {
                 egui::Align2::CENTER_CENTER,
                 format!("{:.1} λ", rate),
                 f_m,
-                egui::Color32::WHITE,
+                theme.text,
             );
             painter.text(
                 rect.center() + egui::vec2(0., 20. * zoom),
                 egui::Align2::CENTER_CENTER,
                 "REQUESTS / S",
                 f_s,
-                egui::Color32::from_gray(180),
+                theme.text_dim,
             );
         }
     }
@@ -56,10 +60,11 @@ impl ComponentView for ClientView {
             let mut val = rate.as_f64().unwrap_or(5.0) as f32;
             if ui
                 .add(
-                    egui::Slider::new(&mut val, 1.0..=500000.0)
+                    SliderEntry::new(&mut val, 1.0..=500000.0)
                         .suffix(" RPS")
                         .logarithmic(true),
                 )
+                .explain("arrival_rate")
                 .changed()
             {
                 *rate = Value::from(val);
@@ -72,7 +77,8 @@ impl ComponentView for ClientView {
         if let Some(timeout) = config.get_mut("timeout") {
             let mut val = timeout.as_u64().unwrap_or(5000);
             if ui
-                .add(egui::Slider::new(&mut val, 10..=30000).suffix("ms"))
+                .add(SliderEntry::new(&mut val, 10..=30000).suffix("ms"))
+                .explain("timeout")
                 .changed()
             {
                 *timeout = Value::from(val);
@@ -80,13 +86,40 @@ impl ComponentView for ClientView {
             }
         }
 
-        if changed {
-            if let Some(gen_id) = config.get_mut("generation_id") {
-                let current = gen_id.as_u64().unwrap_or(1);
-                *gen_id = Value::from(current + 1);
+        ui.add_space(10.0);
+        ui.label("Max Hops:");
+        if let Some(max_hops) = config.get_mut("max_hops") {
+            let mut val = max_hops.as_u64().unwrap_or(32);
+            if ui
+                .add(SliderEntry::new(&mut val, 1..=64).suffix(" hops"))
+                .on_hover_text("Requests travelling further are dropped (routing loop guard)")
+                .explain("max_hops")
+                .changed()
+            {
+                *max_hops = Value::from(val);
+                changed = true;
             }
         }
 
+        ui.add_space(10.0);
+        ui.label("Arrival Batching:");
+        if let Some(batch) = config.get_mut("batch_us") {
+            let mut val = batch.as_u64().unwrap_or(0);
+            if ui
+                .add(SliderEntry::new(&mut val, 0..=100_000).suffix("µs"))
+                .on_hover_text("Plan this far ahead per generator event (0: one event per request)")
+                .explain("batch_us")
+                .changed()
+            {
+                *batch = Value::from(val);
+                changed = true;
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.label("Request Size:");
+        changed |= super::render_size(ui, config, "request_size");
+
         changed
     }
 }
@@ -0,0 +1,129 @@
+use crate::components::ComponentView;
+use crate::explain::Explain;
+use crate::theme::theme;
+use eframe::egui;
+use serde_json::Value;
+
+/// Fallback view for custom components that were registered in core
+/// without a dedicated view. Edits top-level numbers, booleans and text;
+/// text spanning several lines, such as a script, gets a code editor.
+pub struct GenericView {
+    pub label: &'static str,
+    pub description: &'static str,
+}
+
+impl ComponentView for GenericView {
+    fn name(&self) -> &'static str {
+        self.label
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn color(&self) -> egui::Color32 {
+        theme().custom
+    }
+
+    fn render_canvas(
+        &self,
+        ui: &mut egui::Ui,
+        rect: egui::Rect,
+        snapshot: &slay_core::traits::VisualState,
+        zoom: f32,
+    ) {
+        let painter = ui.painter();
+        let theme = theme();
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            self.label,
+            egui::FontId::proportional(14.0 * zoom),
+            theme.text,
+        );
+        if let slay_core::traits::VisualState::Custom(stats) = snapshot {
+            // Guest logic that failed says so instead of its counters
+            if stats.last_error.is_some() {
+                painter.text(
+                    rect.center() + egui::vec2(0., 20. * zoom),
+                    egui::Align2::CENTER_CENTER,
+                    "⚠ error",
+                    egui::FontId::proportional(9.0 * zoom),
+                    theme.critical,
+                );
+                return;
+            }
+            // The component's own values, or the host's counters if it has none
+            let summary: Vec<String> = if stats.fields.is_empty() {
+                vec![
+                    format!("rps: {:.0}", stats.rps),
+                    format!("active: {}", stats.active),
+                ]
+            } else {
+                stats
+                    .fields
+                    .iter()
+                    .take(2)
+                    .map(|(k, v)| format!("{}: {}", k, v))
+                    .collect()
+            };
+            painter.text(
+                rect.center() + egui::vec2(0., 20. * zoom),
+                egui::Align2::CENTER_CENTER,
+                summary.join("  "),
+                egui::FontId::proportional(9.0 * zoom),
+                theme.text_dim,
+            );
+        }
+    }
+
+    fn render_inspector(&self, ui: &mut egui::Ui, config: &mut Value) -> bool {
+        let mut changed = false;
+        let Some(fields) = config.as_object_mut() else {
+            return false;
+        };
+        for (key, value) in fields.iter_mut() {
+            if let Some(text) = value.as_str().filter(|t| t.contains('\n')) {
+                let mut text = text.to_string();
+                ui.label(key.as_str()).explain(key);
+                let edit = ui.add(
+                    egui::TextEdit::multiline(&mut text)
+                        .code_editor()
+                        .desired_rows(12)
+                        .desired_width(f32::INFINITY),
+                );
+                if edit.changed() {
+                    *value = Value::from(text);
+                    changed = true;
+                }
+                continue;
+            }
+            ui.horizontal(|ui| {
+                ui.label(key.as_str()).explain(key);
+                if let Some(mut b) = value.as_bool() {
+                    if ui.checkbox(&mut b, "").changed() {
+                        *value = Value::from(b);
+                        changed = true;
+                    }
+                } else if let Some(mut n) = value.as_u64() {
+                    if ui.add(egui::DragValue::new(&mut n)).changed() {
+                        *value = Value::from(n);
+                        changed = true;
+                    }
+                } else if let Some(mut f) = value.as_f64() {
+                    if ui.add(egui::DragValue::new(&mut f).speed(0.01)).changed() {
+                        *value = Value::from(f);
+                        changed = true;
+                    }
+                } else if let Some(text) = value.as_str() {
+                    let mut text = text.to_string();
+                    if ui.text_edit_singleline(&mut text).changed() {
+                        *value = Value::from(text);
+                        changed = true;
+                    }
+                }
+            });
+        }
+        changed
+    }
+}
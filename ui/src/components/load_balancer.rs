@@ -1,4 +1,7 @@
 use crate::components::ComponentView;
+use crate::explain::Explain;
+use crate::theme::theme;
+use crate::widgets::SliderEntry;
 use eframe::egui;
 use serde_json::Value;
 
@@ -14,7 +17,7 @@ impl ComponentView for LoadBalancerView {
     }
 
     fn color(&self) -> egui::Color32 {
-        egui::Color32::from_rgb(136, 192, 208) // Cyan
+        theme().load_balancer
     }
 
     fn render_canvas(
@@ -25,6 +28,7 @@ impl ComponentView for LoadBalancerView {
         zoom: f32,
     ) {
         let painter = ui.painter();
+        let theme = theme();
         let f_xl = egui::FontId::proportional(22.0 * zoom);
         let f_s = egui::FontId::proportional(10.0 * zoom);
         let f_xs = egui::FontId::proportional(9.0 * zoom);
@@ -44,7 +48,7 @@ impl ComponentView for LoadBalancerView {
                 egui::Align2::CENTER_CENTER,
                 strategy_name,
                 f_xl,
-                egui::Color32::WHITE,
+                theme.text,
             );
 
             // 2. RPS Badge
@@ -55,7 +59,7 @@ impl ComponentView for LoadBalancerView {
                     egui::Align2::RIGHT_TOP,
                     format!("{:.0} RPS", rps),
                     f_s.clone(),
-                    egui::Color32::from_rgb(0, 255, 150),
+                    theme.throughput,
                 );
             }
 
@@ -67,7 +71,7 @@ impl ComponentView for LoadBalancerView {
                     egui::Align2::LEFT_TOP,
                     format!("↻ {}", active_retries),
                     f_s.clone(),
-                    egui::Color32::from_rgb(255, 200, 0),
+                    theme.warn,
                 );
             }
 
@@ -79,7 +83,7 @@ impl ComponentView for LoadBalancerView {
                     egui::Align2::LEFT_TOP,
                     format!("x {}", failed),
                     f_s,
-                    egui::Color32::from_rgb(255, 80, 80),
+                    theme.critical,
                 );
             }
 
@@ -105,9 +109,9 @@ impl ComponentView for LoadBalancerView {
                     );
 
                     let col = if load > 0 {
-                        egui::Color32::from_rgb(136, 192, 208)
+                        theme.load_balancer
                     } else {
-                        egui::Color32::from_gray(50)
+                        theme.inactive
                     };
                     painter.rect_filled(bar_rect, 1.0 * zoom, col);
 
@@ -117,7 +121,7 @@ impl ComponentView for LoadBalancerView {
                             egui::Align2::CENTER_BOTTOM,
                             format!("#{}", target_id),
                             f_xs.clone(),
-                            egui::Color32::from_gray(150),
+                            theme.text_dim,
                         );
                         if load > 0 {
                             painter.text(
@@ -125,7 +129,7 @@ impl ComponentView for LoadBalancerView {
                                 egui::Align2::CENTER_TOP,
                                 format!("{}", load),
                                 f_xs.clone(),
-                                egui::Color32::GOLD,
+                                theme.warn,
                             );
                         }
                     }
@@ -165,12 +169,35 @@ impl ComponentView for LoadBalancerView {
                     {
                         changed = true;
                     }
-                });
+                })
+                .response
+                .explain("strategy");
             if changed {
                 *strategy = Value::from(current);
             }
         }
 
+        if let Some(obj) = config.as_object_mut() {
+            let service = obj.entry("service").or_insert(Value::Null);
+            let mut name = service.as_str().unwrap_or_default().to_string();
+            ui.horizontal(|ui| {
+                ui.label("Service");
+                if ui
+                    .add(egui::TextEdit::singleline(&mut name).hint_text("connected targets"))
+                    .on_hover_text("Route to the current members of this service")
+                    .changed()
+                {
+                    let name = name.trim();
+                    *service = if name.is_empty() {
+                        Value::Null
+                    } else {
+                        Value::from(name)
+                    };
+                    changed = true;
+                }
+            });
+        }
+
         ui.add_space(10.0);
         ui.label(egui::RichText::new("RESILIENCE").small().strong());
 
@@ -182,7 +209,8 @@ impl ComponentView for LoadBalancerView {
             if let Some(val_ref) = max_retries.as_u64() {
                 let mut val = val_ref as u32;
                 if ui
-                    .add(egui::Slider::new(&mut val, 0..=5).text("Max Retries"))
+                    .add(SliderEntry::new(&mut val, 0..=5).text("Max Retries"))
+                    .explain("max_retries")
                     .changed()
                 {
                     *max_retries = serde_json::Value::from(val);
@@ -196,7 +224,8 @@ impl ComponentView for LoadBalancerView {
             if let Some(val_ref) = backoff.as_u64() {
                 let mut val = val_ref;
                 if ui
-                    .add(egui::Slider::new(&mut val, 0..=500).text("Backoff (ms)"))
+                    .add(SliderEntry::new(&mut val, 0..=500).text("Backoff (ms)"))
+                    .explain("retry_backoff_ms")
                     .changed()
                 {
                     *backoff = serde_json::Value::from(val);
@@ -210,13 +239,48 @@ impl ComponentView for LoadBalancerView {
             if let Some(val_ref) = budget.as_f64() {
                 let mut val = val_ref;
                 if ui
-                    .add(egui::Slider::new(&mut val, 0.0..=1.0).text("Retry Budget %"))
+                    .add(SliderEntry::new(&mut val, 0.0..=1.0).text("Retry Budget %"))
+                    .explain("retry_budget_ratio")
                     .changed()
                 {
                     *budget = serde_json::Value::from(val);
                     changed = true;
                 }
             }
+
+            let aware = obj
+                .entry("deadline_aware")
+                .or_insert(serde_json::Value::from(false));
+            if let Some(val_ref) = aware.as_bool() {
+                let mut val = val_ref;
+                if ui
+                    .checkbox(&mut val, "Deadline Aware")
+                    .on_hover_text(
+                        "Drop expired requests and skip retries that cannot finish in time",
+                    )
+                    .explain("deadline_aware")
+                    .changed()
+                {
+                    *aware = serde_json::Value::from(val);
+                    changed = true;
+                }
+            }
+
+            let avoid = obj
+                .entry("avoid_degraded")
+                .or_insert(serde_json::Value::from(false));
+            if let Some(val_ref) = avoid.as_bool() {
+                let mut val = val_ref;
+                if ui
+                    .checkbox(&mut val, "Avoid Degraded")
+                    .on_hover_text("Send less traffic to backends that are slow or flaky")
+                    .explain("avoid_degraded")
+                    .changed()
+                {
+                    *avoid = serde_json::Value::from(val);
+                    changed = true;
+                }
+            }
         }
 
         ui.add_space(10.0);
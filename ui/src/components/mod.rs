@@ -1,12 +1,16 @@
+use crate::explain::Explain;
+use crate::widgets::SliderEntry;
 use eframe::egui;
 use serde_json::Value;
+use std::sync::RwLock;
 
 pub mod client;
+pub mod generic;
 pub mod load_balancer;
 pub mod server;
 
 /// The visual counterpart of a core component.
-pub trait ComponentView {
+pub trait ComponentView: Send + Sync {
     fn name(&self) -> &'static str;
     fn description(&self) -> &'static str;
     fn color(&self) -> egui::Color32;
@@ -30,19 +34,25 @@ macro_rules! register_views {
                         Some(INSTANCE.get_or_init(Default::default))
                     }
                 )*
-                _ => None,
+                _ => custom_view(kind),
             }
         }
 
         pub fn get_all_views() -> Vec<(&'static str, &'static dyn ComponentView)> {
-            vec![
+            let mut views = vec![
                 $(
                     {
                         static INSTANCE: std::sync::OnceLock<$view_type> = std::sync::OnceLock::new();
                         ($kind, INSTANCE.get_or_init(Default::default) as &dyn ComponentView)
                     },
                 )*
-            ]
+            ];
+            for reg in slay_core::registered_components() {
+                if let Some(view) = custom_view(reg.kind) {
+                    views.push((reg.kind, view));
+                }
+            }
+            views
         }
     };
 }
@@ -53,3 +63,83 @@ register_views!(
     "Server" => server::ServerView,
     "LoadBalancer" => load_balancer::LoadBalancerView,
 );
+
+/// Spread given to sizes set from the inspector, before it is adjusted.
+const DEFAULT_SIZE_SPREAD: f64 = 0.5;
+
+/// Edits the `SizeDistribution` at `config[field]`: its median in KB, where
+/// 0 leaves messages unsized, and once sized its spread.
+pub fn render_size(ui: &mut egui::Ui, config: &mut Value, field: &str) -> bool {
+    let size = config.get(field).filter(|s| !s.is_null());
+    let mut kb = size.and_then(|s| s["median_bytes"].as_u64()).unwrap_or(0) as f32 / 1000.0;
+    let mut spread = size
+        .and_then(|s| s["spread"].as_f64())
+        .unwrap_or(DEFAULT_SIZE_SPREAD) as f32;
+    let sized = size.is_some();
+
+    let mut changed = ui
+        .add(
+            SliderEntry::new(&mut kb, 0.0..=100_000.0)
+                .logarithmic(true)
+                .suffix(" KB"),
+        )
+        .on_hover_text("Median size (0: unsized, crosses links in no time)")
+        .explain(field)
+        .changed();
+    if sized {
+        changed |= ui
+            .add(SliderEntry::new(&mut spread, 0.0..=2.0).text("Spread"))
+            .on_hover_text("How far sizes stray from the median (0: all the same)")
+            .changed();
+    }
+    if changed {
+        config[field] = if kb > 0.0 {
+            serde_json::json!({
+                "median_bytes": (kb as f64 * 1000.0).round() as u64,
+                "spread": spread,
+            })
+        } else {
+            Value::Null
+        };
+    }
+    changed
+}
+
+static VIEW_REGISTRY: RwLock<Vec<(&'static str, &'static dyn ComponentView)>> =
+    RwLock::new(Vec::new());
+
+/// Registers the view for a custom component kind (see
+/// `slay_core::register_component`) and returns it. Kinds without a view
+/// fall back to `GenericView`.
+pub fn register_view(
+    kind: &'static str,
+    view: Box<dyn ComponentView>,
+) -> &'static dyn ComponentView {
+    let view: &'static dyn ComponentView = Box::leak(view);
+    let mut registry = VIEW_REGISTRY.write().unwrap();
+    registry.retain(|(k, _)| *k != kind);
+    registry.push((kind, view));
+    view
+}
+
+fn custom_view(kind: &str) -> Option<&'static dyn ComponentView> {
+    if let Some((_, view)) = VIEW_REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .find(|(k, _)| *k == kind)
+    {
+        return Some(*view);
+    }
+    // Components registered in core without a view get a generic one
+    let reg = slay_core::registered_components()
+        .into_iter()
+        .find(|r| r.kind == kind)?;
+    Some(register_view(
+        reg.kind,
+        Box::new(generic::GenericView {
+            label: reg.label,
+            description: reg.description,
+        }),
+    ))
+}
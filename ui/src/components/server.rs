@@ -1,4 +1,7 @@
 use crate::components::ComponentView;
+use crate::explain::Explain;
+use crate::theme::theme;
+use crate::widgets::SliderEntry;
 use eframe::egui;
 use serde_json::Value;
 
@@ -15,7 +18,7 @@ impl ComponentView for ServerView {
     }
 
     fn color(&self) -> egui::Color32 {
-        egui::Color32::from_rgb(129, 161, 193) // Blue
+        theme().server
     }
 
     fn render_canvas(
@@ -26,6 +29,7 @@ impl ComponentView for ServerView {
         zoom: f32,
     ) {
         let painter = ui.painter();
+        let theme = theme();
         let f_m = egui::FontId::proportional(22.0 * zoom);
         let f_s = egui::FontId::proportional(9.0 * zoom);
         let f_xs = egui::FontId::proportional(11.0 * zoom);
@@ -43,11 +47,11 @@ impl ComponentView for ServerView {
                 0.0
             };
 
-            let mut text_color = egui::Color32::WHITE;
+            let mut text_color = theme.text;
             if load_factor > 0.9 {
-                text_color = egui::Color32::from_rgb(255, 100, 100); // Red
+                text_color = theme.critical;
             } else if load_factor > 0.7 {
-                text_color = egui::Color32::from_rgb(255, 200, 100); // Yellow/Orange
+                text_color = theme.warn;
             }
 
             if rps > 0.0 {
@@ -56,7 +60,7 @@ impl ComponentView for ServerView {
                     egui::Align2::RIGHT_TOP,
                     format!("{:.0} RPS", rps),
                     f_xs,
-                    egui::Color32::from_rgb(0, 255, 150),
+                    theme.throughput,
                 );
             }
 
@@ -72,7 +76,7 @@ impl ComponentView for ServerView {
                 egui::Align2::CENTER_CENTER,
                 "BUSY THREADS",
                 f_s,
-                egui::Color32::from_gray(180),
+                theme.text_dim,
             );
 
             let grid_width = (concurrency as f32 * 10.0).min(160.0);
@@ -83,9 +87,9 @@ impl ComponentView for ServerView {
                     egui::vec2(7.0, 8.0) * zoom,
                 );
                 let col = if i < threads {
-                    egui::Color32::from_rgb(0, 255, 255)
+                    theme.traffic
                 } else {
-                    egui::Color32::from_gray(40)
+                    theme.inactive
                 };
                 painter.rect_filled(tr, 1.0 * zoom, col);
             }
@@ -96,7 +100,40 @@ impl ComponentView for ServerView {
                     egui::Align2::RIGHT_TOP,
                     format!("Q: {}", queue),
                     egui::FontId::proportional(12.0 * zoom),
-                    egui::Color32::GOLD,
+                    theme.warn,
+                );
+            }
+
+            // Waiting longer than being served is the saturation signal
+            if stats.service_time_ms > 0.0 {
+                let wait_color = if stats.queue_wait_ms > stats.service_time_ms {
+                    theme.warn
+                } else {
+                    theme.text_dim
+                };
+                painter.text(
+                    rect.left_top() + egui::vec2(10.0 * zoom, 15.0 * zoom),
+                    egui::Align2::LEFT_TOP,
+                    format!("wait {:.0}ms", stats.queue_wait_ms),
+                    egui::FontId::proportional(10.0 * zoom),
+                    wait_color,
+                );
+                painter.text(
+                    rect.left_top() + egui::vec2(10.0 * zoom, 28.0 * zoom),
+                    egui::Align2::LEFT_TOP,
+                    format!("svc {:.0}ms", stats.service_time_ms),
+                    egui::FontId::proportional(10.0 * zoom),
+                    theme.text_dim,
+                );
+            }
+
+            if let Some(hit_rate) = stats.cache_hit_rate {
+                painter.text(
+                    rect.left_top() + egui::vec2(10.0 * zoom, 41.0 * zoom),
+                    egui::Align2::LEFT_TOP,
+                    format!("hit {:.0}%", hit_rate * 100.0),
+                    egui::FontId::proportional(10.0 * zoom),
+                    theme.success,
                 );
             }
         }
@@ -109,7 +146,8 @@ impl ComponentView for ServerView {
         if let Some(service_time) = config.get_mut("service_time") {
             let mut val = service_time.as_u64().unwrap_or(200);
             if ui
-                .add(egui::Slider::new(&mut val, 1..=2000).suffix("ms"))
+                .add(SliderEntry::new(&mut val, 1..=2000).suffix("ms"))
+                .explain("service_time")
                 .changed()
             {
                 *service_time = Value::from(val);
@@ -120,7 +158,8 @@ impl ComponentView for ServerView {
         if let Some(concurrency) = config.get_mut("concurrency") {
             let mut val = concurrency.as_u64().unwrap_or(4);
             if ui
-                .add(egui::Slider::new(&mut val, 1..=64).suffix(" threads"))
+                .add(SliderEntry::new(&mut val, 1..=64).suffix(" threads"))
+                .explain("concurrency")
                 .changed()
             {
                 *concurrency = Value::from(val);
@@ -133,24 +172,105 @@ impl ComponentView for ServerView {
         if let Some(backlog) = config.get_mut("backlog_limit") {
             let mut val = backlog.as_u64().unwrap_or(50);
             if ui
-                .add(egui::Slider::new(&mut val, 0..=500).suffix(" reqs"))
+                .add(SliderEntry::new(&mut val, 0..=500).suffix(" reqs"))
+                .explain("backlog_limit")
                 .changed()
             {
                 *backlog = Value::from(val);
                 changed = true;
             }
         }
+        if let Some(aware) = config.get_mut("deadline_aware") {
+            let mut val = aware.as_bool().unwrap_or(false);
+            if ui
+                .checkbox(&mut val, "Deadline Aware")
+                .on_hover_text("Reject requests that cannot finish before their deadline")
+                .explain("deadline_aware")
+                .changed()
+            {
+                *aware = Value::from(val);
+                changed = true;
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.label(egui::RichText::new("CACHE").small().strong());
+        let mut keyed = config
+            .get("cache_key")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        ui.horizontal(|ui| {
+            let mut key = keyed.clone().unwrap_or_default();
+            ui.label("Key");
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut key)
+                        .desired_width(120.0)
+                        .hint_text("none: hit by ratio"),
+                )
+                .on_hover_text("Metadata entry naming the request class to cache by")
+                .explain("cache_key")
+                .changed()
+            {
+                keyed = (!key.trim().is_empty()).then(|| key.trim().to_string());
+                config["cache_key"] = keyed.clone().map_or(Value::Null, Value::from);
+                changed = true;
+            }
+        });
+        if keyed.is_some() {
+            if let Some(ttl) = config.get_mut("cache_ttl_ms") {
+                let mut val = ttl.as_u64().unwrap_or(60_000) as f32 / 1000.0;
+                if ui
+                    .add(
+                        SliderEntry::new(&mut val, 0.1..=3600.0)
+                            .logarithmic(true)
+                            .suffix("s"),
+                    )
+                    .explain("cache_ttl_ms")
+                    .changed()
+                {
+                    *ttl = Value::from((val * 1000.0) as u64);
+                    changed = true;
+                }
+            }
+        } else if let Some(ratio) = config.get_mut("cache_hit_ratio") {
+            let mut val = ratio.as_f64().unwrap_or(0.0) as f32;
+            if ui
+                .add(SliderEntry::new(&mut val, 0.0..=1.0).text("Hit Ratio"))
+                .explain("cache_hit_ratio")
+                .changed()
+            {
+                *ratio = Value::from(val);
+                changed = true;
+            }
+        }
+        if let Some(hit_time) = config.get_mut("cache_hit_time") {
+            let mut val = hit_time.as_u64().unwrap_or(1);
+            if ui
+                .add(SliderEntry::new(&mut val, 0..=200).suffix("ms"))
+                .on_hover_text("Time to answer a cache hit")
+                .explain("cache_hit_time")
+                .changed()
+            {
+                *hit_time = Value::from(val);
+                changed = true;
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.label(egui::RichText::new("PAYLOAD").small().strong());
+        ui.label("Response Size:");
+        changed |= super::render_size(ui, config, "response_size");
+        ui.label("Call Size:");
+        changed |= super::render_size(ui, config, "request_size");
 
         ui.add_space(10.0);
         ui.label(egui::RichText::new("SIMULATION").small().strong());
         if let Some(fail_prob) = config.get_mut("failure_probability") {
             let mut val = fail_prob.as_f64().unwrap_or(0.0) as f32;
             if ui
-                .add(
-                    egui::Slider::new(&mut val, 0.0..=1.0)
-                        .show_value(true)
-                        .text("Failure %"),
-                )
+                .add(SliderEntry::new(&mut val, 0.0..=1.0).text("Failure %"))
+                .explain("failure_probability")
                 .changed()
             {
                 *fail_prob = Value::from(val);
@@ -161,12 +281,9 @@ impl ComponentView for ServerView {
         if let Some(sat_penalty) = config.get_mut("saturation_penalty") {
             let mut val = sat_penalty.as_f64().unwrap_or(0.5) as f32;
             if ui
-                .add(
-                    egui::Slider::new(&mut val, 0.0..=5.0)
-                        .show_value(true)
-                        .text("Saturation Penalty"),
-                )
+                .add(SliderEntry::new(&mut val, 0.0..=5.0).text("Saturation Penalty"))
                 .on_hover_text("Slowdown factor at max concurrency")
+                .explain("saturation_penalty")
                 .changed()
             {
                 *sat_penalty = Value::from(val);
@@ -0,0 +1,231 @@
+use crate::app::SlayApp;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use slay_core::{MetricsCollector, NodeId, RunMetrics, ScenarioAction, Simulation, Topology};
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use tungstenite::Message;
+
+/// A node named in a command, by id or by name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum NodeRef {
+    Id(NodeId),
+    Name(String),
+}
+
+/// What a control client can ask for. Commands are JSON text messages,
+/// e.g. `{"id": 7, "command": "inject", "action": {"Kill": {"node_id": 3}}}`;
+/// the reply carries the same `id`.
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Replaces the design: a topology object, or the text of a topology
+    /// file, Graphviz diagram, Kubernetes manifests or docker-compose file
+    LoadTopology { topology: Value },
+    /// Merges `config` into a node's config and hot-applies it
+    SetConfig { node: NodeRef, config: Value },
+    /// Applies a fault or fix, in the format of scenario steps
+    Inject { action: ScenarioAction },
+    /// Headline metrics since the warm-up ended
+    Metrics,
+}
+
+impl ControlCommand {
+    /// The topology a `LoadTopology` command carries.
+    pub fn parse_topology(topology: Value) -> Result<Topology, String> {
+        match topology {
+            Value::String(text) if text.trim_start().starts_with('{') => {
+                Topology::from_json(&text).map_err(|e| format!("invalid topology: {}", e))
+            }
+            Value::String(text) => crate::project::import_topology(&text),
+            object => {
+                serde_json::from_value(object).map_err(|e| format!("invalid topology: {}", e))
+            }
+        }
+    }
+
+    /// Runs a command against the running simulation, noting changes on
+    /// the metrics timeline. `LoadTopology` is left to the caller, which
+    /// knows how its design is replaced.
+    pub fn apply(
+        self,
+        sim: &mut Simulation,
+        metrics: &mut MetricsCollector,
+    ) -> Result<Value, String> {
+        let action = match self {
+            ControlCommand::LoadTopology { .. } => {
+                return Err("load_topology is not supported here".to_string())
+            }
+            ControlCommand::SetConfig { node, config } => {
+                if !config.is_object() {
+                    return Err("config must be an object".to_string());
+                }
+                ScenarioAction::SetConfig {
+                    node_id: node_id(sim, &node)?,
+                    config,
+                }
+            }
+            ControlCommand::Inject { action } => action,
+            ControlCommand::Metrics => {
+                let measured_us = sim.time.saturating_sub(sim.warmup_end());
+                let result = RunMetrics::from_simulation(sim, measured_us);
+                let mut reply = serde_json::to_value(result).map_err(|e| e.to_string())?;
+                reply["time_s"] = json!(sim.time as f64 / 1_000_000.0);
+                return Ok(reply);
+            }
+        };
+        for id in action.node_ids() {
+            if !sim.components.contains_key(&id) {
+                return Err(format!("no node #{}", id));
+            }
+        }
+        metrics.annotate(sim.time, action.describe());
+        sim.apply_action(action.clone());
+        sim.record_action(action);
+        Ok(Value::Null)
+    }
+}
+
+fn node_id(sim: &Simulation, node: &NodeRef) -> Result<NodeId, String> {
+    match node {
+        NodeRef::Id(id) => Ok(*id),
+        NodeRef::Name(name) => {
+            let mut named = sim.components.iter().filter(|(_, c)| c.name() == *name);
+            match (named.next(), named.next()) {
+                (Some((&id, _)), None) => Ok(id),
+                _ => Err(format!("no single node named '{}'", name)),
+            }
+        }
+    }
+}
+
+/// A command waiting for the simulation, with where to send the reply.
+struct Pending {
+    id: Value,
+    command: Result<ControlCommand, String>,
+    reply: Sender<String>,
+}
+
+/// Accepts commands over WebSocket so test harnesses and game-day tooling
+/// can drive the simulator: load a topology, change config, inject faults
+/// and query metrics.
+///
+/// Connections are served on background threads; commands queue up until
+/// the owner of the simulation calls `poll`, so they always land between
+/// steps.
+pub struct ControlServer {
+    commands: Receiver<Pending>,
+    /// Woken when a command arrives, so a paused window still answers
+    waker: Arc<Mutex<Option<egui::Context>>>,
+}
+
+impl ControlServer {
+    pub fn start(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (sender, commands) = mpsc::channel();
+        let waker = Arc::new(Mutex::new(None));
+        let shared = Arc::clone(&waker);
+        std::thread::Builder::new()
+            .name("slay-control".into())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let sender = sender.clone();
+                    let waker = Arc::clone(&shared);
+                    let spawned = std::thread::Builder::new()
+                        .name("slay-control-conn".into())
+                        .spawn(move || {
+                            if let Err(err) = serve(stream, &sender, &waker) {
+                                log::debug!("control connection closed: {}", err);
+                            }
+                        });
+                    if let Err(err) = spawned {
+                        log::error!("failed to serve control connection: {}", err);
+                    }
+                }
+            })?;
+        log::info!("Accepting control commands on ws://{}", addr);
+        Ok(Self { commands, waker })
+    }
+
+    /// Repaints `ctx` whenever a command arrives.
+    pub fn wake(&self, ctx: &egui::Context) {
+        if let Ok(mut waker) = self.waker.lock() {
+            *waker = Some(ctx.clone());
+        }
+    }
+
+    /// Runs every queued command through `handle` and sends the replies.
+    pub fn poll(&mut self, mut handle: impl FnMut(ControlCommand) -> Result<Value, String>) {
+        while let Ok(pending) = self.commands.try_recv() {
+            let reply = match pending.command.and_then(&mut handle) {
+                Ok(result) => json!({ "id": pending.id, "ok": true, "result": result }),
+                Err(error) => json!({ "id": pending.id, "ok": false, "error": error }),
+            };
+            // The client may have gone away meanwhile
+            let _ = pending.reply.send(reply.to_string());
+        }
+    }
+}
+
+impl SlayApp {
+    /// Answers the control commands that arrived since the last frame.
+    pub fn poll_control(&mut self) {
+        let Some(mut server) = self.control_server.take() else {
+            return;
+        };
+        server.poll(|command| match command {
+            ControlCommand::LoadTopology { topology } => {
+                let topology = ControlCommand::parse_topology(topology)?;
+                self.open_topology(&topology);
+                Ok(json!({ "nodes": topology.nodes.len() }))
+            }
+            command => command.apply(&mut self.simulation, &mut self.metrics),
+        });
+        self.control_server = Some(server);
+    }
+}
+
+fn serve(
+    stream: TcpStream,
+    commands: &Sender<Pending>,
+    waker: &Mutex<Option<egui::Context>>,
+) -> Result<(), String> {
+    let mut socket = tungstenite::accept(stream).map_err(|e| e.to_string())?;
+    let (reply, replies) = mpsc::channel();
+    loop {
+        let text = match socket.read().map_err(|e| e.to_string())? {
+            Message::Text(text) => text,
+            Message::Close(_) => return Ok(()),
+            // Pings are answered by tungstenite itself
+            _ => continue,
+        };
+        let pending = match serde_json::from_str::<Value>(&text) {
+            Ok(value) => Pending {
+                id: value.get("id").cloned().unwrap_or_default(),
+                command: serde_json::from_value(value)
+                    .map_err(|e| format!("invalid command: {}", e)),
+                reply: reply.clone(),
+            },
+            Err(err) => Pending {
+                id: Value::Null,
+                command: Err(format!("invalid JSON: {}", err)),
+                reply: reply.clone(),
+            },
+        };
+        commands
+            .send(pending)
+            .map_err(|_| "the simulator stopped".to_string())?;
+        if let Some(ctx) = waker.lock().ok().and_then(|w| w.clone()) {
+            ctx.request_repaint();
+        }
+        let answer = replies
+            .recv()
+            .map_err(|_| "the simulator stopped".to_string())?;
+        socket
+            .send(Message::Text(answer))
+            .map_err(|e| e.to_string())?;
+    }
+}
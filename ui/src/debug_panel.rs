@@ -0,0 +1,308 @@
+use crate::app::SlayApp;
+use crate::export::unix_time_ns;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::{Breakpoint, Event, EventType};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BreakpointKind {
+    ArrivalAt,
+    EventAt,
+    RequestFailed,
+    TimeReached,
+}
+
+/// Scratch state for the "add breakpoint" form.
+pub struct DebugForm {
+    pub kind: BreakpointKind,
+    pub node_id: u32,
+    pub request_id: String,
+    pub time_s: f32,
+}
+
+impl Default for DebugForm {
+    fn default() -> Self {
+        Self {
+            kind: BreakpointKind::ArrivalAt,
+            node_id: 1,
+            request_id: String::new(),
+            time_s: 10.0,
+        }
+    }
+}
+
+pub fn render_debug_panel(ui: &mut egui::Ui, app: &mut SlayApp) {
+    // Controls
+    ui.horizontal(|ui| {
+        if ui
+            .button("⏭ Step")
+            .on_hover_text("Process one event")
+            .clicked()
+        {
+            app.is_running = false;
+            app.simulation.step_checked();
+        }
+        if ui.button("▶ Continue").clicked() {
+            app.is_running = true;
+        }
+        ui.label(
+            egui::RichText::new(format!(
+                "t = {:.6}s",
+                app.simulation.time as f64 / 1_000_000.0
+            ))
+            .color(theme().text_dim),
+        );
+    });
+
+    if let Some(idx) = app.simulation.debugger.hit {
+        if let Some(bp) = app.simulation.debugger.breakpoints.get(idx) {
+            ui.label(
+                egui::RichText::new(format!("⏸ Paused on: {}", bp.describe()))
+                    .strong()
+                    .color(theme().warn),
+            );
+        }
+    }
+
+    ui.add_space(6.0);
+    ui.label(egui::RichText::new("NEXT EVENT").small().strong());
+    match app.simulation.peek_event() {
+        Some(ev) => describe_event(ui, ev),
+        None => {
+            ui.label(egui::RichText::new("Queue empty").color(theme().text_dim));
+        }
+    }
+
+    ui.add_space(6.0);
+    ui.label(egui::RichText::new("LAST EVENT").small().strong());
+    match &app.simulation.debugger.last_event {
+        Some(ev) => describe_event(ui, ev),
+        None => {
+            ui.label(egui::RichText::new("—").color(theme().text_dim));
+        }
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.label(egui::RichText::new("BREAKPOINTS").small().strong());
+
+    let mut to_remove = None;
+    for (i, bp) in app.simulation.debugger.breakpoints.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(bp.describe());
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button(egui::RichText::new("✖").color(theme().critical))
+                    .clicked()
+                {
+                    to_remove = Some(i);
+                }
+            });
+        });
+    }
+    if let Some(i) = to_remove {
+        app.simulation.debugger.breakpoints.remove(i);
+        app.simulation.debugger.hit = None;
+    }
+
+    ui.add_space(6.0);
+    let form = &mut app.debug_form;
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("bp_kind")
+            .selected_text(match form.kind {
+                BreakpointKind::ArrivalAt => "Arrival at",
+                BreakpointKind::EventAt => "Event at",
+                BreakpointKind::RequestFailed => "Request fails",
+                BreakpointKind::TimeReached => "Time reached",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut form.kind, BreakpointKind::ArrivalAt, "Arrival at");
+                ui.selectable_value(&mut form.kind, BreakpointKind::EventAt, "Event at");
+                ui.selectable_value(
+                    &mut form.kind,
+                    BreakpointKind::RequestFailed,
+                    "Request fails",
+                );
+                ui.selectable_value(&mut form.kind, BreakpointKind::TimeReached, "Time reached");
+            });
+
+        match form.kind {
+            BreakpointKind::ArrivalAt | BreakpointKind::EventAt => {
+                ui.add(egui::DragValue::new(&mut form.node_id).prefix("#"));
+            }
+            BreakpointKind::RequestFailed => {
+                ui.add(
+                    egui::TextEdit::singleline(&mut form.request_id)
+                        .hint_text("any (hex id)")
+                        .desired_width(100.0),
+                );
+            }
+            BreakpointKind::TimeReached => {
+                ui.add(
+                    egui::DragValue::new(&mut form.time_s)
+                        .suffix("s")
+                        .speed(0.1),
+                );
+            }
+        }
+    });
+
+    if ui.button("+ Add Breakpoint").clicked() {
+        let bp = match form.kind {
+            BreakpointKind::ArrivalAt => Breakpoint::ArrivalAt {
+                node_id: form.node_id,
+            },
+            BreakpointKind::EventAt => Breakpoint::EventAt {
+                node_id: form.node_id,
+            },
+            BreakpointKind::RequestFailed => Breakpoint::RequestFailed {
+                request_id: u128::from_str_radix(form.request_id.trim(), 16).ok(),
+            },
+            BreakpointKind::TimeReached => Breakpoint::TimeReached {
+                time_us: (form.time_s.max(0.0) as f64 * 1_000_000.0) as u64,
+            },
+        };
+        app.simulation.debugger.breakpoints.push(bp);
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    render_profiler(ui, app);
+}
+
+/// Engine throughput and the nodes whose events cost the most wall time,
+/// to find what makes a topology stutter.
+fn render_profiler(ui: &mut egui::Ui, app: &mut SlayApp) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("PROFILER").small().strong());
+        ui.checkbox(&mut app.profiling, "Measure");
+        if app.profiling && ui.small_button("Reset").clicked() {
+            app.simulation.enable_profiling(unix_time_ns);
+        }
+    });
+    if !app.profiling {
+        app.simulation.disable_profiling();
+        return;
+    }
+    if app.simulation.profiler.is_none() {
+        app.simulation.enable_profiling(unix_time_ns);
+    }
+    let Some(report) = app.simulation.profile() else {
+        return;
+    };
+    let frame_ms = ui.ctx().input(|i| i.stable_dt) * 1000.0;
+    egui::Grid::new("profile_totals")
+        .num_columns(2)
+        .spacing([12.0, 2.0])
+        .show(ui, |ui| {
+            let row = |ui: &mut egui::Ui, label: &str, value: String| {
+                ui.label(egui::RichText::new(label).color(theme().text_dim));
+                ui.label(value);
+                ui.end_row();
+            };
+            row(ui, "Events/s", format!("{:.0}", report.events_per_sec));
+            row(
+                ui,
+                "Flat out",
+                format!("{:.0} events/s", report.capacity_per_sec),
+            );
+            row(
+                ui,
+                "In components",
+                format!("{:.0}%", report.handler_share * 100.0),
+            );
+            row(
+                ui,
+                "Pending",
+                format!("{} (peak {})", report.pending, report.peak_pending),
+            );
+            row(
+                ui,
+                "Allocations",
+                report
+                    .allocations
+                    .map_or("not counted".to_string(), |n| n.to_string()),
+            );
+            row(ui, "Frame", format!("{:.1} ms", frame_ms));
+        });
+
+    ui.add_space(4.0);
+    egui::Grid::new("profile_nodes")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            for heading in ["Node", "Events", "Mean", "Allocs"] {
+                ui.label(egui::RichText::new(heading).small().strong());
+            }
+            ui.end_row();
+            for node in report.nodes.iter().take(8) {
+                ui.label(&node.name)
+                    .on_hover_text(format!("#{} · {}", node.node_id, node.kind));
+                ui.label(node.cost.events.to_string());
+                ui.label(format!("{:.1} µs", node.mean_ns() / 1000.0));
+                ui.label(node.cost.allocations.to_string());
+                ui.end_row();
+            }
+        });
+}
+
+fn describe_event(ui: &mut egui::Ui, ev: &Event) {
+    let (label, detail) = match &ev.event_type {
+        EventType::Arrival {
+            request_id, path, ..
+        } => ("Arrival", format!("req {:x} via {:?}", request_id, path)),
+        EventType::ProcessComplete {
+            request_id,
+            success,
+            ..
+        } => (
+            "ProcessComplete",
+            format!("req {:x} ok={}", request_id, success),
+        ),
+        EventType::Response {
+            request_id,
+            path,
+            success,
+            failure,
+            ..
+        } => (
+            "Response",
+            match failure {
+                Some(reason) => format!(
+                    "req {:x} ok={} ({}) path {:?}",
+                    request_id,
+                    success,
+                    reason.label(),
+                    path
+                ),
+                None => format!("req {:x} ok={} path {:?}", request_id, success, path),
+            },
+        ),
+        EventType::GenerateNext { generation_id } => {
+            ("GenerateNext", format!("gen {}", generation_id))
+        }
+    };
+    ui.label(
+        egui::RichText::new(format!(
+            "{:.6}s  #{}  {}",
+            ev.time as f64 / 1_000_000.0,
+            ev.node_id,
+            label
+        ))
+        .color(theme().accent),
+    );
+    ui.label(egui::RichText::new(detail).small().color(theme().text_dim));
+
+    if let EventType::Arrival { metadata, .. }
+    | EventType::ProcessComplete { metadata, .. }
+    | EventType::Response { metadata, .. } = &ev.event_type
+    {
+        for (key, value) in metadata.iter() {
+            ui.label(
+                egui::RichText::new(format!("{} = {}", key, value))
+                    .small()
+                    .color(theme().text_dim),
+            );
+        }
+    }
+}
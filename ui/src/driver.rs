@@ -0,0 +1,298 @@
+use slay_core::{RunOutcome, Simulation, StepResult};
+
+/// How the driver advances virtual time while it holds the simulation.
+#[derive(Clone, Copy)]
+pub enum Pace {
+    /// `speed` × real time, pausing at the absolute virtual time `until_us`
+    /// if one is given
+    RealTime { speed: f32, until_us: Option<u64> },
+    /// As fast as possible up to an absolute virtual time
+    FastForward { target_us: u64 },
+}
+
+/// What happened while the simulation was lent out.
+#[derive(Default, Clone, Copy)]
+pub struct RunReport {
+    pub hit_breakpoint: bool,
+    /// A fast-forward or a timed real-time run reached its target
+    pub reached_target: bool,
+}
+
+impl RunReport {
+    fn from_fast_forward(outcome: RunOutcome) -> Self {
+        Self {
+            hit_breakpoint: matches!(outcome, RunOutcome::Breakpoint(_)),
+            reached_target: outcome == RunOutcome::ConditionMet,
+        }
+    }
+}
+
+/// Advances the simulation in real time between UI frames.
+///
+/// At the end of a frame the app lends the simulation to the driver; at the
+/// start of the next frame (and before saving) it takes it back. On native
+/// targets the stepping happens on a worker thread, so a heavy topology only
+/// slows virtual time down instead of freezing rendering. On wasm there are
+/// no threads and the stepping happens synchronously in `reclaim`.
+#[derive(Default)]
+pub struct SimDriver {
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: native::Worker,
+    #[cfg(target_arch = "wasm32")]
+    pending: Option<(Pace, bool, f64)>,
+}
+
+/// Steps every event due by `target` (stopping on breakpoints), then moves
+/// the clock to `target` unless interrupted. Returns false if it stopped early.
+fn advance_to(
+    sim: &mut Simulation,
+    target: u64,
+    use_breakpoints: bool,
+    report: &mut RunReport,
+    mut keep_going: impl FnMut(usize) -> bool,
+) -> bool {
+    let mut processed = 0;
+    while sim.peek_event().is_some_and(|e| e.time <= target) {
+        if !keep_going(processed) {
+            return false;
+        }
+        if use_breakpoints {
+            if let StepResult::Breakpoint(_) = sim.step_checked() {
+                report.hit_breakpoint = true;
+                return false;
+            }
+        } else {
+            sim.step();
+        }
+        processed += 1;
+    }
+    sim.time = sim.time.max(target);
+    sim.apply_scenario_until(target);
+    true
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SimDriver {
+    /// Hands `sim` to the worker, leaving a placeholder in its place. The
+    /// worker runs from `now`, the start of the frame, so the time the
+    /// frame spent before lending is simulated too.
+    pub fn lend(&mut self, sim: &mut Simulation, pace: Pace, now: f64) {
+        self.worker.lend(sim, pace, now);
+    }
+
+    /// Takes the simulation back from the worker, if it was lent.
+    pub fn reclaim(&mut self, sim: &mut Simulation, now: f64) -> RunReport {
+        self.worker.reclaim(sim, now)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SimDriver {
+    pub fn lend(&mut self, sim: &mut Simulation, pace: Pace, now: f64) {
+        let use_breakpoints = !sim.debugger.breakpoints.is_empty();
+        self.pending = Some((pace, use_breakpoints, now));
+    }
+
+    /// Single-threaded fallback: catch up on the time elapsed since `lend`,
+    /// capped per frame so the page stays responsive.
+    pub fn reclaim(&mut self, sim: &mut Simulation, now: f64) -> RunReport {
+        const MAX_EVENTS_PER_FRAME: usize = 10_000;
+        let mut report = RunReport::default();
+        match self.pending.take() {
+            Some((Pace::RealTime { speed, until_us }, use_breakpoints, lent_at)) => {
+                let virtual_dt = ((now - lent_at).max(0.0) * speed as f64 * 1_000_000.0) as u64;
+                let target = (sim.time + virtual_dt).min(until_us.unwrap_or(u64::MAX));
+                let caught_up = advance_to(sim, target, use_breakpoints, &mut report, |n| {
+                    n < MAX_EVENTS_PER_FRAME
+                });
+                report.reached_target = caught_up && until_us == Some(target);
+            }
+            Some((Pace::FastForward { target_us }, _, _)) => {
+                let mut calls = 0;
+                let outcome = sim.fast_forward(target_us, MAX_EVENTS_PER_FRAME, |_| {
+                    calls += 1;
+                    calls == 1
+                });
+                report = RunReport::from_fast_forward(outcome);
+            }
+            None => {}
+        }
+        report
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use super::{advance_to, Pace, RunReport};
+    use slay_core::Simulation;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// How many events the worker processes between checks of the stop flag.
+    const CHECK_EVERY: usize = 256;
+
+    struct Job {
+        sim: Simulation,
+        pace: Pace,
+        /// When the frame that lent the simulation started
+        lent_at: Instant,
+    }
+
+    type Channels = (Sender<Job>, Receiver<(Simulation, RunReport)>);
+
+    #[derive(Default)]
+    pub struct Worker {
+        channels: Option<Channels>,
+        stop: Arc<AtomicBool>,
+        lent: bool,
+        /// Stand-in kept in the app while the real simulation is lent out
+        spare: Option<Simulation>,
+        /// A frame timestamp and the instant it was taken, to place the
+        /// app's timestamps on the worker's clock
+        anchor: Option<(f64, Instant)>,
+    }
+
+    impl Worker {
+        fn channels(&mut self) -> &Channels {
+            let stop = Arc::clone(&self.stop);
+            self.channels.get_or_insert_with(|| {
+                let (job_tx, job_rx) = channel::<Job>();
+                let (done_tx, done_rx) = channel();
+                std::thread::Builder::new()
+                    .name("slay-sim".into())
+                    .spawn(move || {
+                        while let Ok(job) = job_rx.recv() {
+                            let result = run(job, &stop);
+                            if done_tx.send(result).is_err() {
+                                break;
+                            }
+                        }
+                    })
+                    .expect("failed to spawn simulation thread");
+                (job_tx, done_rx)
+            })
+        }
+
+        /// The instant of the frame timestamp `now`.
+        fn instant_of(&self, now: f64) -> Instant {
+            let Some((anchor_now, anchor)) = self.anchor else {
+                return Instant::now();
+            };
+            let offset = Duration::from_secs_f64((now - anchor_now).abs());
+            let instant = if now >= anchor_now {
+                anchor.checked_add(offset)
+            } else {
+                anchor.checked_sub(offset)
+            };
+            // A timestamp can't be later than the moment it is used
+            instant.unwrap_or(anchor).min(Instant::now())
+        }
+
+        pub fn lend(&mut self, sim: &mut Simulation, pace: Pace, now: f64) {
+            if self.lent {
+                return;
+            }
+            let lent_at = self.instant_of(now);
+            let spare = self.spare.take().unwrap_or_else(|| Simulation::new(0));
+            let real = std::mem::replace(sim, spare);
+            self.stop.store(false, Ordering::Relaxed);
+            let (job_tx, _) = self.channels();
+            match job_tx.send(Job {
+                sim: real,
+                pace,
+                lent_at,
+            }) {
+                Ok(()) => self.lent = true,
+                // Worker is gone: put the simulation back
+                Err(err) => self.spare = Some(std::mem::replace(sim, err.0.sim)),
+            }
+        }
+
+        pub fn reclaim(&mut self, sim: &mut Simulation, now: f64) -> RunReport {
+            self.anchor = Some((now, Instant::now()));
+            if !self.lent {
+                return RunReport::default();
+            }
+            self.lent = false;
+            self.stop.store(true, Ordering::Relaxed);
+            let (_, done_rx) = self.channels();
+            let (real, report) = done_rx
+                .recv()
+                .expect("simulation thread exited while holding the simulation");
+            self.spare = Some(std::mem::replace(sim, real));
+            report
+        }
+    }
+
+    /// Advances virtual time according to `pace` until asked to stop.
+    fn run(job: Job, stop: &AtomicBool) -> (Simulation, RunReport) {
+        let Job {
+            mut sim,
+            pace,
+            lent_at,
+        } = job;
+        let (speed, until_us) = match pace {
+            Pace::RealTime { speed, until_us } => (speed, until_us.unwrap_or(u64::MAX)),
+            Pace::FastForward { target_us } => {
+                let outcome =
+                    sim.fast_forward(target_us, CHECK_EVERY, |_| !stop.load(Ordering::Relaxed));
+                return (sim, RunReport::from_fast_forward(outcome));
+            }
+        };
+        let use_breakpoints = !sim.debugger.breakpoints.is_empty();
+        let mut report = RunReport::default();
+        let base = sim.time;
+
+        while !stop.load(Ordering::Relaxed) {
+            let target = (base
+                + (lent_at.elapsed().as_secs_f64() * speed as f64 * 1_000_000.0) as u64)
+                .min(until_us);
+            let caught_up = advance_to(&mut sim, target, use_breakpoints, &mut report, |n| {
+                n % CHECK_EVERY != 0 || !stop.load(Ordering::Relaxed)
+            });
+            if report.hit_breakpoint {
+                break;
+            }
+            if caught_up && target == until_us {
+                report.reached_target = true;
+                break;
+            }
+            if caught_up {
+                std::thread::sleep(Duration::from_micros(500));
+            }
+        }
+        (sim, report)
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_time_before_lending_is_simulated() {
+        let mut driver = SimDriver::default();
+        let mut sim = Simulation::new(1);
+        let frame_start = 10.0;
+        let started = Instant::now();
+        driver.reclaim(&mut sim, frame_start);
+
+        // The rest of the frame: UI work before the simulation is lent
+        std::thread::sleep(Duration::from_millis(60));
+        let pace = Pace::RealTime {
+            speed: 2.0,
+            until_us: None,
+        };
+        driver.lend(&mut sim, pace, frame_start);
+        std::thread::sleep(Duration::from_millis(60));
+
+        let reclaimed_at = frame_start + started.elapsed().as_secs_f64();
+        driver.reclaim(&mut sim, reclaimed_at);
+        let expected = (reclaimed_at - frame_start) * 2.0 * 1_000_000.0;
+        let error = (sim.time as f64 - expected).abs();
+        assert!(error < 20_000.0, "{} vs {}", sim.time, expected);
+    }
+}
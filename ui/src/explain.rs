@@ -0,0 +1,68 @@
+use crate::theme::theme;
+use eframe::egui;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Storage key of the explain mode switch.
+pub const EXPLAIN_KEY: &str = "slay_explain";
+
+/// What a config field means, keyed by the field's name in the component
+/// config. The entries live in `ui/assets/explainers.json`.
+#[derive(Deserialize)]
+pub struct Explainer {
+    pub title: String,
+    pub explanation: String,
+    /// Values seen in real systems
+    pub typical: String,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Whether hovering a config field explains it.
+pub fn explain_mode() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_explain_mode(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The explainer for a config field, if there is one.
+pub fn explainer(field: &str) -> Option<&'static Explainer> {
+    static EXPLAINERS: OnceLock<HashMap<String, Explainer>> = OnceLock::new();
+    EXPLAINERS
+        .get_or_init(|| {
+            serde_json::from_str(include_str!("../assets/explainers.json"))
+                .expect("explainers.json is valid")
+        })
+        .get(field)
+}
+
+pub trait Explain {
+    /// In explain mode, shows what `field` means when the control is
+    /// hovered.
+    fn explain(self, field: &str) -> Self;
+}
+
+impl Explain for egui::Response {
+    fn explain(self, field: &str) -> Self {
+        if !explain_mode() {
+            return self;
+        }
+        let Some(explainer) = explainer(field) else {
+            return self;
+        };
+        self.on_hover_ui(|ui| {
+            ui.set_max_width(280.0);
+            ui.label(egui::RichText::new(&explainer.title).strong());
+            ui.label(&explainer.explanation);
+            ui.label(
+                egui::RichText::new(format!("Typical: {}", explainer.typical))
+                    .small()
+                    .color(theme().text_dim),
+            );
+        })
+    }
+}
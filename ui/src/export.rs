@@ -0,0 +1,83 @@
+/// Hands a generated file to the user: a browser download on the web, a
+/// file in the working directory on native targets.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(name: &str, _mime: &str, contents: &str) {
+    match std::fs::write(name, contents) {
+        Ok(()) => log::info!("Wrote {}", name),
+        Err(err) => log::error!("Failed to write {}: {}", name, err),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(name: &str, mime: &str, contents: &str) {
+    use eframe::wasm_bindgen::JsCast as _;
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Ok(anchor) = document.create_element("a") else {
+        return;
+    };
+    let Ok(anchor) = anchor.dyn_into::<web_sys::HtmlAnchorElement>() else {
+        return;
+    };
+    anchor.set_href(&format!(
+        "data:text/csv;charset=utf-8,{}",
+        percent_encode(contents)
+    ));
+    anchor.set_download(name);
+    anchor.click();
+}
+
+/// Offers binary `contents` as a browser download.
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(name: &str, mime: &str, contents: &[u8]) {
+    use base64::Engine as _;
+    use eframe::wasm_bindgen::JsCast as _;
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let Ok(anchor) = document.create_element("a") else {
+        return;
+    };
+    let Ok(anchor) = anchor.dyn_into::<web_sys::HtmlAnchorElement>() else {
+        return;
+    };
+    anchor.set_href(&format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(contents)
+    ));
+    anchor.set_download(name);
+    anchor.click();
+}
+
+/// Current wall-clock time, for anchoring virtual time in exports.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_time_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn unix_time_ns() -> u64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .map_or(0, |p| ((p.time_origin() + p.now()) * 1_000_000.0) as u64)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn percent_encode(text: &str) -> String {
+    use std::fmt::Write as _;
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~,".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "%{:02X}", byte);
+        }
+    }
+    out
+}
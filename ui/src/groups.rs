@@ -0,0 +1,289 @@
+use crate::app::{SlayApp, Vec2Serde};
+use crate::theme::theme;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use slay_core::NodeId;
+use std::collections::BTreeSet;
+
+/// Node size on the canvas, in world units.
+const NODE_SIZE: egui::Vec2 = egui::vec2(180.0, 90.0);
+/// Space kept around grouped nodes, and the height of the title bar.
+const PADDING: f32 = 30.0;
+const TITLE_HEIGHT: f32 = 26.0;
+const MIN_SIZE: egui::Vec2 = egui::vec2(220.0, 140.0);
+
+/// A titled box clustering nodes on the canvas, e.g. "Region us-east".
+///
+/// Groups are purely visual: the simulation does not know about them.
+/// Nodes join the group they are dropped into and leave it when dragged
+/// out. A collapsed group is drawn as a single node-sized box and the
+/// edges of its members attach to it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Group {
+    pub title: String,
+    /// Top-left corner, in world units
+    pub pos: Vec2Serde,
+    pub size: Vec2Serde,
+    pub members: BTreeSet<NodeId>,
+    pub collapsed: bool,
+}
+
+impl Group {
+    /// Area the group covers, in world units.
+    pub fn rect(&self) -> egui::Rect {
+        let size = if self.collapsed {
+            NODE_SIZE
+        } else {
+            egui::vec2(self.size.x, self.size.y)
+        };
+        egui::Rect::from_min_size(egui::pos2(self.pos.x, self.pos.y), size)
+    }
+}
+
+enum GroupAction {
+    Move(usize, egui::Vec2),
+    Resize(usize, egui::Vec2),
+    Toggle(usize),
+    Rename(usize, String),
+    Ungroup(usize),
+}
+
+impl SlayApp {
+    /// Wraps the selected nodes in a new group, taking them out of any
+    /// group they were in.
+    pub fn group_selection(&mut self) {
+        let ids = self.selected_nodes();
+        if ids.is_empty() {
+            return;
+        }
+        for group in &mut self.groups {
+            group.members.retain(|id| !ids.contains(id));
+        }
+        let mut group = Group {
+            title: format!("Group {}", self.groups.len() + 1),
+            pos: Vec2Serde { x: 0.0, y: 0.0 },
+            size: Vec2Serde { x: 0.0, y: 0.0 },
+            members: ids.into_iter().collect(),
+            collapsed: false,
+        };
+        self.wrap_members(&mut group);
+        self.groups.push(group);
+    }
+
+    /// Resizes every group that has members to just enclose them, e.g.
+    /// after the nodes were laid out again.
+    pub fn wrap_groups(&mut self) {
+        let mut groups = std::mem::take(&mut self.groups);
+        for group in &mut groups {
+            self.wrap_members(group);
+        }
+        self.groups = groups;
+    }
+
+    fn wrap_members(&self, group: &mut Group) {
+        let Some(bounds) = group
+            .members
+            .iter()
+            .filter_map(|id| self.node_states.get(id))
+            .map(|s| egui::Rect::from_min_size(egui::pos2(s.pos.x, s.pos.y), NODE_SIZE))
+            .reduce(|a, b| a.union(b))
+        else {
+            return;
+        };
+        let min = bounds.min - egui::vec2(PADDING, PADDING + TITLE_HEIGHT);
+        let size =
+            (bounds.size() + egui::vec2(PADDING, PADDING) * 2.0 + egui::vec2(0.0, TITLE_HEIGHT))
+                .max(MIN_SIZE);
+        group.pos = Vec2Serde { x: min.x, y: min.y };
+        group.size = Vec2Serde {
+            x: size.x,
+            y: size.y,
+        };
+    }
+
+    /// Moves `ids` into the innermost expanded group under their centre,
+    /// or out of all groups if they were dropped on open canvas.
+    pub fn regroup(&mut self, ids: &[NodeId]) {
+        for &id in ids {
+            let Some(state) = self.node_states.get(&id) else {
+                continue;
+            };
+            let center = egui::pos2(state.pos.x, state.pos.y) + NODE_SIZE / 2.0;
+            let target = self
+                .groups
+                .iter()
+                .enumerate()
+                .filter(|(_, g)| !g.collapsed && g.rect().contains(center))
+                .min_by(|(_, a), (_, b)| a.rect().area().total_cmp(&b.rect().area()))
+                .map(|(i, _)| i);
+            for (i, group) in self.groups.iter_mut().enumerate() {
+                if Some(i) == target {
+                    group.members.insert(id);
+                } else {
+                    group.members.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// The collapsed group hiding `id`, if any.
+    pub fn collapsed_group_of(&self, id: NodeId) -> Option<&Group> {
+        self.groups
+            .iter()
+            .find(|g| g.collapsed && g.members.contains(&id))
+    }
+
+    /// Top-left corner edges attach to: the node itself, or the collapsed
+    /// group standing in for it.
+    pub fn node_anchor(&self, id: NodeId) -> Option<egui::Pos2> {
+        if let Some(group) = self.collapsed_group_of(id) {
+            return Some(egui::pos2(group.pos.x, group.pos.y));
+        }
+        let state = self.node_states.get(&id)?;
+        Some(egui::pos2(state.pos.x, state.pos.y))
+    }
+
+    /// Draws the group boxes underneath the nodes and handles dragging,
+    /// resizing and the title menu.
+    pub fn draw_groups(&mut self, ui: &mut egui::Ui) {
+        for group in &mut self.groups {
+            group.members.retain(|id| self.node_states.contains_key(id));
+        }
+        let mut action = None;
+        for (i, group) in self.groups.iter().enumerate() {
+            let world = group.rect();
+            let rect = egui::Rect::from_min_max(
+                self.world_to_screen(world.min),
+                self.world_to_screen(world.max),
+            );
+            let rounding = 6.0 * self.zoom;
+            let title_rect = if group.collapsed {
+                rect
+            } else {
+                egui::Rect::from_min_size(
+                    rect.min,
+                    egui::vec2(rect.width(), TITLE_HEIGHT * self.zoom),
+                )
+            };
+
+            if group.collapsed {
+                ui.painter()
+                    .rect_filled(rect, rounding, theme().panel.gamma_multiply(0.9));
+                ui.painter().rect_stroke(
+                    rect,
+                    rounding,
+                    egui::Stroke::new(1.5 * self.zoom, theme().accent.gamma_multiply(0.6)),
+                );
+                ui.painter().text(
+                    rect.center() + egui::vec2(0.0, 12.0) * self.zoom,
+                    egui::Align2::CENTER_CENTER,
+                    format!("{} nodes", group.members.len()),
+                    egui::FontId::proportional(11.0 * self.zoom),
+                    theme().text_dim,
+                );
+            } else {
+                ui.painter()
+                    .rect_filled(rect, rounding, theme().accent.gamma_multiply(0.04));
+                ui.painter().rect_stroke(
+                    rect,
+                    rounding,
+                    egui::Stroke::new(1.0 * self.zoom, theme().accent.gamma_multiply(0.35)),
+                );
+            }
+            let arrow = if group.collapsed { "▸" } else { "▾" };
+            ui.painter().text(
+                title_rect.left_top() + egui::vec2(10.0, 6.0) * self.zoom,
+                egui::Align2::LEFT_TOP,
+                format!("{} {}", arrow, group.title),
+                egui::FontId::proportional(12.0 * self.zoom),
+                theme().text,
+            );
+
+            let title = ui.interact(
+                title_rect,
+                egui::Id::new(("group", i)),
+                egui::Sense::click_and_drag(),
+            );
+            if title.hovered() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+            }
+            if title.dragged() {
+                action = Some(GroupAction::Move(i, title.drag_delta() / self.zoom));
+            }
+            if title.double_clicked() {
+                action = Some(GroupAction::Toggle(i));
+            }
+            title.context_menu(|ui| {
+                let mut name = group.title.clone();
+                if ui.text_edit_singleline(&mut name).changed() {
+                    action = Some(GroupAction::Rename(i, name));
+                }
+                let toggle = if group.collapsed {
+                    "Expand"
+                } else {
+                    "Collapse"
+                };
+                if ui.button(toggle).clicked() {
+                    action = Some(GroupAction::Toggle(i));
+                    ui.close_menu();
+                }
+                if ui.button("Ungroup").clicked() {
+                    action = Some(GroupAction::Ungroup(i));
+                    ui.close_menu();
+                }
+            });
+
+            if !group.collapsed {
+                let handle = egui::Rect::from_center_size(rect.max, egui::vec2(16.0, 16.0));
+                let resize = ui.interact(
+                    handle,
+                    egui::Id::new(("group-resize", i)),
+                    egui::Sense::drag(),
+                );
+                if resize.hovered() || resize.dragged() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeNwSe);
+                }
+                if resize.dragged() {
+                    action = Some(GroupAction::Resize(i, resize.drag_delta() / self.zoom));
+                }
+            }
+        }
+
+        match action {
+            Some(GroupAction::Move(i, delta)) => {
+                let group = &mut self.groups[i];
+                group.pos.x += delta.x;
+                group.pos.y += delta.y;
+                for id in &group.members {
+                    if let Some(s) = self.node_states.get_mut(id) {
+                        s.pos.x += delta.x;
+                        s.pos.y += delta.y;
+                    }
+                }
+            }
+            Some(GroupAction::Resize(i, delta)) => {
+                let group = &mut self.groups[i];
+                group.size.x = (group.size.x + delta.x).max(MIN_SIZE.x);
+                group.size.y = (group.size.y + delta.y).max(MIN_SIZE.y);
+            }
+            Some(GroupAction::Toggle(i)) => {
+                let group = &mut self.groups[i];
+                group.collapsed = !group.collapsed;
+                if group.collapsed {
+                    self.selection.retain(|id| !group.members.contains(id));
+                    if self
+                        .selected_node
+                        .is_some_and(|id| group.members.contains(&id))
+                    {
+                        self.selected_node = None;
+                    }
+                }
+            }
+            Some(GroupAction::Rename(i, title)) => self.groups[i].title = title,
+            Some(GroupAction::Ungroup(i)) => {
+                self.groups.remove(i);
+            }
+            None => {}
+        }
+    }
+}
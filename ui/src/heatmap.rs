@@ -0,0 +1,64 @@
+use crate::app::SlayApp;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::NodeId;
+
+/// Queue depth that is always shown as fully hot; deeper queues elsewhere
+/// on the canvas raise the scale.
+const QUEUE_HEAT_FLOOR: f32 = 10.0;
+
+/// What node colours show instead of their kind.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Heatmap {
+    #[default]
+    Off,
+    Utilization,
+    QueueDepth,
+}
+
+impl Heatmap {
+    pub const ALL: [Heatmap; 3] = [Heatmap::Off, Heatmap::Utilization, Heatmap::QueueDepth];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Heatmap::Off => "Kind colours",
+            Heatmap::Utilization => "Utilization",
+            Heatmap::QueueDepth => "Queue depth",
+        }
+    }
+}
+
+/// Green when idle, through amber, to red when saturated.
+pub fn heat_color(heat: f32) -> egui::Color32 {
+    let heat = heat.clamp(0.0, 1.0);
+    if heat < 0.5 {
+        theme().success.lerp_to_gamma(theme().warn, heat * 2.0)
+    } else {
+        theme()
+            .warn
+            .lerp_to_gamma(theme().critical, (heat - 0.5) * 2.0)
+    }
+}
+
+impl SlayApp {
+    /// How hot node `id` is on the chosen heatmap, 0.0..=1.0, from its
+    /// latest sampled metrics. `None` with the heatmap off or before the
+    /// node has been sampled.
+    pub fn node_heat(&self, id: NodeId) -> Option<f32> {
+        let latest = |id: NodeId| self.metrics.node_history(id)?.back().copied();
+        match self.heatmap {
+            Heatmap::Off => None,
+            Heatmap::Utilization => Some(latest(id)?.utilization),
+            Heatmap::QueueDepth => {
+                let depth = latest(id)?.queue_depth as f32;
+                let deepest = self
+                    .node_states
+                    .keys()
+                    .filter_map(|&other| latest(other))
+                    .map(|p| p.queue_depth as f32)
+                    .fold(QUEUE_HEAT_FLOOR, f32::max);
+                Some(depth / deepest)
+            }
+        }
+    }
+}
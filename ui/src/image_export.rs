@@ -0,0 +1,632 @@
+use crate::app::SlayApp;
+use crate::components::get_view;
+use crate::theme::theme;
+use eframe::egui;
+use egui::{Align2, Color32, Pos2};
+use slay_core::NodeId;
+use std::fmt::Write as _;
+
+/// Node size on the canvas, in world units.
+const NODE_SIZE: egui::Vec2 = egui::vec2(180.0, 90.0);
+/// Empty space kept around the drawing, in world units.
+const MARGIN: f32 = 40.0;
+/// Pixels per world unit in PNG output, so text stays sharp on slides.
+const PNG_SCALE: f32 = 2.0;
+/// Longest PNG side; larger designs are scaled down to fit.
+const MAX_PNG_SIDE: f32 = 8192.0;
+const FONT_FAMILY: &str = "Ubuntu, 'Segoe UI', Helvetica, Arial, sans-serif";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn mime(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+enum Shape {
+    Rect {
+        rect: egui::Rect,
+        rounding: f32,
+        fill: Color32,
+        stroke: (f32, Color32),
+    },
+    Curve {
+        points: [Pos2; 4],
+        width: f32,
+        color: Color32,
+    },
+    Circle {
+        center: Pos2,
+        radius: f32,
+        fill: Color32,
+    },
+    Text {
+        pos: Pos2,
+        anchor: Align2,
+        text: String,
+        size: f32,
+        color: Color32,
+    },
+}
+
+/// The design drawn in world units with the origin at the top-left margin,
+/// independent of the screen, zoom and pan.
+pub struct Scene {
+    size: egui::Vec2,
+    background: Color32,
+    shapes: Vec<Shape>,
+}
+
+impl SlayApp {
+    /// Lays out every group, connection and node for an image; `None` on an
+    /// empty canvas. `with_stats` adds the latest load of nodes and edges.
+    pub fn scene(&self, with_stats: bool) -> Option<Scene> {
+        let theme = theme();
+        let mut ids: Vec<NodeId> = self.node_states.keys().copied().collect();
+        ids.sort();
+        let bounds = self.world_bounds(&ids, true)?;
+        let origin = bounds.min - egui::vec2(MARGIN, MARGIN);
+        let at = |p: Pos2| (p - origin).to_pos2();
+        let mut shapes = Vec::new();
+
+        for group in &self.groups {
+            let rect = group.rect().translate(-origin.to_vec2());
+            let (fill, stroke) = if group.collapsed {
+                (theme.panel, theme.accent.gamma_multiply(0.6))
+            } else {
+                (
+                    theme.accent.gamma_multiply(0.04),
+                    theme.accent.gamma_multiply(0.35),
+                )
+            };
+            shapes.push(Shape::Rect {
+                rect,
+                rounding: 6.0,
+                fill,
+                stroke: (1.0, stroke),
+            });
+            shapes.push(Shape::Text {
+                pos: rect.left_top() + egui::vec2(10.0, 6.0),
+                anchor: Align2::LEFT_TOP,
+                text: group.title.clone(),
+                size: 12.0,
+                color: theme.text,
+            });
+            if group.collapsed {
+                shapes.push(Shape::Text {
+                    pos: rect.center() + egui::vec2(0.0, 12.0),
+                    anchor: Align2::CENTER_CENTER,
+                    text: format!("{} nodes", group.members.len()),
+                    size: 11.0,
+                    color: theme.text_dim,
+                });
+            }
+        }
+
+        let mut sources: Vec<_> = self.simulation.components.iter().collect();
+        sources.sort_by_key(|(id, _)| **id);
+        for (&id, comp) in sources {
+            for target in comp.get_targets() {
+                let (Some(source), Some(dest)) = (self.node_anchor(id), self.node_anchor(target))
+                else {
+                    continue;
+                };
+                if source == dest && self.collapsed_group_of(id).is_some() {
+                    continue;
+                }
+                let p1 = at(source + egui::vec2(NODE_SIZE.x, NODE_SIZE.y / 2.0));
+                let p2 = at(dest + egui::vec2(0.0, NODE_SIZE.y / 2.0));
+                let cp_offset = (p2.x - p1.x).abs().max(40.0) * 0.5;
+                let points = [
+                    p1,
+                    p1 + egui::vec2(cp_offset, 0.0),
+                    p2 - egui::vec2(cp_offset, 0.0),
+                    p2,
+                ];
+                let traffic = self
+                    .metrics
+                    .edge_history(id, target)
+                    .and_then(|h| h.back().copied())
+                    .filter(|_| with_stats)
+                    .unwrap_or_default();
+                let mut color = theme.edge;
+                if traffic.rps > 0.0 && traffic.drop_rps > 0.0 {
+                    color = color.lerp_to_gamma(theme.critical, traffic.drop_rps / traffic.rps);
+                }
+                shapes.push(Shape::Curve {
+                    points,
+                    width: 1.5 + (traffic.rps / 50.0).min(3.0),
+                    color,
+                });
+                if traffic.rps > 0.0 {
+                    // Midpoint of the cubic curve
+                    let mid = ((p1.to_vec2() + p2.to_vec2()) / 8.0
+                        + (points[1].to_vec2() + points[2].to_vec2()) * 3.0 / 8.0)
+                        .to_pos2();
+                    shapes.push(Shape::Text {
+                        pos: mid - egui::vec2(0.0, 4.0),
+                        anchor: Align2::CENTER_BOTTOM,
+                        text: format!("{:.0} rps", traffic.rps),
+                        size: 10.0,
+                        color: theme.text_dim,
+                    });
+                }
+            }
+        }
+
+        for &id in &ids {
+            if self.collapsed_group_of(id).is_some() {
+                continue;
+            }
+            let (Some(comp), Some(state)) = (
+                self.simulation.components.get(&id),
+                self.node_states.get(&id),
+            ) else {
+                continue;
+            };
+            let rect =
+                egui::Rect::from_min_size(at(egui::pos2(state.pos.x, state.pos.y)), NODE_SIZE);
+            let view = get_view(comp.kind());
+            let base = view.map_or(theme.custom, |v| v.color());
+            let healthy = comp.is_healthy();
+            let border = if healthy {
+                base.gamma_multiply(0.5)
+            } else {
+                theme.critical
+            };
+            shapes.push(Shape::Rect {
+                rect,
+                rounding: 6.0,
+                fill: if healthy {
+                    base.gamma_multiply(0.1)
+                } else {
+                    theme.inactive
+                },
+                stroke: (1.5, border),
+            });
+            shapes.push(Shape::Text {
+                pos: rect.left_top() + egui::vec2(10.0, 15.0),
+                anchor: Align2::LEFT_TOP,
+                text: comp.name().to_uppercase(),
+                size: 11.0,
+                color: theme.text_dim,
+            });
+            shapes.push(Shape::Text {
+                pos: rect.center(),
+                anchor: Align2::CENTER_CENTER,
+                text: view.map_or(comp.kind(), |v| v.name()).to_string(),
+                size: 16.0,
+                color: theme.text,
+            });
+            shapes.push(Shape::Circle {
+                center: rect.left_center(),
+                radius: 4.5,
+                fill: theme.edge,
+            });
+            shapes.push(Shape::Circle {
+                center: rect.right_center(),
+                radius: 4.5,
+                fill: border,
+            });
+
+            if !with_stats {
+                continue;
+            }
+            if let Some(point) = self.metrics.node_history(id).and_then(|h| h.back()) {
+                shapes.push(Shape::Text {
+                    pos: rect.center() + egui::vec2(0.0, 20.0),
+                    anchor: Align2::CENTER_CENTER,
+                    text: format!(
+                        "{:.0} RPS · {:.0}% busy",
+                        point.rps,
+                        point.utilization * 100.0
+                    ),
+                    size: 10.0,
+                    color: theme.text_dim,
+                });
+                if point.queue_depth > 0 {
+                    shapes.push(Shape::Text {
+                        pos: rect.right_top() + egui::vec2(-10.0, 15.0),
+                        anchor: Align2::RIGHT_TOP,
+                        text: format!("Q: {}", point.queue_depth),
+                        size: 11.0,
+                        color: theme.warn,
+                    });
+                }
+            }
+            let errors = comp.error_count();
+            if errors > 0 {
+                shapes.push(Shape::Text {
+                    pos: rect.right_bottom() - egui::vec2(10.0, 10.0),
+                    anchor: Align2::RIGHT_BOTTOM,
+                    text: format!("! {} ERR", errors),
+                    size: 11.0,
+                    color: theme.critical,
+                });
+            }
+        }
+
+        Some(Scene {
+            size: bounds.size() + egui::vec2(MARGIN, MARGIN) * 2.0,
+            background: theme.bg,
+            shapes,
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_image(&mut self, format: ImageFormat, with_stats: bool) {
+        let Some(bytes) = self.render_image(format, with_stats) else {
+            return;
+        };
+        let extension = format.extension();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(extension.to_uppercase(), &[extension])
+            .set_file_name(format!("topology.{}", extension))
+            .save_file()
+        else {
+            return;
+        };
+        let path = path.with_extension(extension);
+        match std::fs::write(&path, bytes) {
+            Ok(()) => log::info!("Wrote {}", path.display()),
+            Err(err) => log::error!("Failed to write {}: {}", path.display(), err),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn export_image(&mut self, format: ImageFormat, with_stats: bool) {
+        if let Some(bytes) = self.render_image(format, with_stats) {
+            let name = format!("topology.{}", format.extension());
+            crate::export::save_bytes(&name, format.mime(), &bytes);
+        }
+    }
+
+    fn render_image(&self, format: ImageFormat, with_stats: bool) -> Option<Vec<u8>> {
+        let scene = self.scene(with_stats)?;
+        let result = match format {
+            ImageFormat::Svg => Ok(scene.to_svg().into_bytes()),
+            ImageFormat::Png => scene.to_png(),
+        };
+        result
+            .map_err(|err| log::error!("Failed to render the image: {}", err))
+            .ok()
+    }
+}
+
+impl Scene {
+    pub fn to_svg(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{f}">"#,
+            w = self.size.x.ceil(),
+            h = self.size.y.ceil(),
+            f = FONT_FAMILY
+        );
+        let _ = writeln!(
+            out,
+            r#"<rect width="100%" height="100%" {}/>"#,
+            svg_paint("fill", self.background)
+        );
+        for shape in &self.shapes {
+            let _ = match shape {
+                Shape::Rect {
+                    rect,
+                    rounding,
+                    fill,
+                    stroke,
+                } => writeln!(
+                    out,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {} {} stroke-width="{}"/>"#,
+                    rect.min.x,
+                    rect.min.y,
+                    rect.width(),
+                    rect.height(),
+                    rounding,
+                    svg_paint("fill", *fill),
+                    svg_paint("stroke", stroke.1),
+                    stroke.0
+                ),
+                Shape::Curve {
+                    points: [a, b, c, d],
+                    width,
+                    color,
+                } => writeln!(
+                    out,
+                    r#"<path d="M {} {} C {} {}, {} {}, {} {}" fill="none" {} stroke-width="{}"/>"#,
+                    a.x,
+                    a.y,
+                    b.x,
+                    b.y,
+                    c.x,
+                    c.y,
+                    d.x,
+                    d.y,
+                    svg_paint("stroke", *color),
+                    width
+                ),
+                Shape::Circle {
+                    center,
+                    radius,
+                    fill,
+                } => writeln!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                    center.x,
+                    center.y,
+                    radius,
+                    svg_paint("fill", *fill)
+                ),
+                Shape::Text {
+                    pos,
+                    anchor,
+                    text,
+                    size,
+                    color,
+                } => {
+                    let text_anchor = match anchor.x() {
+                        egui::Align::Min => "start",
+                        egui::Align::Center => "middle",
+                        egui::Align::Max => "end",
+                    };
+                    let baseline = match anchor.y() {
+                        egui::Align::Min => "hanging",
+                        egui::Align::Center => "central",
+                        egui::Align::Max => "text-after-edge",
+                    };
+                    writeln!(
+                        out,
+                        r#"<text x="{}" y="{}" font-size="{}" text-anchor="{}" dominant-baseline="{}" {}>{}</text>"#,
+                        pos.x,
+                        pos.y,
+                        size,
+                        text_anchor,
+                        baseline,
+                        svg_paint("fill", *color),
+                        xml_escape(text)
+                    )
+                }
+            };
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// Rasterises the scene without a GPU, using egui's default font.
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let scale = PNG_SCALE.min(MAX_PNG_SIDE / self.size.x.max(self.size.y));
+        let width = (self.size.x * scale).ceil() as u32;
+        let height = (self.size.y * scale).ceil() as u32;
+        let mut pixmap = tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| format!("cannot allocate a {}x{} image", width, height))?;
+        pixmap.fill(skia_color(self.background));
+
+        let fonts = egui::FontDefinitions::default();
+        let font_data = fonts
+            .families
+            .get(&egui::FontFamily::Proportional)
+            .and_then(|names| names.first())
+            .and_then(|name| fonts.font_data.get(name))
+            .ok_or("no default font")?;
+        let font = ab_glyph::FontRef::try_from_slice_and_index(&font_data.font, font_data.index)
+            .map_err(|e| e.to_string())?;
+
+        let transform = tiny_skia::Transform::from_scale(scale, scale);
+        for shape in &self.shapes {
+            match shape {
+                Shape::Rect {
+                    rect,
+                    rounding,
+                    fill,
+                    stroke,
+                } => {
+                    if let Some(path) = rounded_rect(*rect, *rounding) {
+                        pixmap.fill_path(
+                            &path,
+                            &paint(*fill),
+                            tiny_skia::FillRule::Winding,
+                            transform,
+                            None,
+                        );
+                        pixmap.stroke_path(
+                            &path,
+                            &paint(stroke.1),
+                            &line(stroke.0),
+                            transform,
+                            None,
+                        );
+                    }
+                }
+                Shape::Curve {
+                    points: [a, b, c, d],
+                    width,
+                    color,
+                } => {
+                    let mut pb = tiny_skia::PathBuilder::new();
+                    pb.move_to(a.x, a.y);
+                    pb.cubic_to(b.x, b.y, c.x, c.y, d.x, d.y);
+                    if let Some(path) = pb.finish() {
+                        pixmap.stroke_path(&path, &paint(*color), &line(*width), transform, None);
+                    }
+                }
+                Shape::Circle {
+                    center,
+                    radius,
+                    fill,
+                } => {
+                    if let Some(path) =
+                        tiny_skia::PathBuilder::from_circle(center.x, center.y, *radius)
+                    {
+                        pixmap.fill_path(
+                            &path,
+                            &paint(*fill),
+                            tiny_skia::FillRule::Winding,
+                            transform,
+                            None,
+                        );
+                    }
+                }
+                Shape::Text {
+                    pos,
+                    anchor,
+                    text,
+                    size,
+                    color,
+                } => draw_text(
+                    &mut pixmap,
+                    &font,
+                    (pos.to_vec2() * scale).to_pos2(),
+                    *anchor,
+                    text,
+                    size * scale,
+                    *color,
+                ),
+            }
+        }
+
+        let rgba: Vec<u8> = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|p| {
+                let c = p.demultiply();
+                [c.red(), c.green(), c.blue(), c.alpha()]
+            })
+            .collect();
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(&rgba).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+fn svg_paint(attribute: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    format!(
+        r#"{attribute}="rgb({r},{g},{b})" {attribute}-opacity="{:.3}""#,
+        a as f32 / 255.0
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn skia_color(color: Color32) -> tiny_skia::Color {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    tiny_skia::Color::from_rgba8(r, g, b, a)
+}
+
+fn paint(color: Color32) -> tiny_skia::Paint<'static> {
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(skia_color(color));
+    paint.anti_alias = true;
+    paint
+}
+
+fn line(width: f32) -> tiny_skia::Stroke {
+    tiny_skia::Stroke {
+        width,
+        ..Default::default()
+    }
+}
+
+fn rounded_rect(rect: egui::Rect, radius: f32) -> Option<tiny_skia::Path> {
+    let r = radius.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    let (l, t, rt, b) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let mut pb = tiny_skia::PathBuilder::new();
+    pb.move_to(l + r, t);
+    pb.line_to(rt - r, t);
+    pb.quad_to(rt, t, rt, t + r);
+    pb.line_to(rt, b - r);
+    pb.quad_to(rt, b, rt - r, b);
+    pb.line_to(l + r, b);
+    pb.quad_to(l, b, l, b - r);
+    pb.line_to(l, t + r);
+    pb.quad_to(l, t, l + r, t);
+    pb.close();
+    pb.finish()
+}
+
+/// Draws one line of text with its `anchor` point at `pos`, in pixels.
+fn draw_text(
+    pixmap: &mut tiny_skia::Pixmap,
+    font: &ab_glyph::FontRef,
+    pos: Pos2,
+    anchor: Align2,
+    text: &str,
+    size: f32,
+    color: Color32,
+) {
+    use ab_glyph::{Font as _, ScaleFont as _};
+
+    let scaled = font.as_scaled(size);
+    let mut glyphs = Vec::new();
+    let mut x = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(prev) = previous {
+            x += scaled.kern(prev, id);
+        }
+        glyphs.push((id, x));
+        x += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    let height = scaled.ascent() - scaled.descent();
+    let left = pos.x - anchor.x().to_factor() * x;
+    let baseline = pos.y - anchor.y().to_factor() * height + scaled.ascent();
+
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let (width, pixmap_height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let pixels = pixmap.pixels_mut();
+    for (id, offset) in glyphs {
+        let glyph = id.with_scale_and_position(size, ab_glyph::point(left + offset, baseline));
+        let Some(outline) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outline.px_bounds();
+        outline.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= width || py >= pixmap_height {
+                return;
+            }
+            let pixel = &mut pixels[(py * width + px) as usize];
+            let alpha = coverage.clamp(0.0, 1.0) * a as f32 / 255.0;
+            let over = |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)) as u8;
+            let out_a = (alpha * 255.0 + pixel.alpha() as f32 * (1.0 - alpha)) as u8;
+            let premultiplied = |src: u8, dst: u8| over(src, dst).min(out_a);
+            if let Some(blended) = tiny_skia::PremultipliedColorU8::from_rgba(
+                premultiplied(r, pixel.red()),
+                premultiplied(g, pixel.green()),
+                premultiplied(b, pixel.blue()),
+                out_a,
+            ) {
+                *pixel = blended;
+            }
+        });
+    }
+}
@@ -1,39 +1,142 @@
+use crate::analytics::SparklineWidget;
+use crate::canvas::FitTarget;
 use crate::components::{get_view, ComponentView};
-use crate::theme::*;
+use crate::explain::Explain;
+use crate::theme::theme;
+use crate::widgets::SliderEntry;
 use eframe::egui;
-use slay_core::{NodeId, Simulation};
+use serde_json::Value;
+use slay_core::{
+    Brownout, EdgeMetricPoint, Health, KindMetricPoint, Link, MetricsCollector, NodeId,
+    NodeMetricPoint, ScenarioAction, Server, Simulation,
+};
+use std::collections::BTreeSet;
 
+/// The config of the selected node, or nodes, as the inspector edits it.
+///
+/// Widgets work on `buffer`, read from the components once rather than every
+/// frame. The edit reaches the components as a `SetConfig` of the changed
+/// fields per node when the pointer is released, so dragging a slider does
+/// not re-encode configs or take the components' locks on every frame.
+pub struct ConfigEdit {
+    /// The nodes edited together; `base` is the first one's config
+    node_ids: Vec<NodeId>,
+    /// `Simulation::config_revision` when `base` was read
+    revision: u64,
+    /// The config as the component has it
+    base: Value,
+    buffer: Value,
+    /// `buffer` holds changes not yet applied
+    dirty: bool,
+    /// Fields whose values differ between the nodes
+    differing: Vec<String>,
+}
+
+impl ConfigEdit {
+    fn read(simulation: &Simulation, node_ids: &[NodeId]) -> Option<Self> {
+        let configs: Vec<Value> = node_ids
+            .iter()
+            .filter_map(|id| simulation.components.get(id))
+            .map(|comp| comp.encode_config())
+            .collect();
+        let base = configs.first()?.clone();
+        let differing = base
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(k, v)| {
+                k.as_str() != "generation_id" && configs.iter().any(|c| c.get(k) != Some(v))
+            })
+            .map(|(k, _)| k.clone())
+            .collect();
+        Some(Self {
+            node_ids: node_ids.to_vec(),
+            revision: simulation.config_revision,
+            buffer: base.clone(),
+            base,
+            dirty: false,
+            differing,
+        })
+    }
+
+    /// Whether the buffer still reflects `node_ids` in `simulation`. An
+    /// unapplied edit is kept even if the config changed meanwhile.
+    fn is_current(&self, simulation: &Simulation, node_ids: &[NodeId]) -> bool {
+        self.node_ids == node_ids && (self.dirty || self.revision == simulation.config_revision)
+    }
+
+    /// The fields changed since the last commit, as an action per node.
+    fn commit(&mut self) -> Vec<ScenarioAction> {
+        self.dirty = false;
+        let Some(ScenarioAction::SetConfig { config, .. }) =
+            ScenarioAction::config_change(self.node_ids[0], &self.base, &self.buffer)
+        else {
+            return Vec::new();
+        };
+        self.base = self.buffer.clone();
+        self.node_ids
+            .iter()
+            .map(|&node_id| ScenarioAction::SetConfig {
+                node_id,
+                config: config.clone(),
+            })
+            .collect()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render_inspector(
     ui: &mut egui::Ui,
     simulation: &mut Simulation,
     selected_node: &mut Option<NodeId>,
     selected_edge: &mut Option<(NodeId, NodeId)>,
     node_states: &mut std::collections::HashMap<NodeId, crate::app::NodeVisualState>,
-    should_fit_to_view: &mut bool,
+    fit_request: &mut Option<FitTarget>,
+    metrics: &mut MetricsCollector,
+    config_edit: &mut Option<ConfigEdit>,
 ) {
-    ui.add_space(15.0);
-    ui.horizontal(|ui| {
-        ui.heading("Properties");
-        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            if ui.button("⛶ Fit").clicked() {
-                *should_fit_to_view = true;
-            }
-        });
-    });
-    ui.add_space(10.0);
+    render_header(ui, selected_node.is_some(), fit_request);
 
     let mut to_remove = None;
     let mut edge_to_remove = None;
-    let mut pending_cmds = Vec::new();
+    let mut configure = None;
+    let mut health_changed = None;
+    let mut chaos = None;
+    let mut annotation = None;
+    let mut recorded = Vec::new();
 
     if let Some(id) = *selected_node {
+        if !config_edit
+            .as_ref()
+            .is_some_and(|edit| edit.is_current(simulation, &[id]))
+        {
+            *config_edit = ConfigEdit::read(simulation, &[id]);
+        }
+        let brownout = simulation.brownout(id).copied();
+        let health = simulation.health(id);
+        let now = simulation.time;
         if let Some(comp) = simulation.components.get_mut(&id) {
             // Header
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new(format!("#{} {}", id, comp.name())).strong());
+                ui.label(egui::RichText::new(format!("#{}", id)).strong());
+                let mut name = comp.name().to_string();
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut name)
+                        .desired_width(140.0)
+                        .hint_text(comp.kind()),
+                );
+                if response.changed() {
+                    comp.set_name(&name);
+                }
+                // An empty name falls back to the kind's label once editing ends
+                if response.lost_focus() && name.trim().is_empty() {
+                    let fallback = get_view(comp.kind())
+                        .map_or_else(|| comp.kind().to_string(), |v| v.name().to_string());
+                    comp.set_name(&fallback);
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui
-                        .button(egui::RichText::new("🗑").color(COLOR_CRITICAL))
+                        .button(egui::RichText::new("🗑").color(theme().critical))
                         .on_hover_text("Delete Node")
                         .clicked()
                     {
@@ -44,12 +147,12 @@ pub fn render_inspector(
             ui.separator();
             ui.add_space(10.0);
 
-            // Hot Update Logic
-            if let Some(view) = get_view(comp.kind()) {
-                let mut config_json = comp.encode_config();
+            // Hot Update Logic: applied once the drag or keystroke is over
+            if let (Some(view), Some(edit)) = (get_view(comp.kind()), config_edit.as_mut()) {
                 let v: &dyn ComponentView = view;
-                if v.render_inspector(ui, &mut config_json) {
-                    pending_cmds = comp.apply_config(config_json, id);
+                edit.dirty |= v.render_inspector(ui, &mut edit.buffer);
+                if edit.dirty && !ui.ctx().is_using_pointer() {
+                    configure = edit.commit().pop();
                 }
             }
 
@@ -61,8 +164,14 @@ pub fn render_inspector(
                 egui::RichText::new("CHAOS ENGINEERING")
                     .small()
                     .strong()
-                    .color(COLOR_WARN),
+                    .color(theme().warn),
             );
+            let health_col = match health {
+                Health::Healthy => theme().success,
+                Health::Degraded { .. } => theme().warn,
+                Health::Down => theme().critical,
+            };
+            ui.label(egui::RichText::new(format!("● {}", health.label())).color(health_col));
 
             let is_healthy = comp.is_healthy();
             let btn_text = if is_healthy {
@@ -71,9 +180,9 @@ pub fn render_inspector(
                 "🟢 REVIVE"
             };
             let btn_col = if is_healthy {
-                COLOR_CRITICAL
+                theme().critical
             } else {
-                COLOR_SUCCESS
+                theme().success
             };
 
             if ui
@@ -83,19 +192,23 @@ pub fn render_inspector(
                 )
                 .clicked()
             {
-                comp.set_healthy(!is_healthy);
-                if !is_healthy {
-                    let current_conf = comp.encode_config();
-                    pending_cmds.extend(comp.apply_config(current_conf, id));
-                }
+                health_changed = Some(id);
             }
+            chaos = render_brownout(ui, id, brownout, now);
+        }
+        if simulation.components.contains_key(&id) {
+            render_node_history(ui, metrics, id);
+            render_live_load(ui, simulation, metrics, id);
+            render_queueing(ui, simulation, metrics, id);
+            render_services(ui, simulation, id);
+            render_cost(ui, simulation, id);
         }
     } else if let Some((from, to)) = *selected_edge {
         ui.horizontal(|ui| {
             ui.label(egui::RichText::new(format!("Edge {} <-> {}", from, to)).strong());
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 if ui
-                    .button(egui::RichText::new("🗑").color(COLOR_CRITICAL))
+                    .button(egui::RichText::new("🗑").color(theme().critical))
                     .on_hover_text("Delete Edge")
                     .clicked()
                 {
@@ -105,14 +218,16 @@ pub fn render_inspector(
         });
         ui.separator();
 
+        if let Some(action) = render_route_probability(ui, simulation, from, to) {
+            configure = Some(action);
+        }
+
         let link_key = slay_core::canonical_key(from, to);
 
         // Link might not exist yet if created implicitly, but usually canvas creates it?
         // We need to ensure it exists if we selected it.
-        let link = simulation
-            .links
-            .entry(link_key)
-            .or_insert(slay_core::Link::default());
+        let link = simulation.links.entry(link_key).or_default();
+        let before = (link.min_to_max, link.max_to_min);
 
         // Check if data is actually asymmetric to initialize UI state correctly
         let data_is_asymmetric = link.min_to_max != link.max_to_min;
@@ -128,7 +243,7 @@ pub fn render_inspector(
                     egui::RichText::new(format!("Forward Path ({} -> {})", from, to))
                         .strong()
                         .small()
-                        .color(COLOR_ACCENT),
+                        .color(theme().accent),
                 );
                 let edge = link.get_config_mut(from, to);
                 render_edge_config(ui, edge);
@@ -159,7 +274,7 @@ pub fn render_inspector(
                     egui::RichText::new(format!("Return Path ({} -> {})", to, from))
                         .strong()
                         .small()
-                        .color(COLOR_ACCENT),
+                        .color(theme().accent),
                 );
                 let edge = link.get_config_mut(to, from);
                 render_edge_config(ui, edge);
@@ -175,7 +290,7 @@ pub fn render_inspector(
                     egui::RichText::new(format!("Return Path ({} -> {}) [Synced]", to, from))
                         .strong()
                         .small()
-                        .color(COLOR_ACCENT),
+                        .color(theme().accent),
                 );
 
                 ui.add_enabled_ui(false, |ui| {
@@ -185,6 +300,29 @@ pub fn render_inspector(
             });
         }
 
+        if (link.min_to_max, link.max_to_min) != before {
+            annotation = Some(format!("Edge {} <-> {}", from, to));
+            let old = Link {
+                min_to_max: before.0,
+                max_to_min: before.1,
+            };
+            for (a, b) in [(from, to), (to, from)] {
+                let edge = *link.get_config(a, b);
+                if edge != *old.get_config(a, b) {
+                    recorded.push(ScenarioAction::SetEdge {
+                        from: a,
+                        to: b,
+                        edge,
+                    });
+                }
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.separator();
+        render_edge_traffic(ui, metrics, from, to);
+        render_edge_traffic(ui, metrics, to, from);
+
         ui.add_space(10.0);
         ui.small(
             egui::RichText::new(
@@ -196,16 +334,37 @@ pub fn render_inspector(
     } else {
         ui.vertical_centered(|ui| {
             ui.add_space(50.0);
-            ui.label(egui::RichText::new("Select a node\nto see properties").color(COLOR_TEXT_DIM));
+            ui.label(
+                egui::RichText::new("Select a node\nto see properties").color(theme().text_dim),
+            );
         });
+        render_fleet(ui, metrics);
     }
 
-    // Schedule commands
-    for cmd in pending_cmds {
-        simulation.schedule(simulation.time + cmd.delay, cmd.node_id, cmd.event_type);
+    if let Some(action) = configure {
+        annotation = Some(format!("Configure #{}", action.node_ids()[0]));
+        simulation.apply_action(action.clone());
+        recorded.push(action);
+    }
+
+    if let Some(text) = annotation {
+        metrics.annotate(simulation.time, text);
+    }
+    for action in recorded {
+        simulation.record_action(action);
+    }
+
+    if let Some(id) = health_changed {
+        toggle_health(simulation, metrics, id);
+    }
+    if let Some(action) = chaos {
+        metrics.annotate(simulation.time, action.describe());
+        simulation.apply_action(action.clone());
+        simulation.record_action(action);
     }
 
     if let Some(id) = to_remove {
+        metrics.annotate(simulation.time, format!("Remove #{}", id));
         simulation.remove_node(id);
         node_states.remove(&id);
         *selected_node = None;
@@ -220,16 +379,706 @@ pub fn render_inspector(
     }
 }
 
+fn render_header(ui: &mut egui::Ui, has_selection: bool, fit_request: &mut Option<FitTarget>) {
+    ui.add_space(15.0);
+    ui.horizontal(|ui| {
+        ui.heading("Properties");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(has_selection, egui::Button::new("⌖ Selection"))
+                .on_hover_text("Zoom to the selected nodes (Shift+F)")
+                .clicked()
+            {
+                *fit_request = Some(FitTarget::Selection);
+            }
+            if ui
+                .button("⛶ Fit")
+                .on_hover_text("Fit every node into view (F)")
+                .clicked()
+            {
+                *fit_request = Some(FitTarget::All);
+            }
+        });
+    });
+    ui.add_space(10.0);
+}
+
+/// Inspector for a multi-selection: one config editor for several nodes of
+/// the same kind. It shows the first node's values; a changed field is
+/// written to every node once the pointer is released, and the others keep
+/// their own values.
+pub fn render_bulk_inspector(
+    ui: &mut egui::Ui,
+    simulation: &mut Simulation,
+    metrics: &mut MetricsCollector,
+    fit_request: &mut Option<FitTarget>,
+    ids: &BTreeSet<NodeId>,
+    config_edit: &mut Option<ConfigEdit>,
+) {
+    render_header(ui, true, fit_request);
+    let kinds: BTreeSet<&str> = ids
+        .iter()
+        .filter_map(|id| simulation.components.get(id))
+        .map(|comp| comp.kind())
+        .collect();
+    let (Some(&kind), 1) = (kinds.first(), kinds.len()) else {
+        ui.label(egui::RichText::new(format!("{} nodes selected", ids.len())).strong());
+        ui.label(
+            egui::RichText::new(format!(
+                "Mixed kinds ({}). Select nodes of one kind to edit them together.",
+                kinds.into_iter().collect::<Vec<_>>().join(", ")
+            ))
+            .color(theme().text_dim),
+        );
+        return;
+    };
+    let kind = kind.to_string();
+    let Some(view) = get_view(&kind) else {
+        return;
+    };
+    ui.label(egui::RichText::new(format!("{} × {}", ids.len(), view.name())).strong());
+    ui.label(
+        egui::RichText::new(
+            ids.iter()
+                .map(|id| format!("#{}", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+        .small()
+        .color(theme().text_dim),
+    );
+    ui.separator();
+    ui.add_space(10.0);
+
+    let ids: Vec<NodeId> = ids.iter().copied().collect();
+    if !config_edit
+        .as_ref()
+        .is_some_and(|edit| edit.is_current(simulation, &ids))
+    {
+        *config_edit = ConfigEdit::read(simulation, &ids);
+    }
+    let Some(edit) = config_edit.as_mut() else {
+        return;
+    };
+    if !edit.differing.is_empty() {
+        ui.label(
+            egui::RichText::new(format!(
+                "Showing #{}; values differ for {}",
+                ids[0],
+                edit.differing.join(", ")
+            ))
+            .small()
+            .color(theme().warn),
+        );
+        ui.add_space(6.0);
+    }
+
+    edit.dirty |= view.render_inspector(ui, &mut edit.buffer);
+    if !edit.dirty || ui.ctx().is_using_pointer() {
+        return;
+    }
+    let actions = edit.commit();
+    if actions.is_empty() {
+        return;
+    }
+    for action in actions {
+        simulation.apply_action(action.clone());
+        simulation.record_action(action);
+    }
+    metrics.annotate(
+        simulation.time,
+        format!("Configure {} × {}", ids.len(), view.name()),
+    );
+}
+
+/// Degradation controls: slow the node down and make it flaky for a while
+/// without killing it, or restore it. Returns the action to apply.
+fn render_brownout(
+    ui: &mut egui::Ui,
+    id: NodeId,
+    current: Option<Brownout>,
+    now: u64,
+) -> Option<ScenarioAction> {
+    ui.add_space(6.0);
+    if let Some(brownout) = current {
+        let mut status = format!("Slowed ×{:.1}", brownout.slowdown);
+        if brownout.extra_latency_us > 0 {
+            status += &format!(" +{:.0}ms", brownout.extra_latency_us as f64 / 1000.0);
+        }
+        if brownout.error_rate > 0.0 {
+            status += &format!(", {:.0}% errors", brownout.error_rate * 100.0);
+        }
+        if let Some(until) = brownout.until_us {
+            status += &format!(", {:.1}s left", until.saturating_sub(now) as f64 / 1e6);
+        }
+        ui.label(egui::RichText::new(status).color(theme().warn));
+        let restore = ui
+            .add(
+                egui::Button::new(egui::RichText::new("⏩ RESTORE SPEED").strong())
+                    .fill(theme().success.gamma_multiply(0.2)),
+            )
+            .clicked();
+        return restore.then_some(ScenarioAction::ClearLatency { node_id: id });
+    }
+
+    // Draft kept per node between frames: slowdown, extra ms, error %,
+    // duration s
+    let form_id = egui::Id::new("brownout_form").with(id);
+    let (mut slowdown, mut extra_ms, mut error_pct, mut duration_s) = ui
+        .data(|d| d.get_temp::<(f32, f32, f32, f32)>(form_id))
+        .unwrap_or((3.0, 0.0, 0.0, 10.0));
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::DragValue::new(&mut slowdown)
+                .range(1.0..=50.0)
+                .speed(0.1)
+                .prefix("×"),
+        )
+        .on_hover_text("Service time multiplier");
+        ui.add(
+            egui::DragValue::new(&mut extra_ms)
+                .range(0.0..=10_000.0)
+                .speed(1.0)
+                .prefix("+")
+                .suffix("ms"),
+        )
+        .on_hover_text("Fixed latency added to every request");
+        ui.add(
+            egui::DragValue::new(&mut error_pct)
+                .range(0.0..=100.0)
+                .speed(0.5)
+                .suffix("% err"),
+        )
+        .on_hover_text("Share of requests that fail while degraded");
+        ui.add(
+            egui::DragValue::new(&mut duration_s)
+                .range(0.0..=3_600.0)
+                .speed(0.1)
+                .suffix("s"),
+        )
+        .on_hover_text("How long it lasts; 0 until restored");
+    });
+    ui.data_mut(|d| d.insert_temp(form_id, (slowdown, extra_ms, error_pct, duration_s)));
+    let slow = ui
+        .add(
+            egui::Button::new(egui::RichText::new("🐌 SLOW DOWN").strong())
+                .fill(theme().warn.gamma_multiply(0.2)),
+        )
+        .on_hover_text("Degrade: the node keeps serving, only slower and less reliably")
+        .clicked();
+    slow.then(|| ScenarioAction::InjectLatency {
+        node_id: id,
+        slowdown,
+        extra_latency_us: (extra_ms as f64 * 1000.0) as u64,
+        error_rate: error_pct / 100.0,
+        duration_us: (duration_s > 0.0).then_some((duration_s as f64 * 1_000_000.0) as u64),
+    })
+}
+
+/// Kills a healthy node or revives a dead one. Reviving re-applies the
+/// config, which restarts client traffic.
+pub fn toggle_health(simulation: &mut Simulation, metrics: &mut MetricsCollector, id: NodeId) {
+    let Some(comp) = simulation.components.get_mut(&id) else {
+        return;
+    };
+    let was_healthy = comp.is_healthy();
+    comp.set_healthy(!was_healthy);
+    let cmds = if was_healthy {
+        Vec::new()
+    } else {
+        let current_conf = comp.encode_config();
+        comp.apply_config(current_conf, id)
+    };
+    for cmd in cmds {
+        simulation.schedule(simulation.time + cmd.delay, cmd.node_id, cmd.event_type);
+    }
+    simulation.refresh_node(id);
+    simulation.record_action(if was_healthy {
+        ScenarioAction::Kill { node_id: id }
+    } else {
+        ScenarioAction::Revive { node_id: id }
+    });
+    let verb = if was_healthy { "Kill" } else { "Revive" };
+    metrics.annotate(simulation.time, format!("{} #{}", verb, id));
+}
+
+/// Every component kind combined, so fleet-wide load is visible without
+/// selecting nodes one by one.
+fn render_fleet(ui: &mut egui::Ui, metrics: &MetricsCollector) {
+    let kinds: Vec<String> = metrics.kinds().map(str::to_string).collect();
+    if kinds.is_empty() {
+        return;
+    }
+    ui.add_space(30.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("FLEET").small().strong());
+
+    for kind in kinds {
+        let Some(history) = metrics.kind_history(&kind) else {
+            continue;
+        };
+        let points: Vec<KindMetricPoint> = history.iter().copied().collect();
+        let last = points.last().copied().unwrap_or_default();
+
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(kind.to_uppercase()).small().strong());
+            let health_color = if last.healthy < last.nodes {
+                theme().warn
+            } else {
+                theme().text_dim
+            };
+            ui.label(
+                egui::RichText::new(format!("{}/{} healthy", last.healthy, last.nodes))
+                    .small()
+                    .color(health_color),
+            );
+        });
+        ui.add(
+            SparklineWidget::new(
+                "UTILIZATION",
+                &points,
+                |p| p.utilization * 100.0,
+                theme().accent,
+                format!(
+                    "{:.0}% (max {:.0}%)",
+                    last.utilization * 100.0,
+                    last.max_utilization * 100.0
+                ),
+            )
+            .with_line(
+                |p| p.max_utilization * 100.0,
+                theme().warn.gamma_multiply(0.6),
+            )
+            .with_markers(|p| p.sim_time_us, metrics.annotations()),
+        );
+        ui.add(
+            SparklineWidget::new(
+                "TRAFFIC",
+                &points,
+                |p| p.rps,
+                theme().success,
+                format!("{:.0} / {:.1} err", last.rps, last.error_rps),
+            )
+            .with_line(|p| p.error_rps, theme().critical)
+            .with_markers(|p| p.sim_time_us, metrics.annotations()),
+        );
+        if last.queue_depth > 0 {
+            ui.label(
+                egui::RichText::new(format!("{} queued", last.queue_depth))
+                    .small()
+                    .color(theme().text_dim),
+            );
+        }
+    }
+}
+
+/// Recent history of the selected node.
+fn render_node_history(ui: &mut egui::Ui, metrics: &MetricsCollector, id: NodeId) {
+    let Some(history) = metrics.node_history(id) else {
+        return;
+    };
+    let points: Vec<NodeMetricPoint> = history.iter().copied().collect();
+    let last = points.last().copied().unwrap_or_default();
+    let annotations = metrics.annotations();
+
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("HISTORY").small().strong());
+    ui.add(
+        SparklineWidget::new(
+            "RPS",
+            &points,
+            |p| p.rps,
+            theme().success,
+            format!("{:.0}/s", last.rps),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    ui.add(
+        SparklineWidget::new(
+            "ERRORS",
+            &points,
+            |p| p.error_rps,
+            theme().critical,
+            format!("{:.1}/s", last.error_rps),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    ui.add(
+        SparklineWidget::new(
+            "QUEUE",
+            &points,
+            |p| p.queue_depth as f32,
+            theme().warn,
+            format!("{}", last.queue_depth),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    ui.add(
+        SparklineWidget::new(
+            "UTILIZATION",
+            &points,
+            |p| p.utilization * 100.0,
+            theme().accent,
+            format!("{:.0}%", last.utilization * 100.0),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+}
+
+/// Live traffic history of one direction of an edge.
+fn render_edge_traffic(ui: &mut egui::Ui, metrics: &MetricsCollector, from: NodeId, to: NodeId) {
+    let Some(history) = metrics.edge_history(from, to) else {
+        return;
+    };
+    let points: Vec<EdgeMetricPoint> = history.iter().copied().collect();
+    let last = points.last().copied().unwrap_or_default();
+
+    ui.add_space(10.0);
+    ui.label(
+        egui::RichText::new(format!("TRAFFIC {} -> {}", from, to))
+            .small()
+            .strong(),
+    );
+    let annotations = metrics.annotations();
+    ui.add(
+        SparklineWidget::new(
+            "MESSAGES",
+            &points,
+            |p| p.rps,
+            theme().accent,
+            format!("{:.0}/s", last.rps),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    ui.add(
+        SparklineWidget::new(
+            "DROPS",
+            &points,
+            |p| p.drop_rps,
+            theme().critical,
+            format!("{:.1}/s", last.drop_rps),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    let retry_color = if last.is_retry_storm() {
+        theme().critical
+    } else {
+        theme().warn
+    };
+    ui.add(
+        SparklineWidget::new(
+            "RETRIES",
+            &points,
+            |p| p.retry_rps,
+            retry_color,
+            format!("{:.1}/s", last.retry_rps),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+    ui.add(
+        SparklineWidget::new(
+            "ADDED LATENCY",
+            &points,
+            |p| p.avg_latency_ms,
+            theme().warn,
+            format!("{:.1}ms", last.avg_latency_ms),
+        )
+        .with_markers(|p| p.sim_time_us, annotations),
+    );
+}
+
+/// How far back the simulated side of the queueing comparison averages.
+/// Utilization, ρ and Little's L of a server, recomputed every frame.
+fn render_live_load(
+    ui: &mut egui::Ui,
+    simulation: &Simulation,
+    metrics: &MetricsCollector,
+    id: NodeId,
+) {
+    let Some(load) = metrics.live_load(simulation, id) else {
+        return;
+    };
+
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("DERIVED").small().strong());
+
+    let rho = load.rho();
+    let rho_color = if rho >= 1.0 {
+        theme().critical
+    } else if rho >= 0.8 {
+        theme().warn
+    } else {
+        theme().text
+    };
+    egui::Grid::new(("live_load", id))
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Utilization")
+                .on_hover_text("Busy threads / concurrency, right now");
+            ui.label(format!(
+                "{} / {} ({:.0}%)",
+                load.busy,
+                load.workers,
+                load.utilization() * 100.0
+            ));
+            ui.end_row();
+
+            ui.label("ρ = λ / cμ").on_hover_text(
+                "Arrival rate over what the threads can finish at the configured \
+                 service time. At 1 or more the backlog only grows.",
+            );
+            ui.label(
+                egui::RichText::new(format!(
+                    "{:.0} / {:.0} rps = {:.2}",
+                    load.arrival_rps, load.capacity_rps, rho
+                ))
+                .color(rho_color),
+            );
+            ui.end_row();
+
+            ui.label("L = λW").on_hover_text(
+                "Little's law: requests held on average, queued or in service, \
+                 from the arrival rate and smoothed response time",
+            );
+            ui.label(
+                load.mean_in_system()
+                    .map_or("-".to_string(), |l| format!("{:.2} in flight", l)),
+            );
+            ui.end_row();
+        });
+}
+
+const QUEUEING_WINDOW_US: u64 = 10_000_000;
+
+/// M/M/c prediction next to what the simulation measured, for servers.
+fn render_queueing(
+    ui: &mut egui::Ui,
+    simulation: &Simulation,
+    metrics: &MetricsCollector,
+    id: NodeId,
+) {
+    let Some(check) = metrics.queueing_check(simulation, id, QUEUEING_WINDOW_US) else {
+        return;
+    };
+    let model = check.model;
+
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("QUEUEING MODEL").small().strong())
+        .on_hover_text(
+            "M/M/c prediction from the current arrival rate and configured service time. \
+             Differences come from what the model leaves out: near-constant service times, \
+             saturation slowdown, backlog limits, timeouts and retries.",
+        );
+    ui.label(
+        egui::RichText::new(format!(
+            "λ = {:.1}/s, S = {:.1}ms, c = {}",
+            model.arrival_rps,
+            model.service_us / 1000.0,
+            model.servers
+        ))
+        .small()
+        .color(theme().text_dim),
+    );
+
+    let unstable = || egui::RichText::new("unstable").color(theme().critical);
+    let value = |v: Option<f64>, fmt: fn(f64) -> String| match v {
+        Some(v) => egui::RichText::new(fmt(v)),
+        None => unstable(),
+    };
+    let percent = |v: f64| format!("{:.0}%", v * 100.0);
+    let count = |v: f64| format!("{:.2}", v);
+    let ms = |v: f64| format!("{:.1}ms", v / 1000.0);
+
+    egui::Grid::new(("queueing_model", id))
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.label(egui::RichText::new("M/M/c").small().color(theme().text_dim));
+            ui.label(
+                egui::RichText::new("Simulated")
+                    .small()
+                    .color(theme().text_dim),
+            );
+            ui.end_row();
+
+            ui.label("Utilization");
+            ui.label(egui::RichText::new(percent(model.utilization())).color(
+                if model.is_stable() {
+                    theme().text
+                } else {
+                    theme().critical
+                },
+            ));
+            ui.label(percent(check.utilization));
+            ui.end_row();
+
+            ui.label("Queue length");
+            ui.label(value(model.mean_queue_len(), count));
+            ui.label(count(check.queue_len));
+            ui.end_row();
+
+            ui.label("Response time");
+            ui.label(value(model.mean_response_us(), ms));
+            ui.label(check.response_us.map_or("-".to_string(), ms));
+            ui.end_row();
+        });
+}
+
+/// Comma-separated service membership, applied when the field loses focus.
+fn render_services(ui: &mut egui::Ui, simulation: &mut Simulation, id: NodeId) {
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("SERVICES").small().strong());
+
+    let text_id = egui::Id::new("node_services").with(id);
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(text_id))
+        .unwrap_or_else(|| simulation.services.services_of(id).join(", "));
+    let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text("e.g. checkout"));
+    if response.changed() {
+        ui.data_mut(|d| d.insert_temp(text_id, text.clone()));
+    }
+    if response.lost_focus() {
+        let wanted: Vec<&str> = text
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        for service in simulation.services.services_of(id) {
+            if !wanted.contains(&service.as_str()) {
+                simulation.deregister_service(&service, id);
+            }
+        }
+        for service in wanted {
+            simulation.register_service(service, id);
+        }
+        ui.data_mut(|d| d.remove::<String>(text_id));
+    }
+}
+
+fn render_cost(ui: &mut egui::Ui, simulation: &mut Simulation, id: NodeId) {
+    ui.add_space(20.0);
+    ui.separator();
+    ui.add_space(10.0);
+    ui.label(egui::RichText::new("COST").small().strong());
+
+    let mut rate = simulation.costs.rate(id);
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Instance");
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut rate.per_hour)
+                    .range(0.0..=1000.0)
+                    .speed(0.01)
+                    .prefix("$")
+                    .suffix("/h"),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Requests");
+        changed |= ui
+            .add(
+                egui::DragValue::new(&mut rate.per_million_requests)
+                    .range(0.0..=1000.0)
+                    .speed(0.01)
+                    .prefix("$")
+                    .suffix("/M"),
+            )
+            .changed();
+    });
+    if changed {
+        simulation.set_cost_rate(id, rate);
+    }
+    if !rate.is_zero() {
+        ui.label(
+            egui::RichText::new(format!("Accrued ${:.4}", simulation.node_cost(id)))
+                .small()
+                .color(theme().text_dim),
+        );
+    }
+}
+
+/// The chance that `from`, if it is a server, calls `to`. Returns the
+/// change once the slider is let go.
+fn render_route_probability(
+    ui: &mut egui::Ui,
+    simulation: &Simulation,
+    from: NodeId,
+    to: NodeId,
+) -> Option<ScenarioAction> {
+    let comp = simulation.components.get(&from)?;
+    let server = comp.downcast_ref::<Server>()?;
+    let index = server.targets.iter().position(|&t| t == to)?;
+    let mut shares = server
+        .config
+        .read()
+        .unwrap()
+        .route_shares(server.targets.len());
+
+    let id = egui::Id::new("route_probability").with((from, to));
+    let mut percent = ui
+        .data(|d| d.get_temp::<f32>(id))
+        .unwrap_or(shares[index] * 100.0);
+    ui.label(
+        egui::RichText::new("CALL PROBABILITY")
+            .strong()
+            .small()
+            .color(theme().accent),
+    );
+    if ui
+        .add(SliderEntry::new(&mut percent, 0.0..=100.0).suffix("%"))
+        .on_hover_text("Share of requests that call this target; the rest are answered here")
+        .explain("route_probabilities")
+        .changed()
+    {
+        ui.data_mut(|d| d.insert_temp(id, percent));
+    }
+    ui.add_space(10.0);
+    if ui.ctx().is_using_pointer() {
+        return None;
+    }
+    shares[index] = ui.data_mut(|d| d.remove_temp::<f32>(id))? / 100.0;
+    // The other targets make room rather than the total going over one
+    let others: f32 = shares.iter().sum::<f32>() - shares[index];
+    let room = 1.0 - shares[index];
+    if others > room {
+        for (i, share) in shares.iter_mut().enumerate() {
+            if i != index {
+                *share *= room / others;
+            }
+        }
+    }
+
+    let before = comp.encode_config();
+    let mut after = before.clone();
+    after["route_probabilities"] = serde_json::json!(shares);
+    ScenarioAction::config_change(from, &before, &after)
+}
+
 fn render_edge_config(ui: &mut egui::Ui, edge: &mut slay_core::EdgeConfig) {
     ui.horizontal(|ui| {
         ui.label("Latency");
         let mut latency_ms = edge.latency_us as f32 / 1000.0;
         if ui
             .add(
-                egui::Slider::new(&mut latency_ms, 0.0..=2000.0)
+                SliderEntry::new(&mut latency_ms, 0.0..=2000.0)
                     .logarithmic(true)
                     .suffix("ms"),
             )
+            .explain("latency_us")
             .changed()
         {
             edge.latency_us = (latency_ms as f64 * 1000.0) as u64;
@@ -240,7 +1089,8 @@ fn render_edge_config(ui: &mut egui::Ui, edge: &mut slay_core::EdgeConfig) {
         ui.label("Jitter  ");
         let mut jitter_ms = edge.jitter_us as f32 / 1000.0;
         if ui
-            .add(egui::Slider::new(&mut jitter_ms, 0.0..=500.0).suffix("ms"))
+            .add(SliderEntry::new(&mut jitter_ms, 0.0..=500.0).suffix("ms"))
+            .explain("jitter_us")
             .changed()
         {
             edge.jitter_us = (jitter_ms as f64 * 1000.0) as u64;
@@ -251,10 +1101,27 @@ fn render_edge_config(ui: &mut egui::Ui, edge: &mut slay_core::EdgeConfig) {
         ui.label("Loss    ");
         let mut loss_pct = edge.packet_loss_rate * 100.0;
         if ui
-            .add(egui::Slider::new(&mut loss_pct, 0.0..=100.0).suffix("%"))
+            .add(SliderEntry::new(&mut loss_pct, 0.0..=100.0).suffix("%"))
+            .explain("packet_loss_rate")
             .changed()
         {
             edge.packet_loss_rate = loss_pct / 100.0;
         }
     });
+
+    ui.horizontal(|ui| {
+        ui.label("Bandwidth");
+        if ui
+            .add(
+                SliderEntry::new(&mut edge.bandwidth_mbps, 0.0..=100_000.0)
+                    .logarithmic(true)
+                    .suffix(" Mbps"),
+            )
+            .on_hover_text("0: unlimited")
+            .explain("bandwidth_mbps")
+            .changed()
+        {
+            edge.bandwidth_mbps = edge.bandwidth_mbps.max(0.0);
+        }
+    });
 }
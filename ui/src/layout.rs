@@ -0,0 +1,216 @@
+use crate::theme::theme;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Storage key of the panel arrangement. Panel sizes are kept by egui's
+/// own memory, per panel id.
+pub const LAYOUT_KEY: &str = "slay_layout";
+
+/// Width (or height) of a collapsed panel's stub.
+const STUB_SIZE: f32 = 26.0;
+
+/// Window edge a panel is attached to.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dock {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Dock {
+    fn label(self) -> &'static str {
+        match self {
+            Dock::Left => "Left",
+            Dock::Right => "Right",
+            Dock::Top => "Top",
+            Dock::Bottom => "Bottom",
+        }
+    }
+
+    fn is_side(self) -> bool {
+        matches!(self, Dock::Left | Dock::Right)
+    }
+
+    /// Arrow pointing toward the edge, used to collapse a panel there.
+    fn toward(self) -> &'static str {
+        match self {
+            Dock::Left => "⏴",
+            Dock::Right => "⏵",
+            Dock::Top => "⏶",
+            Dock::Bottom => "⏷",
+        }
+    }
+
+    /// Arrow pointing away from the edge, used to expand a panel.
+    fn away(self) -> &'static str {
+        match self {
+            Dock::Left => "⏵",
+            Dock::Right => "⏴",
+            Dock::Top => "⏷",
+            Dock::Bottom => "⏶",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PanelDock {
+    pub dock: Dock,
+    pub collapsed: bool,
+}
+
+impl PanelDock {
+    const fn at(dock: Dock) -> Self {
+        Self {
+            dock,
+            collapsed: false,
+        }
+    }
+}
+
+/// Where the palette, inspector and dashboard sit and whether they are
+/// folded away.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub palette: PanelDock,
+    pub inspector: PanelDock,
+    pub dashboard: PanelDock,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            palette: PanelDock::at(Dock::Left),
+            inspector: PanelDock::at(Dock::Right),
+            dashboard: PanelDock::at(Dock::Bottom),
+        }
+    }
+}
+
+/// Edges each panel may be docked to; the dashboard is laid out in a row,
+/// the others in a column.
+const SIDES: &[Dock] = &[Dock::Left, Dock::Right];
+const ENDS: &[Dock] = &[Dock::Top, Dock::Bottom];
+
+impl PanelLayout {
+    /// The "Layout" menu: where each panel docks and whether it shows.
+    pub fn menu(&mut self, ui: &mut egui::Ui) {
+        for (name, panel, docks) in [
+            ("Palette", &mut self.palette, SIDES),
+            ("Inspector", &mut self.inspector, SIDES),
+            ("Dashboard", &mut self.dashboard, ENDS),
+        ] {
+            ui.horizontal(|ui| {
+                let mut shown = !panel.collapsed;
+                if ui.checkbox(&mut shown, name).changed() {
+                    panel.collapsed = !shown;
+                }
+                for &dock in docks {
+                    ui.selectable_value(&mut panel.dock, dock, dock.label());
+                }
+            });
+        }
+        ui.separator();
+        if ui.button("Reset layout").clicked() {
+            *self = Self::default();
+            ui.close_menu();
+        }
+    }
+}
+
+/// Shows a panel at its dock: resizable when open, a thin stub with an
+/// expand button when collapsed. Side panels get a collapse button in
+/// their top corner; the `Layout` menu folds any of them.
+pub fn show_docked(
+    ctx: &egui::Context,
+    name: &str,
+    state: &mut PanelDock,
+    frame: Option<egui::Frame>,
+    default_size: f32,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let dock = state.dock;
+    // Each edge keeps its own size in egui's memory
+    let id = egui::Id::new((name, dock.label()));
+    if state.collapsed {
+        let stub = |ui: &mut egui::Ui| {
+            let label = if dock.is_side() {
+                dock.away().to_string()
+            } else {
+                format!("{} {}", dock.away(), name)
+            };
+            if ui
+                .small_button(label)
+                .on_hover_text(format!("Show the {}", name.to_lowercase()))
+                .clicked()
+            {
+                state.collapsed = false;
+            }
+        };
+        let stub_frame = egui::Frame::none().fill(theme().panel).inner_margin(2.0);
+        match dock {
+            Dock::Left | Dock::Right => side(dock, id.with("stub"))
+                .resizable(false)
+                .exact_width(STUB_SIZE)
+                .frame(stub_frame)
+                .show(ctx, stub),
+            Dock::Top | Dock::Bottom => end(dock, id.with("stub"))
+                .resizable(false)
+                .exact_height(STUB_SIZE)
+                .frame(stub_frame)
+                .show(ctx, stub),
+        };
+        return;
+    }
+
+    let contents = |ui: &mut egui::Ui| {
+        let corner = ui.clip_rect().right_top();
+        add_contents(ui);
+        if dock.is_side() {
+            let area =
+                egui::Rect::from_min_size(corner + egui::vec2(-24.0, 2.0), egui::vec2(22.0, 20.0));
+            ui.allocate_new_ui(egui::UiBuilder::new().max_rect(area), |ui| {
+                if ui
+                    .small_button(dock.toward())
+                    .on_hover_text(format!("Hide the {}", name.to_lowercase()))
+                    .clicked()
+                {
+                    state.collapsed = true;
+                }
+            });
+        }
+    };
+    match dock {
+        Dock::Left | Dock::Right => {
+            let mut panel = side(dock, id).resizable(true).default_width(default_size);
+            if let Some(frame) = frame {
+                panel = panel.frame(frame);
+            }
+            panel.show(ctx, contents);
+        }
+        Dock::Top | Dock::Bottom => {
+            let mut panel = end(dock, id).resizable(true).default_height(default_size);
+            if let Some(frame) = frame {
+                panel = panel.frame(frame);
+            }
+            panel.show(ctx, contents);
+        }
+    }
+}
+
+fn side(dock: Dock, id: egui::Id) -> egui::SidePanel {
+    if dock == Dock::Left {
+        egui::SidePanel::left(id)
+    } else {
+        egui::SidePanel::right(id)
+    }
+}
+
+fn end(dock: Dock, id: egui::Id) -> egui::TopBottomPanel {
+    if dock == Dock::Top {
+        egui::TopBottomPanel::top(id)
+    } else {
+        egui::TopBottomPanel::bottom(id)
+    }
+}
@@ -1,16 +1,60 @@
+mod align;
 mod analytics;
 mod app;
 mod canvas;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod clipboard;
 pub mod components;
+#[cfg(not(target_arch = "wasm32"))]
+mod control_server;
+mod debug_panel;
+mod driver;
+mod explain;
+mod export;
+mod groups;
+mod heatmap;
+mod image_export;
 mod inspector;
+mod layout;
+#[cfg(not(target_arch = "wasm32"))]
+mod metrics_server;
 mod palette;
+mod project;
+mod replay;
+mod search;
+#[cfg(target_arch = "wasm32")]
+mod share;
+mod shortcuts;
 mod theme;
+mod timeline;
+mod traces;
+mod widgets;
+mod workspaces;
 
 use app::SlayApp;
 
+/// Counts allocations for the engine profiler in the debug panel.
+#[global_allocator]
+static ALLOC: slay_core::CountingAllocator = slay_core::CountingAllocator;
+
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
     use egui::ViewportBuilder;
+
+    let fail = |err: String| -> ! {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    };
+    let opts = cli::Options::parse(std::env::args()).unwrap_or_else(|e| fail(e));
+    opts.load_plugins().unwrap_or_else(|e| fail(e));
+    if let Some(topology) = &opts.topology {
+        cli::run_headless(topology, &opts).unwrap_or_else(|e| fail(e));
+        return Ok(());
+    }
+    let metrics_server = opts.start_metrics_server().unwrap_or_else(|e| fail(e));
+    let control_server = opts.start_control_server().unwrap_or_else(|e| fail(e));
+
     let options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
             .with_inner_size([1300.0, 900.0])
@@ -20,7 +64,15 @@ fn main() -> eframe::Result {
     eframe::run_native(
         "Slay",
         options,
-        Box::new(|cc| Ok(Box::new(SlayApp::new(cc)))),
+        Box::new(|cc| {
+            let mut app = SlayApp::new(cc);
+            app.metrics_server = metrics_server;
+            if let Some(server) = &control_server {
+                server.wake(&cc.egui_ctx);
+            }
+            app.control_server = control_server;
+            Ok(Box::new(app))
+        }),
     )
 }
 
@@ -0,0 +1,82 @@
+use slay_core::{MetricsCollector, Simulation};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Serves the latest metrics on `GET /metrics` in the Prometheus text format.
+///
+/// Requests are answered from a background thread with whatever was last
+/// published, so scrapes never touch the simulation itself.
+pub struct MetricsServer {
+    body: Arc<Mutex<String>>,
+    /// Sample time of the last published metrics
+    published_us: Option<u64>,
+}
+
+impl MetricsServer {
+    pub fn start(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let body = Arc::new(Mutex::new(String::new()));
+        let shared = Arc::clone(&body);
+        std::thread::Builder::new()
+            .name("slay-metrics".into())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    if let Err(err) = respond(stream, &shared) {
+                        log::debug!("metrics request failed: {}", err);
+                    }
+                }
+            })?;
+        log::info!("Serving metrics on http://{}/metrics", addr);
+        Ok(Self {
+            body,
+            published_us: None,
+        })
+    }
+
+    /// Re-renders the exposition if `metrics` took a new sample since the
+    /// last call.
+    pub fn publish(&mut self, metrics: &MetricsCollector, sim: &Simulation) {
+        let latest = metrics.history.back().map(|p| p.sim_time_us);
+        if latest == self.published_us {
+            return;
+        }
+        self.published_us = latest;
+        let text = metrics.to_prometheus(sim);
+        if let Ok(mut body) = self.body.lock() {
+            *body = text;
+        }
+    }
+}
+
+fn respond(stream: TcpStream, body: &Mutex<String>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers; the request has no body we care about
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut stream = reader.into_inner();
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next(), parts.next());
+    if method == Some("GET") && path.is_some_and(|p| p == "/metrics" || p.starts_with("/metrics?"))
+    {
+        let body = body.lock().map(|b| b.clone()).unwrap_or_default();
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+    }
+}
@@ -1,7 +1,12 @@
 use crate::app::SlayApp;
+use crate::canvas::FitTarget;
 use crate::components::get_all_views;
-use crate::theme::*;
+use crate::heatmap::Heatmap;
+use crate::image_export::ImageFormat;
+use crate::shortcuts::SHORTCUTS;
+use crate::theme::{theme, Theme};
 use eframe::egui;
+use slay_core::{stress_topology, Design, Severity, TEMPLATES};
 
 pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
     ui.add_space(15.0);
@@ -13,7 +18,11 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
             ui.allocate_at_least(egui::vec2(ui.available_width(), 40.0), egui::Sense::drag());
 
         let is_hovered = ui.rect_contains_pointer(rect);
-        let bg_color = if is_hovered { COLOR_BG } else { COLOR_PANEL };
+        let bg_color = if is_hovered {
+            theme().bg
+        } else {
+            theme().panel
+        };
 
         ui.painter().rect_filled(rect, 4.0, bg_color);
         ui.painter().rect_stroke(
@@ -26,7 +35,7 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
             egui::Align2::CENTER_CENTER,
             view.name(),
             egui::FontId::proportional(12.0),
-            COLOR_TEXT,
+            theme().text,
         );
 
         let response = response.on_hover_text(view.description());
@@ -36,14 +45,160 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
         }
     }
 
+    let diagnostics = app.diagnostics.clone();
+    if !diagnostics.is_empty() {
+        ui.add_space(15.0);
+        ui.label(
+            egui::RichText::new(format!("⚠ {} ISSUES", diagnostics.len()))
+                .small()
+                .strong()
+                .color(theme().warn),
+        );
+        for diag in &diagnostics {
+            let color = match diag.severity() {
+                Severity::Error => theme().critical,
+                Severity::Warning => theme().warn,
+            };
+            let resp = ui.add(
+                egui::Label::new(egui::RichText::new(diag.message()).small().color(color))
+                    .sense(egui::Sense::click()),
+            );
+            if resp.on_hover_text("Click to show on the canvas").clicked() {
+                app.selected_node = diag.nodes().first().copied();
+                app.selection = diag.nodes().into_iter().collect();
+                app.selected_edge = None;
+                app.fit_request = Some(FitTarget::Selection);
+            }
+        }
+    }
+
+    ui.add_space(15.0);
+    egui::CollapsingHeader::new(
+        egui::RichText::new("SHORTCUTS")
+            .small()
+            .color(theme().text_dim),
+    )
+    .default_open(false)
+    .show(ui, |ui| {
+        egui::Grid::new("shortcuts").num_columns(2).show(ui, |ui| {
+            for (keys, action) in SHORTCUTS {
+                ui.label(egui::RichText::new(*keys).small().strong());
+                ui.label(egui::RichText::new(*action).small().color(theme().text_dim));
+                ui.end_row();
+            }
+        });
+    });
+
     ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
         ui.add_space(15.0);
         if ui
-            .button(egui::RichText::new("🗑 Clear Canvas").color(COLOR_CRITICAL))
+            .button(egui::RichText::new("🗑 Clear Canvas").color(theme().critical))
             .clicked()
         {
             app.reset();
         }
+        ui.menu_button("📋 New from template", |ui| {
+            for template in TEMPLATES {
+                if ui
+                    .button(template.name)
+                    .on_hover_text(template.description)
+                    .clicked()
+                {
+                    app.open_topology(&template.topology());
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            for nodes in [1_000, 5_000, 10_000] {
+                if ui
+                    .button(format!("Stress test ({} nodes)", nodes))
+                    .on_hover_text(
+                        "Identical regions of balanced services, to see how the app copes at scale",
+                    )
+                    .clicked()
+                {
+                    app.open_topology(&stress_topology(nodes));
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("🖼 Export image", |ui| {
+            ui.checkbox(&mut app.export_with_stats, "Include live stats");
+            for format in [ImageFormat::Png, ImageFormat::Svg] {
+                if ui.button(format.extension().to_uppercase()).clicked() {
+                    app.export_image(format, app.export_with_stats);
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.menu_button("🗺 Export diagram", |ui| {
+            let topology = app.simulation.to_topology();
+            if ui
+                .button("YAML design")
+                .on_hover_text("Save the topology and scenario as design.yaml")
+                .clicked()
+            {
+                let design = Design {
+                    topology: topology.clone(),
+                    scenario: app.simulation.scenario.clone(),
+                };
+                crate::export::save_file("design.yaml", "application/yaml", &design.to_yaml());
+                ui.close_menu();
+            }
+            if ui
+                .button("Graphviz DOT")
+                .on_hover_text("Save as topology.dot; opens again with 📂 Open")
+                .clicked()
+            {
+                crate::export::save_file("topology.dot", "text/vnd.graphviz", &topology.to_dot());
+                ui.close_menu();
+            }
+            if ui
+                .button("Mermaid")
+                .on_hover_text("Save as topology.mmd")
+                .clicked()
+            {
+                crate::export::save_file("topology.mmd", "text/plain", &topology.to_mermaid());
+                ui.close_menu();
+            }
+            if ui
+                .button("Copy Mermaid")
+                .on_hover_text("Copy a flowchart to paste into Markdown")
+                .clicked()
+            {
+                ui.ctx().copy_text(topology.to_mermaid());
+                ui.close_menu();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button("💾 Save")
+                .on_hover_text("Save the design to a .slay file")
+                .clicked()
+            {
+                app.save_project();
+            }
+            if ui
+                .button("📂 Open")
+                .on_hover_text(
+                    "Replace the canvas with a .slay file, DOT diagram or Kubernetes manifests",
+                )
+                .clicked()
+            {
+                app.open_project(ui.ctx());
+            }
+            #[cfg(target_arch = "wasm32")]
+            if ui
+                .button("🔗 Share")
+                .on_hover_text("Put the design in the page URL and copy the link")
+                .clicked()
+            {
+                app.share_link(ui.ctx());
+            }
+        });
+        if let Some(err) = &app.project_error {
+            ui.label(egui::RichText::new(err).small().color(theme().critical));
+        }
 
         ui.add_space(10.0);
         let btn = if app.is_running {
@@ -54,12 +209,53 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
         if ui
             .add_sized(
                 [ui.available_width(), 35.0],
-                egui::Button::new(btn).fill(COLOR_ACCENT.gamma_multiply(0.2)),
+                egui::Button::new(btn).fill(theme().accent.gamma_multiply(0.2)),
             )
+            .on_hover_text("Space")
             .clicked()
         {
-            app.is_running = !app.is_running;
+            app.toggle_running();
+        }
+        if let Some(until) = app.pause_at.filter(|_| app.is_running) {
+            ui.label(
+                egui::RichText::new(format!("Pauses at t={:.1}s", until as f64 / 1e6))
+                    .small()
+                    .color(theme().text_dim),
+            );
         }
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!app.is_running, |ui| {
+                if ui
+                    .button("⏭ Event")
+                    .on_hover_text("Process the next event")
+                    .clicked()
+                {
+                    app.step_event();
+                }
+                if ui
+                    .button("+100ms")
+                    .on_hover_text("Process everything due in the next 100ms")
+                    .clicked()
+                {
+                    app.step_by(100_000);
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut app.run_for_input_s)
+                    .range(0.001..=86_400.0)
+                    .speed(0.1)
+                    .suffix("s"),
+            );
+            if ui
+                .button("▶ Run for")
+                .on_hover_text("Run in real time for this long, then pause")
+                .clicked()
+            {
+                app.run_for(app.run_for_input_s);
+            }
+        });
 
         ui.add_space(15.0);
         ui.separator();
@@ -67,9 +263,39 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
         ui.label(
             egui::RichText::new("SIMULATION")
                 .small()
-                .color(COLOR_TEXT_DIM),
+                .color(theme().text_dim),
         );
 
+        if let Some(target) = app.fast_forward_to {
+            let now = app.simulation.time.min(target) as f32;
+            ui.add(
+                egui::ProgressBar::new(now / target.max(1) as f32).text(format!(
+                    "⏩ {:.1}s / {:.1}s",
+                    now / 1e6,
+                    target as f32 / 1e6
+                )),
+            );
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut app.fast_forward_input_s)
+                    .range(0.0..=86_400.0)
+                    .prefix("t=")
+                    .suffix("s"),
+            );
+            if ui
+                .button("⏩ Run to")
+                .on_hover_text("Execute events as fast as possible up to this time")
+                .clicked()
+            {
+                let target = (app.fast_forward_input_s as f64 * 1_000_000.0) as u64;
+                if target > app.simulation.time {
+                    app.fast_forward_to = Some(target);
+                    app.is_running = true;
+                }
+            }
+        });
+
         ui.label("Time Speed:");
         ui.add(
             egui::Slider::new(&mut app.sim_speed, 0.01..=10.0)
@@ -77,9 +303,52 @@ pub fn render_palette(ui: &mut egui::Ui, app: &mut SlayApp) {
                 .logarithmic(true),
         );
 
+        ui.add_space(10.0);
+        ui.checkbox(&mut app.snap_to_grid, "Snap to grid");
+        ui.checkbox(&mut app.alignment_guides, "Alignment guides");
+        ui.horizontal(|ui| {
+            ui.label("Colour nodes by:");
+            egui::ComboBox::from_id_salt("heatmap")
+                .selected_text(app.heatmap.label())
+                .show_ui(ui, |ui| {
+                    for heatmap in Heatmap::ALL {
+                        ui.selectable_value(&mut app.heatmap, heatmap, heatmap.label());
+                    }
+                });
+        });
+
         ui.add_space(10.0);
         ui.label("UI Refresh Rate:");
         ui.add(egui::Slider::new(&mut app.ui_refresh_rate, 0.0..=2.0).suffix("s"));
         ui.add_space(10.0);
+
+        render_theme_picker(ui);
     });
 }
+
+/// Preset chooser plus a color editor; any edit makes a custom palette.
+fn render_theme_picker(ui: &mut egui::Ui) {
+    let mut current = theme();
+    ui.label(egui::RichText::new("THEME").small().color(theme().text_dim));
+    egui::ComboBox::from_id_salt("theme_preset")
+        .selected_text(current.preset_name().unwrap_or("Custom"))
+        .show_ui(ui, |ui| {
+            for (name, preset) in Theme::PRESETS {
+                ui.selectable_value(&mut current, *preset, *name);
+            }
+        });
+    egui::CollapsingHeader::new("Colors")
+        .id_salt("theme_colors")
+        .show(ui, |ui| {
+            egui::Grid::new("theme_color_grid").show(ui, |ui| {
+                for (label, color) in current.colors_mut() {
+                    ui.label(label);
+                    ui.color_edit_button_srgba(color);
+                    ui.end_row();
+                }
+            });
+        });
+    if current != theme() {
+        current.apply(ui.ctx());
+    }
+}
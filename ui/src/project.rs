@@ -0,0 +1,256 @@
+use crate::app::{NodeVisualState, PersistedState, SlayApp, Vec2Serde};
+use crate::canvas::FitTarget;
+use slay_core::{Design, NodeId, NodeSpec, Simulation, Topology};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Spacing of the automatic layout, in world units.
+const LAYER_GAP: f32 = 300.0;
+const ROW_GAP: f32 = 140.0;
+
+/// Extension of saved topology files.
+const EXTENSION: &str = "slay";
+const DEFAULT_NAME: &str = "topology.slay";
+/// Extensions of Graphviz diagrams that can be opened as designs.
+const DOT_EXTENSIONS: [&str; 2] = ["dot", "gv"];
+/// Extensions of YAML designs, Kubernetes manifests and docker-compose
+/// files.
+const MANIFEST_EXTENSIONS: [&str; 2] = ["yaml", "yml"];
+
+/// Contents of a file picked in the browser; the picker completes
+/// asynchronously and the next frame picks the text up.
+#[cfg(target_arch = "wasm32")]
+pub type PendingOpen = std::rc::Rc<std::cell::RefCell<Option<String>>>;
+
+impl SlayApp {
+    /// The design on the canvas (nodes, configs, links and positions) as
+    /// the contents of a `.slay` file.
+    pub fn project_json(&self) -> String {
+        serde_json::to_string_pretty(&self.persisted_state()).unwrap_or_default()
+    }
+
+    /// Replaces the canvas with the design in `json`, stopping the run.
+    pub fn open_project_json(&mut self, json: &str) {
+        match serde_json::from_str::<PersistedState>(json) {
+            Ok(state) => {
+                self.reset();
+                self.apply_state(state);
+                self.project_error = None;
+            }
+            Err(err) => self.project_error = Some(format!("Not a .slay file: {}", err)),
+        }
+    }
+
+    /// Opens a picked file: a `.slay` file, a YAML design with its
+    /// scenario, or anything else `import_topology` reads, told apart by
+    /// their contents.
+    pub fn open_file_text(&mut self, text: &str) {
+        // Saved designs are JSON objects; DOT starts with a keyword or comment
+        if text.trim_start().starts_with('{') {
+            return self.open_project_json(text);
+        }
+        if is_design(text) {
+            match Design::from_yaml(text) {
+                Ok(design) => {
+                    self.open_topology(&design.topology);
+                    self.simulation.load_scenario(design.scenario);
+                    self.scenario_text = self.simulation.scenario.to_json();
+                    self.project_error = None;
+                }
+                Err(err) => self.project_error = Some(format!("Not a YAML design: {}", err)),
+            }
+            return;
+        }
+        match import_topology(text) {
+            Ok(topology) => {
+                self.open_topology(&topology);
+                self.project_error = None;
+            }
+            Err(err) => self.project_error = Some(err),
+        }
+    }
+
+    /// Replaces the canvas with `topology`, which carries no positions:
+    /// nodes are laid out left to right by their distance from a client.
+    pub fn open_topology(&mut self, topology: &Topology) {
+        self.reset();
+        let positions = layered_layout(topology);
+        self.node_states = topology
+            .nodes
+            .iter()
+            .map(|n| {
+                let (x, y) = positions[&n.id];
+                let visual = NodeVisualState {
+                    pos: Vec2Serde { x, y },
+                    last_sync_time: 0.0,
+                };
+                (n.id, visual)
+            })
+            .collect();
+        self.next_node_id = topology.max_node_id() + 1;
+        self.simulation = Simulation::from_topology(topology, self.simulation.seed);
+    }
+
+    /// Arranges the nodes on the canvas in columns by their distance from
+    /// a client, then frames them.
+    pub fn auto_layout(&mut self) {
+        for (id, (x, y)) in layered_layout(&self.simulation.to_topology()) {
+            if let Some(state) = self.node_states.get_mut(&id) {
+                state.pos = Vec2Serde { x, y };
+            }
+        }
+        self.wrap_groups();
+        self.fit_request = Some(FitTarget::All);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_project(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Slay topology", &[EXTENSION])
+            .set_file_name(DEFAULT_NAME)
+            .save_file()
+        else {
+            return;
+        };
+        let path = path.with_extension(EXTENSION);
+        match std::fs::write(&path, self.project_json()) {
+            Ok(()) => log::info!("Wrote {}", path.display()),
+            Err(err) => log::error!("Failed to write {}: {}", path.display(), err),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_project(&mut self, _ctx: &eframe::egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Slay topology", &[EXTENSION])
+            .add_filter("Graphviz diagram", &DOT_EXTENSIONS)
+            .add_filter(
+                "YAML design, Kubernetes or docker-compose",
+                &MANIFEST_EXTENSIONS,
+            )
+            .pick_file()
+        else {
+            return;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => self.open_file_text(&text),
+            Err(err) => {
+                self.project_error = Some(format!("Failed to read {}: {}", path.display(), err))
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn save_project(&mut self) {
+        crate::export::save_file(DEFAULT_NAME, "application/json", &self.project_json());
+    }
+
+    /// Opens the browser's file picker; the chosen file is applied by
+    /// `poll_open_project` once it has been read.
+    #[cfg(target_arch = "wasm32")]
+    pub fn open_project(&mut self, ctx: &eframe::egui::Context) {
+        let pending = std::rc::Rc::clone(&self.pending_open);
+        let ctx = ctx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Slay topology", &[EXTENSION])
+                .add_filter("Graphviz diagram", &DOT_EXTENSIONS)
+                .add_filter(
+                    "YAML design, Kubernetes or docker-compose",
+                    &MANIFEST_EXTENSIONS,
+                )
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let text = String::from_utf8_lossy(&file.read().await).into_owned();
+            *pending.borrow_mut() = Some(text);
+            ctx.request_repaint();
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn poll_open_project(&mut self) {
+        let opened = self.pending_open.borrow_mut().take();
+        if let Some(json) = opened {
+            self.open_file_text(&json);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn poll_open_project(&mut self) {}
+}
+
+/// Whether `text` is a YAML design (see `slay_core::Design`) rather than
+/// Kubernetes manifests or a docker-compose file.
+pub fn is_design(text: &str) -> bool {
+    text.lines().any(|l| l.starts_with("components:"))
+}
+
+/// Imports a YAML design, Graphviz diagram, Kubernetes manifests or a
+/// docker-compose file, told apart by their contents.
+pub fn import_topology(text: &str) -> Result<Topology, String> {
+    if is_design(text) {
+        Topology::from_yaml(text).map_err(|e| format!("Not a YAML design: {}", e))
+    } else if text.lines().any(|l| l.starts_with("services:")) {
+        Topology::from_compose(text).map_err(|e| format!("Not a docker-compose file: {}", e))
+    } else if text.contains("kind:") {
+        Topology::from_kubernetes(text).map_err(|e| format!("Not Kubernetes manifests: {}", e))
+    } else {
+        Topology::from_dot(text).map_err(|e| format!("Not a DOT diagram: {}", e))
+    }
+}
+
+/// Position of every node: the column is its hop distance from the nearest
+/// node nothing points at (usually a client), rows are centred per column.
+fn layered_layout(topology: &Topology) -> HashMap<NodeId, (f32, f32)> {
+    let targeted: HashSet<NodeId> = topology
+        .nodes
+        .iter()
+        .flat_map(|n| n.targets.iter().copied())
+        .collect();
+    let by_id: HashMap<NodeId, &NodeSpec> = topology.nodes.iter().map(|n| (n.id, n)).collect();
+    let mut depth: BTreeMap<NodeId, usize> = topology
+        .nodes
+        .iter()
+        .filter(|n| !targeted.contains(&n.id))
+        .map(|n| (n.id, 0))
+        .collect();
+    let mut frontier: Vec<NodeId> = depth.keys().copied().collect();
+    while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for id in frontier {
+            let d = depth[&id] + 1;
+            let node = by_id.get(&id);
+            for &target in node.iter().flat_map(|n| &n.targets) {
+                if let Entry::Vacant(slot) = depth.entry(target) {
+                    slot.insert(d);
+                    next.push(target);
+                }
+            }
+        }
+        frontier = next;
+    }
+    // Nodes only reachable through a cycle go in the first column
+    for node in &topology.nodes {
+        depth.entry(node.id).or_insert(0);
+    }
+
+    let mut columns: BTreeMap<usize, Vec<NodeId>> = BTreeMap::new();
+    for (&id, &d) in &depth {
+        columns.entry(d).or_default().push(id);
+    }
+    let width = columns.len().saturating_sub(1) as f32 * LAYER_GAP;
+    columns
+        .iter()
+        .flat_map(|(&d, ids)| {
+            let height = ids.len().saturating_sub(1) as f32 * ROW_GAP;
+            ids.iter().enumerate().map(move |(row, &id)| {
+                let x = d as f32 * LAYER_GAP - width / 2.0;
+                let y = row as f32 * ROW_GAP - height / 2.0;
+                (id, (x, y))
+            })
+        })
+        .collect()
+}
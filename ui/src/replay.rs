@@ -0,0 +1,138 @@
+use crate::app::SlayApp;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::Replay;
+
+/// Replay speeds offered next to the scrubber.
+const SPEEDS: &[f32] = &[0.25, 0.5, 1.0, 2.0, 5.0, 10.0];
+/// Metric samples taken while rebuilding a moment, and the closest they
+/// may be spaced.
+const REBUILD_SAMPLES: u64 = 300;
+const MIN_SAMPLE_US: u64 = 50_000;
+
+/// The current run, from its start at t=0, so any moment of it can be
+/// rebuilt by re-simulating.
+pub struct Recording {
+    pub replay: Replay,
+    /// Furthest virtual time the run has reached
+    pub until_us: u64,
+    /// Time the scrubber is being dragged to, in seconds
+    dragging_s: Option<f32>,
+}
+
+impl SlayApp {
+    /// Records the baseline whenever a run starts from t=0, and extends the
+    /// recording as virtual time moves on. Call before lending the
+    /// simulation out.
+    pub fn track_recording(&mut self) {
+        if self.is_running && self.simulation.time == 0 {
+            // Playing again after rewinding to t=0 keeps the recorded extent
+            let until_us = self.recording.as_ref().map_or(0, |r| r.until_us);
+            self.recording = Some(Recording {
+                replay: Replay::capture(&self.simulation),
+                until_us,
+                dragging_s: None,
+            });
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.until_us = recording.until_us.max(self.simulation.time);
+        }
+    }
+
+    /// Replaces the simulation and dashboards with the recorded run as it
+    /// was at `time_us`, paused.
+    pub fn seek(&mut self, time_us: u64) {
+        let Some(recording) = &self.recording else {
+            return;
+        };
+        let window_us = (self.stats_window_seconds * 1_000_000.0) as u64;
+        let every_us = (time_us / REBUILD_SAMPLES).max(MIN_SAMPLE_US);
+        self.metrics.reset();
+        let metrics = &mut self.metrics;
+        let mut sim = recording
+            .replay
+            .simulation_at_sampled(time_us, every_us, |sim| metrics.update(sim, window_us));
+        sim.debugger = std::mem::take(&mut self.simulation.debugger);
+        sim.macro_recorder = self.simulation.macro_recorder.take();
+        self.simulation = sim;
+        self.config_edit = None;
+        self.is_running = false;
+        self.fast_forward_to = None;
+        self.pause_at = None;
+        for state in self.node_states.values_mut() {
+            state.last_sync_time = 0.0;
+        }
+    }
+
+    /// Scrubber over the recorded run: drag back to any moment, then play
+    /// forward from there at the chosen speed.
+    pub fn render_replay(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("REPLAY")
+                    .small()
+                    .strong()
+                    .color(theme().accent),
+            );
+            let Some(recording) = &mut self.recording else {
+                ui.label(
+                    egui::RichText::new("Start a run from t=0 to record it")
+                        .small()
+                        .color(theme().text_dim),
+                );
+                return;
+            };
+            let until_s = recording.until_us as f32 / 1_000_000.0;
+            let now_s = self.simulation.time as f32 / 1_000_000.0;
+            let mut seek_to = None;
+
+            if ui.button("⏮").on_hover_text("Back to t=0").clicked() {
+                seek_to = Some(0);
+            }
+            let play = if self.is_running { "⏸" } else { "▶" };
+            if ui.button(play).on_hover_text("Space").clicked() {
+                self.is_running = !self.is_running;
+                self.fast_forward_to = None;
+                self.pause_at = None;
+            }
+            egui::ComboBox::from_id_salt("replay_speed")
+                .width(60.0)
+                .selected_text(format!("{}x", self.sim_speed))
+                .show_ui(ui, |ui| {
+                    for &speed in SPEEDS {
+                        ui.selectable_value(&mut self.sim_speed, speed, format!("{}x", speed));
+                    }
+                });
+
+            let mut position_s = recording.dragging_s.unwrap_or(now_s.min(until_s));
+            ui.spacing_mut().slider_width = (ui.available_width() - 120.0).max(80.0);
+            let slider = ui
+                .add(
+                    egui::Slider::new(&mut position_s, 0.0..=until_s.max(0.001))
+                        .suffix("s")
+                        .fixed_decimals(1),
+                )
+                .on_hover_text(
+                    "Rebuilds the run at that moment. Edits made while it was \
+                     running are not replayed; script them as a scenario.",
+                );
+            if slider.dragged() {
+                recording.dragging_s = Some(position_s);
+            }
+            // Rebuilding is a re-simulation, so wait for the drag to end
+            if slider.drag_stopped() || (slider.changed() && !slider.dragged()) {
+                recording.dragging_s = None;
+                seek_to = Some((position_s as f64 * 1_000_000.0) as u64);
+            }
+            ui.label(
+                egui::RichText::new(format!("/ {:.1}s", until_s))
+                    .small()
+                    .color(theme().text_dim),
+            );
+
+            if let Some(time_us) = seek_to {
+                self.seek(time_us);
+            }
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use crate::app::SlayApp;
+use crate::canvas::FitTarget;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::NodeId;
+use std::collections::BTreeSet;
+
+/// Widget id of the search box, so a shortcut can focus it.
+pub const SEARCH_ID: &str = "node_search";
+
+impl SlayApp {
+    /// Nodes whose name, kind or `#id` contains the search text, ignoring
+    /// case; `None` while the search box is empty.
+    pub fn search_matches(&self) -> Option<BTreeSet<NodeId>> {
+        let query = self.search.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+        Some(
+            self.simulation
+                .components
+                .iter()
+                .filter(|(id, comp)| {
+                    comp.name().to_lowercase().contains(&query)
+                        || comp.kind().to_lowercase().contains(&query)
+                        || format!("#{}", id).contains(&query)
+                })
+                .map(|(id, _)| *id)
+                .collect(),
+        )
+    }
+
+    /// Selects the match `step` places after the current one (wrapping)
+    /// and moves the camera to it, opening a collapsed group around it.
+    fn jump_to_match(&mut self, step: isize) {
+        let Some(matches) = self.search_matches() else {
+            return;
+        };
+        let matches: Vec<NodeId> = matches.into_iter().collect();
+        if matches.is_empty() {
+            return;
+        }
+        let current = self
+            .selected_node
+            .and_then(|id| matches.iter().position(|&m| m == id));
+        let next = match current {
+            Some(i) => (i as isize + step).rem_euclid(matches.len() as isize) as usize,
+            None if step < 0 => matches.len() - 1,
+            None => 0,
+        };
+        self.focus_nodes(&matches[next..=next]);
+    }
+
+    fn focus_nodes(&mut self, ids: &[NodeId]) {
+        for group in &mut self.groups {
+            if ids.iter().any(|id| group.members.contains(id)) {
+                group.collapsed = false;
+            }
+        }
+        self.selection = ids.iter().copied().collect();
+        self.selected_node = ids.first().copied();
+        self.selected_edge = None;
+        self.fit_request = Some(FitTarget::Selection);
+    }
+
+    /// Search box over the top-left corner of the canvas. Enter jumps to
+    /// the next match (Shift+Enter to the previous one), Esc clears it.
+    pub fn draw_search(&mut self, ui: &mut egui::Ui, canvas: egui::Rect) {
+        let area = egui::Rect::from_min_size(
+            canvas.left_top() + egui::vec2(12.0, 12.0),
+            egui::vec2(canvas.width().min(420.0), 28.0),
+        );
+        let mut jump = None;
+        let mut frame_all = false;
+        ui.allocate_new_ui(egui::UiBuilder::new().max_rect(area), |ui| {
+            ui.horizontal(|ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut self.search)
+                        .id(egui::Id::new(SEARCH_ID))
+                        .hint_text("🔍 Search nodes (Ctrl+F)")
+                        .desired_width(200.0),
+                );
+                if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    jump = Some(if ui.input(|i| i.modifiers.shift) {
+                        -1
+                    } else {
+                        1
+                    });
+                    edit.request_focus();
+                }
+                if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.search.clear();
+                }
+                let Some(matches) = self.search_matches() else {
+                    return;
+                };
+                let count = egui::RichText::new(match matches.len() {
+                    1 => "1 match".to_string(),
+                    n => format!("{} matches", n),
+                })
+                .small();
+                if matches.is_empty() {
+                    ui.label(count.color(theme().critical));
+                    return;
+                }
+                ui.label(count.color(theme().text_dim));
+                if ui.small_button("◀").clicked() {
+                    jump = Some(-1);
+                }
+                if ui.small_button("▶").clicked() {
+                    jump = Some(1);
+                }
+                if ui
+                    .small_button("⛶")
+                    .on_hover_text("Select and frame every match")
+                    .clicked()
+                {
+                    frame_all = true;
+                }
+            });
+        });
+
+        if let Some(step) = jump {
+            self.jump_to_match(step);
+        }
+        if frame_all {
+            if let Some(matches) = self.search_matches() {
+                self.focus_nodes(&matches.into_iter().collect::<Vec<_>>());
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::app::{PersistedState, SlayApp};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+
+/// Prefix of the URL fragment that carries a shared design.
+const FRAGMENT_KEY: &str = "#t=";
+/// Decompressed size a shared design may not exceed.
+const MAX_DESIGN_BYTES: usize = 4 << 20;
+
+/// The design as URL-safe text: deflated JSON in unpadded base64.
+fn encode(state: &PersistedState) -> String {
+    let json = serde_json::to_string(state).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(json.as_bytes(), 9))
+}
+
+fn decode(code: &str) -> Result<PersistedState, String> {
+    let deflated = URL_SAFE_NO_PAD
+        .decode(code.trim())
+        .map_err(|e| format!("Broken share link: {}", e))?;
+    let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&deflated, MAX_DESIGN_BYTES)
+        .map_err(|e| format!("Broken share link: {:?}", e.status))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Broken share link: {}", e))
+}
+
+/// The design in the page's URL fragment, if the page was opened from a
+/// share link.
+pub fn state_from_url() -> Option<Result<PersistedState, String>> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    hash.strip_prefix(FRAGMENT_KEY).map(decode)
+}
+
+impl SlayApp {
+    /// Writes the design into the URL fragment, without adding a history
+    /// entry, and copies the resulting link.
+    pub fn share_link(&mut self, ctx: &eframe::egui::Context) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let location = window.location();
+        let (Ok(origin), Ok(path)) = (location.origin(), location.pathname()) else {
+            return;
+        };
+        let url = format!(
+            "{}{}{}{}",
+            origin,
+            path,
+            FRAGMENT_KEY,
+            encode(&self.persisted_state())
+        );
+        if let Ok(history) = window.history() {
+            let _ = history.replace_state_with_url(
+                &eframe::wasm_bindgen::JsValue::NULL,
+                "",
+                Some(&url),
+            );
+        }
+        ctx.copy_text(url);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::app::SlayApp;
+use crate::canvas::FitTarget;
+use crate::search::SEARCH_ID;
+use eframe::egui;
+use egui::{Key, Modifiers};
+
+/// Canvas movement per arrow key press, in world units (×5 with Shift).
+const NUDGE: f32 = 10.0;
+/// Zoom change per +/- key press.
+const ZOOM_STEP: f32 = 1.2;
+
+/// Every canvas shortcut with its description, for the help list.
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("Space", "Start / stop"),
+    ("Del / ⌫", "Delete selection"),
+    ("Ctrl+C / Ctrl+V", "Copy / paste"),
+    ("Ctrl+D", "Duplicate selection"),
+    ("F", "Fit to view"),
+    ("Shift+F", "Zoom to selection"),
+    ("+ / -", "Zoom in / out"),
+    ("Arrows", "Nudge selection (Shift: more)"),
+    ("Alt+drag", "Move without snapping"),
+    ("Ctrl+F", "Search nodes"),
+    ("Esc", "Cancel linking, clear selection"),
+];
+
+impl SlayApp {
+    /// Applies the keyboard shortcuts pressed this frame, unless a text
+    /// field has focus. `canvas` is the area zoom keys centre on.
+    pub fn handle_shortcuts(&mut self, ctx: &egui::Context, canvas: egui::Rect) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let pressed = |key: Key| ctx.input_mut(|i| i.consume_key(Modifiers::NONE, key));
+
+        if pressed(Key::Space) {
+            self.toggle_running();
+        }
+        if pressed(Key::Delete) || pressed(Key::Backspace) {
+            self.delete_selection();
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::D)) {
+            self.duplicate_selection();
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F)) {
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(SEARCH_ID)));
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::SHIFT, Key::F)) {
+            self.fit_request = Some(FitTarget::Selection);
+        }
+        if pressed(Key::F) {
+            self.fit_request = Some(FitTarget::All);
+        }
+        if pressed(Key::Plus) || pressed(Key::Equals) {
+            self.zoom_by(ZOOM_STEP, canvas.center());
+        }
+        if pressed(Key::Minus) {
+            self.zoom_by(1.0 / ZOOM_STEP, canvas.center());
+        }
+        if pressed(Key::Escape) {
+            self.linking_from = None;
+            self.click_linking = false;
+            self.drag_node_kind = None;
+            self.selected_node = None;
+            self.selected_edge = None;
+            self.selection.clear();
+        }
+
+        let (arrows, shift) = ctx.input(|i| {
+            let axis = |neg: Key, pos: Key| {
+                i.key_pressed(pos) as i32 as f32 - i.key_pressed(neg) as i32 as f32
+            };
+            (
+                egui::vec2(
+                    axis(Key::ArrowLeft, Key::ArrowRight),
+                    axis(Key::ArrowUp, Key::ArrowDown),
+                ),
+                i.modifiers.shift,
+            )
+        });
+        if arrows != egui::Vec2::ZERO {
+            let step = if shift { NUDGE * 5.0 } else { NUDGE };
+            let ids = self.selected_nodes();
+            for id in &ids {
+                if let Some(s) = self.node_states.get_mut(id) {
+                    s.pos.x += arrows.x * step;
+                    s.pos.y += arrows.y * step;
+                }
+            }
+            self.regroup(&ids);
+        }
+    }
+}
@@ -1,10 +1,141 @@
 use eframe::egui;
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
 
-pub const COLOR_BG: egui::Color32 = egui::Color32::from_rgb(43, 48, 59);
-pub const COLOR_PANEL: egui::Color32 = egui::Color32::from_rgb(59, 66, 82);
-pub const COLOR_ACCENT: egui::Color32 = egui::Color32::from_rgb(136, 192, 208);
-pub const COLOR_TEXT: egui::Color32 = egui::Color32::from_rgb(229, 233, 240);
-pub const COLOR_TEXT_DIM: egui::Color32 = egui::Color32::from_rgb(144, 155, 175);
-pub const COLOR_WARN: egui::Color32 = egui::Color32::from_rgb(235, 203, 139);
-pub const COLOR_CRITICAL: egui::Color32 = egui::Color32::from_rgb(191, 97, 106);
-pub const COLOR_SUCCESS: egui::Color32 = egui::Color32::from_rgb(163, 190, 140);
+/// Storage key of the chosen theme, kept apart from the design itself.
+pub const THEME_KEY: &str = "slay_theme";
+
+/// Every color the app draws with.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Theme {
+    /// Base look of egui's own widgets and panels
+    pub dark: bool,
+    pub bg: Color32,
+    pub panel: Color32,
+    pub accent: Color32,
+    pub text: Color32,
+    pub text_dim: Color32,
+    pub warn: Color32,
+    pub critical: Color32,
+    pub success: Color32,
+    pub grid: Color32,
+    /// Idle connections and unused ports
+    pub edge: Color32,
+    /// Requests in flight and busy threads
+    pub traffic: Color32,
+    /// Live request rate on nodes
+    pub throughput: Color32,
+    /// Dead nodes and idle slots
+    pub inactive: Color32,
+    pub chart_bg: Color32,
+    pub client: Color32,
+    pub server: Color32,
+    pub load_balancer: Color32,
+    /// Components registered without a view of their own
+    pub custom: Color32,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        dark: true,
+        bg: Color32::from_rgb(43, 48, 59),
+        panel: Color32::from_rgb(59, 66, 82),
+        accent: Color32::from_rgb(136, 192, 208),
+        text: Color32::from_rgb(229, 233, 240),
+        text_dim: Color32::from_rgb(144, 155, 175),
+        warn: Color32::from_rgb(235, 203, 139),
+        critical: Color32::from_rgb(191, 97, 106),
+        success: Color32::from_rgb(163, 190, 140),
+        grid: Color32::from_gray(35),
+        edge: Color32::from_gray(100),
+        traffic: Color32::from_rgb(0, 255, 255),
+        throughput: Color32::from_rgb(0, 255, 150),
+        inactive: Color32::from_gray(40),
+        chart_bg: Color32::from_black_alpha(40),
+        client: Color32::from_rgb(163, 190, 140),
+        server: Color32::from_rgb(129, 161, 193),
+        load_balancer: Color32::from_rgb(136, 192, 208),
+        custom: Color32::from_rgb(180, 142, 173),
+    };
+
+    pub const LIGHT: Theme = Theme {
+        dark: false,
+        bg: Color32::from_rgb(236, 239, 244),
+        panel: Color32::from_rgb(216, 222, 233),
+        accent: Color32::from_rgb(76, 117, 163),
+        text: Color32::from_rgb(46, 52, 64),
+        text_dim: Color32::from_rgb(98, 108, 128),
+        warn: Color32::from_rgb(184, 124, 20),
+        critical: Color32::from_rgb(180, 70, 80),
+        success: Color32::from_rgb(88, 134, 62),
+        grid: Color32::from_rgb(222, 226, 234),
+        edge: Color32::from_gray(150),
+        traffic: Color32::from_rgb(0, 140, 190),
+        throughput: Color32::from_rgb(20, 140, 90),
+        inactive: Color32::from_gray(200),
+        chart_bg: Color32::from_black_alpha(12),
+        client: Color32::from_rgb(98, 140, 70),
+        server: Color32::from_rgb(76, 110, 155),
+        load_balancer: Color32::from_rgb(46, 136, 160),
+        custom: Color32::from_rgb(146, 96, 140),
+    };
+
+    pub const PRESETS: &'static [(&'static str, Theme)] =
+        &[("Dark", Theme::DARK), ("Light", Theme::LIGHT)];
+
+    /// Name of the preset this theme matches, if it was not customised.
+    pub fn preset_name(&self) -> Option<&'static str> {
+        Self::PRESETS
+            .iter()
+            .find(|(_, preset)| preset == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// The editable colors with their labels, for the palette editor.
+    pub fn colors_mut(&mut self) -> [(&'static str, &mut Color32); 18] {
+        [
+            ("Canvas", &mut self.bg),
+            ("Panels", &mut self.panel),
+            ("Accent", &mut self.accent),
+            ("Text", &mut self.text),
+            ("Dim text", &mut self.text_dim),
+            ("Warning", &mut self.warn),
+            ("Critical", &mut self.critical),
+            ("Success", &mut self.success),
+            ("Grid", &mut self.grid),
+            ("Edges", &mut self.edge),
+            ("Traffic", &mut self.traffic),
+            ("Throughput", &mut self.throughput),
+            ("Inactive", &mut self.inactive),
+            ("Charts", &mut self.chart_bg),
+            ("Client", &mut self.client),
+            ("Server", &mut self.server),
+            ("Load balancer", &mut self.load_balancer),
+            ("Custom nodes", &mut self.custom),
+        ]
+    }
+
+    /// Makes this the theme everything draws with from the next frame on.
+    pub fn apply(self, ctx: &egui::Context) {
+        ctx.set_visuals(if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        });
+        *CURRENT.write().unwrap() = self;
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+static CURRENT: RwLock<Theme> = RwLock::new(Theme::DARK);
+
+/// The theme in use.
+pub fn theme() -> Theme {
+    *CURRENT.read().unwrap()
+}
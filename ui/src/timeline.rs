@@ -0,0 +1,593 @@
+use crate::app::SlayApp;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::{
+    canonical_key, chaos::CHAOS_WINDOW_US, EdgeConfig, Experiment, NodeId, Scenario,
+    ScenarioAction, FAULT_TEMPLATES,
+};
+
+/// How many upcoming steps the header lists during a run.
+const UPCOMING_SHOWN: usize = 3;
+/// Healthy run a fault template leaves before its fault, as a baseline.
+const TEMPLATE_LEAD_US: u64 = 5_000_000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChaosKind {
+    Kill,
+    Revive,
+    Slowdown,
+    Latency,
+    Partition,
+    Heal,
+}
+
+impl ChaosKind {
+    const ALL: [ChaosKind; 6] = [
+        ChaosKind::Kill,
+        ChaosKind::Revive,
+        ChaosKind::Slowdown,
+        ChaosKind::Latency,
+        ChaosKind::Partition,
+        ChaosKind::Heal,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ChaosKind::Kill => "Kill",
+            ChaosKind::Revive => "Revive",
+            ChaosKind::Slowdown => "Slow down",
+            ChaosKind::Latency => "Inject latency",
+            ChaosKind::Partition => "Partition",
+            ChaosKind::Heal => "Heal",
+        }
+    }
+
+    /// Whether the action targets a pair of nodes rather than one.
+    fn is_pair(self) -> bool {
+        matches!(
+            self,
+            ChaosKind::Latency | ChaosKind::Partition | ChaosKind::Heal
+        )
+    }
+}
+
+/// Draft of the chaos action being authored in the timeline panel.
+pub struct ChaosForm {
+    pub kind: ChaosKind,
+    pub node_id: NodeId,
+    /// Second node of pair actions; the receiving side for latency
+    pub other_id: NodeId,
+    pub latency_ms: f32,
+    /// Service time multiplier of a slowdown
+    pub slowdown: f32,
+    /// How long a slowdown lasts; 0 until restored
+    pub duration_s: f32,
+    pub time_s: f32,
+}
+
+impl Default for ChaosForm {
+    fn default() -> Self {
+        Self {
+            kind: ChaosKind::Kill,
+            node_id: 1,
+            other_id: 2,
+            latency_ms: 200.0,
+            slowdown: 3.0,
+            duration_s: 10.0,
+            time_s: 10.0,
+        }
+    }
+}
+
+pub fn render_timeline(ui: &mut egui::Ui, app: &mut SlayApp) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("CHAOS TIMELINE")
+                .small()
+                .strong()
+                .color(theme().warn),
+        );
+        let name = &app.simulation.scenario.name;
+        if !name.is_empty() {
+            ui.label(egui::RichText::new(name).color(theme().text));
+        }
+        draw_upcoming(ui, app);
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.menu_button("Report", |ui| draw_report_menu(ui, app));
+            ui.menu_button("Templates", |ui| draw_templates_menu(ui, app));
+            if ui.button("Clear").clicked() {
+                app.simulation.clear_scenario();
+                app.scenario_error = None;
+            }
+            if ui.button("Load").clicked() {
+                let text = &app.scenario_text;
+                let parsed = if text.trim_start().starts_with('{') {
+                    Scenario::from_json(text).map_err(|e| e.to_string())
+                } else {
+                    Scenario::from_yaml(text).map_err(|e| e.to_string())
+                };
+                match parsed {
+                    Ok(scenario) => {
+                        app.simulation.load_scenario(scenario);
+                        app.scenario_error = None;
+                    }
+                    Err(e) => app.scenario_error = Some(e),
+                }
+            }
+        });
+    });
+
+    let mut edited = draw_macro(ui, app);
+    edited |= draw_track(ui, app);
+    edited |= draw_steps(ui, app);
+    edited |= draw_form(ui, app);
+    if edited {
+        app.scenario_text = app.simulation.scenario.to_json();
+        app.scenario_error = None;
+    }
+
+    egui::CollapsingHeader::new("Edit JSON or YAML")
+        .id_salt("scenario_json")
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut app.scenario_text)
+                    .code_editor()
+                    .desired_rows(6)
+                    .desired_width(f32::INFINITY),
+            );
+        });
+
+    if let Some(err) = &app.scenario_error {
+        ui.label(egui::RichText::new(err).small().color(theme().critical));
+    }
+}
+
+/// Ready-made faults fitted to the current design, replacing the scenario
+/// and starting a few seconds from now.
+fn draw_templates_menu(ui: &mut egui::Ui, app: &mut SlayApp) {
+    let duration_id = egui::Id::new("fault_template_duration");
+    let mut duration_s = ui.data(|d| d.get_temp::<f32>(duration_id)).unwrap_or(10.0);
+    ui.horizontal(|ui| {
+        ui.label("Fault lasts");
+        ui.add(
+            egui::DragValue::new(&mut duration_s)
+                .range(1.0..=600.0)
+                .speed(0.5)
+                .suffix("s"),
+        );
+    });
+    ui.data_mut(|d| d.insert_temp(duration_id, duration_s));
+    ui.separator();
+
+    let topology = app.simulation.to_topology();
+    let start_us = app.simulation.time + TEMPLATE_LEAD_US;
+    let duration_us = (duration_s as f64 * 1_000_000.0) as u64;
+    for template in FAULT_TEMPLATES {
+        let scenario = template.scenario(&topology, start_us, duration_us);
+        let hover = if scenario.is_some() {
+            template.description.to_string()
+        } else {
+            format!(
+                "{}\n\nNothing in this design to apply it to.",
+                template.description
+            )
+        };
+        if ui
+            .add_enabled(scenario.is_some(), egui::Button::new(template.name))
+            .on_hover_text(hover.clone())
+            .on_disabled_hover_text(hover)
+            .clicked()
+        {
+            if let Some(scenario) = scenario {
+                app.simulation.load_scenario(scenario);
+                app.scenario_text = app.simulation.scenario.to_json();
+                app.scenario_error = None;
+            }
+            ui.close_menu();
+        }
+    }
+}
+
+/// Runs the scenario from scratch on a copy of the design and exports how
+/// the SLO fared around each fault.
+fn draw_report_menu(ui: &mut egui::Ui, app: &mut SlayApp) {
+    let slo_id = egui::Id::new("chaos_report_slo");
+    let mut slo = ui.data(|d| d.get_temp::<f64>(slo_id)).unwrap_or(99.9);
+    ui.horizontal(|ui| {
+        ui.label("SLO");
+        ui.add(
+            egui::DragValue::new(&mut slo)
+                .range(0.0..=100.0)
+                .speed(0.01)
+                .suffix("%"),
+        );
+    });
+    ui.data_mut(|d| d.insert_temp(slo_id, slo));
+    let sim = &app.simulation;
+    let faults = !sim.scenario.steps.is_empty();
+    let markdown = ui
+        .add_enabled(faults, egui::Button::new("Export Markdown"))
+        .on_hover_text("Re-runs the scenario from t=0 without touching this run")
+        .clicked();
+    let json = ui
+        .add_enabled(faults, egui::Button::new("Export JSON"))
+        .clicked();
+    if !markdown && !json {
+        return;
+    }
+    // Long enough to see recovery from the last step
+    let last_us = sim.scenario.steps.last().map_or(0, |s| s.at_us);
+    let duration_us = (last_us + CHAOS_WINDOW_US).saturating_sub(sim.warmup_us);
+    let report = Experiment::new(sim.seed, duration_us)
+        .with_warmup(sim.warmup_us)
+        .with_scenario(sim.scenario.clone())
+        .chaos_report(&sim.to_topology(), slo);
+    if markdown {
+        crate::export::save_file(
+            "slay-chaos-report.md",
+            "text/markdown",
+            &report.to_markdown(),
+        );
+    } else {
+        crate::export::save_file(
+            "slay-chaos-report.json",
+            "application/json",
+            &report.to_json(),
+        );
+    }
+    ui.close_menu();
+}
+
+/// Records kills, revives and config or link edits made by hand, then
+/// replays them from now or installs them as the scenario. Returns whether
+/// the scenario changed.
+fn draw_macro(ui: &mut egui::Ui, app: &mut SlayApp) -> bool {
+    let mut edited = false;
+    ui.horizontal(|ui| {
+        let sim = &mut app.simulation;
+        if let Some(recorder) = &sim.macro_recorder {
+            let elapsed_s = sim.time.saturating_sub(recorder.started_us) as f64 / 1_000_000.0;
+            let count = recorder.scenario.steps.len();
+            if ui
+                .button(egui::RichText::new("⏹ Stop").color(theme().critical))
+                .clicked()
+            {
+                app.recorded_macro = sim.stop_macro();
+            }
+            ui.label(
+                egui::RichText::new(format!(
+                    "Recording: {} action{} in {:.1}s",
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    elapsed_s
+                ))
+                .small()
+                .color(theme().critical),
+            );
+            return;
+        }
+        if ui
+            .button("⏺ Record macro")
+            .on_hover_text("Capture kills, revives and config or link edits as you make them")
+            .clicked()
+        {
+            sim.start_macro("Recorded macro");
+        }
+        let Some(recorded) = &app.recorded_macro else {
+            return;
+        };
+        let span_s = recorded.steps.last().map_or(0, |s| s.at_us) as f64 / 1_000_000.0;
+        ui.label(
+            egui::RichText::new(format!(
+                "Macro: {} actions over {:.1}s",
+                recorded.steps.len(),
+                span_s
+            ))
+            .small()
+            .color(theme().text_dim),
+        );
+        if ui
+            .add_enabled(
+                !recorded.steps.is_empty(),
+                egui::Button::new("▶ Replay now"),
+            )
+            .on_hover_text("Schedule the actions again, starting from the current time")
+            .clicked()
+        {
+            for step in recorded.shifted(sim.time).steps {
+                sim.add_scenario_step(step.at_us, step.action);
+            }
+            edited = true;
+        }
+        if ui
+            .add_enabled(
+                !recorded.steps.is_empty(),
+                egui::Button::new("Use as scenario"),
+            )
+            .on_hover_text("Replace the scenario with the macro, timed from t=0")
+            .clicked()
+        {
+            sim.load_scenario(recorded.clone());
+            edited = true;
+        }
+        if ui
+            .small_button("✖")
+            .on_hover_text("Discard the macro")
+            .clicked()
+        {
+            app.recorded_macro = None;
+        }
+    });
+    edited
+}
+
+/// The next enabled steps still to fire, with how far away they are.
+fn draw_upcoming(ui: &mut egui::Ui, app: &SlayApp) {
+    let sim = &app.simulation;
+    let upcoming: Vec<String> = sim
+        .scenario
+        .steps
+        .iter()
+        .skip(sim.scenario_cursor)
+        .filter(|s| s.enabled)
+        .take(UPCOMING_SHOWN)
+        .map(|s| {
+            format!(
+                "{} in {:.1}s",
+                s.action.describe(),
+                s.at_us.saturating_sub(sim.time) as f64 / 1_000_000.0
+            )
+        })
+        .collect();
+    if upcoming.is_empty() {
+        return;
+    }
+    ui.label(
+        egui::RichText::new(format!("Next: {}", upcoming.join(" · ")))
+            .small()
+            .color(theme().text_dim),
+    );
+}
+
+/// Markers for every step against virtual time: pending ones in the
+/// warning color, fired ones dimmed, disabled ones hollow. Clicking a
+/// marker toggles its step. Returns whether a step changed.
+fn draw_track(ui: &mut egui::Ui, app: &mut SlayApp) -> bool {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 28.0), egui::Sense::hover());
+    let painter = ui.painter().clone();
+    painter.rect_filled(rect, 2.0, theme().chart_bg);
+
+    let sim = &mut app.simulation;
+    let now = sim.time;
+    let end = sim
+        .scenario
+        .steps
+        .last()
+        .map_or(0, |s| s.at_us)
+        .max(now)
+        .max(1_000_000);
+    let to_x = |t: u64| rect.left() + (t as f32 / end as f32) * rect.width();
+
+    let mut toggled = None;
+    for (i, step) in sim.scenario.steps.iter().enumerate() {
+        let x = to_x(step.at_us);
+        let fired = i < sim.scenario_cursor;
+        let col = if fired {
+            theme().text_dim
+        } else {
+            theme().warn
+        };
+        let marker =
+            egui::Rect::from_center_size(egui::pos2(x, rect.center().y), egui::vec2(6.0, 16.0));
+        if step.enabled {
+            painter.rect_filled(marker, 1.0, col);
+        } else {
+            painter.rect_stroke(marker, 1.0, egui::Stroke::new(1.0, col));
+        }
+
+        let response = ui.interact(
+            marker.expand(3.0),
+            egui::Id::new(("scenario_step", i)),
+            egui::Sense::click(),
+        );
+        if response
+            .on_hover_text(format!(
+                "{:.1}s — {}{}\nClick to {}",
+                step.at_us as f32 / 1_000_000.0,
+                step.action.describe(),
+                if step.enabled { "" } else { " (disabled)" },
+                if step.enabled { "disable" } else { "enable" }
+            ))
+            .clicked()
+        {
+            toggled = Some(i);
+        }
+    }
+
+    let cursor_x = to_x(now);
+    painter.line_segment(
+        [
+            egui::pos2(cursor_x, rect.top()),
+            egui::pos2(cursor_x, rect.bottom()),
+        ],
+        egui::Stroke::new(1.5, theme().accent),
+    );
+    painter.text(
+        rect.right_bottom() + egui::vec2(-4.0, -2.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format!("{:.0}s", end as f64 / 1_000_000.0),
+        egui::FontId::proportional(9.0),
+        theme().text_dim,
+    );
+
+    if let Some(i) = toggled {
+        let step = &mut sim.scenario.steps[i];
+        step.enabled = !step.enabled;
+    }
+    toggled.is_some()
+}
+
+/// One row per step with its switch, status and a delete button. Returns
+/// whether a step changed.
+fn draw_steps(ui: &mut egui::Ui, app: &mut SlayApp) -> bool {
+    let sim = &mut app.simulation;
+    if sim.scenario.steps.is_empty() {
+        return false;
+    }
+    let now = sim.time;
+    let cursor = sim.scenario_cursor;
+    let mut edited = false;
+    let mut to_remove = None;
+    egui::CollapsingHeader::new(format!("Steps ({})", sim.scenario.steps.len()))
+        .id_salt("scenario_steps")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(120.0)
+                .show(ui, |ui| {
+                    for (i, step) in sim.scenario.steps.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            edited |= ui.checkbox(&mut step.enabled, "").changed();
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{:>7.1}s",
+                                    step.at_us as f64 / 1_000_000.0
+                                ))
+                                .monospace()
+                                .color(theme().text_dim),
+                            );
+                            ui.label(step.action.describe());
+                            let (status, col) = if !step.enabled {
+                                ("off".to_string(), theme().text_dim)
+                            } else if i < cursor {
+                                ("fired".to_string(), theme().text_dim)
+                            } else {
+                                (
+                                    format!(
+                                        "in {:.1}s",
+                                        step.at_us.saturating_sub(now) as f64 / 1_000_000.0
+                                    ),
+                                    theme().warn,
+                                )
+                            };
+                            ui.label(egui::RichText::new(status).small().color(col));
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui
+                                        .small_button(
+                                            egui::RichText::new("✖").color(theme().critical),
+                                        )
+                                        .clicked()
+                                    {
+                                        to_remove = Some(i);
+                                    }
+                                },
+                            );
+                        });
+                    }
+                });
+        });
+    if let Some(i) = to_remove {
+        sim.remove_scenario_step(i);
+        edited = true;
+    }
+    edited
+}
+
+/// Controls for scheduling a new action. Returns whether one was added.
+fn draw_form(ui: &mut egui::Ui, app: &mut SlayApp) -> bool {
+    let form = &mut app.chaos_form;
+    let mut add = false;
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("chaos_kind")
+            .selected_text(form.kind.label())
+            .show_ui(ui, |ui| {
+                for kind in ChaosKind::ALL {
+                    ui.selectable_value(&mut form.kind, kind, kind.label());
+                }
+            });
+        ui.add(egui::DragValue::new(&mut form.node_id).prefix("#"));
+        if form.kind.is_pair() {
+            ui.label(if form.kind == ChaosKind::Latency {
+                "→"
+            } else {
+                "↔"
+            });
+            ui.add(egui::DragValue::new(&mut form.other_id).prefix("#"));
+        }
+        if form.kind == ChaosKind::Slowdown {
+            ui.add(
+                egui::DragValue::new(&mut form.slowdown)
+                    .range(1.0..=50.0)
+                    .prefix("×")
+                    .speed(0.1),
+            );
+            ui.label("for");
+            ui.add(
+                egui::DragValue::new(&mut form.duration_s)
+                    .range(0.0..=3_600.0)
+                    .suffix("s")
+                    .speed(0.1),
+            )
+            .on_hover_text("0 lasts until restored from the inspector");
+        }
+        if form.kind == ChaosKind::Latency {
+            ui.add(
+                egui::DragValue::new(&mut form.latency_ms)
+                    .range(0.0..=60_000.0)
+                    .suffix("ms")
+                    .speed(5.0),
+            );
+        }
+        ui.label("at");
+        ui.add(
+            egui::DragValue::new(&mut form.time_s)
+                .range(0.0..=f32::MAX)
+                .suffix("s")
+                .speed(0.1),
+        );
+        add = ui.button("+ Schedule").clicked();
+    });
+    if !add {
+        return false;
+    }
+
+    let sim = &mut app.simulation;
+    let (a, b) = (form.node_id, form.other_id);
+    let action = match form.kind {
+        ChaosKind::Kill => ScenarioAction::Kill { node_id: a },
+        ChaosKind::Revive => ScenarioAction::Revive { node_id: a },
+        ChaosKind::Slowdown => ScenarioAction::InjectLatency {
+            node_id: a,
+            slowdown: form.slowdown,
+            extra_latency_us: 0,
+            error_rate: 0.0,
+            duration_us: (form.duration_s > 0.0)
+                .then_some((form.duration_s as f64 * 1_000_000.0) as u64),
+        },
+        ChaosKind::Latency => {
+            // Keep the link's jitter and loss; only the latency is injected
+            let current = sim
+                .links
+                .get(&canonical_key(a, b))
+                .map(|link| *link.get_config(a, b))
+                .unwrap_or_default();
+            ScenarioAction::SetEdge {
+                from: a,
+                to: b,
+                edge: EdgeConfig {
+                    latency_us: (form.latency_ms as f64 * 1000.0) as u64,
+                    ..current
+                },
+            }
+        }
+        ChaosKind::Partition => ScenarioAction::Partition { a, b },
+        ChaosKind::Heal => ScenarioAction::Heal { a, b },
+    };
+    // Times already behind the run would never fire, so schedule those now
+    let at_us = ((form.time_s as f64 * 1_000_000.0) as u64).max(sim.time);
+    sim.add_scenario_step(at_us, action);
+    true
+}
@@ -0,0 +1,215 @@
+use crate::app::SlayApp;
+use crate::theme::theme;
+use eframe::egui;
+use slay_core::{LatencyBreakdown, LatencySplit, Segment, Trace};
+
+pub fn render_traces(ui: &mut egui::Ui, app: &mut SlayApp) {
+    let mut export_otlp = false;
+    let Some(tracer) = &mut app.simulation.tracer else {
+        ui.label(egui::RichText::new("Tracing disabled").color(theme().text_dim));
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Sample 1 in");
+        ui.add(egui::DragValue::new(&mut tracer.sample_every).range(1..=10_000));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Clear").clicked() {
+                tracer.clear();
+                app.selected_trace = None;
+            }
+            if ui
+                .button("Export OTLP")
+                .on_hover_text("Save as OTLP/JSON for Jaeger or Tempo")
+                .clicked()
+            {
+                export_otlp = true;
+            }
+        });
+    });
+    if export_otlp {
+        // Place the run so that it ends now
+        let epoch_ns = crate::export::unix_time_ns().saturating_sub(app.simulation.time * 1000);
+        if let Some(otlp) = app.simulation.otlp_traces(epoch_ns) {
+            crate::export::save_file("slay-traces.json", "application/json", &otlp.to_string());
+        }
+    }
+    let Some(tracer) = &mut app.simulation.tracer else {
+        return;
+    };
+    ui.separator();
+
+    let breakdown = tracer.latency_breakdown();
+    if breakdown.requests > 0 {
+        egui::CollapsingHeader::new("Latency breakdown")
+            .id_salt("latency_breakdown")
+            .show(ui, |ui| draw_breakdown(ui, &breakdown));
+        ui.separator();
+    }
+
+    egui::ScrollArea::vertical()
+        .id_salt("trace_list")
+        .max_height(160.0)
+        .show(ui, |ui| {
+            for trace in tracer.completed.iter().rev() {
+                let ok = trace.success == Some(true);
+                let text = format!(
+                    "{:x}  {:>8}  {} hops",
+                    trace.request_id as u64,
+                    trace.duration_us().map_or("lost".to_string(), |d| format!(
+                        "{:.1}ms",
+                        d as f32 / 1000.0
+                    )),
+                    trace.spans.len()
+                );
+                let color = if ok { theme().text } else { theme().critical };
+                let selected = app.selected_trace == Some(trace.request_id);
+                if ui
+                    .selectable_label(selected, egui::RichText::new(text).monospace().color(color))
+                    .clicked()
+                {
+                    app.selected_trace = Some(trace.request_id);
+                }
+            }
+        });
+
+    if let Some(trace) = app
+        .selected_trace
+        .and_then(|id| tracer.completed.iter().find(|t| t.request_id == id))
+    {
+        ui.separator();
+        draw_waterfall(ui, trace);
+    }
+}
+
+fn draw_waterfall(ui: &mut egui::Ui, trace: &Trace) {
+    let row_h = 18.0;
+    let label_w = 60.0;
+    let height = row_h * trace.spans.len() as f32 + 4.0;
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), height),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter();
+
+    let end = trace
+        .spans
+        .iter()
+        .filter_map(|s| s.end_us)
+        .chain(trace.end_us)
+        .max()
+        .unwrap_or(trace.start_us + 1)
+        .max(trace.start_us + 1);
+    let span_total = (end - trace.start_us) as f32;
+    let bar_left = rect.left() + label_w;
+    let bar_w = rect.width() - label_w;
+
+    for (i, span) in trace.spans.iter().enumerate() {
+        let y = rect.top() + i as f32 * row_h;
+        painter.text(
+            egui::pos2(rect.left(), y + row_h / 2.0),
+            egui::Align2::LEFT_CENTER,
+            format!("#{}", span.node_id),
+            egui::FontId::monospace(11.0),
+            theme().text_dim,
+        );
+
+        let x0 = bar_left + (span.start_us - trace.start_us) as f32 / span_total * bar_w;
+        let x1 =
+            bar_left + (span.end_us.unwrap_or(end) - trace.start_us) as f32 / span_total * bar_w;
+        let color = match span.success {
+            Some(true) => theme().success,
+            Some(false) => theme().critical,
+            None => theme().text_dim,
+        };
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(x0, y + 3.0),
+            egui::pos2(x1.max(x0 + 2.0), y + row_h - 3.0),
+        );
+        painter.rect_filled(bar, 2.0, color.gamma_multiply(0.7));
+
+        let label = span
+            .duration_us()
+            .map_or("?".to_string(), |d| format!("{:.1}ms", d as f32 / 1000.0));
+        painter.text(
+            egui::pos2(bar.right() + 4.0, y + row_h / 2.0),
+            egui::Align2::LEFT_CENTER,
+            label,
+            egui::FontId::proportional(10.0),
+            theme().text,
+        );
+    }
+}
+
+/// Stacked bars of where the mean successful request spends its time.
+fn draw_breakdown(ui: &mut egui::Ui, breakdown: &LatencyBreakdown) {
+    let theme = theme();
+    let legend = [
+        ("transit", theme.accent),
+        ("queue", theme.warn),
+        ("service", theme.success),
+        ("processing", theme.text_dim),
+    ];
+    let parts = |s: &LatencySplit| [s.transit_us, s.queue_us, s.service_us, s.processing_us];
+
+    ui.horizontal(|ui| {
+        for (name, color) in legend {
+            ui.label(
+                egui::RichText::new(format!("■ {}", name))
+                    .color(color)
+                    .small(),
+            );
+        }
+    });
+
+    let total = breakdown.mean_total();
+    let scale = total.total_us().max(1) as f32;
+    let mut rows = vec![("total".to_string(), total)];
+    rows.extend(breakdown.segments.keys().filter_map(|&segment| {
+        let mean = breakdown.mean_for(segment);
+        let label = match segment {
+            Segment::Node(id) => format!("#{}", id),
+            Segment::Link(from, to) => format!("{}→{}", from, to),
+        };
+        (mean.total_us() > 0).then_some((label, mean))
+    }));
+
+    let row_h = 16.0;
+    let label_w = 60.0;
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), row_h * rows.len() as f32),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter();
+    let bar_w = (rect.width() - label_w - 50.0).max(1.0);
+
+    for (i, (label, split)) in rows.iter().enumerate() {
+        let y = rect.top() + i as f32 * row_h;
+        painter.text(
+            egui::pos2(rect.left(), y + row_h / 2.0),
+            egui::Align2::LEFT_CENTER,
+            label,
+            egui::FontId::monospace(11.0),
+            theme.text_dim,
+        );
+        let mut x = rect.left() + label_w;
+        for (us, (_, color)) in parts(split).into_iter().zip(legend) {
+            let w = us as f32 / scale * bar_w;
+            if w > 0.0 {
+                let bar = egui::Rect::from_min_max(
+                    egui::pos2(x, y + 3.0),
+                    egui::pos2(x + w.max(1.0), y + row_h - 3.0),
+                );
+                painter.rect_filled(bar, 0.0, color.gamma_multiply(0.8));
+                x += w;
+            }
+        }
+        painter.text(
+            egui::pos2(x + 4.0, y + row_h / 2.0),
+            egui::Align2::LEFT_CENTER,
+            format!("{:.1}ms", split.total_us() as f32 / 1000.0),
+            egui::FontId::proportional(10.0),
+            theme.text,
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use eframe::egui;
+use egui::emath::Numeric;
+use std::ops::RangeInclusive;
+
+/// Drag steps across the whole range of a linear slider.
+const DRAG_STEPS: f64 = 300.0;
+
+/// A slider for quick changes with a number box beside it, so an exact
+/// value can be typed. Both stay within `range` and show the same unit.
+pub struct SliderEntry<'a, Num: Numeric> {
+    value: &'a mut Num,
+    range: RangeInclusive<Num>,
+    suffix: &'static str,
+    text: Option<&'static str>,
+    logarithmic: bool,
+}
+
+impl<'a, Num: Numeric> SliderEntry<'a, Num> {
+    pub fn new(value: &'a mut Num, range: RangeInclusive<Num>) -> Self {
+        Self {
+            value,
+            range,
+            suffix: "",
+            text: None,
+            logarithmic: false,
+        }
+    }
+
+    /// Unit shown after the number, e.g. `"ms"`.
+    pub fn suffix(mut self, suffix: &'static str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Label after the number box.
+    pub fn text(mut self, text: &'static str) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn logarithmic(mut self, logarithmic: bool) -> Self {
+        self.logarithmic = logarithmic;
+        self
+    }
+}
+
+impl<Num: Numeric> egui::Widget for SliderEntry<'_, Num> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (min, max) = (self.range.start().to_f64(), self.range.end().to_f64());
+        // Log sliders span orders of magnitude, so drag by a share of the value
+        let speed = if self.logarithmic {
+            (self.value.to_f64().abs() * 0.01).max((max - min) / 1e6)
+        } else {
+            (max - min) / DRAG_STEPS
+        };
+        let speed = if Num::INTEGRAL { speed.max(0.1) } else { speed };
+
+        ui.horizontal(|ui| {
+            let slider = ui.add(
+                egui::Slider::new(&mut *self.value, self.range.clone())
+                    .logarithmic(self.logarithmic)
+                    .show_value(false),
+            );
+            let entry = ui.add(
+                egui::DragValue::new(&mut *self.value)
+                    .range(self.range)
+                    .suffix(self.suffix)
+                    .speed(speed)
+                    .max_decimals(3),
+            );
+            if let Some(text) = self.text {
+                ui.label(text);
+            }
+            slider | entry
+        })
+        .inner
+    }
+}
@@ -0,0 +1,299 @@
+use crate::app::{NodeVisualState, PersistedState, SlayApp};
+use crate::canvas::FitTarget;
+use crate::groups::Group;
+use crate::replay::Recording;
+use crate::theme::theme;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use slay_core::{MetricsCollector, NodeId, Scenario, Simulation};
+use std::collections::{BTreeSet, HashMap};
+
+/// Storage key of the tabs besides the active one, which is saved under
+/// `eframe::APP_KEY` as before.
+pub const WORKSPACES_KEY: &str = "slay_workspaces";
+
+/// Everything that belongs to one open topology. The active tab's state
+/// lives on `SlayApp` itself; switching tabs swaps it with the parked
+/// copy, so the rest of the app only ever sees one design. Parked tabs
+/// are paused until they are brought back.
+pub struct Workspace {
+    pub name: String,
+    simulation: Simulation,
+    node_states: HashMap<NodeId, NodeVisualState>,
+    next_node_id: NodeId,
+    pan: egui::Vec2,
+    target_pan: egui::Vec2,
+    zoom: f32,
+    target_zoom: f32,
+    selected_node: Option<NodeId>,
+    selection: BTreeSet<NodeId>,
+    selected_edge: Option<(NodeId, NodeId)>,
+    groups: Vec<Group>,
+    linking_from: Option<NodeId>,
+    fit_request: Option<FitTarget>,
+    is_running: bool,
+    fast_forward_to: Option<u64>,
+    pause_at: Option<u64>,
+    metrics: MetricsCollector,
+    recording: Option<Recording>,
+    selected_trace: Option<u128>,
+    scenario_text: String,
+    scenario_error: Option<String>,
+    recorded_macro: Option<Scenario>,
+}
+
+impl Workspace {
+    /// An empty tab; its design is filled in once it is swapped in.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            simulation: Simulation::new(42),
+            node_states: HashMap::new(),
+            next_node_id: 1,
+            pan: egui::Vec2::ZERO,
+            target_pan: egui::Vec2::ZERO,
+            zoom: 1.0,
+            target_zoom: 1.0,
+            selected_node: None,
+            selection: BTreeSet::new(),
+            selected_edge: None,
+            groups: Vec::new(),
+            linking_from: None,
+            fit_request: Some(FitTarget::All),
+            is_running: false,
+            fast_forward_to: None,
+            pause_at: None,
+            metrics: MetricsCollector::new(300),
+            recording: None,
+            selected_trace: None,
+            scenario_text: Scenario::new("New scenario").to_json(),
+            scenario_error: None,
+            recorded_macro: None,
+        }
+    }
+
+    fn swap_with(&mut self, app: &mut SlayApp) {
+        use std::mem::swap;
+        swap(&mut self.simulation, &mut app.simulation);
+        app.config_edit = None;
+        swap(&mut self.node_states, &mut app.node_states);
+        swap(&mut self.next_node_id, &mut app.next_node_id);
+        swap(&mut self.pan, &mut app.pan);
+        swap(&mut self.target_pan, &mut app.target_pan);
+        swap(&mut self.zoom, &mut app.zoom);
+        swap(&mut self.target_zoom, &mut app.target_zoom);
+        swap(&mut self.selected_node, &mut app.selected_node);
+        swap(&mut self.selection, &mut app.selection);
+        swap(&mut self.selected_edge, &mut app.selected_edge);
+        swap(&mut self.groups, &mut app.groups);
+        swap(&mut self.linking_from, &mut app.linking_from);
+        swap(&mut self.fit_request, &mut app.fit_request);
+        swap(&mut self.is_running, &mut app.is_running);
+        swap(&mut self.fast_forward_to, &mut app.fast_forward_to);
+        swap(&mut self.pause_at, &mut app.pause_at);
+        swap(&mut self.metrics, &mut app.metrics);
+        swap(&mut self.recording, &mut app.recording);
+        swap(&mut self.selected_trace, &mut app.selected_trace);
+        swap(&mut self.scenario_text, &mut app.scenario_text);
+        swap(&mut self.scenario_error, &mut app.scenario_error);
+        swap(&mut self.recorded_macro, &mut app.recorded_macro);
+    }
+}
+
+/// A tab as saved between sessions; the active one has no `state` of its
+/// own because it is saved as the app state.
+#[derive(Serialize, Deserialize)]
+pub struct SavedTab {
+    name: String,
+    state: Option<PersistedState>,
+}
+
+enum TabAction {
+    Switch(usize),
+    New,
+    Duplicate,
+    Close(usize),
+    Rename(usize, String),
+}
+
+impl SlayApp {
+    /// First "Design N" name no open tab uses.
+    fn next_tab_name(&self) -> String {
+        let mut n = self.tabs.len() + 1;
+        while self.tabs.iter().any(|t| t.name == format!("Design {}", n)) {
+            n += 1;
+        }
+        format!("Design {}", n)
+    }
+
+    /// Brings tab `index` to the canvas, parking the current design in its
+    /// place.
+    pub fn switch_tab(&mut self, index: usize) {
+        let active = self.active_tab;
+        if index == active || index >= self.tabs.len() {
+            return;
+        }
+        let mut parked = std::mem::replace(&mut self.tabs[index], Workspace::new(String::new()));
+        parked.swap_with(self);
+        // Names stay with their designs; the active slot only keeps a name
+        self.tabs[index].name = std::mem::replace(
+            &mut parked.name,
+            std::mem::take(&mut self.tabs[active].name),
+        );
+        self.tabs[active] = parked;
+        self.active_tab = index;
+        self.drag_node_kind = None;
+        self.click_linking = false;
+    }
+
+    /// Opens a new tab holding `state`, or the default design, and shows it.
+    pub fn open_tab(&mut self, name: String, state: Option<PersistedState>) {
+        self.tabs.push(Workspace::new(name));
+        let index = self.tabs.len() - 1;
+        self.switch_tab(index);
+        match state {
+            Some(state) => self.apply_state(state),
+            None => self.setup_default_topology(),
+        }
+        self.fit_request = Some(FitTarget::All);
+    }
+
+    fn close_tab(&mut self, index: usize) {
+        if self.tabs.len() <= 1 || index >= self.tabs.len() {
+            return;
+        }
+        if index == self.active_tab {
+            let neighbour = if index + 1 < self.tabs.len() {
+                index + 1
+            } else {
+                index - 1
+            };
+            self.switch_tab(neighbour);
+        }
+        self.tabs.remove(index);
+        if self.active_tab > index {
+            self.active_tab -= 1;
+        }
+    }
+
+    /// Every tab for saving; the active one's design goes under
+    /// `eframe::APP_KEY`.
+    pub fn saved_tabs(&mut self) -> Vec<SavedTab> {
+        let mut saved = Vec::with_capacity(self.tabs.len());
+        for i in 0..self.tabs.len() {
+            let mut tab = std::mem::replace(&mut self.tabs[i], Workspace::new(String::new()));
+            let state = (i != self.active_tab).then(|| {
+                // Saved with the app's own serialiser
+                tab.swap_with(self);
+                let state = self.persisted_state();
+                tab.swap_with(self);
+                state
+            });
+            saved.push(SavedTab {
+                name: tab.name.clone(),
+                state,
+            });
+            self.tabs[i] = tab;
+        }
+        saved
+    }
+
+    /// Reopens the tabs of a previous session around the design already
+    /// restored on the app.
+    pub fn restore_tabs(&mut self, saved: Vec<SavedTab>) {
+        let Some(active) = saved.iter().position(|t| t.state.is_none()) else {
+            return;
+        };
+        let mut tabs = Vec::with_capacity(saved.len());
+        for tab in saved {
+            let mut workspace = Workspace::new(tab.name);
+            if let Some(state) = tab.state {
+                // Build the parked design with the app's own loader
+                workspace.swap_with(self);
+                self.apply_state(state);
+                workspace.swap_with(self);
+            }
+            tabs.push(workspace);
+        }
+        self.tabs = tabs;
+        self.active_tab = active;
+    }
+
+    /// Strip of open designs above the canvas.
+    pub fn render_tabs(&mut self, ui: &mut egui::Ui) {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            for (i, tab) in self.tabs.iter().enumerate() {
+                let active = i == self.active_tab;
+                let running = if active {
+                    self.is_running
+                } else {
+                    tab.is_running
+                };
+                let label = if running {
+                    format!("▶ {}", tab.name)
+                } else {
+                    tab.name.clone()
+                };
+                let response = ui
+                    .selectable_label(active, label)
+                    .on_hover_text("Right-click to rename or close. Background tabs are paused.");
+                if response.clicked() {
+                    action = Some(TabAction::Switch(i));
+                }
+                response.context_menu(|ui| {
+                    let mut name = tab.name.clone();
+                    if ui.text_edit_singleline(&mut name).changed() {
+                        action = Some(TabAction::Rename(i, name));
+                    }
+                    if ui
+                        .add_enabled(self.tabs.len() > 1, egui::Button::new("Close"))
+                        .clicked()
+                    {
+                        action = Some(TabAction::Close(i));
+                        ui.close_menu();
+                    }
+                });
+                if active && self.tabs.len() > 1 && ui.small_button("×").clicked() {
+                    action = Some(TabAction::Close(i));
+                }
+            }
+            ui.separator();
+            if ui
+                .small_button("+")
+                .on_hover_text("New tab with the default design")
+                .clicked()
+            {
+                action = Some(TabAction::New);
+            }
+            if ui
+                .small_button("⧉")
+                .on_hover_text("Duplicate this design into a new tab")
+                .clicked()
+            {
+                action = Some(TabAction::Duplicate);
+            }
+            ui.label(
+                egui::RichText::new(format!("{} open", self.tabs.len()))
+                    .small()
+                    .color(theme().text_dim),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.menu_button("Layout", |ui| self.layout.menu(ui));
+            });
+        });
+
+        match action {
+            Some(TabAction::Switch(i)) => self.switch_tab(i),
+            Some(TabAction::New) => self.open_tab(self.next_tab_name(), None),
+            Some(TabAction::Duplicate) => {
+                let name = format!("{} copy", self.tabs[self.active_tab].name);
+                let state = self.persisted_state();
+                self.open_tab(name, Some(state));
+            }
+            Some(TabAction::Close(i)) => self.close_tab(i),
+            Some(TabAction::Rename(i, name)) => self.tabs[i].name = name,
+            None => {}
+        }
+    }
+}